@@ -0,0 +1,117 @@
+use arrow_array::{Array, ArrowPrimitiveType, PrimitiveArray};
+
+use crate::helpers::NumExt;
+use crate::Stats;
+
+/// An extension trait for [Arrow](https://docs.rs/arrow-array) primitive
+/// arrays, to calculate statistics of their values in place, skipping the
+/// nulls with the validity bitmap, like [`OptionStats`](crate::OptionStats)
+/// does for collections of [`Option`]s.
+///
+/// # Examples
+/// ```
+/// use arrow_array::Int32Array;
+/// use stats_traits::{ArrowStats, Stats};
+/// let column = Int32Array::from(vec![Some(1), None, Some(5), Some(6)]);
+/// let values = column.skip_nulls();
+/// assert_eq!(values.null_count(), 1);
+/// assert_eq!(values.sum(), 12);
+/// assert_eq!(values.mean(), Ok(4));
+/// ```
+pub trait ArrowStats<T>
+where
+    T: ArrowPrimitiveType,
+{
+    /// Returns the array as a [`Stats`] of the values that aren't null,
+    /// so every method of [`Stats`] can be used on it
+    fn skip_nulls(&self) -> SkipNulls<'_, T>;
+}
+
+impl<T> ArrowStats<T> for PrimitiveArray<T>
+where
+    T: ArrowPrimitiveType,
+{
+    fn skip_nulls(&self) -> SkipNulls<'_, T> {
+        SkipNulls { array: self }
+    }
+}
+
+/// An Arrow primitive array seen as the values that aren't null,
+/// created by [`ArrowStats::skip_nulls`]. It implements [`Stats`].
+#[derive(Debug)]
+pub struct SkipNulls<'a, T>
+where
+    T: ArrowPrimitiveType,
+{
+    array: &'a PrimitiveArray<T>,
+}
+
+impl<T: ArrowPrimitiveType> Clone for SkipNulls<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ArrowPrimitiveType> Copy for SkipNulls<'_, T> {}
+
+impl<'a, T> SkipNulls<'a, T>
+where
+    T: ArrowPrimitiveType,
+{
+    /// Returns the array with the nulls
+    pub fn get(&self) -> &'a PrimitiveArray<T> {
+        self.array
+    }
+
+    /// Returns the number of nulls in the array
+    pub fn null_count(&self) -> usize {
+        self.array.null_count()
+    }
+}
+
+impl<T> Stats for SkipNulls<'_, T>
+where
+    T: ArrowPrimitiveType,
+    T::Native: NumExt,
+{
+    type Item = T::Native;
+
+    fn items<'a>(&'a self) -> impl Iterator<Item = T::Native> + 'a
+    where
+        Self::Item: 'a,
+    {
+        self.array.iter().flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StatsError;
+
+    extern crate std;
+    use std::vec;
+    use std::vec::Vec;
+
+    use arrow_array::{Float64Array, UInt8Array};
+
+    #[test]
+    fn test_nulls_skipped() {
+        let column = Float64Array::from(vec![None, Some(2.0), Some(4.0), None]);
+        let values = column.skip_nulls();
+        assert_eq!(values.null_count(), 2);
+        assert_eq!(values.count(), 2);
+        assert_eq!(values.mean(), Ok(3.0));
+        assert_eq!(values.variance(), Ok(1.0));
+        assert_eq!(Stats::max(&values), Ok(4.0));
+    }
+
+    #[test]
+    fn test_without_nulls() {
+        let column = UInt8Array::from(vec![3, 1, 2]);
+        assert_eq!(column.skip_nulls().null_count(), 0);
+        assert_eq!(column.skip_nulls().median(), Ok(2.0));
+        let empty = UInt8Array::from(Vec::<u8>::new());
+        assert_eq!(empty.skip_nulls().mean(), Err(StatsError::EmptyCollection));
+    }
+}
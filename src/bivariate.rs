@@ -0,0 +1,258 @@
+use crate::helpers::Float;
+use crate::Result;
+use crate::StatsError;
+
+/// Accumulates statistics over a stream of `(x, y)` pairs one at a time,
+/// without storing any of them, including the covariance and correlation
+/// of the two values.
+///
+/// This is the two-variable counterpart of [`OnlineStats`](crate::OnlineStats).
+/// The co-moment is updated in the same way as the variance in Welford's
+/// algorithm, so it doesn't suffer from the loss of precision of keeping
+/// a running sum of products.
+///
+/// # Examples
+/// ```
+/// use stats_traits::OnlineBivariate;
+/// let mut stats = OnlineBivariate::new();
+/// for (x, y) in [(1.0, 2.0), (2.0, 4.0), (3.0, 6.0)] {
+///     stats.push(x, y);
+/// }
+/// assert_eq!(stats.count(), 3);
+/// assert_eq!(stats.mean_y(), Ok(4.0));
+/// assert_eq!(stats.covariance(), Ok(4.0 / 3.0));
+/// assert_eq!(stats.correlation(), Ok(1.0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OnlineBivariate {
+    count: usize,
+    mean_x: f64,
+    mean_y: f64,
+    m2_x: f64,
+    m2_y: f64,
+    /// Sum of the products of the differences of `x` and `y` from their means
+    co_moment: f64,
+}
+
+impl OnlineBivariate {
+    /// Create an accumulator that hasn't seen any pairs
+    pub const fn new() -> Self {
+        Self {
+            count: 0,
+            mean_x: 0.0,
+            mean_y: 0.0,
+            m2_x: 0.0,
+            m2_y: 0.0,
+            co_moment: 0.0,
+        }
+    }
+
+    /// Add a pair of values to the accumulator
+    pub fn push(&mut self, x: f64, y: f64) {
+        self.count += 1;
+        let n = self.count as f64;
+        let delta_x = x - self.mean_x;
+        let delta_y = y - self.mean_y;
+        self.mean_x += delta_x / n;
+        self.mean_y += delta_y / n;
+        // one difference from the old mean and one from the new one
+        self.m2_x += delta_x * (x - self.mean_x);
+        self.m2_y += delta_y * (y - self.mean_y);
+        self.co_moment += delta_x * (y - self.mean_y);
+    }
+
+    /// Combine the pairs seen by `other` into `self`, as if every
+    /// pair pushed to `other` had been pushed to `self` as well
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::OnlineBivariate;
+    /// let mut a: OnlineBivariate = [(1.0, 1.0), (2.0, 3.0)].into_iter().collect();
+    /// let b: OnlineBivariate = [(3.0, 2.0)].into_iter().collect();
+    /// a.merge(&b);
+    /// assert_eq!(a.count(), 3);
+    /// assert_eq!(a.mean_x(), Ok(2.0));
+    /// assert_eq!(a.mean_y(), Ok(2.0));
+    /// ```
+    pub fn merge(&mut self, other: &Self) {
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            *self = *other;
+            return;
+        }
+        let count = self.count + other.count;
+        let (n_a, n_b, n) = (self.count as f64, other.count as f64, count as f64);
+        let delta_x = other.mean_x - self.mean_x;
+        let delta_y = other.mean_y - self.mean_y;
+        self.mean_x += delta_x * n_b / n;
+        self.mean_y += delta_y * n_b / n;
+        self.m2_x += other.m2_x + delta_x * delta_x * n_a * n_b / n;
+        self.m2_y += other.m2_y + delta_y * delta_y * n_a * n_b / n;
+        self.co_moment += other.co_moment + delta_x * delta_y * n_a * n_b / n;
+        self.count = count;
+    }
+
+    /// Returns the number of pairs that have been pushed
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Returns `Err(StatsError::EmptyCollection)` if no pairs have been pushed
+    fn non_empty(&self) -> Result<()> {
+        if self.count == 0 {
+            Err(StatsError::EmptyCollection)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns the mean of the `x` values pushed so far
+    ///
+    /// # Errors
+    /// Returns an error if no pairs have been pushed
+    pub fn mean_x(&self) -> Result<f64> {
+        self.non_empty()?;
+        Ok(self.mean_x)
+    }
+
+    /// Returns the mean of the `y` values pushed so far
+    ///
+    /// # Errors
+    /// Returns an error if no pairs have been pushed
+    pub fn mean_y(&self) -> Result<f64> {
+        self.non_empty()?;
+        Ok(self.mean_y)
+    }
+
+    /// Returns the (population) variance of the `x` values pushed so far
+    ///
+    /// # Errors
+    /// Returns an error if no pairs have been pushed
+    pub fn variance_x(&self) -> Result<f64> {
+        self.non_empty()?;
+        Ok(self.m2_x / self.count as f64)
+    }
+
+    /// Returns the (population) variance of the `y` values pushed so far
+    ///
+    /// # Errors
+    /// Returns an error if no pairs have been pushed
+    pub fn variance_y(&self) -> Result<f64> {
+        self.non_empty()?;
+        Ok(self.m2_y / self.count as f64)
+    }
+
+    /// Returns the (population) covariance of the pairs pushed so far,
+    /// which is positive when `x` and `y` tend to be above their means at
+    /// the same time, and negative when one tends to be above its mean
+    /// while the other is below.
+    ///
+    /// [Wikipedia](<https://en.wikipedia.org/wiki/Covariance>)
+    ///
+    /// # Errors
+    /// Returns an error if no pairs have been pushed
+    pub fn covariance(&self) -> Result<f64> {
+        self.non_empty()?;
+        Ok(self.co_moment / self.count as f64)
+    }
+
+    /// Returns the Pearson correlation coefficient of the pairs pushed so far,
+    /// which is between -1 and 1 and measures how close to a straight line
+    /// the pairs are.
+    ///
+    /// [Wikipedia](<https://en.wikipedia.org/wiki/Pearson_correlation_coefficient>)
+    ///
+    /// # Errors
+    /// Returns an error if no pairs have been pushed.
+    /// If every `x` or every `y` is the same the result is `NaN`.
+    pub fn correlation(&self) -> Result<f64> {
+        self.non_empty()?;
+        Ok(self.co_moment / Float::sqrt(self.m2_x * self.m2_y))
+    }
+}
+
+impl Default for OnlineBivariate {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Extend<(f64, f64)> for OnlineBivariate {
+    fn extend<I: IntoIterator<Item = (f64, f64)>>(&mut self, iter: I) {
+        for (x, y) in iter {
+            self.push(x, y);
+        }
+    }
+}
+
+impl FromIterator<(f64, f64)> for OnlineBivariate {
+    fn from_iter<I: IntoIterator<Item = (f64, f64)>>(iter: I) -> Self {
+        let mut stats = Self::new();
+        stats.extend(iter);
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use approx::assert_relative_eq;
+
+    const PAIRS: [(f64, f64); 6] = [
+        (1.0, 2.0),
+        (2.0, 1.0),
+        (4.0, 5.0),
+        (5.0, 4.0),
+        (7.0, 9.0),
+        (8.0, 6.0),
+    ];
+
+    #[test]
+    fn test_empty() {
+        let stats = OnlineBivariate::new();
+        assert_eq!(stats.mean_x(), Err(StatsError::EmptyCollection));
+        assert_eq!(stats.covariance(), Err(StatsError::EmptyCollection));
+        assert_eq!(stats.correlation(), Err(StatsError::EmptyCollection));
+    }
+
+    #[test]
+    fn test_covariance_correlation() {
+        let stats: OnlineBivariate = PAIRS.into_iter().collect();
+        assert_relative_eq!(stats.mean_x().unwrap(), 4.5);
+        assert_relative_eq!(stats.mean_y().unwrap(), 4.5);
+        assert_relative_eq!(stats.variance_x().unwrap(), 37.5 / 6.0);
+        assert_relative_eq!(stats.variance_y().unwrap(), 41.5 / 6.0);
+        assert_relative_eq!(stats.covariance().unwrap(), 33.5 / 6.0);
+        assert_relative_eq!(
+            stats.correlation().unwrap(),
+            33.5 / (37.5_f64 * 41.5).sqrt()
+        );
+    }
+
+    #[test]
+    fn test_negative_correlation() {
+        let stats: OnlineBivariate = [(1.0, 3.0), (2.0, 2.0), (3.0, 1.0)].into_iter().collect();
+        assert_relative_eq!(stats.correlation().unwrap(), -1.0);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut a: OnlineBivariate = PAIRS[..2].iter().copied().collect();
+        let b: OnlineBivariate = PAIRS[2..].iter().copied().collect();
+        let all: OnlineBivariate = PAIRS.into_iter().collect();
+        a.merge(&b);
+        assert_eq!(a.count(), all.count());
+        assert_relative_eq!(a.covariance().unwrap(), all.covariance().unwrap());
+        assert_relative_eq!(a.variance_y().unwrap(), all.variance_y().unwrap());
+        assert_relative_eq!(a.correlation().unwrap(), all.correlation().unwrap());
+
+        let mut empty = OnlineBivariate::new();
+        empty.merge(&all);
+        assert_eq!(empty, all);
+    }
+}
@@ -0,0 +1,127 @@
+use crate::helpers::Float;
+use crate::Result;
+use crate::StatsError;
+
+/// Range of the parameters searched by [`optimal_lambda`]
+const LAMBDA_RANGE: (f64, f64) = (-5.0, 5.0);
+
+/// Returns the [Box–Cox transform](<https://en.wikipedia.org/wiki/Power_transform#Box%E2%80%93Cox_transformation>)
+/// with parameter `lambda` of `x`, which is `(x^λ - 1) / λ`, or `ln(x)` when
+/// `lambda` is 0, calculated so that it stays accurate as `lambda` nears 0
+pub(crate) fn transform(x: f64, lambda: f64) -> f64 {
+    if lambda == 0.0 {
+        Float::ln(x)
+    } else {
+        Float::exp_m1(lambda * Float::ln(x)) / lambda
+    }
+}
+
+/// Returns the `x` whose Box–Cox transform with parameter `lambda` is `y`,
+/// or `NaN` if there isn't one
+pub(crate) fn inverse(y: f64, lambda: f64) -> f64 {
+    if lambda == 0.0 {
+        Float::exp(y)
+    } else {
+        Float::exp(Float::ln_1p(lambda * y) / lambda)
+    }
+}
+
+/// Returns the profile log-likelihood, up to a constant, of the Box–Cox
+/// transform with parameter `lambda` making normal the items whose
+/// logarithms are `logs`
+fn log_likelihood(logs: &[f64], lambda: f64) -> f64 {
+    let (mut mean, mut m2, mut log_sum) = (0.0, 0.0, 0.0);
+    for (i, &l) in logs.iter().enumerate() {
+        let y = if lambda == 0.0 {
+            l
+        } else {
+            Float::exp_m1(lambda * l) / lambda
+        };
+        let delta = y - mean;
+        mean += delta / (i + 1) as f64;
+        m2 += delta * (y - mean);
+        log_sum += l;
+    }
+    let n = logs.len() as f64;
+    (lambda - 1.0) * log_sum - n / 2.0 * Float::ln(m2 / n)
+}
+
+/// Returns the parameter of the Box–Cox transform that maximizes the
+/// likelihood of the transformed items being normal, searching between
+/// -5 and 5, where `logs` are the logarithms of the items
+///
+/// # Errors
+/// Returns an error if there are fewer than 2 items, or if every item is
+/// the same
+pub(crate) fn optimal_lambda(logs: &[f64]) -> Result<f64> {
+    if logs.len() < 2 {
+        return Err(StatsError::InsufficientData {
+            required: 2,
+            actual: logs.len(),
+        });
+    }
+    if logs.iter().all(|&l| l == logs[0]) {
+        return Err(StatsError::ZeroVariance);
+    }
+    // golden-section search, as the log-likelihood has a single maximum
+    let ratio = (Float::sqrt(5.0f64) - 1.0) / 2.0;
+    let (mut a, mut b) = LAMBDA_RANGE;
+    let mut c = b - ratio * (b - a);
+    let mut d = a + ratio * (b - a);
+    let (mut fc, mut fd) = (log_likelihood(logs, c), log_likelihood(logs, d));
+    while b - a > 1e-10 {
+        if fc > fd {
+            (b, d, fd) = (d, c, fc);
+            c = b - ratio * (b - a);
+            fc = log_likelihood(logs, c);
+        } else {
+            (a, c, fc) = (c, d, fd);
+            d = a + ratio * (b - a);
+            fd = log_likelihood(logs, d);
+        }
+    }
+    Ok((a + b) / 2.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_transform_and_inverse() {
+        assert_eq!(transform(1.0, 2.0), 0.0);
+        assert_relative_eq!(transform(3.0, 2.0), 4.0, epsilon = 1e-12);
+        assert_relative_eq!(transform(3.0, 1e-12), 3.0f64.ln(), epsilon = 1e-9);
+        for lambda in [-1.5, 0.0, 0.25, 2.0] {
+            assert_relative_eq!(
+                inverse(transform(7.5, lambda), lambda),
+                7.5,
+                epsilon = 1e-12
+            );
+        }
+        assert!(inverse(-1.0, 1.0).is_finite());
+        assert!(inverse(-2.0, 1.0).is_nan());
+    }
+
+    #[test]
+    fn test_optimal_lambda() {
+        // the logarithms are symmetric, so the logarithm makes them most normal
+        let logs = [-2.0, -1.0, 0.0, 1.0, 2.0];
+        assert_relative_eq!(optimal_lambda(&logs).unwrap(), 0.0, epsilon = 1e-8);
+        // the log-likelihood is lower on either side of the maximum
+        let logs = [1.0f64, 2.0, 3.0, 4.0, 5.0].map(|x| (x * x).ln());
+        let lambda = optimal_lambda(&logs).unwrap();
+        for nearby in [lambda - 1e-3, lambda + 1e-3] {
+            assert!(log_likelihood(&logs, nearby) < log_likelihood(&logs, lambda));
+        }
+        assert_eq!(
+            optimal_lambda(&[1.0]),
+            Err(StatsError::InsufficientData {
+                required: 2,
+                actual: 1
+            })
+        );
+        assert_eq!(optimal_lambda(&[1.0, 1.0]), Err(StatsError::ZeroVariance));
+    }
+}
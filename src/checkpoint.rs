@@ -0,0 +1,194 @@
+//! Compact binary checkpoints of the streaming types, so that long-running
+//! aggregations can be saved, restored after a restart or sent to another
+//! machine. Every checkpoint starts with a byte identifying the type and a
+//! byte for the version of the format, and the numbers follow in little
+//! endian order, so checkpoints are the same on every platform.
+
+use core::fmt;
+
+/// The version of the format written by the `to_bytes` methods
+pub(crate) const VERSION: u8 = 1;
+
+/// The first byte of the checkpoints of [`OnlineStats`](crate::OnlineStats)
+pub(crate) const ONLINE_STATS: u8 = 1;
+/// The first byte of the checkpoints of `RollingStats`
+#[cfg(feature = "alloc")]
+pub(crate) const ROLLING_STATS: u8 = 2;
+/// The first byte of the checkpoints of `TDigest`
+#[cfg(feature = "alloc")]
+pub(crate) const TDIGEST: u8 = 3;
+/// The first byte of the checkpoints of `HdrHistogram`
+#[cfg(feature = "alloc")]
+pub(crate) const HDR_HISTOGRAM: u8 = 4;
+
+/// Error returned when a checkpoint can't be restored by a `from_bytes`
+/// method, such as [`OnlineStats::from_bytes`](crate::OnlineStats::from_bytes)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum CheckpointError {
+    /// The checkpoint ended before all of the state was read
+    Truncated,
+    /// The checkpoint is of a different type
+    WrongType,
+    /// The checkpoint was written in a version of the format that this
+    /// version of the crate doesn't know, which is likely a newer one
+    UnsupportedVersion(u8),
+    /// The state in the checkpoint is inconsistent, or has bytes after it
+    Invalid,
+}
+
+impl fmt::Display for CheckpointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Truncated => write!(f, "the checkpoint is truncated"),
+            Self::WrongType => write!(f, "the checkpoint is of a different type"),
+            Self::UnsupportedVersion(version) => {
+                write!(
+                    f,
+                    "version {version} of the checkpoint format is not supported"
+                )
+            }
+            Self::Invalid => write!(f, "the checkpoint is invalid"),
+        }
+    }
+}
+
+impl core::error::Error for CheckpointError {}
+
+/// Writes the numbers of a checkpoint into a buffer of exactly the right length
+pub(crate) struct Writer<'a> {
+    bytes: &'a mut [u8],
+}
+
+impl<'a> Writer<'a> {
+    /// Start a checkpoint of the type identified by `tag` in `bytes`
+    pub(crate) fn new(bytes: &'a mut [u8], tag: u8) -> Self {
+        let mut writer = Self { bytes };
+        writer.put_bytes(&[tag, VERSION]);
+        writer
+    }
+
+    /// Write `value` and move past it
+    pub(crate) fn put_bytes(&mut self, value: &[u8]) {
+        let (head, tail) = core::mem::take(&mut self.bytes).split_at_mut(value.len());
+        head.copy_from_slice(value);
+        self.bytes = tail;
+    }
+
+    #[cfg(feature = "alloc")]
+    pub(crate) fn put_u8(&mut self, value: u8) {
+        self.put_bytes(&[value]);
+    }
+
+    pub(crate) fn put_u64(&mut self, value: u64) {
+        self.put_bytes(&value.to_le_bytes());
+    }
+
+    pub(crate) fn put_usize(&mut self, value: usize) {
+        self.put_u64(value as u64);
+    }
+
+    pub(crate) fn put_f64(&mut self, value: f64) {
+        self.put_bytes(&value.to_le_bytes());
+    }
+}
+
+/// Reads the numbers of a checkpoint, checking that it is long enough
+pub(crate) struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    /// Start reading a checkpoint, checking that it is of the type identified
+    /// by `tag` and in a version of the format that can be read
+    pub(crate) fn new(bytes: &'a [u8], tag: u8) -> Result<Self, CheckpointError> {
+        let mut reader = Self { bytes };
+        if reader.get_u8()? != tag {
+            return Err(CheckpointError::WrongType);
+        }
+        match reader.get_u8()? {
+            VERSION => Ok(reader),
+            version => Err(CheckpointError::UnsupportedVersion(version)),
+        }
+    }
+
+    /// Read the next `len` bytes and move past them
+    pub(crate) fn get_bytes(&mut self, len: usize) -> Result<&'a [u8], CheckpointError> {
+        if self.bytes.len() < len {
+            return Err(CheckpointError::Truncated);
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    /// Read the next `N` bytes as an array
+    fn get_array<const N: usize>(&mut self) -> Result<[u8; N], CheckpointError> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.get_bytes(N)?);
+        Ok(array)
+    }
+
+    pub(crate) fn get_u8(&mut self) -> Result<u8, CheckpointError> {
+        Ok(self.get_array::<1>()?[0])
+    }
+
+    pub(crate) fn get_u64(&mut self) -> Result<u64, CheckpointError> {
+        self.get_array().map(u64::from_le_bytes)
+    }
+
+    pub(crate) fn get_usize(&mut self) -> Result<usize, CheckpointError> {
+        usize::try_from(self.get_u64()?).map_err(|_| CheckpointError::Invalid)
+    }
+
+    pub(crate) fn get_f64(&mut self) -> Result<f64, CheckpointError> {
+        self.get_array().map(f64::from_le_bytes)
+    }
+
+    /// Check that the whole checkpoint has been read
+    pub(crate) fn finish(self) -> Result<(), CheckpointError> {
+        if self.bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(CheckpointError::Invalid)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let mut bytes = [0; 19];
+        let mut writer = Writer::new(&mut bytes, 7);
+        writer.put_bytes(&[3]);
+        writer.put_usize(42);
+        writer.put_f64(-1.5);
+        let mut reader = Reader::new(&bytes, 7).unwrap();
+        assert_eq!(reader.get_u8(), Ok(3));
+        assert_eq!(reader.get_usize(), Ok(42));
+        assert_eq!(reader.get_f64(), Ok(-1.5));
+        assert_eq!(reader.finish(), Ok(()));
+    }
+
+    #[test]
+    fn test_invalid_headers() {
+        assert!(matches!(
+            Reader::new(&[7], 7),
+            Err(CheckpointError::Truncated)
+        ));
+        assert!(matches!(
+            Reader::new(&[8, VERSION], 7),
+            Err(CheckpointError::WrongType)
+        ));
+        assert!(matches!(
+            Reader::new(&[7, 200], 7),
+            Err(CheckpointError::UnsupportedVersion(200))
+        ));
+        let reader = Reader::new(&[7, VERSION, 0], 7).unwrap();
+        assert_eq!(reader.finish(), Err(CheckpointError::Invalid));
+    }
+}
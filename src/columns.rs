@@ -0,0 +1,298 @@
+use alloc::vec::Vec;
+
+use num_traits::ToPrimitive;
+
+use crate::helpers::item_to_f64;
+use crate::OnlineStats;
+use crate::Result;
+use crate::StatsError;
+use crate::Summary;
+
+/// Which way to reduce tabular data, mirroring the `axis` of numpy, as used
+/// by [`summaries_along`] and the functions like [`mean_along`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Axis {
+    /// Calculate a statistic of every row, across its columns,
+    /// like `axis=1` in numpy
+    Rows,
+    /// Calculate a statistic of every column, down the rows,
+    /// like `axis=0` in numpy. See [`column_stats`].
+    Columns,
+}
+
+/// Calculate a [`Summary`] of every column of tabular data, given as its
+/// rows, such as a `&[Vec<T>]` or a `&[&[T]]`, in one pass over the rows
+/// without copying the columns out of them
+///
+/// # Examples
+/// ```
+/// use stats_traits::column_stats;
+/// let rows = [vec![1.0, 10.0], vec![2.0, 20.0], vec![3.0, 60.0]];
+/// let columns = column_stats(&rows).unwrap();
+/// assert_eq!(columns.len(), 2);
+/// assert_eq!(columns[0].mean, 2.0);
+/// assert_eq!(columns[1].max, 60.0);
+/// ```
+///
+/// # Errors
+/// Returns an error if there are no rows, or if an item can't be converted to [`f64`]
+///
+/// # Panics
+/// Panics if the rows don't all have the same length
+pub fn column_stats<R, T>(rows: &[R]) -> Result<Vec<Summary>>
+where
+    R: AsRef<[T]>,
+    T: ToPrimitive + Clone,
+{
+    let width = rows.first().map_or(0, |row| row.as_ref().len());
+    assert!(
+        rows.iter().all(|row| row.as_ref().len() == width),
+        "every row must have the same length"
+    );
+    summaries(rows.iter().map(AsRef::as_ref), width)
+}
+
+/// Calculate a [`Summary`] of every column of tabular data stored in one
+/// buffer, one row after another, where `stride` is the number of columns.
+/// See [`column_stats`].
+///
+/// # Examples
+/// ```
+/// use stats_traits::strided_column_stats;
+/// let buffer = [1, 10, 2, 20, 3, 60];
+/// let columns = strided_column_stats(&buffer, 2).unwrap();
+/// assert_eq!(columns[0].mean, 2.0);
+/// assert_eq!(columns[1].min, 10.0);
+/// ```
+///
+/// # Errors
+/// Returns an error if the buffer is empty, or if an item can't be converted to [`f64`]
+///
+/// # Panics
+/// Panics if `stride` is zero, or if the length of the buffer isn't a multiple of it
+pub fn strided_column_stats<T>(values: &[T], stride: usize) -> Result<Vec<Summary>>
+where
+    T: ToPrimitive + Clone,
+{
+    assert!(stride > 0, "stride must be greater than 0");
+    assert!(
+        values.len() % stride == 0,
+        "the length must be a multiple of stride"
+    );
+    summaries(values.chunks_exact(stride), stride)
+}
+
+/// Calculate a [`Summary`] of every row or column of tabular data, given
+/// as its rows like in [`column_stats`]
+///
+/// # Examples
+/// ```
+/// use stats_traits::{summaries_along, Axis};
+/// let rows = [[1.0, 3.0], [2.0, 6.0], [3.0, 9.0]];
+/// let rows_summaries = summaries_along(&rows, Axis::Rows).unwrap();
+/// assert_eq!(rows_summaries.len(), 3);
+/// assert_eq!(rows_summaries[2].mean, 6.0);
+/// let column_summaries = summaries_along(&rows, Axis::Columns).unwrap();
+/// assert_eq!(column_summaries.len(), 2);
+/// assert_eq!(column_summaries[1].mean, 6.0);
+/// ```
+///
+/// # Errors
+/// Returns an error if there is a row or column to summarize that is
+/// empty, or if an item can't be converted to [`f64`]
+///
+/// # Panics
+/// Panics if the rows don't all have the same length
+pub fn summaries_along<R, T>(rows: &[R], axis: Axis) -> Result<Vec<Summary>>
+where
+    R: AsRef<[T]>,
+    T: ToPrimitive + Clone,
+{
+    match axis {
+        Axis::Columns => column_stats(rows),
+        Axis::Rows => {
+            let width = rows.first().map_or(0, |row| row.as_ref().len());
+            assert!(
+                rows.iter().all(|row| row.as_ref().len() == width),
+                "every row must have the same length"
+            );
+            rows.iter()
+                .map(|row| {
+                    let mut stats = OnlineStats::new();
+                    for x in row.as_ref() {
+                        stats.push(item_to_f64(x.clone())?);
+                    }
+                    stats.summary()
+                })
+                .collect()
+        }
+    }
+}
+
+/// Calculate the mean of every row or column of tabular data.
+/// See [`summaries_along`].
+///
+/// # Examples
+/// ```
+/// use stats_traits::{mean_along, Axis};
+/// let rows = [[1, 3], [2, 6], [3, 9]];
+/// assert_eq!(mean_along(&rows, Axis::Rows), Ok(vec![2.0, 4.0, 6.0]));
+/// assert_eq!(mean_along(&rows, Axis::Columns), Ok(vec![2.0, 6.0]));
+/// ```
+///
+/// # Errors
+/// Errors under the same conditions as [`summaries_along`]
+///
+/// # Panics
+/// Panics if the rows don't all have the same length
+pub fn mean_along<R, T>(rows: &[R], axis: Axis) -> Result<Vec<f64>>
+where
+    R: AsRef<[T]>,
+    T: ToPrimitive + Clone,
+{
+    along(rows, axis, |summary| summary.mean)
+}
+
+/// Calculate the (population) variance of every row or column of tabular
+/// data. See [`summaries_along`].
+///
+/// # Errors
+/// Errors under the same conditions as [`summaries_along`]
+///
+/// # Panics
+/// Panics if the rows don't all have the same length
+pub fn variance_along<R, T>(rows: &[R], axis: Axis) -> Result<Vec<f64>>
+where
+    R: AsRef<[T]>,
+    T: ToPrimitive + Clone,
+{
+    along(rows, axis, |summary| summary.variance)
+}
+
+/// Find the smallest value of every row or column of tabular data.
+/// See [`summaries_along`].
+///
+/// # Errors
+/// Errors under the same conditions as [`summaries_along`]
+///
+/// # Panics
+/// Panics if the rows don't all have the same length
+pub fn min_along<R, T>(rows: &[R], axis: Axis) -> Result<Vec<f64>>
+where
+    R: AsRef<[T]>,
+    T: ToPrimitive + Clone,
+{
+    along(rows, axis, |summary| summary.min)
+}
+
+/// Find the largest value of every row or column of tabular data.
+/// See [`summaries_along`].
+///
+/// # Errors
+/// Errors under the same conditions as [`summaries_along`]
+///
+/// # Panics
+/// Panics if the rows don't all have the same length
+pub fn max_along<R, T>(rows: &[R], axis: Axis) -> Result<Vec<f64>>
+where
+    R: AsRef<[T]>,
+    T: ToPrimitive + Clone,
+{
+    along(rows, axis, |summary| summary.max)
+}
+
+/// Returns `statistic` of the summary of every row or column of `rows`
+fn along<R, T, F>(rows: &[R], axis: Axis, statistic: F) -> Result<Vec<f64>>
+where
+    R: AsRef<[T]>,
+    T: ToPrimitive + Clone,
+    F: FnMut(&Summary) -> f64,
+{
+    Ok(summaries_along(rows, axis)?.iter().map(statistic).collect())
+}
+
+/// Returns the summaries of the columns of `rows`, which all have `width` items
+fn summaries<'a, I, T>(rows: I, width: usize) -> Result<Vec<Summary>>
+where
+    I: IntoIterator<Item = &'a [T]>,
+    T: ToPrimitive + Clone + 'a,
+{
+    let mut columns = Vec::new();
+    columns.resize_with(width, OnlineStats::new);
+    let mut empty = true;
+    for row in rows {
+        empty = false;
+        for (column, x) in columns.iter_mut().zip(row) {
+            column.push(item_to_f64(x.clone())?);
+        }
+    }
+    if empty {
+        return Err(StatsError::EmptyCollection);
+    }
+    columns.iter().map(OnlineStats::summary).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::vec;
+
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_column_stats() {
+        let (a, b, c) = ([1u8, 4, 9], [3, 4, 1], [5, 4, 2]);
+        let columns = column_stats(&[&a[..], &b, &c]).unwrap();
+        assert_eq!(columns.len(), 3);
+        assert_eq!(columns[0].mean, 3.0);
+        assert_relative_eq!(columns[0].variance, 8.0 / 3.0);
+        assert_eq!(columns[1].variance, 0.0);
+        assert_eq!(columns[2].min, 1.0);
+        assert_eq!(columns[2].count, 3);
+        let buffer = [a, b, c].concat();
+        assert_eq!(strided_column_stats(&buffer, 3).unwrap(), columns);
+    }
+
+    #[test]
+    fn test_along_axes() {
+        let rows = [[4.0, -1.0, 0.5], [2.0, 3.0, 0.5]];
+        assert_eq!(mean_along(&rows, Axis::Columns), Ok(vec![3.0, 1.0, 0.5]));
+        assert_eq!(
+            variance_along(&rows, Axis::Columns),
+            Ok(vec![1.0, 4.0, 0.0])
+        );
+        assert_eq!(min_along(&rows, Axis::Rows), Ok(vec![-1.0, 0.5]));
+        assert_eq!(max_along(&rows, Axis::Rows), Ok(vec![4.0, 3.0]));
+        assert_eq!(summaries_along(&rows, Axis::Columns), column_stats(&rows));
+        let no_rows: [[f64; 2]; 0] = [];
+        assert_eq!(mean_along(&no_rows, Axis::Rows), Ok(vec![]));
+        let empty_rows: [[f64; 0]; 2] = [[], []];
+        assert_eq!(
+            mean_along(&empty_rows, Axis::Rows),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+
+    #[test]
+    fn test_no_rows() {
+        let rows: [[f64; 2]; 0] = [];
+        assert_eq!(column_stats(&rows), Err(StatsError::EmptyCollection));
+        assert_eq!(
+            strided_column_stats::<f64>(&[], 2),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "every row must have the same length")]
+    fn test_ragged_rows() {
+        let _ = column_stats(&[vec![1.0, 2.0], vec![3.0]]);
+    }
+
+    #[test]
+    #[should_panic(expected = "the length must be a multiple of stride")]
+    fn test_partial_row() {
+        let _ = strided_column_stats(&[1.0, 2.0, 3.0], 2);
+    }
+}
@@ -0,0 +1,142 @@
+use num_complex::Complex;
+use num_traits::Float;
+
+use crate::Result;
+use crate::Stats;
+use crate::StatsError;
+
+/// An extension trait for collections of [`Complex`] numbers, such as IQ
+/// samples. The sum and mean come from [`Stats`] as usual, and this adds
+/// the statistics that need the magnitude of the numbers, which is real.
+///
+/// It is implemented for every [`Stats`] whose items are complex.
+///
+/// # Examples
+/// ```
+/// use num_complex::Complex;
+/// use stats_traits::{ComplexStats, Stats};
+/// let samples = vec![Complex::new(1.0, 1.0), Complex::new(-1.0, 1.0), Complex::new(0.0, -2.0)];
+/// assert_eq!(samples.mean(), Ok(Complex::new(0.0, 0.0)));
+/// // the mean squared magnitude of the differences from the mean
+/// assert_eq!(samples.complex_variance(), Ok(8.0 / 3.0));
+/// assert_eq!(samples.max_by_magnitude(), Ok(Complex::new(0.0, -2.0)));
+/// ```
+pub trait ComplexStats<T>: Stats<Item = Complex<T>>
+where
+    T: Float,
+{
+    /// Calculate the (population) variance of the items, which is the mean
+    /// of the squared magnitudes of their differences from their mean. It
+    /// is real, and equal to the sum of the variances of the real and
+    /// imaginary parts.
+    ///
+    /// # Errors
+    /// Returns an error under the same conditions as [`Stats::mean`]
+    fn complex_variance(&self) -> Result<T> {
+        let mean = self.mean()?;
+        let (sum, count) = self
+            .items()
+            .fold((T::zero(), T::zero()), |(sum, count), x| {
+                (sum + (x - mean).norm_sqr(), count + T::one())
+            });
+        Ok(sum / count)
+    }
+
+    /// Calculate the standard deviation of the items, which is the square
+    /// root of [`ComplexStats::complex_variance`]
+    ///
+    /// # Errors
+    /// Returns an error under the same conditions as [`Stats::mean`]
+    fn complex_std_dev(&self) -> Result<T> {
+        Ok(self.complex_variance()?.sqrt())
+    }
+
+    /// Find the item with the smallest magnitude, or the first of them if
+    /// there are several. Items with a `NaN` part are ignored.
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty, or only has items with a `NaN` part
+    fn min_by_magnitude(&self) -> Result<Complex<T>> {
+        self.items()
+            .filter(|x| !x.re.is_nan() && !x.im.is_nan())
+            .reduce(|min, x| {
+                if x.norm_sqr() < min.norm_sqr() {
+                    x
+                } else {
+                    min
+                }
+            })
+            .ok_or(StatsError::EmptyCollection)
+    }
+
+    /// Find the item with the largest magnitude, or the first of them if
+    /// there are several. Items with a `NaN` part are ignored.
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty, or only has items with a `NaN` part
+    fn max_by_magnitude(&self) -> Result<Complex<T>> {
+        self.items()
+            .filter(|x| !x.re.is_nan() && !x.im.is_nan())
+            .reduce(|max, x| {
+                if x.norm_sqr() > max.norm_sqr() {
+                    x
+                } else {
+                    max
+                }
+            })
+            .ok_or(StatsError::EmptyCollection)
+    }
+}
+
+/// Blanket implementation for all [`Stats`] with complex items
+impl<S, T> ComplexStats<T> for S
+where
+    S: Stats<Item = Complex<T>> + ?Sized,
+    T: Float,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_variance_is_sum_of_parts() {
+        let samples = [
+            Complex::new(0.5, 2.0),
+            Complex::new(1.5, -1.0),
+            Complex::new(-3.0, 0.25),
+            Complex::new(2.0, 4.0),
+        ];
+        let re = samples.map(|x| x.re);
+        let im = samples.map(|x| x.im);
+        assert_relative_eq!(
+            samples.complex_variance().unwrap(),
+            re.variance().unwrap() + im.variance().unwrap(),
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(
+            samples.complex_std_dev().unwrap(),
+            samples.complex_variance().unwrap().sqrt()
+        );
+        assert_eq!(samples.sum(), Complex::new(1.0, 5.25));
+    }
+
+    #[test]
+    fn test_magnitudes() {
+        let samples = [
+            Complex::new(3.0f32, 4.0),
+            Complex::new(f32::NAN, 0.0),
+            Complex::new(-5.0, 0.0),
+            Complex::new(0.0, 1.0),
+        ];
+        assert_eq!(samples.min_by_magnitude(), Ok(Complex::new(0.0, 1.0)));
+        // ties go to the first
+        assert_eq!(samples.max_by_magnitude(), Ok(Complex::new(3.0, 4.0)));
+        let empty: [Complex<f64>; 0] = [];
+        assert_eq!(empty.complex_variance(), Err(StatsError::EmptyCollection));
+        assert_eq!(empty.max_by_magnitude(), Err(StatsError::EmptyCollection));
+    }
+}
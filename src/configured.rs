@@ -0,0 +1,553 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::ops::{Deref, DerefMut};
+
+use num_traits::ToPrimitive;
+
+#[cfg(feature = "alloc")]
+use crate::helpers::interpolate;
+use crate::helpers::item_to_f64;
+#[cfg(feature = "alloc")]
+use crate::helpers::partial_cmp_nan_last;
+#[cfg(feature = "alloc")]
+use crate::helpers::quantile_of_sorted;
+use crate::helpers::Float;
+use crate::OnlineStats;
+use crate::Result;
+use crate::Stats;
+use crate::StatsError;
+
+/// How a quantile between two items is calculated, as in numpy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Interpolation {
+    /// Interpolate linearly between the two items
+    #[default]
+    Linear,
+    /// Use the smaller item
+    Lower,
+    /// Use the larger item
+    Higher,
+    /// Use the nearer item, choosing with the [`TieBreak`] when
+    /// the quantile is exactly halfway between them
+    Nearest,
+    /// Use the mean of the two items
+    Midpoint,
+}
+
+/// What to do with `NaN` items
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum NanPolicy {
+    /// Keep them, so any statistic of a collection with a `NaN` is `NaN`
+    #[default]
+    Propagate,
+    /// Skip them, as if they weren't in the collection
+    Omit,
+}
+
+/// Which of two equally good results to choose, such as values that are
+/// equally frequent for the mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TieBreak {
+    /// Choose the smaller one
+    #[default]
+    Smallest,
+    /// Choose the larger one
+    Largest,
+}
+
+/// Policies for calculating statistics, which a [`Configured`] collection
+/// applies to every statistic instead of them being passed to each call.
+/// Start with [`StatsConfig::new`] and change the policies that matter.
+///
+/// # Examples
+/// ```
+/// use stats_traits::{Interpolation, NanPolicy, StatsConfig};
+/// let config = StatsConfig::new()
+///     .ddof(1)
+///     .interpolation(Interpolation::Lower)
+///     .nan_policy(NanPolicy::Omit);
+/// let data = config.wrap(vec![1.0, f64::NAN, 2.0, 3.0, 4.0]);
+/// assert_eq!(data.count(), Ok(4));
+/// assert_eq!(data.variance(), Ok(5.0 / 3.0));
+/// assert_eq!(data.max(), Ok(4.0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct StatsConfig {
+    ddof: usize,
+    interpolation: Interpolation,
+    nan_policy: NanPolicy,
+    tie_break: TieBreak,
+}
+
+impl StatsConfig {
+    /// The default policies: no delta degrees of freedom (population
+    /// statistics), [`Interpolation::Linear`], [`NanPolicy::Propagate`]
+    /// and [`TieBreak::Smallest`]
+    pub const fn new() -> Self {
+        Self {
+            ddof: 0,
+            interpolation: Interpolation::Linear,
+            nan_policy: NanPolicy::Propagate,
+            tie_break: TieBreak::Smallest,
+        }
+    }
+
+    /// Use `ddof` delta degrees of freedom for the variance and standard
+    /// deviation, such as 1 for sample statistics. See [`Stats::variance_ddof`].
+    #[must_use]
+    pub const fn ddof(mut self, ddof: usize) -> Self {
+        self.ddof = ddof;
+        self
+    }
+
+    /// Calculate quantiles between two items with `interpolation`
+    #[must_use]
+    pub const fn interpolation(mut self, interpolation: Interpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
+    /// Treat `NaN` items according to `nan_policy`
+    #[must_use]
+    pub const fn nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+
+    /// Break ties according to `tie_break`
+    #[must_use]
+    pub const fn tie_break(mut self, tie_break: TieBreak) -> Self {
+        self.tie_break = tie_break;
+        self
+    }
+
+    /// Returns the delta degrees of freedom
+    pub const fn get_ddof(&self) -> usize {
+        self.ddof
+    }
+
+    /// Returns how quantiles are interpolated
+    pub const fn get_interpolation(&self) -> Interpolation {
+        self.interpolation
+    }
+
+    /// Returns what is done with `NaN` items
+    pub const fn get_nan_policy(&self) -> NanPolicy {
+        self.nan_policy
+    }
+
+    /// Returns how ties are broken
+    pub const fn get_tie_break(&self) -> TieBreak {
+        self.tie_break
+    }
+
+    /// Wrap `inner`, so its statistics are calculated with these policies
+    pub const fn wrap<T>(self, inner: T) -> Configured<T> {
+        Configured::new(inner, self)
+    }
+}
+
+/// Wraps a collection with a [`StatsConfig`], whose policies are applied to
+/// every statistic of it. The statistics are calculated on the items
+/// converted to [`f64`], like those of [`Described`](crate::Described).
+///
+/// The collection can be read through [`Deref`] and changed through [`DerefMut`].
+///
+/// # Examples
+/// ```
+/// use stats_traits::{Configured, StatsConfig};
+/// let mut sample = Configured::new(vec![2, 4, 4, 4, 5, 5, 7], StatsConfig::new().ddof(1));
+/// sample.push(9);
+/// assert_eq!(sample.variance(), Ok(32.0 / 7.0));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Configured<T> {
+    inner: T,
+    config: StatsConfig,
+}
+
+impl<T> Configured<T> {
+    /// Wrap `inner`, so its statistics are calculated with the policies of `config`
+    pub const fn new(inner: T, config: StatsConfig) -> Self {
+        Self { inner, config }
+    }
+
+    /// Returns the policies
+    pub fn config(&self) -> StatsConfig {
+        self.config
+    }
+
+    /// Replace the policies with `config`
+    pub fn set_config(&mut self, config: StatsConfig) {
+        self.config = config;
+    }
+
+    /// Returns a reference to the collection
+    pub fn get(&self) -> &T {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the collection
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Unwrap the collection
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T> Configured<T>
+where
+    T: Stats,
+    T::Item: ToPrimitive,
+{
+    /// Returns the items converted to [`f64`], without
+    /// the `NaN`s if they are to be omitted
+    fn values(&self) -> impl Iterator<Item = Result<f64>> + '_ {
+        let omit = self.config.nan_policy == NanPolicy::Omit;
+        self.inner
+            .items()
+            .map(item_to_f64)
+            .filter(move |x| !(omit && matches!(x, Ok(x) if x.is_nan())))
+    }
+
+    /// Returns an [`OnlineStats`] with every value pushed to it
+    fn online_stats(&self) -> Result<OnlineStats> {
+        let mut stats = OnlineStats::new();
+        for x in self.values() {
+            stats.push(x?);
+        }
+        Ok(stats)
+    }
+
+    /// Returns the value that `pick` chooses over every other one,
+    /// or `NaN` if there is a `NaN` and it isn't omitted
+    fn extreme(&self, pick: fn(f64, f64) -> f64) -> Result<f64> {
+        let mut extreme = None;
+        for x in self.values() {
+            let x = x?;
+            if x.is_nan() {
+                return Ok(f64::NAN);
+            }
+            extreme = Some(extreme.map_or(x, |extreme| pick(extreme, x)));
+        }
+        extreme.ok_or(StatsError::EmptyCollection)
+    }
+
+    /// Returns the number of items, not counting omitted `NaN`s
+    ///
+    /// # Errors
+    /// Returns an error if an item can't be converted to [`f64`]
+    pub fn count(&self) -> Result<usize> {
+        self.values().try_fold(0, |count, x| x.map(|_| count + 1))
+    }
+
+    /// Returns the mean of the items
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty, or if an item can't be converted to [`f64`]
+    pub fn mean(&self) -> Result<f64> {
+        self.online_stats()?.mean()
+    }
+
+    /// Returns the variance of the items with the configured delta degrees
+    /// of freedom. See [`Stats::variance_ddof`].
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty, if an item can't be converted
+    /// to [`f64`], or `StatsError::InsufficientData` if there are `ddof` items or fewer
+    pub fn variance(&self) -> Result<f64> {
+        let stats = self.online_stats()?;
+        let population = stats.variance()?;
+        let (count, ddof) = (stats.count(), self.config.ddof);
+        if count <= ddof {
+            return Err(StatsError::InsufficientData {
+                required: ddof + 1,
+                actual: count,
+            });
+        }
+        Ok(population * count as f64 / (count - ddof) as f64)
+    }
+
+    /// Returns the standard deviation of the items, which
+    /// is the square root of [`Configured::variance`]
+    ///
+    /// # Errors
+    /// Errors under the same conditions as [`Configured::variance`]
+    pub fn std_dev(&self) -> Result<f64> {
+        self.variance().map(Float::sqrt)
+    }
+
+    /// Returns the smallest item
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty, or if an item can't be converted to [`f64`]
+    pub fn min(&self) -> Result<f64> {
+        self.extreme(f64::min)
+    }
+
+    /// Returns the largest item
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty, or if an item can't be converted to [`f64`]
+    pub fn max(&self) -> Result<f64> {
+        self.extreme(f64::max)
+    }
+
+    /// Returns the items sorted, or `None` if there
+    /// is a `NaN` and it isn't omitted
+    #[cfg(feature = "alloc")]
+    fn sorted(&self) -> Result<Option<Vec<f64>>> {
+        let mut sorted = self.values().collect::<Result<Vec<f64>>>()?;
+        if sorted.is_empty() {
+            return Err(StatsError::EmptyCollection);
+        }
+        if sorted.iter().any(|x| x.is_nan()) {
+            return Ok(None);
+        }
+        sorted.sort_by(partial_cmp_nan_last);
+        Ok(Some(sorted))
+    }
+
+    /// Returns the `p` quantile of the items, interpolated between the two
+    /// nearest items with the configured [`Interpolation`]
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::{Interpolation, StatsConfig};
+    /// let data = StatsConfig::new().wrap(vec![1, 2, 3, 4, 5]);
+    /// assert_eq!(data.quantile(0.3), Ok(2.2));
+    /// let data = StatsConfig::new()
+    ///     .interpolation(Interpolation::Nearest)
+    ///     .wrap(vec![1, 2, 3, 4, 5]);
+    /// assert_eq!(data.quantile(0.3), Ok(2.0));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty, or if an item can't be converted to [`f64`]
+    ///
+    /// # Panics
+    /// Panics if `p` is not between 0 and 1
+    #[cfg(feature = "alloc")]
+    pub fn quantile(&self, p: f64) -> Result<f64> {
+        assert!((0.0..=1.0).contains(&p), "p must be between 0 and 1");
+        let Some(sorted) = self.sorted()? else {
+            return Ok(f64::NAN);
+        };
+        Ok(self.quantile_of_sorted(&sorted, p))
+    }
+
+    /// Returns the quantile of the items for every `p` in `ps`, in the same
+    /// order, like [`Configured::quantile`] but sorting the items only once
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::StatsConfig;
+    /// let data = StatsConfig::new().wrap(vec![5, 1, 4, 2, 3]);
+    /// assert_eq!(data.quantiles(&[0.5, 0.25, 1.0]), Ok(vec![3.0, 2.0, 5.0]));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty, or if an item can't be converted to [`f64`]
+    ///
+    /// # Panics
+    /// Panics if any `p` is not between 0 and 1
+    #[cfg(feature = "alloc")]
+    pub fn quantiles(&self, ps: &[f64]) -> Result<Vec<f64>> {
+        assert!(
+            ps.iter().all(|p| (0.0..=1.0).contains(p)),
+            "p must be between 0 and 1"
+        );
+        Ok(match self.sorted()? {
+            Some(sorted) => ps
+                .iter()
+                .map(|&p| self.quantile_of_sorted(&sorted, p))
+                .collect(),
+            None => alloc::vec![f64::NAN; ps.len()],
+        })
+    }
+
+    /// Returns the `p` quantile of the non-empty `sorted` items
+    #[cfg(feature = "alloc")]
+    fn quantile_of_sorted(&self, sorted: &[f64], p: f64) -> f64 {
+        let position = p * (sorted.len() - 1) as f64;
+        let (lower, upper) = (
+            sorted[Float::floor(position) as usize],
+            sorted[Float::ceil(position) as usize],
+        );
+        let fraction = position - Float::floor(position);
+        match self.config.interpolation {
+            Interpolation::Linear => quantile_of_sorted(sorted, p),
+            Interpolation::Lower => lower,
+            Interpolation::Higher => upper,
+            Interpolation::Nearest if fraction == 0.5 => match self.config.tie_break {
+                TieBreak::Smallest => lower,
+                TieBreak::Largest => upper,
+            },
+            Interpolation::Nearest if fraction < 0.5 => lower,
+            Interpolation::Nearest => upper,
+            Interpolation::Midpoint => interpolate(lower, upper, 0.5),
+        }
+    }
+
+    /// Returns the median of the items, which is the 0.5 quantile.
+    /// See [`Configured::quantile`].
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty, or if an item can't be converted to [`f64`]
+    #[cfg(feature = "alloc")]
+    pub fn median(&self) -> Result<f64> {
+        self.quantile(0.5)
+    }
+
+    /// Returns the most frequent item, choosing between equally
+    /// frequent items with the configured [`TieBreak`]
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::{Configured, StatsConfig, TieBreak};
+    /// let votes = Configured::new(
+    ///     vec![3, 1, 3, 2, 1],
+    ///     StatsConfig::new().tie_break(TieBreak::Largest),
+    /// );
+    /// assert_eq!(votes.mode(), Ok(3.0));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty, or if an item can't be converted to [`f64`]
+    #[cfg(feature = "alloc")]
+    pub fn mode(&self) -> Result<f64> {
+        let Some(sorted) = self.sorted()? else {
+            return Ok(f64::NAN);
+        };
+        let (mut mode, mut frequency) = (sorted[0], 0);
+        for run in sorted.chunk_by(|a, b| a == b) {
+            let better = match self.config.tie_break {
+                TieBreak::Smallest => run.len() > frequency,
+                TieBreak::Largest => run.len() >= frequency,
+            };
+            if better {
+                (mode, frequency) = (run[0], run.len());
+            }
+        }
+        Ok(mode)
+    }
+}
+
+impl<T> Deref for Configured<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T> DerefMut for Configured<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    extern crate std;
+    use std::vec;
+
+    #[test]
+    fn test_nan_policy() {
+        let values = [1.0, f64::NAN, 3.0];
+        let propagate = StatsConfig::new().wrap(values);
+        assert_eq!(propagate.count(), Ok(3));
+        assert!(propagate.mean().unwrap().is_nan());
+        assert!(propagate.min().unwrap().is_nan());
+        assert!(propagate.max().unwrap().is_nan());
+        let omit = StatsConfig::new().nan_policy(NanPolicy::Omit).wrap(values);
+        assert_eq!(omit.count(), Ok(2));
+        assert_eq!(omit.mean(), Ok(2.0));
+        assert_eq!(omit.min(), Ok(1.0));
+        assert_eq!(omit.max(), Ok(3.0));
+        let only_nan = StatsConfig::new()
+            .nan_policy(NanPolicy::Omit)
+            .wrap([f64::NAN]);
+        assert_eq!(only_nan.mean(), Err(StatsError::EmptyCollection));
+    }
+
+    #[test]
+    fn test_ddof() {
+        let values = vec![1, 2, 3, 4, 5];
+        for ddof in 0..5 {
+            let data = StatsConfig::new().ddof(ddof).wrap(values.clone());
+            assert_eq!(data.variance(), Ok(10.0 / (5 - ddof) as f64));
+        }
+        let data = StatsConfig::new().ddof(5).wrap(values);
+        assert_eq!(
+            data.std_dev(),
+            Err(StatsError::InsufficientData {
+                required: 6,
+                actual: 5
+            })
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_interpolation() {
+        // the 0.5 quantile is halfway between 2 and 3
+        let values = [4, 1, 3, 2];
+        let expected = [
+            (Interpolation::Linear, 2.5),
+            (Interpolation::Lower, 2.0),
+            (Interpolation::Higher, 3.0),
+            (Interpolation::Nearest, 2.0),
+            (Interpolation::Midpoint, 2.5),
+        ];
+        for (interpolation, median) in expected {
+            let data = StatsConfig::new().interpolation(interpolation).wrap(values);
+            assert_eq!(data.median(), Ok(median), "{interpolation:?}");
+        }
+        let data = StatsConfig::new()
+            .interpolation(Interpolation::Nearest)
+            .tie_break(TieBreak::Largest)
+            .wrap(values);
+        assert_eq!(data.median(), Ok(3.0));
+        assert_eq!(data.quantile(0.4), Ok(2.0));
+        assert_eq!(data.quantile(1.0), Ok(4.0));
+        assert_eq!(data.quantiles(&[1.0, 0.4]), Ok(vec![4.0, 2.0]));
+        let data = StatsConfig::new().wrap([1.0, f64::NAN]);
+        assert!(data.quantiles(&[0.5]).unwrap()[0].is_nan());
+        let data = StatsConfig::new().wrap([1.0, 2.0, f64::INFINITY]);
+        assert_eq!(data.median(), Ok(2.0));
+        assert_eq!(data.quantiles(&[0.0, 0.75]), Ok(vec![1.0, f64::INFINITY]));
+        let data = StatsConfig::new()
+            .interpolation(Interpolation::Midpoint)
+            .wrap([-f64::MAX, f64::MAX, f64::MAX, f64::INFINITY]);
+        assert_eq!(data.quantile(1.0 / 6.0), Ok(0.0));
+        assert_eq!(data.quantile(0.5), Ok(f64::MAX));
+        assert_eq!(data.quantile(0.9), Ok(f64::INFINITY));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_mode() {
+        let mut data = Configured::new(vec![2, 5, 2, 5, 1], StatsConfig::new());
+        assert_eq!(data.mode(), Ok(2.0));
+        data.set_config(data.config().tie_break(TieBreak::Largest));
+        assert_eq!(data.mode(), Ok(5.0));
+        data.push(1);
+        data.push(1);
+        assert_eq!(data.mode(), Ok(1.0));
+        data.clear();
+        assert_eq!(data.mode(), Err(StatsError::EmptyCollection));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    #[should_panic]
+    fn test_quantile_out_of_range() {
+        let _ = StatsConfig::new().wrap([1.0]).quantile(1.5);
+    }
+}
@@ -0,0 +1,221 @@
+use crate::error::DataType;
+use crate::Result;
+use crate::StatsError;
+
+/// Wraps an array to calculate its statistics in `const fn`s, so lookup
+/// tables and calibration constants can be summarized at compile time.
+///
+/// Traits can't be used in `const fn`s yet, so [`Stats`](crate::Stats) can't
+/// be either, and the methods here are implemented separately for each of
+/// the primitive integer and float types. They go through the items the
+/// same way as [`Stats`](crate::Stats), so they give the same results, and
+/// an overflow is a compile error when they are evaluated at compile time.
+///
+/// # Examples
+/// ```
+/// use stats_traits::ConstArray;
+/// const TABLE: [u16; 4] = [120, 340, 560, 980];
+/// const MEAN: u16 = match ConstArray(TABLE).mean() {
+///     Ok(mean) => mean,
+///     Err(_) => panic!("the table is empty"),
+/// };
+/// assert_eq!(MEAN, 500);
+/// const GAINS: ConstArray<f32, 3> = ConstArray([0.5, 1.5, 1.0]);
+/// const TOTAL: f32 = GAINS.sum();
+/// assert_eq!(TOTAL, 3.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ConstArray<T, const N: usize>(pub [T; N]);
+
+macro_rules! impl_const_array_int {
+    ($($type: ty)*) => {
+        $(impl<const N: usize> ConstArray<$type, N> {
+            /// Calculate the sum of the items
+            ///
+            /// # Panics
+            /// Panics if the sum overflows, which is a compile error at compile time
+            pub const fn sum(&self) -> $type {
+                let mut sum = 0;
+                let mut i = 0;
+                while i < N {
+                    sum += self.0[i];
+                    i += 1;
+                }
+                sum
+            }
+
+            /// Calculate the mean of the items, rounded towards zero like [`Stats::mean`](crate::Stats::mean)
+            ///
+            /// # Errors
+            /// Returns an error if the array is empty, or if
+            /// its length is too large for the type of the items
+            ///
+            /// # Panics
+            /// Panics if the sum overflows, which is a compile error at compile time
+            pub const fn mean(&self) -> Result<$type> {
+                if N == 0 {
+                    return Err(StatsError::EmptyCollection);
+                }
+                if N as u128 > <$type>::MAX as u128 {
+                    return Err(StatsError::CouldNotConvert {
+                        from: DataType::Usize,
+                        to: DataType::Item,
+                    });
+                }
+                Ok(self.sum() / N as $type)
+            }
+
+            /// Find the smallest item
+            ///
+            /// # Errors
+            /// Returns an error if the array is empty
+            pub const fn min(&self) -> Result<$type> {
+                if N == 0 {
+                    return Err(StatsError::EmptyCollection);
+                }
+                let mut min = self.0[0];
+                let mut i = 1;
+                while i < N {
+                    if self.0[i] < min {
+                        min = self.0[i];
+                    }
+                    i += 1;
+                }
+                Ok(min)
+            }
+
+            /// Find the largest item
+            ///
+            /// # Errors
+            /// Returns an error if the array is empty
+            pub const fn max(&self) -> Result<$type> {
+                if N == 0 {
+                    return Err(StatsError::EmptyCollection);
+                }
+                let mut max = self.0[0];
+                let mut i = 1;
+                while i < N {
+                    if self.0[i] > max {
+                        max = self.0[i];
+                    }
+                    i += 1;
+                }
+                Ok(max)
+            }
+        })*
+    };
+}
+
+macro_rules! impl_const_array_float {
+    ($($type: ty)*) => {
+        $(impl<const N: usize> ConstArray<$type, N> {
+            /// Calculate the sum of the items, adding them one after another
+            pub const fn sum(&self) -> $type {
+                let mut sum = 0.0;
+                let mut i = 0;
+                while i < N {
+                    sum += self.0[i];
+                    i += 1;
+                }
+                sum
+            }
+
+            /// Calculate the mean of the items
+            ///
+            /// # Errors
+            /// Returns an error if the array is empty
+            pub const fn mean(&self) -> Result<$type> {
+                if N == 0 {
+                    return Err(StatsError::EmptyCollection);
+                }
+                Ok(self.sum() / N as $type)
+            }
+
+            /// Find the smallest item, ignoring `NaN`s like [`Stats::min`](crate::Stats::min)
+            ///
+            /// # Errors
+            /// Returns an error if the array is empty
+            pub const fn min(&self) -> Result<$type> {
+                if N == 0 {
+                    return Err(StatsError::EmptyCollection);
+                }
+                let mut min = self.0[0];
+                let mut i = 1;
+                while i < N {
+                    if self.0[i] < min || min.is_nan() {
+                        min = self.0[i];
+                    }
+                    i += 1;
+                }
+                Ok(min)
+            }
+
+            /// Find the largest item, ignoring `NaN`s like [`Stats::max`](crate::Stats::max)
+            ///
+            /// # Errors
+            /// Returns an error if the array is empty
+            pub const fn max(&self) -> Result<$type> {
+                if N == 0 {
+                    return Err(StatsError::EmptyCollection);
+                }
+                let mut max = self.0[0];
+                let mut i = 1;
+                while i < N {
+                    if self.0[i] > max || max.is_nan() {
+                        max = self.0[i];
+                    }
+                    i += 1;
+                }
+                Ok(max)
+            }
+        })*
+    };
+}
+
+impl_const_array_int!(i8 i16 i32 i64 i128 isize);
+impl_const_array_int!(u8 u16 u32 u64 u128 usize);
+impl_const_array_float!(f32 f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Stats;
+
+    const SQUARES: ConstArray<i32, 5> = ConstArray([1, 4, 9, 16, 25]);
+    const SUM: i32 = SQUARES.sum();
+
+    #[test]
+    fn test_matches_stats() {
+        assert_eq!(SUM, SQUARES.0.sum());
+        assert_eq!(SQUARES.mean(), SQUARES.0.mean());
+        assert_eq!(SQUARES.min(), Ok(1));
+        assert_eq!(SQUARES.max(), Ok(25));
+        let floats = ConstArray([0.1, 0.7, -0.3, f64::NAN]);
+        assert!(floats.mean().unwrap().is_nan());
+        assert_eq!(floats.min(), Stats::min(&floats.0));
+        assert_eq!(floats.max(), Stats::max(&floats.0));
+        let first_nan = ConstArray([f32::NAN, 2.0, 1.0]);
+        assert_eq!(first_nan.min(), Ok(1.0));
+        assert_eq!(first_nan.max(), Ok(2.0));
+    }
+
+    #[test]
+    fn test_empty_and_long() {
+        let empty: ConstArray<u8, 0> = ConstArray([]);
+        assert_eq!(empty.sum(), 0);
+        assert_eq!(empty.mean(), Err(StatsError::EmptyCollection));
+        assert_eq!(empty.min(), Err(StatsError::EmptyCollection));
+        assert_eq!(
+            ConstArray([0.0f32; 0]).max(),
+            Err(StatsError::EmptyCollection)
+        );
+        assert_eq!(
+            ConstArray([0i8; 200]).mean(),
+            Err(StatsError::CouldNotConvert {
+                from: DataType::Usize,
+                to: DataType::Item
+            })
+        );
+        assert_eq!(ConstArray([1u8; 200]).min(), Ok(1));
+    }
+}
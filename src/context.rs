@@ -0,0 +1,133 @@
+use core::fmt;
+
+use crate::StatsError;
+
+/// A [`StatsError`] with the name of the statistic that was being
+/// calculated, and the index of the item that caused it when that is
+/// known, to find the cause of an error from deep inside a pipeline.
+///
+/// Add the name to any result with [`WithContext::context`]. The
+/// `*_with_context` methods, such as
+/// [`Stats::strict_mean_with_context`](crate::Stats::strict_mean_with_context) and
+/// [`FrequencyStats::variance_with_context`](crate::FrequencyStats::variance_with_context),
+/// also fill in the index of the item where the error happens.
+///
+/// # Examples
+/// ```
+/// use stats_traits::{Stats, WithContext};
+/// let result = Vec::<f64>::new().mean().context("mean of the latencies");
+/// assert_eq!(
+///     result.unwrap_err().to_string(),
+///     "could not calculate the mean of the latencies: the collection is empty"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ErrorContext {
+    /// Name of the statistic that was being calculated
+    pub statistic: &'static str,
+    /// Index of the item that caused the error, if it is known
+    pub index: Option<usize>,
+    /// The error
+    pub error: StatsError,
+}
+
+impl ErrorContext {
+    /// Add the name of the `statistic` that was being calculated to `error`
+    pub const fn new(statistic: &'static str, error: StatsError) -> Self {
+        Self {
+            statistic,
+            index: None,
+            error,
+        }
+    }
+
+    /// Add the `index` of the item that caused the error
+    #[must_use]
+    pub const fn at(mut self, index: usize) -> Self {
+        self.index = Some(index);
+        self
+    }
+}
+
+impl fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "could not calculate the {}: {}",
+            self.statistic, self.error
+        )?;
+        if let Some(index) = self.index {
+            write!(f, " (caused by the item at index {index})")?;
+        }
+        Ok(())
+    }
+}
+
+impl core::error::Error for ErrorContext {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl From<ErrorContext> for StatsError {
+    fn from(context: ErrorContext) -> Self {
+        context.error
+    }
+}
+
+/// An extension trait for results of the crate, to add the name
+/// of the statistic that was being calculated to their errors
+pub trait WithContext<T> {
+    /// Add the name of the `statistic` that was being calculated to the error
+    ///
+    /// # Errors
+    /// Returns the error with the name of the statistic, if there is one
+    fn context(self, statistic: &'static str) -> Result<T, ErrorContext>;
+}
+
+impl<T> WithContext<T> for crate::Result<T> {
+    fn context(self, statistic: &'static str) -> Result<T, ErrorContext> {
+        self.map_err(|error| ErrorContext::new(statistic, error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::DataType;
+
+    extern crate std;
+    use std::string::ToString;
+
+    #[test]
+    fn test_display() {
+        let error = StatsError::CouldNotConvert {
+            from: DataType::Usize,
+            to: DataType::Item,
+        };
+        let context = ErrorContext::new("sum", error).at(3);
+        assert_eq!(
+            context.to_string(),
+            "could not calculate the sum: could not convert from usize to \
+             the type of the items (caused by the item at index 3)"
+        );
+        assert_eq!(StatsError::from(context), error);
+        assert!(core::error::Error::source(&context).is_some());
+    }
+
+    #[test]
+    fn test_context() {
+        let ok: crate::Result<u8> = Ok(1);
+        assert_eq!(ok.context("anything"), Ok(1));
+        let err: crate::Result<u8> = Err(StatsError::ZeroVariance);
+        assert_eq!(
+            err.context("skewness"),
+            Err(ErrorContext {
+                statistic: "skewness",
+                index: None,
+                error: StatsError::ZeroVariance
+            })
+        );
+    }
+}
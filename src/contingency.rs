@@ -0,0 +1,325 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::special::upper_incomplete_gamma;
+use crate::Result;
+use crate::StatsError;
+
+/// A table counting how often each combination of two categorical variables
+/// occurs, also called a cross tabulation.
+///
+/// The table is built from `(row, column)` observations, and the keys of the
+/// rows and columns are kept sorted. Only keys that have been observed at least
+/// once are in the table.
+///
+/// # Examples
+/// ```
+/// use stats_traits::ContingencyTable;
+/// let table = ContingencyTable::new()
+///     .with("smoker", "asthma")
+///     .with("smoker", "healthy")
+///     .with("non-smoker", "healthy")
+///     .with_many("non-smoker", "healthy", 2);
+/// assert_eq!(table.row_keys(), &["non-smoker", "smoker"]);
+/// assert_eq!(table.column_keys(), &["asthma", "healthy"]);
+/// assert_eq!(table.get(&"non-smoker", &"healthy"), 3);
+/// assert_eq!(table.get(&"non-smoker", &"asthma"), 0);
+/// assert_eq!(table.total(), 5);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContingencyTable<R, C> {
+    row_keys: Vec<R>,
+    column_keys: Vec<C>,
+    /// Counts of each column for each row
+    counts: Vec<Vec<usize>>,
+}
+
+impl<R, C> ContingencyTable<R, C> {
+    /// Create an empty table
+    pub const fn new() -> Self {
+        Self {
+            row_keys: Vec::new(),
+            column_keys: Vec::new(),
+            counts: Vec::new(),
+        }
+    }
+
+    /// Returns the keys of the rows, sorted
+    pub fn row_keys(&self) -> &[R] {
+        &self.row_keys
+    }
+
+    /// Returns the keys of the columns, sorted
+    pub fn column_keys(&self) -> &[C] {
+        &self.column_keys
+    }
+
+    /// Returns the number of rows and columns in the table
+    pub fn shape(&self) -> (usize, usize) {
+        (self.row_keys.len(), self.column_keys.len())
+    }
+
+    /// Returns the total number of observations
+    pub fn total(&self) -> usize {
+        self.counts.iter().flatten().sum()
+    }
+
+    /// Returns an iterator over the counts of each row, with a count
+    /// for every column, in the order of [`ContingencyTable::row_keys`]
+    pub fn rows(&self) -> impl Iterator<Item = &[usize]> {
+        self.counts.iter().map(Vec::as_slice)
+    }
+
+    /// Returns the total number of observations in each row
+    pub fn row_totals(&self) -> Vec<usize> {
+        self.rows().map(|row| row.iter().sum()).collect()
+    }
+
+    /// Returns the total number of observations in each column
+    pub fn column_totals(&self) -> Vec<usize> {
+        let mut totals = vec![0; self.column_keys.len()];
+        for row in self.rows() {
+            for (total, count) in totals.iter_mut().zip(row) {
+                *total += count;
+            }
+        }
+        totals
+    }
+
+    /// Run [Pearson's chi-squared test](<https://en.wikipedia.org/wiki/Pearson%27s_chi-squared_test#Testing_for_statistical_independence>)
+    /// of whether the row and column variables are independent, comparing each
+    /// count with the count expected from the row and column totals if they were.
+    ///
+    /// The test is an approximation that is only reliable when the expected
+    /// counts are not too small; a common rule of thumb is at least 5 in
+    /// every cell.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::ContingencyTable;
+    /// let mut table = ContingencyTable::new();
+    /// table.insert_many("A", "yes", 20);
+    /// table.insert_many("A", "no", 15);
+    /// table.insert_many("B", "yes", 30);
+    /// table.insert_many("B", "no", 35);
+    /// let test = table.chi_square_independence().unwrap();
+    /// assert_eq!(test.degrees_of_freedom, 1);
+    /// assert_eq!(test.expected[0], vec![17.5, 17.5]);
+    /// assert!(test.p_value > 0.05);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the table is empty, or if it has
+    /// fewer than two rows or fewer than two columns
+    pub fn chi_square_independence(&self) -> Result<ChiSquareTest> {
+        let (rows, columns) = self.shape();
+        if rows == 0 {
+            return Err(StatsError::EmptyCollection);
+        }
+        if rows < 2 || columns < 2 {
+            return Err(StatsError::InsufficientData {
+                required: 2,
+                actual: rows.min(columns),
+            });
+        }
+        let total = self.total() as f64;
+        let column_totals = self.column_totals();
+        let mut statistic = 0.0;
+        let mut expected = Vec::with_capacity(rows);
+        for (row, row_total) in self.rows().zip(self.row_totals()) {
+            let expected_row: Vec<f64> = column_totals
+                .iter()
+                .map(|&column_total| (row_total * column_total) as f64 / total)
+                .collect();
+            for (&count, &expected_count) in row.iter().zip(&expected_row) {
+                let difference = count as f64 - expected_count;
+                statistic += difference * difference / expected_count;
+            }
+            expected.push(expected_row);
+        }
+        let degrees_of_freedom = (rows - 1) * (columns - 1);
+        Ok(ChiSquareTest {
+            statistic,
+            degrees_of_freedom,
+            p_value: upper_incomplete_gamma(degrees_of_freedom as f64 / 2.0, statistic / 2.0),
+            expected,
+        })
+    }
+}
+
+impl<R, C> ContingencyTable<R, C>
+where
+    R: Ord,
+    C: Ord,
+{
+    /// Record one observation of `row` and `column` together
+    pub fn insert(&mut self, row: R, column: C) {
+        self.insert_many(row, column, 1);
+    }
+
+    /// Record `count` observations of `row` and `column` together
+    pub fn insert_many(&mut self, row: R, column: C, count: usize) {
+        if count == 0 {
+            return;
+        }
+        let j = match self.column_keys.binary_search(&column) {
+            Ok(j) => j,
+            Err(j) => {
+                self.column_keys.insert(j, column);
+                for counts in &mut self.counts {
+                    counts.insert(j, 0);
+                }
+                j
+            }
+        };
+        let i = match self.row_keys.binary_search(&row) {
+            Ok(i) => i,
+            Err(i) => {
+                self.row_keys.insert(i, row);
+                self.counts.insert(i, vec![0; self.column_keys.len()]);
+                i
+            }
+        };
+        self.counts[i][j] += count;
+    }
+
+    /// Record one observation of `row` and `column` together,
+    /// returning the table so that calls can be chained
+    #[must_use]
+    pub fn with(mut self, row: R, column: C) -> Self {
+        self.insert(row, column);
+        self
+    }
+
+    /// Record `count` observations of `row` and `column` together,
+    /// returning the table so that calls can be chained
+    #[must_use]
+    pub fn with_many(mut self, row: R, column: C, count: usize) -> Self {
+        self.insert_many(row, column, count);
+        self
+    }
+
+    /// Returns how many times `row` and `column` were observed together
+    pub fn get(&self, row: &R, column: &C) -> usize {
+        match (
+            self.row_keys.binary_search(row),
+            self.column_keys.binary_search(column),
+        ) {
+            (Ok(i), Ok(j)) => self.counts[i][j],
+            _ => 0,
+        }
+    }
+}
+
+impl<R, C> Default for ContingencyTable<R, C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R, C> FromIterator<(R, C)> for ContingencyTable<R, C>
+where
+    R: Ord,
+    C: Ord,
+{
+    fn from_iter<I: IntoIterator<Item = (R, C)>>(iter: I) -> Self {
+        let mut table = Self::new();
+        table.extend(iter);
+        table
+    }
+}
+
+impl<R, C> Extend<(R, C)> for ContingencyTable<R, C>
+where
+    R: Ord,
+    C: Ord,
+{
+    fn extend<I: IntoIterator<Item = (R, C)>>(&mut self, iter: I) {
+        for (row, column) in iter {
+            self.insert(row, column);
+        }
+    }
+}
+
+/// The result of a chi-squared test, as returned by
+/// [`ContingencyTable::chi_square_independence`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChiSquareTest {
+    /// The chi-squared statistic, which is larger the
+    /// further the counts are from the expected counts
+    pub statistic: f64,
+    /// Degrees of freedom of the chi-squared distribution the statistic is compared with
+    pub degrees_of_freedom: usize,
+    /// The p-value, which is the probability of a statistic at least
+    /// this large if the variables are independent
+    pub p_value: f64,
+    /// The count expected in each cell if the variables were independent,
+    /// in the same layout as [`ContingencyTable::rows`]
+    pub expected: Vec<Vec<f64>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_insert_keeps_keys_sorted() {
+        let table: ContingencyTable<i32, char> = [(2, 'b'), (1, 'c'), (2, 'a'), (1, 'b'), (2, 'b')]
+            .into_iter()
+            .collect();
+        assert_eq!(table.row_keys(), &[1, 2]);
+        assert_eq!(table.column_keys(), &['a', 'b', 'c']);
+        let rows: Vec<&[usize]> = table.rows().collect();
+        assert_eq!(rows, vec![&[0, 1, 1][..], &[1, 2, 0][..]]);
+        assert_eq!(table.row_totals(), vec![2, 3]);
+        assert_eq!(table.column_totals(), vec![1, 3, 1]);
+    }
+
+    #[test]
+    fn test_chi_square_independence() {
+        let table = ContingencyTable::new()
+            .with_many(0, 0, 12)
+            .with_many(0, 1, 5)
+            .with_many(0, 2, 9)
+            .with_many(1, 0, 7)
+            .with_many(1, 1, 14)
+            .with_many(1, 2, 3);
+        let test = table.chi_square_independence().unwrap();
+        assert_relative_eq!(test.statistic, 8.512567476383268, epsilon = 1e-12);
+        assert_eq!(test.degrees_of_freedom, 2);
+        // with 2 degrees of freedom the p-value is exp(-statistic / 2)
+        assert_relative_eq!(test.p_value, 0.01417488222291884, epsilon = 1e-14);
+        assert_relative_eq!(test.expected[1][2], 5.76, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_chi_square_two_by_two() {
+        let table = ContingencyTable::new()
+            .with_many('a', true, 20)
+            .with_many('a', false, 15)
+            .with_many('b', true, 30)
+            .with_many('b', false, 35);
+        let test = table.chi_square_independence().unwrap();
+        assert_relative_eq!(test.statistic, 1.098901098901099, epsilon = 1e-12);
+        assert_relative_eq!(test.p_value, 0.2945073936801102, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_chi_square_too_small() {
+        let empty: ContingencyTable<u8, u8> = ContingencyTable::new();
+        assert_eq!(
+            empty.chi_square_independence(),
+            Err(StatsError::EmptyCollection)
+        );
+        let one_column = ContingencyTable::new().with(1, 1).with(2, 1);
+        assert_eq!(
+            one_column.chi_square_independence(),
+            Err(StatsError::InsufficientData {
+                required: 2,
+                actual: 1
+            })
+        );
+    }
+}
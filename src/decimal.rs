@@ -0,0 +1,55 @@
+use rust_decimal::{Decimal, MathematicalOps};
+
+use crate::SquareRoot;
+
+/// The square root of a [`Decimal`] is calculated with the Babylonian method
+/// of [`MathematicalOps::sqrt`], to the full precision of the type
+///
+/// # Examples
+/// ```
+/// use rust_decimal::Decimal;
+/// use stats_traits::Stats;
+/// let prices: Vec<Decimal> = ["10.10", "10.20", "10.60"]
+///     .into_iter()
+///     .map(|x| x.parse().unwrap())
+///     .collect();
+/// assert_eq!(prices.mean(), Ok("10.30".parse().unwrap()));
+/// assert_eq!(prices.variance(), Ok("0.0466666666666666666666666667".parse().unwrap()));
+/// let std_dev = prices.root_std_dev().unwrap();
+/// assert_eq!(std_dev.round_dp(6), "0.216025".parse().unwrap());
+/// ```
+impl SquareRoot for Decimal {
+    fn square_root(&self) -> Option<Self> {
+        MathematicalOps::sqrt(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Stats;
+    use crate::StatsError;
+
+    use rust_decimal::Decimal;
+
+    fn decimal(x: &str) -> Decimal {
+        x.parse().unwrap()
+    }
+
+    #[test]
+    fn test_exact() {
+        // 0.1 and 0.2 aren't exact in binary floats, but are in decimals
+        let values = [decimal("0.1"), decimal("0.2"), decimal("0.3")];
+        assert_eq!(values.sum(), decimal("0.6"));
+        assert_eq!(values.mean(), Ok(decimal("0.2")));
+        assert_eq!(
+            values.root_std_dev().unwrap().round_dp(20),
+            decimal("0.08164965809277260327")
+        );
+        assert_eq!(Stats::max(&values), Ok(decimal("0.3")));
+        assert_eq!([Decimal::ONE; 4].root_std_dev(), Ok(Decimal::ZERO));
+        assert_eq!(
+            <[Decimal; 0]>::default().root_std_dev(),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+}
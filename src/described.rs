@@ -0,0 +1,205 @@
+use core::cell::OnceCell;
+use core::ops::{Deref, DerefMut};
+
+use num_traits::ToPrimitive;
+
+use crate::helpers::item_to_f64;
+use crate::OnlineStats;
+use crate::Result;
+use crate::Stats;
+use crate::Summary;
+
+/// Wraps a collection and remembers its statistics, so they are calculated
+/// in a single pass the first time one of them is needed and never again
+/// until the collection changes.
+///
+/// The collection can be read through [`Deref`], and changed through
+/// [`DerefMut`] or [`Described::get_mut`], which forget the statistics so
+/// they are calculated again next time. The statistics are calculated with
+/// [`OnlineStats`], so they are [`f64`]s whatever the type of the items.
+///
+/// # Examples
+/// ```
+/// use stats_traits::Described;
+/// let mut data = Described::new(vec![1.0, 2.0, 3.0]);
+/// assert_eq!(data.mean(), Ok(2.0));
+/// // calculated already, so this doesn't go through the items again
+/// assert_eq!(data.max(), Ok(3.0));
+/// data.push(6.0);
+/// assert_eq!(data.mean(), Ok(3.0));
+/// assert_eq!(data.len(), 4);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Described<T> {
+    inner: T,
+    /// The statistics of `inner` and the sum of its items, once calculated
+    cache: OnceCell<Result<(OnlineStats, f64)>>,
+}
+
+impl<T> Described<T> {
+    /// Wrap `inner`, without calculating anything yet
+    pub const fn new(inner: T) -> Self {
+        Self {
+            inner,
+            cache: OnceCell::new(),
+        }
+    }
+
+    /// Returns a reference to the collection
+    pub fn get(&self) -> &T {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the collection,
+    /// forgetting the statistics calculated so far
+    pub fn get_mut(&mut self) -> &mut T {
+        self.cache.take();
+        &mut self.inner
+    }
+
+    /// Unwrap the collection
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T> Described<T>
+where
+    T: Stats,
+    T::Item: ToPrimitive,
+{
+    /// Returns the statistics of the collection and the sum of
+    /// its items, calculating them if this is the first time
+    fn described(&self) -> Result<&(OnlineStats, f64)> {
+        self.cache
+            .get_or_init(|| {
+                let (mut stats, mut sum) = (OnlineStats::new(), 0.0);
+                for x in self.inner.items() {
+                    let x = item_to_f64(x)?;
+                    stats.push(x);
+                    sum += x;
+                }
+                Ok((stats, sum))
+            })
+            .as_ref()
+            .map_err(|&error| error)
+    }
+
+    /// Returns the number of items
+    ///
+    /// # Errors
+    /// Returns an error if an item can't be converted to [`f64`]
+    pub fn count(&self) -> Result<usize> {
+        Ok(self.described()?.0.count())
+    }
+
+    /// Returns the sum of the items
+    ///
+    /// # Errors
+    /// Returns an error if an item can't be converted to [`f64`]
+    pub fn sum(&self) -> Result<f64> {
+        Ok(self.described()?.1)
+    }
+
+    /// Returns the mean of the items
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty, or if an item can't be converted to [`f64`]
+    pub fn mean(&self) -> Result<f64> {
+        self.described()?.0.mean()
+    }
+
+    /// Returns the (population) variance of the items.
+    /// See [`Stats::variance`].
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty, or if an item can't be converted to [`f64`]
+    pub fn variance(&self) -> Result<f64> {
+        self.described()?.0.variance()
+    }
+
+    /// Returns the standard deviation of the items, which
+    /// is the square root of [`Described::variance`]
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty, or if an item can't be converted to [`f64`]
+    pub fn std_dev(&self) -> Result<f64> {
+        self.described()?.0.std_dev()
+    }
+
+    /// Returns the smallest item
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty, or if an item can't be converted to [`f64`]
+    pub fn min(&self) -> Result<f64> {
+        self.described()?.0.min()
+    }
+
+    /// Returns the largest item
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty, or if an item can't be converted to [`f64`]
+    pub fn max(&self) -> Result<f64> {
+        self.described()?.0.max()
+    }
+
+    /// Returns a [`Summary`] of the items
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty, or if an item can't be converted to [`f64`]
+    pub fn summary(&self) -> Result<Summary> {
+        self.described()?.0.summary()
+    }
+}
+
+impl<T> From<T> for Described<T> {
+    fn from(inner: T) -> Self {
+        Self::new(inner)
+    }
+}
+
+impl<T> Deref for Described<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T> DerefMut for Described<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.get_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StatsError;
+
+    extern crate std;
+    use std::vec;
+
+    #[test]
+    fn test_calculates_once() {
+        let data = Described::new([4, 8, 6]);
+        assert!(data.cache.get().is_none());
+        assert_eq!(data.sum(), Ok(18.0));
+        assert!(data.cache.get().is_some());
+        assert_eq!(data.count(), Ok(3));
+        assert_eq!(data.summary().unwrap().range(), 4.0);
+    }
+
+    #[test]
+    fn test_mutation_forgets() {
+        let mut data = Described::from(vec![1, 2, 3]);
+        assert_eq!(data.variance(), Ok(2.0 / 3.0));
+        data.get_mut().clear();
+        assert!(data.cache.get().is_none());
+        assert_eq!(data.count(), Ok(0));
+        assert_eq!(data.mean(), Err(StatsError::EmptyCollection));
+        data.extend([5, 5]);
+        assert_eq!(data.std_dev(), Ok(0.0));
+        assert_eq!(data.into_inner(), vec![5, 5]);
+    }
+}
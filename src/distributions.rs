@@ -0,0 +1,1033 @@
+//! Probability distributions, for calculating probabilities and
+//! quantiles and for fitting them to collections.
+
+use core::f64::consts::PI;
+
+use crate::helpers::Float;
+use crate::poisson_rate_interval;
+use crate::special::{
+    gamma_quantile, ln_gamma, normal_cdf, normal_quantile, upper_incomplete_gamma,
+};
+use crate::Result;
+use crate::StatsError;
+#[cfg(feature = "alloc")]
+use crate::TestResult;
+
+/// The [Euler–Mascheroni constant](<https://en.wikipedia.org/wiki/Euler%27s_constant>)
+const EULER_GAMMA: f64 = 0.577_215_664_901_532_9;
+
+/// The [normal distribution](<https://en.wikipedia.org/wiki/Normal_distribution>)
+/// with a given mean and standard deviation
+///
+/// # Examples
+/// ```
+/// use stats_traits::distributions::Normal;
+/// let heights = Normal::new(170.0, 10.0);
+/// // the proportion of people shorter than 180
+/// assert!((heights.cdf(180.0) - 0.8413).abs() < 1e-4);
+/// // the height that 97.5% of people are shorter than
+/// assert!((heights.inverse_cdf(0.975) - 189.6).abs() < 0.1);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Normal {
+    mean: f64,
+    std_dev: f64,
+}
+
+impl Normal {
+    /// Create a normal distribution with mean `mean` and standard deviation `std_dev`
+    ///
+    /// # Panics
+    /// Panics if `mean` is not finite, or if `std_dev` is not finite and greater than 0
+    pub fn new(mean: f64, std_dev: f64) -> Self {
+        assert!(mean.is_finite(), "mean must be finite");
+        assert!(
+            std_dev.is_finite() && std_dev > 0.0,
+            "standard deviation must be finite and greater than 0"
+        );
+        Self { mean, std_dev }
+    }
+
+    /// Create the standard normal distribution, with mean 0 and standard deviation 1
+    pub const fn standard() -> Self {
+        Self {
+            mean: 0.0,
+            std_dev: 1.0,
+        }
+    }
+
+    /// Returns the mean of the distribution
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Returns the standard deviation of the distribution
+    pub fn std_dev(&self) -> f64 {
+        self.std_dev
+    }
+
+    /// Returns the variance of the distribution
+    pub fn variance(&self) -> f64 {
+        self.std_dev * self.std_dev
+    }
+
+    /// Returns the probability density function of the distribution at `x`
+    pub fn pdf(&self, x: f64) -> f64 {
+        let z = (x - self.mean) / self.std_dev;
+        Float::exp(-z * z / 2.0) / (self.std_dev * Float::sqrt(2.0 * PI))
+    }
+
+    /// Returns the cumulative distribution function of the distribution at `x`,
+    /// which is the probability that a value from it is at most `x`
+    pub fn cdf(&self, x: f64) -> f64 {
+        normal_cdf((x - self.mean) / self.std_dev)
+    }
+
+    /// Returns the value that a value from the distribution is at most with
+    /// probability `p`, which is the inverse of [`Normal::cdf`]. It uses
+    /// Acklam's approximation refined by a step of Halley's method, so it is
+    /// accurate to nearly full precision. It is -∞ when `p` is 0 and ∞ when
+    /// `p` is 1.
+    ///
+    /// # Panics
+    /// Panics if `p` is not between 0 and 1
+    pub fn inverse_cdf(&self, p: f64) -> f64 {
+        assert!((0.0..=1.0).contains(&p), "p must be between 0 and 1");
+        self.mean + self.std_dev * normal_quantile(p)
+    }
+}
+
+impl Default for Normal {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+/// A [`Normal`] distribution fitted to a collection, as
+/// returned by [`Stats::fit_normal`](crate::Stats::fit_normal)
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NormalFit {
+    /// The fitted distribution, whose mean and standard deviation are the
+    /// maximum likelihood estimates, which are the mean and the population
+    /// standard deviation of the items
+    pub distribution: Normal,
+    /// The natural logarithm of the likelihood of the items under the fitted distribution
+    pub log_likelihood: f64,
+    /// The Kolmogorov–Smirnov statistic of the items against the fitted
+    /// distribution, which is the largest distance between their distribution
+    /// functions, so smaller means a better fit. The parameters were estimated
+    /// from the items, so it must be compared with the critical values of the
+    /// [Lilliefors test](<https://en.wikipedia.org/wiki/Lilliefors_test>),
+    /// not those of the Kolmogorov–Smirnov test.
+    pub ks_statistic: f64,
+}
+
+#[cfg(feature = "alloc")]
+impl NormalFit {
+    /// Fit a normal distribution to the non-empty `sorted` values
+    pub(crate) fn new(sorted: &[f64]) -> Result<Self> {
+        // the values are sorted, so only the ends can be infinite
+        if sorted[0].is_infinite() || sorted[sorted.len() - 1].is_infinite() {
+            return Err(StatsError::InfinityEncountered);
+        }
+        let n = sorted.len() as f64;
+        let mean = sorted.iter().sum::<f64>() / n;
+        let variance = sorted.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / n;
+        if variance == 0.0 {
+            return Err(StatsError::ZeroVariance);
+        }
+        if !variance.is_finite() {
+            return Err(StatsError::Overflow);
+        }
+        let distribution = Normal::new(mean, Float::sqrt(variance));
+        Ok(Self {
+            distribution,
+            log_likelihood: -n / 2.0 * (Float::ln(2.0 * PI * variance) + 1.0),
+            ks_statistic: TestResult::kolmogorov_smirnov(sorted, |x| distribution.cdf(x)).statistic,
+        })
+    }
+}
+
+/// The [exponential distribution](<https://en.wikipedia.org/wiki/Exponential_distribution>)
+/// with a given rate, which is the distribution of the time between events
+/// that happen independently at a constant average rate
+///
+/// # Examples
+/// ```
+/// use stats_traits::distributions::Exponential;
+/// // requests arrive 2 per second on average
+/// let gaps = Exponential::new(2.0);
+/// assert_eq!(gaps.mean(), 0.5);
+/// // the probability that the next request arrives within a second
+/// assert!((gaps.cdf(1.0) - 0.8647).abs() < 1e-4);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Exponential {
+    rate: f64,
+}
+
+impl Exponential {
+    /// Create an exponential distribution with rate `rate`
+    ///
+    /// # Panics
+    /// Panics if `rate` is not finite and greater than 0
+    pub fn new(rate: f64) -> Self {
+        assert!(
+            rate.is_finite() && rate > 0.0,
+            "rate must be finite and greater than 0"
+        );
+        Self { rate }
+    }
+
+    /// Returns the rate of the distribution
+    pub fn rate(&self) -> f64 {
+        self.rate
+    }
+
+    /// Returns the mean of the distribution, which is one over the rate
+    pub fn mean(&self) -> f64 {
+        1.0 / self.rate
+    }
+
+    /// Returns the variance of the distribution
+    pub fn variance(&self) -> f64 {
+        1.0 / (self.rate * self.rate)
+    }
+
+    /// Returns the probability density function of the distribution at `x`
+    pub fn pdf(&self, x: f64) -> f64 {
+        if x < 0.0 {
+            0.0
+        } else {
+            self.rate * Float::exp(-self.rate * x)
+        }
+    }
+
+    /// Returns the cumulative distribution function of the distribution at `x`,
+    /// which is the probability that a value from it is at most `x`
+    pub fn cdf(&self, x: f64) -> f64 {
+        if x < 0.0 {
+            0.0
+        } else {
+            -Float::exp_m1(-self.rate * x)
+        }
+    }
+
+    /// Returns the value that a value from the distribution is at most with
+    /// probability `p`, which is the inverse of [`Exponential::cdf`]. It is ∞
+    /// when `p` is 1.
+    ///
+    /// # Panics
+    /// Panics if `p` is not between 0 and 1
+    pub fn inverse_cdf(&self, p: f64) -> f64 {
+        assert!((0.0..=1.0).contains(&p), "p must be between 0 and 1");
+        -Float::ln_1p(-p) / self.rate
+    }
+}
+
+/// The [Gumbel distribution](<https://en.wikipedia.org/wiki/Gumbel_distribution>)
+/// with a given location and scale, which is the distribution of the largest
+/// of many independent values, such as the highest river level in a year
+///
+/// # Examples
+/// ```
+/// use stats_traits::distributions::Gumbel;
+/// // the highest river level in each year, in metres
+/// let levels = Gumbel::new(3.0, 0.5);
+/// // the probability that next year's highest level is at most 4 metres
+/// assert!((levels.cdf(4.0) - 0.8734).abs() < 1e-4);
+/// // the level that is exceeded once in 100 years on average
+/// assert!((levels.return_level(100.0) - 5.300).abs() < 1e-3);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gumbel {
+    location: f64,
+    scale: f64,
+}
+
+impl Gumbel {
+    /// Create a Gumbel distribution with location `location` and scale `scale`
+    ///
+    /// # Panics
+    /// Panics if `location` is not finite, or if `scale` is not finite and greater than 0
+    pub fn new(location: f64, scale: f64) -> Self {
+        assert!(location.is_finite(), "location must be finite");
+        assert!(
+            scale.is_finite() && scale > 0.0,
+            "scale must be finite and greater than 0"
+        );
+        Self { location, scale }
+    }
+
+    /// Returns the location of the distribution, which is its mode
+    pub fn location(&self) -> f64 {
+        self.location
+    }
+
+    /// Returns the scale of the distribution
+    pub fn scale(&self) -> f64 {
+        self.scale
+    }
+
+    /// Returns the mean of the distribution, which is the location plus
+    /// the scale times the Euler–Mascheroni constant
+    pub fn mean(&self) -> f64 {
+        self.location + EULER_GAMMA * self.scale
+    }
+
+    /// Returns the variance of the distribution
+    pub fn variance(&self) -> f64 {
+        PI * PI * self.scale * self.scale / 6.0
+    }
+
+    /// Returns the probability density function of the distribution at `x`
+    pub fn pdf(&self, x: f64) -> f64 {
+        let z = (x - self.location) / self.scale;
+        Float::exp(-z - Float::exp(-z)) / self.scale
+    }
+
+    /// Returns the cumulative distribution function of the distribution at `x`,
+    /// which is the probability that a value from it is at most `x`
+    pub fn cdf(&self, x: f64) -> f64 {
+        Float::exp(-Float::exp(-(x - self.location) / self.scale))
+    }
+
+    /// Returns the value that a value from the distribution is at most with
+    /// probability `p`, which is the inverse of [`Gumbel::cdf`]. It is -∞ when
+    /// `p` is 0 and ∞ when `p` is 1.
+    ///
+    /// # Panics
+    /// Panics if `p` is not between 0 and 1
+    pub fn inverse_cdf(&self, p: f64) -> f64 {
+        assert!((0.0..=1.0).contains(&p), "p must be between 0 and 1");
+        self.location - self.scale * Float::ln(-Float::ln(p))
+    }
+
+    /// Returns the [return level](<https://en.wikipedia.org/wiki/Return_period>)
+    /// for a return period of `period` blocks, which is the value exceeded
+    /// once every `period` blocks on average, such as the 1-in-100-year flood
+    /// level when the distribution is fitted to yearly maxima. It is the value
+    /// that a value from the distribution is at most with probability
+    /// `1 - 1 / period`.
+    ///
+    /// # Panics
+    /// Panics if `period` is not greater than 1
+    pub fn return_level(&self, period: f64) -> f64 {
+        assert!(period > 1.0, "period must be greater than 1");
+        self.location - self.scale * Float::ln(-Float::ln_1p(-1.0 / period))
+    }
+}
+
+/// A [`Gumbel`] distribution fitted to a collection of maxima, such as
+/// [`Stats::block_maxima`](crate::Stats::block_maxima), as returned by
+/// [`Stats::fit_gumbel`](crate::Stats::fit_gumbel)
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GumbelFit {
+    /// The fitted distribution, whose location and scale are the maximum
+    /// likelihood estimates
+    pub distribution: Gumbel,
+    /// The natural logarithm of the likelihood of the items under the fitted distribution
+    pub log_likelihood: f64,
+}
+
+#[cfg(feature = "alloc")]
+impl GumbelFit {
+    /// Fit a Gumbel distribution to the non-empty `values` by maximum likelihood
+    pub(crate) fn new(values: &[f64]) -> Result<Self> {
+        if values.iter().any(|x| x.is_infinite()) {
+            return Err(StatsError::InfinityEncountered);
+        }
+        let n = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / n;
+        let variance = values.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / n;
+        if variance == 0.0 {
+            return Err(StatsError::ZeroVariance);
+        }
+        if !variance.is_finite() {
+            return Err(StatsError::Overflow);
+        }
+        // the values are shifted by the smallest so the weights can't overflow
+        let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+        // the weighted mean of the values and the sum of the weights, where the
+        // weight of each value is exp(-(x - min) / scale)
+        let weighted = |scale: f64| {
+            let (mut sum, mut weighted_sum, mut weighted_square_sum) = (0.0, 0.0, 0.0);
+            for &x in values {
+                let w = Float::exp(-(x - min) / scale);
+                sum += w;
+                weighted_sum += w * (x - min);
+                weighted_square_sum += w * (x - min) * (x - min);
+            }
+            let weighted_mean = weighted_sum / sum;
+            (
+                weighted_mean,
+                weighted_square_sum / sum - weighted_mean * weighted_mean,
+                sum,
+            )
+        };
+        // Newton's method on the equation the scale satisfies, starting from
+        // the estimate by the method of moments
+        let mut scale = Float::sqrt(variance) * Float::sqrt(6.0f64) / PI;
+        for _ in 0..100 {
+            let (weighted_mean, weighted_variance, _) = weighted(scale);
+            let g = scale - (mean - min) + weighted_mean;
+            let step = g / (1.0 + weighted_variance / (scale * scale));
+            let next = if scale - step > 0.0 {
+                scale - step
+            } else {
+                scale / 2.0
+            };
+            let done = (next - scale).abs() <= 1e-12 * scale;
+            scale = next;
+            if done {
+                break;
+            }
+        }
+        let (_, _, sum) = weighted(scale);
+        let location = min - scale * Float::ln(sum / n);
+        if !(location.is_finite() && scale.is_finite() && scale > 0.0) {
+            return Err(StatsError::Overflow);
+        }
+        let log_likelihood = values
+            .iter()
+            .map(|x| {
+                let z = (x - location) / scale;
+                -Float::ln(scale) - z - Float::exp(-z)
+            })
+            .sum();
+        Ok(Self {
+            distribution: Gumbel::new(location, scale),
+            log_likelihood,
+        })
+    }
+}
+
+/// The [generalized Pareto distribution](<https://en.wikipedia.org/wiki/Generalized_Pareto_distribution>)
+/// with a given scale and shape, and a location of 0, which is the
+/// distribution of how far values above a high threshold exceed it. A
+/// positive shape gives a heavy tail, a shape of 0 is the exponential
+/// distribution, and a negative shape gives a tail with an upper bound.
+///
+/// # Examples
+/// ```
+/// use stats_traits::distributions::GeneralizedPareto;
+/// let excesses = GeneralizedPareto::new(2.0, 0.5);
+/// assert_eq!(excesses.mean(), 4.0);
+/// // the probability that a value over the threshold exceeds it by at most 12
+/// assert!((excesses.cdf(12.0) - 0.9375).abs() < 1e-12);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeneralizedPareto {
+    scale: f64,
+    shape: f64,
+}
+
+impl GeneralizedPareto {
+    /// Create a generalized Pareto distribution with scale `scale` and shape `shape`
+    ///
+    /// # Panics
+    /// Panics if `scale` is not finite and greater than 0, or if `shape` is not finite
+    pub fn new(scale: f64, shape: f64) -> Self {
+        assert!(
+            scale.is_finite() && scale > 0.0,
+            "scale must be finite and greater than 0"
+        );
+        assert!(shape.is_finite(), "shape must be finite");
+        Self { scale, shape }
+    }
+
+    /// Returns the scale of the distribution
+    pub fn scale(&self) -> f64 {
+        self.scale
+    }
+
+    /// Returns the shape of the distribution
+    pub fn shape(&self) -> f64 {
+        self.shape
+    }
+
+    /// Returns the mean of the distribution, which is ∞ if the shape is at least 1
+    pub fn mean(&self) -> f64 {
+        if self.shape < 1.0 {
+            self.scale / (1.0 - self.shape)
+        } else {
+            f64::INFINITY
+        }
+    }
+
+    /// Returns the variance of the distribution, which is ∞ if the shape is at least 0.5
+    pub fn variance(&self) -> f64 {
+        if self.shape < 0.5 {
+            let one_minus = 1.0 - self.shape;
+            self.scale * self.scale / (one_minus * one_minus * (1.0 - 2.0 * self.shape))
+        } else {
+            f64::INFINITY
+        }
+    }
+
+    /// Returns the probability density function of the distribution at `x`
+    pub fn pdf(&self, x: f64) -> f64 {
+        let z = x / self.scale;
+        if x < 0.0 || 1.0 + self.shape * z < 0.0 {
+            0.0
+        } else if self.shape == 0.0 {
+            Float::exp(-z) / self.scale
+        } else {
+            Float::exp(-(1.0 / self.shape + 1.0) * Float::ln_1p(self.shape * z)) / self.scale
+        }
+    }
+
+    /// Returns the cumulative distribution function of the distribution at `x`,
+    /// which is the probability that a value from it is at most `x`
+    pub fn cdf(&self, x: f64) -> f64 {
+        let z = x / self.scale;
+        if x < 0.0 {
+            0.0
+        } else if 1.0 + self.shape * z <= 0.0 {
+            1.0
+        } else if self.shape == 0.0 {
+            -Float::exp_m1(-z)
+        } else {
+            -Float::exp_m1(-Float::ln_1p(self.shape * z) / self.shape)
+        }
+    }
+
+    /// Returns the value that a value from the distribution is at most with
+    /// probability `p`, which is the inverse of [`GeneralizedPareto::cdf`].
+    /// It is ∞ when `p` is 1 and the shape isn't negative.
+    ///
+    /// # Panics
+    /// Panics if `p` is not between 0 and 1
+    pub fn inverse_cdf(&self, p: f64) -> f64 {
+        assert!((0.0..=1.0).contains(&p), "p must be between 0 and 1");
+        let ln_survival = Float::ln_1p(-p);
+        if self.shape == 0.0 {
+            -self.scale * ln_survival
+        } else {
+            self.scale * Float::exp_m1(-self.shape * ln_survival) / self.shape
+        }
+    }
+}
+
+/// A [`GeneralizedPareto`] distribution fitted to the excesses of the items
+/// over a threshold, as returned by
+/// [`Stats::fit_generalized_pareto`](crate::Stats::fit_generalized_pareto)
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeneralizedParetoFit {
+    /// The fitted distribution of the excesses, estimated by the
+    /// probability-weighted moments of Hosking and Wallis, which are
+    /// reliable for shapes below 0.5
+    pub distribution: GeneralizedPareto,
+    /// The threshold the items exceeded
+    pub threshold: f64,
+    /// The fraction of the items that exceeded the threshold
+    pub exceedance_rate: f64,
+}
+
+#[cfg(feature = "alloc")]
+impl GeneralizedParetoFit {
+    /// Fit a generalized Pareto distribution to the `sorted` excesses
+    /// over `threshold` of `count` items
+    pub(crate) fn new(sorted: &[f64], threshold: f64, count: usize) -> Result<Self> {
+        if sorted.len() < 2 {
+            return Err(StatsError::InsufficientData {
+                required: 2,
+                actual: sorted.len(),
+            });
+        }
+        // the excesses are sorted, so only the largest can be infinite
+        if sorted[sorted.len() - 1].is_infinite() {
+            return Err(StatsError::InfinityEncountered);
+        }
+        if sorted[0] == sorted[sorted.len() - 1] {
+            return Err(StatsError::ZeroVariance);
+        }
+        let n = sorted.len() as f64;
+        // the mean, and the mean weighted by the survival function at the
+        // plotting positions of Landwehr et al.
+        let a0 = sorted.iter().sum::<f64>() / n;
+        let a1 = sorted
+            .iter()
+            .enumerate()
+            .map(|(i, y)| (1.0 - (i as f64 + 0.65) / n) * y)
+            .sum::<f64>()
+            / n;
+        let denominator = a0 - 2.0 * a1;
+        let (scale, shape) = (2.0 * a0 * a1 / denominator, 2.0 - a0 / denominator);
+        if !(scale.is_finite() && scale > 0.0 && shape.is_finite()) {
+            return Err(StatsError::Overflow);
+        }
+        Ok(Self {
+            distribution: GeneralizedPareto::new(scale, shape),
+            threshold,
+            exceedance_rate: n / count as f64,
+        })
+    }
+
+    /// Returns the [return level](<https://en.wikipedia.org/wiki/Return_period>)
+    /// for a return period of `period` items, which is the value exceeded once
+    /// every `period` items on average, such as the daily load exceeded once
+    /// in 10 years for a `period` of 3650 daily items. It is only above the
+    /// threshold if the threshold is exceeded more often than once every
+    /// `period` items.
+    ///
+    /// # Panics
+    /// Panics if `period` is not greater than 1
+    pub fn return_level(&self, period: f64) -> f64 {
+        assert!(period > 1.0, "period must be greater than 1");
+        let (scale, shape) = (self.distribution.scale, self.distribution.shape);
+        let ln_exceedances = Float::ln(period * self.exceedance_rate);
+        let excess = if shape == 0.0 {
+            scale * ln_exceedances
+        } else {
+            scale * Float::exp_m1(shape * ln_exceedances) / shape
+        };
+        self.threshold + excess
+    }
+}
+
+/// The [Poisson distribution](<https://en.wikipedia.org/wiki/Poisson_distribution>)
+/// with a given rate, which is the distribution of the number of events in
+/// an interval when they happen independently at a constant average rate
+///
+/// # Examples
+/// ```
+/// use stats_traits::distributions::Poisson;
+/// // a shop sells 2 umbrellas a day on average
+/// let sales = Poisson::new(2.0);
+/// // the probability of selling exactly 3 tomorrow
+/// assert!((sales.pmf(3) - 0.1804).abs() < 1e-4);
+/// // the probability of selling at most 3
+/// assert!((sales.cdf(3) - 0.8571).abs() < 1e-4);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Poisson {
+    rate: f64,
+}
+
+impl Poisson {
+    /// Create a Poisson distribution with rate `rate`, which is also its mean.
+    /// A rate of 0 is allowed, and means there are never any events.
+    ///
+    /// # Panics
+    /// Panics if `rate` is not finite and at least 0
+    pub fn new(rate: f64) -> Self {
+        assert!(
+            rate.is_finite() && rate >= 0.0,
+            "rate must be finite and at least 0"
+        );
+        Self { rate }
+    }
+
+    /// Returns the rate of the distribution
+    pub fn rate(&self) -> f64 {
+        self.rate
+    }
+
+    /// Returns the mean of the distribution, which is the rate
+    pub fn mean(&self) -> f64 {
+        self.rate
+    }
+
+    /// Returns the variance of the distribution, which is also the rate
+    pub fn variance(&self) -> f64 {
+        self.rate
+    }
+
+    /// Returns the probability mass function of the distribution at `k`,
+    /// which is the probability of exactly `k` events
+    pub fn pmf(&self, k: usize) -> f64 {
+        Float::exp(self.ln_pmf(k))
+    }
+
+    /// Returns the cumulative distribution function of the distribution at `k`,
+    /// which is the probability of at most `k` events
+    pub fn cdf(&self, k: usize) -> f64 {
+        if self.rate == 0.0 {
+            1.0
+        } else {
+            upper_incomplete_gamma(k as f64 + 1.0, self.rate)
+        }
+    }
+
+    /// Returns the natural logarithm of the probability of exactly `k` events
+    fn ln_pmf(&self, k: usize) -> f64 {
+        if k == 0 {
+            -self.rate
+        } else {
+            let k = k as f64;
+            k * Float::ln(self.rate) - self.rate - ln_gamma(k + 1.0)
+        }
+    }
+}
+
+/// An [`Exponential`] distribution fitted to a collection, as returned by
+/// [`Stats::fit_exponential`](crate::Stats::fit_exponential)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExponentialFit {
+    /// The fitted distribution, whose rate is the maximum likelihood
+    /// estimate, which is one over the mean of the items
+    pub distribution: Exponential,
+    /// The lower and upper bounds of the exact confidence interval for the rate
+    pub rate_interval: (f64, f64),
+    /// The natural logarithm of the likelihood of the items under the fitted distribution
+    pub log_likelihood: f64,
+}
+
+impl ExponentialFit {
+    /// Fit an exponential distribution to `values`, skipping `NaN`s,
+    /// with a confidence interval at the `confidence` level
+    pub(crate) fn new<I>(values: I, confidence: f64) -> Result<Self>
+    where
+        I: IntoIterator<Item = Result<f64>>,
+    {
+        assert!(
+            confidence > 0.0 && confidence < 1.0,
+            "confidence must be between 0 and 1"
+        );
+        let (mut total, mut count) = (0.0, 0usize);
+        for x in values {
+            let x = x?;
+            if x.is_nan() {
+                continue;
+            }
+            if x < 0.0 {
+                return Err(StatsError::OutOfDomain);
+            }
+            if x.is_infinite() {
+                return Err(StatsError::InfinityEncountered);
+            }
+            total += x;
+            count += 1;
+        }
+        if count == 0 {
+            return Err(StatsError::EmptyCollection);
+        }
+        if total == 0.0 {
+            return Err(StatsError::ZeroVariance);
+        }
+        let n = count as f64;
+        let rate = n / total;
+        // the total or the rate is too large if the items are too large or small
+        if total.is_infinite() || rate.is_infinite() {
+            return Err(StatsError::Overflow);
+        }
+        // twice the rate times the total has a chi-squared distribution with
+        // 2n degrees of freedom, and half of that is a gamma distribution with shape n
+        let alpha = 1.0 - confidence;
+        Ok(Self {
+            distribution: Exponential::new(rate),
+            rate_interval: (
+                gamma_quantile(alpha / 2.0, n) / total,
+                gamma_quantile(1.0 - alpha / 2.0, n) / total,
+            ),
+            log_likelihood: n * (Float::ln(rate) - 1.0),
+        })
+    }
+}
+
+/// A [`Poisson`] distribution fitted to a collection of counts, as
+/// returned by [`Stats::fit_poisson`](crate::Stats::fit_poisson)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PoissonFit {
+    /// The fitted distribution, whose rate is the maximum likelihood
+    /// estimate, which is the mean of the counts
+    pub distribution: Poisson,
+    /// The lower and upper bounds of the exact confidence interval for the rate,
+    /// as calculated by [`poisson_rate_interval`](crate::poisson_rate_interval)
+    pub rate_interval: (f64, f64),
+    /// The natural logarithm of the likelihood of the counts under the fitted distribution
+    pub log_likelihood: f64,
+}
+
+impl PoissonFit {
+    /// Fit a Poisson distribution to `counts`, with a
+    /// confidence interval at the `confidence` level
+    pub(crate) fn new<I>(counts: I, confidence: f64) -> Result<Self>
+    where
+        I: IntoIterator<Item = Result<usize>>,
+    {
+        let (mut events, mut intervals) = (0usize, 0usize);
+        // the sum of the logarithms of the factorials of the counts
+        let mut ln_factorials = 0.0;
+        for k in counts {
+            let k = k?;
+            events += k;
+            intervals += 1;
+            ln_factorials += ln_gamma(k as f64 + 1.0);
+        }
+        if intervals == 0 {
+            return Err(StatsError::EmptyCollection);
+        }
+        let (k, n) = (events as f64, intervals as f64);
+        let rate = k / n;
+        let log_likelihood = if events == 0 {
+            0.0
+        } else {
+            k * Float::ln(rate) - k - ln_factorials
+        };
+        Ok(Self {
+            distribution: Poisson::new(rate),
+            rate_interval: poisson_rate_interval(events, intervals as f64, confidence),
+            log_likelihood,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "alloc")]
+    use alloc::vec::Vec;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_standard_normal() {
+        let normal = Normal::default();
+        assert_eq!(normal, Normal::standard());
+        assert_relative_eq!(normal.pdf(0.0), 0.398_942_280_401_432_7, epsilon = 1e-15);
+        assert_relative_eq!(normal.pdf(-1.0), 0.241_970_724_519_143_37, epsilon = 1e-15);
+        assert_eq!(normal.cdf(0.0), 0.5);
+        assert_relative_eq!(
+            normal.cdf(-1.96),
+            0.024_997_895_148_220_428,
+            epsilon = 1e-15
+        );
+        assert_eq!(normal.inverse_cdf(0.5), 0.0);
+        assert_eq!(normal.inverse_cdf(0.0), f64::NEG_INFINITY);
+        assert_eq!(normal.inverse_cdf(1.0), f64::INFINITY);
+    }
+
+    #[test]
+    fn test_normal() {
+        let normal = Normal::new(-3.0, 2.5);
+        assert_eq!(normal.variance(), 6.25);
+        assert_relative_eq!(
+            normal.pdf(-3.0),
+            0.398_942_280_401_432_7 / 2.5,
+            epsilon = 1e-15
+        );
+        for p in [1e-10, 0.01, 0.3, 0.5, 0.8, 0.999] {
+            assert_relative_eq!(normal.cdf(normal.inverse_cdf(p)), p, max_relative = 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_gumbel() {
+        let gumbel = Gumbel::new(1.0, 2.0);
+        assert_relative_eq!(gumbel.mean(), 1.0 + 2.0 * EULER_GAMMA);
+        assert_relative_eq!(gumbel.variance(), 4.0 * PI * PI / 6.0);
+        assert_relative_eq!(gumbel.cdf(1.0), (-1.0f64).exp(), epsilon = 1e-15);
+        assert_relative_eq!(gumbel.pdf(1.0), (-1.0f64).exp() / 2.0, epsilon = 1e-15);
+        for p in [1e-10, 0.01, 0.3, 0.5, 0.8, 0.999] {
+            assert_relative_eq!(gumbel.cdf(gumbel.inverse_cdf(p)), p, max_relative = 1e-12);
+        }
+        assert_relative_eq!(
+            gumbel.return_level(50.0),
+            gumbel.inverse_cdf(0.98),
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_gumbel_fit() {
+        // quantiles of a Gumbel distribution at evenly spaced probabilities
+        let gumbel = Gumbel::new(10.0, 3.0);
+        let values: Vec<f64> = (1..200)
+            .map(|i| gumbel.inverse_cdf(i as f64 / 200.0))
+            .collect();
+        let fit = GumbelFit::new(&values).unwrap();
+        assert_relative_eq!(fit.distribution.location(), 10.0, epsilon = 0.1);
+        assert_relative_eq!(fit.distribution.scale(), 3.0, epsilon = 0.1);
+        // the likelihood is largest at the fitted parameters
+        let log_likelihood = |location: f64, scale: f64| {
+            values
+                .iter()
+                .map(|x| (Gumbel::new(location, scale).pdf(*x)).ln())
+                .sum::<f64>()
+        };
+        let (location, scale) = (fit.distribution.location(), fit.distribution.scale());
+        assert_relative_eq!(
+            fit.log_likelihood,
+            log_likelihood(location, scale),
+            max_relative = 1e-12
+        );
+        for (dl, ds) in [(1e-3, 0.0), (-1e-3, 0.0), (0.0, 1e-3), (0.0, -1e-3)] {
+            assert!(log_likelihood(location + dl, scale + ds) < fit.log_likelihood);
+        }
+        assert_eq!(GumbelFit::new(&[2.0, 2.0]), Err(StatsError::ZeroVariance));
+        assert_eq!(
+            GumbelFit::new(&[1.0, 2.0, f64::INFINITY]),
+            Err(StatsError::InfinityEncountered)
+        );
+        assert_eq!(GumbelFit::new(&[-1e300, 1e300]), Err(StatsError::Overflow));
+    }
+
+    #[test]
+    fn test_generalized_pareto() {
+        // a shape of 0 is the exponential distribution
+        let exponential = GeneralizedPareto::new(0.5, 0.0);
+        assert_eq!(exponential.mean(), 0.5);
+        assert_eq!(exponential.cdf(1.5), Exponential::new(2.0).cdf(1.5));
+        assert_eq!(exponential.pdf(1.5), Exponential::new(2.0).pdf(1.5));
+        // a negative shape has an upper bound of the scale over minus the shape
+        let bounded = GeneralizedPareto::new(2.0, -0.5);
+        assert_eq!(bounded.cdf(4.0), 1.0);
+        assert_eq!(bounded.pdf(5.0), 0.0);
+        assert_eq!(bounded.inverse_cdf(1.0), 4.0);
+        assert_relative_eq!(bounded.variance(), 4.0 / (2.25 * 2.0));
+        let heavy = GeneralizedPareto::new(1.0, 0.75);
+        assert_eq!(heavy.variance(), f64::INFINITY);
+        assert_eq!(heavy.inverse_cdf(1.0), f64::INFINITY);
+        for distribution in [exponential, bounded, heavy] {
+            assert_eq!(distribution.cdf(-1.0), 0.0);
+            for p in [1e-10, 0.01, 0.3, 0.5, 0.8, 0.999] {
+                let x = distribution.inverse_cdf(p);
+                assert_relative_eq!(distribution.cdf(x), p, max_relative = 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_generalized_pareto_fit() {
+        // quantiles of a generalized Pareto distribution at evenly spaced probabilities
+        let distribution = GeneralizedPareto::new(2.0, 0.2);
+        let excesses: Vec<f64> = (0..500)
+            .map(|i| distribution.inverse_cdf((i as f64 + 0.5) / 500.0))
+            .collect();
+        let fit = GeneralizedParetoFit::new(&excesses, 10.0, 5000).unwrap();
+        assert_relative_eq!(fit.distribution.scale(), 2.0, epsilon = 0.05);
+        assert_relative_eq!(fit.distribution.shape(), 0.2, epsilon = 0.05);
+        assert_eq!(fit.exceedance_rate, 0.1);
+        // exceeded once in 10 items is the threshold itself
+        assert_relative_eq!(fit.return_level(10.0), 10.0, epsilon = 1e-12);
+        let level = fit.return_level(1000.0);
+        let excess = fit.distribution.inverse_cdf(0.99);
+        assert_relative_eq!(level, 10.0 + excess, epsilon = 1e-9);
+        assert_eq!(
+            GeneralizedParetoFit::new(&[1.0], 0.0, 10),
+            Err(StatsError::InsufficientData {
+                required: 2,
+                actual: 1
+            })
+        );
+        assert_eq!(
+            GeneralizedParetoFit::new(&[1.0, 1.0], 0.0, 10),
+            Err(StatsError::ZeroVariance)
+        );
+        assert_eq!(
+            GeneralizedParetoFit::new(&[1.0, f64::INFINITY], 0.0, 10),
+            Err(StatsError::InfinityEncountered)
+        );
+        assert_eq!(
+            GeneralizedParetoFit::new(&[1.0, f64::MAX], 0.0, 10),
+            Err(StatsError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_exponential() {
+        let exponential = Exponential::new(2.0);
+        assert_eq!(exponential.variance(), 0.25);
+        assert_eq!(exponential.pdf(-1.0), 0.0);
+        assert_relative_eq!(
+            exponential.pdf(0.5),
+            2.0 * (-1.0_f64).exp(),
+            epsilon = 1e-15
+        );
+        assert_relative_eq!(exponential.cdf(1.5), 0.950_212_931_632_136, epsilon = 1e-15);
+        assert_relative_eq!(
+            exponential.inverse_cdf(0.9),
+            1.151_292_546_497_023,
+            epsilon = 1e-15
+        );
+        assert_eq!(exponential.inverse_cdf(0.0), 0.0);
+        assert_eq!(exponential.inverse_cdf(1.0), f64::INFINITY);
+    }
+
+    #[test]
+    fn test_poisson() {
+        let poisson = Poisson::new(2.0);
+        assert_relative_eq!(poisson.pmf(0), (-2.0_f64).exp(), epsilon = 1e-15);
+        assert_relative_eq!(poisson.pmf(3), 0.180_447_044_315_483_6, epsilon = 1e-14);
+        assert_relative_eq!(poisson.cdf(3), 0.857_123_460_498_547, epsilon = 1e-14);
+        let never = Poisson::new(0.0);
+        assert_eq!(never.pmf(0), 1.0);
+        assert_eq!(never.pmf(1), 0.0);
+        assert_eq!(never.cdf(0), 1.0);
+    }
+
+    #[test]
+    fn test_exponential_fit() {
+        let values = [0.5, 1.2, 0.3, 2.1, 0.8, f64::NAN, 1.5, 0.4, 0.9];
+        let fit = ExponentialFit::new(values.map(Ok), 0.95).unwrap();
+        assert_relative_eq!(fit.distribution.rate(), 8.0 / 7.7, epsilon = 1e-12);
+        assert_relative_eq!(fit.rate_interval.0, 0.448_549_633_343_961_2, epsilon = 1e-9);
+        assert_relative_eq!(fit.rate_interval.1, 1.873_074_722_299_01, epsilon = 1e-9);
+        assert_relative_eq!(fit.log_likelihood, -7.694_230_297_438_418, epsilon = 1e-12);
+        assert_eq!(
+            ExponentialFit::new([Ok(0.0), Ok(0.0)], 0.95),
+            Err(StatsError::ZeroVariance)
+        );
+        assert_eq!(
+            ExponentialFit::new([], 0.95),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+
+    #[test]
+    fn test_exponential_fit_negative() {
+        assert_eq!(
+            ExponentialFit::new([Ok(1.0), Ok(-1.0)], 0.95),
+            Err(StatsError::OutOfDomain)
+        );
+        assert_eq!(
+            ExponentialFit::new([Ok(f64::NEG_INFINITY)], 0.95),
+            Err(StatsError::OutOfDomain)
+        );
+    }
+
+    #[test]
+    fn test_poisson_fit() {
+        let counts = [2, 3, 1, 4, 2, 0, 3, 2, 1, 2];
+        let fit = PoissonFit::new(counts.map(Ok), 0.95).unwrap();
+        assert_eq!(fit.distribution.rate(), 2.0);
+        assert_relative_eq!(fit.rate_interval.0, 1.221_651_958_540_394, epsilon = 1e-9);
+        assert_relative_eq!(fit.rate_interval.1, 3.088_837_790_267_46, epsilon = 1e-9);
+        assert_relative_eq!(fit.log_likelihood, -15.671_217_879_844_93, epsilon = 1e-12);
+        let zeros = PoissonFit::new([Ok(0), Ok(0)], 0.95).unwrap();
+        assert_eq!(zeros.distribution.rate(), 0.0);
+        assert_eq!(zeros.rate_interval.0, 0.0);
+        assert_eq!(zeros.log_likelihood, 0.0);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_normal_fit() {
+        let mut sorted = [4.2, 5.1, 3.9, 4.8, 5.5, 4.4, 4.9, 5.0, 4.6, 4.1, 5.3];
+        sorted.sort_by(f64::total_cmp);
+        let fit = NormalFit::new(&sorted).unwrap();
+        assert_relative_eq!(fit.distribution.mean(), 4.709090909090909, epsilon = 1e-12);
+        assert_relative_eq!(
+            fit.distribution.std_dev(),
+            0.49074071186421014,
+            epsilon = 1e-12
+        );
+        let log_likelihood: f64 = sorted.iter().map(|&x| fit.distribution.pdf(x).ln()).sum();
+        assert_relative_eq!(fit.log_likelihood, log_likelihood, epsilon = 1e-12);
+        assert_relative_eq!(fit.ks_statistic, 0.12295086975088282, epsilon = 1e-12);
+        assert_eq!(NormalFit::new(&[2.0, 2.0]), Err(StatsError::ZeroVariance));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_zero_std_dev() {
+        Normal::new(0.0, 0.0);
+    }
+}
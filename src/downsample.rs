@@ -0,0 +1,161 @@
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+
+use num_traits::ToPrimitive;
+
+use crate::helpers::item_to_f64;
+use crate::helpers::median_in_place;
+use crate::Result;
+
+/// How each block of items is reduced to one value by
+/// [`IteratorStats::downsample`](crate::IteratorStats::downsample)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Aggregate {
+    /// The mean of the block
+    #[default]
+    Mean,
+    /// The smallest item of the block
+    Min,
+    /// The largest item of the block
+    Max,
+    /// The median of the block
+    Median,
+    /// The first item of the block
+    First,
+    /// The last item of the block
+    Last,
+}
+
+impl Aggregate {
+    /// Returns the aggregate of the non-empty `block`, which may be reordered
+    fn reduce(self, block: &mut [f64]) -> f64 {
+        match self {
+            Self::Mean => block.iter().sum::<f64>() / block.len() as f64,
+            Self::Min => block.iter().copied().fold(f64::INFINITY, f64::min),
+            Self::Max => block.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            Self::Median => median_in_place(block),
+            Self::First => block[0],
+            Self::Last => block[block.len() - 1],
+        }
+    }
+}
+
+/// An iterator that reduces each block of consecutive items of another one
+/// to a single value. It is created by
+/// [`IteratorStats::downsample`](crate::IteratorStats::downsample).
+#[derive(Debug, Clone)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct Downsample<I> {
+    iter: I,
+    block_size: usize,
+    aggregate: Aggregate,
+    /// The items of the current block, kept to reuse the allocation
+    block: Vec<f64>,
+}
+
+impl<I> Downsample<I> {
+    /// Create the iterator, for [`IteratorStats::downsample`](crate::IteratorStats::downsample)
+    pub(crate) fn new(iter: I, block_size: usize, aggregate: Aggregate) -> Self {
+        assert!(block_size > 0, "block size must be greater than 0");
+        Self {
+            iter,
+            block_size,
+            aggregate,
+            block: Vec::with_capacity(block_size),
+        }
+    }
+}
+
+impl<I> Iterator for Downsample<I>
+where
+    I: Iterator,
+    I::Item: ToPrimitive,
+{
+    type Item = Result<f64>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.block.clear();
+        let mut error = None;
+        let mut taken = 0;
+        for x in self.iter.by_ref().take(self.block_size) {
+            taken += 1;
+            match item_to_f64(x) {
+                Ok(x) => self.block.push(x),
+                Err(e) => error = error.or(Some(e)),
+            }
+        }
+        if taken == 0 {
+            return None;
+        }
+        Some(match error {
+            Some(error) => Err(error),
+            None => Ok(self.aggregate.reduce(&mut self.block)),
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        (
+            lower.div_ceil(self.block_size),
+            upper.map(|upper| upper.div_ceil(self.block_size)),
+        )
+    }
+}
+
+impl<I> ExactSizeIterator for Downsample<I>
+where
+    I: ExactSizeIterator,
+    I::Item: ToPrimitive,
+{
+}
+
+impl<I> FusedIterator for Downsample<I>
+where
+    I: FusedIterator,
+    I::Item: ToPrimitive,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IteratorStats;
+
+    extern crate std;
+    use std::vec;
+
+    fn downsample(values: &[f64], aggregate: Aggregate) -> Vec<f64> {
+        values
+            .iter()
+            .copied()
+            .downsample(3, aggregate)
+            .map(Result::unwrap)
+            .collect()
+    }
+
+    #[test]
+    fn test_aggregates() {
+        let values = [3.0, 1.0, 2.0, 10.0, 40.0, 4.0, 7.0];
+        assert_eq!(downsample(&values, Aggregate::Mean), [2.0, 18.0, 7.0]);
+        assert_eq!(downsample(&values, Aggregate::Min), [1.0, 4.0, 7.0]);
+        assert_eq!(downsample(&values, Aggregate::Max), [3.0, 40.0, 7.0]);
+        assert_eq!(downsample(&values, Aggregate::Median), [2.0, 10.0, 7.0]);
+        assert_eq!(downsample(&values, Aggregate::First), [3.0, 10.0, 7.0]);
+        assert_eq!(downsample(&values, Aggregate::Last), [2.0, 4.0, 7.0]);
+        assert!(downsample(&[], Aggregate::Mean).is_empty());
+    }
+
+    #[test]
+    fn test_length() {
+        let blocks = (0..10).downsample(4, Aggregate::Max);
+        assert_eq!(blocks.len(), 3);
+        let maxima: Vec<f64> = blocks.map(Result::unwrap).collect();
+        assert_eq!(maxima, vec![3.0, 7.0, 9.0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "block size must be greater than 0")]
+    fn test_zero_block_size() {
+        let _ = [1.0].into_iter().downsample(0, Aggregate::Mean);
+    }
+}
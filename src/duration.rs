@@ -0,0 +1,279 @@
+use core::time::Duration;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+#[cfg(feature = "alloc")]
+use crate::helpers::Float;
+#[cfg(feature = "alloc")]
+use crate::InterArrival;
+use crate::OnlineStats;
+use crate::Result;
+use crate::StatsError;
+
+/// The number of nanoseconds in a second
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+/// Returns the durations sorted, checking that there is at least one
+#[cfg(feature = "alloc")]
+fn sorted_durations(durations: impl Iterator<Item = Duration>) -> Result<Vec<Duration>> {
+    let mut sorted: Vec<Duration> = durations.collect();
+    if sorted.is_empty() {
+        return Err(StatsError::EmptyCollection);
+    }
+    sorted.sort_unstable();
+    Ok(sorted)
+}
+
+/// Returns the `p` quantile of the non-empty `sorted` durations,
+/// interpolating linearly and rounding to the nanosecond
+#[cfg(feature = "alloc")]
+fn quantile_of_sorted(sorted: &[Duration], p: f64) -> Duration {
+    let h = (sorted.len() - 1) as f64 * p;
+    // h is between 0 and the last index, so this truncation is a floor
+    let lower = h as usize;
+    let Some(&upper) = sorted.get(lower + 1) else {
+        return sorted[lower];
+    };
+    let between = upper - sorted[lower];
+    let fraction = h - lower as f64;
+    let offset = Float::round(between.as_nanos() as f64 * fraction) as u128;
+    sorted[lower] + from_nanos(offset)
+}
+
+/// A trait for collections of [`Duration`]s, such as the timings of a
+/// benchmark, with statistics that are durations too. [`Duration`] isn't a
+/// number, so it doesn't implement [`Stats`](crate::Stats).
+///
+/// It is implemented for the same collections as [`Stats`](crate::Stats),
+/// when their items are durations. The sum, mean and quantiles are exact
+/// to the nanosecond, and the standard deviation is calculated in seconds
+/// as an [`f64`].
+///
+/// # Examples
+/// ```
+/// use core::time::Duration;
+/// use stats_traits::DurationStats;
+/// let timings = vec![
+///     Duration::from_millis(12),
+///     Duration::from_millis(10),
+///     Duration::from_millis(17),
+///     Duration::from_millis(13),
+/// ];
+/// assert_eq!(timings.mean(), Ok(Duration::from_millis(13)));
+/// assert_eq!(DurationStats::min(&timings), Ok(Duration::from_millis(10)));
+/// let std_dev = timings.std_dev().unwrap();
+/// assert_eq!(std_dev.as_micros(), 2_549);
+/// ```
+pub trait DurationStats {
+    /// Returns an iterator over the durations of the collection
+    fn durations(&self) -> impl Iterator<Item = Duration>;
+
+    /// Returns an [`OnlineStats`] of the durations in seconds
+    fn online_stats(&self) -> OnlineStats {
+        self.durations().map(|x| x.as_secs_f64()).collect()
+    }
+
+    /// Calculate the sum of the durations, which is zero if there aren't any
+    ///
+    /// # Panics
+    /// Panics if the sum overflows a [`Duration`], like [`Iterator::sum`]
+    fn sum(&self) -> Duration {
+        self.durations().sum()
+    }
+
+    /// Calculate the mean of the durations, rounded down to the nanosecond
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty
+    fn mean(&self) -> Result<Duration> {
+        let (sum, count) = self.durations().fold((0u128, 0u128), |(sum, count), x| {
+            (sum + x.as_nanos(), count + 1)
+        });
+        if count == 0 {
+            return Err(StatsError::EmptyCollection);
+        }
+        Ok(from_nanos(sum / count))
+    }
+
+    /// Calculate the (population) standard deviation of the durations
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty
+    fn std_dev(&self) -> Result<Duration> {
+        self.online_stats().std_dev().map(Duration::from_secs_f64)
+    }
+
+    /// Find the shortest duration
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty
+    fn min(&self) -> Result<Duration> {
+        self.durations().min().ok_or(StatsError::EmptyCollection)
+    }
+
+    /// Find the longest duration
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty
+    fn max(&self) -> Result<Duration> {
+        self.durations().max().ok_or(StatsError::EmptyCollection)
+    }
+
+    /// Find the median of the durations, which is the mean of the
+    /// middle two if there are an even number of them
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty
+    #[cfg(feature = "alloc")]
+    fn median(&self) -> Result<Duration> {
+        self.quantile(0.5)
+    }
+
+    /// Find the `p` quantile of the durations, interpolating linearly between
+    /// the two nearest durations like [`Stats::quantile`](crate::Stats::quantile),
+    /// so the 99th percentile is `quantile(0.99)`
+    ///
+    /// # Examples
+    /// ```
+    /// use core::time::Duration;
+    /// use stats_traits::DurationStats;
+    /// let timings = [12, 10, 17, 13].map(Duration::from_millis);
+    /// assert_eq!(timings.median(), Ok(Duration::from_micros(12_500)));
+    /// assert_eq!(timings.quantile(0.99), Ok(Duration::from_micros(16_880)));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty
+    ///
+    /// # Panics
+    /// Panics if `p` is not between 0 and 1
+    #[cfg(feature = "alloc")]
+    fn quantile(&self, p: f64) -> Result<Duration> {
+        assert!((0.0..=1.0).contains(&p), "p must be between 0 and 1");
+        Ok(quantile_of_sorted(&sorted_durations(self.durations())?, p))
+    }
+
+    /// Find the quantile of the durations for every `p` in `ps`, in the same
+    /// order, like [`DurationStats::quantile`] but sorting them only once
+    ///
+    /// # Examples
+    /// ```
+    /// use core::time::Duration;
+    /// use stats_traits::DurationStats;
+    /// let timings = [12, 10, 17, 13].map(Duration::from_millis);
+    /// let [p50, p99] = timings.quantiles(&[0.5, 0.99]).unwrap()[..] else {
+    ///     unreachable!()
+    /// };
+    /// assert_eq!(p50, Duration::from_micros(12_500));
+    /// assert_eq!(p99, Duration::from_micros(16_880));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty
+    ///
+    /// # Panics
+    /// Panics if any `p` is not between 0 and 1
+    #[cfg(feature = "alloc")]
+    fn quantiles(&self, ps: &[f64]) -> Result<Vec<Duration>> {
+        assert!(
+            ps.iter().all(|p| (0.0..=1.0).contains(p)),
+            "p must be between 0 and 1"
+        );
+        let sorted = sorted_durations(self.durations())?;
+        Ok(ps.iter().map(|&p| quantile_of_sorted(&sorted, p)).collect())
+    }
+
+    /// Calculate the statistics of the gaps between the durations, treating
+    /// them as timestamps in ascending order, such as the times since the
+    /// start of a log. See [`InterArrival::from_timestamps`].
+    ///
+    /// # Errors
+    /// Errors under the same conditions as [`InterArrival::from_timestamps`]
+    #[cfg(feature = "alloc")]
+    fn inter_arrival(&self) -> Result<InterArrival> {
+        InterArrival::from_timestamps(self.durations())
+    }
+}
+
+/// Blanket implementation for all collections of durations that
+/// can be iterated over by reference, like [`Stats`](crate::Stats)
+impl<T> DurationStats for T
+where
+    T: ?Sized,
+    for<'a> &'a T: IntoIterator<Item = &'a Duration>,
+{
+    fn durations(&self) -> impl Iterator<Item = Duration> {
+        self.into_iter().copied()
+    }
+}
+
+/// Returns the duration of `nanos` nanoseconds, which must fit in a [`Duration`]
+fn from_nanos(nanos: u128) -> Duration {
+    // the quotient fits because it is at most the seconds of a duration
+    Duration::new(
+        (nanos / NANOS_PER_SEC) as u64,
+        (nanos % NANOS_PER_SEC) as u32,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_durations() {
+        let timings = [
+            Duration::new(1, 999_999_999),
+            Duration::new(2, 1),
+            Duration::from_secs(3),
+        ];
+        assert_eq!(timings.sum(), Duration::from_secs(7));
+        assert_eq!(timings.mean(), Ok(Duration::new(2, 333_333_333)));
+        assert_eq!(
+            DurationStats::min(&timings),
+            Ok(Duration::new(1, 999_999_999))
+        );
+        assert_eq!(DurationStats::max(&timings), Ok(Duration::from_secs(3)));
+        let std_dev = timings.std_dev().unwrap().as_secs_f64();
+        assert!((std_dev - (2.0f64 / 9.0).sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_huge_durations() {
+        // the sum of the nanoseconds doesn't fit in a u64
+        let timings = [Duration::from_secs(u64::MAX / 2); 4];
+        assert_eq!(timings.mean(), Ok(Duration::from_secs(u64::MAX / 2)));
+    }
+
+    #[test]
+    fn test_empty() {
+        let timings: [Duration; 0] = [];
+        assert_eq!(timings.sum(), Duration::ZERO);
+        assert_eq!(timings.mean(), Err(StatsError::EmptyCollection));
+        assert_eq!(timings.std_dev(), Err(StatsError::EmptyCollection));
+        assert_eq!(
+            DurationStats::max(&timings),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_quantiles() {
+        let timings = [5, 1, 4, 2, 3].map(Duration::from_nanos);
+        assert_eq!(timings.median(), Ok(Duration::from_nanos(3)));
+        assert_eq!(timings.quantile(0.0), Ok(Duration::from_nanos(1)));
+        assert_eq!(timings.quantile(1.0), Ok(Duration::from_nanos(5)));
+        assert_eq!(timings.quantile(0.125), Ok(Duration::from_nanos(2)));
+        assert_eq!(
+            timings.quantiles(&[1.0, 0.125]),
+            Ok(alloc::vec![
+                Duration::from_nanos(5),
+                Duration::from_nanos(2)
+            ])
+        );
+        let empty: [Duration; 0] = [];
+        assert_eq!(empty.median(), Err(StatsError::EmptyCollection));
+    }
+}
@@ -1,10 +1,55 @@
 //! Contains types related to error handling in the crate
 
+use core::fmt;
+
 /// Error type for the crate
-#[derive(Debug, PartialEq, Eq)]
+///
+/// More variants may be added as new statistics need them, so matches on it
+/// outside of this crate need a wildcard arm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
 pub enum StatsError {
     /// Could not be calculated because the collection was empty
     EmptyCollection,
+    /// Could not be calculated because the collection had too few items
+    InsufficientData {
+        /// Number of items needed
+        required: usize,
+        /// Number of items in the collection
+        actual: usize,
+    },
+    /// Could not be calculated because every item had the same value,
+    /// so the statistic would divide by a variance of zero
+    ZeroVariance,
+    /// Could not be calculated because the result, or a value calculated
+    /// on the way to it, was too large for the type of the items
+    Overflow,
+    /// Could not be calculated because an item was `NaN`
+    NaNEncountered,
+    /// Could not be calculated because an item was infinite, such as
+    /// when fitting a distribution, whose parameters must be finite
+    InfinityEncountered,
+    /// Could not be calculated because an item was outside the values the
+    /// statistic is defined for, like a negative item for a statistic of
+    /// shares, or 0 for one that takes the logarithm of every item
+    OutOfDomain,
+    /// Could not be calculated because the items needed to be in ascending
+    /// order, like timestamps, and an item was smaller than the one before it
+    NotSorted,
+    /// Could not be calculated because a parameter was outside the values
+    /// it can take for the items. Parameters that are invalid whatever the
+    /// items are, like a probability above 1, cause a panic instead.
+    ///
+    /// With the `serde` feature, this variant can be serialized but not
+    /// deserialized, because the name is a `&'static str`.
+    #[cfg_attr(feature = "serde", serde(skip_deserializing))]
+    InvalidParameter {
+        /// Name of the parameter
+        #[cfg_attr(feature = "serde", serde(skip_deserializing))]
+        name: &'static str,
+    },
     /// Could not convert between data types
     CouldNotConvert {
         /// Data type the conversion was attempted from
@@ -16,9 +61,127 @@ pub enum StatsError {
 
 /// Enum for representations of data types the crate might try
 /// and convert between
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DataType {
     Usize,
     F64,
+    I64,
     Item,
+    WideSum,
+}
+
+impl fmt::Display for StatsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyCollection => write!(f, "the collection is empty"),
+            Self::InsufficientData { required, actual } => write!(
+                f,
+                "the collection has {actual} items, but at least {required} are needed"
+            ),
+            Self::ZeroVariance => write!(f, "every item has the same value"),
+            Self::Overflow => write!(f, "the result is too large for the type of the items"),
+            Self::NaNEncountered => write!(f, "an item is NaN"),
+            Self::InfinityEncountered => write!(f, "an item is infinite"),
+            Self::OutOfDomain => write!(
+                f,
+                "an item is outside the values the statistic is defined for"
+            ),
+            Self::NotSorted => write!(f, "the items are not in ascending order"),
+            Self::InvalidParameter { name } => {
+                write!(f, "the parameter `{name}` is invalid for these items")
+            }
+            Self::CouldNotConvert { from, to } => {
+                write!(f, "could not convert from {from} to {to}")
+            }
+        }
+    }
+}
+
+impl core::error::Error for StatsError {}
+
+impl fmt::Display for DataType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Usize => "usize",
+            Self::F64 => "f64",
+            Self::I64 => "i64",
+            Self::Item => "the type of the items",
+            Self::WideSum => "the wider type the items are added up in",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    extern crate std;
+    use std::boxed::Box;
+    use std::string::ToString;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            StatsError::InsufficientData {
+                required: 3,
+                actual: 1
+            }
+            .to_string(),
+            "the collection has 1 items, but at least 3 are needed"
+        );
+        assert_eq!(
+            StatsError::InvalidParameter { name: "lag" }.to_string(),
+            "the parameter `lag` is invalid for these items"
+        );
+        assert_eq!(
+            StatsError::InfinityEncountered.to_string(),
+            "an item is infinite"
+        );
+        assert_eq!(
+            StatsError::OutOfDomain.to_string(),
+            "an item is outside the values the statistic is defined for"
+        );
+        assert_eq!(
+            StatsError::NotSorted.to_string(),
+            "the items are not in ascending order"
+        );
+        assert_eq!(
+            StatsError::CouldNotConvert {
+                from: DataType::Usize,
+                to: DataType::Item
+            }
+            .to_string(),
+            "could not convert from usize to the type of the items"
+        );
+        assert_eq!(
+            StatsError::CouldNotConvert {
+                from: DataType::Usize,
+                to: DataType::WideSum
+            }
+            .to_string(),
+            "could not convert from usize to the wider type the items are added up in"
+        );
+    }
+
+    #[test]
+    fn test_boxed_error() {
+        let error: Box<dyn core::error::Error> = Box::new(StatsError::EmptyCollection);
+        assert_eq!(error.to_string(), "the collection is empty");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let error = StatsError::CouldNotConvert {
+            from: DataType::F64,
+            to: DataType::Item,
+        };
+        let json = serde_json::to_string(&error).unwrap();
+        assert_eq!(json, r#"{"CouldNotConvert":{"from":"F64","to":"Item"}}"#);
+        assert_eq!(serde_json::from_str::<StatsError>(&json).unwrap(), error);
+        let json = serde_json::to_string(&StatsError::InvalidParameter { name: "lag" }).unwrap();
+        assert!(serde_json::from_str::<StatsError>(&json).is_err());
+    }
 }
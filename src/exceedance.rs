@@ -0,0 +1,95 @@
+use crate::Result;
+use crate::StatsError;
+
+/// Statistics of the items above a threshold, for the peaks-over-threshold
+/// approach to extreme values, as calculated by
+/// [`Stats::exceedances`](crate::Stats::exceedances). Unlike block maxima,
+/// it uses every high item, even several from the same block.
+/// [`Stats::fit_generalized_pareto`](crate::Stats::fit_generalized_pareto)
+/// fits a distribution to the excesses over the threshold.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Exceedances {
+    /// The threshold the items are compared with
+    pub threshold: f64,
+    /// Number of items that aren't `NaN`
+    pub count: usize,
+    /// Number of items greater than the threshold
+    pub exceedance_count: usize,
+    /// Fraction of the items that are greater than the threshold
+    pub exceedance_rate: f64,
+    /// Mean of how far the items greater than the threshold exceed it, or
+    /// `NaN` if none do. Plotted against the threshold, it is roughly a
+    /// straight line above thresholds high enough for the excesses to follow
+    /// a generalized Pareto distribution.
+    pub mean_excess: f64,
+    /// How far the largest item exceeds the threshold, or `NaN` if no item does
+    pub max_excess: f64,
+}
+
+impl Exceedances {
+    /// Calculate the statistics of the `values` above `threshold`, skipping `NaN`s
+    ///
+    /// # Errors
+    /// Returns an error if a value is an error,
+    /// or if there are no values that aren't `NaN`
+    pub(crate) fn new<I>(values: I, threshold: f64) -> Result<Self>
+    where
+        I: IntoIterator<Item = Result<f64>>,
+    {
+        let (mut count, mut exceedance_count) = (0, 0);
+        let (mut excess_sum, mut max_excess) = (0.0, f64::NAN);
+        for x in values {
+            let x = x?;
+            if x.is_nan() {
+                continue;
+            }
+            count += 1;
+            if x > threshold {
+                exceedance_count += 1;
+                excess_sum += x - threshold;
+                max_excess = max_excess.max(x - threshold);
+            }
+        }
+        if count == 0 {
+            return Err(StatsError::EmptyCollection);
+        }
+        let mean_excess = if exceedance_count == 0 {
+            f64::NAN
+        } else {
+            excess_sum / exceedance_count as f64
+        };
+        Ok(Self {
+            threshold,
+            count,
+            exceedance_count,
+            exceedance_rate: exceedance_count as f64 / count as f64,
+            mean_excess,
+            max_excess,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let values = [1.0, 5.0, f64::NAN, 3.0, 8.0, 2.0].map(Ok);
+        let exceedances = Exceedances::new(values, 2.0).unwrap();
+        assert_eq!(exceedances.count, 5);
+        assert_eq!(exceedances.exceedance_count, 3);
+        assert_eq!(exceedances.exceedance_rate, 0.6);
+        assert_eq!(exceedances.mean_excess, 10.0 / 3.0);
+        assert_eq!(exceedances.max_excess, 6.0);
+        let none = Exceedances::new(values, 8.0).unwrap();
+        assert_eq!(none.exceedance_count, 0);
+        assert!(none.mean_excess.is_nan() && none.max_excess.is_nan());
+        assert_eq!(
+            Exceedances::new([Ok(f64::NAN)], 0.0),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+}
@@ -0,0 +1,208 @@
+use core::fmt;
+
+use num_traits::ToPrimitive;
+
+use crate::helpers::item_to_f64;
+use crate::OnlineStats;
+use crate::StatsError;
+use crate::Summary;
+
+/// Error type for [`TryIteratorStats`], which is either the first error
+/// of the items, or an error calculating the statistic
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryStatsError<E> {
+    /// An item was an error, so the items after it weren't used
+    Item(E),
+    /// The statistic couldn't be calculated from the items
+    Stats(StatsError),
+}
+
+impl<E> From<StatsError> for TryStatsError<E> {
+    fn from(error: StatsError) -> Self {
+        Self::Stats(error)
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for TryStatsError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Item(error) => write!(f, "an item is an error: {error}"),
+            Self::Stats(error) => error.fmt(f),
+        }
+    }
+}
+
+impl<E> core::error::Error for TryStatsError<E>
+where
+    E: core::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Item(error) => Some(error),
+            Self::Stats(error) => Some(error),
+        }
+    }
+}
+
+/// An extension trait for [`Iterator`]s over [`Result`]s, such as values
+/// being parsed, that stops at the first error and returns it, so reading
+/// and calculating statistics are done in one pass without collecting the
+/// values first. Like [`IteratorStats`](crate::IteratorStats), each method
+/// converts the items to [`f64`] and pushes them to an [`OnlineStats`].
+///
+/// # Examples
+/// ```
+/// use stats_traits::{TryIteratorStats, TryStatsError};
+/// let column = "1.5,2.5,5.0";
+/// let mean = column.split(',').map(str::parse::<f64>).try_mean();
+/// assert_eq!(mean, Ok(3.0));
+/// let column = "1.5,oops,5.0";
+/// let mean = column.split(',').map(str::parse::<f64>).try_mean();
+/// assert!(matches!(mean, Err(TryStatsError::Item(_))));
+/// ```
+pub trait TryIteratorStats<T, E>: Iterator<Item = Result<T, E>> + Sized
+where
+    T: ToPrimitive,
+{
+    /// Push every item to an [`OnlineStats`], which has all the statistics
+    /// of the items, for when more than one of them is needed
+    ///
+    /// # Errors
+    /// Returns the first item that is an error, or an error if an item
+    /// can't be converted to [`f64`]
+    fn try_online(self) -> Result<OnlineStats, TryStatsError<E>> {
+        let mut stats = OnlineStats::new();
+        for x in self {
+            stats.push(item_to_f64(x.map_err(TryStatsError::Item)?)?);
+        }
+        Ok(stats)
+    }
+
+    /// Calculate the sum of the items, which is zero if there aren't any
+    ///
+    /// # Errors
+    /// Returns the first item that is an error, or an error if an item
+    /// can't be converted to [`f64`]
+    fn try_sum(self) -> Result<f64, TryStatsError<E>> {
+        let mut sum = 0.0;
+        for x in self {
+            sum += item_to_f64(x.map_err(TryStatsError::Item)?)?;
+        }
+        Ok(sum)
+    }
+
+    /// Calculate the mean of the items
+    ///
+    /// # Errors
+    /// Returns the first item that is an error, or an error if there are
+    /// no items or if an item can't be converted to [`f64`]
+    fn try_mean(self) -> Result<f64, TryStatsError<E>> {
+        Ok(self.try_online()?.mean()?)
+    }
+
+    /// Calculate the (population) variance of the items.
+    /// See [`Stats::variance`](crate::Stats::variance).
+    ///
+    /// # Errors
+    /// Returns the first item that is an error, or an error if there are
+    /// no items or if an item can't be converted to [`f64`]
+    fn try_variance(self) -> Result<f64, TryStatsError<E>> {
+        Ok(self.try_online()?.variance()?)
+    }
+
+    /// Calculate the standard deviation of the items, which is
+    /// the square root of [`TryIteratorStats::try_variance`]
+    ///
+    /// # Errors
+    /// Returns the first item that is an error, or an error if there are
+    /// no items or if an item can't be converted to [`f64`]
+    fn try_std_dev(self) -> Result<f64, TryStatsError<E>> {
+        Ok(self.try_online()?.std_dev()?)
+    }
+
+    /// Find the smallest item
+    ///
+    /// # Errors
+    /// Returns the first item that is an error, or an error if there are
+    /// no items or if an item can't be converted to [`f64`]
+    fn try_min(self) -> Result<f64, TryStatsError<E>> {
+        Ok(self.try_online()?.min()?)
+    }
+
+    /// Find the largest item
+    ///
+    /// # Errors
+    /// Returns the first item that is an error, or an error if there are
+    /// no items or if an item can't be converted to [`f64`]
+    fn try_max(self) -> Result<f64, TryStatsError<E>> {
+        Ok(self.try_online()?.max()?)
+    }
+
+    /// Calculate a [`Summary`] of the items
+    ///
+    /// # Errors
+    /// Returns the first item that is an error, or an error if there are
+    /// no items or if an item can't be converted to [`f64`]
+    fn try_summary(self) -> Result<Summary, TryStatsError<E>> {
+        Ok(self.try_online()?.summary()?)
+    }
+}
+
+/// Blanket implementation for all [`Iterator`]s over [`Result`]s
+/// whose values can be converted to [`f64`]
+impl<I, T, E> TryIteratorStats<T, E> for I
+where
+    I: Iterator<Item = Result<T, E>>,
+    T: ToPrimitive,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    extern crate std;
+    use std::string::ToString;
+
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_stops_at_first_error() {
+        let mut read = 0;
+        let items = [Ok(1), Ok(2), Err("bad row"), Ok(4)]
+            .into_iter()
+            .inspect(|_| read += 1);
+        assert_eq!(items.try_sum(), Err(TryStatsError::Item("bad row")));
+        assert_eq!(read, 3);
+    }
+
+    #[test]
+    fn test_methods() {
+        let items = || [2, 4, 4, 4, 5, 5, 7, 9].map(Ok::<_, ()>).into_iter();
+        assert_eq!(items().try_sum(), Ok(40.0));
+        assert_eq!(items().try_mean(), Ok(5.0));
+        assert_relative_eq!(items().try_variance().unwrap(), 4.0, epsilon = 1e-12);
+        assert_relative_eq!(items().try_std_dev().unwrap(), 2.0, epsilon = 1e-12);
+        assert_eq!(items().try_min(), Ok(2.0));
+        assert_eq!(items().try_max(), Ok(9.0));
+        assert_eq!(items().try_summary().unwrap().count, 8);
+        assert_eq!(
+            core::iter::empty::<Result<f64, ()>>().try_mean(),
+            Err(TryStatsError::Stats(StatsError::EmptyCollection))
+        );
+    }
+
+    #[test]
+    fn test_error() {
+        let error = "1.0,x"
+            .split(',')
+            .map(str::parse::<f64>)
+            .try_max()
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "an item is an error: invalid float literal"
+        );
+        assert!(core::error::Error::source(&error).is_some());
+    }
+}
@@ -0,0 +1,64 @@
+use crate::Stats;
+
+/// A collection seen as only the items for which a predicate returns
+/// `true`, created by [`Stats::filtered`]. It implements [`Stats`], so
+/// every statistic can be calculated of the items that match, such as only
+/// the successful requests, without copying them into a new collection.
+///
+/// The items are filtered again for each statistic, so the predicate
+/// should be cheap and give the same answer every time.
+#[derive(Debug, Clone, Copy)]
+pub struct Filtered<'a, T: ?Sized, F> {
+    inner: &'a T,
+    predicate: F,
+}
+
+impl<'a, T: ?Sized, F> Filtered<'a, T, F> {
+    /// Create a view of the items of `inner` for which `predicate` returns `true`
+    pub(crate) fn new(inner: &'a T, predicate: F) -> Self {
+        Self { inner, predicate }
+    }
+
+    /// Returns the collection with all of the items
+    pub fn get(&self) -> &'a T {
+        self.inner
+    }
+}
+
+impl<T, F> Stats for Filtered<'_, T, F>
+where
+    T: Stats + ?Sized,
+    F: Fn(&T::Item) -> bool,
+{
+    type Item = T::Item;
+
+    fn items<'a>(&'a self) -> impl Iterator<Item = T::Item> + 'a
+    where
+        Self::Item: 'a,
+    {
+        self.inner.items().filter(&self.predicate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Stats;
+    use crate::StatsError;
+
+    #[test]
+    fn test_filtered() {
+        let latencies = [120, 95, 3000, 110, 2500, 105];
+        let fast = latencies.filtered(|&x| x < 1000);
+        assert_eq!(fast.count(), 4);
+        assert_eq!(fast.mean(), Ok(107));
+        assert_eq!(Stats::max(&fast), Ok(120));
+        assert_eq!(fast.get(), &latencies);
+        // views can be filtered again
+        let slow = latencies.filtered(|&x| x >= 1000);
+        assert_eq!(slow.filtered(|&x| x < 2600).sum(), 2500);
+        assert_eq!(
+            latencies.filtered(|_| false).variance(),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+}
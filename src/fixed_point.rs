@@ -0,0 +1,59 @@
+use fixed::traits::Fixed;
+use fixed::{FixedI128, FixedI16, FixedI32, FixedI64, FixedI8};
+use fixed::{FixedU128, FixedU16, FixedU32, FixedU64, FixedU8};
+
+use crate::SquareRoot;
+
+/// Implements [`SquareRoot`] for fixed-point types with their own square
+/// root, which only uses integer arithmetic, so [`Stats::root_std_dev`](crate::Stats::root_std_dev)
+/// works on targets without a floating point unit
+macro_rules! impl_square_root_fixed {
+    ($($type: ident)*) => {
+        $(impl<Frac> SquareRoot for $type<Frac>
+        where
+            $type<Frac>: Fixed,
+        {
+            fn square_root(&self) -> Option<Self> {
+                Fixed::checked_sqrt(*self)
+            }
+        })*
+    };
+}
+
+impl_square_root_fixed!(FixedI8 FixedI16 FixedI32 FixedI64 FixedI128);
+impl_square_root_fixed!(FixedU8 FixedU16 FixedU32 FixedU64 FixedU128);
+
+#[cfg(test)]
+mod tests {
+    use crate::SquareRoot;
+    use crate::Stats;
+    use crate::StatsError;
+
+    use fixed::types::{I16F16, U8F8};
+
+    #[test]
+    fn test_fixed_point() {
+        let samples = [2, 4, 4, 4, 5, 5, 7, 9].map(I16F16::from_num);
+        assert_eq!(samples.sum(), I16F16::from_num(40));
+        assert_eq!(samples.mean(), Ok(I16F16::from_num(5)));
+        assert_eq!(samples.variance(), Ok(I16F16::from_num(4)));
+        assert_eq!(samples.root_std_dev(), Ok(I16F16::from_num(2)));
+        assert_eq!(Stats::min(&samples), Ok(I16F16::from_num(2)));
+        let halves = [0.5, 1.5].map(I16F16::from_num);
+        assert_eq!(halves.root_std_dev(), Ok(I16F16::from_num(0.5)));
+    }
+
+    #[test]
+    fn test_square_root() {
+        assert_eq!(
+            I16F16::from_num(2.25).square_root(),
+            Some(I16F16::from_num(1.5))
+        );
+        assert_eq!(I16F16::from_num(-1).square_root(), None);
+        assert_eq!(U8F8::from_num(16).square_root(), Some(U8F8::from_num(4)));
+        assert_eq!(
+            <[U8F8; 0]>::default().root_std_dev(),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+}
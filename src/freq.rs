@@ -1,20 +1,65 @@
 use num_traits::ToPrimitive;
 
 use crate::error::DataType;
+use crate::helpers::is_nan;
+use crate::helpers::Float;
 use crate::helpers::MinMax;
 use crate::helpers::NumExt;
+use crate::ErrorContext;
 use crate::Result;
 use crate::StatsError;
+use crate::WithContext;
 
 /// Type alias for a tuple containing a frequency and a value.
 type Frequency<T> = (usize, T);
 
+/// Convert the frequency of the tuple at `index` to the type of the values,
+/// naming the `statistic` and the tuple in the error if it doesn't fit
+fn frequency_into_item<T>(
+    freq: usize,
+    statistic: &'static str,
+    index: usize,
+) -> core::result::Result<T, ErrorContext>
+where
+    T: NumExt,
+{
+    T::from_usize(freq).ok_or_else(|| {
+        ErrorContext::new(
+            statistic,
+            StatsError::CouldNotConvert {
+                from: DataType::Usize,
+                to: DataType::Item,
+            },
+        )
+        .at(index)
+    })
+}
+
+/// Sum the values multiplied by their frequencies, for `statistic`
+fn weighted_sum<F, T>(tuples: &F, statistic: &'static str) -> core::result::Result<T, ErrorContext>
+where
+    F: FrequencyStats<T> + ?Sized,
+    T: NumExt,
+{
+    let mut sum = T::zero();
+    for (index, (freq, val)) in tuples.items().enumerate() {
+        sum = sum + val * frequency_into_item(freq, statistic, index)?;
+    }
+    Ok(sum)
+}
+
 /// Similar to [`Stats`](crate::Stats), but for collections of tuples
-/// containing a frequency and a value.
-pub trait FrequencyStats<T>: IntoIterator<Item = Frequency<T>> + Clone
+/// containing a frequency and a value. Like [`Stats`](crate::Stats), the
+/// methods borrow the collection, so nothing is copied to calculate a statistic.
+pub trait FrequencyStats<T>
 where
     T: NumExt,
 {
+    /// Returns an iterator over clones of the tuples in the collection
+    fn items<'a>(&'a self) -> impl Iterator<Item = Frequency<T>> + 'a
+    where
+        T: 'a;
+
     /// Returns the total number of values in the collection.
     /// This is the sum of all frequencies.
     ///
@@ -25,7 +70,29 @@ where
     /// assert_eq!(vec.count(), 3);
     /// ```
     fn count(&self) -> usize {
-        self.clone().into_iter().map(|(freq, _)| freq).sum()
+        self.items().map(|(freq, _)| freq).sum()
+    }
+
+    /// Returns how many times `value` occurs in the collection.
+    /// This is the sum of the frequencies of every tuple whose value
+    /// is equal to `value`, so values that appear in more than one
+    /// tuple are counted correctly. Values that aren't equal to themselves,
+    /// such as `NaN`, count as the same value, like in [`crate::Stats::count_of`].
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::FrequencyStats;
+    /// let vec: Vec<(usize, i32)> = vec![(1, 1), (2, 2), (3, 1)];
+    /// assert_eq!(vec.count_of(1), 4);
+    /// assert_eq!(vec.count_of(3), 0);
+    /// let vec: Vec<(usize, f64)> = vec![(1, f64::NAN), (2, 1.0), (3, f64::NAN)];
+    /// assert_eq!(vec.count_of(f64::NAN), 4);
+    /// ```
+    fn count_of(&self, value: T) -> usize {
+        self.items()
+            .filter(|(_, val)| *val == value || (is_nan(val) && is_nan(&value)))
+            .map(|(freq, _)| freq)
+            .sum()
     }
 
     /// Count the items in the collection, returning
@@ -40,9 +107,9 @@ where
     }
 
     /// Count the items in the collection and convert the result
-    /// to [`Self::Item`](IntoIterator::Item). Return an error
+    /// to the type of the values, `T`. Return an error
     /// under the same conditions as [`crate::Stats::non_zero_count`],
-    /// or if the length could not be converted to [`Self::Item`](IntoIterator::Item)
+    /// or if the length could not be converted to the type of the values, `T`
     fn non_zero_count_into_item(&self) -> Result<T> {
         T::from_usize(self.non_zero_count()?).ok_or(StatsError::CouldNotConvert {
             from: DataType::Usize,
@@ -66,18 +133,27 @@ where
     /// ```
     ///
     /// # Errors
-    /// Returns an error if the frequency could not be converted to [`Self::Item`](IntoIterator::Item)
+    /// Returns an error if the frequency could not be converted to the type of the values, `T`
     fn sum(&self) -> Result<T> {
-        let mut sum = T::zero();
-        for (freq, val) in self.clone().into_iter() {
-            sum = sum
-                + val
-                    * T::from_usize(freq).ok_or(StatsError::CouldNotConvert {
-                        from: DataType::Usize,
-                        to: DataType::Item,
-                    })?;
-        }
-        Ok(sum)
+        Ok(self.sum_with_context()?)
+    }
+
+    /// Calculate the sum like [`FrequencyStats::sum`], but with the name of
+    /// the statistic and the index of the tuple whose frequency couldn't be
+    /// converted in the error
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::FrequencyStats;
+    /// let vec: Vec<(usize, u8)> = vec![(3, 1), (300, 2), (400, 3)];
+    /// let error = vec.sum_with_context().unwrap_err();
+    /// assert_eq!((error.statistic, error.index), ("sum", Some(1)));
+    /// ```
+    ///
+    /// # Errors
+    /// Errors under the same conditions as [`FrequencyStats::sum`]
+    fn sum_with_context(&self) -> core::result::Result<T, ErrorContext> {
+        weighted_sum(self, "sum")
     }
 
     /// Calculate the mean of the collection.
@@ -86,29 +162,53 @@ where
     /// This is the same as [`crate::Stats::mean`], but for collections of tuples
     /// containing a frequency and a value.
     fn mean(&self) -> Result<T> {
-        Ok(self.sum()? / self.non_zero_count_into_item()?)
+        Ok(self.mean_with_context()?)
+    }
+
+    /// Calculate the mean like [`FrequencyStats::mean`], but with the name of
+    /// the statistic in the error, and the index of the tuple whose frequency
+    /// couldn't be converted if that is what caused it
+    ///
+    /// # Errors
+    /// Errors under the same conditions as [`FrequencyStats::mean`]
+    fn mean_with_context(&self) -> core::result::Result<T, ErrorContext> {
+        Ok(weighted_sum(self, "mean")? / self.non_zero_count_into_item().context("mean")?)
     }
 
     /// Calculate the variance of the collection.
     /// See [`crate::Stats::variance`] or
     /// [Wikipedia](<https://en.wikipedia.org/wiki/Variance>) for more information.
     fn variance(&self) -> Result<T> {
-        let mean = self.mean()?;
+        Ok(self.variance_with_context()?)
+    }
+
+    /// Calculate the variance like [`FrequencyStats::variance`], but with the
+    /// name of the statistic in the error, and the index of the tuple whose
+    /// frequency couldn't be converted if that is what caused it
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::FrequencyStats;
+    /// let vec: Vec<(usize, i8)> = vec![(1, 1), (200, 2)];
+    /// let error = vec.variance_with_context().unwrap_err();
+    /// assert_eq!((error.statistic, error.index), ("variance", Some(1)));
+    /// ```
+    ///
+    /// # Errors
+    /// Errors under the same conditions as [`FrequencyStats::variance`]
+    fn variance_with_context(&self) -> core::result::Result<T, ErrorContext> {
+        let total = weighted_sum(self, "variance")?;
+        let count = self.non_zero_count_into_item().context("variance")?;
+        let mean = total / count.clone();
         let mut sum = T::zero();
 
         // tried using .map() and .sum() but it didn't work
-        for (freq, val) in self.clone().into_iter() {
-            let diff = val - mean;
+        for (index, (freq, val)) in self.items().enumerate() {
+            let diff = val - mean.clone();
             // don't use += as that would require T to implement AddAssign
-            sum = sum
-                + diff
-                    * diff
-                    * T::from_usize(freq).ok_or(StatsError::CouldNotConvert {
-                        from: DataType::Usize,
-                        to: DataType::Item,
-                    })?;
+            sum = sum + diff.clone() * diff * frequency_into_item(freq, "variance", index)?;
         }
-        Ok(sum / self.non_zero_count_into_item()?)
+        Ok(sum / count)
     }
 
     /// Calculate the standard deviation of the collection.
@@ -118,15 +218,12 @@ where
     where
         T: ToPrimitive,
     {
-        T::from_f64(
-            self.variance()?
-                .to_f64()
-                .ok_or(StatsError::CouldNotConvert {
-                    from: DataType::Item,
-                    to: DataType::F64,
-                })?
-                .sqrt(),
-        )
+        T::from_f64(Float::sqrt(self.variance()?.to_f64().ok_or(
+            StatsError::CouldNotConvert {
+                from: DataType::Item,
+                to: DataType::F64,
+            },
+        )?))
         .ok_or(StatsError::CouldNotConvert {
             from: DataType::F64,
             to: DataType::Item,
@@ -138,8 +235,7 @@ where
     where
         T: MinMax,
     {
-        self.clone()
-            .into_iter()
+        self.items()
             .map(|(_, val)| val)
             .reduce(T::min)
             .ok_or(StatsError::EmptyCollection)
@@ -150,8 +246,7 @@ where
     where
         T: MinMax,
     {
-        self.clone()
-            .into_iter()
+        self.items()
             .map(|(_, val)| val)
             .reduce(T::max)
             .ok_or(StatsError::EmptyCollection)
@@ -170,31 +265,87 @@ where
     /// Return the most frequently occurring value in the collection,
     /// which is the one with the highest frequency
     fn mode(&self) -> Result<T> {
-        /*
-            tried the following
-            self
-                .clone()
-                .into_iter()
-                .max_by_key(|(freq, _)| freq) <- complains about lifetimes
-                .map(|(_, val)| val)
-                .ok_or(StatsError::EmptyCollection)
-        */
         let mut mode = (0, T::zero());
         self.non_zero_count()?;
-        for (freq, val) in self.clone() {
+        for (freq, val) in self.items() {
             if freq >= mode.0 {
                 mode = (freq, val);
             }
         }
         Ok(mode.1)
     }
+
+    /// Returns the empirical distribution function at `x`, which is the
+    /// fraction of the values that are less than or equal to it, in one pass
+    /// over the tuples rather than over every value they count
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::FrequencyStats;
+    /// // the number of people in each household
+    /// let households: Vec<(usize, u32)> = vec![(30, 1), (45, 2), (15, 3), (10, 4)];
+    /// assert_eq!(households.cdf(2), Ok(0.75));
+    /// assert_eq!(households.cdf(0), Ok(0.0));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the total frequency is 0
+    fn cdf(&self, x: T) -> Result<f64>
+    where
+        T: PartialOrd,
+    {
+        let total = self.non_zero_count()?;
+        let at_most: usize = self
+            .items()
+            .filter(|(_, val)| *val <= x)
+            .map(|(freq, _)| freq)
+            .sum();
+        Ok(at_most as f64 / total as f64)
+    }
+
+    /// Returns the survival function at `x`, which is the fraction of the
+    /// values that are greater than it, so it is 1 minus
+    /// [`FrequencyStats::cdf`] when none of the values are `NaN`
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::FrequencyStats;
+    /// let households: Vec<(usize, u32)> = vec![(30, 1), (45, 2), (15, 3), (10, 4)];
+    /// assert_eq!(households.survival(2), Ok(0.25));
+    /// assert_eq!(households.survival(4), Ok(0.0));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the total frequency is 0
+    fn survival(&self, x: T) -> Result<f64>
+    where
+        T: PartialOrd,
+    {
+        let total = self.non_zero_count()?;
+        let above: usize = self
+            .items()
+            .filter(|(_, val)| *val > x)
+            .map(|(freq, _)| freq)
+            .sum();
+        Ok(above as f64 / total as f64)
+    }
 }
 
-impl<T, I> FrequencyStats<T> for I
+/// Blanket implementation for all types whose references implement
+/// [`IntoIterator`] over references to the tuples, such as `Vec<(usize, T)>`,
+/// arrays and slices of tuples, and [`FrequencyTable`](crate::FrequencyTable)
+impl<C, T> FrequencyStats<T> for C
 where
-    I: IntoIterator<Item = Frequency<T>> + Clone,
+    C: ?Sized,
+    for<'a> &'a C: IntoIterator<Item = &'a Frequency<T>>,
     T: NumExt,
 {
+    fn items<'a>(&'a self) -> impl Iterator<Item = Frequency<T>> + 'a
+    where
+        T: 'a,
+    {
+        self.into_iter().cloned()
+    }
 }
 
 #[cfg(test)]
@@ -202,8 +353,8 @@ mod tests {
     use super::*;
 
     extern crate std;
-    use std::prelude::rust_2021::*;
     use std::vec;
+    use std::vec::Vec;
 
     use approx::assert_relative_eq;
 
@@ -213,12 +364,49 @@ mod tests {
         assert_eq!(vec.non_zero_count(), Ok(3));
     }
 
+    #[test]
+    fn test_count_of() {
+        let vec: Vec<(usize, i32)> = vec![(1, 1), (2, 2), (3, 1)];
+        assert_eq!(vec.count_of(1), 4);
+        assert_eq!(vec.count_of(2), 2);
+        assert_eq!(vec.count_of(0), 0);
+        let vec: Vec<(usize, f64)> = vec![(1, f64::NAN), (2, 1.0), (3, f64::NAN)];
+        assert_eq!(vec.count_of(f64::NAN), 4);
+        assert_eq!(vec.count_of(1.0), 2);
+    }
+
+    #[test]
+    fn test_borrowed_collections() {
+        let array = [(1, 1), (2, 2)];
+        assert_eq!(array.sum(), Ok(5));
+        assert_eq!(array[..1].count(), 1);
+        fn total(tuples: &[(usize, i32)]) -> Result<i32> {
+            tuples.sum()
+        }
+        assert_eq!(total(&array), Ok(5));
+    }
+
     #[test]
     fn test_sum() {
         let vec: Vec<(usize, i32)> = vec![(1, 1), (2, 2)];
         assert_eq!(vec.sum(), Ok(5));
     }
 
+    #[test]
+    fn test_error_context() {
+        let vec: Vec<(usize, i8)> = vec![(1, 1), (200, 2), (300, 3)];
+        let context = vec.variance_with_context().unwrap_err();
+        assert_eq!(context.statistic, "variance");
+        assert_eq!(context.index, Some(1));
+        assert_eq!(vec.variance(), Err(context.error));
+        assert_eq!(vec.sum_with_context().unwrap_err().index, Some(1));
+        let fine: Vec<(usize, i8)> = vec![(100, 0), (100, 1)];
+        assert_eq!(fine.sum_with_context(), Ok(100));
+        // the total count doesn't fit, but no single frequency is to blame
+        let context = fine.mean_with_context().unwrap_err();
+        assert_eq!((context.statistic, context.index), ("mean", None));
+    }
+
     #[test]
     fn test_mean() {
         let vec: Vec<(usize, i32)> = vec![(1, 1), (2, 2)];
@@ -237,6 +425,17 @@ mod tests {
         assert_relative_eq!(vec.variance().unwrap(), 2.0 / 9.0);
     }
 
+    #[test]
+    fn test_cdf_and_survival() {
+        let vec: Vec<(usize, f64)> = vec![(1, 0.5), (2, f64::NAN), (3, 1.5), (2, 0.5)];
+        assert_eq!(vec.cdf(0.5), Ok(3.0 / 8.0));
+        assert_eq!(vec.survival(0.5), Ok(3.0 / 8.0));
+        assert_eq!(vec.cdf(10.0), Ok(6.0 / 8.0));
+        let empty: Vec<(usize, f64)> = vec![(0, 1.0)];
+        assert_eq!(empty.cdf(1.0), Err(StatsError::EmptyCollection));
+        assert_eq!(empty.survival(1.0), Err(StatsError::EmptyCollection));
+    }
+
     #[test]
     fn test_std_dev() {
         let vec: Vec<(usize, i32)> = vec![(1, 1), (2, 2)];
@@ -0,0 +1,122 @@
+use half::{bf16, f16};
+
+use crate::Stats;
+
+/// A half precision floating point type, which converts to [`f32`] exactly
+pub trait HalfFloat: Copy + Into<f32> + private::Sealed {}
+
+impl HalfFloat for f16 {}
+impl HalfFloat for bf16 {}
+
+mod private {
+    pub trait Sealed {}
+
+    impl Sealed for half::f16 {}
+    impl Sealed for half::bf16 {}
+}
+
+/// An extension trait for collections of half precision floats, such as
+/// tensors of [`f16`]s or [`bf16`]s, to calculate statistics of them in
+/// [`f32`] without converting the whole collection first. Half precision
+/// only has a few significant digits, so summing in it loses most of them.
+///
+/// It is implemented for the same collections as [`Stats`], when their
+/// items are [`f16`]s or [`bf16`]s.
+///
+/// # Examples
+/// ```
+/// use half::f16;
+/// use stats_traits::{HalfStats, Stats};
+/// let tensor = vec![f16::from_f32(0.5), f16::from_f32(1.5), f16::from_f32(4.0)];
+/// assert_eq!(tensor.widen().mean(), Ok(2.0));
+/// assert_eq!(tensor.widen().max(), Ok(4.0));
+/// ```
+pub trait HalfStats<H>
+where
+    H: HalfFloat,
+{
+    /// Returns the collection as a [`Stats`] of [`f32`]s, converting each
+    /// item as it is used, so every method of [`Stats`] can be used on it
+    fn widen(&self) -> Widened<'_, Self> {
+        Widened { inner: self }
+    }
+}
+
+/// Blanket implementation for all collections of half precision floats
+/// that can be iterated over by reference, like [`Stats`]
+impl<T, H> HalfStats<H> for T
+where
+    T: ?Sized,
+    for<'a> &'a T: IntoIterator<Item = &'a H>,
+    H: HalfFloat + 'static,
+{
+}
+
+/// A collection of half precision floats seen as [`f32`]s, created by
+/// [`HalfStats::widen`]. It implements [`Stats`].
+#[derive(Debug)]
+pub struct Widened<'a, T: ?Sized> {
+    inner: &'a T,
+}
+
+impl<T: ?Sized> Clone for Widened<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized> Copy for Widened<'_, T> {}
+
+impl<'a, T: ?Sized> Widened<'a, T> {
+    /// Returns the collection of half precision floats
+    pub fn get(&self) -> &'a T {
+        self.inner
+    }
+}
+
+impl<T, H> Stats for Widened<'_, T>
+where
+    T: ?Sized,
+    for<'a> &'a T: IntoIterator<Item = &'a H>,
+    H: HalfFloat + 'static,
+{
+    type Item = f32;
+
+    fn items<'a>(&'a self) -> impl Iterator<Item = f32> + 'a
+    where
+        Self::Item: 'a,
+    {
+        self.inner.into_iter().map(|&x| x.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StatsError;
+
+    #[test]
+    fn test_accumulates_in_f32() {
+        // 2048 is where f16 stops representing odd integers, so summing
+        // in f16 would get stuck there
+        let ones = [f16::ONE; 3000];
+        assert_eq!(
+            ones.iter().fold(f16::ZERO, |sum, &x| sum + x),
+            f16::from_f32(2048.0)
+        );
+        assert_eq!(ones.widen().sum(), 3000.0);
+        assert_eq!(ones.widen().mean(), Ok(1.0));
+        assert_eq!(ones.widen().get(), &ones);
+    }
+
+    #[test]
+    fn test_bf16() {
+        let values = [1.0, 2.0, 3.0, 4.0].map(bf16::from_f32);
+        assert_eq!(values.widen().variance(), Ok(1.25));
+        assert_eq!(Stats::min(&values.widen()), Ok(1.0));
+        assert_eq!(
+            <[bf16; 0]>::default().widen().mean(),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+}
@@ -0,0 +1,523 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::checkpoint;
+use crate::checkpoint::Reader;
+use crate::checkpoint::Writer;
+use crate::CheckpointError;
+use crate::FrequencyStats;
+use crate::Result;
+use crate::StatsError;
+
+/// A histogram of `u64` values with a bounded relative error, in the style of
+/// [HdrHistogram](<http://hdrhistogram.org/>).
+///
+/// Values are counted in buckets whose width grows with the size of the value,
+/// so every recorded value can be told apart from values that differ from it in
+/// the first `significant_digits` decimal digits. This keeps the memory used
+/// small across a huge range of values, which makes it the usual choice for
+/// recording latencies. The histogram grows as larger values are recorded.
+///
+/// It implements [`FrequencyStats`](crate::FrequencyStats) with `(count, value)`
+/// tuples for each non-empty bucket, which `&HdrHistogram` also iterates over.
+/// Each bucket is represented by the value in the middle of it.
+///
+/// # Examples
+/// ```
+/// use stats_traits::{FrequencyStats, HdrHistogram};
+/// let mut histogram = HdrHistogram::new(3);
+/// for latency in 1..=1000 {
+///     histogram.record(latency);
+/// }
+/// assert_eq!(histogram.value_at_percentile(50.0), Ok(500));
+/// assert_eq!(histogram.value_at_percentile(99.0), Ok(990));
+/// assert_eq!(histogram.count_between(100, 199), 100);
+/// assert_eq!(histogram.mean(), Ok(500));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(try_from = "HdrHistogramRepr", into = "HdrHistogramRepr")
+)]
+pub struct HdrHistogram {
+    significant_digits: u8,
+    /// Base 2 logarithm of the number of sub-buckets in each bucket
+    sub_bucket_magnitude: u32,
+    counts: Vec<u64>,
+    total: u64,
+}
+
+impl HdrHistogram {
+    /// Create an empty histogram that keeps `significant_digits`
+    /// decimal digits of precision for every value
+    ///
+    /// # Panics
+    /// Panics if `significant_digits` is not between 1 and 5
+    pub fn new(significant_digits: u8) -> Self {
+        assert!(
+            (1..=5).contains(&significant_digits),
+            "significant digits must be between 1 and 5"
+        );
+        // values up to this have a bucket each
+        let single_unit_resolution = 2 * 10_u64.pow(u32::from(significant_digits));
+        let sub_bucket_magnitude = single_unit_resolution.next_power_of_two().trailing_zeros();
+        Self {
+            significant_digits,
+            sub_bucket_magnitude,
+            counts: Vec::new(),
+            total: 0,
+        }
+    }
+
+    /// Returns the number of significant digits the histogram was created with
+    pub fn significant_digits(&self) -> u8 {
+        self.significant_digits
+    }
+
+    /// Returns the number of values that have been recorded
+    pub fn count(&self) -> u64 {
+        self.total
+    }
+
+    /// Returns the number of sub-buckets in each bucket
+    fn sub_bucket_count(&self) -> u64 {
+        1 << self.sub_bucket_magnitude
+    }
+
+    /// Returns the index into `counts` of the bucket containing `value`
+    fn index_of(&self, value: u64) -> usize {
+        let mask = self.sub_bucket_count() - 1;
+        let bucket = (64 - self.sub_bucket_magnitude) - (value | mask).leading_zeros();
+        let sub_bucket = value >> bucket;
+        let half_count = self.sub_bucket_count() / 2;
+        (((u64::from(bucket) + 1) << (self.sub_bucket_magnitude - 1)) + sub_bucket - half_count)
+            as usize
+    }
+
+    /// Returns the smallest value that falls in the bucket at `index`
+    fn lowest_value_at(&self, index: usize) -> u64 {
+        let half_magnitude = self.sub_bucket_magnitude - 1;
+        let half_count = self.sub_bucket_count() / 2;
+        let index = index as u64;
+        let mut bucket = (index >> half_magnitude) as i64 - 1;
+        let mut sub_bucket = (index & (half_count - 1)) + half_count;
+        if bucket < 0 {
+            sub_bucket -= half_count;
+            bucket = 0;
+        }
+        sub_bucket << bucket
+    }
+
+    /// Returns how many values share the bucket at `index`
+    fn bucket_width_at(&self, index: usize) -> u64 {
+        let half_magnitude = self.sub_bucket_magnitude - 1;
+        let bucket = ((index as u64) >> half_magnitude).saturating_sub(1);
+        1 << bucket
+    }
+
+    /// Returns the largest value that falls in the bucket at `index`
+    fn highest_value_at(&self, index: usize) -> u64 {
+        self.lowest_value_at(index)
+            .saturating_add(self.bucket_width_at(index) - 1)
+    }
+
+    /// Returns the value in the middle of the bucket at `index`
+    fn middle_value_at(&self, index: usize) -> u64 {
+        self.lowest_value_at(index)
+            .saturating_add(self.bucket_width_at(index) / 2)
+    }
+
+    /// Record one occurrence of `value`
+    pub fn record(&mut self, value: u64) {
+        self.record_n(value, 1);
+    }
+
+    /// Record `count` occurrences of `value`
+    pub fn record_n(&mut self, value: u64, count: u64) {
+        if count == 0 {
+            return;
+        }
+        let index = self.index_of(value);
+        if index >= self.counts.len() {
+            self.counts.resize(index + 1, 0);
+        }
+        self.counts[index] += count;
+        self.total += count;
+    }
+
+    /// Add every value recorded in `other` to `self`. If the histograms
+    /// have different precisions, the values of `other` are recorded
+    /// at the precision of `self`.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::HdrHistogram;
+    /// let mut a = HdrHistogram::new(2);
+    /// let mut b = HdrHistogram::new(2);
+    /// a.record(5);
+    /// b.record(5);
+    /// b.record(7);
+    /// a.merge(&b);
+    /// assert_eq!(a.count(), 3);
+    /// assert_eq!(a.count_between(5, 5), 2);
+    /// ```
+    pub fn merge(&mut self, other: &Self) {
+        if self.sub_bucket_magnitude == other.sub_bucket_magnitude {
+            if other.counts.len() > self.counts.len() {
+                self.counts.resize(other.counts.len(), 0);
+            }
+            for (count, other_count) in self.counts.iter_mut().zip(&other.counts) {
+                *count += other_count;
+            }
+            self.total += other.total;
+        } else {
+            for (index, &count) in other.counts.iter().enumerate() {
+                self.record_n(other.middle_value_at(index), count);
+            }
+        }
+    }
+
+    /// Returns the smallest value recorded, to the precision of the histogram
+    ///
+    /// # Errors
+    /// Returns an error if no values have been recorded
+    pub fn min(&self) -> Result<u64> {
+        self.counts
+            .iter()
+            .position(|&count| count > 0)
+            .map(|index| self.lowest_value_at(index))
+            .ok_or(StatsError::EmptyCollection)
+    }
+
+    /// Returns the largest value recorded, to the precision of the histogram
+    ///
+    /// # Errors
+    /// Returns an error if no values have been recorded
+    pub fn max(&self) -> Result<u64> {
+        self.counts
+            .iter()
+            .rposition(|&count| count > 0)
+            .map(|index| self.highest_value_at(index))
+            .ok_or(StatsError::EmptyCollection)
+    }
+
+    /// Returns the value that `percentile` percent of the recorded values are
+    /// less than or equal to, where `percentile` is between 0 and 100. The
+    /// value is the largest one that falls in the same bucket as the answer.
+    ///
+    /// # Errors
+    /// Returns an error if no values have been recorded
+    ///
+    /// # Panics
+    /// Panics if `percentile` is not between 0 and 100
+    pub fn value_at_percentile(&self, percentile: f64) -> Result<u64> {
+        assert!(
+            (0.0..=100.0).contains(&percentile),
+            "percentile must be between 0 and 100"
+        );
+        if self.total == 0 {
+            return Err(StatsError::EmptyCollection);
+        }
+        if percentile == 0.0 {
+            return self.min();
+        }
+        // rounded to the nearest count, so floating point error can't skip a bucket
+        let target = ((percentile / 100.0 * self.total as f64 + 0.5) as u64).max(1);
+        let mut seen = 0;
+        for (index, &count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= target {
+                return Ok(self.highest_value_at(index));
+            }
+        }
+        self.max()
+    }
+
+    /// Returns how many recorded values are between `low` and `high` inclusive,
+    /// to the precision of the histogram
+    pub fn count_between(&self, low: u64, high: u64) -> u64 {
+        if low > high || self.counts.is_empty() {
+            return 0;
+        }
+        let last = self.counts.len() - 1;
+        let start = self.index_of(low);
+        let end = self.index_of(high).min(last);
+        if start > last {
+            return 0;
+        }
+        self.counts[start..=end].iter().sum()
+    }
+
+    /// Returns an iterator over `(count, value)` tuples for every bucket
+    /// that has values recorded in it, from the smallest value to the largest.
+    /// Each bucket is represented by the value in the middle of it.
+    pub fn iter_recorded(&self) -> HdrIter<'_> {
+        HdrIter {
+            histogram: self,
+            index: 0,
+        }
+    }
+
+    /// Save the counts of the histogram as a compact checkpoint, which
+    /// [`HdrHistogram::from_bytes`] restores, so a long-running aggregation
+    /// can survive a restart or be sent to another machine. Only the buckets
+    /// with values recorded in them are stored.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::HdrHistogram;
+    /// let mut histogram = HdrHistogram::new(3);
+    /// histogram.record_n(1_000_000, 5);
+    /// histogram.record(12);
+    /// let checkpoint = histogram.to_bytes();
+    /// assert_eq!(checkpoint.len(), 43);
+    /// assert_eq!(HdrHistogram::from_bytes(&checkpoint), Ok(histogram));
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let recorded = self.counts.iter().filter(|&&count| count > 0).count();
+        let mut bytes = vec![0; 11 + 16 * recorded];
+        let mut writer = Writer::new(&mut bytes, checkpoint::HDR_HISTOGRAM);
+        writer.put_u8(self.significant_digits);
+        writer.put_usize(recorded);
+        for (index, &count) in self.counts.iter().enumerate() {
+            if count > 0 {
+                writer.put_usize(index);
+                writer.put_u64(count);
+            }
+        }
+        bytes
+    }
+
+    /// Restore a histogram from a checkpoint written by [`HdrHistogram::to_bytes`]
+    ///
+    /// # Errors
+    /// Returns an error if `bytes` isn't a checkpoint of an `HdrHistogram`
+    /// in a version of the format this version of the crate can read
+    pub fn from_bytes(bytes: &[u8]) -> core::result::Result<Self, CheckpointError> {
+        let mut reader = Reader::new(bytes, checkpoint::HDR_HISTOGRAM)?;
+        let significant_digits = reader.get_u8()?;
+        if !(1..=5).contains(&significant_digits) {
+            return Err(CheckpointError::Invalid);
+        }
+        let mut histogram = Self::new(significant_digits);
+        for _ in 0..reader.get_usize()? {
+            histogram.restore_bucket(reader.get_usize()?, reader.get_u64()?)?;
+        }
+        reader.finish()?;
+        Ok(histogram)
+    }
+
+    /// Restore the `count` of the bucket at `index`, after the buckets
+    /// before it have been restored
+    fn restore_bucket(
+        &mut self,
+        index: usize,
+        count: u64,
+    ) -> core::result::Result<(), CheckpointError> {
+        // the buckets are stored in order, and only if they have values
+        if index > self.index_of(u64::MAX) || index < self.counts.len() || count == 0 {
+            return Err(CheckpointError::Invalid);
+        }
+        self.counts.resize(index + 1, 0);
+        self.counts[index] = count;
+        self.total = self
+            .total
+            .checked_add(count)
+            .ok_or(CheckpointError::Invalid)?;
+        Ok(())
+    }
+}
+
+/// The state of an [`HdrHistogram`] that it is serialized as, which like
+/// its checkpoints only holds the `(index, count)` pairs of the buckets with
+/// values recorded in them
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct HdrHistogramRepr {
+    significant_digits: u8,
+    buckets: Vec<(usize, u64)>,
+}
+
+#[cfg(feature = "serde")]
+impl From<HdrHistogram> for HdrHistogramRepr {
+    fn from(histogram: HdrHistogram) -> Self {
+        let buckets = histogram
+            .counts
+            .iter()
+            .enumerate()
+            .filter(|&(_, &count)| count > 0)
+            .map(|(index, &count)| (index, count))
+            .collect();
+        Self {
+            significant_digits: histogram.significant_digits,
+            buckets,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<HdrHistogramRepr> for HdrHistogram {
+    type Error = CheckpointError;
+
+    fn try_from(repr: HdrHistogramRepr) -> core::result::Result<Self, Self::Error> {
+        if !(1..=5).contains(&repr.significant_digits) {
+            return Err(CheckpointError::Invalid);
+        }
+        let mut histogram = Self::new(repr.significant_digits);
+        for (index, count) in repr.buckets {
+            histogram.restore_bucket(index, count)?;
+        }
+        Ok(histogram)
+    }
+}
+
+/// Iterator over the non-empty buckets of an [`HdrHistogram`],
+/// returned by [`HdrHistogram::iter_recorded`]
+#[derive(Debug, Clone)]
+pub struct HdrIter<'a> {
+    histogram: &'a HdrHistogram,
+    index: usize,
+}
+
+impl Iterator for HdrIter<'_> {
+    type Item = (usize, u64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(&count) = self.histogram.counts.get(self.index) {
+            let index = self.index;
+            self.index += 1;
+            if count > 0 {
+                return Some((count as usize, self.histogram.middle_value_at(index)));
+            }
+        }
+        None
+    }
+}
+
+impl FrequencyStats<u64> for HdrHistogram {
+    fn items<'a>(&'a self) -> impl Iterator<Item = (usize, u64)> + 'a
+    where
+        u64: 'a,
+    {
+        self.iter_recorded()
+    }
+}
+
+impl<'a> IntoIterator for &'a HdrHistogram {
+    type Item = (usize, u64);
+    type IntoIter = HdrIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_recorded()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FrequencyStats;
+
+    #[test]
+    fn test_checkpoint() {
+        let mut histogram = HdrHistogram::new(2);
+        for value in [0, 1, 99, 100, 101, 12_345, u64::MAX] {
+            histogram.record_n(value, value % 7 + 1);
+        }
+        let checkpoint = histogram.to_bytes();
+        assert_eq!(HdrHistogram::from_bytes(&checkpoint), Ok(histogram));
+        let empty = HdrHistogram::new(5);
+        assert_eq!(HdrHistogram::from_bytes(&empty.to_bytes()), Ok(empty));
+        assert_eq!(
+            crate::TDigest::from_bytes(&checkpoint),
+            Err(CheckpointError::WrongType)
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let mut histogram = HdrHistogram::new(2);
+        histogram.record_n(12, 3);
+        histogram.record(u64::MAX);
+        let json = serde_json::to_string(&histogram).unwrap();
+        assert_eq!(
+            serde_json::from_str::<HdrHistogram>(&json).unwrap(),
+            histogram
+        );
+        let empty = HdrHistogram::new(5);
+        let json = serde_json::to_string(&empty).unwrap();
+        assert_eq!(json, r#"{"significant_digits":5,"buckets":[]}"#);
+        for tampered in [
+            r#"{"significant_digits":9,"buckets":[]}"#,
+            r#"{"significant_digits":2,"buckets":[[5,1],[3,1]]}"#,
+            r#"{"significant_digits":2,"buckets":[[5,0]]}"#,
+            r#"{"significant_digits":2,"buckets":[[100000,1]]}"#,
+            r#"{"significant_digits":2,"buckets":[[1,18446744073709551615],[2,1]]}"#,
+        ] {
+            assert!(serde_json::from_str::<HdrHistogram>(tampered).is_err());
+        }
+    }
+
+    #[test]
+    fn test_empty() {
+        let histogram = HdrHistogram::new(3);
+        assert_eq!(histogram.min(), Err(StatsError::EmptyCollection));
+        assert_eq!(
+            histogram.value_at_percentile(50.0),
+            Err(StatsError::EmptyCollection)
+        );
+        assert_eq!(histogram.count_between(0, 100), 0);
+    }
+
+    #[test]
+    fn test_small_values_exact() {
+        let mut histogram = HdrHistogram::new(3);
+        for value in 0..2000 {
+            histogram.record(value);
+        }
+        assert_eq!(histogram.min(), Ok(0));
+        assert_eq!(histogram.max(), Ok(1999));
+        assert_eq!(histogram.value_at_percentile(50.0), Ok(999));
+        assert_eq!(histogram.count_between(10, 19), 10);
+    }
+
+    #[test]
+    fn test_relative_error() {
+        let mut histogram = HdrHistogram::new(3);
+        let values = [1_u64, 1_234, 98_765, 12_345_678, 9_876_543_210, u64::MAX];
+        for value in values {
+            histogram.record(value);
+        }
+        for (i, &value) in values.iter().enumerate() {
+            let percentile = (i + 1) as f64 / values.len() as f64 * 100.0;
+            let estimate = histogram.value_at_percentile(percentile).unwrap();
+            assert!(estimate >= value);
+            assert!((estimate - value) as f64 <= value as f64 / 1000.0);
+        }
+        assert_eq!(histogram.max(), Ok(u64::MAX));
+    }
+
+    #[test]
+    fn test_merge_different_precision() {
+        let mut a = HdrHistogram::new(3);
+        let mut b = HdrHistogram::new(1);
+        a.record(100);
+        b.record_n(100, 4);
+        a.merge(&b);
+        assert_eq!(a.count(), 5);
+        // with one significant digit 100 shares a bucket with 101 to 103
+        assert_eq!(a.count_between(100, 100), 1);
+        assert_eq!(a.count_between(100, 103), 5);
+    }
+
+    #[test]
+    fn test_frequency_stats() {
+        let mut histogram = HdrHistogram::new(2);
+        histogram.record_n(10, 3);
+        histogram.record(20);
+        assert_eq!(histogram.sum(), Ok(50));
+        assert_eq!(histogram.mean(), Ok(12));
+        assert_eq!(histogram.mode(), Ok(10));
+    }
+}
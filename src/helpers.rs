@@ -1,12 +1,34 @@
+#[cfg(feature = "alloc")]
+use alloc::vec;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::convert::Infallible;
 use core::iter::Sum;
 
-use num_traits::{FromPrimitive, Num};
+/// The floating point functions, such as `sqrt` and `ln`, which are called
+/// through the trait (`Float::sqrt(x)`) so that they work without `std`
+pub(crate) use num_traits::Float;
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::error::DataType;
+#[cfg(feature = "alloc")]
+use crate::RankTies;
+use crate::Result;
+use crate::StatsError;
 
 /// Trait for a number-like type that we can calculate statistics on.
-pub trait NumExt: Num + FromPrimitive + Copy + Sum {}
-impl<T> NumExt for T where T: Num + FromPrimitive + Copy + Sum {}
+/// It only needs to be [`Clone`], so arbitrary precision numbers like
+/// `num_bigint::BigInt` work as well as the primitive types.
+pub trait NumExt: Num + FromPrimitive + Clone + Sum {}
+impl<T> NumExt for T where T: Num + FromPrimitive + Clone + Sum {}
 
-/// Trait for types that support returning whether one is greater than the otehr
+/// Trait for types that support returning the smaller or larger of two values.
+/// It is implemented for every [`PartialOrd`] type, so integers, floats and
+/// newtypes around them all work. Values that aren't comparable with
+/// themselves, such as `NaN`, are ignored, so the result is only `NaN` if
+/// both values are, like [`f64::min`] and [`f64::max`]. Use [`TotalOrder`]
+/// to order `NaN`s too.
 pub trait MinMax {
     /// Return the smaller of `self` and `other`
     fn min(self, other: Self) -> Self;
@@ -15,37 +37,337 @@ pub trait MinMax {
     fn max(self, other: Self) -> Self;
 }
 
-macro_rules! impl_min_max_using_ord {
+impl<T: PartialOrd> MinMax for T {
+    fn min(self, other: Self) -> Self {
+        if other < self || is_nan(&self) {
+            other
+        } else {
+            self
+        }
+    }
+
+    fn max(self, other: Self) -> Self {
+        if other > self || is_nan(&self) {
+            other
+        } else {
+            self
+        }
+    }
+}
+
+/// Trait for types with a total order, including every float. For floats
+/// this is [`f64::total_cmp`], which puts negative `NaN`s before every other
+/// value and positive `NaN`s (such as [`f64::NAN`]) after every other value.
+pub trait TotalOrder {
+    /// Compare `self` and `other` in the total order
+    fn total_cmp(&self, other: &Self) -> Ordering;
+}
+
+/// Trait for types that can calculate their own square root, for
+/// [`Stats::root_std_dev`](crate::Stats::root_std_dev), so that a standard
+/// deviation can be found without converting the items to [`f64`] and back
+pub trait SquareRoot: Sized {
+    /// Returns the square root of `self`, rounded down for integers, or
+    /// `None` if it can't be represented in this type, like the square root
+    /// of a negative number
+    fn square_root(&self) -> Option<Self>;
+}
+
+/// Returns the index of the bin `x` falls in, or `None` if it is outside the edges.
+/// Bins include their left edge, and the last bin includes its right edge too.
+#[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+pub(crate) fn bin_of(edges: &[f64], x: f64) -> Option<usize> {
+    let last = edges.len() - 1;
+    if !(edges[0]..=edges[last]).contains(&x) {
+        return None;
+    }
+    // index of the first edge greater than x, so x is in the bin before it
+    Some(Ord::min(edges.partition_point(|&edge| edge <= x), last) - 1)
+}
+
+/// Returns the edges of a single bin around `x`, for when every value is
+/// `x` and so has no range to split up. The bin goes from half below `x` to
+/// half above it, or wider for large `x`, where adding a half wouldn't
+/// change it, but never beyond the largest finite [`f64`].
+#[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+pub(crate) fn range_around(x: f64) -> (f64, f64) {
+    let half = f64::max(0.5, x.abs() * 1e-6);
+    let (low, high) = (x - half, x + half);
+    (
+        if low.is_finite() { low } else { x },
+        if high.is_finite() { high } else { x },
+    )
+}
+
+/// Convert an item to an [`f64`], returning
+/// [`StatsError::CouldNotConvert`] if that isn't possible
+pub(crate) fn item_to_f64<T: ToPrimitive>(x: T) -> Result<f64> {
+    x.to_f64().ok_or(StatsError::CouldNotConvert {
+        from: DataType::Item,
+        to: DataType::F64,
+    })
+}
+
+/// Returns `true` if `x` is not equal to itself, which
+/// is only the case for `NaN` among the number types
+#[allow(clippy::eq_op)]
+pub(crate) fn is_nan<T: PartialEq>(x: &T) -> bool {
+    x != x
+}
+
+/// Compare two values that are only [`PartialOrd`], treating any value
+/// that is not equal to itself (such as `NaN`) as larger than every other
+/// value, so that the result is always a consistent ordering
+pub(crate) fn partial_cmp_nan_last<T: PartialOrd>(a: &T, b: &T) -> Ordering {
+    match a.partial_cmp(b) {
+        Some(ordering) => ordering,
+        #[allow(clippy::eq_op)]
+        None => match (a != a, b != b) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Greater,
+            _ => Ordering::Less,
+        },
+    }
+}
+
+/// Returns the `p`-quantile of a sorted, non-empty slice, interpolating
+/// linearly between the two closest items (type 7 in the taxonomy
+/// of Hyndman and Fan, which is also the default of numpy and R)
+pub(crate) fn quantile_of_sorted(sorted: &[f64], p: f64) -> f64 {
+    let Ok(quantile) = quantile_by(sorted.len(), p, |n| Ok::<_, Infallible>(sorted[n]));
+    quantile
+}
+
+/// Returns the `p`-quantile of `len` sorted items like [`quantile_of_sorted`],
+/// where `item(n)` returns the `n`th of them, so that only the one or two
+/// items that are needed are looked up and converted
+pub(crate) fn quantile_by<E>(
+    len: usize,
+    p: f64,
+    mut item: impl FnMut(usize) -> core::result::Result<f64, E>,
+) -> core::result::Result<f64, E> {
+    let position = p * (len - 1) as f64;
+    let lower = position as usize;
+    let fraction = position - lower as f64;
+    let lower_value = item(lower)?;
+    if fraction == 0.0 {
+        return Ok(lower_value);
+    }
+    let upper_value = item(Ord::min(lower + 1, len - 1))?;
+    Ok(interpolate(lower_value, upper_value, fraction))
+}
+
+/// Returns the value `fraction` of the way from `lower` to `upper`, which
+/// is `lower` itself when `fraction` is 0 or the two are the same, even if
+/// they are infinite
+pub(crate) fn interpolate(lower: f64, upper: f64, fraction: f64) -> f64 {
+    if fraction == 0.0 || lower == upper {
+        lower
+    } else if lower.is_infinite() || upper.is_infinite() {
+        // an infinity outweighs a finite neighbour, and infinities of
+        // opposite signs give NaN
+        lower + upper
+    } else {
+        let difference = upper - lower;
+        if difference.is_infinite() {
+            // finite values of opposite signs so large that the distance
+            // between them overflows, but weighting each of them can't
+            lower * (1.0 - fraction) + upper * fraction
+        } else {
+            lower + difference * fraction
+        }
+    }
+}
+
+/// Returns the median of the non-empty `values`, reordering them in place
+#[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+pub(crate) fn median_in_place(values: &mut [f64]) -> f64 {
+    quantile_in_place(values, 0.5)
+}
+
+/// Returns the `p`-quantile of the non-empty `values` like [`quantile_of_sorted`],
+/// but selecting the two closest values in linear time rather than sorting
+/// them all, which reorders them in place
+#[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+pub(crate) fn quantile_in_place(values: &mut [f64], p: f64) -> f64 {
+    let position = p * (values.len() - 1) as f64;
+    let lower = position as usize;
+    let fraction = position - lower as f64;
+    let (_, &mut lower, above) = values.select_nth_unstable_by(lower, partial_cmp_nan_last);
+    // the next value is the smallest of the ones above
+    let upper = above
+        .iter()
+        .copied()
+        .min_by(partial_cmp_nan_last)
+        .unwrap_or(lower);
+    interpolate(lower, upper, fraction)
+}
+
+/// Returns the quantiles of the non-empty `values` for every `p` in `ps`,
+/// like [`quantile_in_place`], but selecting all of the values they need
+/// together, by partitioning around the middle one and then each half,
+/// so the values are only passed over about log(`ps.len()`) times
+#[cfg(feature = "alloc")]
+pub(crate) fn quantiles_in_place(values: &mut [f64], ps: &[f64]) -> Vec<f64> {
+    let last = values.len() - 1;
+    let positions = ps.iter().map(|p| p * last as f64);
+    let mut ranks: Vec<usize> = positions
+        .clone()
+        .flat_map(|position| [position as usize, Ord::min(position as usize + 1, last)])
+        .collect();
+    ranks.sort_unstable();
+    ranks.dedup();
+    select_ranks(values, &ranks, 0);
+    positions
+        .map(|position| {
+            let lower = position as usize;
+            let upper = Ord::min(lower + 1, last);
+            let fraction = position - lower as f64;
+            interpolate(values[lower], values[upper], fraction)
+        })
+        .collect()
+}
+
+/// Reorder `values`, which start at index `offset` of the whole slice, so
+/// that every one of the ascending `ranks` holds the value it would if
+/// they were sorted
+#[cfg(feature = "alloc")]
+fn select_ranks(values: &mut [f64], ranks: &[usize], offset: usize) {
+    if ranks.is_empty() {
+        return;
+    }
+    let middle = ranks.len() / 2;
+    let rank = ranks[middle];
+    let (below, _, above) = values.select_nth_unstable_by(rank - offset, partial_cmp_nan_last);
+    select_ranks(below, &ranks[..middle], offset);
+    select_ranks(above, &ranks[middle + 1..], rank + 1);
+}
+
+/// Returns the rank of each value, from 1 for the smallest to `values.len()`
+/// for the largest, giving tied values the mean of the ranks they span
+#[cfg(feature = "alloc")]
+pub(crate) fn average_ranks(values: &[f64]) -> Vec<f64> {
+    ranks(values, RankTies::Average)
+}
+
+/// Returns the rank of each value, from 1 for the smallest, giving tied
+/// values ranks as `ties` says
+#[cfg(feature = "alloc")]
+pub(crate) fn ranks(values: &[f64], ties: RankTies) -> Vec<f64> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    // a stable sort, so tied values stay in their order for `RankTies::Ordinal`
+    order.sort_by(|&a, &b| partial_cmp_nan_last(&values[a], &values[b]));
+    let mut ranks = vec![0.0; values.len()];
+    let mut start = 0;
+    let mut distinct = 0;
+    while start < order.len() {
+        let value = &values[order[start]];
+        let end = start
+            + order[start..]
+                .iter()
+                .take_while(|&&i| partial_cmp_nan_last(&values[i], value) == Ordering::Equal)
+                .count();
+        distinct += 1;
+        for (offset, &i) in order[start..end].iter().enumerate() {
+            ranks[i] = match ties {
+                // the mean of the ranks start + 1 to end
+                RankTies::Average => (start + 1 + end) as f64 / 2.0,
+                RankTies::Min => (start + 1) as f64,
+                RankTies::Max => end as f64,
+                RankTies::Dense => distinct as f64,
+                RankTies::Ordinal => (start + 1 + offset) as f64,
+            };
+        }
+        start = end;
+    }
+    ranks
+}
+
+/// Wrapper that gives a [`PartialOrd`] type a total order using
+/// [`partial_cmp_nan_last`], so that it can be used in ordered collections
+#[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct NanLast<T>(pub T);
+
+impl<T: PartialOrd> PartialEq for NanLast<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T: PartialOrd> Eq for NanLast<T> {}
+
+impl<T: PartialOrd> PartialOrd for NanLast<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: PartialOrd> Ord for NanLast<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        partial_cmp_nan_last(&self.0, &other.0)
+    }
+}
+
+macro_rules! impl_total_order_using_ord {
     ($($type: ty)*) => {
-        $(impl MinMax for $type {
-            fn min(self, other: Self) -> Self {
-                <Self as Ord>::min(self, other)
+        $(impl TotalOrder for $type {
+            fn total_cmp(&self, other: &Self) -> Ordering {
+                self.cmp(other)
             }
+        })*
+    };
+}
 
-            fn max(self, other: Self) -> Self {
-                <Self as Ord>::max(self, other)
+macro_rules! impl_total_order_using_assoc_func {
+    ($($type: ty)*) => {
+        $(impl TotalOrder for $type {
+            fn total_cmp(&self, other: &Self) -> Ordering {
+                <$type>::total_cmp(self, other)
             }
         })*
     };
 }
 
-macro_rules! impl_min_max_using_assoc_func {
+impl_total_order_using_ord!(i8 i16 i32 i64 i128 isize);
+impl_total_order_using_ord!(u8 u16 u32 u64 u128 usize);
+impl_total_order_using_assoc_func!(f32 f64);
+
+macro_rules! impl_square_root_signed {
     ($($type: ty)*) => {
-        $(impl MinMax for $type {
-            fn min(self, other: Self) -> Self {
-                <$type>::min(self, other)
+        $(impl SquareRoot for $type {
+            fn square_root(&self) -> Option<Self> {
+                self.checked_isqrt()
             }
+        })*
+    };
+}
 
-            fn max(self, other: Self) -> Self {
-                <$type>::max(self, other)
+macro_rules! impl_square_root_unsigned {
+    ($($type: ty)*) => {
+        $(impl SquareRoot for $type {
+            fn square_root(&self) -> Option<Self> {
+                Some(self.isqrt())
             }
         })*
     };
 }
 
-impl_min_max_using_ord!(i8 i16 i32 i64 i128);
-impl_min_max_using_ord!(u8 u16 u32 u64 u128);
-impl_min_max_using_assoc_func!(f32 f64);
+macro_rules! impl_square_root_float {
+    ($($type: ty)*) => {
+        $(impl SquareRoot for $type {
+            fn square_root(&self) -> Option<Self> {
+                // NaN has a square root, which is NaN
+                (*self >= 0.0 || self.is_nan()).then(|| Float::sqrt(*self))
+            }
+        })*
+    };
+}
+
+impl_square_root_signed!(i8 i16 i32 i64 i128 isize);
+impl_square_root_unsigned!(u8 u16 u32 u64 u128 usize);
+impl_square_root_float!(f32 f64);
 
 #[cfg(test)]
 mod tests {
@@ -71,6 +393,144 @@ mod tests {
         assert_eq!(<f32 as MinMax>::max(1.0, f32::NAN), 1.0);
     }
 
+    #[test]
+    fn test_min_max_nan_first() {
+        assert_eq!(<f64 as MinMax>::min(f64::NAN, 1.0), 1.0);
+        assert_eq!(<f64 as MinMax>::max(f64::NAN, 1.0), 1.0);
+        assert!(<f64 as MinMax>::max(f64::NAN, f64::NAN).is_nan());
+    }
+
+    #[test]
+    fn test_square_root() {
+        assert_eq!(17u32.square_root(), Some(4));
+        assert_eq!((-4i64).square_root(), None);
+        assert_eq!(2.25f32.square_root(), Some(1.5));
+        assert_eq!((-1.0f64).square_root(), None);
+        assert!(f64::NAN.square_root().unwrap().is_nan());
+    }
+
+    #[test]
+    fn test_min_max_newtype() {
+        #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+        struct Metres(f64);
+        assert_eq!(Metres(2.0).min(Metres(1.0)), Metres(1.0));
+        assert_eq!(<usize as MinMax>::max(3, 5), 5);
+    }
+
+    #[test]
+    fn test_total_order() {
+        assert_eq!(f64::NAN.total_cmp(&f64::INFINITY), Ordering::Greater);
+        assert_eq!((-f64::NAN).total_cmp(&f64::NEG_INFINITY), Ordering::Less);
+        assert_eq!((-0.0).total_cmp(&0.0), Ordering::Less);
+        assert_eq!(TotalOrder::total_cmp(&2u8, &1), Ordering::Greater);
+    }
+
+    #[test]
+    fn test_partial_cmp_nan_last() {
+        assert_eq!(partial_cmp_nan_last(&1.0, &2.0), Ordering::Less);
+        assert_eq!(partial_cmp_nan_last(&f64::NAN, &2.0), Ordering::Greater);
+        assert_eq!(partial_cmp_nan_last(&2.0, &f64::NAN), Ordering::Less);
+        assert_eq!(partial_cmp_nan_last(&f64::NAN, &f64::NAN), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_quantile_of_sorted() {
+        let sorted = [1.0, 2.0, 3.0, 4.0];
+        assert_eq!(quantile_of_sorted(&sorted, 0.0), 1.0);
+        assert_eq!(quantile_of_sorted(&sorted, 0.5), 2.5);
+        assert_eq!(quantile_of_sorted(&sorted, 0.25), 1.75);
+        assert_eq!(quantile_of_sorted(&sorted, 1.0), 4.0);
+        assert_eq!(quantile_of_sorted(&[7.0], 0.3), 7.0);
+    }
+
+    #[test]
+    fn test_quantile_of_sorted_infinite() {
+        let sorted = [1.0, 2.0, f64::INFINITY];
+        assert_eq!(quantile_of_sorted(&sorted, 0.5), 2.0);
+        assert_eq!(quantile_of_sorted(&sorted, 0.75), f64::INFINITY);
+        assert_eq!(quantile_of_sorted(&[1.0, f64::INFINITY], 0.0), 1.0);
+        assert_eq!(
+            quantile_of_sorted(&[f64::NEG_INFINITY, 1.0], 0.5),
+            f64::NEG_INFINITY
+        );
+        let infinities = [f64::INFINITY, f64::INFINITY];
+        assert_eq!(quantile_of_sorted(&infinities, 0.5), f64::INFINITY);
+        assert!(quantile_of_sorted(&[f64::NEG_INFINITY, f64::INFINITY], 0.5).is_nan());
+        assert_eq!(quantile_of_sorted(&[-f64::MAX, f64::MAX], 0.5), 0.0);
+        let quantile = quantile_of_sorted(&[-f64::MAX, f64::MAX], 0.75);
+        assert!((quantile / (f64::MAX / 2.0) - 1.0).abs() < 1e-15);
+    }
+
+    #[test]
+    fn test_median_in_place() {
+        assert_eq!(median_in_place(&mut [3.0, 1.0, 2.0]), 2.0);
+        assert_eq!(median_in_place(&mut [4.0, 1.0, 3.0, 2.0]), 2.5);
+        assert_eq!(quantile_in_place(&mut [4.0, 1.0, 3.0, 2.0], 0.25), 1.75);
+        assert_eq!(quantile_in_place(&mut [7.0], 0.3), 7.0);
+    }
+
+    #[test]
+    fn test_quantile_in_place_infinite() {
+        assert_eq!(median_in_place(&mut [1.0, 2.0, f64::INFINITY]), 2.0);
+        assert_eq!(quantile_in_place(&mut [1.0, f64::INFINITY], 0.0), 1.0);
+        assert_eq!(
+            quantile_in_place(&mut [f64::INFINITY, 1.0], 0.5),
+            f64::INFINITY
+        );
+        assert_eq!(
+            median_in_place(&mut [f64::INFINITY, 3.0, f64::INFINITY, f64::INFINITY]),
+            f64::INFINITY
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_quantiles_in_place() {
+        let mut values: Vec<f64> = (0..1000).map(|x| f64::from(x * 7919 % 1009)).collect();
+        let mut sorted = values.clone();
+        sorted.sort_unstable_by(partial_cmp_nan_last);
+        let ps = [0.99, 0.0, 0.5, 0.25, 0.999, 0.5, 1.0];
+        let quantiles = quantiles_in_place(&mut values, &ps);
+        for (&p, quantile) in ps.iter().zip(quantiles) {
+            assert_eq!(quantile, quantile_of_sorted(&sorted, p));
+        }
+        let mut values = [f64::INFINITY, 2.0, 1.0];
+        assert_eq!(quantiles_in_place(&mut values, &[0.5, 0.0]), [2.0, 1.0]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_average_ranks() {
+        assert_eq!(
+            average_ranks(&[10.0, 30.0, 20.0, 30.0, 5.0]),
+            vec![2.0, 4.5, 3.0, 4.5, 1.0]
+        );
+        assert_eq!(average_ranks(&[1.0, 1.0, 1.0]), vec![2.0, 2.0, 2.0]);
+        assert!(average_ranks(&[]).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ranks_ties() {
+        let values = [7.0, f64::NAN, 3.0, 7.0, 7.0, 1.0];
+        assert_eq!(
+            ranks(&values, RankTies::Min),
+            vec![3.0, 6.0, 2.0, 3.0, 3.0, 1.0]
+        );
+        assert_eq!(
+            ranks(&values, RankTies::Max),
+            vec![5.0, 6.0, 2.0, 5.0, 5.0, 1.0]
+        );
+        assert_eq!(
+            ranks(&values, RankTies::Dense),
+            vec![3.0, 4.0, 2.0, 3.0, 3.0, 1.0]
+        );
+        assert_eq!(
+            ranks(&values, RankTies::Ordinal),
+            vec![3.0, 6.0, 2.0, 4.0, 5.0, 1.0]
+        );
+    }
+
     #[test]
     fn test_min_i32() {
         assert_eq!(<i32 as MinMax>::min(0, 1), 0);
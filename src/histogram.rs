@@ -0,0 +1,781 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use num_traits::ToPrimitive;
+
+use crate::helpers::bin_of;
+use crate::helpers::item_to_f64;
+use crate::helpers::partial_cmp_nan_last;
+use crate::helpers::quantile_of_sorted;
+use crate::helpers::range_around;
+use crate::helpers::Float;
+use crate::render;
+use crate::FrequencyStats;
+use crate::Result;
+use crate::StatsError;
+
+/// Rules for choosing how many bins a [`Histogram`] has
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinRule {
+    /// `⌈log₂ n⌉ + 1` bins. Works well for roughly normal data
+    /// with fewer than a couple of hundred values.
+    Sturges,
+    /// `⌈2 ∛n⌉` bins
+    Rice,
+    /// Bins of width `3.49 σ / ∛n`, where `σ` is the standard deviation,
+    /// but no more than [`BinRule::MAX_WIDTH_BINS`] of them
+    Scott,
+    /// Bins of width `2 IQR / ∛n`, where `IQR` is the interquartile range,
+    /// but no more than [`BinRule::MAX_WIDTH_BINS`] of them.
+    /// This is less affected by outliers than [`BinRule::Scott`].
+    FreedmanDiaconis,
+    /// A fixed number of bins, or fewer if the range of the values is
+    /// only a few representable [`f64`]s wide
+    Count(usize),
+}
+
+impl BinRule {
+    /// Largest number of bins that [`BinRule::Scott`] and
+    /// [`BinRule::FreedmanDiaconis`] choose, so that a few values far from
+    /// the rest can't make the histogram too large to allocate
+    pub const MAX_WIDTH_BINS: usize = 10_000;
+}
+
+/// How the bins of a [`Histogram`] are chosen
+#[derive(Debug, Clone, PartialEq)]
+enum Bins {
+    /// Equal width bins between the smallest and largest value
+    Rule(BinRule),
+    /// Explicit bin edges
+    Edges(Vec<f64>),
+}
+
+/// Builder for a [`Histogram`], created by [`Histogram::builder`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistogramBuilder {
+    bins: Bins,
+}
+
+impl HistogramBuilder {
+    /// Use equal width bins between the smallest and largest value,
+    /// with the number of bins chosen by `rule`
+    #[must_use]
+    pub fn rule(mut self, rule: BinRule) -> Self {
+        self.bins = Bins::Rule(rule);
+        self
+    }
+
+    /// Use `count` equal width bins between the smallest and largest value,
+    /// or fewer if the range is too narrow to split into that many
+    #[must_use]
+    pub fn bins(self, count: usize) -> Self {
+        self.rule(BinRule::Count(count))
+    }
+
+    /// Use the given bin edges, so bin `i` holds the values from `edges[i]`
+    /// up to (but not including) `edges[i + 1]`. The last bin also holds
+    /// values equal to its right edge. Values outside the edges aren't counted.
+    ///
+    /// # Panics
+    /// Panics if there are fewer than two edges or they aren't increasing
+    #[must_use]
+    pub fn edges(mut self, edges: Vec<f64>) -> Self {
+        assert!(edges.len() >= 2, "there must be at least two edges");
+        assert!(
+            edges.windows(2).all(|pair| pair[0] < pair[1]),
+            "edges must be strictly increasing"
+        );
+        self.bins = Bins::Edges(edges);
+        self
+    }
+
+    /// Returns the bin edges to use for the sorted, non-empty `values`
+    ///
+    /// # Errors
+    /// Returns an error if the edges come from a rule and a value is
+    /// infinite, or the range of the values overflows
+    fn edges_for(&self, values: &[f64]) -> Result<Vec<f64>> {
+        match &self.bins {
+            Bins::Edges(edges) => Ok(edges.clone()),
+            Bins::Rule(rule) => equal_width_edges(values, *rule),
+        }
+    }
+
+    /// Count the items of `data` into bins. `NaN`s are not counted.
+    ///
+    /// # Errors
+    /// Returns an error if `data` has no items (other than `NaN`s), if the
+    /// bins come from a [`BinRule`] and an item is infinite
+    /// (`StatsError::InfinityEncountered`) or the range of the items
+    /// overflows (`StatsError::Overflow`), or if an item can't be converted
+    /// to [`f64`]
+    pub fn build<I>(&self, data: I) -> Result<Histogram>
+    where
+        I: IntoIterator,
+        I::Item: ToPrimitive,
+    {
+        let mut values = data
+            .into_iter()
+            .map(item_to_f64)
+            .filter(|x| !matches!(x, Ok(x) if x.is_nan()))
+            .collect::<Result<Vec<f64>>>()?;
+        if values.is_empty() {
+            return Err(StatsError::EmptyCollection);
+        }
+        values.sort_by(partial_cmp_nan_last);
+
+        let edges = self.edges_for(&values)?;
+        let mut counts = alloc::vec![0; edges.len() - 1];
+        for x in values {
+            if let Some(bin) = bin_of(&edges, x) {
+                counts[bin] += 1;
+            }
+        }
+        Ok(Histogram { edges, counts })
+    }
+}
+
+/// Returns the edges of equal width bins covering the sorted, non-empty `values`
+///
+/// # Errors
+/// Returns an error if a value is infinite, or the range of the values overflows
+fn equal_width_edges(values: &[f64], rule: BinRule) -> Result<Vec<f64>> {
+    let n = values.len() as f64;
+    let (min, max) = (values[0], values[values.len() - 1]);
+    if min.is_infinite() || max.is_infinite() {
+        return Err(StatsError::InfinityEncountered);
+    }
+    if min == max {
+        // every value is the same, so use a single bin around it
+        let (low, high) = range_around(min);
+        return Ok(alloc::vec![low, high]);
+    }
+    let range = max - min;
+    if range.is_infinite() {
+        return Err(StatsError::Overflow);
+    }
+    let from_width = |width: f64| {
+        if width > 0.0 {
+            (Float::ceil(range / width) as usize).min(BinRule::MAX_WIDTH_BINS)
+        } else {
+            1
+        }
+    };
+    let count = match rule {
+        BinRule::Sturges => Float::ceil(Float::log2(n)) as usize + 1,
+        BinRule::Rice => Float::ceil(2.0 * Float::cbrt(n)) as usize,
+        BinRule::Scott => {
+            let mean = values.iter().sum::<f64>() / n;
+            let variance = values.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / n;
+            from_width(3.49 * Float::sqrt(variance) / Float::cbrt(n))
+        }
+        BinRule::FreedmanDiaconis => {
+            let iqr = quantile_of_sorted(values, 0.75) - quantile_of_sorted(values, 0.25);
+            from_width(2.0 * iqr / Float::cbrt(n))
+        }
+        BinRule::Count(count) => count,
+    }
+    .max(1);
+    let width = range / count as f64;
+    let mut edges = alloc::vec![min];
+    for i in 1..count {
+        let edge = min + width * i as f64;
+        // in a range only a few representable values wide, neighbouring
+        // edges round to the same value, so leave out the repeats
+        if edge > edges[edges.len() - 1] && edge < max {
+            edges.push(edge);
+        }
+    }
+    edges.push(max);
+    Ok(edges)
+}
+
+/// Counts of values falling into consecutive bins (intervals).
+///
+/// It implements [`FrequencyStats`](crate::FrequencyStats) with the count of
+/// each bin and the value in the middle of it, so all of its methods can be
+/// used to estimate statistics of the binned data. [`IntoIterator`] gives the
+/// same `(usize, f64)` tuples.
+///
+/// # Examples
+/// ```
+/// use stats_traits::{BinRule, FrequencyStats, Histogram};
+/// let data = vec![1.0, 2.0, 2.5, 3.0, 3.5, 4.0, 9.0];
+/// let histogram = Histogram::builder().bins(4).build(data.iter().copied()).unwrap();
+/// assert_eq!(histogram.edges(), &[1.0, 3.0, 5.0, 7.0, 9.0]);
+/// assert_eq!(histogram.counts(), &[3, 3, 0, 1]);
+/// assert_eq!(histogram.count(), 7);
+///
+/// let sturges = Histogram::builder().rule(BinRule::Sturges).build(data.iter().copied()).unwrap();
+/// assert_eq!(sturges.len(), 4);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Histogram {
+    edges: Vec<f64>,
+    counts: Vec<usize>,
+}
+
+impl Histogram {
+    /// Start building a histogram. By default the number
+    /// of bins is chosen with [`BinRule::Sturges`].
+    pub fn builder() -> HistogramBuilder {
+        HistogramBuilder {
+            bins: Bins::Rule(BinRule::Sturges),
+        }
+    }
+
+    /// Create a histogram from grouped data that has already been counted,
+    /// such as a frequency table of class intervals, where `counts[i]` is the
+    /// number of values from `edges[i]` up to `edges[i + 1]`
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Histogram;
+    /// // ages in classes 0-10, 10-20, 20-40 and 40-60
+    /// let ages = Histogram::from_counts(vec![0.0, 10.0, 20.0, 40.0, 60.0], vec![5, 15, 20, 10]);
+    /// assert_eq!(ages.total(), 50);
+    /// assert_eq!(ages.median(), Ok(25.0));
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if there aren't exactly one more edges than counts, if there
+    /// are no counts, or if the edges aren't strictly increasing
+    pub fn from_counts(edges: Vec<f64>, counts: Vec<usize>) -> Self {
+        assert!(!counts.is_empty(), "there must be at least one count");
+        assert!(
+            edges.len() == counts.len() + 1,
+            "there must be one more edge than counts"
+        );
+        assert!(
+            edges.windows(2).all(|pair| pair[0] < pair[1]),
+            "edges must be strictly increasing"
+        );
+        Self { edges, counts }
+    }
+
+    /// Returns the number of bins
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Returns `true` if there are no bins. This is never the case
+    /// for a histogram made by [`HistogramBuilder::build`].
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Returns the edges of the bins, which has one more item than there are bins
+    pub fn edges(&self) -> &[f64] {
+        &self.edges
+    }
+
+    /// Returns the number of values in each bin
+    pub fn counts(&self) -> &[usize] {
+        &self.counts
+    }
+
+    /// Returns the total number of values counted in the bins
+    pub fn total(&self) -> usize {
+        self.counts.iter().sum()
+    }
+
+    /// Returns an iterator over the densities of the bins, which are the
+    /// counts divided by the total count and the width of the bin, so that
+    /// the area of the histogram is 1
+    pub fn densities(&self) -> impl Iterator<Item = f64> + '_ {
+        let total = self.total() as f64;
+        self.counts
+            .iter()
+            .zip(self.edges.windows(2))
+            .map(move |(&count, edges)| count as f64 / (total * (edges[1] - edges[0])))
+    }
+
+    /// Returns an iterator over the values in the middle of each bin
+    pub fn centres(&self) -> impl Iterator<Item = f64> + '_ {
+        self.edges
+            .windows(2)
+            .map(|edges| edges[0] + (edges[1] - edges[0]) / 2.0)
+    }
+
+    /// Returns an iterator over `(count, centre)` tuples for each bin
+    pub fn iter(&self) -> impl Iterator<Item = (usize, f64)> + '_ {
+        self.counts.iter().copied().zip(self.centres())
+    }
+
+    /// Estimate the `p` quantile of the values by interpolating linearly
+    /// within the bin that contains it, as if the values in each bin were
+    /// spread evenly across it, which is the textbook method for grouped
+    /// data: `L + (p * N - F) / f * w`, where `L` is the lower edge of the
+    /// bin, `N` the total count, `F` the count of the bins before it, `f`
+    /// its count and `w` its width. It follows the ogive (the cumulative
+    /// frequency polygon), so 0 and 1 are the edges of the outermost bins
+    /// that aren't empty.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Histogram;
+    /// let marks = Histogram::from_counts(vec![0.0, 20.0, 40.0, 60.0, 80.0], vec![4, 10, 16, 10]);
+    /// // the 20th of 40 marks is 6 into the 16 in the class from 40 to 60
+    /// assert_eq!(marks.median(), Ok(47.5));
+    /// assert_eq!(marks.quantile(0.25), Ok(32.0));
+    /// assert_eq!(marks.quantile(0.9), Ok(72.0));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if every count is 0
+    ///
+    /// # Panics
+    /// Panics if `p` is not between 0 and 1
+    pub fn quantile(&self, p: f64) -> Result<f64> {
+        assert!((0.0..=1.0).contains(&p), "p must be between 0 and 1");
+        let target = p * self.total() as f64;
+        let mut below = 0;
+        for (bin, &count) in self.counts.iter().enumerate() {
+            if count > 0 && (below + count) as f64 >= target {
+                let (lower, upper) = (self.edges[bin], self.edges[bin + 1]);
+                let fraction = (target - below as f64) / count as f64;
+                return Ok(lower + fraction.max(0.0) * (upper - lower));
+            }
+            below += count;
+        }
+        Err(StatsError::EmptyCollection)
+    }
+
+    /// Estimate the median of the values by interpolating within the bin
+    /// that contains it. See [`Histogram::quantile`].
+    ///
+    /// # Errors
+    /// Returns an error if every count is 0
+    pub fn median(&self) -> Result<f64> {
+        self.quantile(0.5)
+    }
+
+    /// Render the counts as a sparkline, with a block for each bin as high
+    /// as its count relative to the largest one, or a space for an empty
+    /// bin, to show the shape of the distribution on one line of a log
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Histogram;
+    /// let histogram = Histogram::from_counts(vec![0.0, 1.0, 2.0, 3.0, 4.0], vec![2, 8, 0, 4]);
+    /// assert_eq!(histogram.sparkline(), "▂█ ▄");
+    /// ```
+    pub fn sparkline(&self) -> String {
+        render::sparkline(self.counts.iter().copied())
+    }
+
+    /// Render the histogram as a horizontal bar chart, with a line for each
+    /// bin showing its edges, a bar of `bar` (such as `'█'`, or `'#'` for
+    /// plain ASCII) up to `width` characters long for the largest count,
+    /// and its count
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Histogram;
+    /// let histogram = Histogram::from_counts(vec![0.0, 10.0, 20.0, 30.0], vec![4, 8, 2]);
+    /// let expected = "\
+    /// [0, 10)  │#### 4
+    /// [10, 20) │######## 8
+    /// [20, 30] │## 2
+    /// ";
+    /// assert_eq!(histogram.bar_chart(8, '#'), expected);
+    /// ```
+    pub fn bar_chart(&self, width: usize, bar: char) -> String {
+        let last = self.len() - 1;
+        let rows =
+            self.edges
+                .windows(2)
+                .zip(&self.counts)
+                .enumerate()
+                .map(|(bin, (edges, &count))| {
+                    let close = if bin == last { ']' } else { ')' };
+                    (format!("[{}, {}{close}", edges[0], edges[1]), count)
+                });
+        render::bar_chart(rows, width, bar)
+    }
+}
+
+impl FrequencyStats<f64> for Histogram {
+    fn items<'a>(&'a self) -> impl Iterator<Item = (usize, f64)> + 'a
+    where
+        f64: 'a,
+    {
+        self.iter()
+    }
+}
+
+impl IntoIterator for Histogram {
+    type Item = (usize, f64);
+    type IntoIter = alloc::vec::IntoIter<(usize, f64)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter().collect::<Vec<_>>().into_iter()
+    }
+}
+
+/// Builder for a [`Histogram2D`], created by [`Histogram2D::builder`].
+/// The bins of each axis are chosen independently by a [`HistogramBuilder`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram2DBuilder {
+    x: HistogramBuilder,
+    y: HistogramBuilder,
+}
+
+impl Histogram2DBuilder {
+    /// Choose the bins of the x axis with `builder`
+    #[must_use]
+    pub fn x(mut self, builder: HistogramBuilder) -> Self {
+        self.x = builder;
+        self
+    }
+
+    /// Choose the bins of the y axis with `builder`
+    #[must_use]
+    pub fn y(mut self, builder: HistogramBuilder) -> Self {
+        self.y = builder;
+        self
+    }
+
+    /// Count the `(x, y)` pairs of `data` into bins. Pairs where either
+    /// value is `NaN`, or outside the edges of its axis, are not counted.
+    ///
+    /// # Errors
+    /// Returns an error if `data` has no pairs (other than those containing `NaN`s),
+    /// under the same conditions as [`HistogramBuilder::build`] for either axis,
+    /// or if a value can't be converted to [`f64`]
+    pub fn build<I, X, Y>(&self, data: I) -> Result<Histogram2D>
+    where
+        I: IntoIterator<Item = (X, Y)>,
+        X: ToPrimitive,
+        Y: ToPrimitive,
+    {
+        let pairs = data
+            .into_iter()
+            .map(|(x, y)| Ok((item_to_f64(x)?, item_to_f64(y)?)))
+            .filter(|pair| !matches!(pair, Ok((x, y)) if x.is_nan() || y.is_nan()))
+            .collect::<Result<Vec<(f64, f64)>>>()?;
+        if pairs.is_empty() {
+            return Err(StatsError::EmptyCollection);
+        }
+
+        let sorted = |values: Vec<f64>| {
+            let mut values = values;
+            values.sort_by(partial_cmp_nan_last);
+            values
+        };
+        let x_edges = self
+            .x
+            .edges_for(&sorted(pairs.iter().map(|&(x, _)| x).collect()))?;
+        let y_edges = self
+            .y
+            .edges_for(&sorted(pairs.iter().map(|&(_, y)| y).collect()))?;
+
+        let y_bins = y_edges.len() - 1;
+        let mut counts = alloc::vec![0; (x_edges.len() - 1) * y_bins];
+        for (x, y) in pairs {
+            if let (Some(i), Some(j)) = (bin_of(&x_edges, x), bin_of(&y_edges, y)) {
+                counts[i * y_bins + j] += 1;
+            }
+        }
+        Ok(Histogram2D {
+            x_edges,
+            y_edges,
+            counts,
+        })
+    }
+}
+
+/// Counts of `(x, y)` pairs falling into a grid of bins, giving
+/// the joint distribution of the two values.
+///
+/// # Examples
+/// ```
+/// use stats_traits::{Histogram, Histogram2D};
+/// let data = vec![(0.0, 0.0), (0.5, 3.0), (1.5, 0.5), (2.0, 3.5), (1.9, 4.0)];
+/// let histogram = Histogram2D::builder()
+///     .x(Histogram::builder().bins(2))
+///     .y(Histogram::builder().edges(vec![0.0, 2.0, 4.0]))
+///     .build(data)
+///     .unwrap();
+/// assert_eq!(histogram.shape(), (2, 2));
+/// assert_eq!(histogram.get(0, 1), Some(1));
+/// assert_eq!(histogram.get(1, 1), Some(2));
+/// assert_eq!(histogram.marginal_x().counts(), &[2, 3]);
+/// assert_eq!(histogram.marginal_y().counts(), &[2, 3]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Histogram2D {
+    x_edges: Vec<f64>,
+    y_edges: Vec<f64>,
+    /// Counts with the y bins of each x bin next to each other
+    counts: Vec<usize>,
+}
+
+impl Histogram2D {
+    /// Start building a 2D histogram. By default the number of bins
+    /// of each axis is chosen with [`BinRule::Sturges`].
+    pub fn builder() -> Histogram2DBuilder {
+        Histogram2DBuilder {
+            x: Histogram::builder(),
+            y: Histogram::builder(),
+        }
+    }
+
+    /// Returns the number of bins along the x and y axes
+    pub fn shape(&self) -> (usize, usize) {
+        (self.x_edges.len() - 1, self.y_edges.len() - 1)
+    }
+
+    /// Returns the edges of the bins along the x axis
+    pub fn x_edges(&self) -> &[f64] {
+        &self.x_edges
+    }
+
+    /// Returns the edges of the bins along the y axis
+    pub fn y_edges(&self) -> &[f64] {
+        &self.y_edges
+    }
+
+    /// Returns the number of pairs in x bin `i` and y bin `j`,
+    /// or `None` if there is no such bin
+    pub fn get(&self, i: usize, j: usize) -> Option<usize> {
+        let (x_bins, y_bins) = self.shape();
+        if i < x_bins && j < y_bins {
+            Some(self.counts[i * y_bins + j])
+        } else {
+            None
+        }
+    }
+
+    /// Returns the total number of pairs counted in the bins
+    pub fn total(&self) -> usize {
+        self.counts.iter().sum()
+    }
+
+    /// Returns an iterator over the counts of each row of bins, that is,
+    /// the counts of every y bin for each x bin in turn
+    pub fn rows(&self) -> impl Iterator<Item = &[usize]> {
+        self.counts.chunks(self.shape().1)
+    }
+
+    /// Returns the histogram of the x values, adding up the counts of every y bin
+    pub fn marginal_x(&self) -> Histogram {
+        Histogram {
+            edges: self.x_edges.clone(),
+            counts: self.rows().map(|row| row.iter().sum()).collect(),
+        }
+    }
+
+    /// Returns the histogram of the y values, adding up the counts of every x bin
+    pub fn marginal_y(&self) -> Histogram {
+        let mut counts = alloc::vec![0; self.shape().1];
+        for row in self.rows() {
+            for (count, cell) in counts.iter_mut().zip(row) {
+                *count += cell;
+            }
+        }
+        Histogram {
+            edges: self.y_edges.clone(),
+            counts,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FrequencyStats;
+
+    extern crate std;
+    use std::vec;
+
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(
+            Histogram::builder().build(Vec::<f64>::new()),
+            Err(StatsError::EmptyCollection)
+        );
+        assert_eq!(
+            Histogram::builder().build(vec![f64::NAN]),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+
+    #[test]
+    fn test_rules() {
+        let data: Vec<f64> = (0..100).map(|x| x as f64).collect();
+        let count = |rule| {
+            Histogram::builder()
+                .rule(rule)
+                .build(data.iter().copied())
+                .unwrap()
+                .len()
+        };
+        assert_eq!(count(BinRule::Sturges), 8);
+        assert_eq!(count(BinRule::Rice), 10);
+        // σ = 28.866, width = 21.71
+        assert_eq!(count(BinRule::Scott), 5);
+        // IQR = 49.5, width = 21.33
+        assert_eq!(count(BinRule::FreedmanDiaconis), 5);
+    }
+
+    #[test]
+    fn test_rules_infinite_and_spread_out() {
+        let build =
+            |rule, data: &[f64]| Histogram::builder().rule(rule).build(data.iter().copied());
+        for rule in [BinRule::Sturges, BinRule::Scott, BinRule::FreedmanDiaconis] {
+            assert_eq!(
+                build(rule, &[0.0, 1.0, f64::INFINITY]),
+                Err(StatsError::InfinityEncountered)
+            );
+            assert_eq!(
+                build(rule, &[f64::NEG_INFINITY, 1.0]),
+                Err(StatsError::InfinityEncountered)
+            );
+            assert_eq!(
+                build(rule, &[-f64::MAX, f64::MAX]),
+                Err(StatsError::Overflow)
+            );
+        }
+        // the interquartile range is tiny compared to the range
+        let mut data: Vec<f64> = (0..100).map(|x| x as f64).collect();
+        data[99] = 1e12;
+        let histogram = build(BinRule::FreedmanDiaconis, &data).unwrap();
+        assert_eq!(histogram.len(), BinRule::MAX_WIDTH_BINS);
+        // explicit edges just leave infinite values out
+        let histogram = Histogram::builder()
+            .edges(vec![0.0, 1.0, 2.0])
+            .build([0.5, f64::INFINITY])
+            .unwrap();
+        assert_eq!(histogram.counts(), &[1, 0]);
+    }
+
+    #[test]
+    fn test_counts_include_last_edge() {
+        let histogram = Histogram::builder()
+            .bins(2)
+            .build(vec![0, 1, 2, 3, 4])
+            .unwrap();
+        assert_eq!(histogram.edges(), &[0.0, 2.0, 4.0]);
+        assert_eq!(histogram.counts(), &[2, 3]);
+    }
+
+    #[test]
+    fn test_explicit_edges() {
+        let histogram = Histogram::builder()
+            .edges(vec![0.0, 1.0, 10.0])
+            .build(vec![-1.0, 0.0, 0.5, 1.0, 5.0, 10.0, 11.0])
+            .unwrap();
+        assert_eq!(histogram.counts(), &[2, 3]);
+        assert_eq!(histogram.total(), 5);
+        let densities: Vec<f64> = histogram.densities().collect();
+        assert_relative_eq!(densities[0], 0.4);
+        assert_relative_eq!(densities[1], 3.0 / 45.0);
+    }
+
+    #[test]
+    fn test_single_value() {
+        let histogram = Histogram::builder().build(vec![2.0, 2.0]).unwrap();
+        assert_eq!(histogram.edges(), &[1.5, 2.5]);
+        assert_eq!(histogram.counts(), &[2]);
+
+        let histogram = Histogram::builder().build([1e20, 1e20]).unwrap();
+        assert_eq!(histogram.edges(), &[1e20 - 1e14, 1e20 + 1e14]);
+        assert!(histogram.densities().all(f64::is_finite));
+        let histogram = Histogram::builder().build([f64::MAX]).unwrap();
+        assert_eq!(histogram.counts(), &[1]);
+        assert!(histogram.densities().all(f64::is_finite));
+    }
+
+    #[test]
+    fn test_narrow_range() {
+        let histogram = Histogram::builder()
+            .bins(10)
+            .build([1.0, 1.0 + f64::EPSILON])
+            .unwrap();
+        assert_eq!(histogram.edges(), &[1.0, 1.0 + f64::EPSILON]);
+        assert_eq!(histogram.counts(), &[2]);
+        assert!(histogram.densities().all(f64::is_finite));
+
+        let histogram = Histogram::builder()
+            .bins(4)
+            .build([1.0, 1.0 + 2.0 * f64::EPSILON])
+            .unwrap();
+        assert_eq!(histogram.len(), 2);
+        assert!(histogram.edges().windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn test_frequency_stats() {
+        let histogram = Histogram::builder()
+            .edges(vec![0.0, 2.0, 4.0])
+            .build(vec![0.5, 1.5, 3.0])
+            .unwrap();
+        assert_eq!(histogram.centres().collect::<Vec<_>>(), vec![1.0, 3.0]);
+        assert_relative_eq!(histogram.mean().unwrap(), 5.0 / 3.0);
+        assert_eq!(histogram.mode(), Ok(1.0));
+    }
+
+    #[test]
+    fn test_grouped_quantiles() {
+        let histogram = Histogram::from_counts(vec![0.0, 1.0, 2.0, 4.0, 8.0], vec![2, 0, 4, 2]);
+        assert_eq!(histogram.quantile(0.0), Ok(0.0));
+        assert_eq!(histogram.quantile(0.25), Ok(1.0));
+        // the 3rd value is the first of the bin from 2 to 4
+        assert_eq!(histogram.median(), Ok(3.0));
+        assert_eq!(histogram.quantile(1.0), Ok(8.0));
+        let trailing_empty = Histogram::from_counts(vec![0.0, 1.0, 2.0], vec![3, 0]);
+        assert_eq!(trailing_empty.quantile(1.0), Ok(1.0));
+        let empty = Histogram::from_counts(vec![0.0, 1.0], vec![0]);
+        assert_eq!(empty.median(), Err(StatsError::EmptyCollection));
+        // agrees with the quantiles of evenly spread values
+        let data: Vec<f64> = (0..100).map(|x| f64::from(x) + 0.5).collect();
+        let histogram = Histogram::builder()
+            .edges(vec![0.0, 10.0, 50.0, 100.0])
+            .build(data)
+            .unwrap();
+        assert_relative_eq!(histogram.quantile(0.3).unwrap(), 30.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "there must be one more edge than counts")]
+    fn test_from_counts_mismatched() {
+        Histogram::from_counts(vec![0.0, 1.0], vec![1, 2]);
+    }
+
+    #[test]
+    fn test_2d_joint_and_marginals() {
+        let data = vec![(1, 1.0), (1, 2.0), (2, 1.0), (3, 3.0), (3, f64::NAN)];
+        let histogram = Histogram2D::builder()
+            .x(Histogram::builder().edges(vec![1.0, 2.0, 3.0]))
+            .y(Histogram::builder().bins(2))
+            .build(data)
+            .unwrap();
+        assert_eq!(histogram.shape(), (2, 2));
+        assert_eq!(histogram.y_edges(), &[1.0, 2.0, 3.0]);
+        assert_eq!(histogram.rows().collect::<Vec<_>>(), vec![&[1, 1], &[1, 1]]);
+        assert_eq!(histogram.get(2, 0), None);
+        assert_eq!(histogram.total(), 4);
+        assert_eq!(histogram.marginal_x().counts(), &[2, 2]);
+        assert_eq!(histogram.marginal_y().counts(), &[2, 2]);
+    }
+
+    #[test]
+    fn test_2d_empty() {
+        assert_eq!(
+            Histogram2D::builder().build(vec![(f64::NAN, 1.0)]),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_decreasing_edges() {
+        let _ = Histogram::builder().edges(vec![1.0, 0.0]);
+    }
+}
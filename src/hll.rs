@@ -0,0 +1,263 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::hash::{Hash, Hasher};
+
+use crate::helpers::Float;
+
+/// Estimates the number of distinct values in a stream in a small, fixed
+/// amount of memory, using [HyperLogLog](<https://algo.inria.fr/flajolet/Publications/FlFuGaMe07.pdf>).
+///
+/// Each value is hashed, and the hash picks one of `2^precision` registers
+/// and records the longest run of leading zero bits seen by that register.
+/// The relative error of the estimate is about `1.04 / sqrt(2^precision)`,
+/// so a precision of 14 uses 16 KiB and is accurate to within about 1%,
+/// whether there are a thousand distinct values or a billion.
+///
+/// # Examples
+/// ```
+/// use stats_traits::HyperLogLog;
+/// let mut hll = HyperLogLog::new(14);
+/// for id in 0..100_000 {
+///     // every id is inserted twice, but only counted once
+///     hll.insert(&id);
+///     hll.insert(&id);
+/// }
+/// let estimate = hll.estimate();
+/// assert!((estimate - 100_000.0).abs() < 2_000.0);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "HyperLogLogRepr"))]
+pub struct HyperLogLog {
+    precision: u8,
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    /// Create an empty sketch with `2^precision` registers
+    ///
+    /// # Panics
+    /// Panics if `precision` is not between 4 and 18
+    pub fn new(precision: u8) -> Self {
+        assert!(
+            (4..=18).contains(&precision),
+            "precision must be between 4 and 18"
+        );
+        Self {
+            precision,
+            registers: vec![0; 1 << precision],
+        }
+    }
+
+    /// Returns the precision the sketch was created with
+    pub fn precision(&self) -> u8 {
+        self.precision
+    }
+
+    /// Add a value to the sketch
+    pub fn insert<T: Hash + ?Sized>(&mut self, value: &T) {
+        let mut hasher = Fnv1a::default();
+        value.hash(&mut hasher);
+        self.insert_hash(hasher.finish());
+    }
+
+    /// Add a value to the sketch by its 64 bit hash. The hashes of different
+    /// values should look uniformly random, as the estimate depends on it.
+    pub fn insert_hash(&mut self, hash: u64) {
+        let precision = u32::from(self.precision);
+        let index = (hash >> (64 - precision)) as usize;
+        // the bit set at the end stops the rank going past the bits left
+        let rest = (hash << precision) | (1 << (precision - 1));
+        let rank = rest.leading_zeros() as u8 + 1;
+        self.registers[index] = self.registers[index].max(rank);
+    }
+
+    /// Combine the values seen by `other` into `self`, as if every value
+    /// inserted into `other` had been inserted into `self` as well
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::HyperLogLog;
+    /// let mut a = HyperLogLog::new(12);
+    /// let mut b = HyperLogLog::new(12);
+    /// for id in 0..1000 {
+    ///     a.insert(&id);
+    ///     b.insert(&(id + 500));
+    /// }
+    /// a.merge(&b);
+    /// assert!((a.estimate() - 1500.0).abs() < 100.0);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if the sketches have different precisions
+    pub fn merge(&mut self, other: &Self) {
+        assert_eq!(
+            self.precision, other.precision,
+            "can't merge sketches with different precisions"
+        );
+        for (register, &other_register) in self.registers.iter_mut().zip(&other.registers) {
+            *register = (*register).max(other_register);
+        }
+    }
+
+    /// Remove every value from the sketch
+    pub fn clear(&mut self) {
+        self.registers.fill(0);
+    }
+
+    /// Returns the estimated number of distinct values inserted
+    pub fn estimate(&self) -> f64 {
+        let m = self.registers.len() as f64;
+        let alpha = match self.registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / m),
+        };
+        let harmonic_sum: f64 = self
+            .registers
+            .iter()
+            .map(|&rank| 1.0 / (1_u64 << rank) as f64)
+            .sum();
+        let estimate = alpha * m * m / harmonic_sum;
+
+        // with few values many registers are still empty, and counting them is more accurate
+        let empty = self.registers.iter().filter(|&&rank| rank == 0).count();
+        if estimate <= 2.5 * m && empty > 0 {
+            m * Float::ln(m / empty as f64)
+        } else {
+            estimate
+        }
+    }
+}
+
+/// The fields of a [`HyperLogLog`] as they are deserialized, before they
+/// are checked against each other
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct HyperLogLogRepr {
+    precision: u8,
+    registers: Vec<u8>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<HyperLogLogRepr> for HyperLogLog {
+    type Error = crate::CheckpointError;
+
+    fn try_from(repr: HyperLogLogRepr) -> Result<Self, Self::Error> {
+        let HyperLogLogRepr {
+            precision,
+            registers,
+        } = repr;
+        if !(4..=18).contains(&precision) || registers.len() != 1 << precision {
+            return Err(crate::CheckpointError::Invalid);
+        }
+        // a rank counts the leading zeros of the bits left after the index, plus one
+        if registers.iter().any(|&rank| rank > 65 - precision) {
+            return Err(crate::CheckpointError::Invalid);
+        }
+        Ok(Self {
+            precision,
+            registers,
+        })
+    }
+}
+
+/// The 64 bit FNV-1a hash, with the result mixed by the finalizer of MurmurHash3
+/// so that every bit of the input affects the leading bits of the hash
+#[derive(Debug, Clone, Copy)]
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for Fnv1a {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        let mut hash = self.0;
+        hash ^= hash >> 33;
+        hash = hash.wrapping_mul(0xff51_afd7_ed55_8ccd);
+        hash ^= hash >> 33;
+        hash = hash.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+        hash ^ (hash >> 33)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(HyperLogLog::new(10).estimate(), 0.0);
+    }
+
+    #[test]
+    fn test_small_counts() {
+        let mut hll = HyperLogLog::new(14);
+        for word in ["a", "b", "c", "a", "b", "a"] {
+            hll.insert(word);
+        }
+        assert!((hll.estimate() - 3.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_accuracy() {
+        for precision in [8, 12, 16] {
+            let mut hll = HyperLogLog::new(precision);
+            for id in 0..1_000_000_u64 {
+                hll.insert(&id);
+            }
+            let relative_error = 1.04 / ((1 << precision) as f64).sqrt();
+            let estimate = hll.estimate();
+            assert!((estimate / 1_000_000.0 - 1.0).abs() < 3.0 * relative_error);
+        }
+    }
+
+    #[test]
+    fn test_merge_and_clear() {
+        let mut a = HyperLogLog::new(12);
+        let mut b = HyperLogLog::new(12);
+        for id in 0..10_000 {
+            a.insert(&id);
+            b.insert(&id);
+        }
+        let estimate = a.estimate();
+        a.merge(&b);
+        assert_eq!(a.estimate(), estimate);
+        a.clear();
+        assert_eq!(a, HyperLogLog::new(12));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let mut hll = HyperLogLog::new(4);
+        for id in 0..100 {
+            hll.insert(&id);
+        }
+        let json = serde_json::to_string(&hll).unwrap();
+        let mut loaded: HyperLogLog = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, hll);
+        loaded.insert(&100);
+        let tampered = json.replace(r#""precision":4"#, r#""precision":10"#);
+        assert!(serde_json::from_str::<HyperLogLog>(&tampered).is_err());
+        let rank = r#"{"precision":4,"registers":[99,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0]}"#;
+        assert!(serde_json::from_str::<HyperLogLog>(rank).is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_merge_different_precision() {
+        HyperLogLog::new(10).merge(&HyperLogLog::new(12));
+    }
+}
@@ -0,0 +1,1759 @@
+#[cfg(feature = "alloc")]
+use alloc::vec;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "alloc")]
+use core::f64::consts::{FRAC_1_SQRT_2, PI};
+
+#[cfg(feature = "alloc")]
+use crate::helpers::average_ranks;
+#[cfg(feature = "alloc")]
+use crate::helpers::median_in_place;
+#[cfg(feature = "alloc")]
+use crate::helpers::partial_cmp_nan_last;
+use crate::helpers::Float;
+use num_traits::ToPrimitive;
+#[cfg(feature = "rand")]
+use rand::Rng;
+
+use crate::helpers::item_to_f64;
+use crate::special::beta_quantile;
+use crate::special::binomial_cdf;
+use crate::special::f_upper_tail;
+use crate::special::gamma_quantile;
+#[cfg(feature = "alloc")]
+use crate::special::kolmogorov_upper_tail;
+use crate::special::ln_gamma;
+#[cfg(feature = "alloc")]
+use crate::special::normal_cdf;
+use crate::special::normal_quantile;
+use crate::special::normal_two_sided;
+use crate::special::student_t_quantile;
+use crate::special::student_t_two_sided;
+use crate::special::upper_incomplete_gamma;
+use crate::OnlineStats;
+use crate::Result;
+use crate::StatsError;
+
+/// The result of a hypothesis test that is summarised by its statistic
+/// and p-value, such as [`Stats::jarque_bera`](crate::Stats::jarque_bera)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TestResult {
+    /// The test statistic
+    pub statistic: f64,
+    /// The p-value, which is the probability of a statistic at least as
+    /// extreme as this one if the null hypothesis of the test is true
+    pub p_value: f64,
+}
+
+impl TestResult {
+    /// The Ljung–Box test of a series of `count` items, given
+    /// a function returning its autocorrelation at a lag
+    pub(crate) fn ljung_box<F>(mut autocorrelation: F, max_lag: usize, count: usize) -> Result<Self>
+    where
+        F: FnMut(usize) -> Result<f64>,
+    {
+        let n = count as f64;
+        let mut sum = 0.0;
+        // from the largest lag down, so that too short a series fails straight away
+        for lag in (1..=max_lag).rev() {
+            let r = autocorrelation(lag)?;
+            sum += r * r / (n - lag as f64);
+        }
+        let statistic = n * (n + 2.0) * sum;
+        Ok(Self {
+            statistic,
+            // compared with the chi-squared distribution with one degree of freedom per lag
+            p_value: upper_incomplete_gamma(max_lag as f64 / 2.0, statistic / 2.0),
+        })
+    }
+
+    /// The Jarque–Bera test of the moments in `stats`
+    pub(crate) fn jarque_bera(stats: &OnlineStats) -> Result<Self> {
+        if stats.variance()? == 0.0 {
+            return Err(StatsError::ZeroVariance);
+        }
+        let skewness = stats.skewness()?;
+        let kurtosis = stats.kurtosis()?;
+        let statistic =
+            stats.count() as f64 / 6.0 * (skewness * skewness + kurtosis * kurtosis / 4.0);
+        Ok(Self {
+            statistic,
+            // compared with the chi-squared distribution with 2 degrees of freedom
+            p_value: upper_incomplete_gamma(1.0, statistic / 2.0),
+        })
+    }
+}
+
+impl TestResult {
+    /// The sign test of `positive` differences above 0 and `negative` below it,
+    /// out of `count` differences including the zeros
+    pub(crate) fn sign_test(positive: usize, negative: usize, count: usize) -> Result<Self> {
+        if count == 0 {
+            return Err(StatsError::EmptyCollection);
+        }
+        let n = positive + negative;
+        if n == 0 {
+            return Err(StatsError::ZeroVariance);
+        }
+        let tail = binomial_cdf(positive.min(negative), n, 0.5);
+        Ok(Self {
+            statistic: positive as f64,
+            p_value: (2.0 * tail).min(1.0),
+        })
+    }
+
+    /// The Wald–Wolfowitz runs test of a sequence of two kinds of item,
+    /// given by whether each item is of the first kind
+    #[cfg(feature = "alloc")]
+    pub(crate) fn runs_test<I>(sequence: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = bool>,
+    {
+        let (mut first, mut second, mut runs) = (0.0, 0.0, 0.0);
+        let mut previous = None;
+        for kind in sequence {
+            if kind {
+                first += 1.0;
+            } else {
+                second += 1.0;
+            }
+            if previous != Some(kind) {
+                runs += 1.0;
+            }
+            previous = Some(kind);
+        }
+        if runs == 0.0 {
+            return Err(StatsError::EmptyCollection);
+        }
+        if first == 0.0 || second == 0.0 {
+            return Err(StatsError::ZeroVariance);
+        }
+        let n = first + second;
+        let product = 2.0 * first * second;
+        let mean = product / n + 1.0;
+        let variance = product * (product - n) / (n * n * (n - 1.0));
+        Ok(Self {
+            statistic: runs,
+            p_value: normal_two_sided((runs - mean) / Float::sqrt(variance)),
+        })
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl TestResult {
+    /// The one-sample Kolmogorov–Smirnov test of the `sorted` values against `cdf`
+    pub(crate) fn kolmogorov_smirnov<F>(sorted: &[f64], mut cdf: F) -> Self
+    where
+        F: FnMut(f64) -> f64,
+    {
+        let n = sorted.len() as f64;
+        let statistic = sorted
+            .iter()
+            .enumerate()
+            .map(|(i, &x)| {
+                let p = cdf(x);
+                // the empirical CDF jumps from i / n to (i + 1) / n at x
+                ((i + 1) as f64 / n - p).max(p - i as f64 / n)
+            })
+            .fold(0.0, f64::max);
+        Self::kolmogorov_smirnov_result(statistic, n)
+    }
+
+    /// The two-sample Kolmogorov–Smirnov test of the `sorted` values of each sample
+    pub(crate) fn kolmogorov_smirnov_two_sample(a: &[f64], b: &[f64]) -> Self {
+        let (n_a, n_b) = (a.len() as f64, b.len() as f64);
+        let (mut i, mut j) = (0, 0);
+        let mut statistic: f64 = 0.0;
+        while i < a.len() && j < b.len() {
+            // step past every copy of the next value in either sample
+            let x = a[i].min(b[j]);
+            while i < a.len() && a[i] == x {
+                i += 1;
+            }
+            while j < b.len() && b[j] == x {
+                j += 1;
+            }
+            statistic = statistic.max((i as f64 / n_a - j as f64 / n_b).abs());
+        }
+        Self::kolmogorov_smirnov_result(statistic, n_a * n_b / (n_a + n_b))
+    }
+
+    /// The Shapiro–Wilk test of the `sorted` values, using the approximations
+    /// of the coefficients and of the distribution of the statistic from
+    /// [Royston's algorithm AS R94](<https://doi.org/10.2307/2986146>)
+    pub(crate) fn shapiro_wilk(sorted: &[f64]) -> Result<Self> {
+        const C1: [f64; 6] = [0.0, 0.221157, -0.147981, -2.07119, 4.434685, -2.706056];
+        const C2: [f64; 6] = [0.0, 0.042981, -0.293762, -1.752461, 5.682633, -3.582633];
+        const C3: [f64; 4] = [0.544, -0.39978, 0.025054, -6.714e-4];
+        const C4: [f64; 4] = [1.3822, -0.77857, 0.062767, -0.0020322];
+        const C5: [f64; 4] = [-1.5861, -0.31082, -0.083751, 0.0038915];
+        const C6: [f64; 3] = [-0.4803, -0.082676, 0.0030302];
+        const G: [f64; 2] = [-2.273, 0.459];
+        let polynomial = |coefficients: &[f64], x: f64| {
+            coefficients.iter().rev().fold(0.0, |sum, c| sum * x + c)
+        };
+
+        let count = sorted.len();
+        if count < 3 {
+            return Err(StatsError::InsufficientData {
+                required: 3,
+                actual: count,
+            });
+        }
+        if sorted[0] == sorted[count - 1] {
+            return Err(StatsError::ZeroVariance);
+        }
+        let n = count as f64;
+
+        // the coefficients of the lower half of the sorted values,
+        // which are the negated coefficients of the upper half
+        let half = count / 2;
+        let coefficients: Vec<f64> = if count == 3 {
+            vec![FRAC_1_SQRT_2]
+        } else {
+            let m: Vec<f64> = (1..=half)
+                .map(|i| normal_quantile((i as f64 - 0.375) / (n + 0.25)))
+                .collect();
+            let sum_squares = 2.0 * m.iter().map(|m| m * m).sum::<f64>();
+            let root_sum_squares = Float::sqrt(sum_squares);
+            let root_n = 1.0 / Float::sqrt(n);
+            let mut coefficients = vec![polynomial(&C1, root_n) - m[0] / root_sum_squares];
+            if count > 5 {
+                coefficients.push(polynomial(&C2, root_n) - m[1] / root_sum_squares);
+            }
+            let fixed = coefficients.len();
+            let scale = ((sum_squares - 2.0 * m[..fixed].iter().map(|m| m * m).sum::<f64>())
+                / (1.0 - 2.0 * coefficients.iter().map(|a| a * a).sum::<f64>()))
+            .sqrt();
+            coefficients.extend(m[fixed..].iter().map(|m| -m / scale));
+            coefficients
+        };
+
+        let mean = sorted.iter().sum::<f64>() / n;
+        let sum_squares: f64 = sorted.iter().map(|x| (x - mean) * (x - mean)).sum();
+        let numerator: f64 = coefficients
+            .iter()
+            .enumerate()
+            .map(|(i, a)| a * (sorted[count - 1 - i] - sorted[i]))
+            .sum();
+        let statistic = (numerator * numerator / sum_squares).min(1.0);
+
+        let p_value = if count == 3 {
+            // the exact distribution
+            (6.0 / PI * (Float::asin(Float::sqrt(statistic)) - PI / 3.0)).max(0.0)
+        } else {
+            // ln(1 - W) is close to normally distributed after a transformation
+            let y = Float::ln(1.0 - statistic);
+            let (y, mean, std_dev) = if count <= 11 {
+                let gamma = polynomial(&G, n);
+                if y >= gamma {
+                    return Ok(Self {
+                        statistic,
+                        p_value: 0.0,
+                    });
+                }
+                (
+                    -Float::ln(gamma - y),
+                    polynomial(&C3, n),
+                    Float::exp(polynomial(&C4, n)),
+                )
+            } else {
+                let ln_n = Float::ln(n);
+                (y, polynomial(&C5, ln_n), Float::exp(polynomial(&C6, ln_n)))
+            };
+            1.0 - normal_cdf((y - mean) / std_dev)
+        };
+        Ok(Self { statistic, p_value })
+    }
+
+    /// Returns the statistic and its asymptotic p-value for an effective sample
+    /// size of `n`, using the correction for small samples by Stephens
+    fn kolmogorov_smirnov_result(statistic: f64, n: f64) -> Self {
+        let root_n = Float::sqrt(n);
+        let lambda = (root_n + 0.12 + 0.11 / root_n) * statistic;
+        Self {
+            statistic,
+            p_value: kolmogorov_upper_tail(lambda),
+        }
+    }
+}
+
+#[cfg(feature = "rand")]
+impl TestResult {
+    /// Hartigan's dip test of the `sorted` values, comparing their dip with the
+    /// dips of `simulations` samples of the same size from the uniform distribution
+    pub(crate) fn dip_test<R>(sorted: &[f64], simulations: usize, rng: &mut R) -> Self
+    where
+        R: Rng + ?Sized,
+    {
+        assert!(
+            simulations > 0,
+            "number of simulations must be greater than 0"
+        );
+        let statistic = dip(sorted);
+        let mut sample = vec![0.0; sorted.len()];
+        let mut at_least = 0;
+        for _ in 0..simulations {
+            for x in &mut sample {
+                *x = rng.gen();
+            }
+            sample.sort_by(partial_cmp_nan_last);
+            if dip(&sample) >= statistic {
+                at_least += 1;
+            }
+        }
+        Self {
+            statistic,
+            // counting the sample itself, so the p-value is never 0
+            p_value: (at_least + 1) as f64 / (simulations + 1) as f64,
+        }
+    }
+}
+
+/// The result of a [t-test](<https://en.wikipedia.org/wiki/Student%27s_t-test>)
+/// comparing the means of two samples, as returned by
+/// [`Stats::t_test_two_sample`](crate::Stats::t_test_two_sample) and
+/// [`Stats::pooled_t_test_two_sample`](crate::Stats::pooled_t_test_two_sample)
+///
+/// # Examples
+/// ```
+/// use stats_traits::Stats;
+/// let control = vec![5.1, 4.9, 5.3, 5.0, 4.8];
+/// let variant = vec![5.6, 5.9, 5.4, 5.8, 6.0];
+/// let test = control.t_test_two_sample(&variant).unwrap();
+/// assert!(test.statistic < 0.0);
+/// assert!(test.p_value < 0.01);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TTest {
+    /// The t statistic, which is positive when the
+    /// mean of the first sample is the larger one
+    pub statistic: f64,
+    /// Degrees of freedom of the t distribution the statistic is compared
+    /// with. For Welch's test this is usually not a whole number.
+    pub degrees_of_freedom: f64,
+    /// The two-sided p-value, which is the probability of a statistic at
+    /// least this far from 0 if the samples have the same mean
+    pub p_value: f64,
+}
+
+impl TTest {
+    fn new(statistic: f64, degrees_of_freedom: f64) -> Self {
+        Self {
+            statistic,
+            degrees_of_freedom,
+            p_value: student_t_two_sided(statistic, degrees_of_freedom),
+        }
+    }
+
+    /// Welch's t-test, which doesn't assume the samples have the same variance
+    pub(crate) fn welch(a: &OnlineStats, b: &OnlineStats) -> Result<Self> {
+        let (n_a, mean_a, var_a) = sample_moments(a)?;
+        let (n_b, mean_b, var_b) = sample_moments(b)?;
+        let se2_a = var_a / n_a;
+        let se2_b = var_b / n_b;
+        let se2 = se2_a + se2_b;
+        if se2 == 0.0 {
+            return Err(StatsError::ZeroVariance);
+        }
+        // the Welch–Satterthwaite equation
+        let degrees_of_freedom =
+            se2 * se2 / (se2_a * se2_a / (n_a - 1.0) + se2_b * se2_b / (n_b - 1.0));
+        Ok(Self::new(
+            (mean_a - mean_b) / Float::sqrt(se2),
+            degrees_of_freedom,
+        ))
+    }
+
+    /// Student's t-test, which pools the variances of the samples
+    pub(crate) fn pooled(a: &OnlineStats, b: &OnlineStats) -> Result<Self> {
+        let (n_a, mean_a, var_a) = sample_moments(a)?;
+        let (n_b, mean_b, var_b) = sample_moments(b)?;
+        let degrees_of_freedom = n_a + n_b - 2.0;
+        let pooled = ((n_a - 1.0) * var_a + (n_b - 1.0) * var_b) / degrees_of_freedom;
+        if pooled == 0.0 {
+            return Err(StatsError::ZeroVariance);
+        }
+        let se = Float::sqrt(pooled * (1.0 / n_a + 1.0 / n_b));
+        Ok(Self::new((mean_a - mean_b) / se, degrees_of_freedom))
+    }
+}
+
+/// The result of an [F-test of equality of variances](<https://en.wikipedia.org/wiki/F-test_of_equality_of_variances>),
+/// as returned by [`Stats::f_test_two_sample`](crate::Stats::f_test_two_sample)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FTest {
+    /// The F statistic, which is the sample variance of the
+    /// first sample divided by that of the second
+    pub statistic: f64,
+    /// Degrees of freedom of the numerator and the denominator of the
+    /// statistic, which are the sizes of the samples minus one
+    pub degrees_of_freedom: (usize, usize),
+    /// The two-sided p-value, which is the probability of a ratio at least
+    /// this far from 1 if the samples have the same variance
+    pub p_value: f64,
+}
+
+impl FTest {
+    pub(crate) fn new(a: &OnlineStats, b: &OnlineStats) -> Result<Self> {
+        let (n_a, _, var_a) = sample_moments(a)?;
+        let (n_b, _, var_b) = sample_moments(b)?;
+        if var_b == 0.0 {
+            return Err(StatsError::ZeroVariance);
+        }
+        let statistic = var_a / var_b;
+        let (d1, d2) = (n_a - 1.0, n_b - 1.0);
+        let upper = f_upper_tail(statistic, d1, d2);
+        Ok(Self {
+            statistic,
+            degrees_of_freedom: (a.count() - 1, b.count() - 1),
+            p_value: (2.0 * upper.min(1.0 - upper)).min(1.0),
+        })
+    }
+}
+
+/// Which extreme of the items a test looks at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Tail {
+    /// Only the smallest item
+    Lower,
+    /// Only the largest item
+    Upper,
+    /// Whichever of the smallest and largest items is further from the mean
+    Both,
+}
+
+/// The result of [Grubbs' test](<https://en.wikipedia.org/wiki/Grubbs%27s_test>)
+/// for a single outlier, as returned by [`Stats::grubbs_test`](crate::Stats::grubbs_test)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GrubbsTest {
+    /// The G statistic, which is how many sample standard
+    /// deviations the suspected outlier is from the mean
+    pub statistic: f64,
+    /// The largest statistic that isn't significant at the chosen alpha
+    pub critical_value: f64,
+    /// The p-value, which is the probability of an item at least this far from
+    /// the mean if there are no outliers. It is the Bonferroni bound from the t
+    /// distribution, which is exact whenever it is small enough to matter.
+    pub p_value: f64,
+    /// The index of the suspected outlier in the collection
+    pub index: usize,
+    /// The suspected outlier
+    pub value: f64,
+    /// Whether the suspected outlier is a significant outlier at the chosen
+    /// alpha, which is when the statistic is larger than the critical value
+    pub is_outlier: bool,
+}
+
+impl GrubbsTest {
+    /// Test whether `value`, at `index` in the items summarised by `stats`, is an outlier
+    pub(crate) fn new(
+        stats: &OnlineStats,
+        index: usize,
+        value: f64,
+        tail: Tail,
+        alpha: f64,
+    ) -> Result<Self> {
+        let (n, mean, variance) = sample_moments(stats)?;
+        if n < 3.0 {
+            return Err(StatsError::InsufficientData {
+                required: 3,
+                actual: stats.count(),
+            });
+        }
+        if variance == 0.0 {
+            return Err(StatsError::ZeroVariance);
+        }
+        let statistic = (value - mean).abs() / Float::sqrt(variance);
+        let sides = if tail == Tail::Both { 2.0 } else { 1.0 };
+        let df = n - 2.0;
+        let t = student_t_quantile(1.0 - alpha / (sides * n), df);
+        let critical_value = (n - 1.0) / Float::sqrt(n) * Float::sqrt(t * t / (df + t * t));
+        // the t statistic the G statistic corresponds to
+        let denominator = (n - 1.0) * (n - 1.0) - n * statistic * statistic;
+        let p_value = if denominator > 0.0 {
+            let t = Float::sqrt(n * df * statistic * statistic / denominator);
+            (sides * n * student_t_two_sided(t, df) / 2.0).min(1.0)
+        } else {
+            0.0
+        };
+        Ok(Self {
+            statistic,
+            critical_value,
+            p_value,
+            index,
+            value,
+            is_outlier: statistic > critical_value,
+        })
+    }
+}
+
+/// The result of a [z-test for proportions](<https://en.wikipedia.org/wiki/Test_statistic#Common_test_statistics>),
+/// as returned by [`proportion_z_test`] and [`two_proportion_z_test`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProportionTest {
+    /// The z statistic, which is positive when the observed
+    /// proportion is larger than the one it is compared with
+    pub statistic: f64,
+    /// The two-sided p-value, which is the probability of a statistic at
+    /// least this far from 0 if the proportions are the same
+    pub p_value: f64,
+    /// The observed difference between the proportions
+    pub difference: f64,
+    /// The lower and upper bounds of the (Wald) confidence interval for the difference
+    pub confidence_interval: (f64, f64),
+}
+
+impl ProportionTest {
+    fn new(statistic: f64, difference: f64, std_err: f64, confidence: f64) -> Self {
+        let margin = normal_quantile((1.0 + confidence) / 2.0) * std_err;
+        Self {
+            statistic,
+            p_value: normal_two_sided(statistic),
+            difference,
+            confidence_interval: (difference - margin, difference + margin),
+        }
+    }
+}
+
+/// Test whether `successes` out of `trials` is consistent with each trial
+/// succeeding with probability `p`, using the normal approximation to the
+/// binomial distribution. The confidence interval is for the difference
+/// between the observed proportion and `p`, at the `confidence` level
+/// (such as `0.95`).
+///
+/// # Examples
+/// ```
+/// use stats_traits::proportion_z_test;
+/// // is a coin that landed heads 60 times out of 100 fair?
+/// let test = proportion_z_test(60, 100, 0.5, 0.95).unwrap();
+/// assert!((test.statistic - 2.0).abs() < 1e-12);
+/// assert!((test.p_value - 0.0455).abs() < 1e-4);
+/// assert!((test.difference - 0.1).abs() < 1e-12);
+/// ```
+///
+/// # Errors
+/// Returns an error if `trials` is 0
+///
+/// # Panics
+/// Panics if `successes` is greater than `trials`, or if
+/// `p` or `confidence` is not strictly between 0 and 1
+pub fn proportion_z_test(
+    successes: usize,
+    trials: usize,
+    p: f64,
+    confidence: f64,
+) -> Result<ProportionTest> {
+    assert!(p > 0.0 && p < 1.0, "p must be between 0 and 1");
+    let observed = proportion(successes, trials, confidence)?;
+    let n = trials as f64;
+    let statistic = (observed - p) / Float::sqrt(p * (1.0 - p) / n);
+    let std_err = Float::sqrt(observed * (1.0 - observed) / n);
+    Ok(ProportionTest::new(
+        statistic,
+        observed - p,
+        std_err,
+        confidence,
+    ))
+}
+
+/// Test whether two groups have the same proportion of successes, such as the
+/// conversion rates of the two arms of an A/B test. The test uses the proportion
+/// of both groups pooled together, and the confidence interval is for the
+/// proportion of `a` minus the proportion of `b`, at the `confidence` level
+/// (such as `0.95`).
+///
+/// # Examples
+/// ```
+/// use stats_traits::two_proportion_z_test;
+/// let test = two_proportion_z_test(45, 100, 30, 100, 0.95).unwrap();
+/// assert!(test.p_value < 0.05);
+/// let (low, high) = test.confidence_interval;
+/// assert!(low > 0.0 && high < 0.3);
+/// ```
+///
+/// # Errors
+/// Returns an error if either number of trials is 0, or if every trial
+/// succeeded or every trial failed (`StatsError::ZeroVariance`)
+///
+/// # Panics
+/// Panics if either number of successes is greater than its number
+/// of trials, or if `confidence` is not strictly between 0 and 1
+pub fn two_proportion_z_test(
+    successes_a: usize,
+    trials_a: usize,
+    successes_b: usize,
+    trials_b: usize,
+    confidence: f64,
+) -> Result<ProportionTest> {
+    let p_a = proportion(successes_a, trials_a, confidence)?;
+    let p_b = proportion(successes_b, trials_b, confidence)?;
+    let (n_a, n_b) = (trials_a as f64, trials_b as f64);
+    let pooled = (successes_a + successes_b) as f64 / (n_a + n_b);
+    if pooled == 0.0 || pooled == 1.0 {
+        return Err(StatsError::ZeroVariance);
+    }
+    let statistic = (p_a - p_b) / Float::sqrt(pooled * (1.0 - pooled) * (1.0 / n_a + 1.0 / n_b));
+    let std_err = Float::sqrt(p_a * (1.0 - p_a) / n_a + p_b * (1.0 - p_b) / n_b);
+    Ok(ProportionTest::new(
+        statistic,
+        p_a - p_b,
+        std_err,
+        confidence,
+    ))
+}
+
+/// Calculate the [Wilson score interval](<https://en.wikipedia.org/wiki/Binomial_proportion_confidence_interval#Wilson_score_interval>)
+/// for the probability of success, given `successes` out of `trials`, at the
+/// `confidence` level (such as `0.95`), returning its lower and upper bounds.
+/// Unlike the interval of [`proportion_z_test`], it stays between 0 and 1 and
+/// covers the true probability about as often as it should even for few trials
+/// or proportions near 0 or 1.
+///
+/// # Examples
+/// ```
+/// use stats_traits::wilson_interval;
+/// let (lower, upper) = wilson_interval(0, 10, 0.95).unwrap();
+/// assert_eq!(lower, 0.0);
+/// assert!((upper - 0.2775).abs() < 1e-4);
+/// ```
+///
+/// # Errors
+/// Returns an error if `trials` is 0
+///
+/// # Panics
+/// Panics if `successes` is greater than `trials`,
+/// or if `confidence` is not strictly between 0 and 1
+pub fn wilson_interval(successes: usize, trials: usize, confidence: f64) -> Result<(f64, f64)> {
+    let p = proportion(successes, trials, confidence)?;
+    let n = trials as f64;
+    let z = normal_quantile((1.0 + confidence) / 2.0);
+    let z2 = z * z;
+    let denominator = 1.0 + z2 / n;
+    let centre = (p + z2 / (2.0 * n)) / denominator;
+    let margin = z / denominator * Float::sqrt(p * (1.0 - p) / n + z2 / (4.0 * n * n));
+    // the bounds are exactly 0 and 1 at the extremes, apart from rounding
+    let lower = if successes == 0 { 0.0 } else { centre - margin };
+    let upper = if successes == trials {
+        1.0
+    } else {
+        centre + margin
+    };
+    Ok((lower, upper))
+}
+
+/// Calculate the [Clopper–Pearson interval](<https://en.wikipedia.org/wiki/Binomial_proportion_confidence_interval#Clopper%E2%80%93Pearson_interval>)
+/// for the probability of success, given `successes` out of `trials`, at the
+/// `confidence` level (such as `0.95`), returning its lower and upper bounds.
+/// It is found from the binomial distribution itself rather than an
+/// approximation, so it is often called the exact interval. It is guaranteed
+/// to cover the true probability at least as often as `confidence`, which
+/// makes it wider than [`wilson_interval`].
+///
+/// # Examples
+/// ```
+/// use stats_traits::clopper_pearson_interval;
+/// let (lower, upper) = clopper_pearson_interval(7, 20, 0.95).unwrap();
+/// assert!((lower - 0.1539).abs() < 1e-4);
+/// assert!((upper - 0.5922).abs() < 1e-4);
+/// ```
+///
+/// # Errors
+/// Returns an error if `trials` is 0
+///
+/// # Panics
+/// Panics if `successes` is greater than `trials`,
+/// or if `confidence` is not strictly between 0 and 1
+pub fn clopper_pearson_interval(
+    successes: usize,
+    trials: usize,
+    confidence: f64,
+) -> Result<(f64, f64)> {
+    proportion(successes, trials, confidence)?;
+    let alpha = 1.0 - confidence;
+    let (x, n) = (successes as f64, trials as f64);
+    let lower = if successes == 0 {
+        0.0
+    } else {
+        beta_quantile(alpha / 2.0, x, n - x + 1.0)
+    };
+    let upper = if successes == trials {
+        1.0
+    } else {
+        beta_quantile(1.0 - alpha / 2.0, x + 1.0, n - x)
+    };
+    Ok((lower, upper))
+}
+
+/// Calculate the exact confidence interval for the rate of a
+/// [Poisson process](<https://en.wikipedia.org/wiki/Poisson_distribution#Confidence_interval>),
+/// given the number of `events` seen over an `exposure` (such as a length of time,
+/// or a number of person-years), at the `confidence` level (such as `0.95`).
+/// Returns the lower and upper bounds of the rate in events per unit of exposure.
+/// The bounds come from quantiles of the chi-squared distribution, which
+/// makes the interval cover the true rate at least as often as `confidence`.
+///
+/// # Examples
+/// ```
+/// use stats_traits::poisson_rate_interval;
+/// // 5 incidents in 100 days
+/// let (lower, upper) = poisson_rate_interval(5, 100.0, 0.95);
+/// assert!((lower - 0.0162).abs() < 1e-4);
+/// assert!((upper - 0.1167).abs() < 1e-4);
+/// ```
+///
+/// # Panics
+/// Panics if `exposure` is not greater than 0, or if
+/// `confidence` is not strictly between 0 and 1
+pub fn poisson_rate_interval(events: usize, exposure: f64, confidence: f64) -> (f64, f64) {
+    assert!(exposure > 0.0, "exposure must be greater than 0");
+    assert!(
+        confidence > 0.0 && confidence < 1.0,
+        "confidence must be between 0 and 1"
+    );
+    let alpha = 1.0 - confidence;
+    let k = events as f64;
+    // half of a chi-squared quantile with 2k degrees of freedom
+    // is a quantile of the gamma distribution with shape k
+    let lower = if events == 0 {
+        0.0
+    } else {
+        gamma_quantile(alpha / 2.0, k)
+    };
+    let upper = gamma_quantile(1.0 - alpha / 2.0, k + 1.0);
+    (lower / exposure, upper / exposure)
+}
+
+/// Returns the proportion of trials that succeeded, after checking the arguments
+/// shared by the proportion tests
+fn proportion(successes: usize, trials: usize, confidence: f64) -> Result<f64> {
+    assert!(
+        successes <= trials,
+        "successes must not be greater than trials"
+    );
+    assert!(
+        confidence > 0.0 && confidence < 1.0,
+        "confidence must be between 0 and 1"
+    );
+    if trials == 0 {
+        return Err(StatsError::EmptyCollection);
+    }
+    Ok(successes as f64 / trials as f64)
+}
+
+/// Test whether several groups have the same variance with
+/// [Levene's test](<https://en.wikipedia.org/wiki/Levene%27s_test>), which is
+/// a one-way analysis of variance of how far each item is from the mean of its
+/// group. It is much less sensitive to the groups not being normally distributed
+/// than the F-test, so it is the usual check before [`anova_one_way`].
+/// See [`brown_forsythe_test`] for the more robust version using the medians.
+///
+/// # Examples
+/// ```
+/// use stats_traits::levene_test;
+/// let groups = [
+///     vec![10.1, 9.9, 10.0, 10.2, 9.8],
+///     vec![12.0, 8.5, 10.9, 7.6, 11.8],
+///     vec![10.3, 9.6, 10.1, 9.8, 10.4],
+/// ];
+/// let test = levene_test(&groups).unwrap();
+/// assert_eq!(test.between_degrees_of_freedom, 2);
+/// assert!(test.p_value < 0.05);
+/// ```
+///
+/// # Errors
+/// Returns an error under the same conditions as [`anova_one_way`]
+#[cfg(feature = "alloc")]
+pub fn levene_test<S>(groups: &[S]) -> Result<Anova>
+where
+    S: IntoIterator + Clone,
+    S::Item: ToPrimitive,
+{
+    anova_of_deviations(groups, |values| {
+        values.iter().sum::<f64>() / values.len() as f64
+    })
+}
+
+/// Test whether several groups have the same variance with the
+/// [Brown–Forsythe test](<https://en.wikipedia.org/wiki/Brown%E2%80%93Forsythe_test>),
+/// which is [`levene_test`] using how far each item is from the median of its
+/// group instead of the mean. This keeps it reliable for skewed distributions.
+///
+/// # Examples
+/// ```
+/// use stats_traits::brown_forsythe_test;
+/// let groups = [vec![1, 2, 3, 4, 5], vec![2, 3, 3, 4, 3], vec![0, 3, 6, 9, 12]];
+/// let test = brown_forsythe_test(&groups).unwrap();
+/// assert!(test.p_value < 0.05);
+/// ```
+///
+/// # Errors
+/// Returns an error under the same conditions as [`anova_one_way`]
+#[cfg(feature = "alloc")]
+pub fn brown_forsythe_test<S>(groups: &[S]) -> Result<Anova>
+where
+    S: IntoIterator + Clone,
+    S::Item: ToPrimitive,
+{
+    anova_of_deviations(groups, median_in_place)
+}
+
+/// Returns the one-way analysis of variance of the absolute differences
+/// between each item and the `centre` of its group, which may reorder the
+/// values it is given
+#[cfg(feature = "alloc")]
+fn anova_of_deviations<S, F>(groups: &[S], mut centre: F) -> Result<Anova>
+where
+    S: IntoIterator + Clone,
+    S::Item: ToPrimitive,
+    F: FnMut(&mut [f64]) -> f64,
+{
+    let mut deviations = Vec::with_capacity(groups.len());
+    for group in groups {
+        let mut values = group
+            .clone()
+            .into_iter()
+            .map(item_to_f64)
+            .collect::<Result<Vec<f64>>>()?;
+        if values.is_empty() {
+            return Err(StatsError::EmptyCollection);
+        }
+        let centre = centre(&mut values);
+        deviations.push(
+            values
+                .into_iter()
+                .map(|x| (x - centre).abs())
+                .collect::<Vec<f64>>(),
+        );
+    }
+    anova_one_way(&deviations)
+}
+
+/// The result of the [Wilcoxon signed-rank test](<https://en.wikipedia.org/wiki/Wilcoxon_signed-rank_test>),
+/// as returned by [`PairedStats::wilcoxon_signed_rank`](crate::PairedStats::wilcoxon_signed_rank)
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SignedRankTest {
+    /// The sum of the ranks of the positive differences (often called `W+` or `V`)
+    pub statistic: f64,
+    /// The two-sided p-value, which is the probability of a statistic at
+    /// least this far from its mean if the differences are centred on 0
+    pub p_value: f64,
+    /// Whether the p-value is exact, rather than from the normal approximation
+    pub exact: bool,
+}
+
+#[cfg(feature = "alloc")]
+impl SignedRankTest {
+    /// The largest number of differences for which the exact p-value is calculated
+    const MAX_EXACT: usize = 50;
+
+    /// Run the test on the differences between pairs. Zero differences
+    /// are dropped before ranking, and tied differences share their ranks.
+    pub(crate) fn from_differences(differences: &[f64]) -> Result<Self> {
+        if differences.is_empty() {
+            return Err(StatsError::EmptyCollection);
+        }
+        let non_zero: Vec<f64> = differences.iter().copied().filter(|&d| d != 0.0).collect();
+        if non_zero.is_empty() {
+            return Err(StatsError::ZeroVariance);
+        }
+        let magnitudes: Vec<f64> = non_zero.iter().map(|d| d.abs()).collect();
+        let statistic = average_ranks(&magnitudes)
+            .into_iter()
+            .zip(&non_zero)
+            .filter(|&(_, &d)| d > 0.0)
+            .map(|(rank, _)| rank)
+            .sum();
+
+        let n = non_zero.len();
+        let ties = tie_correction(magnitudes);
+        if ties == 0.0 && n == differences.len() && n < Self::MAX_EXACT {
+            return Ok(Self {
+                statistic,
+                p_value: exact_signed_rank_p_value(statistic as usize, n),
+                exact: true,
+            });
+        }
+        let n = n as f64;
+        let mean = n * (n + 1.0) / 4.0;
+        let variance = n * (n + 1.0) * (2.0 * n + 1.0) / 24.0 - ties / 48.0;
+        Ok(Self {
+            statistic,
+            p_value: normal_two_sided((statistic - mean) / Float::sqrt(variance)),
+            exact: false,
+        })
+    }
+}
+
+/// Returns the sum of `t³ - t` over each group of `t` tied values,
+/// which is how much ties reduce the variance of rank statistics
+#[cfg(feature = "alloc")]
+fn tie_correction(mut values: Vec<f64>) -> f64 {
+    values.sort_by(partial_cmp_nan_last);
+    let mut correction = 0.0;
+    let mut start = 0;
+    while start < values.len() {
+        let end = start
+            + values[start..]
+                .iter()
+                .take_while(|&&v| v == values[start])
+                .count();
+        let t = (end - start) as f64;
+        correction += t * t * t - t;
+        start = end;
+    }
+    correction
+}
+
+/// Returns the exact two-sided p-value of a signed-rank statistic of `w`
+/// from `n` differences without ties, counting how many of the `2^n`
+/// ways of giving signs to the ranks `1..=n` have each sum
+#[cfg(feature = "alloc")]
+fn exact_signed_rank_p_value(w: usize, n: usize) -> f64 {
+    let max = n * (n + 1) / 2;
+    let mut ways = vec![0.0; max + 1];
+    ways[0] = 1.0;
+    for rank in 1..=n {
+        for sum in (rank..=max).rev() {
+            ways[sum] += ways[sum - rank];
+        }
+    }
+    let total: f64 = ways.iter().sum();
+    let lower: f64 = ways[..=w].iter().sum();
+    let upper: f64 = ways[w..].iter().sum();
+    (2.0 * lower.min(upper) / total).min(1.0)
+}
+
+/// The result of a [one-way analysis of variance](<https://en.wikipedia.org/wiki/One-way_analysis_of_variance>),
+/// as returned by [`anova_one_way`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Anova {
+    /// The F statistic, which is the mean square between
+    /// the groups divided by the mean square within them
+    pub statistic: f64,
+    /// The p-value, which is the probability of a statistic at least
+    /// this large if every group has the same mean
+    pub p_value: f64,
+    /// Sum of the squared differences between the mean of each
+    /// group and the overall mean, weighted by the size of the group
+    pub between_sum_of_squares: f64,
+    /// Sum of the squared differences between each item and the mean of its group
+    pub within_sum_of_squares: f64,
+    /// Degrees of freedom between the groups, which is the number of groups minus one
+    pub between_degrees_of_freedom: usize,
+    /// Degrees of freedom within the groups, which is the
+    /// number of items minus the number of groups
+    pub within_degrees_of_freedom: usize,
+}
+
+/// Test whether several groups have the same mean with a one-way analysis
+/// of variance, which compares the variation between the means of the groups
+/// with the variation within them. This assumes the groups are normally
+/// distributed with the same variance.
+///
+/// # Examples
+/// ```
+/// use stats_traits::anova_one_way;
+/// let groups = [
+///     vec![6, 8, 4, 5, 3, 4],
+///     vec![8, 12, 9, 11, 6, 8],
+///     vec![13, 9, 11, 8, 7, 12],
+/// ];
+/// let anova = anova_one_way(&groups).unwrap();
+/// assert_eq!(anova.between_sum_of_squares, 84.0);
+/// assert_eq!(anova.within_sum_of_squares, 68.0);
+/// assert_eq!(anova.between_degrees_of_freedom, 2);
+/// assert_eq!(anova.within_degrees_of_freedom, 15);
+/// assert!(anova.p_value < 0.01);
+/// ```
+///
+/// # Errors
+/// Returns an error if there are fewer than two groups, if a group is empty,
+/// if there are no more items than groups, if every group is constant
+/// (`StatsError::ZeroVariance`), or if an item can't be converted to [`f64`]
+pub fn anova_one_way<S>(groups: &[S]) -> Result<Anova>
+where
+    S: IntoIterator + Clone,
+    S::Item: ToPrimitive,
+{
+    if groups.len() < 2 {
+        return Err(StatsError::InsufficientData {
+            required: 2,
+            actual: groups.len(),
+        });
+    }
+    let mut overall = OnlineStats::new();
+    let mut within_sum_of_squares = 0.0;
+    for group in groups {
+        let mut stats = OnlineStats::new();
+        for x in group.clone() {
+            stats.push(item_to_f64(x)?);
+        }
+        within_sum_of_squares += stats.variance()? * stats.count() as f64;
+        overall.merge(&stats);
+    }
+    let count = overall.count();
+    if count <= groups.len() {
+        return Err(StatsError::InsufficientData {
+            required: groups.len() + 1,
+            actual: count,
+        });
+    }
+    if within_sum_of_squares == 0.0 {
+        return Err(StatsError::ZeroVariance);
+    }
+    let between_sum_of_squares = overall.variance()? * count as f64 - within_sum_of_squares;
+    let between_degrees_of_freedom = groups.len() - 1;
+    let within_degrees_of_freedom = count - groups.len();
+    let (d1, d2) = (
+        between_degrees_of_freedom as f64,
+        within_degrees_of_freedom as f64,
+    );
+    let statistic = (between_sum_of_squares / d1) / (within_sum_of_squares / d2);
+    Ok(Anova {
+        statistic,
+        p_value: f_upper_tail(statistic, d1, d2),
+        between_sum_of_squares,
+        within_sum_of_squares,
+        between_degrees_of_freedom,
+        within_degrees_of_freedom,
+    })
+}
+
+/// Calculate the [pooled variance](<https://en.wikipedia.org/wiki/Pooled_variance>)
+/// of several groups, which estimates the variance they share if they come from
+/// populations with the same variance but possibly different means. It is the
+/// average of the sample variances of the groups weighted by their degrees of
+/// freedom, so larger groups count for more, and groups with one item count
+/// for nothing.
+///
+/// # Examples
+/// ```
+/// use stats_traits::pooled_variance;
+/// let groups = [
+///     vec![6, 8, 4, 5, 3, 4],
+///     vec![8, 12, 9, 11, 6, 8],
+///     vec![13, 9, 11, 8, 7, 12],
+/// ];
+/// // the sum of the squared differences from the mean of each group over 18 - 3
+/// assert_eq!(pooled_variance(&groups).unwrap(), 68.0 / 15.0);
+/// ```
+///
+/// # Errors
+/// Returns an error if there are no groups, if a group is empty, if there
+/// are no more items than groups, or if an item can't be converted to [`f64`]
+pub fn pooled_variance<S>(groups: &[S]) -> Result<f64>
+where
+    S: IntoIterator + Clone,
+    S::Item: ToPrimitive,
+{
+    if groups.is_empty() {
+        return Err(StatsError::EmptyCollection);
+    }
+    let mut count = 0;
+    let mut sum_of_squares = 0.0;
+    for group in groups {
+        let mut stats = OnlineStats::new();
+        for x in group.clone() {
+            stats.push(item_to_f64(x)?);
+        }
+        sum_of_squares += stats.variance()? * stats.count() as f64;
+        count += stats.count();
+    }
+    if count <= groups.len() {
+        return Err(StatsError::InsufficientData {
+            required: groups.len() + 1,
+            actual: count,
+        });
+    }
+    Ok(sum_of_squares / (count - groups.len()) as f64)
+}
+
+/// Calculate the pooled standard deviation of several groups,
+/// which is the square root of [`pooled_variance`]
+///
+/// # Examples
+/// ```
+/// use stats_traits::pooled_std_dev;
+/// let groups = [vec![1.0, 2.0, 3.0], vec![5.0, 7.0, 9.0]];
+/// assert_eq!(pooled_std_dev(&groups).unwrap(), 2.5_f64.sqrt());
+/// ```
+///
+/// # Errors
+/// Returns an error under the same conditions as [`pooled_variance`]
+pub fn pooled_std_dev<S>(groups: &[S]) -> Result<f64>
+where
+    S: IntoIterator + Clone,
+    S::Item: ToPrimitive,
+{
+    pooled_variance(groups).map(Float::sqrt)
+}
+
+/// Returns Hartigan's dip of the non-empty `sorted` values, which is the largest
+/// distance between their empirical distribution function and the closest
+/// unimodal distribution function. This is a translation of Hartigan's algorithm
+/// AS 217, with the fixes of the R package diptest, so it is indexed from 1.
+#[cfg(feature = "alloc")]
+pub(crate) fn dip(sorted: &[f64]) -> f64 {
+    let n = sorted.len();
+    let x = |i: usize| sorted[i - 1];
+    // twice n times the dip, until the end
+    let mut dip = 1.0;
+    if n < 2 || x(n) == x(1) {
+        return dip / (2 * n) as f64;
+    }
+
+    // the indices to combine for the greatest convex minorant
+    let mut mn = vec![0; n + 1];
+    mn[1] = 1;
+    for j in 2..=n {
+        mn[j] = j - 1;
+        loop {
+            let mnj = mn[j];
+            let mnmnj = mn[mnj];
+            if mnj == 1
+                || (x(j) - x(mnj)) * ((mnj - mnmnj) as f64)
+                    < (x(mnj) - x(mnmnj)) * ((j - mnj) as f64)
+            {
+                break;
+            }
+            mn[j] = mnmnj;
+        }
+    }
+    // the indices to combine for the least concave majorant
+    let mut mj = vec![0; n + 1];
+    mj[n] = n;
+    for k in (1..n).rev() {
+        mj[k] = k + 1;
+        loop {
+            let mjk = mj[k];
+            let mjmjk = mj[mjk];
+            if mjk == n
+                || (x(k) - x(mjk)) * (mjk as f64 - mjmjk as f64)
+                    < (x(mjk) - x(mjmjk)) * (k as f64 - mjk as f64)
+            {
+                break;
+            }
+            mj[k] = mjmjk;
+        }
+    }
+
+    let mut gcm = vec![0; n + 2];
+    let mut lcm = vec![0; n + 2];
+    let (mut low, mut high) = (1, n);
+    loop {
+        // the change points of the convex minorant from `high` down to `low`
+        gcm[1] = high;
+        let mut i = 1;
+        while gcm[i] > low {
+            gcm[i + 1] = mn[gcm[i]];
+            i += 1;
+        }
+        let gcm_len = i;
+        let (mut ig, mut ix) = (gcm_len, gcm_len - 1);
+        // the change points of the concave majorant from `low` up to `high`
+        lcm[1] = low;
+        let mut i = 1;
+        while lcm[i] < high {
+            lcm[i + 1] = mj[lcm[i]];
+            i += 1;
+        }
+        let lcm_len = i;
+        let (mut ih, mut iv) = (lcm_len, 2);
+
+        // the largest distance between them from `low` to `high`
+        let mut d = 0.0;
+        if gcm_len != 2 || lcm_len != 2 {
+            loop {
+                let (gcm_ix, lcm_iv) = (gcm[ix], lcm[iv]);
+                if gcm_ix > lcm_iv {
+                    let gcm_ix1 = gcm[ix + 1];
+                    let dx = (lcm_iv + 1 - gcm_ix1) as f64
+                        - (x(lcm_iv) - x(gcm_ix1)) * (gcm_ix - gcm_ix1) as f64
+                            / (x(gcm_ix) - x(gcm_ix1));
+                    iv += 1;
+                    if dx >= d {
+                        d = dx;
+                        ig = ix + 1;
+                        ih = iv - 1;
+                    }
+                } else {
+                    let lcm_iv1 = lcm[iv - 1];
+                    let dx = (x(gcm_ix) - x(lcm_iv1)) * (lcm_iv - lcm_iv1) as f64
+                        / (x(lcm_iv) - x(lcm_iv1))
+                        - (gcm_ix - lcm_iv1 - 1) as f64;
+                    ix -= 1;
+                    if dx >= d {
+                        d = dx;
+                        ig = ix + 1;
+                        ih = iv;
+                    }
+                }
+                ix = ix.max(1);
+                iv = iv.min(lcm_len);
+                if gcm[ix] == lcm[iv] {
+                    break;
+                }
+            }
+        } else {
+            d = 1.0;
+        }
+        if d < dip {
+            break;
+        }
+
+        // the dips of the convex minorant and the concave majorant
+        let mut dip_low: f64 = 0.0;
+        for j in ig..gcm_len {
+            let (start, end) = (gcm[j + 1], gcm[j]);
+            let mut max = 1.0_f64;
+            if end - start > 1 && x(end) != x(start) {
+                let c = (end - start) as f64 / (x(end) - x(start));
+                for k in start..=end {
+                    max = max.max((k + 1 - start) as f64 - (x(k) - x(start)) * c);
+                }
+            }
+            dip_low = dip_low.max(max);
+        }
+        let mut dip_high: f64 = 0.0;
+        for j in ih..lcm_len {
+            let (start, end) = (lcm[j], lcm[j + 1]);
+            let mut max = 1.0_f64;
+            if end - start > 1 && x(end) != x(start) {
+                let c = (end - start) as f64 / (x(end) - x(start));
+                for k in start..=end {
+                    max = max.max((x(k) - x(start)) * c - (k as f64 - start as f64 - 1.0));
+                }
+            }
+            dip_high = dip_high.max(max);
+        }
+        dip = dip.max(dip_low).max(dip_high);
+
+        if low == gcm[ig] && high == lcm[ih] {
+            break;
+        }
+        low = gcm[ig];
+        high = lcm[ih];
+    }
+    dip / (2 * n) as f64
+}
+
+/// Returns the difference between the means of `a` and `b` divided by their
+/// pooled standard deviation, multiplied by Hedges' correction for the bias of
+/// the pooled standard deviation in small samples if `hedges` is `true`
+pub(crate) fn cohens_d(a: &OnlineStats, b: &OnlineStats, hedges: bool) -> Result<f64> {
+    let (n_a, mean_a, var_a) = sample_moments(a)?;
+    let (n_b, mean_b, var_b) = sample_moments(b)?;
+    let degrees_of_freedom = n_a + n_b - 2.0;
+    let pooled = ((n_a - 1.0) * var_a + (n_b - 1.0) * var_b) / degrees_of_freedom;
+    if pooled == 0.0 {
+        return Err(StatsError::ZeroVariance);
+    }
+    let d = (mean_a - mean_b) / Float::sqrt(pooled);
+    if !hedges {
+        return Ok(d);
+    }
+    let half = degrees_of_freedom / 2.0;
+    let correction = Float::exp(ln_gamma(half) - ln_gamma(half - 0.5)) / Float::sqrt(half);
+    Ok(d * correction)
+}
+
+/// Returns the count, mean and sample variance of the values, which
+/// needs at least two of them
+fn sample_moments(stats: &OnlineStats) -> Result<(f64, f64, f64)> {
+    let count = stats.count();
+    if count < 2 {
+        return Err(StatsError::InsufficientData {
+            required: 2,
+            actual: count,
+        });
+    }
+    let n = count as f64;
+    Ok((n, stats.mean()?, stats.variance()? * n / (n - 1.0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use approx::assert_relative_eq;
+
+    const A: [f64; 10] = [19.8, 20.4, 19.6, 17.8, 18.5, 18.9, 18.3, 18.9, 19.5, 22.0];
+    const B: [f64; 20] = [
+        28.2, 26.6, 20.1, 23.3, 25.2, 22.1, 17.7, 27.6, 20.6, 13.7, 23.2, 17.5, 20.6, 18.0, 23.9,
+        21.6, 24.3, 20.4, 23.9, 13.3,
+    ];
+
+    #[test]
+    fn test_welch() {
+        let a: OnlineStats = A.into_iter().collect();
+        let b: OnlineStats = B.into_iter().collect();
+        let test = TTest::welch(&a, &b).unwrap();
+        // the p-value was found by integrating the density of the t distribution
+        assert_relative_eq!(test.statistic, -2.2255120399698485, epsilon = 1e-12);
+        assert_relative_eq!(test.degrees_of_freedom, 24.524634944257343, epsilon = 1e-9);
+        assert_relative_eq!(test.p_value, 0.03548453083001548, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn test_pooled() {
+        let a: OnlineStats = A.into_iter().collect();
+        let b: OnlineStats = B.into_iter().collect();
+        let test = TTest::pooled(&a, &b).unwrap();
+        assert_relative_eq!(test.statistic, -1.6544465858663975, epsilon = 1e-12);
+        assert_eq!(test.degrees_of_freedom, 28.0);
+        assert_relative_eq!(test.p_value, 0.10920550418097455, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn test_symmetric() {
+        let a: OnlineStats = A.into_iter().collect();
+        let b: OnlineStats = B.into_iter().collect();
+        let forwards = TTest::welch(&a, &b).unwrap();
+        let backwards = TTest::welch(&b, &a).unwrap();
+        assert_eq!(forwards.statistic, -backwards.statistic);
+        assert_eq!(forwards.p_value, backwards.p_value);
+    }
+
+    #[test]
+    fn test_errors() {
+        let one: OnlineStats = [1.0].into_iter().collect();
+        let constant: OnlineStats = [2.0, 2.0, 2.0].into_iter().collect();
+        assert_eq!(
+            TTest::welch(&one, &constant),
+            Err(StatsError::InsufficientData {
+                required: 2,
+                actual: 1
+            })
+        );
+        assert_eq!(
+            TTest::pooled(&constant, &constant),
+            Err(StatsError::ZeroVariance)
+        );
+    }
+
+    #[test]
+    fn test_proportion() {
+        let test = proportion_z_test(60, 100, 0.5, 0.99).unwrap();
+        assert_relative_eq!(test.statistic, 2.0, epsilon = 1e-12);
+        assert_relative_eq!(test.p_value, 0.04550026389635847, epsilon = 1e-12);
+        assert_relative_eq!(
+            test.confidence_interval.0,
+            -0.02618934916406737,
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(
+            test.confidence_interval.1,
+            0.22618934916406733,
+            epsilon = 1e-12
+        );
+        assert_eq!(
+            proportion_z_test(0, 0, 0.5, 0.95),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+
+    #[test]
+    fn test_two_proportions() {
+        let test = two_proportion_z_test(45, 100, 30, 100, 0.95).unwrap();
+        assert_relative_eq!(test.statistic, 2.1908902300206647, epsilon = 1e-12);
+        assert_relative_eq!(test.p_value, 0.02845973691631057, epsilon = 1e-12);
+        assert_relative_eq!(test.difference, 0.15, epsilon = 1e-12);
+        assert_relative_eq!(
+            test.confidence_interval.0,
+            0.017430493307564854,
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(
+            test.confidence_interval.1,
+            0.2825695066924352,
+            epsilon = 1e-12
+        );
+        assert_eq!(
+            two_proportion_z_test(0, 10, 0, 20, 0.95),
+            Err(StatsError::ZeroVariance)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_more_successes_than_trials() {
+        let _ = two_proportion_z_test(11, 10, 3, 10, 0.95);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_signed_rank_exact() {
+        let differences = [0.952, -0.147, 1.022, 0.43, 0.62, 0.59, 0.49, -0.08, 0.01];
+        let test = SignedRankTest::from_differences(&differences).unwrap();
+        assert_eq!(test.statistic, 40.0);
+        // 10 of the 512 ways of giving signs to the ranks are at least as extreme
+        assert_relative_eq!(test.p_value, 20.0 / 512.0, epsilon = 1e-15);
+        assert!(test.exact);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_signed_rank_zeros_and_ties() {
+        let differences = [15.0, -7.0, 5.0, 20.0, 0.0, -9.0, 17.0, -12.0, 5.0, -10.0];
+        let test = SignedRankTest::from_differences(&differences).unwrap();
+        assert_eq!(test.statistic, 27.0);
+        assert_relative_eq!(test.p_value, 0.5936305914425295, epsilon = 1e-12);
+        assert!(!test.exact);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_signed_rank_errors() {
+        assert_eq!(
+            SignedRankTest::from_differences(&[]),
+            Err(StatsError::EmptyCollection)
+        );
+        assert_eq!(
+            SignedRankTest::from_differences(&[0.0, 0.0]),
+            Err(StatsError::ZeroVariance)
+        );
+    }
+
+    #[test]
+    fn test_anova_one_way() {
+        let groups = [
+            [6.0, 8.0, 4.0, 5.0, 3.0, 4.0],
+            [8.0, 12.0, 9.0, 11.0, 6.0, 8.0],
+            [13.0, 9.0, 11.0, 8.0, 7.0, 12.0],
+        ];
+        let anova = anova_one_way(&groups).unwrap();
+        assert_relative_eq!(anova.between_sum_of_squares, 84.0, epsilon = 1e-12);
+        assert_relative_eq!(anova.within_sum_of_squares, 68.0, epsilon = 1e-12);
+        assert_relative_eq!(anova.statistic, 9.264705882352942, epsilon = 1e-12);
+        // found by integrating the density of the F distribution
+        assert_relative_eq!(anova.p_value, 0.002398777329393306, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_anova_errors() {
+        assert_eq!(
+            anova_one_way(&[[1.0, 2.0]]),
+            Err(StatsError::InsufficientData {
+                required: 2,
+                actual: 1
+            })
+        );
+        assert_eq!(
+            anova_one_way(&[0..2, 0..0]),
+            Err(StatsError::EmptyCollection)
+        );
+        assert_eq!(
+            anova_one_way(&[[1.0], [2.0]]),
+            Err(StatsError::InsufficientData {
+                required: 3,
+                actual: 2
+            })
+        );
+        assert_eq!(
+            anova_one_way(&[[1.0, 1.0], [2.0, 2.0]]),
+            Err(StatsError::ZeroVariance)
+        );
+    }
+
+    #[test]
+    fn test_f_test() {
+        let a: OnlineStats = A.into_iter().collect();
+        let b: OnlineStats = B.into_iter().collect();
+        let test = FTest::new(&a, &b).unwrap();
+        assert_relative_eq!(test.statistic, 0.08521999145664243, epsilon = 1e-12);
+        assert_eq!(test.degrees_of_freedom, (9, 19));
+        // found by integrating the density of the F distribution
+        assert_relative_eq!(test.p_value, 0.0006777367805459195, epsilon = 1e-12);
+        let backwards = FTest::new(&b, &a).unwrap();
+        assert_relative_eq!(backwards.p_value, test.p_value, epsilon = 1e-12);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_levene_and_brown_forsythe() {
+        let groups = [
+            [8.88, 9.12, 9.04, 8.98, 9.00, 9.08, 9.01, 8.85, 9.06, 8.99],
+            [8.88, 8.95, 9.29, 9.44, 9.15, 9.58, 8.36, 9.18, 8.67, 9.05],
+            [8.95, 9.12, 8.95, 8.85, 9.03, 8.84, 9.07, 8.98, 8.86, 8.98],
+        ];
+        let levene = levene_test(&groups).unwrap();
+        assert_relative_eq!(levene.statistic, 7.905194483442053, epsilon = 1e-9);
+        assert_relative_eq!(levene.p_value, 0.0019837958174731656, epsilon = 1e-9);
+        let brown_forsythe = brown_forsythe_test(&groups).unwrap();
+        assert_relative_eq!(brown_forsythe.statistic, 7.584952754501659, epsilon = 1e-9);
+        assert_relative_eq!(
+            brown_forsythe.p_value,
+            0.0024315059672502535,
+            epsilon = 1e-9
+        );
+        assert_eq!(brown_forsythe.within_degrees_of_freedom, 27);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_kolmogorov_smirnov() {
+        let sorted = [0.1, 0.3, 0.35, 0.5, 0.52, 0.9];
+        let test = TestResult::kolmogorov_smirnov(&sorted, |x| x.clamp(0.0, 1.0));
+        assert_relative_eq!(test.statistic, 0.31333333333333335, epsilon = 1e-15);
+        assert_relative_eq!(test.p_value, 0.5132788142758465, epsilon = 1e-12);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_kolmogorov_smirnov_two_sample() {
+        let a = [1.1, 1.2, 2.2, 2.9, 3.3, 3.3, 3.4, 4.4, 5.1, 6.0];
+        let b = [2.5, 3.3, 4.9, 5.5, 6.1, 6.6, 7.1, 7.7, 8.2];
+        let test = TestResult::kolmogorov_smirnov_two_sample(&a, &b);
+        assert_relative_eq!(test.statistic, 0.5777777777777778, epsilon = 1e-15);
+        assert_relative_eq!(test.p_value, 0.050565343120271895, epsilon = 1e-12);
+        let same = TestResult::kolmogorov_smirnov_two_sample(&a, &a);
+        assert_eq!(same.statistic, 0.0);
+        assert_eq!(same.p_value, 1.0);
+    }
+
+    #[test]
+    fn test_jarque_bera() {
+        let stats: OnlineStats = [2.1, 3.4, 1.9, 5.6, 2.2, 2.8, 3.1, 9.7, 2.5, 3.0, 2.7, 4.1]
+            .into_iter()
+            .collect();
+        let test = TestResult::jarque_bera(&stats).unwrap();
+        assert_relative_eq!(test.statistic, 14.186693851164499, epsilon = 1e-12);
+        assert_relative_eq!(test.p_value, 0.0008306127092618027, epsilon = 1e-14);
+        let constant: OnlineStats = [1.0, 1.0].into_iter().collect();
+        assert_eq!(
+            TestResult::jarque_bera(&constant),
+            Err(StatsError::ZeroVariance)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_shapiro_wilk() {
+        // the expected values are from a direct transcription of AS R94
+        let cases: [(&[f64], f64, f64); 4] = [
+            (&[1.0, 2.0, 4.0], 0.9642857142857146, 0.6368868450289714),
+            (
+                &[2.4, 3.1, 3.3, 4.2, 5.0],
+                0.9693350834822431,
+                0.8709690843695692,
+            ),
+            (
+                &[
+                    148.0, 154.0, 158.0, 160.0, 161.0, 162.0, 166.0, 170.0, 182.0, 195.0, 236.0,
+                ],
+                0.7888146948353878,
+                0.006703814056502999,
+            ),
+            (
+                &[1.9, 2.1, 2.2, 2.5, 2.7, 2.8, 3.0, 3.1, 3.4, 4.1, 5.6, 9.7],
+                0.7093245474667069,
+                0.0010377481980716752,
+            ),
+        ];
+        for (sorted, statistic, p_value) in cases {
+            let test = TestResult::shapiro_wilk(sorted).unwrap();
+            assert_relative_eq!(test.statistic, statistic, epsilon = 1e-12);
+            assert_relative_eq!(test.p_value, p_value, epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_shapiro_wilk_errors() {
+        assert_eq!(
+            TestResult::shapiro_wilk(&[1.0, 2.0]),
+            Err(StatsError::InsufficientData {
+                required: 3,
+                actual: 2
+            })
+        );
+        assert_eq!(
+            TestResult::shapiro_wilk(&[3.0, 3.0, 3.0, 3.0]),
+            Err(StatsError::ZeroVariance)
+        );
+    }
+
+    #[test]
+    fn test_sign_test() {
+        let test = TestResult::sign_test(10, 2, 13).unwrap();
+        assert_eq!(test.statistic, 10.0);
+        assert_relative_eq!(test.p_value, 0.03857421875, epsilon = 1e-14);
+        assert_eq!(TestResult::sign_test(6, 6, 12).unwrap().p_value, 1.0);
+        assert_eq!(
+            TestResult::sign_test(0, 0, 3),
+            Err(StatsError::ZeroVariance)
+        );
+        assert_eq!(
+            TestResult::sign_test(0, 0, 0),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_runs_test() {
+        // alternating items have as many runs as possible
+        let test = TestResult::runs_test([true, false].into_iter().cycle().take(20)).unwrap();
+        assert_eq!(test.statistic, 20.0);
+        assert!(test.p_value < 1e-4);
+        assert_eq!(
+            TestResult::runs_test([true, true]),
+            Err(StatsError::ZeroVariance)
+        );
+        assert_eq!(TestResult::runs_test([]), Err(StatsError::EmptyCollection));
+    }
+
+    #[test]
+    fn test_wilson_interval() {
+        let cases = [
+            (7, 20, 0.1811918241010821, 0.5671457233147637),
+            (0, 10, 0.0, 0.27753279986288915),
+            (10, 10, 0.7224672001371109, 1.0),
+            (1, 50, 0.0035392592716462293, 0.1049544358963781),
+        ];
+        for (successes, trials, lower, upper) in cases {
+            let interval = wilson_interval(successes, trials, 0.95).unwrap();
+            assert_relative_eq!(interval.0, lower, epsilon = 1e-12);
+            assert_relative_eq!(interval.1, upper, epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_clopper_pearson_interval() {
+        // found by solving for the binomial tail probabilities directly
+        let cases = [
+            (7, 20, 0.15390920478454112, 0.5921885345328282),
+            (0, 10, 0.0, 0.3084971078187607),
+            (10, 10, 0.6915028921812392, 1.0),
+            (1, 50, 0.0005062279830408412, 0.10646954571149986),
+        ];
+        for (successes, trials, lower, upper) in cases {
+            let interval = clopper_pearson_interval(successes, trials, 0.95).unwrap();
+            assert_relative_eq!(interval.0, lower, epsilon = 1e-12);
+            assert_relative_eq!(interval.1, upper, epsilon = 1e-12);
+        }
+        assert_eq!(
+            clopper_pearson_interval(0, 0, 0.95),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+
+    #[test]
+    fn test_poisson_rate_interval() {
+        // found by solving for the Poisson tail probabilities directly
+        let cases = [
+            (5, 0.95, 1.6234863901184227, 11.668332079322667),
+            (0, 0.95, 0.0, 3.688879454113935),
+            (40, 0.99, 25.585965945222803, 59.36306720681712),
+            (1, 0.9, 0.05129329438755052, 4.743864518390579),
+        ];
+        for (events, confidence, lower, upper) in cases {
+            let interval = poisson_rate_interval(events, 1.0, confidence);
+            assert_relative_eq!(interval.0, lower, epsilon = 1e-10);
+            assert_relative_eq!(interval.1, upper, epsilon = 1e-10);
+        }
+        let (lower, upper) = poisson_rate_interval(5, 100.0, 0.95);
+        assert_relative_eq!(lower, 0.016234863901184227, epsilon = 1e-12);
+        assert_relative_eq!(upper, 0.11668332079322667, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_cohens_d() {
+        let a: OnlineStats = [12.1, 11.8, 12.4, 12.0, 11.9, 12.2].into_iter().collect();
+        let b: OnlineStats = [12.6, 12.9, 12.3, 13.1, 12.8].into_iter().collect();
+        assert_relative_eq!(
+            cohens_d(&a, &b, false).unwrap(),
+            -2.5962952256394947,
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(
+            cohens_d(&a, &b, true).unwrap(),
+            -2.3726890183928138,
+            epsilon = 1e-12
+        );
+        let constant: OnlineStats = [1.0, 1.0].into_iter().collect();
+        assert_eq!(
+            cohens_d(&constant, &constant, false),
+            Err(StatsError::ZeroVariance)
+        );
+    }
+
+    #[test]
+    fn test_pooled_variance() {
+        let groups = [0..3, 9..11, 7..8];
+        // the group with one item adds no degrees of freedom
+        assert_relative_eq!(
+            pooled_variance(&groups).unwrap(),
+            2.5 / 3.0,
+            epsilon = 1e-12
+        );
+        let no_groups: [core::ops::Range<u8>; 0] = [];
+        assert_eq!(
+            pooled_variance(&no_groups),
+            Err(StatsError::EmptyCollection)
+        );
+        assert_eq!(
+            pooled_std_dev(&[0..1, 3..4]),
+            Err(StatsError::InsufficientData {
+                required: 3,
+                actual: 2
+            })
+        );
+        assert_eq!(
+            pooled_variance(&[0..2, 0..0]),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+
+    #[test]
+    fn test_grubbs() {
+        let values = [
+            199.31, 199.53, 200.19, 200.82, 201.92, 201.95, 202.18, 245.57,
+        ];
+        let stats: OnlineStats = values.into_iter().collect();
+        let test = GrubbsTest::new(&stats, 7, 245.57, Tail::Both, 0.05).unwrap();
+        // the critical value and p-value were found with the t distribution in mpmath
+        assert_relative_eq!(test.statistic, 2.4687646112124505, epsilon = 1e-12);
+        assert_relative_eq!(test.critical_value, 2.1266450871954653, epsilon = 1e-8);
+        assert_relative_eq!(test.p_value, 3.002638682070797e-7, max_relative = 1e-6);
+        assert!(test.is_outlier);
+        let upper = GrubbsTest::new(&stats, 7, 245.57, Tail::Upper, 0.05).unwrap();
+        assert_relative_eq!(upper.critical_value, 2.0316520015499436, epsilon = 1e-8);
+        assert_relative_eq!(upper.p_value, test.p_value / 2.0, max_relative = 1e-12);
+        let lower = GrubbsTest::new(&stats, 0, 199.31, Tail::Lower, 0.05).unwrap();
+        assert_eq!(lower.p_value, 1.0);
+        assert!(!lower.is_outlier);
+    }
+
+    #[test]
+    fn test_grubbs_critical_value_has_p_value_alpha() {
+        let stats: OnlineStats = A.into_iter().collect();
+        let test = GrubbsTest::new(&stats, 9, 22.0, Tail::Both, 0.05).unwrap();
+        let mean = stats.mean().unwrap();
+        let std_dev = (stats.variance().unwrap() * 10.0 / 9.0).sqrt();
+        let at_critical = mean + test.critical_value * std_dev;
+        let test = GrubbsTest::new(&stats, 9, at_critical, Tail::Both, 0.05).unwrap();
+        assert_relative_eq!(test.p_value, 0.05, epsilon = 1e-9);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_dip() {
+        // evenly spaced values have the smallest possible dip
+        let even: Vec<f64> = (1..=10).map(f64::from).collect();
+        assert_relative_eq!(dip(&even), 0.05, epsilon = 1e-15);
+        let clusters = [0.0, 0.01, 0.02, 0.03, 0.04, 1.0, 1.01, 1.02, 1.03, 1.04];
+        assert_relative_eq!(dip(&clusters), 0.24, epsilon = 1e-12);
+        assert_eq!(dip(&[3.0, 3.0, 3.0]), 1.0 / 6.0);
+        // the dip doesn't change when the values are reflected
+        let skewed = [0.1, 0.4, 0.5, 0.55, 0.6, 1.9, 2.0, 2.2, 2.7, 4.1, 4.2, 7.3];
+        let mut reflected: Vec<f64> = skewed.iter().map(|x| -x).collect();
+        reflected.reverse();
+        // the same as a translation of the algorithm to Python
+        assert_relative_eq!(dip(&skewed), 0.10317460317460315, epsilon = 1e-12);
+        assert_relative_eq!(dip(&reflected), 0.10317460317460315, epsilon = 1e-12);
+    }
+}
@@ -0,0 +1,156 @@
+use core::time::Duration;
+
+use alloc::vec::Vec;
+
+use crate::DurationStats;
+use crate::Result;
+use crate::StatsError;
+
+/// Statistics of the gaps between consecutive timestamps, such as the times
+/// of requests in a log, created by [`InterArrival::from_timestamps`] or
+/// [`DurationStats::inter_arrival`].
+///
+/// # Examples
+/// ```
+/// use stats_traits::InterArrival;
+/// // nanoseconds since the epoch
+/// let timestamps = [1_000, 1_010, 1_020, 1_030, 1_070];
+/// let gaps = InterArrival::from_nanos(timestamps).unwrap();
+/// assert_eq!(gaps.count, 4);
+/// assert_eq!(gaps.mean.as_nanos(), 17);
+/// assert_eq!(gaps.median.as_nanos(), 10);
+/// assert_eq!(gaps.max.as_nanos(), 40);
+/// assert!(gaps.burstiness < 0.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InterArrival {
+    /// Number of gaps, which is one less than the number of timestamps
+    pub count: usize,
+    /// Mean gap, rounded down to the nanosecond
+    pub mean: Duration,
+    /// Median gap
+    pub median: Duration,
+    /// 99th percentile of the gaps
+    pub p99: Duration,
+    /// Longest gap
+    pub max: Duration,
+    /// Jitter, which is the (population) standard deviation of the gaps
+    pub jitter: Duration,
+    /// [Burstiness](<https://doi.org/10.1209/0295-5075/81/48002>) of the
+    /// timestamps, which is the standard deviation of the gaps minus their
+    /// mean, divided by their sum. It is -1 for evenly spaced timestamps,
+    /// around 0 for random ones like a Poisson process, and close to 1 for
+    /// timestamps that come in bursts.
+    pub burstiness: f64,
+}
+
+impl InterArrival {
+    /// Calculate the statistics of the gaps between `timestamps`, which are
+    /// the times since an epoch, such as the Unix epoch
+    ///
+    /// # Errors
+    /// Returns `StatsError::InsufficientData` if there are fewer than two
+    /// timestamps, `StatsError::NotSorted` if a timestamp is before the one
+    /// preceding it, and `StatsError::ZeroVariance` if they are all the same
+    pub fn from_timestamps<I>(timestamps: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = Duration>,
+    {
+        let mut timestamps = timestamps.into_iter();
+        let Some(mut previous) = timestamps.next() else {
+            return Err(StatsError::InsufficientData {
+                required: 2,
+                actual: 0,
+            });
+        };
+        let gaps = timestamps
+            .map(|x| {
+                let gap = x.checked_sub(previous).ok_or(StatsError::NotSorted);
+                previous = x;
+                gap
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if gaps.is_empty() {
+            return Err(StatsError::InsufficientData {
+                required: 2,
+                actual: 1,
+            });
+        }
+        let online = gaps.online_stats();
+        let (mean, std_dev) = (online.mean()?, online.std_dev()?);
+        if mean == 0.0 {
+            return Err(StatsError::ZeroVariance);
+        }
+        Ok(Self {
+            count: gaps.len(),
+            mean: DurationStats::mean(&gaps)?,
+            median: gaps.median()?,
+            p99: gaps.quantile(0.99)?,
+            max: DurationStats::max(&gaps)?,
+            jitter: DurationStats::std_dev(&gaps)?,
+            burstiness: (std_dev - mean) / (std_dev + mean),
+        })
+    }
+
+    /// Calculate the statistics of the gaps between timestamps given in
+    /// nanoseconds since an epoch, like [`InterArrival::from_timestamps`]
+    ///
+    /// # Errors
+    /// Errors under the same conditions as [`InterArrival::from_timestamps`]
+    pub fn from_nanos<I>(timestamps: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = u64>,
+    {
+        Self::from_timestamps(timestamps.into_iter().map(Duration::from_nanos))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_evenly_spaced() {
+        let timestamps = (0..200).map(|x| Duration::from_millis(5 * x));
+        let gaps = InterArrival::from_timestamps(timestamps).unwrap();
+        assert_eq!(gaps.count, 199);
+        assert_eq!(gaps.mean, Duration::from_millis(5));
+        assert_eq!(gaps.p99, Duration::from_millis(5));
+        assert_eq!(gaps.jitter, Duration::ZERO);
+        assert_relative_eq!(gaps.burstiness, -1.0);
+    }
+
+    #[test]
+    fn test_bursts() {
+        // bursts of 10 requests 1µs apart, every second
+        let timestamps = (0..10u64)
+            .flat_map(|burst| (0..10).map(move |x| burst * 1_000_000_000 + x * 1_000))
+            .collect::<Vec<_>>();
+        let gaps = InterArrival::from_nanos(timestamps).unwrap();
+        assert_eq!(gaps.median, Duration::from_micros(1));
+        assert_eq!(gaps.max, Duration::from_nanos(999_991_000));
+        assert!(gaps.burstiness > 0.5);
+    }
+
+    #[test]
+    fn test_invalid_timestamps() {
+        assert_eq!(
+            InterArrival::from_nanos([1]),
+            Err(StatsError::InsufficientData {
+                required: 2,
+                actual: 1
+            })
+        );
+        assert_eq!(
+            InterArrival::from_nanos([1, 3, 2]),
+            Err(StatsError::NotSorted)
+        );
+        assert_eq!(
+            InterArrival::from_nanos([7, 7, 7]),
+            Err(StatsError::ZeroVariance)
+        );
+    }
+}
@@ -0,0 +1,351 @@
+use core::iter::FusedIterator;
+
+use num_traits::ToPrimitive;
+
+use crate::helpers::item_to_f64;
+#[cfg(feature = "alloc")]
+use crate::Aggregate;
+#[cfg(feature = "alloc")]
+use crate::Downsample;
+use crate::OnlineStats;
+use crate::Result;
+#[cfg(feature = "alloc")]
+use crate::RollingZScores;
+use crate::Summary;
+
+/// An extension trait for [`Iterator`]s that provides statistical methods
+/// which consume the iterator, so it works on iterators that can't be
+/// cloned, such as ones that generate their items lazily or read them
+/// from a file. Each method goes through the items once, converting
+/// them to [`f64`] and pushing them to an [`OnlineStats`].
+///
+/// The methods are prefixed with `stats_` so that they don't clash with
+/// the methods of [`Iterator`], like [`Iterator::max`]. Use [`Stats`](crate::Stats)
+/// for collections, which has many more methods.
+///
+/// # Examples
+/// ```
+/// use stats_traits::IteratorStats;
+/// let squares = (1..=4).map(|x| x * x);
+/// assert_eq!(squares.stats_mean(), Ok(7.5));
+/// let summary = (1..=4).map(|x| x * x).stats_summary().unwrap();
+/// assert_eq!(summary.max, 16.0);
+/// ```
+pub trait IteratorStats: Iterator + Sized
+where
+    Self::Item: ToPrimitive,
+{
+    /// Push every item to an [`OnlineStats`], which has all the statistics
+    /// of the items, for when more than one of them is needed
+    ///
+    /// # Errors
+    /// Returns an error if an item can't be converted to [`f64`]
+    fn stats_online(self) -> Result<OnlineStats> {
+        let mut stats = OnlineStats::new();
+        for x in self {
+            stats.push(item_to_f64(x)?);
+        }
+        Ok(stats)
+    }
+
+    /// Calculate the mean of the items
+    ///
+    /// # Errors
+    /// Returns an error if there are no items, or if an item can't be converted to [`f64`]
+    fn stats_mean(self) -> Result<f64> {
+        self.stats_online()?.mean()
+    }
+
+    /// Calculate the (population) variance of the items.
+    /// See [`Stats::variance`](crate::Stats::variance).
+    ///
+    /// # Errors
+    /// Returns an error if there are no items, or if an item can't be converted to [`f64`]
+    fn stats_variance(self) -> Result<f64> {
+        self.stats_online()?.variance()
+    }
+
+    /// Calculate the standard deviation of the items, which is
+    /// the square root of [`IteratorStats::stats_variance`]
+    ///
+    /// # Errors
+    /// Returns an error if there are no items, or if an item can't be converted to [`f64`]
+    fn stats_std_dev(self) -> Result<f64> {
+        self.stats_online()?.std_dev()
+    }
+
+    /// Calculate the (population) skewness of the items.
+    /// See [`OnlineStats::skewness`].
+    ///
+    /// # Errors
+    /// Returns an error if there are no items, or if an item can't be converted to [`f64`]
+    fn stats_skewness(self) -> Result<f64> {
+        self.stats_online()?.skewness()
+    }
+
+    /// Calculate the (population) excess kurtosis of the items.
+    /// See [`OnlineStats::kurtosis`].
+    ///
+    /// # Errors
+    /// Returns an error if there are no items, or if an item can't be converted to [`f64`]
+    fn stats_kurtosis(self) -> Result<f64> {
+        self.stats_online()?.kurtosis()
+    }
+
+    /// Find the smallest item
+    ///
+    /// # Errors
+    /// Returns an error if there are no items, or if an item can't be converted to [`f64`]
+    fn stats_min(self) -> Result<f64> {
+        self.stats_online()?.min()
+    }
+
+    /// Find the largest item
+    ///
+    /// # Errors
+    /// Returns an error if there are no items, or if an item can't be converted to [`f64`]
+    fn stats_max(self) -> Result<f64> {
+        self.stats_online()?.max()
+    }
+
+    /// Find the range of the items (the smallest subtracted from the largest)
+    ///
+    /// # Errors
+    /// Returns an error if there are no items, or if an item can't be converted to [`f64`]
+    fn stats_range(self) -> Result<f64> {
+        let stats = self.stats_online()?;
+        Ok(stats.max()? - stats.min()?)
+    }
+
+    /// Calculate a [`Summary`] of the items
+    ///
+    /// # Errors
+    /// Returns an error if there are no items, or if an item can't be converted to [`f64`]
+    fn stats_summary(self) -> Result<Summary> {
+        self.stats_online()?.summary()
+    }
+
+    /// Wrap the iterator in one that yields a [`Summary`] of the items so
+    /// far after each item, with the count, mean, variance, smallest and
+    /// largest, for progress displays or to watch a simulation converge.
+    /// The items are pushed to an [`OnlineStats`] as they go by, so it
+    /// takes constant time and memory per item.
+    ///
+    /// An item that can't be converted to [`f64`] yields an error instead,
+    /// and isn't included in the statistics of the items after it.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::IteratorStats;
+    /// let means: Vec<f64> = [4, 2, 6, 8]
+    ///     .into_iter()
+    ///     .running_stats()
+    ///     .map(|summary| summary.unwrap().mean)
+    ///     .collect();
+    /// assert_eq!(means, [4.0, 3.0, 4.0, 5.0]);
+    /// ```
+    fn running_stats(self) -> RunningStats<Self> {
+        RunningStats {
+            iter: self,
+            stats: OnlineStats::new(),
+        }
+    }
+
+    /// Wrap the iterator in one that scores each item against a
+    /// [`RollingStats`](crate::RollingStats) window of the `window_size`
+    /// items before it, yielding its z-score (how many standard deviations
+    /// it is from the mean of the window) and whether that is more than
+    /// `threshold` in either direction, to flag anomalies in a stream.
+    ///
+    /// The item being scored isn't in the window, so a spike doesn't hide
+    /// itself, but it joins the window afterwards. Until the window is
+    /// full the z-scores are `NaN` and nothing is flagged.
+    ///
+    /// An item that can't be converted to [`f64`] yields an error instead,
+    /// and doesn't join the window. `NaN`s and infinities are scored (so an
+    /// infinity is flagged once the window is full), but don't join the
+    /// window either, so they can't stop later items from being scored.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::IteratorStats;
+    /// let latencies = [20, 22, 19, 21, 20, 95, 21, 20];
+    /// let anomalies: Vec<usize> = latencies
+    ///     .into_iter()
+    ///     .rolling_z_scores(4, 3.0)
+    ///     .enumerate()
+    ///     .filter(|(_, point)| point.unwrap().is_anomaly)
+    ///     .map(|(i, _)| i)
+    ///     .collect();
+    /// assert_eq!(anomalies, [5]);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `window_size` is 0, or if `threshold` is negative or `NaN`
+    #[cfg(feature = "alloc")]
+    fn rolling_z_scores(self, window_size: usize, threshold: f64) -> RollingZScores<Self> {
+        RollingZScores::new(self, window_size, threshold)
+    }
+
+    /// Wrap the iterator in one that groups the items into consecutive
+    /// blocks of `block_size` and reduces each block to one value with
+    /// `aggregate`, such as its mean or largest item, to decimate a long
+    /// time series before charting it. The last block is shorter if the
+    /// number of items isn't a multiple of `block_size`.
+    ///
+    /// A block with an item that can't be converted to [`f64`] yields an
+    /// error instead.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::{Aggregate, IteratorStats};
+    /// let readings = [1, 3, 2, 8, 6, 4, 5];
+    /// let peaks: Vec<f64> = readings
+    ///     .into_iter()
+    ///     .downsample(3, Aggregate::Max)
+    ///     .collect::<Result<_, _>>()
+    ///     .unwrap();
+    /// assert_eq!(peaks, [3.0, 8.0, 5.0]);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `block_size` is 0
+    #[cfg(feature = "alloc")]
+    fn downsample(self, block_size: usize, aggregate: Aggregate) -> Downsample<Self> {
+        Downsample::new(self, block_size, aggregate)
+    }
+}
+
+/// Blanket implementation for all [`Iterator`]s whose items can be converted to [`f64`]
+impl<I> IteratorStats for I
+where
+    I: Iterator,
+    I::Item: ToPrimitive,
+{
+}
+
+/// An iterator that yields a [`Summary`] of the items of another one so far
+/// after each of them. It is created by [`IteratorStats::running_stats`].
+#[derive(Debug, Clone)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct RunningStats<I> {
+    iter: I,
+    stats: OnlineStats,
+}
+
+impl<I> RunningStats<I> {
+    /// Returns the statistics of the items so far, which can also be merged
+    /// or checkpointed with [`OnlineStats::to_bytes`]
+    pub fn stats(&self) -> &OnlineStats {
+        &self.stats
+    }
+}
+
+impl<I> Iterator for RunningStats<I>
+where
+    I: Iterator,
+    I::Item: ToPrimitive,
+{
+    type Item = Result<Summary>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let x = self.iter.next()?;
+        Some(item_to_f64(x).and_then(|x| {
+            self.stats.push(x);
+            self.stats.summary()
+        }))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I> ExactSizeIterator for RunningStats<I>
+where
+    I: ExactSizeIterator,
+    I::Item: ToPrimitive,
+{
+}
+
+impl<I> FusedIterator for RunningStats<I>
+where
+    I: FusedIterator,
+    I::Item: ToPrimitive,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StatsError;
+
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_one_shot_iterator() {
+        // `from_fn` can only be iterated over once, so it can't be used with `Stats`
+        let mut n = 0;
+        let counter = core::iter::from_fn(|| {
+            n += 1;
+            (n <= 5).then_some(n)
+        });
+        let summary = counter.stats_summary().unwrap();
+        assert_eq!(summary.count, 5);
+        assert_eq!(summary.mean, 3.0);
+        assert_eq!(summary.variance, 2.0);
+        assert_eq!(summary.min, 1.0);
+        assert_eq!(summary.max, 5.0);
+    }
+
+    #[test]
+    fn test_methods() {
+        let values = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+        assert_relative_eq!(
+            values.iter().copied().stats_std_dev().unwrap(),
+            2.0,
+            epsilon = 1e-12
+        );
+        assert_eq!(values.into_iter().stats_range(), Ok(7.0));
+        assert_eq!([1, 2, 3].into_iter().stats_skewness(), Ok(0.0));
+        assert_eq!(
+            core::iter::empty::<f64>().stats_mean(),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+
+    #[test]
+    fn test_running_stats() {
+        let values = [3.0, 1.0, f64::NAN, 5.0];
+        let mut running = values.into_iter().running_stats();
+        assert_eq!(running.len(), 4);
+        let first = running.next().unwrap().unwrap();
+        assert_eq!((first.count, first.mean, first.variance), (1, 3.0, 0.0));
+        let second = running.next().unwrap().unwrap();
+        assert_eq!((second.min, second.max, second.variance), (1.0, 3.0, 1.0));
+        assert_eq!(running.stats().count(), 2);
+        let last = running.last().unwrap().unwrap();
+        assert_eq!(last.count, 4);
+        assert!(last.mean.is_nan());
+
+        let third = [1u64, 2, 3].into_iter().running_stats().nth(2);
+        assert_eq!(third.unwrap().unwrap().mean, 2.0);
+        // an item that only converts when it is present
+        struct Maybe(Option<i64>);
+        impl ToPrimitive for Maybe {
+            fn to_i64(&self) -> Option<i64> {
+                self.0
+            }
+            fn to_u64(&self) -> Option<u64> {
+                self.0.and_then(|x| x.try_into().ok())
+            }
+        }
+        let mut unconvertible = [Maybe(Some(1)), Maybe(None)].into_iter().running_stats();
+        assert!(unconvertible.next().unwrap().is_ok());
+        assert!(matches!(
+            unconvertible.next(),
+            Some(Err(StatsError::CouldNotConvert { .. }))
+        ));
+        assert_eq!(unconvertible.stats().count(), 1);
+    }
+}
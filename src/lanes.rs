@@ -0,0 +1,220 @@
+//! Statistics of slices of [`f32`] or [`f64`] with several accumulators.
+//!
+//! [`Stats`](crate::Stats) goes through the items one at a time with a single
+//! accumulator, so every addition has to wait for the one before it. The
+//! functions here keep [`LANES`] separate accumulators, one for each item of
+//! a chunk, and only combine them at the end, so the additions of a chunk
+//! don't depend on each other. This is portable code without any `unsafe` or
+//! explicit SIMD instructions: the independent additions make it faster on
+//! any processor, and give the compiler the chance to vectorize them, but
+//! whether it does depends on the target and the optimization level. The
+//! sums are usually more accurate too, but they can differ from those of
+//! [`Stats`](crate::Stats) in the last bits.
+//!
+//! # Examples
+//! ```
+//! use stats_traits::lanes;
+//! let values: Vec<f64> = (1..=1000).map(f64::from).collect();
+//! assert_eq!(lanes::sum(&values), 500_500.0);
+//! assert_eq!(lanes::mean(&values), Ok(500.5));
+//! assert_eq!(lanes::min_max(&values), Ok((1.0, 1000.0)));
+//! ```
+
+use core::ops::{Add, Div, Mul, Sub};
+
+use crate::Result;
+use crate::StatsError;
+
+/// The number of accumulators, which is enough to hide the latency of the
+/// additions, and to fill the widest vector registers with [`f32`]s if the
+/// compiler vectorizes them
+pub const LANES: usize = 16;
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for f32 {}
+    impl Sealed for f64 {}
+}
+
+/// The floating point types the functions of this module work on,
+/// which are [`f32`] and [`f64`]
+pub trait LaneFloat:
+    Copy
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + sealed::Sealed
+{
+    /// Zero
+    const ZERO: Self;
+
+    /// Convert a number of items
+    fn from_count(count: usize) -> Self;
+
+    /// Returns the smaller of `self` and `other`, ignoring `NaN`
+    fn lane_min(self, other: Self) -> Self;
+
+    /// Returns the larger of `self` and `other`, ignoring `NaN`
+    fn lane_max(self, other: Self) -> Self;
+}
+
+macro_rules! impl_lane_float {
+    ($t:ty) => {
+        impl LaneFloat for $t {
+            const ZERO: Self = 0.0;
+
+            fn from_count(count: usize) -> Self {
+                count as $t
+            }
+
+            fn lane_min(self, other: Self) -> Self {
+                self.min(other)
+            }
+
+            fn lane_max(self, other: Self) -> Self {
+                self.max(other)
+            }
+        }
+    };
+}
+
+impl_lane_float!(f32);
+impl_lane_float!(f64);
+
+/// Returns the sum of `f` of each value, accumulating each lane separately
+fn lane_sum<T, F>(values: &[T], f: F) -> T
+where
+    T: LaneFloat,
+    F: Fn(T) -> T,
+{
+    let mut lanes = [T::ZERO; LANES];
+    let chunks = values.chunks_exact(LANES);
+    let remainder = chunks.remainder();
+    for chunk in chunks {
+        for (lane, &x) in lanes.iter_mut().zip(chunk) {
+            *lane = *lane + f(x);
+        }
+    }
+    for (lane, &x) in lanes.iter_mut().zip(remainder) {
+        *lane = *lane + f(x);
+    }
+    // add the lanes in pairs, which keeps the error small
+    let mut width = LANES;
+    while width > 1 {
+        width /= 2;
+        for i in 0..width {
+            lanes[i] = lanes[i] + lanes[i + width];
+        }
+    }
+    lanes[0]
+}
+
+/// Returns the sum of the values, which is 0 if there aren't any
+pub fn sum<T: LaneFloat>(values: &[T]) -> T {
+    lane_sum(values, |x| x)
+}
+
+/// Returns the mean of the values
+///
+/// # Errors
+/// Returns an error if `values` is empty
+pub fn mean<T: LaneFloat>(values: &[T]) -> Result<T> {
+    if values.is_empty() {
+        return Err(StatsError::EmptyCollection);
+    }
+    Ok(sum(values) / T::from_count(values.len()))
+}
+
+/// Returns the (population) variance of the values, going through them
+/// twice: once for the mean, and once for the squares of the differences
+/// from it. See [`Stats::variance`](crate::Stats::variance).
+///
+/// # Errors
+/// Returns an error if `values` is empty
+pub fn variance<T: LaneFloat>(values: &[T]) -> Result<T> {
+    let mean = mean(values)?;
+    let squares = lane_sum(values, |x| (x - mean) * (x - mean));
+    Ok(squares / T::from_count(values.len()))
+}
+
+/// Returns the smallest and largest of the values, going through them once.
+/// `NaN`s are ignored, unless every value is `NaN`, when both are `NaN`.
+/// Ignoring them takes comparisons that compilers rarely vectorize, so
+/// this gains less from the separate lanes than the sums do.
+///
+/// # Errors
+/// Returns an error if `values` is empty
+pub fn min_max<T: LaneFloat>(values: &[T]) -> Result<(T, T)> {
+    let first = *values.first().ok_or(StatsError::EmptyCollection)?;
+    let (mut min, mut max) = ([first; LANES], [first; LANES]);
+    let chunks = values.chunks_exact(LANES);
+    let remainder = chunks.remainder();
+    for chunk in chunks {
+        for ((min, max), &x) in min.iter_mut().zip(&mut max).zip(chunk) {
+            *min = min.lane_min(x);
+            *max = max.lane_max(x);
+        }
+    }
+    for ((min, max), &x) in min.iter_mut().zip(&mut max).zip(remainder) {
+        *min = min.lane_min(x);
+        *max = max.lane_max(x);
+    }
+    Ok((
+        min.into_iter().fold(first, T::lane_min),
+        max.into_iter().fold(first, T::lane_max),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Stats;
+
+    extern crate std;
+    use std::vec::Vec;
+
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_matches_stats() {
+        // lengths with and without a remainder after the chunks
+        for n in [1, 5, LANES, 3 * LANES + 7, 1000] {
+            let values: Vec<f64> = (0..n).map(|i| ((i * 7919) % 101) as f64 / 7.0).collect();
+            assert_relative_eq!(sum(&values), values.sum(), max_relative = 1e-12);
+            assert_relative_eq!(
+                variance(&values).unwrap(),
+                values.variance().unwrap(),
+                max_relative = 1e-12,
+                epsilon = 1e-12
+            );
+            assert_eq!(
+                min_max(&values).unwrap(),
+                (values.min().unwrap(), values.max().unwrap())
+            );
+        }
+    }
+
+    #[test]
+    fn test_f32() {
+        let values: Vec<f32> = (1..=100).map(|i| i as f32).collect();
+        assert_eq!(sum(&values), 5050.0);
+        assert_eq!(mean(&values), Ok(50.5));
+        assert_eq!(variance(&values), Ok(833.25));
+        assert_eq!(min_max(&values), Ok((1.0, 100.0)));
+    }
+
+    #[test]
+    fn test_empty_and_nan() {
+        let empty: [f64; 0] = [];
+        assert_eq!(sum(&empty), 0.0);
+        assert_eq!(mean(&empty), Err(StatsError::EmptyCollection));
+        assert_eq!(variance(&empty), Err(StatsError::EmptyCollection));
+        assert_eq!(min_max(&empty), Err(StatsError::EmptyCollection));
+        assert_eq!(min_max(&[f64::NAN, 2.0, -1.0]), Ok((-1.0, 2.0)));
+        let (min, max) = min_max(&[f64::NAN]).unwrap();
+        assert!(min.is_nan() && max.is_nan());
+    }
+}
@@ -1,18 +1,258 @@
 //! Library for calculating statistics on collections of numbers.
+//!
+//! # Features
+//! - `std` (enabled by default): use the standard library for square roots,
+//!   logarithms and the other floating point functions, and read numbers
+//!   from any `std::io::Read` source, such as a column of a CSV file, with
+//!   `summarize_reader`
+//! - `libm`: use [`libm`](https://docs.rs/libm) for the floating point
+//!   functions instead, for targets without the standard library. One of
+//!   `std` and `libm` must be enabled, so use `default-features = false`
+//!   and `features = ["libm"]` (and `alloc` if it is available) for these
+//! - `alloc` (enabled by default): types and methods that need to allocate,
+//!   such as [`FrequencyTable`], [`RollingStats`] and the order statistics
+//!   of [`Stats`] like `Stats::median`, `Stats::quantile` and `Stats::mode`.
+//!   [`Stats`] itself and the streaming types like [`OnlineStats`] never allocate,
+//!   and [`FixedRollingStats`] and [`FixedMovingMedian`] keep their windows inline.
+//! - `rand`: random sampling with [`rand`](https://docs.rs/rand), such as
+//!   `Reservoir` and `Stats::bootstrap`. Implies `alloc`.
+//! - `num-rational`: exact means and variances of integers as fractions
+//!   with [`num-rational`](https://docs.rs/num-rational), such as
+//!   `Stats::mean_exact`, and a square root for collections of fractions,
+//!   which is exact when the root is a fraction, for `Stats::root_std_dev`
+//! - `uom`: statistics of dimensioned quantities from [`uom`](https://docs.rs/uom)
+//!   that keep their units, with `QuantityStats`
+//! - `defmt`: implement [`defmt::Format`](https://docs.rs/defmt) for the error
+//!   types, [`Summary`] and the streaming types like [`OnlineStats`], to log
+//!   them efficiently on embedded targets
+//! - `arrow`: statistics of [`arrow-array`](https://docs.rs/arrow-array)
+//!   primitive arrays with `ArrowStats`, reading the values in place and
+//!   skipping the nulls. Implies `std` and `alloc`, because `arrow-array` needs `std`.
+//! - `rust_decimal`: statistics of [`rust_decimal::Decimal`](https://docs.rs/rust_decimal)s,
+//!   including a standard deviation to the full precision of the type with
+//!   `Stats::root_std_dev`
+//! - `fixed`: statistics of the fixed-point numbers of [`fixed`](https://docs.rs/fixed),
+//!   like `I16F16`, including a standard deviation that only uses integer
+//!   arithmetic with `Stats::root_std_dev`, for targets without a floating
+//!   point unit
+//! - `num-complex`: statistics of [`num-complex`](https://docs.rs/num-complex)
+//!   numbers with `ComplexStats`, such as their variance, which is real
+//! - `half`: statistics of the half precision floats of [`half`](https://docs.rs/half)
+//!   with `HalfStats`, adding them up in `f32` without converting the
+//!   whole collection first
+//! - `serde`: implement `Serialize` and `Deserialize` from
+//!   [`serde`](https://docs.rs/serde) for the error types, [`Summary`],
+//!   [`FrequencyTable`], the histograms and the streaming types like
+//!   [`OnlineStats`] and [`TDigest`], to send their state over the wire or
+//!   persist it and keep pushing values after loading it
 
 #![warn(missing_docs)]
 #![warn(clippy::cargo)]
 #![no_std]
 
+#[cfg(not(any(feature = "std", feature = "libm")))]
+compile_error!(
+    "either the `std` or the `libm` feature must be enabled for the floating point functions"
+);
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(feature = "arrow")]
+mod arrow;
+mod bivariate;
+#[cfg(feature = "alloc")]
+mod box_cox;
+mod checkpoint;
+#[cfg(feature = "alloc")]
+mod columns;
+#[cfg(feature = "num-complex")]
+mod complex;
+mod configured;
+mod const_array;
+mod context;
+#[cfg(feature = "alloc")]
+mod contingency;
+#[cfg(feature = "rust_decimal")]
+mod decimal;
+mod described;
+pub mod distributions;
+#[cfg(feature = "alloc")]
+mod downsample;
+mod duration;
 mod error;
+mod exceedance;
+mod fallible;
+mod filtered;
+#[cfg(feature = "fixed")]
+mod fixed_point;
 mod freq;
+#[cfg(feature = "half")]
+mod half_precision;
+#[cfg(feature = "alloc")]
+mod hdr;
 mod helpers;
+#[cfg(feature = "alloc")]
+mod histogram;
+#[cfg(feature = "alloc")]
+mod hll;
+mod hypothesis;
+#[cfg(feature = "alloc")]
+mod inter_arrival;
+mod iterator;
+pub mod lanes;
+mod log_summary;
+#[cfg(feature = "alloc")]
+mod matrix;
+mod measure;
+#[cfg(feature = "alloc")]
+mod median;
+mod nonzero;
+#[cfg(feature = "alloc")]
+mod normalize;
+mod online;
+mod optional;
+#[cfg(feature = "alloc")]
+mod order_stats;
+mod p2;
+mod paired;
+#[cfg(feature = "alloc")]
+mod pivot;
+mod provider;
+#[cfg(feature = "uom")]
+mod quantity;
+#[cfg(feature = "num-rational")]
+mod rational;
+#[cfg(feature = "std")]
+mod reader;
+mod regression;
+#[cfg(feature = "alloc")]
+mod remedian;
+#[cfg(feature = "alloc")]
+mod render;
+#[cfg(feature = "alloc")]
+mod resampling;
+#[cfg(feature = "rand")]
+mod reservoir;
+#[cfg(feature = "alloc")]
+mod rolling;
+mod runs;
+#[cfg(feature = "rand")]
+mod sampling;
+mod seven_number;
+mod sorted;
+mod special;
 mod stats;
+mod strided;
+mod summary;
+pub mod summation;
+mod survey;
+#[cfg(feature = "alloc")]
+mod table;
+#[cfg(feature = "alloc")]
+mod tdigest;
+mod validity;
+mod wide;
+mod window;
+#[cfg(feature = "alloc")]
+mod winsorize;
 
+#[cfg(feature = "arrow")]
+pub use crate::arrow::{ArrowStats, SkipNulls};
+pub use crate::bivariate::OnlineBivariate;
+pub use crate::checkpoint::CheckpointError;
+#[cfg(feature = "alloc")]
+pub use crate::columns::{
+    column_stats, max_along, mean_along, min_along, strided_column_stats, summaries_along,
+    variance_along, Axis,
+};
+#[cfg(feature = "num-complex")]
+pub use crate::complex::ComplexStats;
+pub use crate::configured::{Configured, Interpolation, NanPolicy, StatsConfig, TieBreak};
+pub use crate::const_array::ConstArray;
+pub use crate::context::{ErrorContext, WithContext};
+#[cfg(feature = "alloc")]
+pub use crate::contingency::{ChiSquareTest, ContingencyTable};
+pub use crate::described::Described;
+#[cfg(feature = "alloc")]
+pub use crate::downsample::{Aggregate, Downsample};
+pub use crate::duration::DurationStats;
 pub use crate::error::StatsError;
+pub use crate::exceedance::Exceedances;
+pub use crate::fallible::{TryIteratorStats, TryStatsError};
+pub use crate::filtered::Filtered;
 pub use crate::freq::FrequencyStats;
-pub use crate::stats::Stats;
+#[cfg(feature = "half")]
+pub use crate::half_precision::{HalfFloat, HalfStats, Widened};
+#[cfg(feature = "alloc")]
+pub use crate::hdr::{HdrHistogram, HdrIter};
+pub use crate::helpers::{SquareRoot, TotalOrder};
+#[cfg(feature = "alloc")]
+pub use crate::histogram::{BinRule, Histogram, Histogram2D, Histogram2DBuilder, HistogramBuilder};
+#[cfg(feature = "alloc")]
+pub use crate::hll::HyperLogLog;
+pub use crate::hypothesis::{
+    anova_one_way, clopper_pearson_interval, poisson_rate_interval, pooled_std_dev,
+    pooled_variance, proportion_z_test, two_proportion_z_test, wilson_interval, Anova, FTest,
+    GrubbsTest, ProportionTest, TTest, Tail, TestResult,
+};
+#[cfg(feature = "alloc")]
+pub use crate::hypothesis::{brown_forsythe_test, levene_test, SignedRankTest};
+#[cfg(feature = "alloc")]
+pub use crate::inter_arrival::InterArrival;
+pub use crate::iterator::{IteratorStats, RunningStats};
+pub use crate::log_summary::LogSummary;
+#[cfg(feature = "alloc")]
+pub use crate::matrix::{correlation_matrix, covariance_matrix, sample_covariance_matrix, Matrix};
+pub use crate::measure::{Measure, MeasureStats};
+#[cfg(feature = "alloc")]
+pub use crate::median::MovingMedian;
+pub use crate::nonzero::{Integers, NonZeroInteger, NonZeroStats};
+#[cfg(feature = "alloc")]
+pub use crate::normalize::quantile_normalize;
+pub use crate::online::OnlineStats;
+pub use crate::optional::{OptionStats, SkipMissing};
+#[cfg(feature = "alloc")]
+pub use crate::order_stats::OrderStatsSet;
+pub use crate::p2::P2Quantile;
+pub use crate::paired::PairedStats;
+#[cfg(feature = "alloc")]
+pub use crate::pivot::{PivotCell, PivotTable};
+pub use crate::provider::StatsProvider;
+#[cfg(feature = "uom")]
+pub use crate::quantity::QuantityStats;
+#[cfg(feature = "std")]
+pub use crate::reader::{extend_from_reader, summarize_reader, DataFormat, ReadError};
+pub use crate::regression::LinearRegression;
+#[cfg(feature = "alloc")]
+pub use crate::remedian::Remedian;
+#[cfg(feature = "rand")]
+pub use crate::resampling::Bootstrap;
+#[cfg(feature = "alloc")]
+pub use crate::resampling::Jackknife;
+#[cfg(feature = "rand")]
+pub use crate::reservoir::Reservoir;
+#[cfg(feature = "alloc")]
+pub use crate::rolling::{RollingStats, RollingZScores, ScoredPoint};
+pub use crate::runs::RunStats;
+pub use crate::seven_number::SevenNumberSummary;
+pub use crate::sorted::Sorted;
+pub use crate::stats::{RankTies, RobustScale, Rounding, Stats};
+pub use crate::strided::Strided;
+pub use crate::summary::Summary;
+pub use crate::survey::SurveyEstimate;
+#[cfg(feature = "alloc")]
+pub use crate::table::FrequencyTable;
+#[cfg(feature = "alloc")]
+pub use crate::tdigest::TDigest;
 pub use crate::types::Result;
+pub use crate::validity::ValidityReport;
+pub use crate::wide::Widen;
+pub use crate::window::{FixedMovingMedian, FixedRollingStats};
+#[cfg(feature = "alloc")]
+pub use crate::winsorize::winsorize_in_place;
 
 /// Module with type aliases
 pub mod types {
@@ -0,0 +1,90 @@
+use crate::helpers::Float;
+use crate::OnlineStats;
+use crate::Result;
+
+/// Summary of the logarithms of positive items, such as file sizes or
+/// latencies, whose spread grows with their size, as calculated by
+/// [`Stats::log_summary`](crate::Stats::log_summary). Such items are better
+/// described by how many times larger or smaller than typical they are than
+/// by how far from the mean they are, so the mean and standard deviation of
+/// their natural logarithms are transformed back into a geometric mean and
+/// a multiplicative standard deviation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LogSummary {
+    /// Number of items
+    pub count: usize,
+    /// Mean of the natural logarithms of the items
+    pub log_mean: f64,
+    /// (Population) standard deviation of the natural logarithms of the items
+    pub log_std_dev: f64,
+    /// [Geometric mean](<https://en.wikipedia.org/wiki/Geometric_mean>) of the
+    /// items, the exponential of [`LogSummary::log_mean`], which is the median
+    /// of log-normally distributed items
+    pub geometric_mean: f64,
+    /// [Geometric standard deviation](<https://en.wikipedia.org/wiki/Geometric_standard_deviation>)
+    /// of the items, the exponential of [`LogSummary::log_std_dev`], which is
+    /// the factor the items typically differ from the geometric mean by
+    pub geometric_std_dev: f64,
+}
+
+impl LogSummary {
+    /// Summarize the items from the statistics of their logarithms
+    ///
+    /// # Errors
+    /// Returns an error if no logarithms have been pushed to `log_stats`
+    pub(crate) fn new(log_stats: &OnlineStats) -> Result<Self> {
+        let log_mean = log_stats.mean()?;
+        let log_std_dev = log_stats.std_dev()?;
+        Ok(Self {
+            count: log_stats.count(),
+            log_mean,
+            log_std_dev,
+            geometric_mean: Float::exp(log_mean),
+            geometric_std_dev: Float::exp(log_std_dev),
+        })
+    }
+
+    /// Returns the bounds `k` geometric standard deviations either side of
+    /// the geometric mean, which are the geometric mean divided and multiplied
+    /// by the geometric standard deviation to the power of `k`. If the items
+    /// are log-normally distributed, about 68% of them are within the bounds
+    /// for a `k` of 1 and about 95% for a `k` of 2.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let sizes = vec![10.0, 100.0, 1000.0];
+    /// let summary = sizes.log_summary().unwrap();
+    /// let (lower, upper) = summary.bounds(1.0);
+    /// assert!((summary.geometric_mean - 100.0).abs() < 1e-9);
+    /// assert!((lower * upper - 100.0 * 100.0).abs() < 1e-6);
+    /// ```
+    pub fn bounds(&self, k: f64) -> (f64, f64) {
+        let factor = Float::powf(self.geometric_std_dev, k);
+        (self.geometric_mean / factor, self.geometric_mean * factor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_new() {
+        let log_stats: OnlineStats = [1.0, 2.0, 3.0].into_iter().collect();
+        let summary = LogSummary::new(&log_stats).unwrap();
+        assert_eq!(summary.count, 3);
+        assert_eq!(summary.log_mean, 2.0);
+        assert_relative_eq!(summary.geometric_mean, 2.0f64.exp());
+        let log_std_dev = (2.0f64 / 3.0).sqrt();
+        assert_relative_eq!(summary.log_std_dev, log_std_dev);
+        let (lower, upper) = summary.bounds(2.0);
+        assert_relative_eq!(lower, (2.0 - 2.0 * log_std_dev).exp(), epsilon = 1e-12);
+        assert_relative_eq!(upper, (2.0 + 2.0 * log_std_dev).exp(), epsilon = 1e-12);
+        assert!(LogSummary::new(&OnlineStats::new()).is_err());
+    }
+}
@@ -0,0 +1,303 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::Index;
+
+use num_traits::ToPrimitive;
+
+use crate::helpers::item_to_f64;
+use crate::helpers::Float;
+use crate::Result;
+use crate::StatsError;
+
+/// A square matrix of statistics between every two of a set of series,
+/// such as the one returned by [`covariance_matrix`]. The value for series
+/// `i` and `j` is at row `i` and column `j`, and can be read with
+/// [`Matrix::get`] or by indexing with `(i, j)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix {
+    size: usize,
+    /// The values with the columns of each row next to each other
+    values: Vec<f64>,
+}
+
+impl Matrix {
+    /// Returns the number of rows (and columns) of the matrix
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the value at row `i` and column `j`,
+    /// or `None` if there is no such row or column
+    pub fn get(&self, i: usize, j: usize) -> Option<f64> {
+        if i < self.size && j < self.size {
+            Some(self.values[i * self.size + j])
+        } else {
+            None
+        }
+    }
+
+    /// Returns an iterator over the rows of the matrix
+    pub fn rows(&self) -> impl Iterator<Item = &[f64]> {
+        self.values.chunks(self.size)
+    }
+
+    /// Returns the values of the matrix, one row after another
+    pub fn as_slice(&self) -> &[f64] {
+        &self.values
+    }
+}
+
+impl Index<(usize, usize)> for Matrix {
+    type Output = f64;
+
+    fn index(&self, (i, j): (usize, usize)) -> &f64 {
+        assert!(i < self.size && j < self.size, "index out of bounds");
+        &self.values[i * self.size + j]
+    }
+}
+
+/// Convert every series to [`f64`]s with their mean subtracted, checking
+/// that there is at least one series and that they all have the same length
+fn centred<S>(series: &[S]) -> Result<Vec<Vec<f64>>>
+where
+    S: IntoIterator + Clone,
+    S::Item: ToPrimitive,
+{
+    let centred = series
+        .iter()
+        .map(|s| {
+            let values = s
+                .clone()
+                .into_iter()
+                .map(item_to_f64)
+                .collect::<Result<Vec<f64>>>()?;
+            let mean = values.iter().sum::<f64>() / values.len() as f64;
+            Ok(values.into_iter().map(|x| x - mean).collect())
+        })
+        .collect::<Result<Vec<Vec<f64>>>>()?;
+    let length = centred.first().map_or(0, Vec::len);
+    assert!(
+        centred.iter().all(|s| s.len() == length),
+        "every series must have the same length"
+    );
+    if length == 0 {
+        return Err(StatsError::EmptyCollection);
+    }
+    Ok(centred)
+}
+
+/// Returns the matrix of the sums of the products of the centred series
+fn co_moments(centred: &[Vec<f64>]) -> Matrix {
+    let size = centred.len();
+    let mut values = vec![0.0; size * size];
+    for i in 0..size {
+        for j in i..size {
+            let sum: f64 = centred[i].iter().zip(&centred[j]).map(|(x, y)| x * y).sum();
+            values[i * size + j] = sum;
+            values[j * size + i] = sum;
+        }
+    }
+    Matrix { size, values }
+}
+
+/// Calculate the (population) covariance between every two of `series`,
+/// where each series is a collection of the same length. The diagonal of
+/// the matrix holds the variance of each series.
+/// See [`PairedStats::covariance`](crate::PairedStats::covariance).
+///
+/// # Examples
+/// ```
+/// use stats_traits::covariance_matrix;
+/// let returns = [vec![1.0, 2.0, 3.0], vec![2.0, 4.0, 6.0], vec![3.0, 2.0, 1.0]];
+/// let covariances = covariance_matrix(&returns).unwrap();
+/// assert_eq!(covariances.size(), 3);
+/// assert_eq!(covariances[(0, 0)], 2.0 / 3.0);
+/// assert_eq!(covariances[(0, 1)], 4.0 / 3.0);
+/// assert_eq!(covariances.get(2, 0), Some(-2.0 / 3.0));
+/// ```
+///
+/// # Errors
+/// Returns an error if there are no series or they are empty,
+/// or if an item can't be converted to [`f64`]
+///
+/// # Panics
+/// Panics if the series don't all have the same length
+pub fn covariance_matrix<S>(series: &[S]) -> Result<Matrix>
+where
+    S: IntoIterator + Clone,
+    S::Item: ToPrimitive,
+{
+    let centred = centred(series)?;
+    let n = centred[0].len() as f64;
+    let mut matrix = co_moments(&centred);
+    matrix.values.iter_mut().for_each(|x| *x /= n);
+    Ok(matrix)
+}
+
+/// Calculate the sample covariance between every two of `series`, dividing
+/// by one less than the length of the series, as in [`covariance_matrix`].
+/// See [`PairedStats::sample_covariance`](crate::PairedStats::sample_covariance).
+///
+/// # Errors
+/// Returns an error if there are no series or they have fewer than two items,
+/// or if an item can't be converted to [`f64`]
+///
+/// # Panics
+/// Panics if the series don't all have the same length
+pub fn sample_covariance_matrix<S>(series: &[S]) -> Result<Matrix>
+where
+    S: IntoIterator + Clone,
+    S::Item: ToPrimitive,
+{
+    let centred = centred(series)?;
+    let length = centred[0].len();
+    if length < 2 {
+        return Err(StatsError::InsufficientData {
+            required: 2,
+            actual: length,
+        });
+    }
+    let mut matrix = co_moments(&centred);
+    matrix
+        .values
+        .iter_mut()
+        .for_each(|x| *x /= (length - 1) as f64);
+    Ok(matrix)
+}
+
+/// Calculate the Pearson correlation coefficient between every two of
+/// `series`, where each series is a collection of the same length. The
+/// diagonal of the matrix is 1, as every series is perfectly correlated
+/// with itself. See [`PairedStats::pearson_r`](crate::PairedStats::pearson_r).
+///
+/// A series where every item is the same (a constant column) has no
+/// correlation with anything, so every value in its row and column,
+/// including its place on the diagonal, is `NaN`, as in numpy and pandas.
+/// The rest of the matrix is still calculated.
+///
+/// # Examples
+/// ```
+/// use stats_traits::correlation_matrix;
+/// let series = [vec![1.0, 2.0, 3.0], vec![2.0, 4.0, 7.0], vec![5.0, 5.0, 5.0]];
+/// let correlations = correlation_matrix(&series).unwrap();
+/// assert_eq!(correlations[(0, 0)], 1.0);
+/// assert!(correlations[(0, 1)] > 0.9);
+/// assert!(correlations[(0, 2)].is_nan());
+/// assert!(correlations[(2, 2)].is_nan());
+/// ```
+///
+/// # Errors
+/// Returns an error if there are no series or they are empty,
+/// or if an item can't be converted to [`f64`]
+///
+/// # Panics
+/// Panics if the series don't all have the same length
+pub fn correlation_matrix<S>(series: &[S]) -> Result<Matrix>
+where
+    S: IntoIterator + Clone,
+    S::Item: ToPrimitive,
+{
+    let mut matrix = co_moments(&centred(series)?);
+    let size = matrix.size;
+    let sums_of_squares: Vec<f64> = (0..size).map(|i| matrix.values[i * size + i]).collect();
+    for i in 0..size {
+        for j in 0..size {
+            let denominator = Float::sqrt(sums_of_squares[i] * sums_of_squares[j]);
+            matrix.values[i * size + j] = if denominator == 0.0 {
+                f64::NAN
+            } else if i == j {
+                1.0
+            } else {
+                matrix.values[i * size + j] / denominator
+            };
+        }
+    }
+    Ok(matrix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PairedStats;
+
+    extern crate std;
+    use std::vec::Vec;
+
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_matches_paired_covariance() {
+        let series = [
+            vec![1.0, 2.0, 4.0, 5.0, 7.0, 8.0],
+            vec![2.0, 1.0, 5.0, 4.0, 9.0, 6.0],
+            vec![3.0, 3.0, 0.0, 1.0, -2.0, 5.0],
+        ];
+        let population = covariance_matrix(&series).unwrap();
+        let sample = sample_covariance_matrix(&series).unwrap();
+        for i in 0..3 {
+            for j in 0..3 {
+                let pairs: Vec<(f64, f64)> = series[i]
+                    .iter()
+                    .copied()
+                    .zip(series[j].iter().copied())
+                    .collect();
+                assert_relative_eq!(population[(i, j)], pairs.covariance().unwrap());
+                assert_relative_eq!(sample[(i, j)], pairs.sample_covariance().unwrap());
+            }
+        }
+        assert_eq!(population.rows().count(), 3);
+        assert_eq!(population.get(3, 0), None);
+    }
+
+    #[test]
+    fn test_correlation_matrix() {
+        let series = [
+            vec![1.0, 2.0, 4.0, 5.0, 7.0, 8.0],
+            vec![2.0, 1.0, 5.0, 4.0, 9.0, 6.0],
+            vec![3.0, 3.0, 3.0, 3.0, 3.0, 3.0],
+            vec![8.0, 7.0, 5.0, 4.0, 2.0, 1.0],
+        ];
+        let matrix = correlation_matrix(&series).unwrap();
+        let pairs: Vec<(f64, f64)> = series[0]
+            .iter()
+            .copied()
+            .zip(series[1].iter().copied())
+            .collect();
+        assert_relative_eq!(matrix[(0, 1)], pairs.pearson_r().unwrap());
+        assert_relative_eq!(matrix[(1, 0)], matrix[(0, 1)]);
+        assert_relative_eq!(matrix[(0, 3)], -1.0);
+        assert_eq!(matrix[(3, 3)], 1.0);
+        for i in 0..4 {
+            assert!(matrix[(2, i)].is_nan());
+            assert!(matrix[(i, 2)].is_nan());
+        }
+    }
+
+    #[test]
+    fn test_slices() {
+        let a = [1, 2, 3];
+        let b = [6, 5, 1];
+        let matrix = covariance_matrix(&[a.iter().copied(), b.iter().copied()]).unwrap();
+        assert_relative_eq!(matrix[(0, 1)], -5.0 / 3.0);
+        assert_eq!(matrix.as_slice()[1], matrix.as_slice()[2]);
+    }
+
+    #[test]
+    fn test_errors() {
+        let empty: [Vec<f64>; 0] = [];
+        assert_eq!(covariance_matrix(&empty), Err(StatsError::EmptyCollection));
+        assert_eq!(
+            sample_covariance_matrix(&[vec![1.0], vec![2.0]]),
+            Err(StatsError::InsufficientData {
+                required: 2,
+                actual: 1
+            })
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_different_lengths() {
+        let _ = covariance_matrix(&[vec![1.0, 2.0], vec![1.0]]);
+    }
+}
@@ -0,0 +1,297 @@
+use core::fmt;
+
+use crate::helpers::Float;
+use crate::special::upper_incomplete_gamma;
+use crate::Result;
+use crate::StatsError;
+use crate::TestResult;
+
+/// A measured value with its standard uncertainty, such as a length of
+/// 12.3 ± 0.2 mm, for statistics that carry the uncertainties through
+/// with [`MeasureStats`]
+///
+/// # Examples
+/// ```
+/// use stats_traits::Measure;
+/// let length = Measure::new(12.3, 0.2);
+/// assert_eq!(length.to_string(), "12.3 ± 0.2");
+/// assert_eq!(format!("{length:.2}"), "12.30 ± 0.20");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Measure {
+    /// The measured value
+    pub value: f64,
+    /// The standard uncertainty of the value, which is the standard
+    /// deviation of the errors of measurements like it
+    pub sigma: f64,
+}
+
+impl Measure {
+    /// Create a measurement of `value` with a standard uncertainty of `sigma`
+    ///
+    /// # Panics
+    /// Panics if `sigma` is negative or `NaN`
+    pub fn new(value: f64, sigma: f64) -> Self {
+        assert!(sigma >= 0.0, "sigma must not be negative or NaN");
+        Self { value, sigma }
+    }
+
+    /// Returns the uncertainty relative to the size of the value
+    pub fn relative_uncertainty(&self) -> f64 {
+        self.sigma / self.value.abs()
+    }
+}
+
+impl From<(f64, f64)> for Measure {
+    /// Create a measurement from a `(value, sigma)` pair
+    fn from((value, sigma): (f64, f64)) -> Self {
+        Self { value, sigma }
+    }
+}
+
+impl fmt::Display for Measure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match f.precision() {
+            Some(precision) => write!(f, "{:.precision$} ± {:.precision$}", self.value, self.sigma),
+            None => write!(f, "{} ± {}", self.value, self.sigma),
+        }
+    }
+}
+
+/// An extension trait for collections of measurements with uncertainties,
+/// either [`Measure`]s or `(value, sigma)` pairs, to calculate statistics
+/// that propagate the uncertainties rather than discard them. The errors
+/// of the measurements are assumed to be independent.
+///
+/// It is implemented for the same collections as [`Stats`](crate::Stats).
+///
+/// # Examples
+/// ```
+/// use stats_traits::{Measure, MeasureStats};
+/// let masses = vec![(1.0, 0.3), (2.0, 0.4)];
+/// assert_eq!(masses.propagated_sum(), Measure::new(3.0, 0.5));
+/// ```
+pub trait MeasureStats<M> {
+    /// Returns the sum of the values, with the uncertainties added
+    /// in quadrature, so the uncertainty is the square root of the sum
+    /// of their squares
+    fn propagated_sum(&self) -> Measure;
+
+    /// Returns the mean of the values, with its uncertainty propagated
+    /// from the uncertainties of the values, which is the uncertainty of
+    /// [`MeasureStats::propagated_sum`] divided by the number of values.
+    /// It doesn't include the spread of the values themselves, which
+    /// [`MeasureStats::chi_squared_consistency`] checks.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::{Measure, MeasureStats};
+    /// let readings = vec![Measure::new(9.0, 0.6), Measure::new(11.0, 0.8)];
+    /// assert_eq!(readings.propagated_mean(), Ok(Measure::new(10.0, 0.5)));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty
+    fn propagated_mean(&self) -> Result<Measure>;
+
+    /// Combine independent measurements of the same quantity into one
+    /// estimate, by their mean weighted by the inverse of the variance of
+    /// each, so the more precise measurements count for more. Its
+    /// uncertainty is the inverse of the square root of the sum of the
+    /// weights, which is smaller than that of any one measurement.
+    ///
+    /// [Wikipedia](<https://en.wikipedia.org/wiki/Inverse-variance_weighting>)
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::MeasureStats;
+    /// // two measurements of the speed of light, in km/s
+    /// let measurements = vec![(299_790.0, 4.0), (299_793.0, 4.0)];
+    /// let combined = measurements.inverse_variance_weighted_mean().unwrap();
+    /// assert_eq!(combined.value, 299_791.5);
+    /// assert!((combined.sigma - 8.0f64.sqrt()).abs() < 1e-9);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty,
+    /// or if an uncertainty is 0 (`StatsError::ZeroVariance`)
+    fn inverse_variance_weighted_mean(&self) -> Result<Measure>;
+
+    /// Test whether the values are consistent with being measurements of
+    /// the same quantity, given their uncertainties, with the chi-squared
+    /// test of their squared deviations from their weighted mean, each
+    /// divided by its variance. There are one fewer degrees of freedom
+    /// than values, and a small p-value means that the values are spread
+    /// out more than their uncertainties allow, so they are probably
+    /// underestimated. The statistic divided by the degrees of freedom is
+    /// the reduced chi-squared, which should be close to 1.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::MeasureStats;
+    /// let consistent = vec![(10.1, 0.2), (9.8, 0.2), (10.0, 0.1)];
+    /// assert!(consistent.chi_squared_consistency().unwrap().p_value > 0.05);
+    /// let inconsistent = vec![(10.1, 0.02), (9.8, 0.02), (10.0, 0.01)];
+    /// assert!(inconsistent.chi_squared_consistency().unwrap().p_value < 0.05);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if there are fewer than two values,
+    /// or if an uncertainty is 0 (`StatsError::ZeroVariance`)
+    fn chi_squared_consistency(&self) -> Result<TestResult>;
+}
+
+/// Blanket implementation for all collections of [`Measure`]s, or of
+/// other types that convert to them, that can be iterated over by
+/// reference, like [`Stats`](crate::Stats)
+impl<T, M> MeasureStats<M> for T
+where
+    T: ?Sized,
+    for<'a> &'a T: IntoIterator<Item = &'a M>,
+    M: Into<Measure> + Copy + 'static,
+{
+    fn propagated_sum(&self) -> Measure {
+        let (value, variance) = self
+            .into_iter()
+            .map(|&m| m.into())
+            .fold((0.0, 0.0), |(value, variance), m: Measure| {
+                (value + m.value, variance + m.sigma * m.sigma)
+            });
+        Measure {
+            value,
+            sigma: Float::sqrt(variance),
+        }
+    }
+
+    fn propagated_mean(&self) -> Result<Measure> {
+        let count = self.into_iter().count();
+        if count == 0 {
+            return Err(StatsError::EmptyCollection);
+        }
+        let sum = self.propagated_sum();
+        Ok(Measure {
+            value: sum.value / count as f64,
+            sigma: sum.sigma / count as f64,
+        })
+    }
+
+    fn inverse_variance_weighted_mean(&self) -> Result<Measure> {
+        let measures = self.into_iter().map(|&m| Into::<Measure>::into(m));
+        let mut weighted_sum = 0.0;
+        let mut total_weight = 0.0;
+        for m in measures {
+            if m.sigma == 0.0 {
+                return Err(StatsError::ZeroVariance);
+            }
+            let w = 1.0 / (m.sigma * m.sigma);
+            weighted_sum += w * m.value;
+            total_weight += w;
+        }
+        if total_weight == 0.0 {
+            return Err(StatsError::EmptyCollection);
+        }
+        Ok(Measure {
+            value: weighted_sum / total_weight,
+            sigma: Float::sqrt(total_weight).recip(),
+        })
+    }
+
+    fn chi_squared_consistency(&self) -> Result<TestResult> {
+        let count = self.into_iter().count();
+        if count < 2 {
+            return Err(StatsError::InsufficientData {
+                required: 2,
+                actual: count,
+            });
+        }
+        let mean = self.inverse_variance_weighted_mean()?.value;
+        let statistic: f64 = self
+            .into_iter()
+            .map(|&m| {
+                let m: Measure = m.into();
+                let z = (m.value - mean) / m.sigma;
+                z * z
+            })
+            .sum();
+        Ok(TestResult {
+            statistic,
+            // compared with the chi-squared distribution with count - 1 degrees of freedom
+            p_value: upper_incomplete_gamma((count - 1) as f64 / 2.0, statistic / 2.0),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_propagation() {
+        let measures = [
+            Measure::new(1.0, 0.1),
+            Measure::new(2.0, 0.2),
+            Measure::new(3.0, 0.2),
+        ];
+        let sum = measures.propagated_sum();
+        assert_eq!(sum.value, 6.0);
+        assert_relative_eq!(sum.sigma, 0.3, epsilon = 1e-12);
+        let mean = measures.propagated_mean().unwrap();
+        assert_eq!(mean.value, 2.0);
+        assert_relative_eq!(mean.sigma, 0.1, epsilon = 1e-12);
+        assert_eq!(<[Measure]>::propagated_sum(&[]), Measure::new(0.0, 0.0));
+        let empty: [(f64, f64); 0] = [];
+        assert_eq!(empty.propagated_mean(), Err(StatsError::EmptyCollection));
+        assert_eq!(Measure::new(-4.0, 0.2).relative_uncertainty(), 0.05);
+    }
+
+    #[test]
+    fn test_inverse_variance_weighted_mean() {
+        let measures = [Measure::new(10.0, 1.0), Measure::new(13.0, 2.0)];
+        let combined = measures.inverse_variance_weighted_mean().unwrap();
+        // weights of 1 and 1/4
+        assert_relative_eq!(combined.value, 10.6, epsilon = 1e-12);
+        assert_relative_eq!(combined.sigma, 0.8f64.sqrt(), epsilon = 1e-12);
+        let single = [(5.0, 0.5)].inverse_variance_weighted_mean();
+        assert_eq!(single, Ok(Measure::new(5.0, 0.5)));
+        let empty: [Measure; 0] = [];
+        assert_eq!(
+            empty.inverse_variance_weighted_mean(),
+            Err(StatsError::EmptyCollection)
+        );
+        assert_eq!(
+            [(1.0, 0.0)].inverse_variance_weighted_mean(),
+            Err(StatsError::ZeroVariance)
+        );
+    }
+
+    #[test]
+    fn test_chi_squared_consistency() {
+        // deviations of 1 and -1 standard deviations from the weighted mean of 10
+        let pairs = [(9.0, 1.0), (11.0, 1.0)];
+        let test = pairs.chi_squared_consistency().unwrap();
+        assert_relative_eq!(test.statistic, 2.0);
+        // the upper tail of the chi-squared distribution with 1 degree of freedom at 2
+        assert_relative_eq!(test.p_value, 0.157_299_207_050_285, epsilon = 1e-9);
+        assert_eq!(
+            [(1.0, 0.5)].chi_squared_consistency(),
+            Err(StatsError::InsufficientData {
+                required: 2,
+                actual: 1
+            })
+        );
+        assert_eq!(
+            [(1.0, 0.5), (2.0, 0.0)].chi_squared_consistency(),
+            Err(StatsError::ZeroVariance)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "sigma must not be negative or NaN")]
+    fn test_negative_sigma() {
+        Measure::new(1.0, -0.1);
+    }
+}
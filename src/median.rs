@@ -0,0 +1,302 @@
+use alloc::collections::{BTreeSet, VecDeque};
+#[cfg(feature = "serde")]
+use alloc::vec::Vec;
+
+use crate::helpers::NanLast;
+use crate::helpers::NumExt;
+use crate::window::midpoint;
+use crate::Result;
+use crate::StatsError;
+
+/// An entry in one of the halves of the window. The number
+/// makes every entry unique, so repeated values can be told apart.
+type Entry<T> = (NanLast<T>, usize);
+
+/// Keeps track of the median of the last `N` values of a stream (a sliding window).
+///
+/// The window is split into two ordered sets: the lower half and the upper half
+/// of the values. Pushing a value (and evicting the oldest one) is O(log n),
+/// and the median is found from the largest value of the lower half and the
+/// smallest value of the upper half in O(log n).
+///
+/// # Examples
+/// ```
+/// use stats_traits::MovingMedian;
+/// let mut median = MovingMedian::new(3);
+/// for x in [5, 1, 100, 3] {
+///     median.push(x);
+/// }
+/// // the window is now [1, 100, 3]
+/// assert_eq!(median.median(), Ok(3));
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        try_from = "MovingMedianRepr<T>",
+        into = "MovingMedianRepr<T>",
+        bound(
+            serialize = "T: serde::Serialize + Copy",
+            deserialize = "T: serde::Deserialize<'de> + NumExt + Copy + PartialOrd"
+        )
+    )
+)]
+pub struct MovingMedian<T> {
+    window: VecDeque<(usize, T)>,
+    capacity: usize,
+    pushed: usize,
+    /// Lower half of the window, holding one more entry than `upper`
+    /// when the window has an odd number of values
+    lower: BTreeSet<Entry<T>>,
+    upper: BTreeSet<Entry<T>>,
+}
+
+impl<T> MovingMedian<T>
+where
+    T: NumExt + Copy + PartialOrd,
+{
+    /// Create an empty window that holds at most `window_size` values
+    ///
+    /// # Panics
+    /// Panics if `window_size` is 0
+    pub fn new(window_size: usize) -> Self {
+        assert!(window_size > 0, "window size must be greater than 0");
+        Self {
+            window: VecDeque::with_capacity(window_size),
+            capacity: window_size,
+            pushed: 0,
+            lower: BTreeSet::new(),
+            upper: BTreeSet::new(),
+        }
+    }
+
+    /// Add a value to the window. If the window was already full, the oldest
+    /// value is removed from it and returned.
+    pub fn push(&mut self, x: T) -> Option<T> {
+        let evicted = if self.window.len() == self.capacity {
+            self.evict()
+        } else {
+            None
+        };
+
+        let entry = (NanLast(x), self.pushed);
+        self.window.push_back((self.pushed, x));
+        self.pushed += 1;
+        match self.lower.last() {
+            Some(largest) if entry > *largest => self.upper.insert(entry),
+            _ => self.lower.insert(entry),
+        };
+        self.rebalance();
+
+        evicted
+    }
+
+    /// Remove the oldest value from the window and return it
+    pub fn evict(&mut self) -> Option<T> {
+        let (number, x) = self.window.pop_front()?;
+        let entry = (NanLast(x), number);
+        if !self.lower.remove(&entry) {
+            self.upper.remove(&entry);
+        }
+        self.rebalance();
+        Some(x)
+    }
+
+    /// Move entries between the halves until the lower half has
+    /// the same number of entries as the upper half, or one more
+    fn rebalance(&mut self) {
+        while self.lower.len() > self.upper.len() + 1 {
+            if let Some(entry) = self.lower.pop_last() {
+                self.upper.insert(entry);
+            }
+        }
+        while self.upper.len() > self.lower.len() {
+            if let Some(entry) = self.upper.pop_first() {
+                self.lower.insert(entry);
+            }
+        }
+    }
+
+    /// Remove every value from the window
+    pub fn clear(&mut self) {
+        self.window.clear();
+        self.lower.clear();
+        self.upper.clear();
+    }
+
+    /// Returns the number of values currently in the window
+    pub fn count(&self) -> usize {
+        self.window.len()
+    }
+
+    /// Returns the maximum number of values the window can hold
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the median of the values in the window. When there is an
+    /// even number of values, this is the mean of the middle two, so for
+    /// integers the result is rounded towards the lower of the two.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::MovingMedian;
+    /// let mut median = MovingMedian::new(4);
+    /// for x in [1.0, 4.0, 2.0, 3.0] {
+    ///     median.push(x);
+    /// }
+    /// assert_eq!(median.median(), Ok(2.5));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the window is empty
+    pub fn median(&self) -> Result<T> {
+        let (NanLast(low), _) = *self.lower.last().ok_or(StatsError::EmptyCollection)?;
+        if self.lower.len() > self.upper.len() {
+            return Ok(low);
+        }
+        match self.upper.first() {
+            Some(&(NanLast(high), _)) => Ok(midpoint(low, high)),
+            None => Ok(low),
+        }
+    }
+}
+
+/// The values of a [`MovingMedian`] that it is serialized as. The halves
+/// are rebuilt from them when it is deserialized, so they can't be
+/// inconsistent with the window.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct MovingMedianRepr<T> {
+    capacity: usize,
+    window: Vec<T>,
+}
+
+#[cfg(feature = "serde")]
+impl<T> From<MovingMedian<T>> for MovingMedianRepr<T> {
+    fn from(median: MovingMedian<T>) -> Self {
+        Self {
+            capacity: median.capacity,
+            window: median.window.into_iter().map(|(_, x)| x).collect(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T> TryFrom<MovingMedianRepr<T>> for MovingMedian<T>
+where
+    T: NumExt + Copy + PartialOrd,
+{
+    type Error = crate::CheckpointError;
+
+    fn try_from(repr: MovingMedianRepr<T>) -> core::result::Result<Self, Self::Error> {
+        if repr.capacity == 0 || repr.window.len() > repr.capacity {
+            return Err(crate::CheckpointError::Invalid);
+        }
+        // the capacity isn't trusted to allocate up front
+        let mut median = Self {
+            window: VecDeque::new(),
+            capacity: repr.capacity,
+            pushed: 0,
+            lower: BTreeSet::new(),
+            upper: BTreeSet::new(),
+        };
+        for x in repr.window {
+            median.push(x);
+        }
+        Ok(median)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    extern crate std;
+    use std::vec::Vec;
+
+    /// Median of a slice by sorting it
+    fn sorted_median(values: &[f64]) -> f64 {
+        let mut sorted: Vec<f64> = values.to_vec();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let mid = sorted.len() / 2;
+        if sorted.len() % 2 == 1 {
+            sorted[mid]
+        } else {
+            (sorted[mid - 1] + sorted[mid]) / 2.0
+        }
+    }
+
+    #[test]
+    fn test_matches_sorting() {
+        let data = [
+            3.0, 1.0, 4.0, 1.0, 5.0, 9.0, 2.0, 6.0, 5.0, 3.0, 5.0, 8.0, 9.0, 7.0, 9.0, 3.0, 2.0,
+        ];
+        for size in 1..7 {
+            let mut median = MovingMedian::new(size);
+            for (i, &x) in data.iter().enumerate() {
+                median.push(x);
+                let window = &data[(i + 1).saturating_sub(size)..=i];
+                assert_eq!(median.median(), Ok(sorted_median(window)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_evict() {
+        let mut median = MovingMedian::new(5);
+        for x in [1, 2, 3] {
+            median.push(x);
+        }
+        assert_eq!(median.evict(), Some(1));
+        assert_eq!(median.count(), 2);
+        assert_eq!(median.median(), Ok(2));
+        assert_eq!(median.evict(), Some(2));
+        assert_eq!(median.evict(), Some(3));
+        assert_eq!(median.evict(), None);
+        assert_eq!(median.median(), Err(StatsError::EmptyCollection));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_resume() {
+        let mut median = MovingMedian::new(3);
+        for x in [5, 1, 100, 3] {
+            median.push(x);
+        }
+        let json = serde_json::to_string(&median).unwrap();
+        assert_eq!(json, r#"{"capacity":3,"window":[1,100,3]}"#);
+        let mut loaded: MovingMedian<i32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.median(), Ok(3));
+        assert_eq!(loaded.push(4), Some(1));
+        assert_eq!(loaded.median(), Ok(4));
+        let tampered = r#"{"capacity":2,"window":[1,100,3]}"#;
+        assert!(serde_json::from_str::<MovingMedian<i32>>(tampered).is_err());
+    }
+
+    #[test]
+    fn test_integer_midpoint() {
+        let mut median = MovingMedian::new(2);
+        median.push(i64::MIN);
+        median.push(i64::MAX);
+        assert_eq!(median.median(), Ok(-1));
+        median.push(i64::MAX - 2);
+        assert_eq!(median.median(), Ok(i64::MAX - 1));
+        assert_eq!(midpoint(-3, 0), -2);
+        assert_eq!(midpoint(-3, 2), -1);
+        assert_eq!(midpoint(-4, -1), -3);
+        assert_eq!(midpoint(u8::MAX - 1, u8::MAX), u8::MAX - 1);
+        assert_eq!(midpoint(-1.0, 2.0), 0.5);
+        assert_eq!(midpoint(-f64::MAX, f64::MAX), 0.0);
+        assert_eq!(midpoint(f64::INFINITY, f64::INFINITY), f64::INFINITY);
+    }
+
+    #[test]
+    fn test_push_returns_evicted() {
+        let mut median = MovingMedian::new(2);
+        assert_eq!(median.push(7), None);
+        assert_eq!(median.push(8), None);
+        assert_eq!(median.push(9), Some(7));
+    }
+}
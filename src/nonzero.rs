@@ -0,0 +1,138 @@
+use core::num::NonZero;
+
+use crate::helpers::NumExt;
+use crate::Stats;
+
+/// A non-zero integer type like [`NonZeroU32`](core::num::NonZeroU32),
+/// which is an integer that is never zero
+pub trait NonZeroInteger: Copy + private::Sealed {
+    /// The integer type that this is a non-zero version of
+    type Integer: NumExt + Copy;
+
+    /// Returns the value as its integer type
+    fn get(self) -> Self::Integer;
+}
+
+mod private {
+    pub trait Sealed {}
+}
+
+macro_rules! impl_non_zero_integer {
+    ($($type: ty)*) => {
+        $(impl private::Sealed for NonZero<$type> {}
+
+        impl NonZeroInteger for NonZero<$type> {
+            type Integer = $type;
+
+            fn get(self) -> $type {
+                NonZero::get(self)
+            }
+        })*
+    };
+}
+
+impl_non_zero_integer!(i8 i16 i32 i64 i128 isize);
+impl_non_zero_integer!(u8 u16 u32 u64 u128 usize);
+
+/// An extension trait for collections of non-zero integers, such as
+/// [`NonZeroU32`](core::num::NonZeroU32)s, to calculate statistics of their
+/// values without copying them into a new collection of integers first.
+///
+/// It is implemented for the same collections as [`Stats`], when their
+/// items are non-zero integers.
+///
+/// # Examples
+/// ```
+/// use core::num::NonZeroU32;
+/// use stats_traits::{NonZeroStats, Stats};
+/// let batch_sizes = [8, 16, 4, 4].map(|x| NonZeroU32::new(x).unwrap());
+/// assert_eq!(batch_sizes.integers().sum(), 32);
+/// assert_eq!(batch_sizes.integers().mean(), Ok(8));
+/// assert_eq!(batch_sizes.integers().min(), Ok(4));
+/// ```
+pub trait NonZeroStats<N>
+where
+    N: NonZeroInteger,
+{
+    /// Returns the collection as a [`Stats`] of the values of the non-zero
+    /// integers, so every method of [`Stats`] can be used on it
+    fn integers(&self) -> Integers<'_, Self> {
+        Integers { inner: self }
+    }
+}
+
+/// Blanket implementation for all collections of non-zero integers
+/// that can be iterated over by reference, like [`Stats`]
+impl<T, N> NonZeroStats<N> for T
+where
+    T: ?Sized,
+    for<'a> &'a T: IntoIterator<Item = &'a N>,
+    N: NonZeroInteger + 'static,
+{
+}
+
+/// A collection of non-zero integers seen as their values, created by
+/// [`NonZeroStats::integers`]. It implements [`Stats`].
+#[derive(Debug)]
+pub struct Integers<'a, T: ?Sized> {
+    inner: &'a T,
+}
+
+impl<T: ?Sized> Clone for Integers<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized> Copy for Integers<'_, T> {}
+
+impl<'a, T: ?Sized> Integers<'a, T> {
+    /// Returns the collection of non-zero integers
+    pub fn get(&self) -> &'a T {
+        self.inner
+    }
+}
+
+impl<T, N> Stats for Integers<'_, T>
+where
+    T: ?Sized,
+    for<'a> &'a T: IntoIterator<Item = &'a N>,
+    N: NonZeroInteger + 'static,
+{
+    type Item = N::Integer;
+
+    fn items<'a>(&'a self) -> impl Iterator<Item = N::Integer> + 'a
+    where
+        Self::Item: 'a,
+    {
+        self.inner.into_iter().map(|&x| x.get())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StatsError;
+
+    use core::num::{NonZeroI64, NonZeroU64};
+
+    #[test]
+    fn test_non_zero_integers() {
+        let values = [3, -1, 4, -1, 5].map(|x| NonZeroI64::new(x).unwrap());
+        assert_eq!(values.integers().sum(), 10);
+        assert_eq!(values.integers().mean(), Ok(2));
+        assert_eq!(values.integers().max(), Ok(5));
+        assert_eq!(values.integers().non_zero_count(), Ok(5));
+        assert_eq!(values.integers().get(), &values);
+    }
+
+    #[test]
+    fn test_empty() {
+        let values: [NonZeroU64; 0] = [];
+        assert_eq!(values.integers().count(), 0);
+        assert_eq!(
+            values.integers().variance(),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+}
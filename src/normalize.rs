@@ -0,0 +1,139 @@
+use alloc::vec::Vec;
+
+use num_traits::ToPrimitive;
+
+use crate::helpers::item_to_f64;
+use crate::helpers::partial_cmp_nan_last;
+use crate::helpers::quantile_of_sorted;
+use crate::helpers::ranks;
+use crate::RankTies;
+use crate::Result;
+use crate::StatsError;
+
+/// [Quantile normalize](<https://en.wikipedia.org/wiki/Quantile_normalization>)
+/// the `samples` onto their average quantile profile, so they all have the
+/// same distribution, such as the arrays of a microarray experiment before
+/// they are compared. Each item is replaced by the mean, over the samples,
+/// of the quantile at its rank, so items of the same rank in samples of the
+/// same length get the same value. Tied items get the value of their
+/// average rank, and `NaN`s are kept as they are.
+///
+/// Returns the normalized samples, in the same order and with their items
+/// in the same order. See [`Stats::quantile_normalize`](crate::Stats::quantile_normalize)
+/// to normalize one sample onto another.
+///
+/// # Examples
+/// ```
+/// use stats_traits::quantile_normalize;
+/// let arrays = [vec![5.0, 2.0, 3.0], vec![4.0, 1.0, 6.0], vec![3.0, 5.0, 7.0]];
+/// let normalized = quantile_normalize(&arrays).unwrap();
+/// // the sorted profiles are averaged to 2, 4 and 6
+/// assert_eq!(normalized[0], [6.0, 2.0, 4.0]);
+/// assert_eq!(normalized[1], [4.0, 2.0, 6.0]);
+/// assert_eq!(normalized[2], [2.0, 4.0, 6.0]);
+/// ```
+///
+/// # Errors
+/// Returns an error if there are no samples, if a sample has no items that
+/// aren't `NaN`, or if an item can't be converted to [`f64`]
+pub fn quantile_normalize<S>(samples: &[S]) -> Result<Vec<Vec<f64>>>
+where
+    S: IntoIterator + Clone,
+    S::Item: ToPrimitive,
+{
+    if samples.is_empty() {
+        return Err(StatsError::EmptyCollection);
+    }
+    let samples = samples
+        .iter()
+        .map(|sample| sample.clone().into_iter().map(item_to_f64).collect())
+        .collect::<Result<Vec<Vec<f64>>>>()?;
+    let profiles = samples
+        .iter()
+        .map(|sample| sorted_profile(sample))
+        .collect::<Result<Vec<Vec<f64>>>>()?;
+    Ok(samples
+        .iter()
+        .map(|sample| normalize_onto(sample, &profiles))
+        .collect())
+}
+
+/// Returns the values that aren't `NaN`, sorted
+///
+/// # Errors
+/// Returns an error if every value is `NaN`
+pub(crate) fn sorted_profile(values: &[f64]) -> Result<Vec<f64>> {
+    let mut sorted: Vec<f64> = values.iter().copied().filter(|x| !x.is_nan()).collect();
+    if sorted.is_empty() {
+        return Err(StatsError::EmptyCollection);
+    }
+    sorted.sort_by(partial_cmp_nan_last);
+    Ok(sorted)
+}
+
+/// Returns `values` with each one that isn't `NaN` replaced by the mean of
+/// the quantiles of the sorted `profiles` at its rank among them
+pub(crate) fn normalize_onto(values: &[f64], profiles: &[Vec<f64>]) -> Vec<f64> {
+    let present: Vec<f64> = values.iter().copied().filter(|x| !x.is_nan()).collect();
+    let mut ranks = ranks(&present, RankTies::Average).into_iter();
+    let last = present.len().saturating_sub(1).max(1) as f64;
+    values
+        .iter()
+        .map(|&x| {
+            if x.is_nan() {
+                return x;
+            }
+            let rank = ranks.next().expect("a rank for each value");
+            // a single value is in the middle of the profile
+            let p = if present.len() == 1 {
+                0.5
+            } else {
+                (rank - 1.0) / last
+            };
+            let sum: f64 = profiles
+                .iter()
+                .map(|profile| quantile_of_sorted(profile, p))
+                .sum();
+            sum / profiles.len() as f64
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    extern crate std;
+    use std::vec;
+
+    #[test]
+    fn test_ties_and_nan() {
+        let samples = [
+            vec![2.0, f64::NAN, 2.0, 9.0],
+            vec![1.0, 2.0, 3.0, 4.0],
+            vec![10.0, 20.0, 30.0, 40.0],
+        ];
+        let normalized = quantile_normalize(&samples).unwrap();
+        // the first sample has 3 values, so its quantiles are at 0, 0.5 and 1
+        // of the profiles, and the tied values at 0.25
+        assert!(normalized[0][1].is_nan());
+        assert_eq!(normalized[0][0], normalized[0][2]);
+        let profile_at_quarter = (2.0 + 1.75 + 17.5) / 3.0;
+        assert_eq!(normalized[0][0], profile_at_quarter);
+        assert_eq!(normalized[0][3], (9.0 + 4.0 + 40.0) / 3.0);
+        // the others keep their order
+        assert!(normalized[1].windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(normalized[1], normalized[2]);
+    }
+
+    #[test]
+    fn test_errors() {
+        let empty: [Vec<f64>; 0] = [];
+        assert_eq!(quantile_normalize(&empty), Err(StatsError::EmptyCollection));
+        assert_eq!(
+            quantile_normalize(&[vec![1.0], vec![f64::NAN]]),
+            Err(StatsError::EmptyCollection)
+        );
+        assert_eq!(normalize_onto(&[7.0], &[vec![1.0, 3.0]]), [2.0]);
+    }
+}
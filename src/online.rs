@@ -0,0 +1,500 @@
+use crate::checkpoint;
+use crate::checkpoint::Reader;
+use crate::checkpoint::Writer;
+use crate::helpers::Float;
+use crate::CheckpointError;
+use crate::Result;
+use crate::StatsError;
+use crate::Summary;
+
+/// Accumulates statistics over a stream of values one at a time,
+/// without storing any of them.
+///
+/// This is useful when the data can't be kept around to use
+/// [`Stats`](crate::Stats), which needs to iterate over the
+/// collection several times. The mean and variance are updated
+/// with [Welford's algorithm](<https://en.wikipedia.org/wiki/Algorithms_for_calculating_variance#Welford's_online_algorithm>),
+/// which avoids the loss of precision of keeping a running sum of squares.
+/// The third and fourth central moments are tracked as well, using the
+/// generalisation by [Pébay](<https://www.osti.gov/biblio/1028931>), so that
+/// [`OnlineStats::skewness`] and [`OnlineStats::kurtosis`] are available.
+///
+/// # Examples
+/// ```
+/// use stats_traits::OnlineStats;
+/// let mut stats = OnlineStats::new();
+/// for x in [1.0, 2.0, 3.0] {
+///     stats.push(x);
+/// }
+/// assert_eq!(stats.count(), 3);
+/// assert_eq!(stats.mean(), Ok(2.0));
+/// assert_eq!(stats.max(), Ok(3.0));
+/// ```
+///
+/// Accumulators can be combined with [`OnlineStats::merge`], so a stream
+/// can be split between threads or machines and the partial results
+/// reduced at the end.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OnlineStats {
+    count: usize,
+    mean: f64,
+    m2: f64,
+    m3: f64,
+    m4: f64,
+    min: f64,
+    max: f64,
+}
+
+impl OnlineStats {
+    /// Create an accumulator that hasn't seen any values
+    pub const fn new() -> Self {
+        Self {
+            count: 0,
+            mean: 0.0,
+            m2: 0.0,
+            m3: 0.0,
+            m4: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+
+    /// Add a value to the accumulator
+    pub fn push(&mut self, x: f64) {
+        let n_prev = self.count as f64;
+        self.count += 1;
+        let n = self.count as f64;
+        let delta = x - self.mean;
+        let delta_n = delta / n;
+        let delta_n2 = delta_n * delta_n;
+        let term = delta * delta_n * n_prev;
+        self.mean += delta_n;
+        // the higher moments have to be updated before the lower
+        // ones as they depend on their previous values
+        self.m4 += term * delta_n2 * (n * n - 3.0 * n + 3.0) + 6.0 * delta_n2 * self.m2
+            - 4.0 * delta_n * self.m3;
+        self.m3 += term * delta_n * (n - 2.0) - 3.0 * delta_n * self.m2;
+        self.m2 += term;
+        self.min = self.min.min(x);
+        self.max = self.max.max(x);
+    }
+
+    /// Combine the values seen by `other` into `self`, as if every
+    /// value pushed to `other` had been pushed to `self` as well.
+    ///
+    /// The means and variances are combined with the
+    /// [parallel algorithm](<https://en.wikipedia.org/wiki/Algorithms_for_calculating_variance#Parallel_algorithm>)
+    /// of Chan et al.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::OnlineStats;
+    /// let mut a: OnlineStats = [1.0, 2.0].into_iter().collect();
+    /// let b: OnlineStats = [3.0, 4.0, 5.0].into_iter().collect();
+    /// a.merge(&b);
+    /// assert_eq!(a.count(), 5);
+    /// assert_eq!(a.mean(), Ok(3.0));
+    /// assert_eq!(a.variance(), Ok(2.0));
+    /// ```
+    pub fn merge(&mut self, other: &Self) {
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            *self = *other;
+            return;
+        }
+        let count = self.count + other.count;
+        let (n_a, n_b, n) = (self.count as f64, other.count as f64, count as f64);
+        let delta = other.mean - self.mean;
+        let delta2 = delta * delta;
+        self.mean += delta * n_b / n;
+        self.m4 += other.m4
+            + delta2 * delta2 * n_a * n_b * (n_a * n_a - n_a * n_b + n_b * n_b) / (n * n * n)
+            + 6.0 * delta2 * (n_a * n_a * other.m2 + n_b * n_b * self.m2) / (n * n)
+            + 4.0 * delta * (n_a * other.m3 - n_b * self.m3) / n;
+        self.m3 += other.m3
+            + delta2 * delta * n_a * n_b * (n_a - n_b) / (n * n)
+            + 3.0 * delta * (n_a * other.m2 - n_b * self.m2) / n;
+        self.m2 += other.m2 + delta2 * n_a * n_b / n;
+        self.count = count;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+    }
+
+    /// Returns the number of values that have been pushed
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Returns `Err(StatsError::EmptyCollection)` if no values have been pushed
+    fn non_empty(&self) -> Result<()> {
+        if self.count == 0 {
+            Err(StatsError::EmptyCollection)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns the mean of the values pushed so far
+    ///
+    /// # Errors
+    /// Returns an error if no values have been pushed
+    pub fn mean(&self) -> Result<f64> {
+        self.non_empty()?;
+        Ok(self.mean)
+    }
+
+    /// Returns the (population) variance of the values pushed so far.
+    /// See [`Stats::variance`](crate::Stats::variance).
+    ///
+    /// # Errors
+    /// Returns an error if no values have been pushed
+    pub fn variance(&self) -> Result<f64> {
+        self.non_empty()?;
+        Ok(self.m2 / self.count as f64)
+    }
+
+    /// Returns the standard deviation of the values pushed so far,
+    /// which is the square root of [`OnlineStats::variance`]
+    ///
+    /// # Errors
+    /// Returns an error if no values have been pushed
+    pub fn std_dev(&self) -> Result<f64> {
+        Ok(Float::sqrt(self.variance()?))
+    }
+
+    /// Returns the (population) skewness of the values pushed so far,
+    /// which measures how lopsided the distribution is. It is positive
+    /// when the tail on the right is longer and negative when the tail
+    /// on the left is longer.
+    ///
+    /// [Wikipedia](<https://en.wikipedia.org/wiki/Skewness>)
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::OnlineStats;
+    /// let stats: OnlineStats = [1.0, 2.0, 3.0].into_iter().collect();
+    /// assert_eq!(stats.skewness(), Ok(0.0));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if no values have been pushed.
+    /// If every value is the same the result is `NaN`.
+    pub fn skewness(&self) -> Result<f64> {
+        self.non_empty()?;
+        let n = self.count as f64;
+        Ok(Float::sqrt(n) * self.m3 / Float::powf(self.m2, 1.5))
+    }
+
+    /// Returns the (population) excess kurtosis of the values pushed so far,
+    /// which measures how heavy the tails of the distribution are compared
+    /// to a normal distribution, which has an excess kurtosis of 0.
+    ///
+    /// [Wikipedia](<https://en.wikipedia.org/wiki/Kurtosis>)
+    ///
+    /// # Errors
+    /// Returns an error if no values have been pushed.
+    /// If every value is the same the result is `NaN`.
+    pub fn kurtosis(&self) -> Result<f64> {
+        self.non_empty()?;
+        let n = self.count as f64;
+        Ok(n * self.m4 / (self.m2 * self.m2) - 3.0)
+    }
+
+    /// Returns the smallest value pushed so far
+    ///
+    /// # Errors
+    /// Returns an error if no values have been pushed
+    pub fn min(&self) -> Result<f64> {
+        self.non_empty()?;
+        Ok(self.min)
+    }
+
+    /// Returns the largest value pushed so far
+    ///
+    /// # Errors
+    /// Returns an error if no values have been pushed
+    pub fn max(&self) -> Result<f64> {
+        self.non_empty()?;
+        Ok(self.max)
+    }
+
+    /// Finalize the accumulator into a [`Summary`]
+    ///
+    /// # Errors
+    /// Returns an error if no values have been pushed
+    pub fn summary(&self) -> Result<Summary> {
+        Ok(Summary {
+            count: self.count,
+            mean: self.mean()?,
+            variance: self.variance()?,
+            min: self.min,
+            max: self.max,
+            third_moment: self.m3 / self.count as f64,
+            fourth_moment: self.m4 / self.count as f64,
+        })
+    }
+
+    /// The length of the checkpoints written by [`OnlineStats::to_bytes`]
+    pub const CHECKPOINT_LEN: usize = 58;
+
+    /// Save the state of the accumulator as a compact checkpoint, which
+    /// [`OnlineStats::from_bytes`] restores, so a long-running aggregation
+    /// can survive a restart or be sent to another machine. It doesn't
+    /// allocate, and is the same on every platform.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::OnlineStats;
+    /// let stats = OnlineStats::from_iter([1.0, 2.0, 6.0]);
+    /// let checkpoint = stats.to_bytes();
+    /// let mut restored = OnlineStats::from_bytes(&checkpoint).unwrap();
+    /// assert_eq!(restored, stats);
+    /// restored.push(3.0);
+    /// assert_eq!(restored.mean(), Ok(3.0));
+    /// ```
+    pub fn to_bytes(&self) -> [u8; Self::CHECKPOINT_LEN] {
+        let mut bytes = [0; Self::CHECKPOINT_LEN];
+        let mut writer = Writer::new(&mut bytes, checkpoint::ONLINE_STATS);
+        writer.put_usize(self.count);
+        for value in [self.mean, self.m2, self.m3, self.m4, self.min, self.max] {
+            writer.put_f64(value);
+        }
+        bytes
+    }
+
+    /// Restore an accumulator from a checkpoint written by [`OnlineStats::to_bytes`]
+    ///
+    /// # Errors
+    /// Returns an error if `bytes` isn't a checkpoint of an `OnlineStats`
+    /// in a version of the format this version of the crate can read, or if
+    /// the accumulator in it is inconsistent, such as a negative sum of
+    /// squared differences, or statistics without any values
+    pub fn from_bytes(bytes: &[u8]) -> core::result::Result<Self, CheckpointError> {
+        let mut reader = Reader::new(bytes, checkpoint::ONLINE_STATS)?;
+        let stats = Self {
+            count: reader.get_usize()?,
+            mean: reader.get_f64()?,
+            m2: reader.get_f64()?,
+            m3: reader.get_f64()?,
+            m4: reader.get_f64()?,
+            min: reader.get_f64()?,
+            max: reader.get_f64()?,
+        };
+        reader.finish()?;
+        // an accumulator without values is only ever in its initial state
+        if stats.m2 < 0.0 || (stats.count == 0 && stats != Self::new()) {
+            return Err(CheckpointError::Invalid);
+        }
+        Ok(stats)
+    }
+}
+
+impl From<Summary> for OnlineStats {
+    fn from(summary: Summary) -> Self {
+        let n = summary.count as f64;
+        Self {
+            count: summary.count,
+            mean: summary.mean,
+            m2: summary.variance * n,
+            m3: summary.third_moment * n,
+            m4: summary.fourth_moment * n,
+            min: summary.min,
+            max: summary.max,
+        }
+    }
+}
+
+impl Default for OnlineStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Extend<f64> for OnlineStats {
+    fn extend<I: IntoIterator<Item = f64>>(&mut self, iter: I) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+impl FromIterator<f64> for OnlineStats {
+    fn from_iter<I: IntoIterator<Item = f64>>(iter: I) -> Self {
+        let mut stats = Self::new();
+        stats.extend(iter);
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_checkpoint() {
+        let stats = OnlineStats::from_iter([2.5, -1.0, 7.25, 3.0]);
+        let checkpoint = stats.to_bytes();
+        assert_eq!(OnlineStats::from_bytes(&checkpoint), Ok(stats));
+        let empty = OnlineStats::new();
+        assert_eq!(OnlineStats::from_bytes(&empty.to_bytes()), Ok(empty));
+        assert_eq!(
+            OnlineStats::from_bytes(&checkpoint[..40]),
+            Err(CheckpointError::Truncated)
+        );
+    }
+
+    #[test]
+    fn test_checkpoint_inconsistent() {
+        let mut corrupt = OnlineStats::from_iter([1.0, 2.0]).to_bytes();
+        corrupt[18..26].copy_from_slice(&(-1.0f64).to_le_bytes());
+        assert_eq!(
+            OnlineStats::from_bytes(&corrupt),
+            Err(CheckpointError::Invalid)
+        );
+        let mut corrupt = OnlineStats::new().to_bytes();
+        corrupt[10..18].copy_from_slice(&3.0f64.to_le_bytes());
+        assert_eq!(
+            OnlineStats::from_bytes(&corrupt),
+            Err(CheckpointError::Invalid)
+        );
+    }
+
+    #[test]
+    fn test_empty() {
+        let stats = OnlineStats::new();
+        assert_eq!(stats.count(), 0);
+        assert_eq!(stats.mean(), Err(StatsError::EmptyCollection));
+        assert_eq!(stats.variance(), Err(StatsError::EmptyCollection));
+        assert_eq!(stats.min(), Err(StatsError::EmptyCollection));
+    }
+
+    #[test]
+    fn test_mean_variance() {
+        let stats: OnlineStats = [1.0, 2.0, 3.0].into_iter().collect();
+        assert_relative_eq!(stats.mean().unwrap(), 2.0);
+        assert_relative_eq!(stats.variance().unwrap(), 2.0 / 3.0);
+        assert_relative_eq!(stats.std_dev().unwrap(), 2.0_f64.sqrt() / 3.0_f64.sqrt());
+    }
+
+    #[test]
+    fn test_min_max() {
+        let stats: OnlineStats = [2.0, -1.0, 5.0, 3.0].into_iter().collect();
+        assert_eq!(stats.min(), Ok(-1.0));
+        assert_eq!(stats.max(), Ok(5.0));
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut a: OnlineStats = [1.0, 2.0, 3.0, 4.0].into_iter().collect();
+        let b: OnlineStats = [10.0, 20.0].into_iter().collect();
+        let all: OnlineStats = [1.0, 2.0, 3.0, 4.0, 10.0, 20.0].into_iter().collect();
+        a.merge(&b);
+        assert_eq!(a.count(), all.count());
+        assert_relative_eq!(a.mean().unwrap(), all.mean().unwrap());
+        assert_relative_eq!(a.variance().unwrap(), all.variance().unwrap());
+        assert_eq!(a.min(), Ok(1.0));
+        assert_eq!(a.max(), Ok(20.0));
+    }
+
+    #[test]
+    fn test_merge_empty() {
+        let mut a = OnlineStats::new();
+        let b: OnlineStats = [1.0, 2.0].into_iter().collect();
+        a.merge(&OnlineStats::new());
+        assert_eq!(a, OnlineStats::new());
+        a.merge(&b);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_summary_round_trip() {
+        let stats: OnlineStats = [1.0, 2.0, 4.0].into_iter().collect();
+        let summary = stats.summary().unwrap();
+        assert_eq!(summary.count, 3);
+        assert_relative_eq!(summary.variance, stats.variance().unwrap());
+        let restored = OnlineStats::from(summary);
+        assert_eq!(restored.count(), 3);
+        assert_relative_eq!(restored.variance().unwrap(), stats.variance().unwrap());
+        assert_relative_eq!(
+            restored.skewness().unwrap(),
+            stats.skewness().unwrap(),
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(
+            restored.kurtosis().unwrap(),
+            stats.kurtosis().unwrap(),
+            epsilon = 1e-12
+        );
+        let constant: OnlineStats = [5.0, 5.0].into_iter().collect();
+        let restored = OnlineStats::from(constant.summary().unwrap());
+        assert_eq!(restored, constant);
+        assert_eq!(
+            OnlineStats::new().summary(),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+
+    #[test]
+    fn test_skewness_kurtosis() {
+        let stats: OnlineStats = [2.0, 8.0, 0.0, 4.0, 1.0, 9.0, 9.0, 0.0]
+            .into_iter()
+            .collect();
+        assert_relative_eq!(
+            stats.skewness().unwrap(),
+            0.2650554122698573,
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(
+            stats.kurtosis().unwrap(),
+            -1.6660010752838508,
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn test_merge_moments() {
+        let mut a: OnlineStats = [2.0, 8.0, 0.0].into_iter().collect();
+        let b: OnlineStats = [4.0, 1.0, 9.0, 9.0, 0.0].into_iter().collect();
+        let all: OnlineStats = [2.0, 8.0, 0.0, 4.0, 1.0, 9.0, 9.0, 0.0]
+            .into_iter()
+            .collect();
+        a.merge(&b);
+        assert_relative_eq!(
+            a.skewness().unwrap(),
+            all.skewness().unwrap(),
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(
+            a.kurtosis().unwrap(),
+            all.kurtosis().unwrap(),
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn test_large_offset() {
+        // a naive sum of squares loses all precision here
+        let stats: OnlineStats = [1e9 + 4.0, 1e9 + 7.0, 1e9 + 13.0, 1e9 + 16.0]
+            .into_iter()
+            .collect();
+        assert_relative_eq!(stats.variance().unwrap(), 22.5);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_resume() {
+        let mut stats: OnlineStats = [1.0, 2.0, 4.0].into_iter().collect();
+        let json = serde_json::to_string(&stats).unwrap();
+        let mut loaded: OnlineStats = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, stats);
+        stats.push(8.0);
+        loaded.push(8.0);
+        assert_eq!(loaded, stats);
+    }
+}
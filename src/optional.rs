@@ -0,0 +1,278 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+#[cfg(feature = "alloc")]
+use num_traits::ToPrimitive;
+
+#[cfg(feature = "alloc")]
+use crate::helpers::item_to_f64;
+use crate::helpers::NumExt;
+#[cfg(feature = "alloc")]
+use crate::Result;
+use crate::Stats;
+#[cfg(feature = "alloc")]
+use crate::StatsError;
+
+/// An extension trait for collections of [`Option`]s, such as columns read
+/// from a file with missing values, to calculate statistics of the values
+/// that are present without copying them into a new collection first.
+///
+/// It is implemented for the same collections as [`Stats`], when their
+/// items are [`Option`]s of numbers.
+///
+/// # Examples
+/// ```
+/// use stats_traits::{OptionStats, Stats};
+/// let column = vec![Some(1.0), None, Some(3.0), None, Some(8.0)];
+/// assert_eq!(column.missing_count(), 2);
+/// assert_eq!(column.skip_missing().count(), 3);
+/// assert_eq!(column.skip_missing().mean(), Ok(4.0));
+/// assert_eq!(column.skip_missing().max(), Ok(8.0));
+/// ```
+pub trait OptionStats<I>
+where
+    I: NumExt,
+{
+    /// Returns the collection as a [`Stats`] of the values that are present,
+    /// skipping the `None`s, so every method of [`Stats`] can be used on it
+    fn skip_missing(&self) -> SkipMissing<'_, Self> {
+        SkipMissing { inner: self }
+    }
+
+    /// Returns the number of `None`s in the collection
+    fn missing_count(&self) -> usize;
+
+    /// Returns the values with each `None` replaced by the last value
+    /// before it, as for readings from a sensor that only reports changes.
+    /// The `None`s at the start, which have no value before them, are
+    /// replaced by the first value instead.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::OptionStats;
+    /// let readings = vec![None, Some(3), None, None, Some(5), None];
+    /// assert_eq!(readings.forward_fill(), Ok(vec![3, 3, 3, 3, 5, 5]));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if every item is `None`
+    #[cfg(feature = "alloc")]
+    fn forward_fill(&self) -> Result<Vec<I>>;
+
+    /// Returns the values with each `None` replaced by the next value
+    /// after it. The `None`s at the end, which have no value after them,
+    /// are replaced by the last value instead.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::OptionStats;
+    /// let readings = vec![None, Some(3), None, None, Some(5), None];
+    /// assert_eq!(readings.backward_fill(), Ok(vec![3, 3, 5, 5, 5, 5]));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if every item is `None`
+    #[cfg(feature = "alloc")]
+    fn backward_fill(&self) -> Result<Vec<I>>;
+
+    /// Returns the values with each run of `None`s replaced by points on
+    /// the straight line between the values on either side of it, as if
+    /// the items were evenly spaced in time. The `None`s at the start and
+    /// end are replaced by the nearest value, as there is only a value on
+    /// one side of them.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::OptionStats;
+    /// let readings = vec![None, Some(1.0), None, None, Some(7.0), None];
+    /// assert_eq!(readings.interpolate(), Ok(vec![1.0, 1.0, 3.0, 5.0, 7.0, 7.0]));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if every item is `None`,
+    /// or if a value can't be converted to [`f64`]
+    #[cfg(feature = "alloc")]
+    fn interpolate(&self) -> Result<Vec<f64>>
+    where
+        I: ToPrimitive;
+}
+
+/// Blanket implementation for all collections of [`Option`]s that
+/// can be iterated over by reference, like [`Stats`]
+impl<T, I> OptionStats<I> for T
+where
+    T: ?Sized,
+    for<'a> &'a T: IntoIterator<Item = &'a Option<I>>,
+    I: NumExt,
+{
+    fn missing_count(&self) -> usize {
+        self.into_iter().filter(|x| x.is_none()).count()
+    }
+
+    #[cfg(feature = "alloc")]
+    fn forward_fill(&self) -> Result<Vec<I>> {
+        let mut last = self
+            .into_iter()
+            .find_map(Option::clone)
+            .ok_or(StatsError::EmptyCollection)?;
+        Ok(self
+            .into_iter()
+            .map(|x| {
+                if let Some(x) = x {
+                    last = x.clone();
+                }
+                last.clone()
+            })
+            .collect())
+    }
+
+    #[cfg(feature = "alloc")]
+    fn backward_fill(&self) -> Result<Vec<I>> {
+        let items: Vec<Option<I>> = self.into_iter().cloned().collect();
+        let mut next = items
+            .iter()
+            .rev()
+            .find_map(Option::clone)
+            .ok_or(StatsError::EmptyCollection)?;
+        let mut values: Vec<I> = items
+            .into_iter()
+            .rev()
+            .map(|x| {
+                if let Some(x) = x {
+                    next = x;
+                }
+                next.clone()
+            })
+            .collect();
+        values.reverse();
+        Ok(values)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn interpolate(&self) -> Result<Vec<f64>>
+    where
+        I: ToPrimitive,
+    {
+        let mut values = Vec::new();
+        // the index of the last value, and the value
+        let mut last: Option<(usize, f64)> = None;
+        for (i, x) in self.into_iter().enumerate() {
+            let Some(x) = x else {
+                values.push(f64::NAN);
+                continue;
+            };
+            let x = item_to_f64(x.clone())?;
+            match last {
+                Some((start, from)) => {
+                    let gap = (i - start) as f64;
+                    for (j, value) in values[start + 1..].iter_mut().enumerate() {
+                        *value = from + (x - from) * (j + 1) as f64 / gap;
+                    }
+                }
+                None => values.fill(x),
+            }
+            values.push(x);
+            last = Some((i, x));
+        }
+        let (end, x) = last.ok_or(StatsError::EmptyCollection)?;
+        values[end..].fill(x);
+        Ok(values)
+    }
+}
+
+/// A collection of [`Option`]s seen as the values that are present,
+/// created by [`OptionStats::skip_missing`]. It implements [`Stats`].
+#[derive(Debug)]
+pub struct SkipMissing<'a, T: ?Sized> {
+    inner: &'a T,
+}
+
+impl<T: ?Sized> Clone for SkipMissing<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized> Copy for SkipMissing<'_, T> {}
+
+impl<'a, T: ?Sized> SkipMissing<'a, T> {
+    /// Returns the collection with the missing values
+    pub fn get(&self) -> &'a T {
+        self.inner
+    }
+}
+
+impl<T, I> Stats for SkipMissing<'_, T>
+where
+    T: ?Sized,
+    for<'a> &'a T: IntoIterator<Item = &'a Option<I>>,
+    I: NumExt,
+{
+    type Item = I;
+
+    fn items<'a>(&'a self) -> impl Iterator<Item = I> + 'a
+    where
+        Self::Item: 'a,
+    {
+        self.inner.into_iter().filter_map(Option::clone)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StatsError;
+
+    extern crate std;
+    #[cfg(feature = "alloc")]
+    use std::vec;
+
+    #[test]
+    fn test_skip_missing() {
+        let column = [None, Some(2), Some(4), None, None];
+        assert_eq!(column.missing_count(), 3);
+        let present = column.skip_missing();
+        assert_eq!(present.sum(), 6);
+        assert_eq!(present.mean(), Ok(3));
+        assert_eq!(present.variance(), Ok(1));
+        assert_eq!(Stats::min(&present), Ok(2));
+        assert_eq!(present.get(), &column);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_fill() {
+        let column = [Some(2), None, None, Some(8), None, Some(4)];
+        assert_eq!(column.forward_fill(), Ok(vec![2, 2, 2, 8, 8, 4]));
+        assert_eq!(column.backward_fill(), Ok(vec![2, 8, 8, 8, 4, 4]));
+        assert_eq!(column.interpolate(), Ok(vec![2.0, 4.0, 6.0, 8.0, 6.0, 4.0]));
+        let complete = [1.5, 2.5];
+        let column = complete.map(Some);
+        assert_eq!(column.forward_fill().unwrap(), complete);
+        assert_eq!(column.backward_fill().unwrap(), complete);
+        assert_eq!(column.interpolate().unwrap(), complete);
+        let single = [None, None, Some(7u8), None];
+        assert_eq!(single.interpolate(), Ok(vec![7.0; 4]));
+        assert_eq!(single.backward_fill(), Ok(vec![7; 4]));
+
+        let missing: [Option<f64>; 2] = [None, None];
+        assert_eq!(missing.forward_fill(), Err(StatsError::EmptyCollection));
+        assert_eq!(missing.backward_fill(), Err(StatsError::EmptyCollection));
+        assert_eq!(missing.interpolate(), Err(StatsError::EmptyCollection));
+        assert_eq!(
+            Vec::<Option<f64>>::new().interpolate(),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+
+    #[test]
+    fn test_all_missing() {
+        let column: [Option<f64>; 2] = [None, None];
+        assert_eq!(column.missing_count(), 2);
+        assert_eq!(column.skip_missing().count(), 0);
+        assert_eq!(
+            column.skip_missing().mean(),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+}
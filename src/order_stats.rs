@@ -0,0 +1,372 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use num_traits::ToPrimitive;
+
+use crate::helpers::is_nan;
+use crate::helpers::item_to_f64;
+use crate::helpers::quantile_by;
+use crate::Result;
+use crate::StatsError;
+
+type Link<T> = Option<Box<Node<T>>>;
+
+/// A node of the treap, which is a binary search tree by value
+/// and a heap by priority, so it stays balanced on average
+#[derive(Debug, Clone)]
+struct Node<T> {
+    value: T,
+    priority: u64,
+    /// The number of values in the subtree rooted at this node
+    size: usize,
+    left: Link<T>,
+    right: Link<T>,
+}
+
+/// Returns the number of values in the subtree at `link`
+fn size<T>(link: &Link<T>) -> usize {
+    link.as_ref().map_or(0, |node| node.size)
+}
+
+/// Recalculate the size of `node` after its children changed
+fn update<T>(node: &mut Node<T>) {
+    node.size = 1 + size(&node.left) + size(&node.right);
+}
+
+/// Join two subtrees, where every value in `left` is at most every value in `right`
+fn merge<T>(left: Link<T>, right: Link<T>) -> Link<T> {
+    match (left, right) {
+        (None, right) => right,
+        (left, None) => left,
+        (Some(mut left), Some(mut right)) => {
+            if left.priority > right.priority {
+                left.right = merge(left.right.take(), Some(right));
+                update(&mut left);
+                Some(left)
+            } else {
+                right.left = merge(Some(left), right.left.take());
+                update(&mut right);
+                Some(right)
+            }
+        }
+    }
+}
+
+/// Split a subtree into the values for which `goes_left` is `true` and the
+/// rest, where `goes_left` is `true` for every value up to some point
+fn split<T, F>(link: Link<T>, goes_left: &F) -> (Link<T>, Link<T>)
+where
+    F: Fn(&T) -> bool,
+{
+    let Some(mut node) = link else {
+        return (None, None);
+    };
+    if goes_left(&node.value) {
+        let (middle, right) = split(node.right.take(), goes_left);
+        node.right = middle;
+        update(&mut node);
+        (Some(node), right)
+    } else {
+        let (left, middle) = split(node.left.take(), goes_left);
+        node.left = middle;
+        update(&mut node);
+        (left, Some(node))
+    }
+}
+
+/// A sorted multiset of values that can be inserted and removed in
+/// logarithmic time, with the rank of a value, the value at a rank and
+/// the quantiles calculated in logarithmic time as well. This makes exact
+/// quantiles of a sliding window, or of a stream that is still changing,
+/// practical where sorting every time would not be.
+///
+/// It is a [treap](<https://en.wikipedia.org/wiki/Treap>), a binary search
+/// tree with random priorities that keep it balanced on average, with the
+/// size of each subtree stored to find ranks. `NaN`s are ignored, like
+/// [`Stats::quantile`](crate::Stats::quantile) ignores them.
+///
+/// # Examples
+/// ```
+/// use stats_traits::OrderStatsSet;
+/// let mut set = OrderStatsSet::new();
+/// set.extend([5, 1, 4, 1, 3]);
+/// assert_eq!(set.median(), Ok(3.0));
+/// assert_eq!(set.rank(&4), 3);
+/// assert_eq!(set.nth(0), Some(&1));
+/// set.remove(&1);
+/// set.remove(&5);
+/// assert_eq!(set.len(), 3);
+/// assert_eq!(set.median(), Ok(3.0));
+/// assert_eq!(set.quantile(0.25), Ok(2.0));
+/// ```
+#[derive(Debug, Clone)]
+pub struct OrderStatsSet<T> {
+    root: Link<T>,
+    /// The state of the xorshift generator of the priorities
+    seed: u64,
+}
+
+impl<T: PartialOrd> OrderStatsSet<T> {
+    /// Create an empty set
+    pub const fn new() -> Self {
+        Self {
+            root: None,
+            seed: 0x2545_f491_4f6c_dd1d,
+        }
+    }
+
+    /// Returns the number of values in the set, counting repeated values
+    /// as many times as they were inserted
+    pub fn len(&self) -> usize {
+        size(&self.root)
+    }
+
+    /// Returns `true` if the set has no values
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// Remove every value from the set
+    pub fn clear(&mut self) {
+        self.root = None;
+    }
+
+    /// Returns the next priority, from a xorshift generator
+    fn next_priority(&mut self) -> u64 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        self.seed
+    }
+
+    /// Insert `value` into the set, unless it is `NaN`
+    pub fn insert(&mut self, value: T) {
+        if is_nan(&value) {
+            return;
+        }
+        let node = Box::new(Node {
+            priority: self.next_priority(),
+            value,
+            size: 1,
+            left: None,
+            right: None,
+        });
+        let (left, right) = split(self.root.take(), &|x| *x < node.value);
+        self.root = merge(merge(left, Some(node)), right);
+    }
+
+    /// Remove one occurrence of `value` from the set, returning
+    /// whether it was in the set
+    pub fn remove(&mut self, value: &T) -> bool {
+        let (left, rest) = split(self.root.take(), &|x| x < value);
+        let (equal, right) = split(rest, &|x| x <= value);
+        let found = equal.is_some();
+        let equal = equal.and_then(|node| merge(node.left, node.right));
+        self.root = merge(merge(left, equal), right);
+        found
+    }
+
+    /// Returns the number of values in the set that are less than `value`,
+    /// which is the index it would have in the sorted values
+    pub fn rank(&self, value: &T) -> usize {
+        let mut rank = 0;
+        let mut link = &self.root;
+        while let Some(node) = link {
+            if node.value < *value {
+                rank += size(&node.left) + 1;
+                link = &node.right;
+            } else {
+                link = &node.left;
+            }
+        }
+        rank
+    }
+
+    /// Returns the number of occurrences of `value` in the set
+    pub fn count(&self, value: &T) -> usize {
+        let mut at_most = 0;
+        let mut link = &self.root;
+        while let Some(node) = link {
+            if node.value <= *value {
+                at_most += size(&node.left) + 1;
+                link = &node.right;
+            } else {
+                link = &node.left;
+            }
+        }
+        at_most - self.rank(value)
+    }
+
+    /// Returns the value at index `n` of the sorted values, so 0 is the
+    /// smallest, or `None` if there are `n` values or fewer
+    pub fn nth(&self, mut n: usize) -> Option<&T> {
+        let mut link = &self.root;
+        while let Some(node) = link {
+            let left = size(&node.left);
+            if n < left {
+                link = &node.left;
+            } else if n == left {
+                return Some(&node.value);
+            } else {
+                n -= left + 1;
+                link = &node.right;
+            }
+        }
+        None
+    }
+
+    /// Returns the smallest value in the set
+    ///
+    /// # Errors
+    /// Returns an error if the set is empty
+    pub fn min(&self) -> Result<&T> {
+        self.nth(0).ok_or(StatsError::EmptyCollection)
+    }
+
+    /// Returns the largest value in the set
+    ///
+    /// # Errors
+    /// Returns an error if the set is empty
+    pub fn max(&self) -> Result<&T> {
+        let last = self.len().checked_sub(1);
+        last.and_then(|n| self.nth(n))
+            .ok_or(StatsError::EmptyCollection)
+    }
+
+    /// Find the median of the values in the set, which is the mean of the
+    /// middle two if there are an even number of them
+    ///
+    /// # Errors
+    /// Returns an error if the set is empty,
+    /// or if a value can't be converted to [`f64`]
+    pub fn median(&self) -> Result<f64>
+    where
+        T: ToPrimitive + Clone,
+    {
+        self.quantile(0.5)
+    }
+
+    /// Find the `p` quantile of the values in the set, interpolating linearly
+    /// between the two nearest values like [`Stats::quantile`](crate::Stats::quantile)
+    ///
+    /// # Errors
+    /// Returns an error if the set is empty,
+    /// or if a value can't be converted to [`f64`]
+    ///
+    /// # Panics
+    /// Panics if `p` is not between 0 and 1
+    pub fn quantile(&self, p: f64) -> Result<f64>
+    where
+        T: ToPrimitive + Clone,
+    {
+        assert!((0.0..=1.0).contains(&p), "p must be between 0 and 1");
+        let len = self.len();
+        if len == 0 {
+            return Err(StatsError::EmptyCollection);
+        }
+        quantile_by(len, p, |n| {
+            let value = self.nth(n).cloned().ok_or(StatsError::EmptyCollection)?;
+            item_to_f64(value)
+        })
+    }
+
+    /// Returns an iterator over the values in the set, from smallest to largest
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        let mut stack = Vec::new();
+        let mut link = &self.root;
+        core::iter::from_fn(move || {
+            while let Some(node) = link {
+                stack.push(node);
+                link = &node.left;
+            }
+            let node = stack.pop()?;
+            link = &node.right;
+            Some(&node.value)
+        })
+    }
+}
+
+impl<T: PartialOrd> Default for OrderStatsSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: PartialOrd> Extend<T> for OrderStatsSet<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            self.insert(x);
+        }
+    }
+}
+
+impl<T: PartialOrd> FromIterator<T> for OrderStatsSet<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Stats;
+
+    use alloc::collections::VecDeque;
+    use alloc::vec;
+
+    #[test]
+    fn test_matches_sorting() {
+        let values: Vec<u32> = (0..500).map(|x| x * 7919 % 211).collect();
+        let set = OrderStatsSet::from_iter(values.iter().copied());
+        let mut sorted = values.clone();
+        sorted.sort_unstable();
+        assert!(set.iter().eq(sorted.iter()));
+        assert_eq!(set.nth(123), Some(&sorted[123]));
+        assert_eq!(set.rank(&100), sorted.partition_point(|&x| x < 100));
+        assert_eq!(set.count(&100), values.count_of(100));
+        for p in [0.0, 0.1, 0.5, 0.99, 1.0] {
+            assert_eq!(set.quantile(p), values.quantile(p));
+        }
+    }
+
+    #[test]
+    fn test_infinite_values() {
+        let values = [f64::INFINITY, 1.0, 2.0, f64::NEG_INFINITY];
+        let set = OrderStatsSet::from_iter(values);
+        for p in [0.0, 0.25, 0.5, 0.75, 0.9, 1.0] {
+            assert_eq!(set.quantile(p), values.quantile(p), "{p}");
+        }
+        assert_eq!(set.median(), Ok(1.5));
+    }
+
+    #[test]
+    fn test_sliding_window_median() {
+        let stream = [3.0, 9.0, f64::NAN, 1.0, 4.0, 4.0, 8.0, 2.0];
+        let mut window = VecDeque::new();
+        let mut set = OrderStatsSet::new();
+        let mut medians = vec![];
+        for x in stream {
+            window.push_back(x);
+            set.insert(x);
+            if window.len() > 3 {
+                set.remove(&window.pop_front().unwrap());
+            }
+            medians.push(set.median().unwrap());
+        }
+        assert_eq!(medians, [3.0, 6.0, 6.0, 5.0, 2.5, 4.0, 4.0, 4.0]);
+    }
+
+    #[test]
+    fn test_empty() {
+        let mut set = OrderStatsSet::new();
+        assert_eq!(set.median(), Err(StatsError::EmptyCollection));
+        assert_eq!(set.max(), Err(StatsError::EmptyCollection));
+        assert!(!set.remove(&1));
+        set.insert(1);
+        assert!(set.remove(&1));
+        assert!(set.is_empty());
+        assert_eq!(set.nth(0), None);
+    }
+}
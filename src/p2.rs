@@ -0,0 +1,204 @@
+use crate::helpers::partial_cmp_nan_last;
+use crate::helpers::quantile_of_sorted;
+use crate::Result;
+use crate::StatsError;
+
+/// Estimates a single quantile of a stream of values in constant memory,
+/// using the [P² algorithm](<https://www.cse.wustl.edu/~jain/papers/ftp/psqr.pdf>)
+/// of Jain and Chlamtac.
+///
+/// Only five markers are stored, whose heights are adjusted with a
+/// piecewise-parabolic formula as values arrive, so the state is a handful
+/// of numbers and no allocation is needed. This is much less accurate than
+/// [`TDigest`](crate::TDigest) for small streams, but it is tiny.
+///
+/// # Examples
+/// ```
+/// use stats_traits::P2Quantile;
+/// let mut p95 = P2Quantile::new(0.95);
+/// for i in 0..10_000 {
+///     p95.push(((i * 7919) % 10_000) as f64);
+/// }
+/// let estimate = p95.quantile().unwrap();
+/// assert!((estimate - 9_500.0).abs() < 50.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct P2Quantile {
+    p: f64,
+    count: usize,
+    /// Heights of the markers
+    heights: [f64; 5],
+    /// Actual positions of the markers
+    positions: [f64; 5],
+    /// Desired positions of the markers
+    desired: [f64; 5],
+    /// How much the desired positions move with each value
+    increments: [f64; 5],
+}
+
+impl P2Quantile {
+    /// Create an estimator for the `p`-quantile, where `p` is between
+    /// 0 and 1 (so `0.5` is the median and `0.95` the 95th percentile)
+    ///
+    /// # Panics
+    /// Panics if `p` is not between 0 and 1
+    pub const fn new(p: f64) -> Self {
+        assert!(p >= 0.0 && p <= 1.0, "p must be between 0 and 1");
+        Self {
+            p,
+            count: 0,
+            heights: [0.0; 5],
+            positions: [1.0, 2.0, 3.0, 4.0, 5.0],
+            desired: [1.0, 1.0 + 2.0 * p, 1.0 + 4.0 * p, 3.0 + 2.0 * p, 5.0],
+            increments: [0.0, p / 2.0, p, (1.0 + p) / 2.0, 1.0],
+        }
+    }
+
+    /// Returns the quantile being estimated
+    pub fn p(&self) -> f64 {
+        self.p
+    }
+
+    /// Returns the number of values that have been pushed
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Add a value to the estimator
+    pub fn push(&mut self, x: f64) {
+        if self.count < 5 {
+            self.heights[self.count] = x;
+            self.count += 1;
+            if self.count == 5 {
+                self.heights.sort_unstable_by(partial_cmp_nan_last);
+            }
+            return;
+        }
+        self.count += 1;
+
+        // find the cell the value falls in, extending the extremes if needed
+        let cell = if x < self.heights[0] {
+            self.heights[0] = x;
+            0
+        } else if x >= self.heights[4] {
+            self.heights[4] = x;
+            3
+        } else {
+            (1..4).find(|&i| x < self.heights[i]).unwrap_or(4) - 1
+        };
+
+        for position in &mut self.positions[cell + 1..] {
+            *position += 1.0;
+        }
+        for (desired, increment) in self.desired.iter_mut().zip(self.increments) {
+            *desired += increment;
+        }
+
+        for i in 1..4 {
+            let d = self.desired[i] - self.positions[i];
+            if (d >= 1.0 && self.positions[i + 1] - self.positions[i] > 1.0)
+                || (d <= -1.0 && self.positions[i - 1] - self.positions[i] < -1.0)
+            {
+                let d = d.signum();
+                let parabolic = self.parabolic(i, d);
+                self.heights[i] =
+                    if self.heights[i - 1] < parabolic && parabolic < self.heights[i + 1] {
+                        parabolic
+                    } else {
+                        self.linear(i, d)
+                    };
+                self.positions[i] += d;
+            }
+        }
+    }
+
+    /// Piecewise-parabolic prediction of the new height of marker `i` moved by `d`
+    fn parabolic(&self, i: usize, d: f64) -> f64 {
+        let (q, n) = (&self.heights, &self.positions);
+        q[i] + d / (n[i + 1] - n[i - 1])
+            * ((n[i] - n[i - 1] + d) * (q[i + 1] - q[i]) / (n[i + 1] - n[i])
+                + (n[i + 1] - n[i] - d) * (q[i] - q[i - 1]) / (n[i] - n[i - 1]))
+    }
+
+    /// Linear prediction of the new height of marker `i` moved by `d`
+    fn linear(&self, i: usize, d: f64) -> f64 {
+        let (q, n) = (&self.heights, &self.positions);
+        let j = if d > 0.0 { i + 1 } else { i - 1 };
+        q[i] + d * (q[j] - q[i]) / (n[j] - n[i])
+    }
+
+    /// Returns the current estimate of the quantile. Until five values have
+    /// been pushed, this is the exact quantile of those values, interpolating
+    /// linearly between them.
+    ///
+    /// # Errors
+    /// Returns an error if no values have been pushed
+    pub fn quantile(&self) -> Result<f64> {
+        match self.count {
+            0 => Err(StatsError::EmptyCollection),
+            1..=4 => {
+                let mut sorted = self.heights;
+                let sorted = &mut sorted[..self.count];
+                sorted.sort_unstable_by(partial_cmp_nan_last);
+                Ok(quantile_of_sorted(sorted, self.p))
+            }
+            _ => Ok(self.heights[2]),
+        }
+    }
+}
+
+impl Extend<f64> for P2Quantile {
+    fn extend<I: IntoIterator<Item = f64>>(&mut self, iter: I) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(
+            P2Quantile::new(0.5).quantile(),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+
+    #[test]
+    fn test_few_values_exact() {
+        let mut median = P2Quantile::new(0.5);
+        median.extend([3.0, 1.0, 2.0]);
+        assert_eq!(median.quantile(), Ok(2.0));
+        median.push(10.0);
+        assert_eq!(median.quantile(), Ok(2.5));
+    }
+
+    #[test]
+    fn test_paper_example() {
+        // the worked example from the paper by Jain and Chlamtac
+        let data = [
+            0.02, 0.15, 0.74, 3.39, 0.83, 22.37, 10.15, 15.43, 38.62, 15.92, 34.60, 10.28, 1.47,
+            0.40, 0.05, 11.39, 0.27, 0.42, 0.09, 11.37,
+        ];
+        let mut median = P2Quantile::new(0.5);
+        median.extend(data);
+        assert!((median.quantile().unwrap() - 4.44).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_uniform() {
+        for p in [0.1, 0.5, 0.9, 0.99] {
+            let mut estimator = P2Quantile::new(p);
+            for i in 0..100_000u64 {
+                estimator.push(((i * 7919) % 100_000) as f64);
+            }
+            let estimate = estimator.quantile().unwrap();
+            assert!((estimate / 100_000.0 - p).abs() < 0.01);
+        }
+    }
+}
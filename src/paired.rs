@@ -0,0 +1,1290 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+#[cfg(feature = "alloc")]
+use core::cmp::Ordering;
+
+use num_traits::ToPrimitive;
+
+use crate::error::DataType;
+#[cfg(feature = "alloc")]
+use crate::helpers::average_ranks;
+use crate::helpers::item_to_f64;
+#[cfg(feature = "alloc")]
+use crate::helpers::median_in_place;
+#[cfg(feature = "alloc")]
+use crate::helpers::partial_cmp_nan_last;
+use crate::helpers::Float;
+use crate::helpers::NumExt;
+#[cfg(feature = "alloc")]
+use crate::hypothesis::SignedRankTest;
+use crate::regression::Moments;
+use crate::LinearRegression;
+use crate::OnlineBivariate;
+use crate::Result;
+use crate::StatsError;
+use crate::TestResult;
+
+/// Similar to [`Stats`](crate::Stats), but for collections of `(x, y)` pairs,
+/// providing statistics about the relationship between the two values.
+pub trait PairedStats<T>: IntoIterator<Item = (T, T)> + Clone
+where
+    T: NumExt + Copy,
+{
+    /// Returns the number of pairs in the collection
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::PairedStats;
+    /// let vec = vec![(1, 2), (3, 4)];
+    /// assert_eq!(vec.count(), 2);
+    /// ```
+    fn count(&self) -> usize {
+        self.clone().into_iter().count()
+    }
+
+    /// Count the pairs in the collection and convert the result to `T`.
+    /// Returns an error if there are fewer than `required` pairs, or
+    /// if the count could not be converted to `T`.
+    fn count_into_item(&self, required: usize) -> Result<T> {
+        let count = self.count();
+        if count == 0 {
+            return Err(StatsError::EmptyCollection);
+        }
+        if count < required {
+            return Err(StatsError::InsufficientData {
+                required,
+                actual: count,
+            });
+        }
+        T::from_usize(count).ok_or(StatsError::CouldNotConvert {
+            from: DataType::Usize,
+            to: DataType::Item,
+        })
+    }
+
+    /// Calculate the mean of the `x` values
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::PairedStats;
+    /// let vec = vec![(1.0, 2.0), (3.0, 4.0)];
+    /// assert_eq!(vec.mean_x(), Ok(2.0));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty,
+    /// or if the count could not be converted to `T`
+    fn mean_x(&self) -> Result<T> {
+        let count = self.count_into_item(1)?;
+        Ok(self.clone().into_iter().map(|(x, _)| x).sum::<T>() / count)
+    }
+
+    /// Calculate the mean of the `y` values
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::PairedStats;
+    /// let vec = vec![(1.0, 2.0), (3.0, 4.0)];
+    /// assert_eq!(vec.mean_y(), Ok(3.0));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty,
+    /// or if the count could not be converted to `T`
+    fn mean_y(&self) -> Result<T> {
+        let count = self.count_into_item(1)?;
+        Ok(self.clone().into_iter().map(|(_, y)| y).sum::<T>() / count)
+    }
+
+    /// Returns the sum of the products of the differences
+    /// of `x` and `y` from their means
+    fn co_moment(&self) -> Result<T> {
+        let (mean_x, mean_y) = (self.mean_x()?, self.mean_y()?);
+        Ok(self
+            .clone()
+            .into_iter()
+            .map(|(x, y)| (x - mean_x) * (y - mean_y))
+            .sum())
+    }
+
+    /// Calculate the population covariance of the pairs, which is positive
+    /// when `x` and `y` tend to be above their means at the same time, and
+    /// negative when one tends to be above its mean while the other is below.
+    ///
+    /// [Wikipedia](<https://en.wikipedia.org/wiki/Covariance>)
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::PairedStats;
+    /// let vec = vec![(1.0, 1.0), (2.0, 3.0), (3.0, 2.0)];
+    /// assert_eq!(vec.covariance(), Ok(1.0 / 3.0));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty,
+    /// or if the count could not be converted to `T`
+    fn covariance(&self) -> Result<T> {
+        Ok(self.co_moment()? / self.count_into_item(1)?)
+    }
+
+    /// Calculate the sample covariance of the pairs, which divides by one
+    /// less than the number of pairs (Bessel's correction) to give an
+    /// unbiased estimate of the covariance of the population the pairs
+    /// were sampled from.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::PairedStats;
+    /// let vec = vec![(1.0, 1.0), (2.0, 3.0), (3.0, 2.0)];
+    /// assert_eq!(vec.sample_covariance(), Ok(0.5));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if there are fewer than two pairs,
+    /// or if the count could not be converted to `T`
+    fn sample_covariance(&self) -> Result<T> {
+        let count = self.count_into_item(2)?;
+        Ok(self.co_moment()? / (count - T::one()))
+    }
+
+    /// Calculate the Pearson correlation coefficient of the pairs, which is
+    /// between -1 and 1 and measures how close to a straight line the pairs
+    /// are. It is 1 when `y` increases linearly with `x`, -1 when it decreases
+    /// linearly, and 0 when there is no linear relationship.
+    ///
+    /// [Wikipedia](<https://en.wikipedia.org/wiki/Pearson_correlation_coefficient>)
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::PairedStats;
+    /// let vec = vec![(1, 2), (2, 4), (3, 6)];
+    /// assert_eq!(vec.pearson_r(), Ok(1.0));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty, if every `x` or every `y`
+    /// is the same (`StatsError::ZeroVariance`), or if an item could not be
+    /// converted to [`f64`]
+    fn pearson_r(&self) -> Result<f64>
+    where
+        T: ToPrimitive,
+    {
+        correlation(self.clone().into_iter().map(pair_to_f64))
+    }
+
+    /// Calculate the Spearman rank correlation coefficient of the pairs,
+    /// which is the Pearson correlation coefficient of the ranks of the
+    /// `x` values and the ranks of the `y` values. It is between -1 and 1,
+    /// and measures how well the relationship between `x` and `y` can be
+    /// described by any increasing (or decreasing) function, so it isn't
+    /// thrown off by outliers or non-linear relationships.
+    /// Tied values are given the mean of the ranks they span.
+    ///
+    /// [Wikipedia](<https://en.wikipedia.org/wiki/Spearman%27s_rank_correlation_coefficient>)
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::PairedStats;
+    /// // y = x^3 isn't a straight line, but it is always increasing
+    /// let vec = vec![(1, 1), (2, 8), (3, 27), (4, 64)];
+    /// assert_eq!(vec.spearman_rho(), Ok(1.0));
+    /// assert!(vec.pearson_r().unwrap() < 1.0);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error under the same conditions as [`PairedStats::pearson_r`]
+    #[cfg(feature = "alloc")]
+    fn spearman_rho(&self) -> Result<f64>
+    where
+        T: ToPrimitive,
+    {
+        let (xs, ys): (Vec<f64>, Vec<f64>) = self
+            .clone()
+            .into_iter()
+            .map(pair_to_f64)
+            .collect::<Result<Vec<(f64, f64)>>>()?
+            .into_iter()
+            .unzip();
+        let (x_ranks, y_ranks) = (average_ranks(&xs), average_ranks(&ys));
+        correlation(x_ranks.into_iter().zip(y_ranks).map(Ok))
+    }
+
+    /// Calculate Kendall's tau-b rank correlation coefficient of the pairs,
+    /// which compares how many pairs of pairs are in the same order by `x` as
+    /// by `y` (concordant) with how many are in opposite orders (discordant).
+    /// It is between -1 and 1, and is corrected for ties in either value.
+    ///
+    /// This uses the algorithm of [Knight](<https://doi.org/10.1080/01621459.1966.10480901>),
+    /// which counts the discordant pairs with a merge sort in O(n log n) time.
+    ///
+    /// [Wikipedia](<https://en.wikipedia.org/wiki/Kendall_rank_correlation_coefficient>)
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::PairedStats;
+    /// // of the 10 pairs of pairs, only (1, 2), (2, 1) and (3, 4), (4, 3) are discordant
+    /// let vec = vec![(1, 2), (2, 1), (3, 4), (4, 3), (5, 5)];
+    /// assert_eq!(vec.kendall_tau(), Ok(0.6));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if there are fewer than two pairs, if every `x` or
+    /// every `y` is the same (`StatsError::ZeroVariance`), or if an item
+    /// could not be converted to [`f64`]
+    #[cfg(feature = "alloc")]
+    fn kendall_tau(&self) -> Result<f64>
+    where
+        T: ToPrimitive,
+    {
+        let mut pairs = at_least_two_pairs(self.clone())?;
+
+        pairs.sort_by(|a, b| {
+            partial_cmp_nan_last(&a.0, &b.0).then_with(|| partial_cmp_nan_last(&a.1, &b.1))
+        });
+        let total = pairs.len() as u64 * (pairs.len() as u64 - 1) / 2;
+        let x_ties = tied_pairs(&pairs, |a, b| partial_cmp_nan_last(&a.0, &b.0));
+        let joint_ties = tied_pairs(&pairs, |a, b| {
+            partial_cmp_nan_last(&a.0, &b.0).then_with(|| partial_cmp_nan_last(&a.1, &b.1))
+        });
+
+        let mut ys: Vec<f64> = pairs.iter().map(|&(_, y)| y).collect();
+        let mut buffer = ys.clone();
+        let swaps = merge_sort_swaps(&mut ys, &mut buffer);
+        let y_ties = tied_pairs(&ys, partial_cmp_nan_last);
+
+        if x_ties == total || y_ties == total {
+            return Err(StatsError::ZeroVariance);
+        }
+        // concordant minus discordant pairs, computed as signed floats so it can be negative
+        let difference =
+            (total + joint_ties) as f64 - (x_ties + y_ties) as f64 - 2.0 * swaps as f64;
+        let denominator = Float::sqrt((total - x_ties) as f64 * (total - y_ties) as f64);
+        Ok(difference / denominator)
+    }
+
+    /// Fit a straight line `y = slope * x + intercept` through the pairs,
+    /// choosing the line that minimizes the sum of the squared differences
+    /// between each `y` and the line (ordinary least squares).
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::PairedStats;
+    /// let vec = vec![(1, 1), (2, 3), (3, 2), (4, 5)];
+    /// let fit = vec.linear_regression().unwrap();
+    /// assert_eq!(fit.slope, 1.1);
+    /// assert_eq!(fit.intercept, 0.0);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if there are fewer than two pairs, if every `x`
+    /// is the same (`StatsError::ZeroVariance`), as then the line would be
+    /// vertical, or if an item could not be converted to [`f64`]
+    fn linear_regression(&self) -> Result<LinearRegression>
+    where
+        T: ToPrimitive,
+    {
+        let mut stats = OnlineBivariate::new();
+        for pair in self.clone() {
+            let (x, y) = pair_to_f64(pair)?;
+            stats.push(x, y);
+        }
+        LinearRegression::from_moments(&Moments::from_bivariate(&stats)?)
+    }
+
+    /// Fit a straight line `y = slope * x + intercept` through the pairs by
+    /// [weighted least squares](<https://en.wikipedia.org/wiki/Weighted_least_squares>),
+    /// where each pair has a weight from `weights`, in the same order as the
+    /// pairs. Pairs with larger weights count for more, so when the `y` values
+    /// have different uncertainties, the inverse of the variance of each `y`
+    /// is a good choice of weight. Pairs with a weight of 0 are left out.
+    ///
+    /// The weighted sums of squares are used in place of the ordinary ones
+    /// in the fields of the [`LinearRegression`], and the standard errors
+    /// are scaled by the weighted residual variance, so only the relative
+    /// sizes of the weights matter.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::PairedStats;
+    /// let vec = vec![(0.0, 0.0), (1.0, 1.0), (2.0, 2.0), (3.0, 10.0)];
+    /// // the last point is very uncertain, so it barely counts
+    /// let fit = vec.weighted_linear_regression([1.0, 1.0, 1.0, 1e-9]).unwrap();
+    /// assert!((fit.slope - 1.0).abs() < 1e-6);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if fewer than two pairs have weights greater than 0,
+    /// if every `x` is the same (`StatsError::ZeroVariance`), or if an item
+    /// could not be converted to [`f64`]
+    ///
+    /// # Panics
+    /// Panics if there aren't the same number of weights as pairs,
+    /// or if a weight is negative or `NaN`
+    fn weighted_linear_regression<W>(&self, weights: W) -> Result<LinearRegression>
+    where
+        T: ToPrimitive,
+        W: IntoIterator<Item = f64>,
+    {
+        LinearRegression::from_moments(&weighted_moments(self.clone(), weights)?)
+    }
+
+    /// Calculate the weighted (population) covariance of the pairs, where
+    /// each pair has a weight from `weights`, in the same order as the pairs,
+    /// such as the design weights of a survey. It is the weighted mean of
+    /// the products of the differences of `x` and `y` from their weighted
+    /// means, so it is [`PairedStats::covariance`] when every weight is 1.
+    /// Pairs with a weight of 0 are left out.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::PairedStats;
+    /// use approx::assert_relative_eq;
+    /// let vec = vec![(1.0, 1.0), (2.0, 3.0), (3.0, 2.0)];
+    /// assert_relative_eq!(vec.weighted_covariance([1.0, 1.0, 1.0]).unwrap(), 1.0 / 3.0);
+    /// // the same as repeating the first pair
+    /// let repeated = vec![(1.0, 1.0), (1.0, 1.0), (2.0, 3.0), (3.0, 2.0)];
+    /// assert_relative_eq!(
+    ///     vec.weighted_covariance([2.0, 1.0, 1.0]).unwrap(),
+    ///     repeated.covariance().unwrap()
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if no pair has a weight greater than 0,
+    /// or if an item could not be converted to [`f64`]
+    ///
+    /// # Panics
+    /// Panics if there aren't the same number of weights as pairs,
+    /// or if a weight is negative or `NaN`
+    fn weighted_covariance<W>(&self, weights: W) -> Result<f64>
+    where
+        T: ToPrimitive,
+        W: IntoIterator<Item = f64>,
+    {
+        let moments = weighted_moments(self.clone(), weights)?;
+        if moments.count == 0 {
+            return Err(StatsError::EmptyCollection);
+        }
+        Ok(moments.co_moment / moments.weight)
+    }
+
+    /// Calculate the weighted Pearson correlation coefficient of the pairs,
+    /// where each pair has a weight from `weights`, in the same order as the
+    /// pairs, like [`PairedStats::weighted_covariance`]. It is
+    /// [`PairedStats::pearson_r`] when every weight is 1, and only the
+    /// relative sizes of the weights matter.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::PairedStats;
+    /// let vec = vec![(1.0, 2.0), (2.0, 4.0), (3.0, 6.0), (4.0, 0.0)];
+    /// // the last pair doesn't count, so the rest are on a line
+    /// assert_eq!(vec.weighted_pearson_r([1.0, 1.0, 1.0, 0.0]), Ok(1.0));
+    /// assert!(vec.weighted_pearson_r([1.0, 1.0, 1.0, 1.0]).unwrap() < 0.0);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if no pair has a weight greater than 0, if every `x`
+    /// or every `y` with a weight is the same (`StatsError::ZeroVariance`),
+    /// or if an item could not be converted to [`f64`]
+    ///
+    /// # Panics
+    /// Panics if there aren't the same number of weights as pairs,
+    /// or if a weight is negative or `NaN`
+    fn weighted_pearson_r<W>(&self, weights: W) -> Result<f64>
+    where
+        T: ToPrimitive,
+        W: IntoIterator<Item = f64>,
+    {
+        let moments = weighted_moments(self.clone(), weights)?;
+        if moments.count == 0 {
+            return Err(StatsError::EmptyCollection);
+        }
+        if moments.m2_x == 0.0 || moments.m2_y == 0.0 {
+            return Err(StatsError::ZeroVariance);
+        }
+        let r = moments.co_moment / Float::sqrt(moments.m2_x * moments.m2_y);
+        // rounding can take it just outside of -1 to 1
+        Ok(r.clamp(-1.0, 1.0))
+    }
+
+    /// Fit a straight line `y = slope * x + intercept` through the pairs by
+    /// [quantile regression](<https://en.wikipedia.org/wiki/Quantile_regression>),
+    /// returning its slope and intercept. The line estimates the `p`
+    /// quantile of `y` at each `x`, where [`PairedStats::linear_regression`]
+    /// estimates the mean, so with `p = 0.5` it is least absolute deviations
+    /// regression, which isn't thrown off by a heavy-tailed `y` the way
+    /// least squares is.
+    ///
+    /// The line minimizes the sum of the residuals weighted by `p` above it
+    /// and `1 - p` below it. It is found by iteratively reweighted least
+    /// squares, starting from the least squares line, so it is accurate to
+    /// about 1e-9 of the typical residual rather than exact, and takes a
+    /// pass over the pairs for each iteration, up to 200 of them.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::PairedStats;
+    /// // y = 2x + 1, apart from one wild outlier
+    /// let vec = vec![(0.0, 1.0), (1.0, 3.0), (2.0, 5.0), (3.0, 100.0), (4.0, 9.0)];
+    /// let (slope, intercept) = vec.quantile_regression(0.5).unwrap();
+    /// assert!((slope - 2.0).abs() < 1e-6);
+    /// assert!((intercept - 1.0).abs() < 1e-6);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if there are fewer than two pairs, if every `x` is
+    /// the same (`StatsError::ZeroVariance`), or if an item could not be
+    /// converted to [`f64`]
+    ///
+    /// # Panics
+    /// Panics if `p` is not greater than 0 and less than 1
+    fn quantile_regression(&self, p: f64) -> Result<(f64, f64)>
+    where
+        T: ToPrimitive,
+    {
+        assert!(p > 0.0 && p < 1.0, "p must be between 0 and 1");
+        let fit = self.linear_regression()?;
+        let (mut slope, mut intercept) = (fit.slope, fit.intercept);
+        let mut residuals = 0.0;
+        for pair in self.clone() {
+            let (x, y) = pair_to_f64(pair)?;
+            residuals += (y - slope * x - intercept).abs();
+        }
+        if residuals == 0.0 {
+            return Ok((slope, intercept));
+        }
+        // the smallest residual that is weighted by, so that the weights of
+        // the pairs on the line stay finite
+        let floor = residuals / fit.count as f64 * 1e-9;
+        for _ in 0..200 {
+            let mut moments = Moments::default();
+            for pair in self.clone() {
+                let (x, y) = pair_to_f64(pair)?;
+                let residual = y - slope * x - intercept;
+                let side = if residual < 0.0 { 1.0 - p } else { p };
+                moments.push(x, y, side / residual.abs().max(floor));
+            }
+            let fit = LinearRegression::from_moments(&moments)?;
+            let converged = (fit.slope - slope).abs() <= 1e-12 * (1.0 + slope.abs())
+                && (fit.intercept - intercept).abs() <= 1e-12 * (1.0 + intercept.abs());
+            (slope, intercept) = (fit.slope, fit.intercept);
+            if converged {
+                break;
+            }
+        }
+        Ok((slope, intercept))
+    }
+
+    /// Fit a straight line through the pairs with the
+    /// [Theil–Sen estimator](<https://en.wikipedia.org/wiki/Theil%E2%80%93Sen_estimator>),
+    /// returning its slope and intercept.
+    ///
+    /// The slope is the median of the slopes between every two pairs (that
+    /// have different `x` values), and the intercept is the median of
+    /// `y - slope * x`. Unlike [`PairedStats::linear_regression`], up to
+    /// about 29% of the pairs can be outliers without throwing the line off.
+    /// This takes O(n²) time and memory.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::PairedStats;
+    /// // y = 2x + 1, apart from one wild outlier
+    /// let vec = vec![(0.0, 1.0), (1.0, 3.0), (2.0, 5.0), (3.0, 100.0), (4.0, 9.0)];
+    /// assert_eq!(vec.theil_sen(), Ok((2.0, 1.0)));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if there are fewer than two pairs, if every `x` is
+    /// the same (`StatsError::ZeroVariance`), or if an item could not be
+    /// converted to [`f64`]
+    #[cfg(feature = "alloc")]
+    fn theil_sen(&self) -> Result<(f64, f64)>
+    where
+        T: ToPrimitive,
+    {
+        let pairs = at_least_two_pairs(self.clone())?;
+        let mut slopes: Vec<f64> = pairs
+            .iter()
+            .enumerate()
+            .flat_map(|(i, a)| pairs[i + 1..].iter().map(move |b| (a, b)))
+            .filter(|(a, b)| a.0 != b.0)
+            .map(|(a, b)| (b.1 - a.1) / (b.0 - a.0))
+            .collect();
+        if slopes.is_empty() {
+            return Err(StatsError::ZeroVariance);
+        }
+        let slope = median_in_place(&mut slopes);
+        Ok((slope, median_intercept(&pairs, slope)))
+    }
+
+    /// Fit a straight line through the pairs with Siegel's
+    /// [repeated median](<https://en.wikipedia.org/wiki/Repeated_median_regression>)
+    /// estimator, returning its slope and intercept.
+    ///
+    /// This is a variant of [`PairedStats::theil_sen`] where the slope is the
+    /// median, over each pair, of the median of the slopes from that pair to
+    /// every other pair. It can cope with up to half of the pairs being outliers.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::PairedStats;
+    /// // y = 2x + 1, apart from two wild outliers
+    /// let mut vec: Vec<(f64, f64)> = (0..7).map(|x| (x as f64, 2.0 * x as f64 + 1.0)).collect();
+    /// vec[2].1 = 50.0;
+    /// vec[3].1 = 100.0;
+    /// assert_eq!(vec.repeated_median(), Ok((2.0, 1.0)));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error under the same conditions as [`PairedStats::theil_sen`]
+    #[cfg(feature = "alloc")]
+    fn repeated_median(&self) -> Result<(f64, f64)>
+    where
+        T: ToPrimitive,
+    {
+        let pairs = at_least_two_pairs(self.clone())?;
+        let mut medians: Vec<f64> = pairs
+            .iter()
+            .filter_map(|a| {
+                let mut slopes: Vec<f64> = pairs
+                    .iter()
+                    .filter(|b| b.0 != a.0)
+                    .map(|b| (b.1 - a.1) / (b.0 - a.0))
+                    .collect();
+                (!slopes.is_empty()).then(|| median_in_place(&mut slopes))
+            })
+            .collect();
+        if medians.is_empty() {
+            return Err(StatsError::ZeroVariance);
+        }
+        let slope = median_in_place(&mut medians);
+        Ok((slope, median_intercept(&pairs, slope)))
+    }
+
+    /// Calculate the cross-correlation of the pairs at `lag`, treating the
+    /// `x` values and the `y` values as two time series. This is the
+    /// correlation between each `x` and the `y` that is `lag` places after
+    /// it, so with a positive `lag` the `y` series is correlated with the
+    /// `x` series of `lag` steps earlier, and with a negative `lag` the `x`
+    /// series is correlated with the `y` series of `-lag` steps earlier.
+    /// As with [`Stats::autocorrelation`](crate::Stats::autocorrelation),
+    /// the products are divided by the sums of squares of the whole series.
+    ///
+    /// [Wikipedia](<https://en.wikipedia.org/wiki/Cross-correlation>)
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::PairedStats;
+    /// // y is x delayed by one step
+    /// let x = [1.0, 5.0, 2.0, 4.0, 3.0];
+    /// let y = [3.0, 1.0, 5.0, 2.0, 4.0];
+    /// let series: Vec<(f64, f64)> = x.into_iter().zip(y).collect();
+    /// assert!((series.cross_correlation(1).unwrap() - 1.0).abs() < 1e-12);
+    /// assert!((series.cross_correlation(0).unwrap() + 0.7).abs() < 1e-12);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if there are not more pairs than the size of `lag`,
+    /// if every `x` or every `y` is the same (`StatsError::ZeroVariance`),
+    /// or if an item could not be converted to [`f64`]
+    fn cross_correlation(&self, lag: isize) -> Result<f64>
+    where
+        T: ToPrimitive,
+    {
+        let mut stats = OnlineBivariate::new();
+        for pair in self.clone() {
+            let (x, y) = pair_to_f64(pair)?;
+            stats.push(x, y);
+        }
+        let count = stats.count();
+        if count == 0 {
+            return Err(StatsError::EmptyCollection);
+        }
+        let shift = lag.unsigned_abs();
+        if count <= shift {
+            return Err(StatsError::InsufficientData {
+                required: shift + 1,
+                actual: count,
+            });
+        }
+        let (variance_x, variance_y) = (stats.variance_x()?, stats.variance_y()?);
+        if variance_x == 0.0 || variance_y == 0.0 {
+            return Err(StatsError::ZeroVariance);
+        }
+
+        let (mean_x, mean_y) = (stats.mean_x()?, stats.mean_y()?);
+        let xs = self.clone().into_iter().map(|(x, _)| x);
+        let ys = self.clone().into_iter().map(|(_, y)| y);
+        let (x_skip, y_skip) = if lag >= 0 { (0, shift) } else { (shift, 0) };
+        let mut sum_products = 0.0;
+        for (x, y) in xs.skip(x_skip).zip(ys.skip(y_skip)) {
+            sum_products += (item_to_f64(x)? - mean_x) * (item_to_f64(y)? - mean_y);
+        }
+        Ok(sum_products / (count as f64 * Float::sqrt(variance_x * variance_y)))
+    }
+
+    /// Calculate the cross-correlation function of the pairs, which is the
+    /// [`PairedStats::cross_correlation`] at each lag from `-max_lag` to
+    /// `max_lag`, so the cross-correlation at lag `k` is at index `k + max_lag`
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::PairedStats;
+    /// let x = [1.0, 5.0, 2.0, 4.0, 3.0];
+    /// let y = [3.0, 1.0, 5.0, 2.0, 4.0];
+    /// let series: Vec<(f64, f64)> = x.into_iter().zip(y).collect();
+    /// let ccf = series.ccf(2).unwrap();
+    /// // the lag where the series line up best
+    /// let best = (0..ccf.len()).max_by(|&a, &b| ccf[a].total_cmp(&ccf[b])).unwrap();
+    /// assert_eq!(best as isize - 2, 1);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error under the same conditions as
+    /// [`PairedStats::cross_correlation`] at `max_lag`
+    #[cfg(feature = "alloc")]
+    fn ccf(&self, max_lag: usize) -> Result<Vec<f64>>
+    where
+        T: ToPrimitive,
+    {
+        let max_lag = max_lag as isize;
+        (-max_lag..=max_lag)
+            .map(|lag| self.cross_correlation(lag))
+            .collect()
+    }
+
+    /// Test whether the differences `x - y` of the pairs are centred on 0 with the
+    /// [Wilcoxon signed-rank test](<https://en.wikipedia.org/wiki/Wilcoxon_signed-rank_test>),
+    /// the usual alternative to a paired t-test that doesn't assume the
+    /// differences are normally distributed.
+    ///
+    /// Pairs with a difference of zero are dropped, and tied differences are
+    /// given the mean of the ranks they span. When there are fewer than 50
+    /// differences and no zeros or ties, the p-value is exact; otherwise it
+    /// comes from the normal approximation, corrected for ties but without a
+    /// continuity correction.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::PairedStats;
+    /// let before = [1.83, 0.50, 1.62, 2.48, 1.68, 1.88, 1.55, 3.06, 1.30];
+    /// let after = [0.878, 0.647, 0.598, 2.05, 1.06, 1.29, 1.06, 3.14, 1.29];
+    /// let pairs: Vec<(f64, f64)> = before.into_iter().zip(after).collect();
+    /// let test = pairs.wilcoxon_signed_rank().unwrap();
+    /// assert_eq!(test.statistic, 40.0);
+    /// assert!(test.exact);
+    /// assert!((test.p_value - 0.039).abs() < 1e-3);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty, if every difference is
+    /// zero (`StatsError::ZeroVariance`), or if a value can't be converted to [`f64`]
+    #[cfg(feature = "alloc")]
+    fn wilcoxon_signed_rank(&self) -> Result<SignedRankTest>
+    where
+        T: ToPrimitive,
+    {
+        let differences = self
+            .clone()
+            .into_iter()
+            .map(|pair| pair_to_f64(pair).map(|(x, y)| x - y))
+            .collect::<Result<Vec<f64>>>()?;
+        SignedRankTest::from_differences(&differences)
+    }
+
+    /// Test whether the differences `x - y` of the pairs have a median of 0
+    /// with the [sign test](<https://en.wikipedia.org/wiki/Sign_test>), which
+    /// only uses how many pairs have `x > y` and how many have `x < y`, so it
+    /// makes even fewer assumptions than [`PairedStats::wilcoxon_signed_rank`].
+    /// Pairs with `x == y` are dropped, and the p-value is exact, from the
+    /// binomial distribution. The statistic is the number of pairs with `x > y`.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::PairedStats;
+    /// let before = [72, 80, 65, 90, 77, 84, 69, 75, 88, 70];
+    /// let after = [68, 74, 65, 85, 70, 80, 70, 71, 80, 66];
+    /// let pairs: Vec<(i32, i32)> = before.into_iter().zip(after).collect();
+    /// let test = pairs.sign_test().unwrap();
+    /// assert_eq!(test.statistic, 8.0);
+    /// assert!((test.p_value - 0.0390625).abs() < 1e-12);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty, or if `x == y`
+    /// for every pair (`StatsError::ZeroVariance`)
+    fn sign_test(&self) -> Result<TestResult>
+    where
+        T: PartialOrd,
+    {
+        let (mut above, mut below, mut count) = (0, 0, 0);
+        for (x, y) in self.clone() {
+            count += 1;
+            if x > y {
+                above += 1;
+            } else if x < y {
+                below += 1;
+            }
+        }
+        TestResult::sign_test(above, below, count)
+    }
+}
+
+/// Convert the pairs to [`f64`], returning an error if there are fewer than two of them
+#[cfg(feature = "alloc")]
+fn at_least_two_pairs<I, T>(pairs: I) -> Result<Vec<(f64, f64)>>
+where
+    I: IntoIterator<Item = (T, T)>,
+    T: ToPrimitive,
+{
+    let pairs = pairs
+        .into_iter()
+        .map(pair_to_f64)
+        .collect::<Result<Vec<(f64, f64)>>>()?;
+    match pairs.len() {
+        0 => Err(StatsError::EmptyCollection),
+        1 => Err(StatsError::InsufficientData {
+            required: 2,
+            actual: 1,
+        }),
+        _ => Ok(pairs),
+    }
+}
+
+/// Returns the median of `y - slope * x` over the pairs
+#[cfg(feature = "alloc")]
+fn median_intercept(pairs: &[(f64, f64)], slope: f64) -> f64 {
+    let mut intercepts: Vec<f64> = pairs.iter().map(|&(x, y)| y - slope * x).collect();
+    median_in_place(&mut intercepts)
+}
+
+/// Returns the number of pairs of items of the sorted `items` that `compare`
+/// says are equal, so for each run of `t` equal items there are `t * (t - 1) / 2`
+#[cfg(feature = "alloc")]
+fn tied_pairs<T, F>(items: &[T], mut compare: F) -> u64
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut ties = 0;
+    let mut run = 1;
+    for pair in items.windows(2) {
+        if compare(&pair[0], &pair[1]) == Ordering::Equal {
+            run += 1;
+        } else {
+            ties += run * (run - 1) / 2;
+            run = 1;
+        }
+    }
+    ties + run * (run - 1) / 2
+}
+
+/// Sort `values` with a merge sort, using `buffer` (which must be the same
+/// length) as scratch space, and return how many swaps of neighbouring
+/// values a bubble sort would have needed, which is the number of pairs
+/// of values that were out of order
+#[cfg(feature = "alloc")]
+fn merge_sort_swaps(values: &mut [f64], buffer: &mut [f64]) -> u64 {
+    if values.len() < 2 {
+        return 0;
+    }
+    let mid = values.len() / 2;
+    let mut swaps = merge_sort_swaps(&mut values[..mid], &mut buffer[..mid])
+        + merge_sort_swaps(&mut values[mid..], &mut buffer[mid..]);
+
+    let (mut left, mut right) = (0, mid);
+    for slot in buffer[..values.len()].iter_mut() {
+        if right == values.len()
+            || (left < mid && partial_cmp_nan_last(&values[right], &values[left]) != Ordering::Less)
+        {
+            *slot = values[left];
+            left += 1;
+        } else {
+            // the value from the right half jumps over every value left in the left half
+            *slot = values[right];
+            swaps += (mid - left) as u64;
+            right += 1;
+        }
+    }
+    values.copy_from_slice(&buffer[..values.len()]);
+    swaps
+}
+
+/// Convert both values of a pair to [`f64`]
+fn pair_to_f64<T: ToPrimitive>((x, y): (T, T)) -> Result<(f64, f64)> {
+    Ok((item_to_f64(x)?, item_to_f64(y)?))
+}
+
+/// Returns the weighted moments of `pairs`, where each pair has a weight
+/// from `weights`, in the same order
+///
+/// # Panics
+/// Panics if there aren't the same number of weights as pairs,
+/// or if a weight is negative or `NaN`
+fn weighted_moments<I, T, W>(pairs: I, weights: W) -> Result<Moments>
+where
+    I: IntoIterator<Item = (T, T)>,
+    T: ToPrimitive,
+    W: IntoIterator<Item = f64>,
+{
+    let mut moments = Moments::default();
+    let mut weights = weights.into_iter();
+    for pair in pairs {
+        let w = weights
+            .next()
+            .expect("there must be the same number of weights as pairs");
+        assert!(w >= 0.0, "weights must not be negative or NaN");
+        let (x, y) = pair_to_f64(pair)?;
+        moments.push(x, y, w);
+    }
+    assert!(
+        weights.next().is_none(),
+        "there must be the same number of weights as pairs"
+    );
+    Ok(moments)
+}
+
+/// Returns the Pearson correlation coefficient of `pairs`
+fn correlation<I>(pairs: I) -> Result<f64>
+where
+    I: IntoIterator<Item = Result<(f64, f64)>>,
+{
+    let mut stats = OnlineBivariate::new();
+    for pair in pairs {
+        let (x, y) = pair?;
+        stats.push(x, y);
+    }
+    if stats.variance_x()? == 0.0 || stats.variance_y()? == 0.0 {
+        return Err(StatsError::ZeroVariance);
+    }
+    stats.correlation()
+}
+
+impl<T, I> PairedStats<T> for I
+where
+    I: IntoIterator<Item = (T, T)> + Clone,
+    T: NumExt + Copy,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    extern crate std;
+    use std::vec;
+    use std::vec::Vec;
+
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_means() {
+        let vec = vec![(1, 10), (2, 20), (6, 30)];
+        assert_eq!(vec.count(), 3);
+        assert_eq!(vec.mean_x(), Ok(3));
+        assert_eq!(vec.mean_y(), Ok(20));
+    }
+
+    #[test]
+    fn test_covariance() {
+        let vec = vec![
+            (1.0, 2.0),
+            (2.0, 1.0),
+            (4.0, 5.0),
+            (5.0, 4.0),
+            (7.0, 9.0),
+            (8.0, 6.0),
+        ];
+        assert_relative_eq!(vec.covariance().unwrap(), 33.5 / 6.0);
+        assert_relative_eq!(vec.sample_covariance().unwrap(), 33.5 / 5.0);
+    }
+
+    #[test]
+    fn test_covariance_integers() {
+        let vec = vec![(1, 3), (2, 2), (3, 1)];
+        assert_eq!(vec.covariance(), Ok(0));
+        assert_eq!(vec.sample_covariance(), Ok(-1));
+    }
+
+    #[test]
+    fn test_pearson_r() {
+        let vec = vec![
+            (1.0, 2.0),
+            (2.0, 1.0),
+            (4.0, 5.0),
+            (5.0, 4.0),
+            (7.0, 9.0),
+            (8.0, 6.0),
+        ];
+        assert_relative_eq!(vec.pearson_r().unwrap(), 0.8491904957028005);
+        let vec = vec![(1, 30), (2, 20), (3, 10)];
+        assert_relative_eq!(vec.pearson_r().unwrap(), -1.0);
+    }
+
+    #[test]
+    fn test_pearson_r_zero_variance() {
+        assert_eq!(
+            vec![(1.0, 2.0), (1.0, 3.0)].pearson_r(),
+            Err(StatsError::ZeroVariance)
+        );
+        assert_eq!(
+            vec![(1, 5), (2, 5), (3, 5)].pearson_r(),
+            Err(StatsError::ZeroVariance)
+        );
+        let empty: Vec<(f64, f64)> = Vec::new();
+        assert_eq!(empty.pearson_r(), Err(StatsError::EmptyCollection));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_spearman_rho() {
+        // ranks of x are [1, 2, 3, 4, 5] and of y are [2, 1, 4, 3, 5]
+        let vec = vec![
+            (1.0, 20.0),
+            (2.0, 10.0),
+            (3.0, 1e6),
+            (4.0, 30.0),
+            (5.0, 2e6),
+        ];
+        assert_relative_eq!(vec.spearman_rho().unwrap(), 0.8);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_spearman_rho_ties() {
+        // ranks of x are [1.5, 1.5, 3, 4] and of y are [1, 2.5, 2.5, 4]
+        let vec = vec![(1, 5), (1, 7), (2, 7), (3, 9)];
+        assert_relative_eq!(vec.spearman_rho().unwrap(), 5.0 / 6.0);
+        assert_eq!(
+            vec![(1, 2), (3, 2)].spearman_rho(),
+            Err(StatsError::ZeroVariance)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_kendall_tau_ties() {
+        let vec = vec![(1, 1), (1, 2), (2, 2), (3, 1), (3, 3), (4, 3), (4, 3)];
+        assert_relative_eq!(vec.kendall_tau().unwrap(), 0.5892556509887896);
+        let reversed: Vec<(i32, i32)> = vec.iter().map(|&(x, y)| (x, -y)).collect();
+        assert_relative_eq!(reversed.kendall_tau().unwrap(), -0.5892556509887896);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_kendall_tau_matches_naive() {
+        // a scrambled sequence with plenty of ties
+        let vec: Vec<(f64, f64)> = (0..200_u64)
+            .map(|i| ((i % 17) as f64, ((i * 7919) % 23) as f64))
+            .collect();
+        let (mut concordant, mut discordant, mut x_ties, mut y_ties) = (0.0_f64, 0.0, 0.0, 0.0);
+        for (i, a) in vec.iter().enumerate() {
+            for b in &vec[i + 1..] {
+                let (dx, dy) = (a.0 - b.0, a.1 - b.1);
+                match (dx == 0.0, dy == 0.0) {
+                    (true, true) => {}
+                    (true, false) => x_ties += 1.0,
+                    (false, true) => y_ties += 1.0,
+                    _ if dx * dy > 0.0 => concordant += 1.0,
+                    _ => discordant += 1.0,
+                }
+            }
+        }
+        let naive = (concordant - discordant)
+            / ((concordant + discordant + x_ties) * (concordant + discordant + y_ties)).sqrt();
+        assert_relative_eq!(vec.kendall_tau().unwrap(), naive, epsilon = 1e-12);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_kendall_tau_errors() {
+        assert_eq!(
+            vec![(1.0, 2.0)].kendall_tau(),
+            Err(StatsError::InsufficientData {
+                required: 2,
+                actual: 1
+            })
+        );
+        assert_eq!(
+            vec![(1, 2), (1, 3)].kendall_tau(),
+            Err(StatsError::ZeroVariance)
+        );
+    }
+
+    #[test]
+    fn test_linear_regression() {
+        let vec = vec![
+            (1.0, 2.0),
+            (2.0, 1.0),
+            (4.0, 5.0),
+            (5.0, 4.0),
+            (7.0, 9.0),
+            (8.0, 6.0),
+        ];
+        let fit = vec.linear_regression().unwrap();
+        assert_relative_eq!(fit.slope, 33.5 / 37.5);
+        assert_relative_eq!(fit.intercept, 4.5 - 4.5 * 33.5 / 37.5);
+        assert_relative_eq!(
+            fit.r_squared,
+            0.8491904957028005 * 0.8491904957028005,
+            epsilon = 1e-12
+        );
+
+        let flat = vec![(1, 3), (2, 3), (5, 3)].linear_regression().unwrap();
+        assert_eq!(flat.slope, 0.0);
+        assert_eq!(flat.intercept, 3.0);
+        assert_eq!(flat.r_squared, 1.0);
+        assert_eq!(flat.residual_std_err, 0.0);
+    }
+
+    #[test]
+    fn test_linear_regression_diagnostics() {
+        let vec = vec![(1.0, 1.0), (2.0, 3.0), (3.0, 2.0), (4.0, 5.0), (5.0, 4.0)];
+        let fit = vec.linear_regression().unwrap();
+        assert_eq!(fit.count, 5);
+        assert_relative_eq!(fit.slope, 0.8, epsilon = 1e-12);
+        assert_relative_eq!(fit.intercept, 0.6, epsilon = 1e-12);
+        assert_relative_eq!(fit.r_squared, 0.64, epsilon = 1e-12);
+        assert_relative_eq!(fit.adjusted_r_squared, 0.52, epsilon = 1e-12);
+        assert_relative_eq!(fit.residual_std_err, 1.2_f64.sqrt(), epsilon = 1e-12);
+        assert_relative_eq!(fit.slope_std_err, 0.12_f64.sqrt(), epsilon = 1e-12);
+        assert_relative_eq!(fit.intercept_std_err, 1.32_f64.sqrt(), epsilon = 1e-12);
+        assert_relative_eq!(fit.predict(6.0), 5.4, epsilon = 1e-12);
+        let residuals: Vec<f64> = fit.residuals(vec).collect();
+        assert_relative_eq!(
+            residuals.as_slice(),
+            [-0.4, 0.8, -1.0, 1.2, -0.6].as_slice(),
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn test_weighted_linear_regression() {
+        let vec = vec![(1.0, 1.0), (2.0, 3.0), (3.0, 2.0), (4.0, 5.0), (5.0, 4.0)];
+        // equal weights give the ordinary fit, whatever their size
+        let ordinary = vec.linear_regression().unwrap();
+        let weighted = vec.weighted_linear_regression([2.0; 5]).unwrap();
+        assert_relative_eq!(weighted.slope, ordinary.slope, epsilon = 1e-12);
+        assert_relative_eq!(weighted.intercept, ordinary.intercept, epsilon = 1e-12);
+        assert_relative_eq!(
+            weighted.slope_std_err,
+            ordinary.slope_std_err,
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(
+            weighted.intercept_std_err,
+            ordinary.intercept_std_err,
+            epsilon = 1e-12
+        );
+
+        // a weight of 2 is the same as repeating the pair
+        let weighted = vec
+            .weighted_linear_regression([1.0, 1.0, 2.0, 1.0, 0.0])
+            .unwrap();
+        let repeated = vec![(1.0, 1.0), (2.0, 3.0), (3.0, 2.0), (3.0, 2.0), (4.0, 5.0)]
+            .linear_regression()
+            .unwrap();
+        assert_relative_eq!(weighted.slope, repeated.slope, epsilon = 1e-12);
+        assert_relative_eq!(weighted.intercept, repeated.intercept, epsilon = 1e-12);
+        assert_relative_eq!(weighted.r_squared, repeated.r_squared, epsilon = 1e-12);
+        assert_eq!(weighted.count, 4);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_weighted_linear_regression_too_few_weights() {
+        let _ = vec![(1.0, 1.0), (2.0, 3.0)].weighted_linear_regression([1.0]);
+    }
+
+    #[test]
+    fn test_weighted_covariance_and_correlation() {
+        let vec = vec![(1, 5), (2, 3), (4, 4), (5, 1)];
+        let weights = [0.5, 2.0, 1.0, 1.5];
+        // whole weights are the same as repeating the pairs
+        let repeated: Vec<(f64, f64)> = [(1, 5), (2, 3), (2, 3), (2, 3), (2, 3)]
+            .into_iter()
+            .chain([(4, 4), (4, 4), (5, 1), (5, 1), (5, 1)])
+            .map(|(x, y)| (f64::from(x), f64::from(y)))
+            .collect();
+        let doubled = weights.map(|w| w * 2.0);
+        assert_relative_eq!(
+            vec.weighted_covariance(doubled).unwrap(),
+            repeated.covariance().unwrap(),
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(
+            vec.weighted_pearson_r(weights).unwrap(),
+            repeated.pearson_r().unwrap(),
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(
+            vec.weighted_pearson_r([1.0; 4]).unwrap(),
+            vec.pearson_r().unwrap(),
+            epsilon = 1e-12
+        );
+        assert_eq!(
+            vec.weighted_covariance([0.0; 4]),
+            Err(StatsError::EmptyCollection)
+        );
+        assert_eq!(
+            vec.weighted_pearson_r([1.0, 0.0, 0.0, 0.0]),
+            Err(StatsError::ZeroVariance)
+        );
+    }
+
+    #[test]
+    fn test_linear_regression_errors() {
+        assert_eq!(
+            vec![(1.0, 2.0), (1.0, 3.0)].linear_regression(),
+            Err(StatsError::ZeroVariance)
+        );
+        assert_eq!(
+            vec![(1.0, 2.0)].linear_regression(),
+            Err(StatsError::InsufficientData {
+                required: 2,
+                actual: 1
+            })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_theil_sen() {
+        let vec = vec![(0, 0), (1, 1), (2, 3), (3, 6), (4, 4)];
+        let (slope, intercept) = vec.theil_sen().unwrap();
+        assert_relative_eq!(slope, 1.25);
+        assert_relative_eq!(intercept, 0.0);
+        assert_eq!(
+            vec![(1, 1), (1, 2)].theil_sen(),
+            Err(StatsError::ZeroVariance)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_repeated_median() {
+        let vec = vec![(0, 0), (1, 1), (2, 3), (3, 6), (4, 4)];
+        let (slope, intercept) = vec.repeated_median().unwrap();
+        assert_relative_eq!(slope, 1.5);
+        assert_relative_eq!(intercept, 0.0);
+        assert_eq!(
+            vec![(1.0, 2.0)].repeated_median(),
+            Err(StatsError::InsufficientData {
+                required: 2,
+                actual: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_quantile_regression() {
+        // y = x + 1 with heavy-tailed noise, so most points are on the line
+        let noise = [0.0, 40.0, 0.0, 0.0, -3.0, 0.0, 0.0, 500.0, 0.0, 0.0, -0.5];
+        let vec: Vec<(f64, f64)> = noise
+            .iter()
+            .enumerate()
+            .map(|(x, e)| (x as f64, x as f64 + 1.0 + e))
+            .collect();
+        let (slope, intercept) = vec.quantile_regression(0.5).unwrap();
+        assert_relative_eq!(slope, 1.0, epsilon = 1e-6);
+        assert_relative_eq!(intercept, 1.0, epsilon = 1e-6);
+        assert!((vec.linear_regression().unwrap().slope - 1.0).abs() > 1.0);
+
+        // the upper quantiles pass above more of the points
+        let spread: Vec<(f64, f64)> = (0..40)
+            .map(|i| (f64::from(i / 4), f64::from(i / 4 + i % 4)))
+            .collect();
+        let (slope, intercept) = spread.quantile_regression(0.9).unwrap();
+        assert_relative_eq!(slope, 1.0, epsilon = 1e-6);
+        assert_relative_eq!(intercept, 3.0, epsilon = 1e-6);
+
+        let exact = vec![(0, 1), (1, 3), (2, 5)];
+        assert_eq!(exact.quantile_regression(0.25), Ok((2.0, 1.0)));
+        assert_eq!(
+            vec![(1.0, 2.0), (1.0, 3.0)].quantile_regression(0.5),
+            Err(StatsError::ZeroVariance)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "p must be between 0 and 1")]
+    fn test_quantile_regression_invalid_p() {
+        let _ = vec![(0.0, 1.0), (1.0, 2.0)].quantile_regression(1.0);
+    }
+
+    #[test]
+    fn test_cross_correlation() {
+        let x = [2.0, 4.0, 1.0, 3.0, 5.0, 2.0];
+        let y = [0.0, 1.0, 3.0, 2.0, 0.0, 4.0];
+        let series: Vec<(f64, f64)> = x.into_iter().zip(y).collect();
+        assert_relative_eq!(
+            series.cross_correlation(0).unwrap(),
+            series.pearson_r().unwrap()
+        );
+        assert_relative_eq!(series.cross_correlation(2).unwrap(), 0.22650258012530186);
+        assert_relative_eq!(series.cross_correlation(-2).unwrap(), 0.46225016352102427);
+        assert_eq!(
+            series.cross_correlation(-6),
+            Err(StatsError::InsufficientData {
+                required: 7,
+                actual: 6
+            })
+        );
+        // swapping the series reverses the lags
+        let swapped: Vec<(f64, f64)> = y.into_iter().zip(x).collect();
+        assert_relative_eq!(
+            swapped.cross_correlation(2).unwrap(),
+            series.cross_correlation(-2).unwrap()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ccf() {
+        let series = vec![(1, 2), (2, 1), (3, 4), (4, 3)];
+        let ccf = series.ccf(1).unwrap();
+        assert_eq!(ccf.len(), 3);
+        assert_relative_eq!(ccf[0], series.cross_correlation(-1).unwrap());
+        assert_relative_eq!(ccf[2], series.cross_correlation(1).unwrap());
+    }
+
+    #[test]
+    fn test_too_few_pairs() {
+        let empty: Vec<(f64, f64)> = Vec::new();
+        assert_eq!(empty.covariance(), Err(StatsError::EmptyCollection));
+        assert_eq!(
+            vec![(1.0, 2.0)].sample_covariance(),
+            Err(StatsError::InsufficientData {
+                required: 2,
+                actual: 1
+            })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_wilcoxon_signed_rank() {
+        let vec = vec![(3, 1), (5, 5), (2, 4), (9, 2), (6, 1)];
+        let test = vec.wilcoxon_signed_rank().unwrap();
+        // the differences are 2, -2, 7 and 5 after dropping the zero
+        assert_eq!(test.statistic, 8.5);
+        assert!(!test.exact);
+        assert_eq!(
+            vec![(1, 1)].wilcoxon_signed_rank(),
+            Err(StatsError::ZeroVariance)
+        );
+    }
+
+    #[test]
+    fn test_sign_test() {
+        let vec = vec![(1.0, 0.0), (2.0, 2.0), (3.0, 1.0), (0.5, 1.0)];
+        let test = vec.sign_test().unwrap();
+        assert_eq!(test.statistic, 2.0);
+        assert_eq!(test.p_value, 1.0);
+        assert_eq!(
+            Vec::<(i32, i32)>::new().sign_test(),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+}
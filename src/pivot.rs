@@ -0,0 +1,279 @@
+use alloc::vec::Vec;
+
+use crate::OnlineStats;
+use crate::Result;
+
+/// The values of one cell of a [`PivotTable`], or of a row, a column or
+/// the whole table for the margins
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PivotCell {
+    sum: f64,
+    stats: OnlineStats,
+}
+
+impl PivotCell {
+    /// Add a value to the cell
+    fn push(&mut self, value: f64) {
+        self.sum += value;
+        self.stats.push(value);
+    }
+
+    /// Combine the values of `other` into `self`
+    fn merge(&mut self, other: &Self) {
+        self.sum += other.sum;
+        self.stats.merge(&other.stats);
+    }
+
+    /// Returns the number of values
+    pub fn count(&self) -> usize {
+        self.stats.count()
+    }
+
+    /// Returns the sum of the values, which is 0 if there are none
+    pub fn sum(&self) -> f64 {
+        self.sum
+    }
+
+    /// Returns the mean of the values
+    ///
+    /// # Errors
+    /// Returns an error if there are no values
+    pub fn mean(&self) -> Result<f64> {
+        self.stats.mean()
+    }
+
+    /// Returns the other statistics of the values, such as their
+    /// variance and the smallest and largest of them
+    pub fn stats(&self) -> &OnlineStats {
+        &self.stats
+    }
+}
+
+/// A pivot table, which summarizes values by two keys at once, such as
+/// sales by region and month, with the count, sum, mean and other
+/// statistics of the values of each combination of keys in a
+/// [`PivotCell`], and the margins of each row and column and the whole
+/// table.
+///
+/// The table is built from `(row, column, value)` observations, and the
+/// keys of the rows and columns are kept sorted, like a
+/// [`ContingencyTable`](crate::ContingencyTable). Only keys that have been
+/// observed at least once are in the table, and the cells of combinations
+/// that haven't been observed are empty.
+///
+/// # Examples
+/// ```
+/// use stats_traits::PivotTable;
+/// let sales: PivotTable<&str, u8> = [
+///     ("north", 1, 120.0),
+///     ("north", 1, 80.0),
+///     ("south", 1, 50.0),
+///     ("north", 2, 90.0),
+///     ("south", 2, 70.0),
+///     ("south", 2, 30.0),
+/// ]
+/// .into_iter()
+/// .collect();
+/// assert_eq!(sales.row_keys(), &["north", "south"]);
+/// assert_eq!(sales.column_keys(), &[1, 2]);
+/// let cell = sales.get(&"north", &1).unwrap();
+/// assert_eq!(cell.count(), 2);
+/// assert_eq!(cell.sum(), 200.0);
+/// assert_eq!(sales.get(&"south", &2).unwrap().mean(), Ok(50.0));
+/// // the margins summarize each row, each column and the whole table
+/// assert_eq!(sales.row_margins()[1].sum(), 150.0);
+/// assert_eq!(sales.column_margins()[0].mean(), Ok(250.0 / 3.0));
+/// assert_eq!(sales.total().count(), 6);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PivotTable<R, C> {
+    row_keys: Vec<R>,
+    column_keys: Vec<C>,
+    /// Cells of each column for each row
+    cells: Vec<Vec<PivotCell>>,
+}
+
+impl<R, C> PivotTable<R, C> {
+    /// Create an empty table
+    pub const fn new() -> Self {
+        Self {
+            row_keys: Vec::new(),
+            column_keys: Vec::new(),
+            cells: Vec::new(),
+        }
+    }
+
+    /// Returns the keys of the rows, sorted
+    pub fn row_keys(&self) -> &[R] {
+        &self.row_keys
+    }
+
+    /// Returns the keys of the columns, sorted
+    pub fn column_keys(&self) -> &[C] {
+        &self.column_keys
+    }
+
+    /// Returns the number of rows and columns in the table
+    pub fn shape(&self) -> (usize, usize) {
+        (self.row_keys.len(), self.column_keys.len())
+    }
+
+    /// Returns an iterator over the cells of each row, with a cell
+    /// for every column, in the order of [`PivotTable::row_keys`]
+    pub fn rows(&self) -> impl Iterator<Item = &[PivotCell]> {
+        self.cells.iter().map(Vec::as_slice)
+    }
+
+    /// Returns the margin of each row, which summarizes the values of
+    /// every column of it, in the order of [`PivotTable::row_keys`]
+    pub fn row_margins(&self) -> Vec<PivotCell> {
+        self.rows()
+            .map(|row| {
+                let mut margin = PivotCell::default();
+                for cell in row {
+                    margin.merge(cell);
+                }
+                margin
+            })
+            .collect()
+    }
+
+    /// Returns the margin of each column, which summarizes the values of
+    /// every row of it, in the order of [`PivotTable::column_keys`]
+    pub fn column_margins(&self) -> Vec<PivotCell> {
+        let mut margins = alloc::vec![PivotCell::default(); self.column_keys.len()];
+        for row in self.rows() {
+            for (margin, cell) in margins.iter_mut().zip(row) {
+                margin.merge(cell);
+            }
+        }
+        margins
+    }
+
+    /// Returns the grand margin, which summarizes every value in the table
+    pub fn total(&self) -> PivotCell {
+        let mut total = PivotCell::default();
+        for cell in self.cells.iter().flatten() {
+            total.merge(cell);
+        }
+        total
+    }
+}
+
+impl<R, C> PivotTable<R, C>
+where
+    R: Ord,
+    C: Ord,
+{
+    /// Record a `value` observed with `row` and `column`
+    pub fn insert(&mut self, row: R, column: C, value: f64) {
+        let j = match self.column_keys.binary_search(&column) {
+            Ok(j) => j,
+            Err(j) => {
+                self.column_keys.insert(j, column);
+                for cells in &mut self.cells {
+                    cells.insert(j, PivotCell::default());
+                }
+                j
+            }
+        };
+        let i = match self.row_keys.binary_search(&row) {
+            Ok(i) => i,
+            Err(i) => {
+                self.row_keys.insert(i, row);
+                self.cells
+                    .insert(i, alloc::vec![PivotCell::default(); self.column_keys.len()]);
+                i
+            }
+        };
+        self.cells[i][j].push(value);
+    }
+
+    /// Record a `value` observed with `row` and `column`,
+    /// returning the table so that calls can be chained
+    #[must_use]
+    pub fn with(mut self, row: R, column: C, value: f64) -> Self {
+        self.insert(row, column, value);
+        self
+    }
+
+    /// Returns the cell of `row` and `column`, or `None` if no
+    /// value has been observed with both of them
+    pub fn get(&self, row: &R, column: &C) -> Option<&PivotCell> {
+        let i = self.row_keys.binary_search(row).ok()?;
+        let j = self.column_keys.binary_search(column).ok()?;
+        Some(&self.cells[i][j]).filter(|cell| cell.count() > 0)
+    }
+}
+
+impl<R, C> Default for PivotTable<R, C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R, C> FromIterator<(R, C, f64)> for PivotTable<R, C>
+where
+    R: Ord,
+    C: Ord,
+{
+    fn from_iter<I: IntoIterator<Item = (R, C, f64)>>(iter: I) -> Self {
+        let mut table = Self::new();
+        table.extend(iter);
+        table
+    }
+}
+
+impl<R, C> Extend<(R, C, f64)> for PivotTable<R, C>
+where
+    R: Ord,
+    C: Ord,
+{
+    fn extend<I: IntoIterator<Item = (R, C, f64)>>(&mut self, iter: I) {
+        for (row, column, value) in iter {
+            self.insert(row, column, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StatsError;
+
+    #[test]
+    fn test_cells_and_margins() {
+        let table = PivotTable::new()
+            .with(2, 'b', 4.0)
+            .with(1, 'c', 1.0)
+            .with(2, 'a', 3.0)
+            .with(2, 'b', 8.0);
+        assert_eq!(table.shape(), (2, 3));
+        assert_eq!(table.row_keys(), &[1, 2]);
+        assert_eq!(table.column_keys(), &['a', 'b', 'c']);
+        let counts: Vec<Vec<usize>> = table
+            .rows()
+            .map(|row| row.iter().map(PivotCell::count).collect())
+            .collect();
+        assert_eq!(counts, [[0, 0, 1], [1, 2, 0]]);
+        assert_eq!(table.get(&2, &'b').unwrap().mean(), Ok(6.0));
+        assert_eq!(table.get(&2, &'b').unwrap().stats().variance(), Ok(4.0));
+        assert_eq!(table.get(&1, &'a'), None);
+        assert_eq!(table.get(&3, &'a'), None);
+        let row_sums: Vec<f64> = table.row_margins().iter().map(PivotCell::sum).collect();
+        assert_eq!(row_sums, [1.0, 15.0]);
+        let column_means: Vec<_> = table.column_margins().iter().map(PivotCell::mean).collect();
+        assert_eq!(column_means, [Ok(3.0), Ok(6.0), Ok(1.0)]);
+        assert_eq!(table.total().sum(), 16.0);
+        assert_eq!(table.total().stats().max(), Ok(8.0));
+    }
+
+    #[test]
+    fn test_empty() {
+        let table: PivotTable<u8, u8> = PivotTable::default();
+        assert_eq!(table.shape(), (0, 0));
+        assert!(table.row_margins().is_empty());
+        assert_eq!(table.total().sum(), 0.0);
+        assert_eq!(table.total().mean(), Err(StatsError::EmptyCollection));
+    }
+}
@@ -0,0 +1,97 @@
+use num_traits::ToPrimitive;
+
+use crate::helpers::item_to_f64;
+use crate::OnlineStats;
+use crate::Result;
+use crate::Stats;
+use crate::Summary;
+
+/// An object-safe trait for data sources that can be described with
+/// statistics, so different types can be put behind `dyn StatsProvider`
+/// and summarized the same way, such as the data sources of plugins or
+/// a list of collections with different types of items.
+///
+/// [`Stats`] can't be used as a trait object, because its items are an
+/// associated type and its methods are generic. This trait is implemented
+/// for every type that implements [`Stats`] with items that can be converted
+/// to [`f64`], and can be implemented for other data sources by providing
+/// [`StatsProvider::online_stats`].
+///
+/// # Examples
+/// ```
+/// use std::collections::VecDeque;
+/// use stats_traits::StatsProvider;
+/// let sources: Vec<Box<dyn StatsProvider>> = vec![
+///     Box::new(vec![1, 2, 3]),
+///     Box::new([0.5, 1.5]),
+///     Box::new(VecDeque::from([10u8, 20])),
+/// ];
+/// let means: Vec<f64> = sources
+///     .iter()
+///     .map(|source| source.summary().unwrap().mean)
+///     .collect();
+/// assert_eq!(means, [2.0, 1.0, 15.0]);
+/// ```
+pub trait StatsProvider {
+    /// Returns an [`OnlineStats`] with every item pushed to it
+    ///
+    /// # Errors
+    /// Returns an error if an item can't be converted to [`f64`]
+    fn online_stats(&self) -> Result<OnlineStats>;
+
+    /// Calculate a [`Summary`] of the items
+    ///
+    /// # Errors
+    /// Returns an error if there are no items, or if an item can't be converted to [`f64`]
+    fn summary(&self) -> Result<Summary> {
+        self.online_stats()?.summary()
+    }
+}
+
+/// Blanket implementation for all types that implement [`Stats`]
+/// with items that can be converted to [`f64`]
+impl<T> StatsProvider for T
+where
+    T: Stats + ?Sized,
+    T::Item: ToPrimitive,
+{
+    fn online_stats(&self) -> Result<OnlineStats> {
+        let mut stats = OnlineStats::new();
+        for x in self.items() {
+            stats.push(item_to_f64(x)?);
+        }
+        Ok(stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StatsError;
+
+    /// A data source that isn't a collection, counting down from a number
+    struct Countdown(u32);
+
+    impl StatsProvider for Countdown {
+        fn online_stats(&self) -> Result<OnlineStats> {
+            Ok((0..=self.0).map(f64::from).collect())
+        }
+    }
+
+    #[test]
+    fn test_trait_objects() {
+        let sources: [&dyn StatsProvider; 3] = [&[1, 2, 3], &[4.0, 8.0], &Countdown(4)];
+        let summaries: [Summary; 3] = sources.map(|source| source.summary().unwrap());
+        assert_eq!(summaries[0].mean, 2.0);
+        assert_eq!(summaries[1].range(), 4.0);
+        assert_eq!(summaries[2].count, 5);
+        assert_eq!(summaries[2].max, 4.0);
+    }
+
+    #[test]
+    fn test_empty() {
+        let empty: &dyn StatsProvider = &[0u8; 0];
+        assert_eq!(empty.online_stats().unwrap().count(), 0);
+        assert_eq!(empty.summary(), Err(StatsError::EmptyCollection));
+    }
+}
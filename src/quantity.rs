@@ -0,0 +1,173 @@
+use core::marker::PhantomData;
+use core::ops::Mul;
+
+use uom::si::Dimension;
+use uom::si::Quantity;
+use uom::si::Units;
+
+use crate::OnlineStats;
+use crate::Result;
+
+/// A trait for collections of [`uom`] quantities, such as lengths, durations
+/// or temperatures, with statistics that keep their dimensions, so that
+/// averaging metres with seconds doesn't compile. The mean and standard
+/// deviation have the dimension of the items, and the variance has the
+/// square of it, such as an area for lengths.
+///
+/// It is implemented for the same collections as [`Stats`](crate::Stats),
+/// when their items are quantities of the SI system stored as [`f64`].
+/// The statistics are calculated on the values in base units, so a
+/// collection can mix quantities given in different units.
+///
+/// # Examples
+/// ```
+/// use stats_traits::QuantityStats;
+/// use uom::si::area::square_meter;
+/// use uom::si::f64::Length;
+/// use uom::si::length::{centimeter, meter};
+/// let heights = vec![
+///     Length::new::<meter>(1.5),
+///     Length::new::<centimeter>(170.0),
+///     Length::new::<meter>(1.9),
+/// ];
+/// let mean = heights.mean().unwrap();
+/// assert!((mean.get::<centimeter>() - 170.0).abs() < 1e-9);
+/// // the variance is an area
+/// let variance = heights.variance().unwrap();
+/// assert!((variance.get::<square_meter>() - 0.08 / 3.0).abs() < 1e-9);
+/// ```
+pub trait QuantityStats<D, U>
+where
+    D: Dimension + ?Sized,
+    U: Units<f64> + ?Sized,
+{
+    /// Returns an iterator over the quantities of the collection
+    fn quantities(&self) -> impl Iterator<Item = Quantity<D, U, f64>>;
+
+    /// Returns an [`OnlineStats`] of the values of the quantities in base units
+    fn online_stats(&self) -> OnlineStats {
+        self.quantities().map(|x| x.value).collect()
+    }
+
+    /// Calculate the sum of the quantities, which is zero if there aren't any
+    fn sum(&self) -> Quantity<D, U, f64> {
+        quantity(self.quantities().map(|x| x.value).sum())
+    }
+
+    /// Calculate the mean of the quantities
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty
+    fn mean(&self) -> Result<Quantity<D, U, f64>> {
+        self.online_stats().mean().map(quantity)
+    }
+
+    /// Calculate the (population) variance of the quantities, which has
+    /// the square of their dimension. See [`Stats::variance`](crate::Stats::variance).
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty
+    fn variance<D2>(&self) -> Result<Quantity<D2, U, f64>>
+    where
+        D2: Dimension + ?Sized,
+        Quantity<D, U, f64>: Mul<Output = Quantity<D2, U, f64>>,
+    {
+        self.online_stats().variance().map(quantity)
+    }
+
+    /// Calculate the (population) standard deviation of the quantities,
+    /// which has their dimension
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty
+    fn std_dev(&self) -> Result<Quantity<D, U, f64>> {
+        self.online_stats().std_dev().map(quantity)
+    }
+
+    /// Find the smallest quantity
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty
+    fn min(&self) -> Result<Quantity<D, U, f64>> {
+        self.online_stats().min().map(quantity)
+    }
+
+    /// Find the largest quantity
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty
+    fn max(&self) -> Result<Quantity<D, U, f64>> {
+        self.online_stats().max().map(quantity)
+    }
+}
+
+/// Blanket implementation for all collections of quantities that
+/// can be iterated over by reference, like [`Stats`](crate::Stats)
+impl<T, D, U> QuantityStats<D, U> for T
+where
+    T: ?Sized,
+    for<'a> &'a T: IntoIterator<Item = &'a Quantity<D, U, f64>>,
+    D: Dimension + ?Sized + 'static,
+    U: Units<f64> + ?Sized + 'static,
+{
+    fn quantities(&self) -> impl Iterator<Item = Quantity<D, U, f64>> {
+        self.into_iter().copied()
+    }
+}
+
+/// Returns the quantity with `value` in base units
+fn quantity<D, U>(value: f64) -> Quantity<D, U, f64>
+where
+    D: Dimension + ?Sized,
+    U: Units<f64> + ?Sized,
+{
+    Quantity {
+        dimension: PhantomData,
+        units: PhantomData,
+        value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StatsError;
+
+    use approx::assert_relative_eq;
+    use uom::si::f64::{Length, ThermodynamicTemperature, Time};
+    use uom::si::length::{kilometer, meter};
+    use uom::si::thermodynamic_temperature::{degree_celsius, kelvin};
+    use uom::si::time::{minute, second};
+
+    #[test]
+    fn test_units() {
+        let times = [Time::new::<second>(30.0), Time::new::<minute>(1.5)];
+        assert_relative_eq!(times.sum().get::<minute>(), 2.0);
+        assert_relative_eq!(times.mean().unwrap().get::<second>(), 60.0);
+        assert_relative_eq!(times.std_dev().unwrap().get::<second>(), 30.0);
+        assert_relative_eq!(times.variance().unwrap().value, 900.0);
+        assert_eq!(times.min().unwrap(), Time::new::<second>(30.0));
+        assert_eq!(times.max().unwrap(), Time::new::<second>(90.0));
+    }
+
+    #[test]
+    fn test_temperatures() {
+        let temperatures = [
+            ThermodynamicTemperature::new::<degree_celsius>(10.0),
+            ThermodynamicTemperature::new::<degree_celsius>(20.0),
+        ];
+        let mean = temperatures.mean().unwrap();
+        assert_relative_eq!(mean.get::<degree_celsius>(), 15.0, epsilon = 1e-9);
+        assert_relative_eq!(mean.get::<kelvin>(), 288.15, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn test_empty() {
+        let empty: [Length; 0] = [];
+        assert_eq!(empty.sum(), Length::new::<kilometer>(0.0));
+        assert_eq!(empty.mean(), Err(StatsError::EmptyCollection));
+        assert_eq!(empty.std_dev(), Err(StatsError::EmptyCollection));
+        assert_eq!(empty.max(), Err(StatsError::EmptyCollection));
+        assert_eq!([Length::new::<meter>(2.0)].variance().unwrap().value, 0.0);
+    }
+}
@@ -0,0 +1,76 @@
+use num_integer::{Integer, Roots};
+use num_rational::Ratio;
+use num_traits::{Float, FromPrimitive, ToPrimitive};
+
+use crate::SquareRoot;
+
+/// The square root of a fraction is exact when it is a fraction too, which
+/// is when its numerator and denominator are both perfect squares, and
+/// otherwise the nearest fraction to the square root of it as an [`f64`].
+/// This means [`Stats::root_std_dev`](crate::Stats::root_std_dev) of fractions
+/// is only approximate when the variance, which is always exact, has an
+/// irrational square root.
+///
+/// # Examples
+/// ```
+/// use num_rational::Ratio;
+/// use num_traits::ToPrimitive;
+/// use stats_traits::Stats;
+/// let thirds = [1, 3, 5, 7].map(|x| Ratio::new(x, 3));
+/// assert_eq!(thirds.mean(), Ok(Ratio::new(4, 3)));
+/// assert_eq!(thirds.variance(), Ok(Ratio::new(5, 9)));
+/// // the square root of 5 is irrational
+/// let std_dev = thirds.root_std_dev().unwrap();
+/// assert!((std_dev.to_f64().unwrap() - 5f64.sqrt() / 3.0).abs() < 1e-12);
+/// let halves = [1, 3].map(|x| Ratio::new(x, 2));
+/// assert_eq!(halves.root_std_dev(), Ok(Ratio::new(1, 2)));
+/// ```
+impl<T> SquareRoot for Ratio<T>
+where
+    T: Clone + Integer + Roots,
+    Ratio<T>: FromPrimitive + ToPrimitive,
+{
+    fn square_root(&self) -> Option<Self> {
+        if self.numer() < &T::zero() {
+            return None;
+        }
+        let (numer, denom) = (self.numer().sqrt(), self.denom().sqrt());
+        if numer.clone() * numer.clone() == *self.numer()
+            && denom.clone() * denom.clone() == *self.denom()
+        {
+            return Some(Ratio::new_raw(numer, denom));
+        }
+        Self::from_f64(Float::sqrt(self.to_f64()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Stats;
+    use crate::StatsError;
+
+    #[test]
+    fn test_square_root() {
+        assert_eq!(Ratio::new(9, 4).square_root(), Some(Ratio::new(3, 2)));
+        assert_eq!(Ratio::new(-1, 4).square_root(), None);
+        assert_eq!(Ratio::new(0u8, 1).square_root(), Some(Ratio::new(0, 1)));
+        let root_two = Ratio::new(2i64, 1).square_root().unwrap();
+        assert!((root_two.to_f64().unwrap() - 2f64.sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_ratio_items() {
+        let values = [Ratio::new(1, 3), Ratio::new(2, 3), Ratio::new(5, 2)];
+        assert_eq!(values.sum(), Ratio::new(7, 2));
+        assert_eq!(values.mean(), Ok(Ratio::new(7, 6)));
+        assert_eq!(values.variance(), Ok(Ratio::new(49, 54)));
+        assert_eq!(Stats::max(&values), Ok(Ratio::new(5, 2)));
+        let std_dev = values.root_std_dev().unwrap().to_f64().unwrap();
+        assert!((std_dev - (49.0f64 / 54.0).sqrt()).abs() < 1e-12);
+        assert_eq!(
+            <[Ratio<i32>; 0]>::default().root_std_dev(),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+}
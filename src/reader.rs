@@ -0,0 +1,544 @@
+//! Statistics of numbers read from an [`io::Read`] source in chunks, such
+//! as a file, so that datasets far larger than memory can be summarized
+//! without loading them first.
+
+use core::fmt;
+use std::io;
+use std::io::Read;
+
+use crate::OnlineStats;
+use crate::StatsError;
+use crate::Summary;
+
+/// The number of bytes read at a time, which is also the longest
+/// number that can be read from text
+const CHUNK_LEN: usize = 8192;
+
+/// How the numbers are stored in the data read by [`extend_from_reader`]
+/// and [`summarize_reader`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DataFormat {
+    /// Binary [`f64`]s in little endian order, one after another
+    F64Le,
+    /// Binary [`f32`]s in little endian order, one after another
+    F32Le,
+    /// Binary [`i64`]s in little endian order, one after another
+    I64Le,
+    /// Binary [`i32`]s in little endian order, one after another
+    I32Le,
+    /// Binary [`u64`]s in little endian order, one after another
+    U64Le,
+    /// Binary [`u32`]s in little endian order, one after another
+    U32Le,
+    /// Numbers written as text, separated by `delimiter` or by line breaks,
+    /// such as a CSV file of numbers. The whitespace around each number is
+    /// ignored, as are empty fields, so a delimiter of `b' '` reads numbers
+    /// separated by any number of spaces.
+    Text {
+        /// The byte between the numbers on a line, such as `b','`
+        delimiter: u8,
+    },
+    /// One column of a table written as text, such as a CSV or TSV file,
+    /// with a line for each row. The whitespace around each number is
+    /// ignored, as are empty lines and rows where the field is empty.
+    /// Fields can be quoted, so quoted text before the column can contain
+    /// the delimiter, but they can't span lines.
+    Column {
+        /// The byte between the fields on a line, such as `b','` or `b'\t'`
+        delimiter: u8,
+        /// The index of the column, from 0 for the first
+        column: usize,
+        /// Whether the first line holds the names of the columns, so it is
+        /// skipped
+        header: bool,
+    },
+}
+
+impl DataFormat {
+    /// Returns the number of bytes of each number, or `None` for text
+    fn width(self) -> Option<usize> {
+        match self {
+            Self::F64Le | Self::I64Le | Self::U64Le => Some(8),
+            Self::F32Le | Self::I32Le | Self::U32Le => Some(4),
+            Self::Text { .. } | Self::Column { .. } => None,
+        }
+    }
+
+    /// Returns the number stored in `bytes`, which are `width` bytes long
+    fn decode(self, bytes: &[u8]) -> f64 {
+        let eight = || bytes.try_into().expect("eight bytes");
+        let four = || bytes.try_into().expect("four bytes");
+        match self {
+            Self::F64Le => f64::from_le_bytes(eight()),
+            Self::F32Le => f64::from(f32::from_le_bytes(four())),
+            Self::I64Le => i64::from_le_bytes(eight()) as f64,
+            Self::I32Le => f64::from(i32::from_le_bytes(four())),
+            Self::U64Le => u64::from_le_bytes(eight()) as f64,
+            Self::U32Le => f64::from(u32::from_le_bytes(four())),
+            Self::Text { .. } | Self::Column { .. } => {
+                unreachable!("text has no fixed width")
+            }
+        }
+    }
+}
+
+/// Error returned when the numbers from an [`io::Read`] source can't be
+/// read or summarized, by [`extend_from_reader`] and [`summarize_reader`]
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ReadError {
+    /// The source returned an error
+    Io(io::Error),
+    /// The text at the byte `position` of the source is not a number
+    InvalidNumber {
+        /// The number of bytes before the start of the text
+        position: u64,
+    },
+    /// The line at the byte `position` of the source has fewer fields than
+    /// the column of a [`DataFormat::Column`]
+    MissingColumn {
+        /// The number of bytes before the start of the line
+        position: u64,
+    },
+    /// The binary data ended part of the way through a number
+    Truncated,
+    /// The statistics of the numbers could not be calculated
+    Stats(StatsError),
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "the numbers could not be read: {error}"),
+            Self::InvalidNumber { position } => {
+                write!(f, "the text at byte {position} is not a number")
+            }
+            Self::MissingColumn { position } => {
+                write!(f, "the line at byte {position} does not have the column")
+            }
+            Self::Truncated => write!(f, "the data ends part of the way through a number"),
+            Self::Stats(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for ReadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::Stats(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ReadError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<StatsError> for ReadError {
+    fn from(error: StatsError) -> Self {
+        Self::Stats(error)
+    }
+}
+
+/// Read every number from `reader`, stored in `format`, and push them into
+/// `accumulator`, which is any of the streaming types like [`OnlineStats`],
+/// [`P2Quantile`](crate::P2Quantile) or, with the `alloc` feature, `TDigest`.
+/// The source is read in chunks of a few kilobytes, so the memory used
+/// doesn't depend on its length, and it doesn't need to be buffered.
+///
+/// Returns the number of numbers that were read.
+///
+/// # Examples
+/// ```
+/// use stats_traits::{extend_from_reader, DataFormat, P2Quantile};
+/// let bytes: Vec<u8> = [3.0f64, 1.0, 2.0].iter().flat_map(|x| x.to_le_bytes()).collect();
+/// let mut median = P2Quantile::new(0.5);
+/// let count = extend_from_reader(&bytes[..], DataFormat::F64Le, &mut median).unwrap();
+/// assert_eq!(count, 3);
+/// assert_eq!(median.quantile(), Ok(2.0));
+/// ```
+///
+/// Text is read a chunk at a time too, so a single number, or a line of a
+/// [`DataFormat::Column`], can't be longer than 8 KiB.
+///
+/// # Errors
+/// Returns an error if `reader` returns one, if text isn't a number, if a
+/// line doesn't have the column, or if binary data ends part of the way
+/// through a number. The numbers before the error have been pushed into
+/// `accumulator`.
+pub fn extend_from_reader<R, A>(
+    mut reader: R,
+    format: DataFormat,
+    accumulator: &mut A,
+) -> Result<usize, ReadError>
+where
+    R: Read,
+    A: Extend<f64>,
+{
+    let mut buffer = [0; CHUNK_LEN];
+    let mut filled = 0;
+    // the position in the source of the start of the buffer
+    let mut position = 0;
+    let mut count = 0;
+    loop {
+        let read = match reader.read(&mut buffer[filled..]) {
+            Ok(read) => read,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(ReadError::Io(error)),
+        };
+        filled += read;
+        let at_end = read == 0;
+        let used = match (format, format.width()) {
+            (_, Some(width)) => {
+                let values = buffer[..filled].chunks_exact(width);
+                count += values.len();
+                accumulator.extend(values.map(|bytes| format.decode(bytes)));
+                filled - filled % width
+            }
+            (DataFormat::Text { delimiter }, None) => {
+                let (used, parsed) =
+                    parse_text(&buffer[..filled], delimiter, at_end, position, accumulator)?;
+                count += parsed;
+                used
+            }
+            (
+                DataFormat::Column {
+                    delimiter,
+                    column,
+                    header,
+                },
+                None,
+            ) => {
+                let (used, parsed) = parse_column(
+                    &buffer[..filled],
+                    (delimiter, column, header),
+                    at_end,
+                    position,
+                    accumulator,
+                )?;
+                count += parsed;
+                used
+            }
+            (_, None) => unreachable!("only text has no fixed width"),
+        };
+        buffer.copy_within(used..filled, 0);
+        filled -= used;
+        position += used as u64;
+        if at_end {
+            return if filled == 0 {
+                Ok(count)
+            } else {
+                Err(ReadError::Truncated)
+            };
+        }
+        if filled == CHUNK_LEN {
+            // a single number fills the whole buffer
+            return Err(ReadError::InvalidNumber { position });
+        }
+    }
+}
+
+/// Read every number from `reader`, stored in `format`, and calculate their
+/// [`Summary`] with [`OnlineStats`], in one pass over the source like
+/// [`extend_from_reader`]
+///
+/// # Examples
+/// ```
+/// use stats_traits::{summarize_reader, DataFormat};
+/// let csv = "1.5, 2.5\n3.5,4.5\n";
+/// let summary = summarize_reader(csv.as_bytes(), DataFormat::Text { delimiter: b',' }).unwrap();
+/// assert_eq!(summary.count, 4);
+/// assert_eq!(summary.mean, 3.0);
+/// assert_eq!(summary.max, 4.5);
+/// ```
+///
+/// Or to summarize one column of a CSV file, and estimate its median
+/// with a [`P2Quantile`](crate::P2Quantile) at the same time:
+/// ```
+/// use stats_traits::{extend_from_reader, summarize_reader, DataFormat, P2Quantile};
+/// let csv = "city,population,area\nOslo,709037,454\n\"Bergen, Vestland\",291940,465\n";
+/// let format = DataFormat::Column { delimiter: b',', column: 1, header: true };
+/// let summary = summarize_reader(csv.as_bytes(), format).unwrap();
+/// assert_eq!(summary.count, 2);
+/// assert_eq!(summary.min, 291940.0);
+///
+/// let mut median = P2Quantile::new(0.5);
+/// extend_from_reader(csv.as_bytes(), format, &mut median).unwrap();
+/// assert_eq!(median.quantile(), Ok(500488.5));
+/// ```
+///
+/// # Errors
+/// Returns an error under the same conditions as [`extend_from_reader`],
+/// or if there were no numbers
+pub fn summarize_reader<R>(reader: R, format: DataFormat) -> Result<Summary, ReadError>
+where
+    R: Read,
+{
+    let mut stats = OnlineStats::new();
+    extend_from_reader(reader, format, &mut stats)?;
+    Ok(stats.summary()?)
+}
+
+/// Parse the numbers in `bytes`, which start at `position` in the source,
+/// and push them into `accumulator`. The last one is left for the next
+/// chunk unless the source is `at_end`, as it might not be complete.
+///
+/// Returns the number of bytes used and the number of numbers parsed.
+fn parse_text<A>(
+    bytes: &[u8],
+    delimiter: u8,
+    at_end: bool,
+    position: u64,
+    accumulator: &mut A,
+) -> Result<(usize, usize), ReadError>
+where
+    A: Extend<f64>,
+{
+    let is_separator = |&byte: &u8| byte == delimiter || byte == b'\n' || byte == b'\r';
+    let mut start = 0;
+    let mut count = 0;
+    loop {
+        let end = match bytes[start..].iter().position(is_separator) {
+            Some(len) => start + len,
+            None if at_end => bytes.len(),
+            None => return Ok((start, count)),
+        };
+        if let Some(x) = parse_field(&bytes[start..end], position + start as u64)? {
+            accumulator.extend(core::iter::once(x));
+            count += 1;
+        }
+        if end == bytes.len() {
+            return Ok((end, count));
+        }
+        start = end + 1;
+    }
+}
+
+/// Parse the numbers in the column of the lines in `bytes`, given by the
+/// `delimiter`, `column` and `header` of a [`DataFormat::Column`], which start at
+/// `position` in the source, and push them into `accumulator`. The last
+/// line is left for the next chunk unless the source is `at_end`, as it
+/// might not be complete.
+///
+/// Returns the number of bytes used and the number of numbers parsed.
+fn parse_column<A>(
+    bytes: &[u8],
+    (delimiter, column, header): (u8, usize, bool),
+    at_end: bool,
+    position: u64,
+    accumulator: &mut A,
+) -> Result<(usize, usize), ReadError>
+where
+    A: Extend<f64>,
+{
+    let mut start = 0;
+    let mut count = 0;
+    loop {
+        let end = match bytes[start..].iter().position(|&byte| byte == b'\n') {
+            Some(len) => start + len,
+            None if at_end => bytes.len(),
+            None => return Ok((start, count)),
+        };
+        let line = &bytes[start..end];
+        let line_position = position + start as u64;
+        let is_header = header && line_position == 0;
+        if !is_header && !line.trim_ascii().is_empty() {
+            let (offset, field) =
+                nth_field(line, delimiter, column).ok_or(ReadError::MissingColumn {
+                    position: line_position,
+                })?;
+            if let Some(x) =
+                parse_field(unquote(field.trim_ascii()), line_position + offset as u64)?
+            {
+                accumulator.extend(core::iter::once(x));
+                count += 1;
+            }
+        }
+        if end == bytes.len() {
+            return Ok((end, count));
+        }
+        start = end + 1;
+    }
+}
+
+/// Returns the field at `index` of `line`, and the number of bytes before
+/// it, ignoring delimiters within quotes, or `None` if there are fewer fields
+fn nth_field(line: &[u8], delimiter: u8, index: usize) -> Option<(usize, &[u8])> {
+    let mut in_quotes = false;
+    let mut field = 0;
+    let mut start = 0;
+    for (i, &byte) in line.iter().enumerate() {
+        if byte == b'"' {
+            in_quotes = !in_quotes;
+        } else if byte == delimiter && !in_quotes {
+            if field == index {
+                return Some((start, &line[start..i]));
+            }
+            field += 1;
+            start = i + 1;
+        }
+    }
+    (field == index).then(|| (start, &line[start..]))
+}
+
+/// Returns `field` without the quotes around it, if it has them
+fn unquote(field: &[u8]) -> &[u8] {
+    match field {
+        [b'"', inner @ .., b'"'] => inner.trim_ascii(),
+        _ => field,
+    }
+}
+
+/// Returns the number in `field`, which is at `position` in the source,
+/// ignoring the whitespace around it, or `None` if it is empty
+fn parse_field(field: &[u8], position: u64) -> Result<Option<f64>, ReadError> {
+    let field = field.trim_ascii();
+    if field.is_empty() {
+        return Ok(None);
+    }
+    core::str::from_utf8(field)
+        .ok()
+        .and_then(|field| field.parse().ok())
+        .map(Some)
+        .ok_or(ReadError::InvalidNumber { position })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::string::String;
+    use std::vec::Vec;
+
+    use approx::assert_relative_eq;
+
+    /// A source that returns at most `chunk` bytes from each read,
+    /// to check numbers that are split between reads
+    struct Trickle<'a> {
+        bytes: &'a [u8],
+        chunk: usize,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+            let len = self.chunk.min(buffer.len());
+            self.bytes.read(&mut buffer[..len])
+        }
+    }
+
+    #[test]
+    fn test_binary_formats() {
+        let values: Vec<i32> = (-5000..5000).collect();
+        let bytes: Vec<u8> = values.iter().flat_map(|x| x.to_le_bytes()).collect();
+        let mut stats = OnlineStats::new();
+        let reader = Trickle {
+            bytes: &bytes,
+            chunk: 3,
+        };
+        assert_eq!(
+            extend_from_reader(reader, DataFormat::I32Le, &mut stats).unwrap(),
+            10000
+        );
+        assert_eq!(stats.mean(), Ok(-0.5));
+        assert_eq!(stats.min(), Ok(-5000.0));
+
+        let bytes: Vec<u8> = [u64::MAX, 2].iter().flat_map(|x| x.to_le_bytes()).collect();
+        let summary = summarize_reader(&bytes[..], DataFormat::U64Le).unwrap();
+        assert_eq!(summary.max, u64::MAX as f64);
+        let bytes = 0.25f32.to_le_bytes();
+        let summary = summarize_reader(&bytes[..], DataFormat::F32Le).unwrap();
+        assert_eq!(summary.mean, 0.25);
+    }
+
+    #[test]
+    fn test_text_split_between_chunks() {
+        let values: Vec<f64> = (0..3000).map(|x| f64::from(x) / 8.0).collect();
+        let text: String = values.iter().map(|x| std::format!("{x}\t;\r\n")).collect();
+        let format = DataFormat::Text { delimiter: b';' };
+        let reader = Trickle {
+            bytes: text.as_bytes(),
+            chunk: 1000,
+        };
+        let summary = summarize_reader(reader, format).unwrap();
+        assert_eq!(summary.count, 3000);
+        assert_relative_eq!(summary.mean, 1499.5 / 8.0);
+        let summary = summarize_reader("1 -2   3".as_bytes(), DataFormat::Text { delimiter: b' ' });
+        assert_eq!(summary.unwrap().min, -2.0);
+    }
+
+    #[test]
+    fn test_columns() {
+        let mut text = String::from("id\tname\tscore\r\n");
+        for i in 0..2000 {
+            text += &std::format!("{i}\t\"row\t{i}\"\t {}\r\n", i % 10);
+        }
+        text += "\n2000\tmissing\t\n";
+        let format = DataFormat::Column {
+            delimiter: b'\t',
+            column: 2,
+            header: true,
+        };
+        let reader = Trickle {
+            bytes: text.as_bytes(),
+            chunk: 1000,
+        };
+        let summary = summarize_reader(reader, format).unwrap();
+        assert_eq!(summary.count, 2000);
+        assert_relative_eq!(summary.mean, 4.5, epsilon = 1e-12);
+        assert_eq!(summary.max, 9.0);
+
+        let csv = "1,\"2\"\n3,4";
+        let first = DataFormat::Column {
+            delimiter: b',',
+            column: 0,
+            header: false,
+        };
+        assert_eq!(summarize_reader(csv.as_bytes(), first).unwrap().mean, 2.0);
+        let second = DataFormat::Column {
+            delimiter: b',',
+            column: 1,
+            header: false,
+        };
+        assert_eq!(summarize_reader(csv.as_bytes(), second).unwrap().mean, 3.0);
+    }
+
+    #[test]
+    fn test_errors() {
+        let text = DataFormat::Text { delimiter: b',' };
+        assert!(matches!(
+            summarize_reader("1,2,x3,4".as_bytes(), text),
+            Err(ReadError::InvalidNumber { position: 4 })
+        ));
+        assert!(matches!(
+            summarize_reader(&[0; 7][..], DataFormat::F64Le),
+            Err(ReadError::Truncated)
+        ));
+        assert!(matches!(
+            summarize_reader(" ,\n".as_bytes(), text),
+            Err(ReadError::Stats(StatsError::EmptyCollection))
+        ));
+        let column = DataFormat::Column {
+            delimiter: b',',
+            column: 1,
+            header: true,
+        };
+        assert!(matches!(
+            summarize_reader("a,b\n1,2\n3\n".as_bytes(), column),
+            Err(ReadError::MissingColumn { position: 8 })
+        ));
+        assert!(matches!(
+            summarize_reader("a,b\n1,2\n3, x\n".as_bytes(), column),
+            Err(ReadError::InvalidNumber { position: 10 })
+        ));
+        let long = std::vec![b'1'; CHUNK_LEN + 1];
+        assert!(matches!(
+            summarize_reader(&long[..], text),
+            Err(ReadError::InvalidNumber { position: 0 })
+        ));
+    }
+}
@@ -0,0 +1,173 @@
+use crate::helpers::Float;
+use crate::OnlineBivariate;
+use crate::Result;
+use crate::StatsError;
+
+/// The line of best fit through a set of `(x, y)` pairs found by
+/// [ordinary least squares](<https://en.wikipedia.org/wiki/Simple_linear_regression>),
+/// as returned by [`PairedStats::linear_regression`](crate::PairedStats::linear_regression),
+/// along with measures of how well the line fits.
+///
+/// With only two pairs the line always fits exactly, and
+/// the standard errors and adjusted R² are `NaN`.
+///
+/// # Examples
+/// ```
+/// use stats_traits::PairedStats;
+/// let vec = vec![(0.0, 1.0), (1.0, 3.0), (2.0, 5.0)];
+/// let fit = vec.linear_regression().unwrap();
+/// assert_eq!(fit.slope, 2.0);
+/// assert_eq!(fit.intercept, 1.0);
+/// assert_eq!(fit.r_squared, 1.0);
+/// assert_eq!(fit.predict(10.0), 21.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinearRegression {
+    /// Number of pairs the line was fitted to
+    pub count: usize,
+    /// How much `y` increases for each unit that `x` increases
+    pub slope: f64,
+    /// The value of `y` where the line crosses `x = 0`
+    pub intercept: f64,
+    /// The coefficient of determination, which is the proportion of the
+    /// variance of `y` that the line explains, from 0 for no better than
+    /// the mean of `y` to 1 for a perfect fit
+    pub r_squared: f64,
+    /// [`LinearRegression::r_squared`] adjusted for the number of pairs,
+    /// so that it doesn't flatter fits to only a few pairs
+    pub adjusted_r_squared: f64,
+    /// Standard error of the residuals, which estimates the standard
+    /// deviation of the `y` values around the line
+    pub residual_std_err: f64,
+    /// Standard error of [`LinearRegression::slope`]
+    pub slope_std_err: f64,
+    /// Standard error of [`LinearRegression::intercept`]
+    pub intercept_std_err: f64,
+}
+
+/// Weighted sums describing a set of `(x, y)` pairs, from which a line can be fitted
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub(crate) struct Moments {
+    /// Number of pairs with a weight greater than 0
+    pub count: usize,
+    /// Sum of the weights
+    pub weight: f64,
+    pub mean_x: f64,
+    pub mean_y: f64,
+    /// Weighted sum of the squared differences of `x` from its mean
+    pub m2_x: f64,
+    /// Weighted sum of the squared differences of `y` from its mean
+    pub m2_y: f64,
+    /// Weighted sum of the products of the differences of `x` and `y` from their means
+    pub co_moment: f64,
+}
+
+impl Moments {
+    /// Returns the moments of the pairs pushed to `stats`, which all have a weight of 1
+    pub fn from_bivariate(stats: &OnlineBivariate) -> Result<Self> {
+        let n = stats.count() as f64;
+        Ok(Self {
+            count: stats.count(),
+            weight: n,
+            mean_x: stats.mean_x()?,
+            mean_y: stats.mean_y()?,
+            m2_x: stats.variance_x()? * n,
+            m2_y: stats.variance_y()? * n,
+            co_moment: stats.covariance()? * n,
+        })
+    }
+
+    /// Add a pair with weight `w`, using the weighted form of Welford's algorithm by
+    /// [West](<https://doi.org/10.1145/359146.359153>)
+    pub fn push(&mut self, x: f64, y: f64, w: f64) {
+        if w == 0.0 {
+            return;
+        }
+        self.count += 1;
+        self.weight += w;
+        let delta_x = x - self.mean_x;
+        let delta_y = y - self.mean_y;
+        self.mean_x += delta_x * w / self.weight;
+        self.mean_y += delta_y * w / self.weight;
+        self.m2_x += w * delta_x * (x - self.mean_x);
+        self.m2_y += w * delta_y * (y - self.mean_y);
+        self.co_moment += w * delta_x * (y - self.mean_y);
+    }
+}
+
+impl LinearRegression {
+    /// Fit a line to pairs described by `moments`
+    pub(crate) fn from_moments(moments: &Moments) -> Result<Self> {
+        let Moments {
+            count,
+            weight,
+            mean_x,
+            mean_y,
+            m2_x,
+            m2_y,
+            co_moment,
+        } = *moments;
+        match count {
+            0 => return Err(StatsError::EmptyCollection),
+            1 => {
+                return Err(StatsError::InsufficientData {
+                    required: 2,
+                    actual: 1,
+                })
+            }
+            _ => {}
+        }
+        if m2_x == 0.0 {
+            return Err(StatsError::ZeroVariance);
+        }
+
+        let n = count as f64;
+        let slope = co_moment / m2_x;
+        let intercept = mean_y - slope * mean_x;
+        // if every y is the same, the horizontal line through them fits perfectly
+        let r_squared = if m2_y == 0.0 {
+            1.0
+        } else {
+            co_moment * co_moment / (m2_x * m2_y)
+        };
+        let sum_squared_residuals = (m2_y - slope * co_moment).max(0.0);
+        // two degrees of freedom are used up by the slope and intercept
+        let residual_variance = sum_squared_residuals / (n - 2.0);
+        Ok(Self {
+            count,
+            slope,
+            intercept,
+            r_squared,
+            adjusted_r_squared: 1.0 - (1.0 - r_squared) * (n - 1.0) / (n - 2.0),
+            residual_std_err: Float::sqrt(residual_variance),
+            slope_std_err: Float::sqrt(residual_variance / m2_x),
+            intercept_std_err: Float::sqrt(
+                residual_variance * (1.0 / weight + mean_x * mean_x / m2_x),
+            ),
+        })
+    }
+
+    /// Returns the value of `y` on the line at `x`
+    pub fn predict(&self, x: f64) -> f64 {
+        self.slope * x + self.intercept
+    }
+
+    /// Returns an iterator over the residuals of `pairs`, which are how far
+    /// above the line each `y` is (so negative if it is below the line)
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::PairedStats;
+    /// let vec = vec![(-1.0, 1.0), (0.0, 3.0), (1.0, 2.0)];
+    /// let fit = vec.linear_regression().unwrap();
+    /// let residuals: Vec<f64> = fit.residuals(vec).collect();
+    /// assert_eq!(residuals, vec![-0.5, 1.0, -0.5]);
+    /// ```
+    pub fn residuals<I>(&self, pairs: I) -> impl Iterator<Item = f64>
+    where
+        I: IntoIterator<Item = (f64, f64)>,
+    {
+        let fit = *self;
+        pairs.into_iter().map(move |(x, y)| y - fit.predict(x))
+    }
+}
@@ -0,0 +1,159 @@
+use alloc::vec::Vec;
+
+use crate::helpers::partial_cmp_nan_last;
+use crate::Result;
+use crate::StatsError;
+
+/// Estimates the median of a stream of values with the
+/// [remedian](<https://doi.org/10.1080/01621459.1990.10475331>) of Rousseeuw and Bassett.
+///
+/// Values are collected in a buffer of `base` values. When it is full, its
+/// median is pushed into a second buffer and the first is emptied; when the
+/// second buffer is full, its median is pushed into a third, and so on. So
+/// only `base` values are kept for each power of `base` pushed, which is a
+/// few hundred numbers for billions of values with a base of around 15 to 50.
+/// The estimate is the weighted median of the values left in the buffers.
+///
+/// # Examples
+/// ```
+/// use stats_traits::Remedian;
+/// let mut remedian = Remedian::new(15);
+/// for i in 0..100_000 {
+///     remedian.push(((i * 7919) % 100_000) as f64);
+/// }
+/// let estimate = remedian.median().unwrap();
+/// assert!((estimate - 50_000.0).abs() < 2_000.0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Remedian {
+    base: usize,
+    count: usize,
+    /// The buffers, where each value in `buffers[i]` is the median of `base^i` values
+    buffers: Vec<Vec<f64>>,
+}
+
+impl Remedian {
+    /// Create an empty estimator whose buffers each hold `base` values
+    ///
+    /// # Panics
+    /// Panics if `base` is not an odd number greater than 1
+    pub fn new(base: usize) -> Self {
+        assert!(
+            base > 1 && base % 2 == 1,
+            "base must be an odd number greater than 1"
+        );
+        Self {
+            base,
+            count: 0,
+            buffers: Vec::new(),
+        }
+    }
+
+    /// Returns the number of values each buffer holds
+    pub fn base(&self) -> usize {
+        self.base
+    }
+
+    /// Returns the number of values that have been pushed
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Add a value to the estimator
+    pub fn push(&mut self, x: f64) {
+        self.count += 1;
+        let mut x = x;
+        for level in 0.. {
+            if level == self.buffers.len() {
+                self.buffers.push(Vec::with_capacity(self.base));
+            }
+            let buffer = &mut self.buffers[level];
+            buffer.push(x);
+            if buffer.len() < self.base {
+                return;
+            }
+            buffer.sort_by(partial_cmp_nan_last);
+            x = buffer[self.base / 2];
+            buffer.clear();
+        }
+    }
+
+    /// Returns the current estimate of the median. Until `base` values have
+    /// been pushed, this is the exact median of those values, or the lower of
+    /// the middle two if there is an even number of them.
+    ///
+    /// # Errors
+    /// Returns an error if no values have been pushed
+    pub fn median(&self) -> Result<f64> {
+        let mut weighted = Vec::new();
+        let mut weight = 1.0;
+        for buffer in &self.buffers {
+            weighted.extend(buffer.iter().map(|&x| (x, weight)));
+            weight *= self.base as f64;
+        }
+        weighted.sort_by(|a, b| partial_cmp_nan_last(&a.0, &b.0));
+
+        let total: f64 = weighted.iter().map(|&(_, weight)| weight).sum();
+        let mut seen = 0.0;
+        for (x, weight) in weighted {
+            seen += weight;
+            if seen >= total / 2.0 {
+                return Ok(x);
+            }
+        }
+        Err(StatsError::EmptyCollection)
+    }
+}
+
+impl Extend<f64> for Remedian {
+    fn extend<I: IntoIterator<Item = f64>>(&mut self, iter: I) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(Remedian::new(3).median(), Err(StatsError::EmptyCollection));
+    }
+
+    #[test]
+    fn test_few_values_exact() {
+        let mut remedian = Remedian::new(7);
+        remedian.extend([5.0, 1.0, 9.0]);
+        assert_eq!(remedian.median(), Ok(5.0));
+        remedian.push(0.0);
+        assert_eq!(remedian.median(), Ok(1.0));
+    }
+
+    #[test]
+    fn test_full_levels() {
+        // the medians of [1, 2, 3], [7, 8, 9] and [4, 5, 6] are 2, 8 and 5
+        let mut remedian = Remedian::new(3);
+        remedian.extend([3.0, 1.0, 2.0, 9.0, 8.0, 7.0, 4.0, 6.0, 5.0]);
+        assert_eq!(remedian.count(), 9);
+        assert_eq!(remedian.median(), Ok(5.0));
+    }
+
+    #[test]
+    fn test_uniform() {
+        let mut remedian = Remedian::new(21);
+        for i in 0..1_000_000u64 {
+            remedian.push(((i * 7919) % 1_000_000) as f64);
+        }
+        let estimate = remedian.median().unwrap();
+        assert!((estimate / 1_000_000.0 - 0.5).abs() < 0.02);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_even_base() {
+        let _ = Remedian::new(4);
+    }
+}
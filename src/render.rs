@@ -0,0 +1,78 @@
+//! Rendering of counts as text, such as the bins of a
+//! [`Histogram`](crate::Histogram), for terminals and logs.
+
+use alloc::string::String;
+use core::fmt::Write;
+
+/// The blocks of a sparkline, from the lowest to the highest
+const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Returns one block for each count, as high as the count relative to the
+/// largest one, or a space for a count of 0 so empty bins stand out
+pub(crate) fn sparkline<I>(counts: I) -> String
+where
+    I: IntoIterator<Item = usize> + Clone,
+{
+    let max = counts.clone().into_iter().max().unwrap_or(0);
+    counts
+        .into_iter()
+        .map(|count| match count {
+            0 => ' ',
+            // between 1 and 8 eighths of the largest count, rounded up
+            _ => BLOCKS[(count * BLOCKS.len()).div_ceil(max) - 1],
+        })
+        .collect()
+}
+
+/// Returns a line for each `(label, count)` row, with the labels lined up,
+/// a bar of `bar` as long as the count relative to the largest one, up to
+/// `width` characters, and the count
+pub(crate) fn bar_chart<I, L>(rows: I, width: usize, bar: char) -> String
+where
+    I: IntoIterator<Item = (L, usize)>,
+    I::IntoIter: Clone,
+    L: AsRef<str>,
+{
+    let rows = rows.into_iter();
+    let label_width = rows
+        .clone()
+        .map(|(label, _)| label.as_ref().chars().count())
+        .max()
+        .unwrap_or(0);
+    let max = rows.clone().map(|(_, count)| count).max().unwrap_or(0);
+    let mut chart = String::new();
+    for (label, count) in rows {
+        let length = if max == 0 {
+            0
+        } else {
+            // rounded to the nearest character
+            (2 * count * width + max) / (2 * max)
+        };
+        let label = label.as_ref();
+        let padding = label_width - label.chars().count();
+        let _ = write!(chart, "{label}{:padding$} │", "");
+        chart.extend(core::iter::repeat_n(bar, length));
+        let _ = writeln!(chart, " {count}");
+    }
+    chart
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sparkline() {
+        assert_eq!(sparkline([0, 1, 2, 4, 8]), " ▁▂▄█");
+        assert_eq!(sparkline([3, 3]), "██");
+        assert_eq!(sparkline([1, 100]), "▁█");
+        assert_eq!(sparkline([]), "");
+    }
+
+    #[test]
+    fn test_bar_chart() {
+        let chart = bar_chart([("a", 4), ("bcd", 1), ("ef", 0)], 8, '#');
+        assert_eq!(chart, "a   │######## 4\nbcd │## 1\nef  │ 0\n");
+        assert_eq!(bar_chart([("x", 0)], 8, '#'), "x │ 0\n");
+    }
+}
@@ -0,0 +1,362 @@
+use alloc::vec::Vec;
+#[cfg(feature = "rand")]
+use core::cmp::Ordering;
+
+#[cfg(feature = "rand")]
+use rand::Rng;
+
+#[cfg(feature = "rand")]
+use crate::helpers::partial_cmp_nan_last;
+#[cfg(feature = "rand")]
+use crate::helpers::quantile_of_sorted;
+use crate::helpers::Float;
+#[cfg(feature = "rand")]
+use crate::special::{normal_cdf, normal_quantile};
+use crate::Result;
+use crate::StatsError;
+
+/// Leave-one-out [jackknife](<https://en.wikipedia.org/wiki/Jackknife_resampling>)
+/// estimates of the bias and standard error of a statistic, as returned by
+/// [`Stats::jackknife`](crate::Stats::jackknife).
+///
+/// The statistic is calculated again with each item left out in turn, and
+/// how much these values vary is used to estimate how much the statistic
+/// would vary between samples. Unlike the bootstrap, no randomness is
+/// involved, so the result is always the same.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Jackknife {
+    /// The statistic calculated on all the items
+    pub estimate: f64,
+    /// The estimated bias of the statistic, which is how much larger
+    /// its expected value is than the value it estimates
+    pub bias: f64,
+    /// The estimated standard error of the statistic
+    pub standard_error: f64,
+    /// The statistic calculated with each item left out, in the order of the items
+    pub leave_one_out: Vec<f64>,
+}
+
+impl Jackknife {
+    /// Calculate `statistic` on `values` and on `values` with each value left out
+    pub(crate) fn new<F>(values: &[f64], mut statistic: F) -> Result<Self>
+    where
+        F: FnMut(&[f64]) -> Result<f64>,
+    {
+        check_size(values)?;
+        let estimate = statistic(values)?;
+        let leave_one_out = leave_one_out(values, statistic)?;
+        let n = values.len() as f64;
+        let mean = leave_one_out.iter().sum::<f64>() / n;
+        let squares: f64 = leave_one_out
+            .iter()
+            .map(|theta| (theta - mean) * (theta - mean))
+            .sum();
+        Ok(Self {
+            estimate,
+            bias: (n - 1.0) * (mean - estimate),
+            standard_error: Float::sqrt((n - 1.0) / n * squares),
+            leave_one_out,
+        })
+    }
+
+    /// Returns the estimate with the estimated bias removed
+    pub fn bias_corrected(&self) -> f64 {
+        self.estimate - self.bias
+    }
+}
+
+/// The distribution of a statistic over bootstrap resamples of a collection,
+/// as returned by [`Stats::bootstrap`](crate::Stats::bootstrap).
+///
+/// The [bootstrap](<https://en.wikipedia.org/wiki/Bootstrapping_(statistics)>)
+/// estimates how much a statistic would vary between samples by calculating
+/// it on many resamples, each drawn with replacement from the original items
+/// and the same size as them. It works for any statistic, not only those with
+/// a formula for their standard error.
+#[cfg(feature = "rand")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bootstrap {
+    estimate: f64,
+    /// The statistic of each resample, sorted
+    distribution: Vec<f64>,
+    /// How fast the standard error of the statistic changes with its
+    /// value, estimated with the jackknife, for the BCa interval
+    acceleration: f64,
+}
+
+#[cfg(feature = "rand")]
+impl Bootstrap {
+    /// Calculate `statistic` on `resamples` resamples of `values`
+    pub(crate) fn new<F, R>(
+        values: &[f64],
+        mut statistic: F,
+        resamples: usize,
+        rng: &mut R,
+    ) -> Result<Self>
+    where
+        F: FnMut(&[f64]) -> Result<f64>,
+        R: Rng + ?Sized,
+    {
+        assert!(resamples > 0, "number of resamples must be greater than 0");
+        check_size(values)?;
+        let estimate = statistic(values)?;
+
+        let mut resample = Vec::with_capacity(values.len());
+        let mut distribution = Vec::with_capacity(resamples);
+        for _ in 0..resamples {
+            resample.clear();
+            resample.extend((0..values.len()).map(|_| values[rng.gen_range(0..values.len())]));
+            distribution.push(statistic(&resample)?);
+        }
+        distribution.sort_by(partial_cmp_nan_last);
+
+        let jackknife = leave_one_out(values, statistic)?;
+        let mean = jackknife.iter().sum::<f64>() / jackknife.len() as f64;
+        let (mut squares, mut cubes) = (0.0, 0.0);
+        for theta in jackknife {
+            let difference = mean - theta;
+            squares += difference * difference;
+            cubes += difference * difference * difference;
+        }
+        let acceleration = if squares > 0.0 {
+            cubes / (6.0 * Float::powf(squares, 1.5))
+        } else {
+            0.0
+        };
+
+        Ok(Self {
+            estimate,
+            distribution,
+            acceleration,
+        })
+    }
+
+    /// Returns the statistic calculated on the original items
+    pub fn estimate(&self) -> f64 {
+        self.estimate
+    }
+
+    /// Returns the statistic calculated on each resample, sorted
+    pub fn distribution(&self) -> &[f64] {
+        &self.distribution
+    }
+
+    /// Returns the standard deviation of the statistic over the resamples,
+    /// which estimates the standard error of the statistic
+    pub fn standard_error(&self) -> f64 {
+        let n = self.distribution.len() as f64;
+        let mean = self.distribution.iter().sum::<f64>() / n;
+        let squares: f64 = self
+            .distribution
+            .iter()
+            .map(|theta| (theta - mean) * (theta - mean))
+            .sum();
+        Float::sqrt(squares / (n - 1.0).max(1.0))
+    }
+
+    /// Returns how much larger the mean of the statistic over the resamples
+    /// is than [`Bootstrap::estimate`], which estimates the bias of the statistic
+    pub fn bias(&self) -> f64 {
+        self.distribution.iter().sum::<f64>() / self.distribution.len() as f64 - self.estimate
+    }
+
+    /// Returns the percentile confidence interval with a `confidence` chance of
+    /// containing the true value, which is the central `confidence` proportion
+    /// of the statistics of the resamples
+    ///
+    /// # Panics
+    /// Panics if `confidence` is not strictly between 0 and 1
+    pub fn percentile_interval(&self, confidence: f64) -> (f64, f64) {
+        assert!(
+            confidence > 0.0 && confidence < 1.0,
+            "confidence must be between 0 and 1"
+        );
+        let tail = (1.0 - confidence) / 2.0;
+        (
+            quantile_of_sorted(&self.distribution, tail),
+            quantile_of_sorted(&self.distribution, 1.0 - tail),
+        )
+    }
+
+    /// Returns the [bias-corrected and accelerated](<https://en.wikipedia.org/wiki/Bootstrapping_(statistics)#Methods_for_bootstrap_confidence_intervals>)
+    /// (BCa) confidence interval with a `confidence` chance of containing the
+    /// true value. This adjusts the percentiles of
+    /// [`Bootstrap::percentile_interval`] for the median of the resamples not
+    /// being [`Bootstrap::estimate`], and for the standard error changing with
+    /// the value of the statistic, so it is more accurate when the distribution
+    /// of the statistic is skewed.
+    ///
+    /// # Panics
+    /// Panics if `confidence` is not strictly between 0 and 1
+    pub fn bca_interval(&self, confidence: f64) -> (f64, f64) {
+        assert!(
+            confidence > 0.0 && confidence < 1.0,
+            "confidence must be between 0 and 1"
+        );
+        let n = self.distribution.len() as f64;
+        // resamples equal to the estimate count as half below it, and the
+        // proportion is kept away from 0 and 1 so the correction is finite
+        let below = self
+            .distribution
+            .iter()
+            .map(|&theta| match theta.partial_cmp(&self.estimate) {
+                Some(Ordering::Less) => 1.0,
+                Some(Ordering::Equal) => 0.5,
+                _ => 0.0,
+            })
+            .sum::<f64>();
+        let bias_correction = normal_quantile((below / n).clamp(0.5 / n, 1.0 - 0.5 / n));
+        let adjusted = |p: f64| {
+            let z = bias_correction + normal_quantile(p);
+            normal_cdf(bias_correction + z / (1.0 - self.acceleration * z))
+        };
+        let tail = (1.0 - confidence) / 2.0;
+        (
+            quantile_of_sorted(&self.distribution, adjusted(tail)),
+            quantile_of_sorted(&self.distribution, adjusted(1.0 - tail)),
+        )
+    }
+}
+
+/// Returns an error unless there are at least two values,
+/// so that there is a value left when one is left out
+fn check_size(values: &[f64]) -> Result<()> {
+    if values.is_empty() {
+        return Err(StatsError::EmptyCollection);
+    }
+    if values.len() < 2 {
+        return Err(StatsError::InsufficientData {
+            required: 2,
+            actual: values.len(),
+        });
+    }
+    Ok(())
+}
+
+/// Returns `statistic` calculated on `values` with each value left out in turn
+fn leave_one_out<F>(values: &[f64], mut statistic: F) -> Result<Vec<f64>>
+where
+    F: FnMut(&[f64]) -> Result<f64>,
+{
+    let mut rest = Vec::with_capacity(values.len());
+    let mut results = Vec::with_capacity(values.len());
+    for i in 0..values.len() {
+        rest.clear();
+        rest.extend_from_slice(&values[..i]);
+        rest.extend_from_slice(&values[i + 1..]);
+        results.push(statistic(&rest)?);
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Stats;
+
+    use approx::assert_relative_eq;
+    #[cfg(feature = "rand")]
+    use rand::rngs::SmallRng;
+    #[cfg(feature = "rand")]
+    use rand::SeedableRng;
+
+    fn mean(sample: &[f64]) -> Result<f64> {
+        sample.mean()
+    }
+
+    #[test]
+    fn test_leave_one_out() {
+        let values = [1.0, 2.0, 3.0, 6.0];
+        assert_eq!(
+            leave_one_out(&values, mean),
+            Ok(alloc::vec![11.0 / 3.0, 10.0 / 3.0, 3.0, 2.0])
+        );
+    }
+
+    #[test]
+    fn test_jackknife_mean() {
+        let values = [2.0, 4.0, 4.0, 5.0, 7.0, 8.0];
+        let jackknife = Jackknife::new(&values, mean).unwrap();
+        assert_eq!(jackknife.estimate, 5.0);
+        assert_relative_eq!(jackknife.bias, 0.0, epsilon = 1e-12);
+        // for the mean this is the usual standard error, the sample standard deviation over sqrt(n)
+        assert_relative_eq!(
+            jackknife.standard_error,
+            (4.8_f64 / 6.0).sqrt(),
+            epsilon = 1e-12
+        );
+        assert_eq!(jackknife.leave_one_out.len(), 6);
+    }
+
+    #[test]
+    fn test_jackknife_variance() {
+        // correcting the bias of the population variance gives the sample variance
+        let values = [2.0, 4.0, 4.0, 5.0, 7.0, 8.0];
+        let jackknife = Jackknife::new(&values, |sample| sample.variance()).unwrap();
+        assert_eq!(jackknife.estimate, 4.0);
+        assert_relative_eq!(jackknife.bias, -0.8, epsilon = 1e-12);
+        assert_relative_eq!(jackknife.bias_corrected(), 4.8, epsilon = 1e-12);
+        assert_eq!(
+            Jackknife::new(&[1.0], mean),
+            Err(StatsError::InsufficientData {
+                required: 2,
+                actual: 1
+            })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "rand")]
+    fn test_bootstrap_mean() {
+        let values: Vec<f64> = (1..=20).map(f64::from).collect();
+        let mut rng = SmallRng::seed_from_u64(7);
+        let bootstrap = Bootstrap::new(&values, mean, 2000, &mut rng).unwrap();
+        assert_eq!(bootstrap.estimate(), 10.5);
+        assert_eq!(bootstrap.distribution().len(), 2000);
+        assert!(bootstrap.distribution().windows(2).all(|w| w[0] <= w[1]));
+        // the standard error of the mean is the population standard deviation over sqrt(n)
+        let expected = (399.0_f64 / 12.0 / 20.0).sqrt();
+        assert!((bootstrap.standard_error() / expected - 1.0).abs() < 0.1);
+        assert!(bootstrap.bias().abs() < 0.1);
+        // the jackknife values of the mean of symmetric data aren't skewed
+        assert_relative_eq!(bootstrap.acceleration, 0.0, epsilon = 1e-12);
+
+        let (lower, upper) = bootstrap.percentile_interval(0.95);
+        assert!((lower - (10.5 - 1.96 * expected)).abs() < 0.3);
+        assert!((upper - (10.5 + 1.96 * expected)).abs() < 0.3);
+        let (bca_lower, bca_upper) = bootstrap.bca_interval(0.95);
+        assert!((bca_lower - lower).abs() < 0.3);
+        assert!((bca_upper - upper).abs() < 0.3);
+    }
+
+    #[test]
+    #[cfg(feature = "rand")]
+    fn test_bca_skewed() {
+        // for right-skewed data the BCa interval is shifted to the right
+        let values = [1.0, 1.2, 1.3, 1.5, 1.6, 2.0, 2.4, 3.1, 4.8, 9.5, 17.0, 30.0];
+        let mut rng = SmallRng::seed_from_u64(1);
+        let bootstrap = Bootstrap::new(&values, mean, 4000, &mut rng).unwrap();
+        assert!(bootstrap.acceleration > 0.0);
+        let (lower, upper) = bootstrap.percentile_interval(0.9);
+        let (bca_lower, bca_upper) = bootstrap.bca_interval(0.9);
+        assert!(bca_lower > lower);
+        assert!(bca_upper > upper);
+    }
+
+    #[test]
+    #[cfg(feature = "rand")]
+    fn test_bootstrap_too_small() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        assert_eq!(
+            Bootstrap::new(&[], mean, 10, &mut rng),
+            Err(StatsError::EmptyCollection)
+        );
+        assert_eq!(
+            Bootstrap::new(&[1.0], mean, 10, &mut rng),
+            Err(StatsError::InsufficientData {
+                required: 2,
+                actual: 1
+            })
+        );
+    }
+}
@@ -0,0 +1,180 @@
+use alloc::vec::Vec;
+use core::slice;
+
+use rand::Rng;
+
+use crate::helpers::Float;
+
+/// Keeps a uniform random sample of at most `k` values from a stream,
+/// using [reservoir sampling](<https://en.wikipedia.org/wiki/Reservoir_sampling>).
+///
+/// Every value pushed is equally likely to be in the sample, however long the
+/// stream is, so statistics of the sample are unbiased estimates of those of
+/// the whole stream. Once the reservoir is full, it uses Li's Algorithm L,
+/// which gives the same sample as the simpler Algorithm R but only needs
+/// random numbers for the values that are kept, not for every value pushed.
+///
+/// `&Reservoir` implements [`IntoIterator`] over references to the sampled
+/// values, so [`Stats`](crate::Stats) can be used on the reservoir.
+///
+/// # Examples
+/// ```
+/// use rand::rngs::SmallRng;
+/// use rand::SeedableRng;
+/// use stats_traits::{Reservoir, Stats};
+/// let mut reservoir = Reservoir::new(100, SmallRng::seed_from_u64(1));
+/// for x in 0..100_000 {
+///     reservoir.push(x as f64);
+/// }
+/// assert_eq!(reservoir.sample().len(), 100);
+/// let mean = reservoir.mean().unwrap();
+/// assert!((mean - 50_000.0).abs() < 10_000.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Reservoir<T, R> {
+    sample: Vec<T>,
+    capacity: usize,
+    rng: R,
+    /// Number of values pushed
+    seen: usize,
+    /// Number of values pushed when the next value to be sampled arrives
+    next: usize,
+    /// The largest of `capacity` uniform random numbers, used
+    /// to find how many values to skip before the next one sampled
+    w: f64,
+}
+
+impl<T, R> Reservoir<T, R>
+where
+    R: Rng,
+{
+    /// Create an empty reservoir that keeps a sample of at most `k` values,
+    /// using `rng` as the source of randomness
+    ///
+    /// # Panics
+    /// Panics if `k` is 0
+    pub fn new(k: usize, rng: R) -> Self {
+        assert!(k > 0, "sample size must be greater than 0");
+        Self {
+            sample: Vec::with_capacity(k),
+            capacity: k,
+            rng,
+            seen: 0,
+            next: 0,
+            w: 1.0,
+        }
+    }
+
+    /// Returns a uniform random number in (0, 1]
+    fn random(&mut self) -> f64 {
+        1.0 - self.rng.gen::<f64>()
+    }
+
+    /// Work out when the next value will be sampled
+    fn skip(&mut self) {
+        self.w *= Float::exp(Float::ln(self.random()) / self.capacity as f64);
+        let skipped = Float::floor(Float::ln(self.random()) / Float::ln_1p(-self.w)) as usize;
+        self.next = self.seen.saturating_add(skipped).saturating_add(1);
+    }
+
+    /// Add a value from the stream, which may be kept in the sample
+    pub fn push(&mut self, x: T) {
+        self.seen += 1;
+        if self.sample.len() < self.capacity {
+            self.sample.push(x);
+            if self.sample.len() == self.capacity {
+                self.skip();
+            }
+        } else if self.seen == self.next {
+            let index = self.rng.gen_range(0..self.capacity);
+            self.sample[index] = x;
+            self.skip();
+        }
+    }
+
+    /// Returns the number of values that have been pushed
+    pub fn count(&self) -> usize {
+        self.seen
+    }
+
+    /// Returns the maximum number of values kept in the sample
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the values currently in the sample, in no particular order
+    pub fn sample(&self) -> &[T] {
+        &self.sample
+    }
+
+    /// Consume the reservoir, returning the values in the sample
+    pub fn into_sample(self) -> Vec<T> {
+        self.sample
+    }
+}
+
+impl<T, R> Extend<T> for Reservoir<T, R>
+where
+    R: Rng,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+impl<'a, T, R> IntoIterator for &'a Reservoir<T, R> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.sample.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Stats;
+
+    extern crate std;
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+    use std::vec;
+
+    #[test]
+    fn test_fewer_than_k() {
+        let mut reservoir = Reservoir::new(5, SmallRng::seed_from_u64(0));
+        reservoir.extend([3, 1, 2]);
+        assert_eq!(reservoir.count(), 3);
+        assert_eq!(reservoir.sample(), &[3, 1, 2]);
+        assert_eq!(reservoir.sum(), 6);
+    }
+
+    #[test]
+    fn test_uniform() {
+        // every value should be sampled about as often as every other
+        let (n, k, trials) = (20, 5, 20_000);
+        let mut times_sampled = vec![0; n];
+        let mut rng = SmallRng::seed_from_u64(42);
+        for _ in 0..trials {
+            let mut reservoir = Reservoir::new(k, &mut rng);
+            reservoir.extend(0..n);
+            assert_eq!(reservoir.sample().len(), k);
+            for x in reservoir.into_sample() {
+                times_sampled[x] += 1;
+            }
+        }
+        let expected = (trials * k / n) as f64;
+        for count in times_sampled {
+            assert!((count as f64 - expected).abs() < expected * 0.1);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_zero_size() {
+        let _ = Reservoir::<f64, _>::new(0, SmallRng::seed_from_u64(0));
+    }
+}
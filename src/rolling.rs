@@ -0,0 +1,599 @@
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+
+use num_traits::{FromBytes, ToBytes, ToPrimitive};
+
+use crate::checkpoint;
+use crate::checkpoint::Reader;
+use crate::checkpoint::Writer;
+use crate::helpers::item_to_f64;
+use crate::helpers::MinMax;
+use crate::helpers::NumExt;
+use crate::window::Window;
+use crate::CheckpointError;
+use crate::Result;
+
+/// Keeps statistics over the last `N` values of a stream (a sliding window).
+///
+/// The values in the window are stored in a ring buffer, and the sum,
+/// mean and variance are updated in O(1) as values enter and leave the window,
+/// so they never have to be recalculated from scratch.
+///
+/// The minimum and maximum are tracked with monotonic queues: each holds the
+/// values in the window that could still become the minimum (or maximum)
+/// once older values leave, so [`RollingStats::min`], [`RollingStats::max`]
+/// and [`RollingStats::range`] are O(1) and each push is amortized O(1),
+/// regardless of the size of the window.
+///
+/// # Examples
+/// ```
+/// use stats_traits::RollingStats;
+/// let mut rolling = RollingStats::new(3);
+/// for x in [1, 2, 3, 4] {
+///     rolling.push(x);
+/// }
+/// // only 2, 3 and 4 are still in the window
+/// assert_eq!(rolling.count(), 3);
+/// assert_eq!(rolling.sum(), 9);
+/// assert_eq!(rolling.mean(), Ok(3.0));
+/// assert_eq!(rolling.min(), Ok(2));
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        try_from = "RollingStatsRepr<T>",
+        into = "RollingStatsRepr<T>",
+        bound(
+            serialize = "T: serde::Serialize + Copy",
+            deserialize = "T: serde::Deserialize<'de> + NumExt + Copy + ToPrimitive + MinMax"
+        )
+    )
+)]
+pub struct RollingStats<T> {
+    window: Window<T, VecDeque<T>, VecDeque<(usize, T)>>,
+}
+
+impl<T> RollingStats<T>
+where
+    T: NumExt + Copy + ToPrimitive + MinMax,
+{
+    /// Create an empty window that holds at most `window_size` values
+    ///
+    /// # Panics
+    /// Panics if `window_size` is 0
+    pub fn new(window_size: usize) -> Self {
+        assert!(window_size > 0, "window size must be greater than 0");
+        Self {
+            window: Window::new(
+                window_size,
+                VecDeque::with_capacity(window_size),
+                VecDeque::new(),
+                VecDeque::new(),
+            ),
+        }
+    }
+
+    /// Create an empty window to restore `len` values into, checking
+    /// that a window of `capacity` could have been created and hold them
+    fn restore(capacity: usize, len: usize) -> core::result::Result<Self, CheckpointError> {
+        // no window could have been created with a capacity that overflows
+        let max_capacity = isize::MAX as usize / Ord::max(size_of::<T>(), 1);
+        if capacity == 0 || capacity > max_capacity || len > capacity {
+            return Err(CheckpointError::Invalid);
+        }
+        // the capacity isn't trusted to allocate up front
+        Ok(Self {
+            window: Window::new(capacity, VecDeque::new(), VecDeque::new(), VecDeque::new()),
+        })
+    }
+
+    /// Add a value to the window. If the window was already full, the oldest
+    /// value is removed from it and returned.
+    pub fn push(&mut self, x: T) -> Option<T> {
+        self.window.push(x)
+    }
+
+    /// Remove every value from the window
+    pub fn clear(&mut self) {
+        self.window.clear();
+    }
+
+    /// Returns the number of values currently in the window
+    pub fn count(&self) -> usize {
+        self.window.count()
+    }
+
+    /// Returns the maximum number of values the window can hold
+    pub fn capacity(&self) -> usize {
+        self.window.capacity
+    }
+
+    /// Returns `true` if the window holds as many values as it can
+    pub fn is_full(&self) -> bool {
+        self.window.is_full()
+    }
+
+    /// Returns an iterator over the values in the window, from oldest to newest
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.window.values.iter()
+    }
+
+    /// Returns the sum of the values in the window
+    pub fn sum(&self) -> T {
+        self.window.sum
+    }
+
+    /// Returns the mean of the values in the window
+    ///
+    /// # Errors
+    /// Returns an error if the window is empty
+    pub fn mean(&self) -> Result<f64> {
+        self.window.mean()
+    }
+
+    /// Returns the (population) variance of the values in the window,
+    /// which is `NaN` while a `NaN` or infinity is in the window
+    ///
+    /// # Errors
+    /// Returns an error if the window is empty
+    pub fn variance(&self) -> Result<f64> {
+        self.window.variance()
+    }
+
+    /// Returns the standard deviation of the values in the window
+    ///
+    /// # Errors
+    /// Returns an error if the window is empty
+    pub fn std_dev(&self) -> Result<f64> {
+        self.window.std_dev()
+    }
+
+    /// Returns the smallest value in the window
+    ///
+    /// # Errors
+    /// Returns an error if the window is empty
+    pub fn min(&self) -> Result<T> {
+        self.window.min()
+    }
+
+    /// Returns the largest value in the window
+    ///
+    /// # Errors
+    /// Returns an error if the window is empty
+    pub fn max(&self) -> Result<T> {
+        self.window.max()
+    }
+
+    /// Returns the range of the window (the smallest subtracted from the largest)
+    ///
+    /// # Errors
+    /// Returns an error if the window is empty
+    pub fn range(&self) -> Result<T> {
+        Ok(self.max()? - self.min()?)
+    }
+}
+
+impl<T> RollingStats<T>
+where
+    T: NumExt + Copy + ToPrimitive + MinMax + ToBytes + FromBytes,
+    <T as FromBytes>::Bytes: Default,
+{
+    /// Save the window and its statistics as a compact checkpoint, which
+    /// [`RollingStats::from_bytes`] restores, so a long-running aggregation
+    /// can survive a restart or be sent to another machine. The values are
+    /// stored in their own type, so the checkpoint is exact.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::RollingStats;
+    /// let mut rolling = RollingStats::new(3);
+    /// for x in [1u32, 5, 2, 8] {
+    ///     rolling.push(x);
+    /// }
+    /// let checkpoint = rolling.to_bytes();
+    /// let mut restored = RollingStats::<u32>::from_bytes(&checkpoint).unwrap();
+    /// assert_eq!(restored.max(), Ok(8));
+    /// restored.push(3);
+    /// assert_eq!(restored.sum(), 13);
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let size = size_of::<<T as ToBytes>::Bytes>();
+        let mut bytes = vec![0; 34 + size * (self.count() + 1)];
+        let mut writer = Writer::new(&mut bytes, checkpoint::ROLLING_STATS);
+        writer.put_usize(self.window.capacity);
+        writer.put_usize(self.count());
+        writer.put_f64(self.window.mean);
+        writer.put_f64(self.window.m2);
+        writer.put_bytes(self.window.sum.to_le_bytes().as_ref());
+        for x in self.iter() {
+            writer.put_bytes(x.to_le_bytes().as_ref());
+        }
+        bytes
+    }
+
+    /// Restore a window from a checkpoint written by [`RollingStats::to_bytes`]
+    /// for the same type of values
+    ///
+    /// # Errors
+    /// Returns an error if `bytes` isn't a checkpoint of a `RollingStats`
+    /// in a version of the format this version of the crate can read, if
+    /// the window in it holds more values than its capacity, if its
+    /// capacity is too large for a window of values of this type, or if an
+    /// empty window has a mean or variance other than 0
+    pub fn from_bytes(bytes: &[u8]) -> core::result::Result<Self, CheckpointError> {
+        let mut reader = Reader::new(bytes, checkpoint::ROLLING_STATS)?;
+        let capacity = reader.get_usize()?;
+        let len = reader.get_usize()?;
+        let mut rolling = Self::restore(capacity, len)?;
+        let (mean, m2) = (reader.get_f64()?, reader.get_f64()?);
+        // the statistics are reset whenever the window becomes empty
+        if len == 0 && (mean != 0.0 || m2 != 0.0) {
+            return Err(CheckpointError::Invalid);
+        }
+        let mut get_value = || {
+            let mut value = <T as FromBytes>::Bytes::default();
+            let size = value.as_mut().len();
+            value.as_mut().copy_from_slice(reader.get_bytes(size)?);
+            Ok(T::from_le_bytes(&value))
+        };
+        let sum = get_value()?;
+        for _ in 0..len {
+            rolling.push(get_value()?);
+        }
+        // the statistics were updated as values left the window, so they
+        // can differ from the ones of the values by rounding
+        rolling.window.sum = sum;
+        rolling.window.mean = mean;
+        rolling.window.m2 = m2;
+        reader.finish()?;
+        Ok(rolling)
+    }
+}
+
+/// The values of a [`RollingStats`] that it is serialized as. The monotonic
+/// queues and running totals are rebuilt from them when it is deserialized,
+/// so they can't be inconsistent with the window.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct RollingStatsRepr<T> {
+    capacity: usize,
+    window: Vec<T>,
+}
+
+#[cfg(feature = "serde")]
+impl<T> From<RollingStats<T>> for RollingStatsRepr<T> {
+    fn from(rolling: RollingStats<T>) -> Self {
+        Self {
+            capacity: rolling.window.capacity,
+            window: rolling.window.values.into(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T> TryFrom<RollingStatsRepr<T>> for RollingStats<T>
+where
+    T: NumExt + Copy + ToPrimitive + MinMax,
+{
+    type Error = CheckpointError;
+
+    fn try_from(repr: RollingStatsRepr<T>) -> core::result::Result<Self, Self::Error> {
+        let mut rolling = Self::restore(repr.capacity, repr.window.len())?;
+        for x in repr.window {
+            rolling.push(x);
+        }
+        Ok(rolling)
+    }
+}
+
+/// A value of an iterator scored against the window of values before it,
+/// as yielded by [`IteratorStats::rolling_z_scores`](crate::IteratorStats::rolling_z_scores)
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScoredPoint {
+    /// The value
+    pub value: f64,
+    /// How many standard deviations the value is above the mean of the
+    /// window before it (or below, if negative). It is `NaN` until the
+    /// window is full, and infinite if every value in the window is the
+    /// same and this one is different.
+    pub z_score: f64,
+    /// Whether the size of the z-score is greater than the threshold
+    pub is_anomaly: bool,
+}
+
+/// An iterator that scores each value of another one against a
+/// [`RollingStats`] window of the values before it. It is created by
+/// [`IteratorStats::rolling_z_scores`](crate::IteratorStats::rolling_z_scores).
+#[derive(Debug, Clone)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct RollingZScores<I> {
+    iter: I,
+    window: RollingStats<f64>,
+    threshold: f64,
+}
+
+impl<I> RollingZScores<I> {
+    /// Create the iterator, for [`IteratorStats::rolling_z_scores`](crate::IteratorStats::rolling_z_scores)
+    pub(crate) fn new(iter: I, window_size: usize, threshold: f64) -> Self {
+        assert!(threshold >= 0.0, "threshold must not be negative or NaN");
+        Self {
+            iter,
+            window: RollingStats::new(window_size),
+            threshold,
+        }
+    }
+
+    /// Returns the window of the latest values
+    pub fn window(&self) -> &RollingStats<f64> {
+        &self.window
+    }
+}
+
+impl<I> Iterator for RollingZScores<I>
+where
+    I: Iterator,
+    I::Item: ToPrimitive,
+{
+    type Item = Result<ScoredPoint>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let x = self.iter.next()?;
+        Some(item_to_f64(x).map(|value| {
+            let z_score = if self.window.is_full() {
+                let deviation = value - self.window.mean().unwrap_or(f64::NAN);
+                if deviation == 0.0 {
+                    0.0
+                } else {
+                    deviation / self.window.std_dev().unwrap_or(f64::NAN)
+                }
+            } else {
+                f64::NAN
+            };
+            if value.is_finite() {
+                self.window.push(value);
+            }
+            ScoredPoint {
+                value,
+                z_score,
+                is_anomaly: z_score.abs() > self.threshold,
+            }
+        }))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I> ExactSizeIterator for RollingZScores<I>
+where
+    I: ExactSizeIterator,
+    I::Item: ToPrimitive,
+{
+}
+
+impl<I> FusedIterator for RollingZScores<I>
+where
+    I: FusedIterator,
+    I::Item: ToPrimitive,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IteratorStats;
+    use crate::Stats;
+    use crate::StatsError;
+
+    extern crate std;
+    use std::vec::Vec;
+
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_checkpoint() {
+        let mut rolling = RollingStats::new(4);
+        for x in [0.1, 2.5, -3.0, 7.0, 0.3, 1.9] {
+            rolling.push(x);
+        }
+        let restored = RollingStats::<f64>::from_bytes(&rolling.to_bytes()).unwrap();
+        assert!(restored.iter().eq(rolling.iter()));
+        assert_eq!(restored.capacity(), 4);
+        assert_eq!(restored.sum(), rolling.sum());
+        assert_eq!(restored.mean(), rolling.mean());
+        assert_eq!(restored.variance(), rolling.variance());
+        assert_eq!(restored.min(), Ok(-3.0));
+        let empty = RollingStats::<i64>::new(2).to_bytes();
+        assert_eq!(RollingStats::<i64>::from_bytes(&empty).unwrap().count(), 0);
+        assert_eq!(
+            RollingStats::<i32>::from_bytes(&empty).err(),
+            Some(CheckpointError::Invalid)
+        );
+    }
+
+    #[test]
+    fn test_checkpoint_corrupt_capacity() {
+        let mut checkpoint = RollingStats::<f64>::new(4).to_bytes();
+        checkpoint[2..10].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert_eq!(
+            RollingStats::<f64>::from_bytes(&checkpoint).err(),
+            Some(CheckpointError::Invalid)
+        );
+        let mut corrupt = checkpoint.clone();
+        corrupt[18..26].copy_from_slice(&1.5f64.to_le_bytes());
+        assert_eq!(
+            RollingStats::<f64>::from_bytes(&corrupt).err(),
+            Some(CheckpointError::Invalid)
+        );
+        // a capacity that is possible isn't allocated until values are pushed
+        checkpoint[2..10].copy_from_slice(&(1u64 << 40).to_le_bytes());
+        let restored = RollingStats::<f64>::from_bytes(&checkpoint).unwrap();
+        assert_eq!(restored.capacity(), 1 << 40);
+        assert_eq!(restored.count(), 0);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_resume() {
+        let mut rolling = RollingStats::new(3);
+        for x in [4, 1, 7, 2] {
+            rolling.push(x);
+        }
+        let json = serde_json::to_string(&rolling).unwrap();
+        assert_eq!(json, r#"{"capacity":3,"window":[1,7,2]}"#);
+        let mut loaded: RollingStats<i32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.push(5), Some(1));
+        assert_eq!(loaded.sum(), 14);
+        assert_eq!(loaded.min(), Ok(2));
+        assert_eq!(loaded.max(), Ok(7));
+        let tampered = r#"{"capacity":2,"window":[1,7,2]}"#;
+        assert!(serde_json::from_str::<RollingStats<i32>>(tampered).is_err());
+        let zero = r#"{"capacity":0,"window":[]}"#;
+        assert!(serde_json::from_str::<RollingStats<i32>>(zero).is_err());
+    }
+
+    #[test]
+    fn test_push_evicts_oldest() {
+        let mut rolling = RollingStats::new(2);
+        assert_eq!(rolling.push(1), None);
+        assert_eq!(rolling.push(2), None);
+        assert!(rolling.is_full());
+        assert_eq!(rolling.push(3), Some(1));
+        assert_eq!(rolling.iter().copied().collect::<Vec<_>>(), [2, 3]);
+    }
+
+    #[test]
+    fn test_empty() {
+        let rolling = RollingStats::<f64>::new(4);
+        assert_eq!(rolling.mean(), Err(StatsError::EmptyCollection));
+        assert_eq!(rolling.max(), Err(StatsError::EmptyCollection));
+        assert_eq!(rolling.sum(), 0.0);
+    }
+
+    #[test]
+    fn test_matches_recalculation() {
+        let data = [4.0, 7.0, 13.0, 16.0, 1.0, -3.0, 8.5, 2.25, 9.0, 11.0];
+        let mut rolling = RollingStats::new(4);
+        for (i, &x) in data.iter().enumerate() {
+            rolling.push(x);
+            let window = &data[i.saturating_sub(3)..=i];
+            assert_relative_eq!(
+                rolling.mean().unwrap(),
+                window.mean().unwrap(),
+                epsilon = 1e-9
+            );
+            assert_relative_eq!(
+                rolling.variance().unwrap(),
+                window.variance().unwrap(),
+                epsilon = 1e-9
+            );
+            assert_eq!(rolling.min(), Stats::min(window));
+            assert_eq!(rolling.max(), Stats::max(window));
+            assert_eq!(rolling.range(), Stats::range(window));
+        }
+    }
+
+    #[test]
+    fn test_non_finite_leaves_window() {
+        for bad in [f64::NAN, f64::INFINITY] {
+            let mut rolling = RollingStats::new(3);
+            rolling.push(1.0);
+            rolling.push(bad);
+            assert!(rolling.variance().unwrap().is_nan());
+            for x in [2.0, 4.0, 6.0, 8.0] {
+                rolling.push(x);
+            }
+            assert_eq!(rolling.sum(), 18.0);
+            assert_relative_eq!(rolling.mean().unwrap(), 6.0);
+            assert_relative_eq!(rolling.variance().unwrap(), 8.0 / 3.0);
+        }
+    }
+
+    #[test]
+    fn test_min_max_with_repeats() {
+        let data = [3, 1, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5, 9, 9, 1, 0, 0, 7];
+        for size in 1..6 {
+            let mut rolling = RollingStats::new(size);
+            for (i, &x) in data.iter().enumerate() {
+                rolling.push(x);
+                let window = &data[(i + 1).saturating_sub(size)..=i];
+                assert_eq!(rolling.min(), Stats::min(window));
+                assert_eq!(rolling.max(), Stats::max(window));
+            }
+        }
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut rolling = RollingStats::new(3);
+        rolling.push(5);
+        rolling.clear();
+        assert_eq!(rolling.count(), 0);
+        rolling.push(1);
+        assert_eq!(rolling.mean(), Ok(1.0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_zero_window() {
+        RollingStats::<i32>::new(0);
+    }
+
+    #[test]
+    fn test_rolling_z_scores() {
+        let values = [10.0, 12.0, 10.0, 12.0, 11.0, 30.0, 10.0, 12.0];
+        let points: Vec<ScoredPoint> = values
+            .into_iter()
+            .rolling_z_scores(4, 3.0)
+            .map(Result::unwrap)
+            .collect();
+        assert!(points[..4]
+            .iter()
+            .all(|p| p.z_score.is_nan() && !p.is_anomaly));
+        // the window is 10, 12, 10, 12, with a mean of 11
+        assert_eq!(points[4].z_score, 0.0);
+        // then 12, 10, 12, 11, with a variance of 0.6875
+        assert_relative_eq!(points[5].z_score, 18.75 / 0.6875f64.sqrt(), epsilon = 1e-12);
+        assert!(points[5].is_anomaly);
+        assert_eq!(points[5].value, 30.0);
+        // the spike is in the window, so it widens it
+        assert!(points[6].z_score < 0.0 && !points[6].is_anomaly);
+        assert_eq!(points.iter().filter(|p| p.is_anomaly).count(), 1);
+
+        let mut flat = [5, 5, 5, 6].into_iter().rolling_z_scores(2, 10.0).skip(2);
+        assert_eq!(flat.next().unwrap().unwrap().z_score, 0.0);
+        let step = flat.next().unwrap().unwrap();
+        assert_eq!(step.z_score, f64::INFINITY);
+        assert!(step.is_anomaly);
+    }
+
+    #[test]
+    fn test_rolling_z_scores_non_finite() {
+        let values = [10.0, 12.0, f64::NAN, 10.0, 12.0, f64::INFINITY, 11.0, 30.0];
+        let points: Vec<ScoredPoint> = values
+            .into_iter()
+            .rolling_z_scores(4, 3.0)
+            .map(Result::unwrap)
+            .collect();
+        assert!(points[2].z_score.is_nan() && !points[2].is_anomaly);
+        assert_eq!(points[5].z_score, f64::INFINITY);
+        assert!(points[5].is_anomaly);
+        // the window is 10, 12, 10, 12, without the NaN or infinity
+        assert_eq!(points[6].z_score, 0.0);
+        assert!(points[7].is_anomaly);
+        assert!(points[7].z_score.is_finite());
+    }
+
+    #[test]
+    #[should_panic(expected = "threshold must not be negative or NaN")]
+    fn test_rolling_z_scores_invalid_threshold() {
+        let _ = [1.0].into_iter().rolling_z_scores(2, f64::NAN);
+    }
+}
@@ -0,0 +1,142 @@
+/// Tracks runs (or streaks) of consecutive values on the same side of a
+/// threshold, such as days above a target or wins in a row, as values
+/// arrive one at a time.
+///
+/// Values equal to the threshold and `NaN`s don't belong to either side,
+/// so they are skipped and neither end nor extend a run. Nothing is
+/// allocated, so this is cheap enough to keep for alerting on a stream.
+///
+/// # Examples
+/// ```
+/// use stats_traits::RunStats;
+/// let mut latency = RunStats::new(100.0);
+/// latency.extend([80.0, 120.0, 130.0, 150.0, 90.0, 110.0, 115.0]);
+/// assert_eq!(latency.runs(), 4);
+/// assert_eq!(latency.longest_above(), 3);
+/// assert_eq!(latency.longest_below(), 1);
+/// // alert when the latency has been high for too long
+/// assert_eq!(latency.is_above(), Some(true));
+/// assert_eq!(latency.current_streak(), 2);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RunStats {
+    threshold: f64,
+    runs: usize,
+    longest_above: usize,
+    longest_below: usize,
+    /// Length of the run the last value belongs to
+    current: usize,
+    /// Whether the last run is above the threshold, or `None` before the first run
+    above: Option<bool>,
+}
+
+impl RunStats {
+    /// Create a tracker of runs above and below `threshold`
+    pub const fn new(threshold: f64) -> Self {
+        Self {
+            threshold,
+            runs: 0,
+            longest_above: 0,
+            longest_below: 0,
+            current: 0,
+            above: None,
+        }
+    }
+
+    /// Add a value to the tracker
+    pub fn push(&mut self, x: f64) {
+        if x == self.threshold || x.is_nan() {
+            return;
+        }
+        let above = x > self.threshold;
+        if self.above == Some(above) {
+            self.current += 1;
+        } else {
+            self.runs += 1;
+            self.current = 1;
+            self.above = Some(above);
+        }
+        let longest = if above {
+            &mut self.longest_above
+        } else {
+            &mut self.longest_below
+        };
+        *longest = (*longest).max(self.current);
+    }
+
+    /// Returns the threshold that values are compared with
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+
+    /// Returns the number of runs, which is one more than the
+    /// number of times the values crossed the threshold
+    pub fn runs(&self) -> usize {
+        self.runs
+    }
+
+    /// Returns the length of the longest run above the threshold
+    pub fn longest_above(&self) -> usize {
+        self.longest_above
+    }
+
+    /// Returns the length of the longest run below the threshold
+    pub fn longest_below(&self) -> usize {
+        self.longest_below
+    }
+
+    /// Returns the length of the current run, which is the one
+    /// the last value above or below the threshold belongs to
+    pub fn current_streak(&self) -> usize {
+        self.current
+    }
+
+    /// Returns whether the current run is above the threshold,
+    /// or `None` if no values above or below it have been pushed
+    pub fn is_above(&self) -> Option<bool> {
+        self.above
+    }
+}
+
+impl Extend<f64> for RunStats {
+    fn extend<I: IntoIterator<Item = f64>>(&mut self, iter: I) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty() {
+        let runs = RunStats::new(0.0);
+        assert_eq!(runs.runs(), 0);
+        assert_eq!(runs.current_streak(), 0);
+        assert_eq!(runs.is_above(), None);
+    }
+
+    #[test]
+    fn test_skips_threshold_and_nan() {
+        let mut runs = RunStats::new(0.0);
+        runs.extend([-1.0, 0.0, -2.0, f64::NAN, -3.0, 1.0, 0.0, 2.0]);
+        assert_eq!(runs.runs(), 2);
+        assert_eq!(runs.longest_below(), 3);
+        assert_eq!(runs.longest_above(), 2);
+        assert_eq!(runs.current_streak(), 2);
+        assert_eq!(runs.is_above(), Some(true));
+    }
+
+    #[test]
+    fn test_longest_is_kept() {
+        let mut runs = RunStats::new(5.0);
+        runs.extend([6.0, 7.0, 8.0, 1.0, 9.0]);
+        assert_eq!(runs.runs(), 3);
+        assert_eq!(runs.longest_above(), 3);
+        assert_eq!(runs.current_streak(), 1);
+    }
+}
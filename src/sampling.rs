@@ -0,0 +1,141 @@
+//! Random samples of the items of collections, for
+//! [`Stats::sample`](crate::Stats::sample) and the other samplers.
+
+use alloc::vec::Vec;
+
+use rand::Rng;
+
+use crate::helpers::Float;
+
+/// Returns `k` of the `len` items chosen uniformly at random without
+/// replacement, in their original order, or every item if there are no
+/// more than `k`, using Knuth's selection sampling (Algorithm S)
+pub(crate) fn simple_random<I, R>(items: I, len: usize, k: usize, rng: &mut R) -> Vec<I::Item>
+where
+    I: IntoIterator,
+    R: Rng + ?Sized,
+{
+    let mut sample = Vec::with_capacity(k.min(len));
+    let mut remaining = len;
+    for x in items {
+        if sample.len() == k {
+            break;
+        }
+        // the chance of choosing an item is the number still needed
+        // out of the number left
+        if rng.gen_range(0..remaining) < k - sample.len() {
+            sample.push(x);
+        }
+        remaining -= 1;
+    }
+    sample
+}
+
+/// Returns `k` of the `len` items evenly spaced `len / k` apart, from a
+/// random start within the first interval, or every item if there are no
+/// more than `k`
+pub(crate) fn systematic<I, R>(items: I, len: usize, k: usize, rng: &mut R) -> Vec<I::Item>
+where
+    I: IntoIterator,
+    R: Rng + ?Sized,
+{
+    if k >= len {
+        return items.into_iter().collect();
+    }
+    let interval = len as f64 / k as f64;
+    let start = rng.gen::<f64>() * interval;
+    let mut chosen = (0..k)
+        .map(|i| (start + i as f64 * interval) as usize)
+        .peekable();
+    items
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, x)| chosen.next_if_eq(&i).map(|_| x))
+        .collect()
+}
+
+/// Returns the items grouped into strata by `key`, in the order of the
+/// keys, with a simple random sample of `fraction` of the items of each
+/// stratum, rounded to the nearest item but at least one
+pub(crate) fn stratified<I, K, F, R>(
+    items: I,
+    fraction: f64,
+    mut key: F,
+    rng: &mut R,
+) -> Vec<(K, Vec<I::Item>)>
+where
+    I: IntoIterator,
+    K: Ord,
+    F: FnMut(&I::Item) -> K,
+    R: Rng + ?Sized,
+{
+    let mut keyed: Vec<(K, I::Item)> = items.into_iter().map(|x| (key(&x), x)).collect();
+    keyed.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut strata: Vec<(K, Vec<I::Item>)> = Vec::new();
+    for (k, x) in keyed {
+        match strata.last_mut() {
+            Some((last, stratum)) if *last == k => stratum.push(x),
+            _ => strata.push((k, alloc::vec![x])),
+        }
+    }
+    strata
+        .into_iter()
+        .map(|(k, stratum)| {
+            let len = stratum.len();
+            let size = (Float::round(fraction * len as f64) as usize).max(1);
+            (k, simple_random(stratum, len, size, rng))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_simple_random_is_uniform() {
+        let mut rng = SmallRng::seed_from_u64(7);
+        let mut chosen = [0; 10];
+        for _ in 0..10_000 {
+            let sample = simple_random(0..10, 10, 3, &mut rng);
+            assert_eq!(sample.len(), 3);
+            assert!(sample.windows(2).all(|w| w[0] < w[1]));
+            for i in sample {
+                chosen[i] += 1;
+            }
+        }
+        // each item is chosen 3 times in 10, about 3000 times
+        assert!(chosen.iter().all(|&n| (2800..3200).contains(&n)));
+        assert_eq!(simple_random(0..4, 4, 9, &mut rng), [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_systematic() {
+        let mut rng = SmallRng::seed_from_u64(7);
+        for _ in 0..100 {
+            let sample = systematic(0..100, 100, 7, &mut rng);
+            assert_eq!(sample.len(), 7);
+            assert!(sample[0] < 15);
+            assert!(sample
+                .windows(2)
+                .all(|w| (14..=15).contains(&(w[1] - w[0]))));
+        }
+        assert!(systematic(0..5, 5, 0, &mut rng).is_empty());
+        assert_eq!(systematic(0..3, 3, 3, &mut rng), [0, 1, 2]);
+    }
+
+    #[test]
+    fn test_stratified() {
+        let mut rng = SmallRng::seed_from_u64(7);
+        let strata = stratified(0..103, 0.1, |x| x % 3, &mut rng);
+        let sizes: Vec<(i32, usize)> = strata.iter().map(|(k, s)| (*k, s.len())).collect();
+        // strata of 35, 34 and 34 items
+        assert_eq!(sizes, [(0, 4), (1, 3), (2, 3)]);
+        assert!(strata.iter().all(|(k, s)| s.iter().all(|x| x % 3 == *k)));
+        let tiny = stratified([1, 2], 0.01, |&x| x, &mut rng);
+        assert_eq!(tiny, [(1, alloc::vec![1]), (2, alloc::vec![2])]);
+    }
+}
@@ -0,0 +1,87 @@
+/// A compact description of a distribution by seven of its quantiles: the
+/// smallest value, a low quantile, the lower quartile, the median, the
+/// upper quartile, a high quantile and the largest value, as calculated
+/// by [`Stats::seven_number_summary`](crate::Stats::seven_number_summary).
+///
+/// Bowley's summary uses the 10th and 90th percentiles for the low and
+/// high quantiles, and other variants use the octiles (the 12.5th and
+/// 87.5th percentiles) or the 2nd and 98th percentiles, which `tail` is.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SevenNumberSummary {
+    /// The probability of the low quantile, such as 0.1 for Bowley's summary.
+    /// The high quantile is at 1 minus it.
+    pub tail: f64,
+    /// Smallest value
+    pub min: f64,
+    /// The `tail` quantile
+    pub lower_tail: f64,
+    /// The 0.25 quantile
+    pub lower_quartile: f64,
+    /// The 0.5 quantile
+    pub median: f64,
+    /// The 0.75 quantile
+    pub upper_quartile: f64,
+    /// The 1 - `tail` quantile
+    pub upper_tail: f64,
+    /// Largest value
+    pub max: f64,
+}
+
+impl SevenNumberSummary {
+    /// Returns the interquartile range, which is the difference between the
+    /// upper and lower quartiles
+    pub fn iqr(&self) -> f64 {
+        self.upper_quartile - self.lower_quartile
+    }
+
+    /// Returns Bowley's quartile skewness, which compares the distances of
+    /// the quartiles from the median, so it is between -1 and 1 and 0 for a
+    /// symmetric distribution. It is `NaN` if the quartiles are equal.
+    pub fn quartile_skewness(&self) -> f64 {
+        (self.upper_quartile + self.lower_quartile - 2.0 * self.median) / self.iqr()
+    }
+
+    /// Returns the seven numbers in ascending order, from the smallest
+    /// value to the largest
+    pub fn to_array(&self) -> [f64; 7] {
+        [
+            self.min,
+            self.lower_tail,
+            self.lower_quartile,
+            self.median,
+            self.upper_quartile,
+            self.upper_tail,
+            self.max,
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quartile_skewness() {
+        let summary = SevenNumberSummary {
+            tail: 0.1,
+            min: 0.0,
+            lower_tail: 1.0,
+            lower_quartile: 2.0,
+            median: 3.0,
+            upper_quartile: 8.0,
+            upper_tail: 9.0,
+            max: 10.0,
+        };
+        assert_eq!(summary.iqr(), 6.0);
+        assert_eq!(summary.quartile_skewness(), 2.0 / 3.0);
+        assert_eq!(summary.to_array()[3], 3.0);
+        let flat = SevenNumberSummary {
+            median: 2.0,
+            upper_quartile: 2.0,
+            ..summary
+        };
+        assert!(flat.quartile_skewness().is_nan());
+    }
+}
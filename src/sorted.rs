@@ -0,0 +1,257 @@
+use core::ops::Deref;
+use core::ops::DerefMut;
+
+use num_traits::ToPrimitive;
+
+use crate::helpers::is_nan;
+use crate::helpers::item_to_f64;
+use crate::helpers::partial_cmp_nan_last;
+use crate::helpers::quantile_by;
+use crate::Result;
+use crate::StatsError;
+
+/// A collection that is known to be sorted, such as a `Vec<T>` or a `&[T]`,
+/// so that its order statistics don't need to sort it again. The minimum,
+/// maximum, median and quantiles take constant time, and the empirical
+/// distribution function takes logarithmic time.
+///
+/// `NaN`s are kept at the end of the collection and ignored, like
+/// [`Stats::quantile`](crate::Stats::quantile) ignores them.
+///
+/// # Examples
+/// ```
+/// use stats_traits::Sorted;
+/// let latencies = Sorted::new(vec![12.0, 7.0, f64::NAN, 3.0, 40.0, 9.0]);
+/// assert_eq!(latencies.len(), 5);
+/// assert_eq!(latencies.min(), Ok(3.0));
+/// assert_eq!(latencies.median(), Ok(9.0));
+/// assert_eq!(latencies.quantile(0.75), Ok(12.0));
+/// assert_eq!(latencies.ecdf(&10.0), 0.6);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Sorted<C> {
+    values: C,
+    /// The number of values before the `NaN`s at the end
+    len: usize,
+}
+
+impl<C, T> Sorted<C>
+where
+    C: Deref<Target = [T]>,
+    T: PartialOrd,
+{
+    /// Sort `values` in ascending order, with any `NaN`s at the end
+    pub fn new(mut values: C) -> Self
+    where
+        C: DerefMut,
+    {
+        values.sort_unstable_by(partial_cmp_nan_last);
+        Self::from_sorted(values)
+    }
+
+    /// Wrap `values` that are already sorted in ascending order, with any
+    /// `NaN`s at the end, without sorting them again. Whether they are
+    /// sorted is only checked in debug builds, so use
+    /// [`Sorted::try_from_sorted`] for values that might not be.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Sorted;
+    /// let sizes = [1, 2, 2, 5, 8];
+    /// let sorted = Sorted::from_sorted(&sizes[..]);
+    /// assert_eq!(sorted.max(), Ok(8));
+    /// assert_eq!(sorted.quantile(0.5), Ok(2.0));
+    /// ```
+    ///
+    /// # Panics
+    /// Panics in debug builds if `values` are not sorted
+    pub fn from_sorted(values: C) -> Self {
+        debug_assert!(is_sorted(&values), "the values must be sorted");
+        let len = values.partition_point(|x| !is_nan(x));
+        Self { values, len }
+    }
+
+    /// Wrap `values` like [`Sorted::from_sorted`], but check that they are
+    /// sorted in ascending order, with any `NaN`s at the end, in every build.
+    /// This takes linear time, which is still less than sorting them.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::{Sorted, StatsError};
+    /// let sorted = Sorted::try_from_sorted(&[1, 2, 5][..]).unwrap();
+    /// assert_eq!(sorted.median(), Ok(2.0));
+    /// assert_eq!(
+    ///     Sorted::try_from_sorted(&[2, 1][..]),
+    ///     Err(StatsError::NotSorted)
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `StatsError::NotSorted` if `values` are not sorted
+    pub fn try_from_sorted(values: C) -> Result<Self> {
+        if !is_sorted(&values) {
+            return Err(StatsError::NotSorted);
+        }
+        Ok(Self::from_sorted(values))
+    }
+
+    /// Returns the sorted values, without the `NaN`s
+    pub fn as_slice(&self) -> &[T] {
+        &self.values[..self.len]
+    }
+
+    /// Returns the collection of values, including any `NaN`s
+    pub fn into_inner(self) -> C {
+        self.values
+    }
+
+    /// Returns the number of values, not counting `NaN`s
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no values other than `NaN`s
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the smallest value
+    ///
+    /// # Errors
+    /// Returns an error if there are no values other than `NaN`s
+    pub fn min(&self) -> Result<T>
+    where
+        T: Clone,
+    {
+        self.as_slice()
+            .first()
+            .cloned()
+            .ok_or(StatsError::EmptyCollection)
+    }
+
+    /// Returns the largest value
+    ///
+    /// # Errors
+    /// Returns an error if there are no values other than `NaN`s
+    pub fn max(&self) -> Result<T>
+    where
+        T: Clone,
+    {
+        self.as_slice()
+            .last()
+            .cloned()
+            .ok_or(StatsError::EmptyCollection)
+    }
+
+    /// Find the median of the values, which is the mean of the
+    /// middle two if there are an even number of them
+    ///
+    /// # Errors
+    /// Returns an error if there are no values other than `NaN`s,
+    /// or if a value can't be converted to [`f64`]
+    pub fn median(&self) -> Result<f64>
+    where
+        T: ToPrimitive + Clone,
+    {
+        self.quantile(0.5)
+    }
+
+    /// Find the `p` quantile of the values, interpolating linearly between
+    /// the two nearest values like [`Stats::quantile`](crate::Stats::quantile)
+    ///
+    /// # Errors
+    /// Returns an error if there are no values other than `NaN`s,
+    /// or if a value can't be converted to [`f64`]
+    ///
+    /// # Panics
+    /// Panics if `p` is not between 0 and 1
+    pub fn quantile(&self, p: f64) -> Result<f64>
+    where
+        T: ToPrimitive + Clone,
+    {
+        assert!((0.0..=1.0).contains(&p), "p must be between 0 and 1");
+        let values = self.as_slice();
+        if values.is_empty() {
+            return Err(StatsError::EmptyCollection);
+        }
+        quantile_by(values.len(), p, |n| item_to_f64(values[n].clone()))
+    }
+
+    /// Returns the empirical distribution function at `x`, which is the
+    /// fraction of the values that are less than or equal to it, or 0 if
+    /// there are no values other than `NaN`s
+    pub fn ecdf(&self, x: &T) -> f64 {
+        if self.is_empty() {
+            return 0.0;
+        }
+        self.count_at_most(x) as f64 / self.len as f64
+    }
+
+    /// Returns the number of values that are less than or equal to `x`
+    pub fn count_at_most(&self, x: &T) -> usize {
+        self.as_slice().partition_point(|value| value <= x)
+    }
+}
+
+/// Returns `true` if `values` are in ascending order, with any `NaN`s at the end
+fn is_sorted<T: PartialOrd>(values: &[T]) -> bool {
+    values.is_sorted_by(|a, b| partial_cmp_nan_last(a, b).is_le())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_order_statistics() {
+        let mut values = [5, 3, 9, 1, 7, 3];
+        let sorted = Sorted::new(&mut values[..]);
+        assert_eq!(sorted.as_slice(), &[1, 3, 3, 5, 7, 9]);
+        assert_eq!(sorted.min(), Ok(1));
+        assert_eq!(sorted.max(), Ok(9));
+        assert_eq!(sorted.median(), Ok(4.0));
+        assert_eq!(sorted.quantile(0.1), Ok(2.0));
+        assert_eq!(sorted.count_at_most(&3), 3);
+        assert_eq!(sorted.ecdf(&0), 0.0);
+        assert_eq!(sorted.ecdf(&9), 1.0);
+    }
+
+    #[test]
+    fn test_infinite_values() {
+        let mut values = [f64::INFINITY, 2.0, 1.0];
+        let sorted = Sorted::new(&mut values[..]);
+        assert_eq!(sorted.median(), Ok(2.0));
+        assert_eq!(sorted.quantile(0.75), Ok(f64::INFINITY));
+    }
+
+    #[test]
+    fn test_only_nans() {
+        let mut values = [f64::NAN, f64::NAN];
+        let sorted = Sorted::new(&mut values[..]);
+        assert!(sorted.is_empty());
+        assert_eq!(sorted.median(), Err(StatsError::EmptyCollection));
+        assert_eq!(sorted.max(), Err(StatsError::EmptyCollection));
+        assert_eq!(sorted.ecdf(&1.0), 0.0);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "the values must be sorted")]
+    fn test_unsorted() {
+        Sorted::from_sorted(&[2.0, 1.0][..]);
+    }
+
+    #[test]
+    fn test_try_from_sorted() {
+        let sorted = Sorted::try_from_sorted(&[1.0, 3.0, f64::NAN][..]).unwrap();
+        assert_eq!(sorted.len(), 2);
+        assert_eq!(
+            Sorted::try_from_sorted(&[2.0, 1.0][..]),
+            Err(StatsError::NotSorted)
+        );
+        assert_eq!(
+            Sorted::try_from_sorted(&[f64::NAN, 1.0][..]),
+            Err(StatsError::NotSorted)
+        );
+    }
+}
@@ -0,0 +1,551 @@
+//! Special functions needed to calculate the distributions of test statistics
+
+use core::f64::consts::PI;
+
+use crate::helpers::Float;
+
+/// Coefficients of the Lanczos approximation with `g = 7` and `n = 9`
+const LANCZOS: [f64; 9] = [
+    0.999_999_999_999_809_9,
+    676.520_368_121_885_1,
+    -1_259.139_216_722_402_8,
+    771.323_428_777_653_1,
+    -176.615_029_162_140_6,
+    12.507_343_278_686_905,
+    -0.138_571_095_265_720_12,
+    9.984_369_578_019_572e-6,
+    1.505_632_735_149_311_6e-7,
+];
+
+/// Returns the natural logarithm of the absolute value of the gamma function
+/// at `x`, using the [Lanczos approximation](<https://en.wikipedia.org/wiki/Lanczos_approximation>),
+/// which is accurate to about 15 significant digits
+pub(crate) fn ln_gamma(x: f64) -> f64 {
+    if x < 0.5 {
+        // the reflection formula Γ(x) Γ(1 - x) = π / sin(πx)
+        return Float::ln((PI / Float::sin(PI * x)).abs()) - ln_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let series = LANCZOS[1..]
+        .iter()
+        .enumerate()
+        .fold(LANCZOS[0], |sum, (i, c)| sum + c / (x + i as f64 + 1.0));
+    0.5 * Float::ln(2.0 * PI) + (x + 0.5) * Float::ln(t) - t + Float::ln(series)
+}
+
+/// Returns the regularized incomplete beta function `I_x(a, b)`, which is
+/// the cumulative distribution function of the beta distribution
+pub(crate) fn incomplete_beta(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    let ln_front =
+        ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * Float::ln(x) + b * Float::ln(1.0 - x);
+    // the continued fraction converges quickly on this side of the mean,
+    // and the symmetry I_x(a, b) = 1 - I_{1 - x}(b, a) handles the other side
+    if x < (a + 1.0) / (a + b + 2.0) {
+        Float::exp(ln_front) * beta_continued_fraction(a, b, x) / a
+    } else {
+        1.0 - Float::exp(ln_front) * beta_continued_fraction(b, a, 1.0 - x) / b
+    }
+}
+
+/// Returns the value that the beta distribution with parameters `a` and `b`
+/// is less than with probability `p`, the inverse of [`incomplete_beta`]
+pub(crate) fn beta_quantile(p: f64, a: f64, b: f64) -> f64 {
+    // bisection is slow, but the distribution function is
+    // increasing, so it can't fail to converge
+    let (mut low, mut high) = (0.0, 1.0);
+    for _ in 0..1100 {
+        let mid = (low + high) / 2.0;
+        if mid <= low || mid >= high {
+            break;
+        }
+        if incomplete_beta(a, b, mid) < p {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    (low + high) / 2.0
+}
+
+/// Evaluates the continued fraction for the incomplete beta function
+/// with the modified Lentz's method
+fn beta_continued_fraction(a: f64, b: f64, x: f64) -> f64 {
+    const TINY: f64 = 1e-300;
+    const EPSILON: f64 = 1e-15;
+    let non_zero = |x: f64| if x.abs() < TINY { TINY } else { x };
+
+    let mut c = 1.0;
+    let mut d = 1.0 / non_zero(1.0 - (a + b) * x / (a + 1.0));
+    let mut fraction = d;
+    for m in 1..=300 {
+        let m = f64::from(m);
+        // the even step of the recurrence
+        let numerator = m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m));
+        d = 1.0 / non_zero(1.0 + numerator * d);
+        c = non_zero(1.0 + numerator / c);
+        fraction *= d * c;
+        // the odd step
+        let numerator = -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0));
+        d = 1.0 / non_zero(1.0 + numerator * d);
+        c = non_zero(1.0 + numerator / c);
+        let delta = d * c;
+        fraction *= delta;
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+    fraction
+}
+
+/// Returns the probability that a value from Student's t distribution
+/// with `df` degrees of freedom is further from 0 than `t`
+pub(crate) fn student_t_two_sided(t: f64, df: f64) -> f64 {
+    incomplete_beta(df / 2.0, 0.5, df / (df + t * t))
+}
+
+/// Returns the value that Student's t distribution with `df` degrees of
+/// freedom is less than with probability `p`
+pub(crate) fn student_t_quantile(p: f64, df: f64) -> f64 {
+    if p == 0.5 {
+        return 0.0;
+    }
+    let tail = p.min(1.0 - p);
+    if tail <= 0.0 {
+        return if p < 0.5 {
+            f64::NEG_INFINITY
+        } else {
+            f64::INFINITY
+        };
+    }
+    let ln_density_scale =
+        ln_gamma((df + 1.0) / 2.0) - ln_gamma(df / 2.0) - 0.5 * Float::ln(df * PI);
+    // The t distribution has heavier tails than the normal distribution, so this
+    // starts below the answer. The tail probability is convex for positive t, so
+    // Newton's method then climbs towards the answer without overshooting.
+    let mut t = -normal_quantile(tail);
+    for _ in 0..200 {
+        let density = Float::exp(ln_density_scale - (df + 1.0) / 2.0 * Float::ln_1p(t * t / df));
+        let step = (student_t_two_sided(t, df) / 2.0 - tail) / density;
+        t += step;
+        if step.abs() <= 1e-15 * t {
+            break;
+        }
+    }
+    if p < 0.5 {
+        -t
+    } else {
+        t
+    }
+}
+
+/// Returns the probability that a value from the binomial distribution
+/// with `n` trials and success probability `p` is at most `k`
+pub(crate) fn binomial_cdf(k: usize, n: usize, p: f64) -> f64 {
+    if k >= n {
+        return 1.0;
+    }
+    incomplete_beta((n - k) as f64, k as f64 + 1.0, 1.0 - p)
+}
+
+/// Returns the probability that a value from the F distribution
+/// with `d1` and `d2` degrees of freedom is greater than `f`
+pub(crate) fn f_upper_tail(f: f64, d1: f64, d2: f64) -> f64 {
+    incomplete_beta(d2 / 2.0, d1 / 2.0, d2 / (d2 + d1 * f))
+}
+
+/// Returns the regularized lower incomplete gamma function `P(a, x)`, which is
+/// the cumulative distribution function of the gamma distribution
+pub(crate) fn lower_incomplete_gamma(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        0.0
+    } else if x < a + 1.0 {
+        gamma_series(a, x)
+    } else {
+        1.0 - gamma_continued_fraction(a, x)
+    }
+}
+
+/// Returns the value that the gamma distribution with shape `a` and scale 1
+/// is less than with probability `p`, the inverse of [`lower_incomplete_gamma`]
+pub(crate) fn gamma_quantile(p: f64, a: f64) -> f64 {
+    if p <= 0.0 {
+        return 0.0;
+    }
+    // the distribution function is increasing, so find an upper bound and bisect
+    let mut high = a.max(1.0);
+    while lower_incomplete_gamma(a, high) < p {
+        high *= 2.0;
+    }
+    let mut low = 0.0;
+    for _ in 0..1100 {
+        let mid = (low + high) / 2.0;
+        if mid <= low || mid >= high {
+            break;
+        }
+        if lower_incomplete_gamma(a, mid) < p {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    (low + high) / 2.0
+}
+
+/// Returns the regularized upper incomplete gamma function `Q(a, x)`, which is
+/// one minus the cumulative distribution function of the gamma distribution,
+/// calculated directly so that small values keep their precision
+pub(crate) fn upper_incomplete_gamma(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        1.0
+    } else if x < a + 1.0 {
+        1.0 - gamma_series(a, x)
+    } else {
+        gamma_continued_fraction(a, x)
+    }
+}
+
+/// Evaluates `P(a, x)` with its power series, which converges quickly when `x < a + 1`
+fn gamma_series(a: f64, x: f64) -> f64 {
+    const EPSILON: f64 = 1e-15;
+    let mut term = 1.0 / a;
+    let mut sum = term;
+    let mut denominator = a;
+    for _ in 0..1000 {
+        denominator += 1.0;
+        term *= x / denominator;
+        sum += term;
+        if term.abs() < sum.abs() * EPSILON {
+            break;
+        }
+    }
+    sum * Float::exp(a * Float::ln(x) - x - ln_gamma(a))
+}
+
+/// Evaluates `Q(a, x)` with its continued fraction and the modified
+/// Lentz's method, which converges quickly when `x >= a + 1`
+fn gamma_continued_fraction(a: f64, x: f64) -> f64 {
+    const TINY: f64 = 1e-300;
+    const EPSILON: f64 = 1e-15;
+    let non_zero = |x: f64| if x.abs() < TINY { TINY } else { x };
+
+    let mut b = x + 1.0 - a;
+    let mut c = 1.0 / TINY;
+    let mut d = 1.0 / b;
+    let mut fraction = d;
+    for i in 1..=1000 {
+        let i = f64::from(i);
+        let numerator = -i * (i - a);
+        b += 2.0;
+        d = 1.0 / non_zero(numerator * d + b);
+        c = non_zero(b + numerator / c);
+        let delta = d * c;
+        fraction *= delta;
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+    fraction * Float::exp(a * Float::ln(x) - x - ln_gamma(a))
+}
+
+/// Returns the cumulative distribution function of the standard normal distribution
+pub(crate) fn normal_cdf(z: f64) -> f64 {
+    let tail = normal_two_sided(z) / 2.0;
+    if z < 0.0 {
+        tail
+    } else {
+        1.0 - tail
+    }
+}
+
+/// Returns the probability that a value from the standard
+/// normal distribution is further from 0 than `z`
+pub(crate) fn normal_two_sided(z: f64) -> f64 {
+    // this is erfc(|z| / √2)
+    upper_incomplete_gamma(0.5, z * z / 2.0)
+}
+
+/// Returns the value that the standard normal distribution is less than with
+/// probability `p`, using the rational approximation by
+/// [Acklam](<https://web.archive.org/web/20151030215612/http://home.online.no/~pjacklam/notes/invnorm/>)
+/// followed by a step of Halley's method to bring it to full precision
+pub(crate) fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969_683_028_665_376e1,
+        2.209_460_984_245_205e2,
+        -2.759_285_104_469_687e2,
+        1.383_577_518_672_69e2,
+        -3.066_479_806_614_716e1,
+        2.506_628_277_459_239,
+    ];
+    const B: [f64; 5] = [
+        -5.447_609_879_822_406e1,
+        1.615_858_368_580_409e2,
+        -1.556_989_798_598_866e2,
+        6.680_131_188_771_972e1,
+        -1.328_068_155_288_572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784_894_002_430_293e-3,
+        -3.223_964_580_411_365e-1,
+        -2.400_758_277_161_838,
+        -2.549_732_539_343_734,
+        4.374_664_141_464_968,
+        2.938_163_982_698_783,
+    ];
+    const D: [f64; 4] = [
+        7.784_695_709_041_462e-3,
+        3.224_671_290_700_398e-1,
+        2.445_134_137_142_996,
+        3.754_408_661_907_416,
+    ];
+    const P_LOW: f64 = 0.024_25;
+    let polynomial =
+        |coefficients: &[f64], x: f64| coefficients.iter().fold(0.0, |sum, c| sum * x + c);
+
+    if p <= 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if p >= 1.0 {
+        return f64::INFINITY;
+    }
+    let tail = |q: f64| {
+        let q = Float::sqrt(-2.0 * Float::ln(q));
+        polynomial(&C, q) / (polynomial(&D, q) * q + 1.0)
+    };
+    let x = if p < P_LOW {
+        tail(p)
+    } else if p <= 1.0 - P_LOW {
+        let q = p - 0.5;
+        let r = q * q;
+        polynomial(&A, r) * q / (polynomial(&B, r) * r + 1.0)
+    } else {
+        -tail(1.0 - p)
+    };
+
+    let error = normal_cdf(x) - p;
+    let u = error * Float::sqrt(2.0 * PI) * Float::exp(x * x / 2.0);
+    x - u / (1.0 + x * u / 2.0)
+}
+
+/// Returns the probability that a value from the
+/// [Kolmogorov distribution](<https://en.wikipedia.org/wiki/Kolmogorov%E2%80%93Smirnov_test#Kolmogorov_distribution>)
+/// is greater than `lambda`, which is the limiting distribution of the
+/// Kolmogorov–Smirnov statistic scaled by the square root of the sample size
+#[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+pub(crate) fn kolmogorov_upper_tail(lambda: f64) -> f64 {
+    // the series converges slowly here, and the probability is 1 to within 1e-22
+    if lambda < 0.15 {
+        return 1.0;
+    }
+    let mut sum = 0.0;
+    let mut sign = 1.0;
+    for k in 1..=100 {
+        let k = f64::from(k);
+        let term = Float::exp(-2.0 * k * k * lambda * lambda);
+        sum += sign * term;
+        sign = -sign;
+        if term < 1e-16 * sum {
+            break;
+        }
+    }
+    (2.0 * sum).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_ln_gamma() {
+        assert_relative_eq!(ln_gamma(1.0), 0.0, epsilon = 1e-14);
+        assert_relative_eq!(ln_gamma(5.0), 24.0_f64.ln(), epsilon = 1e-13);
+        assert_relative_eq!(ln_gamma(0.5), PI.sqrt().ln(), epsilon = 1e-14);
+        assert_relative_eq!(ln_gamma(100.0), 359.134_205_369_575_4, epsilon = 1e-10);
+        // Γ(-0.5) = -2√π
+        assert_relative_eq!(ln_gamma(-0.5), (2.0 * PI.sqrt()).ln(), epsilon = 1e-13);
+    }
+
+    #[test]
+    fn test_incomplete_beta() {
+        assert_eq!(incomplete_beta(2.0, 3.0, 0.0), 0.0);
+        assert_eq!(incomplete_beta(2.0, 3.0, 1.0), 1.0);
+        // I_x(1, 1) = x and I_x(a, 1) = x^a
+        assert_relative_eq!(incomplete_beta(1.0, 1.0, 0.3), 0.3, epsilon = 1e-14);
+        assert_relative_eq!(incomplete_beta(3.0, 1.0, 0.6), 0.216, epsilon = 1e-14);
+        // I_x(2, 2) = 3x² - 2x³
+        assert_relative_eq!(incomplete_beta(2.0, 2.0, 0.8), 0.896, epsilon = 1e-14);
+    }
+
+    #[test]
+    fn test_beta_quantile() {
+        // I_x(a, 1) = x^a
+        assert_relative_eq!(beta_quantile(0.216, 3.0, 1.0), 0.6, epsilon = 1e-14);
+        assert_relative_eq!(beta_quantile(1e-12, 3.0, 1.0), 1e-4, max_relative = 1e-12);
+        assert_relative_eq!(beta_quantile(0.896, 2.0, 2.0), 0.8, epsilon = 1e-14);
+        assert_eq!(beta_quantile(0.0, 2.0, 2.0), 0.0);
+    }
+
+    #[test]
+    fn test_student_t() {
+        // the t distribution with 1 degree of freedom is the Cauchy distribution
+        assert_relative_eq!(student_t_two_sided(1.0, 1.0), 0.5, epsilon = 1e-14);
+        // the familiar critical values for 95% confidence
+        assert_relative_eq!(
+            student_t_two_sided(2.228_138_851_986_273_7, 10.0),
+            0.05,
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(
+            student_t_two_sided(1.983_971_518_523_55, 100.0),
+            0.05,
+            epsilon = 1e-12
+        );
+        assert_eq!(student_t_two_sided(0.0, 5.0), 1.0);
+    }
+
+    #[test]
+    fn test_student_t_quantile() {
+        assert_eq!(student_t_quantile(0.5, 3.0), 0.0);
+        assert_relative_eq!(
+            student_t_quantile(0.975, 10.0),
+            2.228_138_851_986_273_7,
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(
+            student_t_quantile(0.025, 100.0),
+            -1.983_971_518_523_55,
+            epsilon = 1e-12
+        );
+        // the Cauchy distribution has the quantile function tan(π(p - 1/2))
+        assert_relative_eq!(
+            student_t_quantile(1.0 - 1e-9, 1.0),
+            (PI * (0.5 - 1e-9)).tan(),
+            max_relative = 1e-6
+        );
+        assert_relative_eq!(
+            student_t_quantile(0.9, 1.0),
+            (0.4 * PI).tan(),
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn test_binomial_cdf() {
+        assert_relative_eq!(
+            binomial_cdf(10, 30, 0.5),
+            0.049_368_573_352_694_51,
+            epsilon = 1e-14
+        );
+        // P(X <= 1) = (1 - p)^n + n p (1 - p)^(n - 1)
+        assert_relative_eq!(binomial_cdf(1, 4, 0.2), 0.8192, epsilon = 1e-14);
+        assert_eq!(binomial_cdf(4, 4, 0.2), 1.0);
+    }
+
+    #[test]
+    fn test_f_upper_tail() {
+        // with 2 degrees of freedom in the numerator, P(F > f) = (1 + 2f / d2)^(-d2 / 2)
+        for (f, d2) in [(0.5, 3.0), (9.0, 15.0), (40.0, 100.0_f64)] {
+            let expected = (1.0 + 2.0 * f / d2).powf(-d2 / 2.0);
+            assert_relative_eq!(f_upper_tail(f, 2.0, d2), expected, max_relative = 1e-12);
+        }
+        assert_eq!(f_upper_tail(0.0, 4.0, 7.0), 1.0);
+    }
+
+    #[test]
+    fn test_incomplete_gamma() {
+        assert_eq!(upper_incomplete_gamma(2.0, 0.0), 1.0);
+        // Q(1, x) = e^-x, on both sides of the switch between methods
+        for x in [0.5, 1.5, 10.0] {
+            assert_relative_eq!(upper_incomplete_gamma(1.0, x), (-x).exp(), epsilon = 1e-14);
+        }
+        // Q(3, x) = e^-x (1 + x + x²/2)
+        assert_relative_eq!(
+            upper_incomplete_gamma(3.0, 2.5),
+            0.543_813_115_883_329_6,
+            epsilon = 1e-14
+        );
+        assert_relative_eq!(
+            upper_incomplete_gamma(3.0, 40.0),
+            3.572_865_928_700_226e-15,
+            max_relative = 1e-12
+        );
+    }
+
+    #[test]
+    fn test_gamma_quantile() {
+        // P(1, x) = 1 - e^-x
+        assert_relative_eq!(gamma_quantile(0.5, 1.0), 2.0_f64.ln(), epsilon = 1e-14);
+        assert_relative_eq!(
+            lower_incomplete_gamma(1.0, 10.0),
+            1.0 - (-10.0_f64).exp(),
+            epsilon = 1e-14
+        );
+        assert_relative_eq!(
+            gamma_quantile(1e-9, 1.0),
+            -(-1e-9_f64).ln_1p(),
+            max_relative = 1e-9
+        );
+        assert_eq!(gamma_quantile(0.0, 3.0), 0.0);
+    }
+
+    #[test]
+    fn test_normal() {
+        assert_eq!(normal_cdf(0.0), 0.5);
+        assert_relative_eq!(normal_cdf(1.0), 0.841_344_746_068_542_9, epsilon = 1e-15);
+        assert_relative_eq!(
+            normal_cdf(-3.0),
+            0.001_349_898_031_630_095_7,
+            epsilon = 1e-15
+        );
+        assert_relative_eq!(
+            normal_two_sided(1.959_963_984_540_054),
+            0.05,
+            epsilon = 1e-15
+        );
+        assert_relative_eq!(
+            normal_two_sided(-8.0),
+            1.244_192_114_854_363_9e-15,
+            max_relative = 1e-12
+        );
+    }
+
+    #[test]
+    fn test_normal_quantile() {
+        assert_eq!(normal_quantile(0.5), 0.0);
+        assert_relative_eq!(
+            normal_quantile(0.975),
+            1.959_963_984_540_054,
+            epsilon = 1e-14
+        );
+        assert_relative_eq!(
+            normal_quantile(0.01),
+            -2.326_347_874_040_841,
+            epsilon = 1e-14
+        );
+        assert_relative_eq!(
+            normal_quantile(1e-10),
+            -6.361_340_902_404_056,
+            epsilon = 1e-12
+        );
+        assert_eq!(normal_quantile(0.0), f64::NEG_INFINITY);
+        assert_eq!(normal_quantile(1.0), f64::INFINITY);
+    }
+
+    #[test]
+    fn test_kolmogorov() {
+        // the familiar critical values for 95% and 99% confidence
+        assert_relative_eq!(kolmogorov_upper_tail(1.358_098_8), 0.05, epsilon = 1e-7);
+        assert_relative_eq!(kolmogorov_upper_tail(1.627_623_9), 0.01, epsilon = 1e-7);
+        assert_eq!(kolmogorov_upper_tail(0.0), 1.0);
+        assert!(kolmogorov_upper_tail(10.0) < 1e-80);
+    }
+}
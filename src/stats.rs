@@ -1,19 +1,166 @@
-use num_traits::{FromPrimitive, ToPrimitive};
+#[cfg(feature = "alloc")]
+use alloc::collections::BTreeMap;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "alloc")]
+use core::cmp::Ordering;
 
+#[cfg(feature = "num-rational")]
+use num_rational::Ratio;
+#[cfg(feature = "alloc")]
+use num_traits::Unsigned;
+use num_traits::{
+    CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, Float, FromPrimitive, One, PrimInt,
+    SaturatingAdd, ToPrimitive, WrappingAdd, Zero,
+};
+#[cfg(feature = "rand")]
+use rand::Rng;
+
+#[cfg(feature = "alloc")]
+use crate::box_cox;
+use crate::distributions::ExponentialFit;
+#[cfg(feature = "alloc")]
+use crate::distributions::GeneralizedParetoFit;
+#[cfg(feature = "alloc")]
+use crate::distributions::GumbelFit;
+#[cfg(feature = "alloc")]
+use crate::distributions::NormalFit;
+use crate::distributions::PoissonFit;
 use crate::error::DataType;
+#[cfg(feature = "alloc")]
+use crate::helpers::average_ranks;
+#[cfg(feature = "alloc")]
+use crate::helpers::bin_of;
+use crate::helpers::is_nan;
+use crate::helpers::item_to_f64;
+#[cfg(feature = "alloc")]
+use crate::helpers::median_in_place;
+#[cfg(feature = "alloc")]
+use crate::helpers::partial_cmp_nan_last;
+#[cfg(feature = "alloc")]
+use crate::helpers::quantile_in_place;
+#[cfg(feature = "alloc")]
+use crate::helpers::quantile_of_sorted;
+#[cfg(feature = "alloc")]
+use crate::helpers::quantiles_in_place;
+#[cfg(feature = "alloc")]
+use crate::helpers::range_around;
+#[cfg(feature = "alloc")]
+use crate::helpers::ranks;
 use crate::helpers::MinMax;
 use crate::helpers::NumExt;
+use crate::helpers::SquareRoot;
+use crate::helpers::TotalOrder;
+use crate::hypothesis::cohens_d;
+#[cfg(feature = "alloc")]
+use crate::hypothesis::dip;
+#[cfg(feature = "alloc")]
+use crate::normalize::normalize_onto;
+#[cfg(feature = "rand")]
+use crate::sampling;
+#[cfg(feature = "alloc")]
+use crate::special::normal_quantile;
+use crate::special::student_t_quantile;
+use crate::summation::Pairwise;
+use crate::summation::SumStrategy;
+#[cfg(feature = "alloc")]
+use crate::winsorize_in_place;
+#[cfg(feature = "rand")]
+use crate::Bootstrap;
+use crate::ErrorContext;
+use crate::Exceedances;
+use crate::FTest;
+use crate::Filtered;
+#[cfg(feature = "alloc")]
+use crate::FrequencyTable;
+use crate::GrubbsTest;
+#[cfg(feature = "alloc")]
+use crate::Jackknife;
+use crate::LogSummary;
+use crate::OnlineStats;
 use crate::Result;
+use crate::RunStats;
+#[cfg(feature = "alloc")]
+use crate::SevenNumberSummary;
 use crate::StatsError;
+use crate::Strided;
+#[cfg(feature = "alloc")]
+use crate::Summary;
+use crate::SurveyEstimate;
+use crate::TTest;
+use crate::Tail;
+use crate::TestResult;
+#[cfg(feature = "alloc")]
+use crate::TieBreak;
+use crate::ValidityReport;
+use crate::Widen;
+use crate::WithContext;
+
+/// How to round the mean of integers, as used by [`Stats::mean_rounded`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rounding {
+    /// Round down, towards negative infinity
+    Floor,
+    /// Round up, towards positive infinity
+    Ceil,
+    /// Round towards zero, which is what integer division and [`Stats::mean`] do
+    TowardZero,
+    /// Round to the nearest integer, and to the even one when
+    /// exactly halfway, which is also called banker's rounding
+    HalfEven,
+}
+
+/// How tied items are ranked by [`Stats::ranks_with`], as in scipy.
+/// For example, the items 10, 20, 20 and 30 are ranked 1, 2.5, 2.5, 4
+/// with the average, 1, 2, 2, 4 with the minimum, 1, 3, 3, 4 with the
+/// maximum, 1, 2, 2, 3 densely and 1, 2, 3, 4 ordinally.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RankTies {
+    /// Give each tied item the mean of the ranks they span, which keeps
+    /// the sum of the ranks the same, as rank tests need
+    #[default]
+    Average,
+    /// Give each tied item the smallest of the ranks they span, as in
+    /// the rankings of a competition
+    Min,
+    /// Give each tied item the largest of the ranks they span
+    Max,
+    /// Give each tied item the smallest rank, like [`RankTies::Min`], but
+    /// rank the next item one higher, so the ranks have no gaps
+    Dense,
+    /// Give the tied items the ranks they span in the order they are in
+    Ordinal,
+}
+
+/// Which robust measure of spread [`Stats::robust_scaled_with`]
+/// divides the distances of the items from their median by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RobustScale {
+    /// The interquartile range, as in scikit-learn's `RobustScaler`
+    #[default]
+    Iqr,
+    /// The median absolute deviation multiplied by 1.4826, like
+    /// [`Stats::mad_scaled`], so the scaled items are comparable to
+    /// z-scores when they are normally distributed
+    Mad,
+}
 
 /// A trait to be implemented for collection-like types
 /// that provides statistical methods. Requires that the
-/// type it is implemented on can be converted into an
-/// [`Iterator`].
-pub trait Stats: IntoIterator + Clone
-where
-    Self::Item: NumExt,
-{
+/// type it is implemented on can be iterated over by
+/// reference, so the methods borrow the collection
+/// rather than copying it. This includes the fixed-capacity
+/// collections used without an allocator, such as `heapless::Vec`,
+/// `heapless::Deque` and `arrayvec::ArrayVec`.
+pub trait Stats {
+    /// The type of the items in the collection
+    type Item: NumExt;
+
+    /// Returns an iterator over clones of the items in the collection
+    fn items<'a>(&'a self) -> impl Iterator<Item = Self::Item> + 'a
+    where
+        Self::Item: 'a;
+
     /// Calculate the sum of all the items in the collection
     ///
     /// # Example
@@ -27,10 +174,361 @@ where
     /// ```
     #[inline]
     fn sum(&self) -> Self::Item {
-        self.clone().into_iter().sum()
+        self.items().sum()
+    }
+
+    /// Calculate the sum of the items in a wider type than the items, such as
+    /// [`u64`] for [`u8`] or [`f64`] for [`f32`], so it doesn't overflow for
+    /// many small items. See [`Widen`] for the wider types.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let pixels = vec![200u8; 300];
+    /// assert_eq!(pixels.wide_sum(), 60_000u64);
+    /// ```
+    fn wide_sum(&self) -> <Self::Item as Widen>::WideSum
+    where
+        Self::Item: Widen,
+    {
+        self.items().map(Widen::widen).sum()
+    }
+
+    /// Calculate the mean of the items, adding them up in a wider type like
+    /// [`Stats::wide_sum`] and only converting back to the type of the items at
+    /// the end, so it works whenever the mean fits, unlike [`Stats::mean`]
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let pixels = vec![200u8, 250, 240, 230];
+    /// assert_eq!(pixels.wide_mean(), Ok(230));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty, or if the number of
+    /// items doesn't fit in the wider type
+    fn wide_mean(&self) -> Result<Self::Item>
+    where
+        Self::Item: Widen,
+    {
+        let count = <Self::Item as Widen>::WideSum::from_usize(self.non_zero_count()?).ok_or(
+            StatsError::CouldNotConvert {
+                from: DataType::Usize,
+                to: DataType::WideSum,
+            },
+        )?;
+        // the mean is between the smallest and largest items, so it always fits
+        Self::Item::narrow(self.wide_sum() / count).ok_or(StatsError::Overflow)
+    }
+
+    /// Calculate the sum of the items, checking each addition for overflow,
+    /// rather than panicking in debug builds and wrapping around in release
+    /// builds like [`Stats::sum`]
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::{Stats, StatsError};
+    /// assert_eq!(vec![100u8, 100].checked_sum(), Ok(200));
+    /// assert_eq!(vec![100u8, 100, 100].checked_sum(), Err(StatsError::Overflow));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `StatsError::Overflow` if the sum overflows
+    fn checked_sum(&self) -> Result<Self::Item>
+    where
+        Self::Item: CheckedAdd,
+    {
+        self.items()
+            .try_fold(Self::Item::zero(), |sum, x| sum.checked_add(&x))
+            .ok_or(StatsError::Overflow)
+    }
+
+    /// Calculate the mean of the items like [`Stats::mean`],
+    /// but checking the sum for overflow like [`Stats::checked_sum`]
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::{Stats, StatsError};
+    /// assert_eq!(vec![100i8, 20].checked_mean(), Ok(60));
+    /// assert_eq!(vec![100i8, 100].checked_mean(), Err(StatsError::Overflow));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `StatsError::Overflow` if the sum overflows, and otherwise
+    /// errors under the same conditions as [`Stats::mean`]
+    fn checked_mean(&self) -> Result<Self::Item>
+    where
+        Self::Item: CheckedAdd + CheckedDiv,
+    {
+        let count = self.non_zero_count_into_item()?;
+        self.checked_sum()?
+            .checked_div(&count)
+            .ok_or(StatsError::Overflow)
+    }
+
+    /// Calculate the (population) variance of the items like [`Stats::variance`],
+    /// but checking every operation for overflow, including the squares of the
+    /// differences from the mean and their sum. For unsigned types, an item
+    /// smaller than the mean makes its difference from the mean overflow.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::{Stats, StatsError};
+    /// assert_eq!(vec![1i16, 2, 3, 10].checked_variance(), Ok(12));
+    /// assert_eq!(vec![0i16, 400].checked_variance(), Err(StatsError::Overflow));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `StatsError::Overflow` if an operation overflows, and otherwise
+    /// errors under the same conditions as [`Stats::variance`]
+    fn checked_variance(&self) -> Result<Self::Item>
+    where
+        Self::Item: CheckedAdd + CheckedSub + CheckedMul + CheckedDiv,
+    {
+        let count = self.non_zero_count_into_item()?;
+        let mean = self.checked_mean()?;
+        self.items()
+            .try_fold(Self::Item::zero(), |sum, x| {
+                let difference = x.checked_sub(&mean)?;
+                sum.checked_add(&difference.checked_mul(&difference)?)
+            })
+            .and_then(|squares| squares.checked_div(&count))
+            .ok_or(StatsError::Overflow)
+    }
+
+    /// Calculate the sum of the items, wrapping around at the bounds of the
+    /// type on overflow in debug and release builds alike, which is the sum
+    /// modulo 2 to the power of the number of bits, for modular arithmetic
+    /// and counters that are expected to wrap
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// assert_eq!(vec![200u8, 100].wrapping_sum(), 44);
+    /// assert_eq!(vec![i8::MAX, 1].wrapping_sum(), i8::MIN);
+    /// ```
+    fn wrapping_sum(&self) -> Self::Item
+    where
+        Self::Item: WrappingAdd,
+    {
+        self.items()
+            .fold(Self::Item::zero(), |sum, x| sum.wrapping_add(&x))
+    }
+
+    /// Calculate the mean of the items like [`Stats::mean`], but with the
+    /// sum wrapping around like [`Stats::wrapping_sum`]
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// assert_eq!(vec![200u8, 100].wrapping_mean(), Ok(22));
+    /// ```
+    ///
+    /// # Errors
+    /// Errors under the same conditions as [`Stats::non_zero_count_into_item`]
+    fn wrapping_mean(&self) -> Result<Self::Item>
+    where
+        Self::Item: WrappingAdd,
+    {
+        let count = self.non_zero_count_into_item()?;
+        Ok(self.wrapping_sum() / count)
+    }
+
+    /// Calculate the sum of the items, stopping at the bounds of the type
+    /// rather than overflowing. The sum is clamped as it goes, so items of
+    /// signed types after the sum saturates can bring it back down.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// assert_eq!(vec![200u8, 100].saturating_sum(), u8::MAX);
+    /// assert_eq!(vec![100i8, 100, -50].saturating_sum(), 77);
+    /// ```
+    fn saturating_sum(&self) -> Self::Item
+    where
+        Self::Item: SaturatingAdd,
+    {
+        self.items()
+            .fold(Self::Item::zero(), |sum, x| sum.saturating_add(&x))
+    }
+
+    /// Calculate the mean of the items like [`Stats::mean`], but with the
+    /// sum saturating like [`Stats::saturating_sum`]
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// assert_eq!(vec![200u8, 100].saturating_mean(), Ok(127));
+    /// ```
+    ///
+    /// # Errors
+    /// Errors under the same conditions as [`Stats::non_zero_count_into_item`]
+    fn saturating_mean(&self) -> Result<Self::Item>
+    where
+        Self::Item: SaturatingAdd,
+    {
+        let count = self.non_zero_count_into_item()?;
+        Ok(self.saturating_sum() / count)
+    }
+
+    /// Calculate the mean of integer items exactly, as a fraction in lowest
+    /// terms, so there is no rounding at all, unlike [`Stats::mean`] which
+    /// rounds integers towards zero, or [`Stats::mean_with`] which has float error.
+    ///
+    /// # Examples
+    /// ```
+    /// use num_rational::Ratio;
+    /// use stats_traits::Stats;
+    /// assert_eq!(vec![1, 2, 4, 5].mean_exact(), Ok(Ratio::new(3, 1)));
+    /// assert_eq!(vec![1, 2, 4, 6].mean_exact(), Ok(Ratio::new(13, 4)));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty, if an item doesn't fit in
+    /// an [`i64`], or `StatsError::Overflow` if the result doesn't
+    #[cfg(feature = "num-rational")]
+    fn mean_exact(&self) -> Result<Ratio<i64>>
+    where
+        Self::Item: PrimInt,
+    {
+        let (count, sum, _) = exact_sums(self)?;
+        narrow_ratio(Ratio::new(sum, count))
+    }
+
+    /// Calculate the (population) variance of integer items exactly, as a
+    /// fraction in lowest terms. See [`Stats::mean_exact`].
+    ///
+    /// # Examples
+    /// ```
+    /// use num_rational::Ratio;
+    /// use stats_traits::Stats;
+    /// // the float variance is 0.6666666666666666
+    /// assert_eq!(vec![1, 2, 3].variance_exact(), Ok(Ratio::new(2, 3)));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty, if an item doesn't fit in
+    /// an [`i64`], or `StatsError::Overflow` if the sum of the squares of the
+    /// items doesn't fit in an [`i128`] or the result doesn't fit in an [`i64`]
+    #[cfg(feature = "num-rational")]
+    fn variance_exact(&self) -> Result<Ratio<i64>>
+    where
+        Self::Item: PrimInt,
+    {
+        // the variance is (n Σx² - (Σx)²) / n², which only needs whole numbers
+        let (count, sum, squares) = exact_sums(self)?;
+        let numerator = count
+            .checked_mul(squares)
+            .and_then(|n_squares| n_squares.checked_sub(sum.checked_mul(sum)?))
+            .ok_or(StatsError::Overflow)?;
+        narrow_ratio(Ratio::new(numerator, count * count))
+    }
+
+    /// Calculate the sum of the items converted to [`f64`] with
+    /// [pairwise summation](<https://en.wikipedia.org/wiki/Pairwise_summation>),
+    /// so the rounding error grows with the logarithm of the number of items
+    /// rather than in proportion to it, as it does for [`Stats::sum`], which adds
+    /// them one after another. It is nearly as fast, and doesn't allocate.
+    /// This is [`Stats::sum_with`] with [`Pairwise`].
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let tenths = vec![0.1_f64; 1_000_000];
+    /// assert!((tenths.sum() - 100_000.0).abs() > 1e-6);
+    /// assert!((tenths.pairwise_sum().unwrap() - 100_000.0).abs() < 1e-8);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if an item can't be converted to [`f64`]
+    fn pairwise_sum(&self) -> Result<f64>
+    where
+        Self::Item: ToPrimitive,
+    {
+        self.sum_with::<Pairwise>()
+    }
+
+    /// Calculate the sum of the items converted to [`f64`], adding them up with
+    /// the [`SumStrategy`] `S`, to choose between speed and accuracy. See the
+    /// [`summation`](crate::summation) module for the strategies.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::summation::Kahan;
+    /// use stats_traits::Stats;
+    /// let values = vec![1.0, 1e100, 1.0, -1e100];
+    /// assert_eq!(values.sum(), 0.0);
+    /// assert_eq!(values.sum_with::<Kahan>(), Ok(2.0));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if an item can't be converted to [`f64`]
+    fn sum_with<S>(&self) -> Result<f64>
+    where
+        Self::Item: ToPrimitive,
+        S: SumStrategy,
+    {
+        let mut sum = S::default();
+        for x in self.items() {
+            sum.add(item_to_f64(x)?);
+        }
+        Ok(sum.sum())
+    }
+
+    /// Calculate the mean of the items converted to [`f64`],
+    /// adding them up with the [`SumStrategy`] `S`
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::summation::Kahan;
+    /// use stats_traits::Stats;
+    /// let values = vec![1e16, 1.0, 1.0, 1.0, 1.0];
+    /// assert_eq!(values.mean_with::<Kahan>(), Ok(2e15 + 0.8));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty, or if an item can't be converted to [`f64`]
+    fn mean_with<S>(&self) -> Result<f64>
+    where
+        Self::Item: ToPrimitive,
+        S: SumStrategy,
+    {
+        let count = self.non_zero_count()?;
+        Ok(self.sum_with::<S>()? / count as f64)
+    }
+
+    /// Calculate the (population) variance of the items converted to [`f64`],
+    /// adding up the items and then the squares of their differences from the
+    /// mean with the [`SumStrategy`] `S`. See [`Stats::variance`].
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::summation::Pairwise;
+    /// use stats_traits::Stats;
+    /// assert_eq!(vec![1, 2, 3, 4].variance_with::<Pairwise>(), Ok(1.25));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty, or if an item can't be converted to [`f64`]
+    fn variance_with<S>(&self) -> Result<f64>
+    where
+        Self::Item: ToPrimitive,
+        S: SumStrategy,
+    {
+        let count = self.non_zero_count()? as f64;
+        let mean = self.sum_with::<S>()? / count;
+        let mut squares = S::default();
+        for x in self.items() {
+            let difference = item_to_f64(x)? - mean;
+            squares.add(difference * difference);
+        }
+        Ok(squares.sum() / count)
     }
 
-    /// Count the items in the collection
+    /// Count the items in the collection. When the iterator over the items knows
+    /// exactly how many there are, as it does for slices, `Vec`s, arrays and
+    /// `VecDeque`s, this takes constant time rather than going through them.
     ///
     /// # Example
     /// ```
@@ -39,7 +537,42 @@ where
     /// ```
     #[inline]
     fn count(&self) -> usize {
-        self.clone().into_iter().count()
+        let items = self.items();
+        match items.size_hint() {
+            (lower, Some(upper)) if lower == upper => lower,
+            _ => items.count(),
+        }
+    }
+
+    /// Count how many times `value` occurs in the collection.
+    /// Items are compared using [`PartialEq`], except that items that
+    /// aren't equal to themselves, such as `NaN`, count as the same value.
+    ///
+    /// # Example
+    /// ```
+    /// use stats_traits::Stats;
+    /// assert_eq!(vec![1, 2, 2, 3].count_of(2), 2);
+    /// assert_eq!(vec![1, 2, 2, 3].count_of(4), 0);
+    /// assert_eq!(vec![f64::NAN, 1.0, f64::NAN].count_of(f64::NAN), 2);
+    /// ```
+    fn count_of(&self, value: Self::Item) -> usize {
+        self.items()
+            .filter(|x| *x == value || (is_nan(x) && is_nan(&value)))
+            .count()
+    }
+
+    /// Count the items for which `predicate` returns `true`
+    ///
+    /// # Example
+    /// ```
+    /// use stats_traits::Stats;
+    /// assert_eq!(vec![1, 5, 2, 8].count_if(|&x| x > 2), 2);
+    /// ```
+    fn count_if<F>(&self, predicate: F) -> usize
+    where
+        F: FnMut(&Self::Item) -> bool,
+    {
+        self.items().filter(predicate).count()
     }
 
     /// Count the items in the collection, returning
@@ -58,7 +591,7 @@ where
     /// under the same conditions as [`Stats::non_zero_count`],
     /// or if the length could not be converted to [`Self::Item`]
     ///
-    /// [`Self::Item`]: IntoIterator::Item
+    /// [`Self::Item`]: Stats::Item
     fn non_zero_count_into_item(&self) -> Result<Self::Item> {
         Self::Item::from_usize(self.non_zero_count()?).ok_or(StatsError::CouldNotConvert {
             from: DataType::Usize,
@@ -87,9 +620,81 @@ where
     /// # Errors
     /// Returns an error if the collection is empty (has a length of 0).
     /// Will also return an error if the length of the collection is too large
-    /// to fit in [`Self::Item`](IntoIterator::Item).
+    /// to fit in [`Self::Item`](Stats::Item).
     fn mean(&self) -> Result<Self::Item> {
-        Ok(self.sum() / self.non_zero_count_into_item()?)
+        let (sum, count) = sum_and_count(self.items())?;
+        Ok(sum / count)
+    }
+
+    /// Find the mean of integer items exactly, as a quotient and a remainder:
+    /// the mean is the quotient plus the remainder divided by the number of
+    /// items. The quotient is rounded down and the remainder is between 0 and
+    /// the number of items, so nothing is lost, unlike with [`Stats::mean`].
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// // the mean is 7 / 3
+    /// assert_eq!(vec![1, 2, 4].mean_div_rem(), Ok((2, 1)));
+    /// // the mean is -7 / 3, which is -3 + 2 / 3
+    /// assert_eq!(vec![-1, -2, -4].mean_div_rem(), Ok((-3, 2)));
+    /// ```
+    ///
+    /// # Errors
+    /// Errors under the same conditions as [`Stats::mean`]
+    fn mean_div_rem(&self) -> Result<(Self::Item, Self::Item)>
+    where
+        Self::Item: PrimInt,
+    {
+        let (sum, count) = sum_and_count(self.items())?;
+        Ok(floor_div_rem(sum, count))
+    }
+
+    /// Find the mean of integer items, rounded to an integer with `rounding`
+    /// rather than always towards zero like [`Stats::mean`]
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::{Rounding, Stats};
+    /// let vec = vec![1, 2, 4, 5];
+    /// assert_eq!(vec.mean(), Ok(3));
+    /// assert_eq!(vec.mean_rounded(Rounding::Ceil), Ok(3));
+    /// let vec = vec![1, 2, 4, 6];
+    /// assert_eq!(vec.mean_rounded(Rounding::Floor), Ok(3));
+    /// assert_eq!(vec.mean_rounded(Rounding::Ceil), Ok(4));
+    /// // 3.25 rounds to 3, and 2.5 rounds to the even 2
+    /// assert_eq!(vec.mean_rounded(Rounding::HalfEven), Ok(3));
+    /// assert_eq!(vec![2, 3].mean_rounded(Rounding::HalfEven), Ok(2));
+    /// ```
+    ///
+    /// # Errors
+    /// Errors under the same conditions as [`Stats::mean`]
+    fn mean_rounded(&self, rounding: Rounding) -> Result<Self::Item>
+    where
+        Self::Item: PrimInt,
+    {
+        let (sum, count) = sum_and_count(self.items())?;
+        let (floor, remainder) = floor_div_rem(sum, count);
+        let zero = Self::Item::zero();
+        if remainder == zero {
+            return Ok(floor);
+        }
+        let round_up = match rounding {
+            Rounding::Floor => false,
+            Rounding::Ceil => true,
+            Rounding::TowardZero => floor < zero,
+            Rounding::HalfEven => {
+                let above_half = count - remainder;
+                remainder > above_half
+                    || (remainder == above_half
+                        && floor % (Self::Item::one() + Self::Item::one()) != zero)
+            }
+        };
+        Ok(if round_up {
+            floor + Self::Item::one()
+        } else {
+            floor
+        })
     }
 
     /// Find the variance of the collection.
@@ -109,18 +714,15 @@ where
     ///
     /// [Wikipedia](<https://en.wikipedia.org/wiki/Variance>)
     fn variance(&self) -> Result<Self::Item> {
-        let mean = self.mean()?;
-        Ok(self
-            .clone()
-            .into_iter()
-            .map(|x| (x - mean) * (x - mean))
-            .sum::<Self::Item>()
-            / self.non_zero_count_into_item()?)
+        let (sum, count) = sum_and_count(self.items())?;
+        let mean = sum / count.clone();
+        Ok(sum_of_squares(self.items(), &mean) / count)
     }
 
     /// Find the standard deviation of the collection.
     /// The standard deviation is the square root of the variance.
-    /// It is a measure of how spread out the items are.
+    /// It is a measure of how spread out the items are. The square root is
+    /// calculated as an [`f64`]; [`Stats::float_std_dev`] avoids this for floats.
     ///
     /// [Wikipedia](<https://en.wikipedia.org/wiki/Standard_deviation>)
     ///
@@ -137,81 +739,4107 @@ where
     where
         Self::Item: ToPrimitive,
     {
-        Self::Item::from_f64(match self.variance()?.to_f64() {
-            Some(x) => x.sqrt(),
-            None => {
-                return Err(StatsError::CouldNotConvert {
-                    from: DataType::Item,
-                    to: DataType::F64,
-                })
-            }
-        })
-        .ok_or(StatsError::CouldNotConvert {
-            from: DataType::F64,
-            to: DataType::Item,
-        })
+        sqrt_of(self.variance()?)
     }
 
-    /// Return the smallest item in the collection
-    fn min(&self) -> Result<Self::Item>
+    /// Find the standard deviation of float items like [`Stats::std_dev`],
+    /// but calculating the square root in the type of the items rather than
+    /// converting to and from [`f64`]. For [`f32`] items, this does no [`f64`]
+    /// arithmetic at all, which is much faster on targets like the Cortex-M4F
+    /// that only have hardware support for [`f32`]. [`Stats::root_std_dev`]
+    /// does the same for any type that implements [`SquareRoot`].
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let vec: Vec<f32> = vec![2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+    /// assert_eq!(vec.float_std_dev(), Ok(2.0));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error under the same conditions as [`Stats::variance`]
+    fn float_std_dev(&self) -> Result<Self::Item>
     where
-        Self::Item: MinMax,
+        Self::Item: Float,
     {
-        self.clone()
-            .into_iter()
-            .reduce(Self::Item::min)
-            .ok_or(StatsError::EmptyCollection)
+        Ok(self.variance()?.sqrt())
     }
 
-    /// Return the largest item in the collection
-    fn max(&self) -> Result<Self::Item>
+    /// Find the standard deviation of the collection like [`Stats::std_dev`],
+    /// but calculating the square root in the type of the items with
+    /// [`SquareRoot`] rather than converting to and from [`f64`], so the
+    /// result keeps all the precision of types like decimals, and integers
+    /// never touch floats at all. The square root of integers is rounded down.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let vec: Vec<i32> = vec![2, 4, 4, 4, 5, 5, 7, 9];
+    /// assert_eq!(vec.root_std_dev(), Ok(2));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error under the same conditions as [`Stats::variance`],
+    /// or `StatsError::Overflow` if the square root can't be represented
+    /// in the type of the items
+    fn root_std_dev(&self) -> Result<Self::Item>
     where
-        Self::Item: MinMax,
+        Self::Item: SquareRoot,
     {
-        self.clone()
-            .into_iter()
-            .reduce(Self::Item::max)
-            .ok_or(StatsError::EmptyCollection)
+        self.variance()?.square_root().ok_or(StatsError::Overflow)
     }
 
-    /// Return the range of the collection
-    /// (the smallest subtracted from the largest)
-    #[inline]
-    fn range(&self) -> Result<Self::Item>
-    where
+    /// Find the variance of the collection with `ddof` delta degrees of freedom,
+    /// which is the sum of the squared differences between each item and the
+    /// mean, divided by the number of items minus `ddof`. A `ddof` of 0 gives
+    /// the population variance, the same as [`Stats::variance`], and a `ddof`
+    /// of 1 gives the sample variance, with
+    /// [Bessel's correction](<https://en.wikipedia.org/wiki/Bessel%27s_correction>).
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::{Stats, StatsError};
+    /// let vec = vec![1.0, 2.0, 3.0, 4.0];
+    /// assert_eq!(vec.variance_ddof(0), vec.variance());
+    /// assert_eq!(vec.variance_ddof(1), Ok(5.0 / 3.0));
+    /// assert_eq!(
+    ///     vec.variance_ddof(4),
+    ///     Err(StatsError::InsufficientData { required: 5, actual: 4 })
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `StatsError::InsufficientData` if there are `ddof` items or fewer,
+    /// and otherwise errors under the same conditions as [`Stats::variance`]
+    fn variance_ddof(&self, ddof: usize) -> Result<Self::Item> {
+        let (sum, actual) = sum_and_len(self.items())?;
+        if actual <= ddof {
+            return Err(StatsError::InsufficientData {
+                required: ddof + 1,
+                actual,
+            });
+        }
+        let to_item = |n| {
+            Self::Item::from_usize(n).ok_or(StatsError::CouldNotConvert {
+                from: DataType::Usize,
+                to: DataType::Item,
+            })
+        };
+        let divisor = to_item(actual - ddof)?;
+        let mean = sum / to_item(actual)?;
+        Ok(sum_of_squares(self.items(), &mean) / divisor)
+    }
+
+    /// Find the standard deviation of the collection with `ddof` delta degrees
+    /// of freedom, which is the square root of [`Stats::variance_ddof`]
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let vec = vec![2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+    /// assert_eq!(vec.std_dev_ddof(0), Ok(2.0));
+    /// assert_eq!(vec.std_dev_ddof(1), Ok((32.0_f64 / 7.0).sqrt()));
+    /// ```
+    ///
+    /// # Errors
+    /// Errors under the same conditions as [`Stats::variance_ddof`]
+    /// and [`Stats::std_dev`]
+    fn std_dev_ddof(&self, ddof: usize) -> Result<Self::Item>
+    where
+        Self::Item: ToPrimitive,
+    {
+        sqrt_of(self.variance_ddof(ddof)?)
+    }
+
+    /// Return the smallest item in the collection
+    fn min(&self) -> Result<Self::Item>
+    where
+        Self::Item: MinMax,
+    {
+        self.items()
+            .reduce(Self::Item::min)
+            .ok_or(StatsError::EmptyCollection)
+    }
+
+    /// Return the largest item in the collection
+    fn max(&self) -> Result<Self::Item>
+    where
+        Self::Item: MinMax,
+    {
+        self.items()
+            .reduce(Self::Item::max)
+            .ok_or(StatsError::EmptyCollection)
+    }
+
+    /// Return the range of the collection
+    /// (the smallest subtracted from the largest)
+    #[inline]
+    fn range(&self) -> Result<Self::Item>
+    where
         Self::Item: MinMax,
     {
         Ok(self.max()? - self.min()?)
     }
-}
 
-/// Blanket implementation for all types that implement [`IntoIterator`] and [`Copy`].
-/// This allows us to use the methods on any type that implements those traits.
-/// For example, we can use the methods on `Vec` and `&[i32]`.
-impl<T> Stats for T
-where
-    T: IntoIterator + Clone,
-    T::Item: NumExt,
-{
-}
+    /// Calculate the sum of the items like [`Stats::sum`], skipping `NaN`s,
+    /// which is zero if every item is `NaN`
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let vec = vec![1.0, f64::NAN, 2.0];
+    /// assert!(vec.sum().is_nan());
+    /// assert_eq!(vec.nan_sum(), 3.0);
+    /// ```
+    fn nan_sum(&self) -> Self::Item {
+        self.items().filter(|x| !is_nan(x)).sum()
+    }
+
+    /// Calculate the mean of the items like [`Stats::mean`], skipping `NaN`s
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// assert_eq!(vec![1.0, f64::NAN, 2.0].nan_mean(), Ok(1.5));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if every item is `NaN`, and otherwise
+    /// errors under the same conditions as [`Stats::mean`]
+    fn nan_mean(&self) -> Result<Self::Item> {
+        let (sum, count) = sum_and_count(self.items().filter(|x| !is_nan(x)))?;
+        Ok(sum / count)
+    }
+
+    /// Calculate the (population) variance of the items like
+    /// [`Stats::variance`], skipping `NaN`s
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// assert_eq!(vec![1.0, f64::NAN, 3.0].nan_variance(), Ok(1.0));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if every item is `NaN`, and otherwise
+    /// errors under the same conditions as [`Stats::variance`]
+    fn nan_variance(&self) -> Result<Self::Item> {
+        let (sum, count) = sum_and_count(self.items().filter(|x| !is_nan(x)))?;
+        let mean = sum / count.clone();
+        Ok(sum_of_squares(self.items().filter(|x| !is_nan(x)), &mean) / count)
+    }
+
+    /// Calculate the sum of the items for which `predicate` returns `true`,
+    /// without collecting them first, which is zero if there are none
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let amounts = vec![120, -40, 75, -15];
+    /// assert_eq!(amounts.sum_if(|&x| x > 0), 195);
+    /// assert_eq!(amounts.sum_if(|&x| x > 1000), 0);
+    /// ```
+    fn sum_if<F>(&self, predicate: F) -> Self::Item
+    where
+        F: FnMut(&Self::Item) -> bool,
+    {
+        self.items().filter(predicate).sum()
+    }
+
+    /// Calculate the mean of the items for which `predicate` returns `true`
+    /// like [`Stats::mean`], in one pass without collecting them first
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let latencies = vec![12.0, 950.0, 15.0, 1200.0, 13.0];
+    /// assert_eq!(latencies.mean_if(|&x| x < 100.0), Ok(40.0 / 3.0));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if `predicate` returns `false` for every item, and
+    /// otherwise errors under the same conditions as [`Stats::mean`]
+    fn mean_if<F>(&self, predicate: F) -> Result<Self::Item>
+    where
+        F: FnMut(&Self::Item) -> bool,
+    {
+        let (sum, count) = sum_and_count(self.items().filter(predicate))?;
+        Ok(sum / count)
+    }
+
+    /// Calculate the (population) variance of the items for which `predicate`
+    /// returns `true` like [`Stats::variance`], without collecting them first.
+    /// `predicate` is called twice for each item, so it should give the same
+    /// answer both times.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let readings = vec![1.0, -999.0, 3.0, -999.0];
+    /// assert_eq!(readings.variance_if(|&x| x != -999.0), Ok(1.0));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if `predicate` returns `false` for every item, and
+    /// otherwise errors under the same conditions as [`Stats::variance`]
+    fn variance_if<F>(&self, mut predicate: F) -> Result<Self::Item>
+    where
+        F: FnMut(&Self::Item) -> bool,
+    {
+        let (sum, count) = sum_and_count(self.items().filter(&mut predicate))?;
+        let mean = sum / count.clone();
+        Ok(sum_of_squares(self.items().filter(predicate), &mean) / count)
+    }
+
+    /// Returns a view of the items for which `predicate` returns `true`,
+    /// which implements [`Stats`] itself, so every statistic can be
+    /// calculated of them lazily, without copying them into a new collection
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let sales = vec![120.0, 80.0, 300.0, 100.0, 260.0];
+    /// let busy = sales.filtered(|&x| x > 100.0);
+    /// assert_eq!(busy.count(), 3);
+    /// assert_eq!(busy.mean(), Ok(680.0 / 3.0));
+    /// assert_eq!(Stats::min(&busy), Ok(120.0));
+    /// ```
+    fn filtered<F>(&self, predicate: F) -> Filtered<'_, Self, F>
+    where
+        F: Fn(&Self::Item) -> bool,
+    {
+        Filtered::new(self, predicate)
+    }
+
+    /// Returns a view of every `step`th item, starting from the one at
+    /// `offset`, which implements [`Stats`] itself. With `step` set to the
+    /// number of channels of interleaved data, and `offset` to one of them,
+    /// every statistic can be calculated of that channel lazily, without
+    /// copying it into a new collection.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// // left and right samples of stereo audio, one after the other
+    /// let samples = vec![0.5, -0.1, 0.25, -0.2, -0.75, -0.3];
+    /// let left = samples.strided(0, 2);
+    /// assert_eq!(left.count(), 3);
+    /// assert_eq!(Stats::min(&left), Ok(-0.75));
+    /// let right = samples.strided(1, 2);
+    /// assert_eq!(Stats::max(&right), Ok(-0.1));
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `step` is 0
+    fn strided(&self, offset: usize, step: usize) -> Strided<'_, Self> {
+        Strided::new(self, offset, step)
+    }
+
+    /// Count the `NaN`s, infinities, zeros and negative items in one pass,
+    /// as a cheap check of the data before trusting its statistics. Items
+    /// that can't be converted to [`f64`] are counted as `NaN`s.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let readings = vec![4.2, f64::NAN, -1.0, 0.0, f64::INFINITY];
+    /// let report = readings.validity_report();
+    /// assert_eq!(report.nan, 1);
+    /// assert_eq!(report.positive_infinity, 1);
+    /// assert_eq!(report.negative, 1);
+    /// assert!(!report.is_finite());
+    /// assert!(vec![1, 2, 3].validity_report().is_finite());
+    /// ```
+    fn validity_report(&self) -> ValidityReport
+    where
+        Self::Item: ToPrimitive,
+    {
+        self.items()
+            .map(|x| x.to_f64().unwrap_or(f64::NAN))
+            .collect()
+    }
+
+    /// Return the smallest item in the collection, skipping `NaN`s
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty or every item is `NaN`
+    fn nan_min(&self) -> Result<Self::Item>
+    where
+        Self::Item: MinMax,
+    {
+        self.items()
+            .filter(|x| !is_nan(x))
+            .reduce(Self::Item::min)
+            .ok_or(StatsError::EmptyCollection)
+    }
+
+    /// Return the largest item in the collection, skipping `NaN`s
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty or every item is `NaN`
+    fn nan_max(&self) -> Result<Self::Item>
+    where
+        Self::Item: MinMax,
+    {
+        self.items()
+            .filter(|x| !is_nan(x))
+            .reduce(Self::Item::max)
+            .ok_or(StatsError::EmptyCollection)
+    }
+
+    /// Calculate the sum of the items like [`Stats::sum`], but
+    /// returning an error rather than `NaN` if an item is `NaN`
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::{Stats, StatsError};
+    /// assert_eq!(vec![1.0, 2.0].strict_sum(), Ok(3.0));
+    /// assert_eq!(vec![1.0, f64::NAN].strict_sum(), Err(StatsError::NaNEncountered));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `StatsError::NaNEncountered` if an item is `NaN`
+    fn strict_sum(&self) -> Result<Self::Item> {
+        Ok(self.strict_sum_with_context()?)
+    }
+
+    /// Calculate the sum of the items like [`Stats::strict_sum`], but with
+    /// the name of the statistic and the index of the `NaN` in the error
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let error = vec![1.0, 2.0, f64::NAN].strict_sum_with_context().unwrap_err();
+    /// assert_eq!(error.index, Some(2));
+    /// assert_eq!(
+    ///     error.to_string(),
+    ///     "could not calculate the sum: an item is NaN (caused by the item at index 2)"
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    /// Errors under the same conditions as [`Stats::strict_sum`]
+    fn strict_sum_with_context(&self) -> core::result::Result<Self::Item, ErrorContext> {
+        let mut sum = Self::Item::zero();
+        for (index, x) in self.items().enumerate() {
+            if is_nan(&x) {
+                return Err(ErrorContext::new("sum", StatsError::NaNEncountered).at(index));
+            }
+            sum = sum + x;
+        }
+        Ok(sum)
+    }
+
+    /// Calculate the mean of the items like [`Stats::mean`], but
+    /// returning an error rather than `NaN` if an item is `NaN`
+    ///
+    /// # Errors
+    /// Returns `StatsError::NaNEncountered` if an item is `NaN`, and
+    /// otherwise errors under the same conditions as [`Stats::mean`]
+    fn strict_mean(&self) -> Result<Self::Item> {
+        Ok(self.strict_mean_with_context()?)
+    }
+
+    /// Calculate the mean of the items like [`Stats::strict_mean`], but with
+    /// the name of the statistic in the error, and the index of the `NaN`
+    /// if that is what caused it
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let error = vec![1.0, f64::NAN, 4.0].strict_mean_with_context().unwrap_err();
+    /// assert_eq!((error.statistic, error.index), ("mean", Some(1)));
+    /// let error = Vec::<f64>::new().strict_mean_with_context().unwrap_err();
+    /// assert_eq!((error.statistic, error.index), ("mean", None));
+    /// ```
+    ///
+    /// # Errors
+    /// Errors under the same conditions as [`Stats::strict_mean`]
+    fn strict_mean_with_context(&self) -> core::result::Result<Self::Item, ErrorContext> {
+        reject_nan(self, "mean")?;
+        self.mean().context("mean")
+    }
+
+    /// Calculate the (population) variance of the items like [`Stats::variance`],
+    /// but returning an error rather than `NaN` if an item is `NaN`
+    ///
+    /// # Errors
+    /// Returns `StatsError::NaNEncountered` if an item is `NaN`, and
+    /// otherwise errors under the same conditions as [`Stats::variance`]
+    fn strict_variance(&self) -> Result<Self::Item> {
+        Ok(self.strict_variance_with_context()?)
+    }
+
+    /// Calculate the variance of the items like [`Stats::strict_variance`],
+    /// but with the name of the statistic in the error, and the index of
+    /// the `NaN` if that is what caused it. See [`Stats::strict_mean_with_context`].
+    ///
+    /// # Errors
+    /// Errors under the same conditions as [`Stats::strict_variance`]
+    fn strict_variance_with_context(&self) -> core::result::Result<Self::Item, ErrorContext> {
+        reject_nan(self, "variance")?;
+        self.variance().context("variance")
+    }
+
+    /// Return the smallest item in the collection like [`Stats::min`],
+    /// but returning an error rather than ignoring `NaN`s
+    ///
+    /// # Errors
+    /// Returns `StatsError::NaNEncountered` if an item is `NaN`,
+    /// or an error if the collection is empty
+    fn strict_min(&self) -> Result<Self::Item>
+    where
+        Self::Item: MinMax,
+    {
+        reject_nan(self, "min")?;
+        self.min()
+    }
+
+    /// Return the largest item in the collection like [`Stats::max`],
+    /// but returning an error rather than ignoring `NaN`s
+    ///
+    /// # Errors
+    /// Returns `StatsError::NaNEncountered` if an item is `NaN`,
+    /// or an error if the collection is empty
+    fn strict_max(&self) -> Result<Self::Item>
+    where
+        Self::Item: MinMax,
+    {
+        reject_nan(self, "max")?;
+        self.max()
+    }
+
+    /// Return the smallest item in the collection in the total order of
+    /// [`TotalOrder`], so unlike [`Stats::min`], `NaN`s are not ignored: a
+    /// negative `NaN` is smaller than every other item, and a positive one
+    /// is larger than every other item, so only the former can be returned
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let vec = vec![2.0, -0.0, 0.0, f64::NAN];
+    /// assert_eq!(vec.total_min().map(f64::to_bits), Ok((-0.0_f64).to_bits()));
+    /// assert!(vec.total_max().unwrap().is_nan());
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty
+    fn total_min(&self) -> Result<Self::Item>
+    where
+        Self::Item: TotalOrder,
+    {
+        self.items()
+            .min_by(TotalOrder::total_cmp)
+            .ok_or(StatsError::EmptyCollection)
+    }
+
+    /// Return the largest item in the collection in the total order of
+    /// [`TotalOrder`], so unlike [`Stats::max`], a positive `NaN` is larger
+    /// than every other item. See [`Stats::total_min`].
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty
+    fn total_max(&self) -> Result<Self::Item>
+    where
+        Self::Item: TotalOrder,
+    {
+        self.items()
+            .max_by(TotalOrder::total_cmp)
+            .ok_or(StatsError::EmptyCollection)
+    }
+
+    /// Calculate the [Herfindahl–Hirschman index](<https://en.wikipedia.org/wiki/Herfindahl%E2%80%93Hirschman_index>)
+    /// of the items, which is the sum of the squares of each item's share of
+    /// their total, to measure how concentrated they are. The items can be
+    /// shares or raw values (such as the sales of each company in a market),
+    /// because they are divided by their total first. The index is 1 when
+    /// a single item has everything and `1 / n` when `n` items are equal.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let sales = vec![50, 30, 20];
+    /// assert!((sales.hhi().unwrap() - 0.38).abs() < 1e-12);
+    /// assert_eq!(vec![5, 5, 5, 5].hhi(), Ok(0.25));
+    /// assert_eq!(vec![0, 7, 0].hhi(), Ok(1.0));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty, if an item is `NaN`
+    /// (`StatsError::NaNEncountered`), if an item is negative or every item
+    /// is 0 (`StatsError::OutOfDomain`), or if an item can't be converted
+    /// to [`f64`]
+    fn hhi(&self) -> Result<f64>
+    where
+        Self::Item: ToPrimitive,
+    {
+        let (mut total, mut count) = (0.0, 0usize);
+        for x in self.items() {
+            let x = item_to_f64(x)?;
+            if x.is_nan() {
+                return Err(StatsError::NaNEncountered);
+            }
+            if x < 0.0 {
+                return Err(StatsError::OutOfDomain);
+            }
+            total += x;
+            count += 1;
+        }
+        if count == 0 {
+            return Err(StatsError::EmptyCollection);
+        }
+        // there are no shares of a total of 0
+        if total == 0.0 {
+            return Err(StatsError::OutOfDomain);
+        }
+        // the shares are squared rather than the items, which could overflow
+        let mut squares = 0.0;
+        for x in self.items() {
+            let share = item_to_f64(x)? / total;
+            squares += share * share;
+        }
+        Ok(squares)
+    }
+
+    /// Calculate the [circular mean](<https://en.wikipedia.org/wiki/Circular_mean>)
+    /// of the items, which are angles in radians, such as directions or times
+    /// of day. It is the direction of the mean of the unit vectors pointing at
+    /// each angle, so angles either side of 0 average to near 0 rather than π.
+    /// The result is between -π and π. It is not meaningful when the angles are
+    /// spread evenly around the circle, which [`Stats::circular_variance`] shows.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let headings = [350.0_f64, 10.0, 20.0, 340.0].map(f64::to_radians);
+    /// let mean = headings.circular_mean().unwrap();
+    /// assert!(mean.to_degrees().abs() < 1e-9);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty, or if an item can't be converted to [`f64`]
+    fn circular_mean(&self) -> Result<f64>
+    where
+        Self::Item: ToPrimitive,
+    {
+        let (cos, sin) = mean_resultant(self.items())?;
+        Ok(sin.atan2(cos))
+    }
+
+    /// Calculate the [circular variance](<https://en.wikipedia.org/wiki/Directional_statistics#Measures_of_location_and_spread>)
+    /// of the items, which are angles in radians. It is one minus the length of
+    /// the mean of the unit vectors pointing at each angle, so it is 0 when the
+    /// angles are all the same and 1 when they cancel out.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// use std::f64::consts::PI;
+    /// assert_eq!(vec![1.0, 1.0].circular_variance(), Ok(0.0));
+    /// assert!((vec![0.0, PI].circular_variance().unwrap() - 1.0).abs() < 1e-12);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty, or if an item can't be converted to [`f64`]
+    fn circular_variance(&self) -> Result<f64>
+    where
+        Self::Item: ToPrimitive,
+    {
+        let (cos, sin) = mean_resultant(self.items())?;
+        Ok(1.0 - cos.hypot(sin))
+    }
+
+    /// Calculate the circular standard deviation of the items, which are angles
+    /// in radians, as `sqrt(-2 ln R)` where `R` is the length of the mean of the
+    /// unit vectors pointing at each angle. The result is in radians, and is
+    /// close to the ordinary standard deviation when the angles are close
+    /// together. It is ∞ when the angles cancel out.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let headings = [350.0_f64, 10.0, 20.0, 340.0].map(f64::to_radians);
+    /// let spread = headings.circular_std_dev().unwrap();
+    /// assert!((spread.to_degrees() - 15.895).abs() < 1e-3);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty, or if an item can't be converted to [`f64`]
+    fn circular_std_dev(&self) -> Result<f64>
+    where
+        Self::Item: ToPrimitive,
+    {
+        let (cos, sin) = mean_resultant(self.items())?;
+        // rounding can make the length slightly more than 1
+        Ok((-2.0 * cos.hypot(sin).min(1.0).ln()).sqrt())
+    }
+
+    /// Calculate the autocovariance of the items at `lag`, which is the
+    /// covariance between each item and the item `lag` places after it,
+    /// treating the collection as a time series. The sum of the products
+    /// of the differences from the mean is divided by the number of items,
+    /// which gives a biased estimate but guarantees the autocovariances
+    /// form a positive semi-definite sequence, as needed for spectral
+    /// analysis. At lag 0 this is the [`Stats::variance`].
+    ///
+    /// [Wikipedia](<https://en.wikipedia.org/wiki/Autocovariance>)
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let series = vec![1.0, -1.0, 1.0, -1.0];
+    /// assert_eq!(series.autocovariance(0), Ok(1.0));
+    /// assert_eq!(series.autocovariance(1), Ok(-0.75));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if there are not more than `lag` items,
+    /// or if an item can't be converted to [`f64`]
+    fn autocovariance(&self, lag: usize) -> Result<f64>
+    where
+        Self::Item: ToPrimitive,
+    {
+        let (sum_products, count) = lagged_co_moment(self, lag)?;
+        Ok(sum_products / count as f64)
+    }
+
+    /// Calculate the unbiased autocovariance of the items at `lag`, which
+    /// is the same as [`Stats::autocovariance`] but divides by the number
+    /// of products summed (the number of items minus `lag`) instead
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let series = vec![1.0, -1.0, 1.0, -1.0];
+    /// assert_eq!(series.unbiased_autocovariance(1), Ok(-1.0));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error under the same conditions as [`Stats::autocovariance`]
+    fn unbiased_autocovariance(&self, lag: usize) -> Result<f64>
+    where
+        Self::Item: ToPrimitive,
+    {
+        let (sum_products, count) = lagged_co_moment(self, lag)?;
+        Ok(sum_products / (count - lag) as f64)
+    }
+
+    /// Calculate the autocorrelation of the items at `lag`, which is the
+    /// correlation between each item and the item `lag` places after it,
+    /// treating the collection as a time series. It is between -1 and 1.
+    /// This is the [`Stats::autocovariance`] at `lag` divided by
+    /// the autocovariance at lag 0, which is the usual estimate
+    /// in time series analysis.
+    ///
+    /// [Wikipedia](<https://en.wikipedia.org/wiki/Autocorrelation>)
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let series = vec![1.0, -1.0, 1.0, -1.0];
+    /// assert_eq!(series.autocorrelation(0), Ok(1.0));
+    /// assert_eq!(series.autocorrelation(1), Ok(-0.75));
+    /// assert_eq!(series.autocorrelation(2), Ok(0.5));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if there are not more than `lag` items, if every
+    /// item is the same (`StatsError::ZeroVariance`), or if an item can't
+    /// be converted to [`f64`]
+    fn autocorrelation(&self, lag: usize) -> Result<f64>
+    where
+        Self::Item: ToPrimitive,
+    {
+        let autocovariance = self.autocovariance(lag)?;
+        let variance = self.autocovariance(0)?;
+        if variance == 0.0 {
+            return Err(StatsError::ZeroVariance);
+        }
+        Ok(autocovariance / variance)
+    }
+
+    /// Calculate the autocorrelation function of the items, which is the
+    /// [`Stats::autocorrelation`] at each lag from 0 to `max_lag`
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let series = vec![1.0, -1.0, 1.0, -1.0];
+    /// assert_eq!(series.acf(2), Ok(vec![1.0, -0.75, 0.5]));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error under the same conditions as [`Stats::autocorrelation`] at `max_lag`
+    #[cfg(feature = "alloc")]
+    fn acf(&self, max_lag: usize) -> Result<Vec<f64>>
+    where
+        Self::Item: ToPrimitive,
+    {
+        (0..=max_lag).map(|lag| self.autocorrelation(lag)).collect()
+    }
+
+    /// Test whether the items are uncorrelated over time (white noise) with the
+    /// [Ljung–Box test](<https://en.wikipedia.org/wiki/Ljung%E2%80%93Box_test>),
+    /// which combines the [`Stats::autocorrelation`] at every lag from 1 to
+    /// `max_lag` into one statistic. It is usually run on the residuals of a time
+    /// series model, where a small p-value means the model missed some structure.
+    /// The p-value is from the chi-squared distribution with `max_lag` degrees
+    /// of freedom.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let trending = vec![0.5, 1.2, 0.8, 1.9, 1.4, 2.2, 1.7, 2.6, 2.0, 2.9, 2.3, 3.1];
+    /// let test = trending.ljung_box(3).unwrap();
+    /// assert!(test.p_value < 0.05);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error under the same conditions as [`Stats::autocorrelation`] at `max_lag`
+    ///
+    /// # Panics
+    /// Panics if `max_lag` is 0
+    fn ljung_box(&self, max_lag: usize) -> Result<TestResult>
+    where
+        Self::Item: ToPrimitive,
+    {
+        assert!(max_lag > 0, "max lag must be greater than 0");
+        let count = self.non_zero_count()?;
+        TestResult::ljung_box(|lag| self.autocorrelation(lag), max_lag, count)
+    }
+
+    /// Split the items into `n` buckets holding about the same number of items
+    /// each, using the quantiles of the items as the edges of the buckets.
+    ///
+    /// Returns the bucket label (from 0 to `n - 1`) of each item in order,
+    /// and the `n + 1` edges of the buckets. Buckets include their left edge,
+    /// and the last bucket includes the largest item. When there are repeated
+    /// items, edges may coincide and leave some buckets empty. `NaN`s are not
+    /// in any bucket, so their label is `None`.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let (labels, edges) = vec![5, 1, 4, 2, 3, 6].qcut(3).unwrap();
+    /// assert_eq!(labels, vec![Some(2), Some(0), Some(1), Some(0), Some(1), Some(2)]);
+    /// assert_eq!(edges, vec![1.0, 8.0 / 3.0, 13.0 / 3.0, 6.0]);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty (or only has `NaN`s),
+    /// or if an item can't be converted to [`f64`]
+    ///
+    /// # Panics
+    /// Panics if `n` is 0
+    #[cfg(feature = "alloc")]
+    fn qcut(&self, n: usize) -> Result<(Vec<Option<usize>>, Vec<f64>)>
+    where
+        Self::Item: ToPrimitive,
+    {
+        assert!(n > 0, "number of buckets must be greater than 0");
+        let values = self
+            .items()
+            .map(item_to_f64)
+            .collect::<Result<Vec<f64>>>()?;
+        let mut sorted: Vec<f64> = values.iter().copied().filter(|x| !x.is_nan()).collect();
+        if sorted.is_empty() {
+            return Err(StatsError::EmptyCollection);
+        }
+        sorted.sort_by(partial_cmp_nan_last);
+
+        let edges: Vec<f64> = (0..=n)
+            .map(|i| quantile_of_sorted(&sorted, i as f64 / n as f64))
+            .collect();
+        let labels = values.iter().map(|&x| bin_of(&edges, x)).collect();
+        Ok((labels, edges))
+    }
+
+    /// Split the range of the items into `n` bins of equal width, and label
+    /// each item with the bin it falls in, as with [`Stats::cut_edges`].
+    /// The edges of bin `i` are `min + i * width` and `min + (i + 1) * width`,
+    /// where `width` is the range divided by `n`. If every item is the same,
+    /// the range is taken to be from half below it to half above it (or a
+    /// millionth of it either side, if that is wider).
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let (labels, counts) = vec![1.0, 2.0, 9.0, 4.0].cut(4).unwrap();
+    /// assert_eq!(labels, vec![Some(0), Some(0), Some(3), Some(1)]);
+    /// assert_eq!(counts.frequency(0), 2);
+    /// assert_eq!(counts.frequency(2), 0);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty (or only has `NaN`s), if
+    /// an item is infinite (`StatsError::InfinityEncountered`), if the range
+    /// of the items overflows (`StatsError::Overflow`), if the range is too
+    /// narrow to split into `n` bins with different edges
+    /// (`StatsError::InvalidParameter`), or if an item can't be converted
+    /// to [`f64`]
+    ///
+    /// # Panics
+    /// Panics if `n` is 0
+    #[cfg(feature = "alloc")]
+    fn cut(&self, n: usize) -> Result<(Vec<Option<usize>>, FrequencyTable<usize>)>
+    where
+        Self::Item: ToPrimitive,
+    {
+        assert!(n > 0, "number of bins must be greater than 0");
+        let mut values = self.items().map(item_to_f64);
+        let (mut min, mut max) = (f64::INFINITY, f64::NEG_INFINITY);
+        for x in &mut values {
+            let x = x?;
+            if !x.is_nan() {
+                min = min.min(x);
+                max = max.max(x);
+            }
+        }
+        if min > max {
+            return Err(StatsError::EmptyCollection);
+        }
+        if min.is_infinite() || max.is_infinite() {
+            return Err(StatsError::InfinityEncountered);
+        }
+        if min == max {
+            (min, max) = range_around(min);
+        }
+        let range = max - min;
+        if range.is_infinite() {
+            return Err(StatsError::Overflow);
+        }
+
+        let width = range / n as f64;
+        let mut edges: Vec<f64> = (0..n).map(|i| min + i as f64 * width).collect();
+        edges.push(max);
+        if !edges.windows(2).all(|pair| pair[0] < pair[1]) {
+            // the range is only a few representable values wide
+            return Err(StatsError::InvalidParameter { name: "n" });
+        }
+        self.cut_edges(&edges)
+    }
+
+    /// Label each item with the bin it falls in, where `edges` are the
+    /// increasing edges of the bins, so bin `i` is from `edges[i]` to
+    /// `edges[i + 1]`. Bins include their left edge, and the last bin includes
+    /// its right edge too. Items outside the edges and `NaN`s aren't in any
+    /// bin, so their label is `None`.
+    ///
+    /// Returns the label of each item in order, and a table of how many
+    /// items are in each bin (bins with no items are left out of it).
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let ages = vec![3, 17, 18, 40, 65, 90];
+    /// let (labels, counts) = ages.cut_edges(&[0.0, 18.0, 65.0]).unwrap();
+    /// assert_eq!(labels, vec![Some(0), Some(0), Some(1), Some(1), Some(1), None]);
+    /// assert_eq!(counts.frequency(1), 3);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if an item can't be converted to [`f64`]
+    ///
+    /// # Panics
+    /// Panics if there are fewer than two edges, or they aren't strictly increasing
+    #[cfg(feature = "alloc")]
+    fn cut_edges(&self, edges: &[f64]) -> Result<(Vec<Option<usize>>, FrequencyTable<usize>)>
+    where
+        Self::Item: ToPrimitive,
+    {
+        assert!(edges.len() >= 2, "there must be at least two edges");
+        assert!(
+            edges.windows(2).all(|pair| pair[0] < pair[1]),
+            "edges must be strictly increasing"
+        );
+        let labels = self
+            .items()
+            .map(|x| Ok(bin_of(edges, item_to_f64(x)?)))
+            .collect::<Result<Vec<Option<usize>>>>()?;
+        let counts = labels.iter().flatten().copied().collect();
+        Ok((labels, counts))
+    }
+
+    /// Split the items into groups by the key that `key` returns for each
+    /// of them, and return a [`Summary`] of each group, ordered by key.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let groups = vec![1, 2, 3, 4, 5, 6].grouped_stats(|x| x % 2 == 0).unwrap();
+    /// assert_eq!(groups[&false].mean, 3.0);
+    /// assert_eq!(groups[&true].count, 3);
+    /// assert_eq!(groups[&true].max, 6.0);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if an item can't be converted to [`f64`]
+    #[cfg(feature = "alloc")]
+    fn grouped_stats<K, F>(&self, mut key: F) -> Result<BTreeMap<K, Summary>>
+    where
+        Self::Item: ToPrimitive,
+        K: Ord,
+        F: FnMut(Self::Item) -> K,
+    {
+        let mut groups: BTreeMap<K, OnlineStats> = BTreeMap::new();
+        for x in self.items() {
+            groups
+                .entry(key(x.clone()))
+                .or_default()
+                .push(item_to_f64(x)?);
+        }
+        groups
+            .into_iter()
+            .map(|(key, stats)| Ok((key, stats.summary()?)))
+            .collect()
+    }
+
+    /// Calculate a confidence interval for the mean of the population the items
+    /// come from, at the confidence `level` (such as `0.95`), returning its lower
+    /// and upper bounds. The interval is the mean plus or minus a quantile of the
+    /// t distribution times the standard error of the mean, which accounts for
+    /// the variance being estimated from the items too; for large collections
+    /// this makes no difference and the interval is the familiar one from the
+    /// normal distribution.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let vec = vec![4.2, 5.1, 3.9, 4.8, 5.5, 4.4, 4.9, 5.0, 4.6, 4.1, 5.3];
+    /// let (lower, upper) = vec.mean_confidence_interval(0.95).unwrap();
+    /// assert!((lower - 4.363).abs() < 1e-3);
+    /// assert!((upper - 5.055).abs() < 1e-3);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if there are fewer than two items,
+    /// or if an item can't be converted to [`f64`]
+    ///
+    /// # Panics
+    /// Panics if `level` is not strictly between 0 and 1
+    fn mean_confidence_interval(&self, level: f64) -> Result<(f64, f64)>
+    where
+        Self::Item: ToPrimitive,
+    {
+        assert!(level > 0.0 && level < 1.0, "level must be between 0 and 1");
+        let stats = online_stats_of(self.items())?;
+        let count = stats.count();
+        if count < 2 {
+            return Err(StatsError::InsufficientData {
+                required: 2,
+                actual: count,
+            });
+        }
+        let n = count as f64;
+        let std_err = (stats.variance()? / (n - 1.0)).sqrt();
+        let margin = student_t_quantile((1.0 + level) / 2.0, n - 1.0) * std_err;
+        let mean = stats.mean()?;
+        Ok((mean - margin, mean + margin))
+    }
+
+    /// Test whether the items and the items of `other` come from populations
+    /// with the same mean, using [Welch's t-test](<https://en.wikipedia.org/wiki/Welch%27s_t-test>),
+    /// which doesn't assume that the populations have the same variance.
+    /// This is the safer default; see [`Stats::pooled_t_test_two_sample`]
+    /// for the version that does assume it.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let control = vec![12.1, 11.8, 12.4, 12.0, 11.9, 12.2];
+    /// let variant = vec![12.6, 12.9, 12.3, 13.1, 12.8];
+    /// let test = control.t_test_two_sample(&variant).unwrap();
+    /// assert!(test.statistic < 0.0);
+    /// assert!(test.p_value < 0.05);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if either collection has fewer than two items,
+    /// if the items of both are all the same (`StatsError::ZeroVariance`),
+    /// or if an item can't be converted to [`f64`]
+    fn t_test_two_sample<O>(&self, other: &O) -> Result<TTest>
+    where
+        Self::Item: ToPrimitive,
+        O: Stats + ?Sized,
+        O::Item: ToPrimitive,
+    {
+        TTest::welch(
+            &online_stats_of(self.items())?,
+            &online_stats_of(other.items())?,
+        )
+    }
+
+    /// Test whether the items and the items of `other` come from populations
+    /// with the same mean, using [Student's t-test](<https://en.wikipedia.org/wiki/Student%27s_t-test#Equal_or_unequal_sample_sizes,_similar_variances_(1/2_%3C_sX1/sX2_%3C_2)>),
+    /// which assumes that the populations have the same variance and pools
+    /// the variances of the samples to estimate it
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let a = vec![1.0, 2.0, 3.0, 4.0];
+    /// let b = vec![3.0, 4.0, 5.0, 6.0];
+    /// let test = a.pooled_t_test_two_sample(&b).unwrap();
+    /// assert_eq!(test.degrees_of_freedom, 6.0);
+    /// assert!((test.statistic + 2.19089).abs() < 1e-5);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error under the same conditions as [`Stats::t_test_two_sample`]
+    fn pooled_t_test_two_sample<O>(&self, other: &O) -> Result<TTest>
+    where
+        Self::Item: ToPrimitive,
+        O: Stats + ?Sized,
+        O::Item: ToPrimitive,
+    {
+        TTest::pooled(
+            &online_stats_of(self.items())?,
+            &online_stats_of(other.items())?,
+        )
+    }
+
+    /// Test whether the items and the items of `other` come from populations
+    /// with the same variance, using the [F-test](<https://en.wikipedia.org/wiki/F-test_of_equality_of_variances>)
+    /// on the ratio of their sample variances. It is often used to choose between
+    /// [`Stats::t_test_two_sample`] and [`Stats::pooled_t_test_two_sample`], but
+    /// it is very sensitive to the populations not being normally distributed.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let steady = vec![10.1, 9.9, 10.0, 10.2, 9.8, 10.0];
+    /// let noisy = vec![12.0, 8.5, 10.9, 7.6, 11.8, 9.2];
+    /// let test = steady.f_test_two_sample(&noisy).unwrap();
+    /// assert_eq!(test.degrees_of_freedom, (5, 5));
+    /// assert!(test.statistic < 1.0);
+    /// assert!(test.p_value < 0.01);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if either collection has fewer than two items, if the
+    /// items of `other` are all the same (`StatsError::ZeroVariance`), or if
+    /// an item can't be converted to [`f64`]
+    fn f_test_two_sample<O>(&self, other: &O) -> Result<FTest>
+    where
+        Self::Item: ToPrimitive,
+        O: Stats + ?Sized,
+        O::Item: ToPrimitive,
+    {
+        FTest::new(
+            &online_stats_of(self.items())?,
+            &online_stats_of(other.items())?,
+        )
+    }
+
+    /// Calculate [Cohen's d](<https://en.wikipedia.org/wiki/Effect_size#Cohen's_d>),
+    /// the difference between the mean of the items and the mean of the items
+    /// of `other` in units of their pooled standard deviation. Unlike a p-value,
+    /// this measures how large the difference is, whatever the sizes of the
+    /// samples; 0.2, 0.5 and 0.8 are conventionally small, medium and large.
+    /// See [`Stats::hedges_g`] for the version corrected for small samples.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let control = vec![12.1, 11.8, 12.4, 12.0, 11.9, 12.2];
+    /// let variant = vec![12.6, 12.9, 12.3, 13.1, 12.8];
+    /// let d = control.cohens_d(&variant).unwrap();
+    /// assert!((d + 2.596).abs() < 1e-3);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error under the same conditions as
+    /// [`Stats::pooled_t_test_two_sample`]
+    fn cohens_d<O>(&self, other: &O) -> Result<f64>
+    where
+        Self::Item: ToPrimitive,
+        O: Stats + ?Sized,
+        O::Item: ToPrimitive,
+    {
+        cohens_d(
+            &online_stats_of(self.items())?,
+            &online_stats_of(other.items())?,
+            false,
+        )
+    }
+
+    /// Calculate [Hedges' g](<https://en.wikipedia.org/wiki/Effect_size#Hedges'_g>),
+    /// which is [`Stats::cohens_d`] corrected for overestimating the size of
+    /// the difference in small samples. The correction is the exact one, using
+    /// the gamma function, and makes little difference once there are more
+    /// than a few dozen items in total.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let control = vec![12.1, 11.8, 12.4, 12.0, 11.9, 12.2];
+    /// let variant = vec![12.6, 12.9, 12.3, 13.1, 12.8];
+    /// let g = control.hedges_g(&variant).unwrap();
+    /// assert!((g + 2.373).abs() < 1e-3);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error under the same conditions as
+    /// [`Stats::pooled_t_test_two_sample`]
+    fn hedges_g<O>(&self, other: &O) -> Result<f64>
+    where
+        Self::Item: ToPrimitive,
+        O: Stats + ?Sized,
+        O::Item: ToPrimitive,
+    {
+        cohens_d(
+            &online_stats_of(self.items())?,
+            &online_stats_of(other.items())?,
+            true,
+        )
+    }
+
+    /// Test whether the items come from a normal distribution with the
+    /// [Jarque–Bera test](<https://en.wikipedia.org/wiki/Jarque%E2%80%93Bera_test>),
+    /// which checks how far their [`OnlineStats::skewness`] and
+    /// [`OnlineStats::kurtosis`] are from those of a normal distribution (both 0).
+    /// It is cheap, but the p-value is from the asymptotic chi-squared
+    /// distribution of the statistic, so it is only reliable for large samples.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// // heavily skewed
+    /// let vec = vec![1, 1, 1, 1, 1, 1, 1, 1, 1, 2, 2, 2, 3, 3, 5, 8, 13, 21, 34];
+    /// let test = vec.jarque_bera().unwrap();
+    /// assert!(test.p_value < 0.01);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty, if every item is the same
+    /// (`StatsError::ZeroVariance`), or if an item can't be converted to [`f64`]
+    fn jarque_bera(&self) -> Result<TestResult>
+    where
+        Self::Item: ToPrimitive,
+    {
+        TestResult::jarque_bera(&online_stats_of(self.items())?)
+    }
+
+    /// Test whether the median of the population the items come from is
+    /// `median` with the [sign test](<https://en.wikipedia.org/wiki/Sign_test>),
+    /// which only uses how many items are above `median` and how many are below
+    /// it, so it makes no assumptions about the distribution. Items equal to
+    /// `median` are dropped, and the p-value is exact, from the binomial
+    /// distribution. The statistic is the number of items above `median`.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let response_times = vec![212, 180, 251, 240, 199, 265, 230, 245, 260, 238];
+    /// let test = response_times.sign_test(200).unwrap();
+    /// assert_eq!(test.statistic, 8.0);
+    /// // 8 out of 10 above is not quite unusual enough to be significant
+    /// assert!((test.p_value - 0.109375).abs() < 1e-12);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty, or if every item
+    /// is equal to `median` (`StatsError::ZeroVariance`)
+    fn sign_test(&self, median: Self::Item) -> Result<TestResult>
+    where
+        Self::Item: PartialOrd,
+    {
+        let (mut above, mut below, mut count) = (0, 0, 0);
+        for x in self.items() {
+            count += 1;
+            if x > median {
+                above += 1;
+            } else if x < median {
+                below += 1;
+            }
+        }
+        TestResult::sign_test(above, below, count)
+    }
+
+    /// Test whether the most extreme item is an outlier with
+    /// [Grubbs' test](<https://en.wikipedia.org/wiki/Grubbs%27s_test>) at
+    /// significance level `alpha`, which is usually 0.05. `tail` chooses whether
+    /// the largest item, the smallest, or whichever is further from the mean is
+    /// tested. The test assumes the other items come from a normal distribution,
+    /// and only tests one item, so to find several outliers remove the outlier
+    /// and test again.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::{Stats, Tail};
+    /// let vec = vec![199.31, 199.53, 200.19, 200.82, 201.92, 201.95, 202.18, 245.57];
+    /// let test = vec.grubbs_test(Tail::Both, 0.05).unwrap();
+    /// assert!(test.is_outlier);
+    /// assert_eq!(test.index, 7);
+    /// assert!((test.statistic - 2.4688).abs() < 1e-4);
+    /// assert!((test.critical_value - 2.1266).abs() < 1e-4);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if there are fewer than three items, if every item is
+    /// the same (`StatsError::ZeroVariance`), or if an item can't be converted
+    /// to [`f64`]
+    ///
+    /// # Panics
+    /// Panics if `alpha` is not strictly between 0 and 1
+    fn grubbs_test(&self, tail: Tail, alpha: f64) -> Result<GrubbsTest>
+    where
+        Self::Item: ToPrimitive,
+    {
+        assert!(alpha > 0.0 && alpha < 1.0, "alpha must be between 0 and 1");
+        let mut stats = OnlineStats::new();
+        let (mut min, mut max) = ((0, f64::INFINITY), (0, f64::NEG_INFINITY));
+        for (i, x) in self.items().enumerate() {
+            let x = item_to_f64(x)?;
+            stats.push(x);
+            if x < min.1 {
+                min = (i, x);
+            }
+            if x > max.1 {
+                max = (i, x);
+            }
+        }
+        let (index, value) = match tail {
+            Tail::Lower => min,
+            Tail::Upper => max,
+            Tail::Both => {
+                let mean = stats.mean()?;
+                if mean - min.1 > max.1 - mean {
+                    min
+                } else {
+                    max
+                }
+            }
+        };
+        GrubbsTest::new(&stats, index, value, tail, alpha)
+    }
+
+    /// Find the median of the items, which is the middle one when they are
+    /// sorted, or the mean of the middle two if there are an even number of
+    /// them. `NaN`s are ignored, and infinities are sorted like any other
+    /// item, so the median is only infinite if a middle item is.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// assert_eq!(vec![5, 1, 100, 2, 4].median(), Ok(4.0));
+    /// assert_eq!(vec![1.0, 4.0, 2.0, 3.0].median(), Ok(2.5));
+    /// assert_eq!(vec![1.0, 2.0, f64::INFINITY].median(), Ok(2.0));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if there are no items that aren't `NaN`,
+    /// or if an item can't be converted to [`f64`]
+    #[cfg(feature = "alloc")]
+    fn median(&self) -> Result<f64>
+    where
+        Self::Item: ToPrimitive,
+    {
+        self.quantile(0.5)
+    }
+
+    /// Find the `p` quantile of the items, interpolating linearly between
+    /// the two nearest items when it falls between them, which is the
+    /// default of numpy and R. See [`Configured::quantile`](crate::Configured::quantile)
+    /// for other ways of interpolating. `NaN`s are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let vec = vec![1, 2, 3, 4, 5];
+    /// assert_eq!(vec.quantile(0.0), Ok(1.0));
+    /// assert_eq!(vec.quantile(0.25), Ok(2.0));
+    /// assert_eq!(vec.quantile(0.9), Ok(4.6));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if there are no items that aren't `NaN`,
+    /// or if an item can't be converted to [`f64`]
+    ///
+    /// # Panics
+    /// Panics if `p` is not between 0 and 1
+    #[cfg(feature = "alloc")]
+    fn quantile(&self, p: f64) -> Result<f64>
+    where
+        Self::Item: ToPrimitive,
+    {
+        assert!((0.0..=1.0).contains(&p), "p must be between 0 and 1");
+        Ok(quantile_in_place(&mut non_nan_values(self.items())?, p))
+    }
+
+    /// Find the quantile of the items for every `p` in `ps`, in the same order,
+    /// like [`Stats::quantile`]. The items are copied once and partitioned
+    /// around the quantiles rather than sorted, which is much faster than
+    /// calling [`Stats::quantile`] for each of them on large collections.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let latencies: Vec<u32> = (1..=1000).collect();
+    /// let quantiles = latencies.quantiles(&[0.5, 0.25, 0.75]).unwrap();
+    /// assert_eq!(quantiles, [500.5, 250.75, 750.25]);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if there are no items that aren't `NaN`,
+    /// or if an item can't be converted to [`f64`]
+    ///
+    /// # Panics
+    /// Panics if any `p` is not between 0 and 1
+    #[cfg(feature = "alloc")]
+    fn quantiles(&self, ps: &[f64]) -> Result<Vec<f64>>
+    where
+        Self::Item: ToPrimitive,
+    {
+        assert!(
+            ps.iter().all(|p| (0.0..=1.0).contains(p)),
+            "p must be between 0 and 1"
+        );
+        Ok(quantiles_in_place(&mut non_nan_values(self.items())?, ps))
+    }
+
+    /// Calculate Bowley's seven-number summary of the items, which is their
+    /// smallest, 10th percentile, quartiles, median, 90th percentile and
+    /// largest, for reporting the shape of their distribution compactly.
+    /// The quantiles are selected together, like [`Stats::quantiles`].
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let latencies = vec![12, 15, 11, 19, 13, 250, 14, 12, 16, 13, 12];
+    /// let summary = latencies.seven_number_summary().unwrap();
+    /// assert_eq!(summary.median, 13.0);
+    /// assert_eq!(summary.iqr(), 3.5);
+    /// assert_eq!(summary.upper_tail, 19.0);
+    /// assert_eq!(summary.max, 250.0);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if there are no items that aren't `NaN`,
+    /// or if an item can't be converted to [`f64`]
+    #[cfg(feature = "alloc")]
+    fn seven_number_summary(&self) -> Result<SevenNumberSummary>
+    where
+        Self::Item: ToPrimitive,
+    {
+        self.seven_number_summary_at(0.1)
+    }
+
+    /// Calculate a seven-number summary of the items like
+    /// [`Stats::seven_number_summary`], with the `tail` and 1 - `tail`
+    /// quantiles instead of the 10th and 90th percentiles, such as 0.125
+    /// for the octiles or 0.02 for the 2nd and 98th percentiles
+    ///
+    /// # Errors
+    /// Returns an error if there are no items that aren't `NaN`,
+    /// or if an item can't be converted to [`f64`]
+    ///
+    /// # Panics
+    /// Panics if `tail` is not between 0 and 0.25
+    #[cfg(feature = "alloc")]
+    fn seven_number_summary_at(&self, tail: f64) -> Result<SevenNumberSummary>
+    where
+        Self::Item: ToPrimitive,
+    {
+        assert!(
+            (0.0..=0.25).contains(&tail),
+            "tail must be between 0 and 0.25"
+        );
+        let ps = [0.0, tail, 0.25, 0.5, 0.75, 1.0 - tail, 1.0];
+        let quantiles = self.quantiles(&ps)?;
+        Ok(SevenNumberSummary {
+            tail,
+            min: quantiles[0],
+            lower_tail: quantiles[1],
+            lower_quartile: quantiles[2],
+            median: quantiles[3],
+            upper_quartile: quantiles[4],
+            upper_tail: quantiles[5],
+            max: quantiles[6],
+        })
+    }
+
+    /// Count how often each item occurs, in a [`FrequencyTable`]
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let tally = vec![3, 1, 3, 2, 3].tally();
+    /// assert_eq!(tally.as_slice(), &[(1, 1), (1, 2), (3, 3)]);
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn tally(&self) -> FrequencyTable<Self::Item>
+    where
+        Self::Item: PartialOrd + Copy,
+    {
+        self.items().collect()
+    }
+
+    /// Find the mode of the items, which is the one that occurs most often,
+    /// or the smallest of them if more than one does
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// assert_eq!(vec![3, 1, 3, 2, 1].mode(), Ok(1));
+    /// assert_eq!(vec![0.5, 0.25, 0.5].mode(), Ok(0.5));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty
+    #[cfg(feature = "alloc")]
+    fn mode(&self) -> Result<Self::Item>
+    where
+        Self::Item: PartialOrd + Copy,
+    {
+        let tally = self.tally();
+        let most = tally.iter().map(|&(freq, _)| freq).max();
+        tally
+            .iter()
+            .find(|&&(freq, _)| Some(freq) == most)
+            .map(|&(_, value)| value)
+            .ok_or(StatsError::EmptyCollection)
+    }
+
+    /// Count the occurrences of every value of unsigned integer items, from
+    /// 0 up to the largest item, so `counts[value]` is the number of times
+    /// `value` occurs, like `numpy.bincount`. For small values, such as HTTP
+    /// status codes or dice rolls, this is much faster than
+    /// [`Stats::tally`]. It is empty if the collection is.
+    ///
+    /// `limit` is the largest item that is allowed, which caps the length
+    /// of the counts, so that one large item can't allocate a huge vector.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let rolls: Vec<u8> = vec![3, 6, 1, 3, 3, 6];
+    /// assert_eq!(rolls.bincount(6), Ok(vec![0, 1, 0, 3, 0, 0, 2]));
+    /// assert!(rolls.bincount(5).is_err());
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `StatsError::InvalidParameter` if an item is larger than
+    /// `limit`, or `StatsError::Overflow` if an item is [`usize::MAX`], so
+    /// there can't be a count for every value up to it
+    #[cfg(feature = "alloc")]
+    fn bincount(&self, limit: usize) -> Result<Vec<usize>>
+    where
+        Self::Item: PrimInt + Unsigned,
+    {
+        let mut counts = Vec::new();
+        for x in self.items() {
+            let x = match x.to_usize() {
+                Some(x) if x <= limit => x,
+                _ => return Err(StatsError::InvalidParameter { name: "limit" }),
+            };
+            if x >= counts.len() {
+                counts.resize(x.checked_add(1).ok_or(StatsError::Overflow)?, 0);
+            }
+            counts[x] += 1;
+        }
+        Ok(counts)
+    }
+
+    /// Find the weighted [geometric mean](<https://en.wikipedia.org/wiki/Weighted_geometric_mean>)
+    /// of the items, which is the exponential of the weighted mean of their
+    /// logarithms, as used to average growth rates or build price indexes.
+    /// Each item has a weight from `weights`, in the same order as the items,
+    /// and items with a weight of 0 are left out. It is 0 if an item is 0.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// use approx::assert_relative_eq;
+    /// // price relatives of two goods, weighted by their share of spending
+    /// let relatives = vec![1.1, 0.9];
+    /// let index = relatives.weighted_geometric_mean([0.75, 0.25]).unwrap();
+    /// assert_relative_eq!(index, 1.1f64.powf(0.75) * 0.9f64.powf(0.25));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if no item has a weight greater than 0, if an item
+    /// is negative (`StatsError::OutOfDomain`), or if an item can't be
+    /// converted to [`f64`]
+    ///
+    /// # Panics
+    /// Panics if there aren't the same number of weights as items,
+    /// or if a weight is negative or `NaN`
+    fn weighted_geometric_mean<W>(&self, weights: W) -> Result<f64>
+    where
+        Self::Item: ToPrimitive,
+        W: IntoIterator<Item = f64>,
+    {
+        let (mut log_sum, mut weight_sum) = (0.0, 0.0);
+        for (x, w) in with_weights(self.items(), weights) {
+            let x = item_to_f64(x)?;
+            if x < 0.0 {
+                return Err(StatsError::OutOfDomain);
+            }
+            log_sum += w * x.ln();
+            weight_sum += w;
+        }
+        if weight_sum == 0.0 {
+            return Err(StatsError::EmptyCollection);
+        }
+        Ok((log_sum / weight_sum).exp())
+    }
+
+    /// Summarize the natural logarithms of the items, which suits positive
+    /// items whose spread grows with their size, like file sizes or latencies.
+    /// Their mean and standard deviation are also transformed back into the
+    /// geometric mean and geometric standard deviation of the items, which
+    /// give [multiplicative bounds](crate::LogSummary::bounds) for them.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let latencies_ms = vec![12.0, 18.0, 25.0, 40.0, 95.0];
+    /// let summary = latencies_ms.log_summary().unwrap();
+    /// // typical latencies are within a factor of about 2 of 29 ms
+    /// assert!((summary.geometric_mean - 29.0).abs() < 0.01);
+    /// assert!((summary.geometric_std_dev - 2.04).abs() < 0.01);
+    /// let (lower, upper) = summary.bounds(1.0);
+    /// assert!(lower > 14.0 && upper < 60.0);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty, if an item is 0 or
+    /// negative (`StatsError::OutOfDomain`), or if an item can't be
+    /// converted to [`f64`]
+    fn log_summary(&self) -> Result<LogSummary>
+    where
+        Self::Item: ToPrimitive,
+    {
+        let mut log_stats = OnlineStats::new();
+        for x in self.items() {
+            let x = item_to_f64(x)?;
+            if x <= 0.0 {
+                return Err(StatsError::OutOfDomain);
+            }
+            log_stats.push(x.ln());
+        }
+        LogSummary::new(&log_stats)
+    }
+
+    /// Find the [geometric standard deviation](<https://en.wikipedia.org/wiki/Geometric_standard_deviation>)
+    /// of the items, which is the exponential of the (population) standard
+    /// deviation of their natural logarithms. It is the factor that positive
+    /// items typically differ from their geometric mean by, so it is at least 1.
+    /// See [`Stats::log_summary`] for the other statistics of the logarithms.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// // each item is 10 times the one before
+    /// let gsd = vec![1.0, 10.0, 100.0].geometric_std_dev().unwrap();
+    /// assert!((gsd - 10f64.powf((2.0f64 / 3.0).sqrt())).abs() < 1e-9);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error under the same conditions as [`Stats::log_summary`]
+    fn geometric_std_dev(&self) -> Result<f64>
+    where
+        Self::Item: ToPrimitive,
+    {
+        Ok(self.log_summary()?.geometric_std_dev)
+    }
+
+    /// Find the weighted [harmonic mean](<https://en.wikipedia.org/wiki/Harmonic_mean#Weighted_harmonic_mean>)
+    /// of the items, which is the sum of the weights divided by the weighted
+    /// sum of the reciprocals of the items, as used to average rates. Each
+    /// item has a weight from `weights`, in the same order as the items, and
+    /// items with a weight of 0 are left out. It is 0 if an item is 0.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// // driving 120 km at 60 km/h and then 60 km at 30 km/h takes 4 hours
+    /// let speeds = vec![60.0, 30.0];
+    /// assert_eq!(speeds.weighted_harmonic_mean([120.0, 60.0]), Ok(45.0));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if no item has a weight greater than 0, if an item
+    /// is negative (`StatsError::OutOfDomain`), or if an item can't be
+    /// converted to [`f64`]
+    ///
+    /// # Panics
+    /// Panics if there aren't the same number of weights as items,
+    /// or if a weight is negative or `NaN`
+    fn weighted_harmonic_mean<W>(&self, weights: W) -> Result<f64>
+    where
+        Self::Item: ToPrimitive,
+        W: IntoIterator<Item = f64>,
+    {
+        let (mut reciprocal_sum, mut weight_sum) = (0.0, 0.0);
+        for (x, w) in with_weights(self.items(), weights) {
+            let x = item_to_f64(x)?;
+            if x < 0.0 {
+                return Err(StatsError::OutOfDomain);
+            }
+            reciprocal_sum += w / x;
+            weight_sum += w;
+        }
+        if weight_sum == 0.0 {
+            return Err(StatsError::EmptyCollection);
+        }
+        Ok(weight_sum / reciprocal_sum)
+    }
+
+    /// Estimate the mean and total of a population from the items, a sample
+    /// of it where each item has a sampling weight from `weights`, in the
+    /// same order as the items, such as the number of people in the
+    /// population that each respondent to a poll stands for. Items with a
+    /// weight of 0 are left out.
+    ///
+    /// The weighted mean and total are the usual estimates, but unequal
+    /// weights make them less precise than the plain formulas for their
+    /// standard errors suggest. The [`SurveyEstimate`] has Kish's design
+    /// effect and effective sample size of the weights, and standard errors
+    /// adjusted for them. Unlike frequency weights, only the relative sizes
+    /// of the weights matter for the mean and its standard error.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// use approx::assert_relative_eq;
+    /// // approval of 0 or 1 from six respondents, the last two of whom
+    /// // are from a group that was undersampled
+    /// let approval = vec![1.0, 0.0, 1.0, 1.0, 0.0, 0.0];
+    /// let weights = [1.0, 1.0, 1.0, 1.0, 4.0, 4.0];
+    /// let estimate = approval.survey_estimate(weights).unwrap();
+    /// assert_eq!(estimate.mean, 0.25);
+    /// assert_eq!(estimate.total, 3.0);
+    /// assert_relative_eq!(estimate.effective_count, 144.0 / 36.0);
+    /// assert_relative_eq!(estimate.design_effect, 1.5);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if fewer than two items have weights greater than 0,
+    /// or if an item can't be converted to [`f64`]
+    ///
+    /// # Panics
+    /// Panics if there aren't the same number of weights as items,
+    /// or if a weight is negative or `NaN`
+    fn survey_estimate<W>(&self, weights: W) -> Result<SurveyEstimate>
+    where
+        Self::Item: ToPrimitive,
+        W: IntoIterator<Item = f64>,
+    {
+        let mut count = 0;
+        let (mut weight_sum, mut weight_square_sum) = (0.0, 0.0);
+        let (mut mean, mut m2) = (0.0, 0.0);
+        for (x, w) in with_weights(self.items(), weights) {
+            let x = item_to_f64(x)?;
+            count += 1;
+            weight_sum += w;
+            weight_square_sum += w * w;
+            // the weighted form of Welford's algorithm
+            let delta = x - mean;
+            mean += delta * w / weight_sum;
+            m2 += w * delta * (x - mean);
+        }
+        if count < 2 {
+            return Err(StatsError::InsufficientData {
+                required: 2,
+                actual: count,
+            });
+        }
+        Ok(SurveyEstimate::new(
+            count,
+            weight_sum,
+            weight_square_sum,
+            mean,
+            m2,
+        ))
+    }
+
+    /// Find the weighted mode of the items, which is the one with the largest
+    /// total weight, adding up the weights of items that are equal. Each item
+    /// has a weight from `weights`, in the same order as the items, and items
+    /// with a weight of 0 are left out. When more than one item has the
+    /// largest total weight, `tie_break` chooses between them, like
+    /// [`Configured::mode`](crate::Configured::mode).
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::{Stats, TieBreak};
+    /// let sizes = vec![8, 10, 8, 12, 10];
+    /// let orders = [1.0, 5.0, 2.0, 4.0, 1.0];
+    /// assert_eq!(sizes.weighted_mode(orders, TieBreak::Smallest), Ok(10));
+    /// let orders = [3.0, 5.0, 3.0, 1.0, 1.0];
+    /// assert_eq!(sizes.weighted_mode(orders, TieBreak::Smallest), Ok(8));
+    /// assert_eq!(sizes.weighted_mode(orders, TieBreak::Largest), Ok(10));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if no item has a weight greater than 0
+    ///
+    /// # Panics
+    /// Panics if there aren't the same number of weights as items,
+    /// or if a weight is negative or `NaN`
+    #[cfg(feature = "alloc")]
+    fn weighted_mode<W>(&self, weights: W, tie_break: TieBreak) -> Result<Self::Item>
+    where
+        Self::Item: PartialOrd + Copy,
+        W: IntoIterator<Item = f64>,
+    {
+        let mut pairs: Vec<(Self::Item, f64)> = with_weights(self.items(), weights).collect();
+        pairs.sort_by(|a, b| partial_cmp_nan_last(&a.0, &b.0));
+        let mut mode = None;
+        for run in pairs.chunk_by(|a, b| a.0 == b.0) {
+            let weight: f64 = run.iter().map(|&(_, w)| w).sum();
+            let better = match (mode, tie_break) {
+                (None, _) => true,
+                (Some((_, most)), TieBreak::Smallest) => weight > most,
+                (Some((_, most)), TieBreak::Largest) => weight >= most,
+            };
+            if better {
+                mode = Some((run[0].0, weight));
+            }
+        }
+        mode.map(|(value, _)| value)
+            .ok_or(StatsError::EmptyCollection)
+    }
+
+    /// Find the rank of each item, from 1 for the smallest to the number of
+    /// items for the largest, in the order of the items. Tied items get the
+    /// mean of the ranks they span, and `NaN`s are ranked after every other item.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// assert_eq!(vec![30, 10, 20, 10].ranks(), Ok(vec![4.0, 1.5, 3.0, 1.5]));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if an item can't be converted to [`f64`]
+    #[cfg(feature = "alloc")]
+    fn ranks(&self) -> Result<Vec<f64>>
+    where
+        Self::Item: ToPrimitive,
+    {
+        Ok(average_ranks(&values_of(self.items())?))
+    }
+
+    /// Find the rank of each item like [`Stats::ranks`], from 1 for the
+    /// smallest, with `ties` choosing the ranks of tied items
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::{RankTies, Stats};
+    /// let scores = vec![30, 10, 20, 10];
+    /// assert_eq!(scores.ranks_with(RankTies::Min), Ok(vec![4.0, 1.0, 3.0, 1.0]));
+    /// assert_eq!(scores.ranks_with(RankTies::Max), Ok(vec![4.0, 2.0, 3.0, 2.0]));
+    /// assert_eq!(scores.ranks_with(RankTies::Dense), Ok(vec![3.0, 1.0, 2.0, 1.0]));
+    /// assert_eq!(scores.ranks_with(RankTies::Ordinal), Ok(vec![4.0, 1.0, 3.0, 2.0]));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if an item can't be converted to [`f64`]
+    #[cfg(feature = "alloc")]
+    fn ranks_with(&self, ties: RankTies) -> Result<Vec<f64>>
+    where
+        Self::Item: ToPrimitive,
+    {
+        Ok(ranks(&values_of(self.items())?, ties))
+    }
+
+    /// Returns the items [winsorized](<https://en.wikipedia.org/wiki/Winsorizing>),
+    /// in the same order, so every statistic of them is robust to
+    /// outliers. The `fraction` of the items that are smallest are raised to
+    /// the smallest item that is kept, and likewise the largest are lowered,
+    /// where the number clipped at each end is `fraction` of the number of
+    /// items that aren't `NaN`, rounded down. `NaN`s are kept as they are.
+    /// See [`winsorize_in_place`](crate::winsorize_in_place) to clip a slice
+    /// without copying it.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let vec = vec![3, 1, 4, 1, 5, 9, 2, 6, 5, 35];
+    /// let winsorized = vec.winsorize(0.1).unwrap();
+    /// assert_eq!(winsorized, [3.0, 1.0, 4.0, 1.0, 5.0, 9.0, 2.0, 6.0, 5.0, 9.0]);
+    /// // the winsorized mean
+    /// assert_eq!(winsorized.mean(), Ok(4.5));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if there are no items that aren't `NaN`,
+    /// or if an item can't be converted to [`f64`]
+    ///
+    /// # Panics
+    /// Panics if `fraction` is not at least 0 and less than 0.5
+    #[cfg(feature = "alloc")]
+    fn winsorize(&self, fraction: f64) -> Result<Vec<f64>>
+    where
+        Self::Item: ToPrimitive,
+    {
+        let mut values = values_of(self.items())?;
+        winsorize_in_place(&mut values, fraction)?;
+        Ok(values)
+    }
+
+    /// [Quantile normalize](<https://en.wikipedia.org/wiki/Quantile_normalization>)
+    /// the items onto the distribution of `reference`, replacing each item by
+    /// the quantile of `reference` at its rank, so the items keep their
+    /// order but take on the quantile profile of `reference`. Tied items get
+    /// the value of their average rank, and `NaN`s are kept as they are.
+    /// See [`quantile_normalize`](crate::quantile_normalize) to normalize
+    /// several samples onto their average profile.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let sample = vec![30, 10, 20];
+    /// let reference = vec![0.5, 0.1, 0.9, 0.3, 0.7];
+    /// assert_eq!(sample.quantile_normalize(&reference), Ok(vec![0.9, 0.1, 0.5]));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if there are no items or no items of `reference`
+    /// that aren't `NaN`, or if an item can't be converted to [`f64`]
+    #[cfg(feature = "alloc")]
+    fn quantile_normalize<R>(&self, reference: &R) -> Result<Vec<f64>>
+    where
+        Self::Item: ToPrimitive,
+        R: Stats + ?Sized,
+        R::Item: ToPrimitive,
+    {
+        let profile = sorted_values(reference.items())?;
+        let values = values_of(self.items())?;
+        if values.iter().all(|x| x.is_nan()) {
+            return Err(StatsError::EmptyCollection);
+        }
+        Ok(normalize_onto(&values, &[profile]))
+    }
+
+    /// Calculate the [median absolute deviation](<https://en.wikipedia.org/wiki/Median_absolute_deviation>)
+    /// (MAD) of the items, which is the median of the distances of the items
+    /// from their median. It is a robust measure of how spread out the items
+    /// are, which isn't changed much by almost half of them being outliers.
+    /// See [`Stats::mad_scaled`] to use it in place of the standard deviation.
+    /// `NaN`s are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// // the distances from the median of 4 are 3, 2, 0, 1 and 96
+    /// assert_eq!(vec![1, 2, 4, 5, 100].mad(), Ok(2.0));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if there are no items that aren't `NaN`,
+    /// or if an item can't be converted to [`f64`]
+    #[cfg(feature = "alloc")]
+    fn mad(&self) -> Result<f64>
+    where
+        Self::Item: ToPrimitive,
+    {
+        Ok(median_and_mad(sorted_values(self.items())?).1)
+    }
+
+    /// Calculate the [`Stats::mad`] multiplied by 1.4826, which makes it
+    /// estimate the standard deviation of normally distributed items, so
+    /// it can be used in its place as a robust measure, such as in control
+    /// limits. See [`Stats::mad_scaled_by`] to use a different constant.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// assert_eq!(vec![1, 2, 4, 5, 100].mad_scaled(), Ok(2.0 * 1.4826));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error under the same conditions as [`Stats::mad`]
+    #[cfg(feature = "alloc")]
+    fn mad_scaled(&self) -> Result<f64>
+    where
+        Self::Item: ToPrimitive,
+    {
+        self.mad_scaled_by(1.4826)
+    }
+
+    /// Calculate the [`Stats::mad`] multiplied by `constant`. The constant that
+    /// makes it estimate the standard deviation of items from a distribution
+    /// is one over the third quartile of the distribution, which is about
+    /// 1.4826 for the normal distribution, as used by [`Stats::mad_scaled`].
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// // the more precise constant for the normal distribution
+    /// let mad = vec![1, 2, 4, 5, 100].mad_scaled_by(1.482602218505602).unwrap();
+    /// assert!((mad - 2.965204437).abs() < 1e-9);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error under the same conditions as [`Stats::mad`]
+    #[cfg(feature = "alloc")]
+    fn mad_scaled_by(&self, constant: f64) -> Result<f64>
+    where
+        Self::Item: ToPrimitive,
+    {
+        Ok(constant * self.mad()?)
+    }
+
+    /// Returns the items robustly scaled, in the same order, by subtracting
+    /// their median and dividing by their interquartile range. Like z-scores
+    /// it puts items on a common scale, but outliers hardly change the median
+    /// and quartiles, so they can't squash the rest of the items together.
+    /// See [`Stats::robust_scaled_with`] to divide by the scaled MAD instead.
+    /// `NaN`s are kept as they are.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// // the median is 4 and the quartiles are 2 and 5
+    /// let scaled = vec![1, 2, 4, 5, 100].robust_scaled().unwrap();
+    /// assert_eq!(scaled, [-1.0, -2.0 / 3.0, 0.0, 1.0 / 3.0, 32.0]);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if there are no items that aren't `NaN`, if the
+    /// interquartile range is 0 (`StatsError::ZeroVariance`),
+    /// or if an item can't be converted to [`f64`]
+    #[cfg(feature = "alloc")]
+    fn robust_scaled(&self) -> Result<Vec<f64>>
+    where
+        Self::Item: ToPrimitive,
+    {
+        self.robust_scaled_with(RobustScale::Iqr)
+    }
+
+    /// Returns the items robustly scaled like [`Stats::robust_scaled`], with
+    /// `scale` choosing what the distances from the median are divided by
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::{RobustScale, Stats};
+    /// // the MAD is 2
+    /// let scaled = vec![1, 2, 4, 5, 100].robust_scaled_with(RobustScale::Mad).unwrap();
+    /// assert!((scaled[0] + 3.0 / (2.0 * 1.4826)).abs() < 1e-12);
+    /// assert!((scaled[4] - 96.0 / (2.0 * 1.4826)).abs() < 1e-12);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if there are no items that aren't `NaN`, if the
+    /// chosen measure of spread is 0 (`StatsError::ZeroVariance`),
+    /// or if an item can't be converted to [`f64`]
+    #[cfg(feature = "alloc")]
+    fn robust_scaled_with(&self, scale: RobustScale) -> Result<Vec<f64>>
+    where
+        Self::Item: ToPrimitive,
+    {
+        let sorted = sorted_values(self.items())?;
+        let (median, spread) = match scale {
+            RobustScale::Iqr => (
+                quantile_of_sorted(&sorted, 0.5),
+                quantile_of_sorted(&sorted, 0.75) - quantile_of_sorted(&sorted, 0.25),
+            ),
+            RobustScale::Mad => {
+                let (median, mad) = median_and_mad(sorted);
+                (median, 1.4826 * mad)
+            }
+        };
+        if spread == 0.0 {
+            return Err(StatsError::ZeroVariance);
+        }
+        let mut values = values_of(self.items())?;
+        for x in &mut values {
+            *x = (*x - median) / spread;
+        }
+        Ok(values)
+    }
+
+    /// Returns the items, in the same order, transformed by the
+    /// [Box–Cox transform](<https://en.wikipedia.org/wiki/Power_transform#Box%E2%80%93Cox_transformation>)
+    /// with parameter `lambda`, which is `(x^λ - 1) / λ`, or `ln(x)` when
+    /// `lambda` is 0. Powers below 1 make right-skewed items more symmetric,
+    /// so they are closer to normal before normal-theory statistics are used.
+    /// See [`Stats::box_cox_optimal`] to estimate the best `lambda`, and
+    /// [`Stats::inverse_box_cox`] to transform back. `NaN`s are kept as they are.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let transformed = vec![1, 4, 9].box_cox(0.5).unwrap();
+    /// assert_eq!(transformed[0], 0.0);
+    /// assert!((transformed[1] - 2.0).abs() < 1e-12);
+    /// assert!((transformed[2] - 4.0).abs() < 1e-12);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if an item is 0 or negative (`StatsError::OutOfDomain`),
+    /// or if an item can't be converted to [`f64`]
+    #[cfg(feature = "alloc")]
+    fn box_cox(&self, lambda: f64) -> Result<Vec<f64>>
+    where
+        Self::Item: ToPrimitive,
+    {
+        let mut values = values_of(self.items())?;
+        for x in &mut values {
+            if *x <= 0.0 {
+                return Err(StatsError::OutOfDomain);
+            }
+            *x = box_cox::transform(*x, lambda);
+        }
+        Ok(values)
+    }
+
+    /// Estimate the parameter of the [`Stats::box_cox`] transform that makes
+    /// the items closest to normal, by maximum likelihood. The parameter is
+    /// searched for between -5 and 5. `NaN`s are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// // the logarithms of the items are symmetric, so the best transform is
+    /// // close to the logarithm
+    /// let incomes = vec![12.0, 20.0, 25.0, 32.0, 40.0, 50.0, 80.0, 100.0, 160.0];
+    /// let lambda = incomes.box_cox_optimal().unwrap();
+    /// assert!(lambda.abs() < 0.25);
+    /// let transformed = incomes.box_cox(lambda).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if there are fewer than 2 items that aren't `NaN`, if
+    /// they are all the same (`StatsError::ZeroVariance`), if an item is 0 or
+    /// negative (`StatsError::OutOfDomain`), or if an item can't be converted
+    /// to [`f64`]
+    #[cfg(feature = "alloc")]
+    fn box_cox_optimal(&self) -> Result<f64>
+    where
+        Self::Item: ToPrimitive,
+    {
+        let mut logs = non_nan_values(self.items())?;
+        for x in &mut logs {
+            if *x <= 0.0 {
+                return Err(StatsError::OutOfDomain);
+            }
+            *x = x.ln();
+        }
+        box_cox::optimal_lambda(&logs)
+    }
+
+    /// Returns the items, in the same order, transformed back from the
+    /// [`Stats::box_cox`] transform with parameter `lambda`, such as to
+    /// turn the bounds of an interval calculated for the transformed items
+    /// into bounds for the original ones. Items that no positive item is
+    /// transformed to, because `λy + 1` isn't positive, become `NaN`, and
+    /// `NaN`s are kept as they are.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let items = vec![0.0, 2.0, 4.0].inverse_box_cox(0.5).unwrap();
+    /// assert_eq!(items[0], 1.0);
+    /// assert!((items[1] - 4.0).abs() < 1e-12);
+    /// assert!((items[2] - 9.0).abs() < 1e-12);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if an item can't be converted to [`f64`]
+    #[cfg(feature = "alloc")]
+    fn inverse_box_cox(&self, lambda: f64) -> Result<Vec<f64>>
+    where
+        Self::Item: ToPrimitive,
+    {
+        let mut values = values_of(self.items())?;
+        for y in &mut values {
+            *y = box_cox::inverse(*y, lambda);
+        }
+        Ok(values)
+    }
+
+    /// Estimate the mode of continuous items with the half-sample mode of Bickel
+    /// and Frühwirth. The shortest interval containing half of the items is found,
+    /// then the shortest interval containing half of those, and so on until at
+    /// most three items are left, whose densest point is the mode. Unlike
+    /// [`FrequencyStats::mode`](crate::FrequencyStats::mode) it doesn't need
+    /// items to be repeated exactly, and it is robust to outliers. When several
+    /// intervals are equally short, the lowest is used. `NaN`s are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let vec = vec![1.0, 2.1, 2.3, 2.35, 2.4, 2.6, 3.5, 5.0, 8.0, 9.0];
+    /// assert_eq!(vec.half_sample_mode(), Ok(2.375));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if there are no items that aren't `NaN`,
+    /// or if an item can't be converted to [`f64`]
+    #[cfg(feature = "alloc")]
+    fn half_sample_mode(&self) -> Result<f64>
+    where
+        Self::Item: ToPrimitive,
+    {
+        let sorted = sorted_values(self.items())?;
+        let mut half = &sorted[..];
+        loop {
+            match *half {
+                [x] => return Ok(x),
+                [x, y] => return Ok((x + y) / 2.0),
+                [x, y, z] => {
+                    return Ok(match (y - x).partial_cmp(&(z - y)) {
+                        Some(Ordering::Less) => (x + y) / 2.0,
+                        Some(Ordering::Greater) => (y + z) / 2.0,
+                        _ => y,
+                    })
+                }
+                _ => {
+                    let width = half.len().div_ceil(2);
+                    let mut start = 0;
+                    for i in 1..=half.len() - width {
+                        if half[i + width - 1] - half[i] < half[start + width - 1] - half[start] {
+                            start = i;
+                        }
+                    }
+                    half = &half[start..start + width];
+                }
+            }
+        }
+    }
+
+    /// Returns the indices of the items whose
+    /// [z-score](<https://en.wikipedia.org/wiki/Standard_score>), which is how many
+    /// standard deviations they are from the mean, is larger than `threshold`
+    /// in either direction. A threshold of 3 is common. Outliers inflate the
+    /// standard deviation, which can hide them, especially in small collections;
+    /// [`Stats::outliers_modified_zscore`] doesn't have this problem.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let mut vec = vec![10.0; 20];
+    /// vec[4] = 9.0;
+    /// vec[12] = 30.0;
+    /// assert_eq!(vec.outliers_zscore(3.0).unwrap(), vec![12]);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty, if every item is the same
+    /// (`StatsError::ZeroVariance`), or if an item can't be converted to [`f64`]
+    #[cfg(feature = "alloc")]
+    fn outliers_zscore(&self, threshold: f64) -> Result<Vec<usize>>
+    where
+        Self::Item: ToPrimitive,
+    {
+        let stats = online_stats_of(self.items())?;
+        let mean = stats.mean()?;
+        let std_dev = stats.variance()?.sqrt();
+        if std_dev == 0.0 {
+            return Err(StatsError::ZeroVariance);
+        }
+        outlier_indices(self.items(), |x| (x - mean).abs() / std_dev > threshold)
+    }
+
+    /// Returns the indices of the items whose modified z-score, as defined by
+    /// Iglewicz and Hoaglin, is larger than `threshold` in either direction. The
+    /// modified z-score of an item is 0.6745 times its distance from the median
+    /// divided by the median absolute deviation (MAD) of the items, and the
+    /// recommended threshold is 3.5. The median and MAD are hardly changed by
+    /// outliers, so unlike [`Stats::outliers_zscore`] the outliers can't hide
+    /// themselves. `NaN`s are ignored and never flagged.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let vec = vec![4.1, 3.9, 4.0, 4.2, 9.0, 3.8, 4.1, 9.5];
+    /// // the two outliers inflate the standard deviation enough to hide
+    /// assert!(vec.outliers_zscore(2.0).unwrap().is_empty());
+    /// assert_eq!(vec.outliers_modified_zscore(3.5).unwrap(), vec![4, 7]);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if there are no items that aren't `NaN`, if more than
+    /// half of them are the same, so that the MAD is 0
+    /// (`StatsError::ZeroVariance`), or if an item can't be converted to [`f64`]
+    #[cfg(feature = "alloc")]
+    fn outliers_modified_zscore(&self, threshold: f64) -> Result<Vec<usize>>
+    where
+        Self::Item: ToPrimitive,
+    {
+        let (median, mad) = median_and_mad(sorted_values(self.items())?);
+        if mad == 0.0 {
+            return Err(StatsError::ZeroVariance);
+        }
+        outlier_indices(self.items(), |x| {
+            0.6745 * (x - median).abs() / mad > threshold
+        })
+    }
+
+    /// Calculate the [Hodges–Lehmann estimator](<https://en.wikipedia.org/wiki/Hodges%E2%80%93Lehmann_estimator>)
+    /// of the centre of the items, which is the median of the averages of every
+    /// pair of items, including each item paired with itself. It is robust to
+    /// outliers like the median, but almost as efficient as the mean for normally
+    /// distributed items. The averages aren't all calculated, so this takes
+    /// O(n log n) time rather than O(n²). `NaN`s are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let vec = vec![1, 2, 3, 4, 100];
+    /// // the 15 averages are 1, 1.5, 2, 2, 2.5, 2.5, 3, 3, 3.5, 4, 50.5, 51, 51.5, 52, 100
+    /// assert_eq!(vec.hodges_lehmann(), Ok(3.0));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if there are no items that aren't `NaN`,
+    /// or if an item can't be converted to [`f64`]
+    #[cfg(feature = "alloc")]
+    fn hodges_lehmann(&self) -> Result<f64>
+    where
+        Self::Item: ToPrimitive,
+    {
+        let sorted = sorted_values(self.items())?;
+        let n = sorted.len();
+        let pairs = n * (n + 1) / 2;
+        let average = |x: f64, y: f64| (x + y) / 2.0;
+        let upper = kth_pair_value(&sorted, 0, pairs / 2 + 1, average);
+        if pairs % 2 == 1 {
+            Ok(upper)
+        } else {
+            Ok((kth_pair_value(&sorted, 0, pairs / 2, average) + upper) / 2.0)
+        }
+    }
+
+    /// Calculate Tukey's [biweight](<https://en.wikipedia.org/wiki/Robust_measures_of_scale#The_biweight_midvariance>)
+    /// location of the items, a robust estimate of their centre. It is a weighted
+    /// mean in which the weight of each item falls smoothly from 1 at the median
+    /// to 0 at 6 median absolute deviations (MAD) from it, so gross outliers are
+    /// ignored but the estimate is nearly as efficient as the mean for normally
+    /// distributed items. If the MAD is 0 this is the median. `NaN`s are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let vec = vec![12.4, 11.9, 12.1, 12.6, 12.0, 11.7, 12.3, 12.2, 30.0, 12.1];
+    /// let location = vec.biweight_location().unwrap();
+    /// assert!((location - 12.144).abs() < 1e-3);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if there are no items that aren't `NaN`,
+    /// or if an item can't be converted to [`f64`]
+    #[cfg(feature = "alloc")]
+    fn biweight_location(&self) -> Result<f64>
+    where
+        Self::Item: ToPrimitive,
+    {
+        let values = sorted_values(self.items())?;
+        let (median, mad) = median_and_mad(values.clone());
+        if mad == 0.0 {
+            return Ok(median);
+        }
+        let (mut numerator, mut denominator) = (0.0, 0.0);
+        for x in values {
+            let u = (x - median) / (6.0 * mad);
+            if u.abs() < 1.0 {
+                let weight = (1.0 - u * u) * (1.0 - u * u);
+                numerator += (x - median) * weight;
+                denominator += weight;
+            }
+        }
+        Ok(median + numerator / denominator)
+    }
+
+    /// Calculate Tukey's [biweight midvariance](<https://en.wikipedia.org/wiki/Robust_measures_of_scale#The_biweight_midvariance>)
+    /// of the items, a robust estimate of their variance. Items more than 9
+    /// median absolute deviations (MAD) from the median are ignored, and those
+    /// nearer count for more the closer they are, so it is much more efficient
+    /// than the MAD for nearly normal items with occasional gross outliers. If
+    /// the MAD is 0 this is 0. `NaN`s are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let vec = vec![12.4, 11.9, 12.1, 12.6, 12.0, 11.7, 12.3, 12.2, 30.0, 12.1];
+    /// let midvariance = vec.biweight_midvariance().unwrap();
+    /// assert!((midvariance - 0.0756).abs() < 1e-4);
+    /// // the outlier makes the variance hundreds of times larger
+    /// assert!(vec.variance().unwrap() > 28.0);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if there are no items that aren't `NaN`,
+    /// or if an item can't be converted to [`f64`]
+    #[cfg(feature = "alloc")]
+    fn biweight_midvariance(&self) -> Result<f64>
+    where
+        Self::Item: ToPrimitive,
+    {
+        let values = sorted_values(self.items())?;
+        let n = values.len() as f64;
+        let (median, mad) = median_and_mad(values.clone());
+        if mad == 0.0 {
+            return Ok(0.0);
+        }
+        let (mut numerator, mut denominator) = (0.0, 0.0);
+        for x in values {
+            let u = (x - median) / (9.0 * mad);
+            if u.abs() < 1.0 {
+                let u2 = u * u;
+                numerator += (x - median) * (x - median) * (1.0 - u2).powi(4);
+                denominator += (1.0 - u2) * (1.0 - 5.0 * u2);
+            }
+        }
+        Ok(n * numerator / (denominator * denominator))
+    }
+
+    /// Calculate the Qn estimator of Rousseeuw and Croux, a robust estimate of
+    /// the standard deviation of the items. It is the first quartile of the
+    /// distances between every pair of items, scaled to estimate the standard
+    /// deviation of normally distributed items, with the factors they give for
+    /// small samples. It ignores almost half of the items being outliers, like
+    /// the MAD, but is much more efficient, and doesn't assume the items are
+    /// symmetric about their median. The distances aren't all calculated, so
+    /// this takes O(n log n) time rather than O(n²). `NaN`s are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let vec = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 100];
+    /// assert!((vec.qn().unwrap() - 3.220).abs() < 1e-3);
+    /// // the standard deviation is dominated by the outlier
+    /// assert!(vec.std_dev().unwrap() >= 28);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if there are fewer than two items that aren't `NaN`,
+    /// or if an item can't be converted to [`f64`]
+    #[cfg(feature = "alloc")]
+    fn qn(&self) -> Result<f64>
+    where
+        Self::Item: ToPrimitive,
+    {
+        let sorted = sorted_values(self.items())?;
+        let n = sorted.len();
+        if n < 2 {
+            return Err(StatsError::InsufficientData {
+                required: 2,
+                actual: n,
+            });
+        }
+        let h = n / 2 + 1;
+        let distance = kth_pair_value(&sorted, 1, h * (h - 1) / 2, |x, y| y - x);
+        let factor = match n {
+            2..=9 => [0.399, 0.994, 0.512, 0.844, 0.611, 0.857, 0.669, 0.872][n - 2],
+            _ if n % 2 == 1 => n as f64 / (n as f64 + 1.4),
+            _ => n as f64 / (n as f64 + 3.8),
+        };
+        Ok(2.2219 * factor * distance)
+    }
+
+    /// Calculate the Sn estimator of Rousseeuw and Croux, a robust estimate of
+    /// the standard deviation of the items. For each item the median distance to
+    /// the other items is found, and the median of these is scaled to estimate the
+    /// standard deviation of normally distributed items, with the factors they
+    /// give for small samples. Like [`Stats::qn`] it ignores almost half of the
+    /// items being outliers and doesn't assume the items are symmetric, and it
+    /// takes O(n log n) time. `NaN`s are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let vec = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 100];
+    /// assert!((vec.sn().unwrap() - 3.578).abs() < 1e-3);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if there are fewer than two items that aren't `NaN`,
+    /// or if an item can't be converted to [`f64`]
+    #[cfg(feature = "alloc")]
+    fn sn(&self) -> Result<f64>
+    where
+        Self::Item: ToPrimitive,
+    {
+        let sorted = sorted_values(self.items())?;
+        let n = sorted.len();
+        if n < 2 {
+            return Err(StatsError::InsufficientData {
+                required: 2,
+                actual: n,
+            });
+        }
+        // the high median of the distances from each item, including the 0 to itself
+        let mut medians: Vec<f64> = (0..n)
+            .map(|i| {
+                let x = sorted[i];
+                kth_of_two_sorted(
+                    |t| x - sorted[i - 1 - t],
+                    i,
+                    |t| sorted[i + 1 + t] - x,
+                    n - 1 - i,
+                    n / 2,
+                )
+            })
+            .collect();
+        medians.sort_by(partial_cmp_nan_last);
+        let factor = match n {
+            2..=9 => [0.743, 1.851, 0.954, 1.351, 0.993, 1.198, 1.005, 1.131][n - 2],
+            _ if n % 2 == 1 => n as f64 / (n as f64 - 0.9),
+            _ => 1.0,
+        };
+        Ok(1.1926 * factor * medians[n.div_ceil(2) - 1])
+    }
+
+    /// Test whether the items come from a normal distribution with the
+    /// [Shapiro–Wilk test](<https://en.wikipedia.org/wiki/Shapiro%E2%80%93Wilk_test>),
+    /// which is one of the most powerful normality tests for small and moderate
+    /// samples. The statistic is between 0 and 1, and values close to 1 mean
+    /// the items look normal. It is calculated with Royston's approximations,
+    /// which were fitted for samples of 3 to 5000 items. `NaN`s are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let heights = vec![148, 154, 158, 160, 161, 162, 166, 170, 182, 195, 236];
+    /// let test = heights.shapiro_wilk().unwrap();
+    /// assert!((test.statistic - 0.789).abs() < 1e-3);
+    /// assert!(test.p_value < 0.01);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if there are no items that aren't `NaN` or fewer than
+    /// three, if every item is the same (`StatsError::ZeroVariance`), or if an item
+    /// can't be converted to [`f64`]
+    #[cfg(feature = "alloc")]
+    fn shapiro_wilk(&self) -> Result<TestResult>
+    where
+        Self::Item: ToPrimitive,
+    {
+        TestResult::shapiro_wilk(&sorted_values(self.items())?)
+    }
+
+    /// Test whether the items are in a random order with the
+    /// [Wald–Wolfowitz runs test](<https://en.wikipedia.org/wiki/Wald%E2%80%93Wolfowitz_runs_test>).
+    /// Each item is marked as above or below the median, items equal to the
+    /// median are dropped, and the statistic is the number of runs of items on
+    /// the same side. Too few runs suggest a trend or clustering, and too many
+    /// suggest the items alternate. The p-value is from the normal approximation.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// // a steady upward trend has only two runs: below the median, then above it
+    /// let trend = vec![3, 1, 2, 5, 4, 6, 8, 7, 9, 10, 12, 11, 14, 13, 15, 17, 16, 18, 20, 19];
+    /// let test = trend.runs_test().unwrap();
+    /// assert_eq!(test.statistic, 2.0);
+    /// assert!(test.p_value < 0.001);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if there are no items that aren't `NaN`, if every item is
+    /// equal to the median (`StatsError::ZeroVariance`), or if an item can't be
+    /// converted to [`f64`]
+    #[cfg(feature = "alloc")]
+    fn runs_test(&self) -> Result<TestResult>
+    where
+        Self::Item: ToPrimitive,
+    {
+        let median = median_in_place(&mut non_nan_values(self.items())?);
+        let mut sides = Vec::new();
+        for x in self.items() {
+            let x = item_to_f64(x)?;
+            if x != median && !x.is_nan() {
+                sides.push(x > median);
+            }
+        }
+        if sides.is_empty() {
+            return Err(StatsError::ZeroVariance);
+        }
+        TestResult::runs_test(sides)
+    }
+
+    /// Returns the [`RunStats`] of the items about their mean, with the number of
+    /// runs of consecutive items on the same side of the mean, the longest runs
+    /// above and below it, and the current streak at the end. Items equal to
+    /// the mean and `NaN`s are skipped. Use [`Stats::run_stats_about`] for a
+    /// threshold other than the mean.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let points = vec![3, 1, 0, 2, 4, 3, 5, 1];
+    /// let runs = points.run_stats().unwrap();
+    /// assert_eq!(runs.threshold(), 2.375);
+    /// assert_eq!(runs.runs(), 4);
+    /// assert_eq!(runs.longest_above(), 3);
+    /// assert_eq!(runs.longest_below(), 3);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty, or if an item can't be converted to [`f64`]
+    fn run_stats(&self) -> Result<RunStats>
+    where
+        Self::Item: ToPrimitive,
+    {
+        let mean = online_stats_of(self.items())?.mean()?;
+        self.run_stats_about(mean)
+    }
+
+    /// Returns the [`RunStats`] of the items about `threshold`, like
+    /// [`Stats::run_stats`] does about the mean
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// // points scored in each game, winning when more than 20
+    /// let scores = vec![24, 27, 17, 21, 30, 19, 14, 22, 25, 28];
+    /// let streaks = scores.run_stats_about(20.0).unwrap();
+    /// assert_eq!(streaks.longest_above(), 3);
+    /// assert_eq!(streaks.longest_below(), 2);
+    /// assert_eq!(streaks.is_above(), Some(true));
+    /// assert_eq!(streaks.current_streak(), 3);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty, or if an item can't be converted to [`f64`]
+    fn run_stats_about(&self, threshold: f64) -> Result<RunStats>
+    where
+        Self::Item: ToPrimitive,
+    {
+        let mut runs = RunStats::new(threshold);
+        let mut empty = true;
+        for x in self.items() {
+            runs.push(item_to_f64(x)?);
+            empty = false;
+        }
+        if empty {
+            return Err(StatsError::EmptyCollection);
+        }
+        Ok(runs)
+    }
+
+    /// Calculate Hartigan's [dip](<https://en.wikipedia.org/wiki/Multimodal_distribution#Hartigan's_dip_test>)
+    /// of the items, which measures how far they are from having a unimodal
+    /// distribution. It is the largest distance between their empirical
+    /// distribution function and the closest unimodal distribution function,
+    /// so it is between 1 / (2n) and 1 / 4, and larger means more multimodal.
+    /// See [`Stats::dip_test`] for its p-value. `NaN`s are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let unimodal = vec![1.2, 2.3, 2.8, 3.1, 3.3, 3.6, 3.9, 4.4, 5.0, 6.1];
+    /// let bimodal = vec![1.0, 1.1, 1.3, 1.4, 1.5, 5.0, 5.2, 5.3, 5.5, 5.6];
+    /// assert!(unimodal.dip().unwrap() < 0.1);
+    /// assert!(bimodal.dip().unwrap() > 0.2);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if there are no items that aren't `NaN`,
+    /// or if an item can't be converted to [`f64`]
+    #[cfg(feature = "alloc")]
+    fn dip(&self) -> Result<f64>
+    where
+        Self::Item: ToPrimitive,
+    {
+        Ok(dip(&sorted_values(self.items())?))
+    }
+
+    /// Test whether the items come from a unimodal distribution with Hartigan's
+    /// [dip test](<https://en.wikipedia.org/wiki/Multimodal_distribution#Hartigan's_dip_test>).
+    /// The statistic is [`Stats::dip`], and the p-value is the proportion of
+    /// `simulations` samples of the same size from the uniform distribution,
+    /// the unimodal distribution whose dip is largest, with a dip at least as
+    /// large, using `rng` as the source of randomness. The sample itself is
+    /// counted as one of the simulations, so the p-value is never 0. A few
+    /// thousand simulations are usually enough. `NaN`s are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// use rand::rngs::SmallRng;
+    /// use rand::SeedableRng;
+    /// use stats_traits::Stats;
+    /// let bimodal = vec![1.0, 1.1, 1.3, 1.4, 1.5, 1.7, 5.0, 5.2, 5.3, 5.5, 5.6, 5.8];
+    /// let mut rng = SmallRng::seed_from_u64(1);
+    /// let test = bimodal.dip_test(2000, &mut rng).unwrap();
+    /// assert!(test.p_value < 0.01);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if there are no items that aren't `NaN`,
+    /// or if an item can't be converted to [`f64`]
+    ///
+    /// # Panics
+    /// Panics if `simulations` is 0
+    #[cfg(feature = "rand")]
+    fn dip_test<R>(&self, simulations: usize, rng: &mut R) -> Result<TestResult>
+    where
+        Self::Item: ToPrimitive,
+        R: Rng + ?Sized,
+    {
+        Ok(TestResult::dip_test(
+            &sorted_values(self.items())?,
+            simulations,
+            rng,
+        ))
+    }
+
+    /// Fit a [normal distribution](<https://en.wikipedia.org/wiki/Normal_distribution>)
+    /// to the items by maximum likelihood, so its mean is the mean of the items
+    /// and its standard deviation is their population standard deviation. The
+    /// result also has the log-likelihood of the items and the Kolmogorov–Smirnov
+    /// distance between them and the fitted distribution, to judge how well it
+    /// fits. `NaN`s are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let vec = vec![4.2, 5.1, 3.9, 4.8, 5.5, 4.4, 4.9, 5.0, 4.6, 4.1, 5.3];
+    /// let fit = vec.fit_normal().unwrap();
+    /// assert!((fit.distribution.mean() - 4.709).abs() < 1e-3);
+    /// assert!((fit.distribution.std_dev() - 0.491).abs() < 1e-3);
+    /// // the critical value of the Lilliefors test for 11 items at the 5% level is about 0.25
+    /// assert!(fit.ks_statistic < 0.25);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if there are no items that aren't `NaN`, if they are all the
+    /// same (`StatsError::ZeroVariance`), if an item is infinite
+    /// (`StatsError::InfinityEncountered`), if the items are so spread out that
+    /// their variance overflows (`StatsError::Overflow`), or if an item can't be
+    /// converted to [`f64`]
+    #[cfg(feature = "alloc")]
+    fn fit_normal(&self) -> Result<NormalFit>
+    where
+        Self::Item: ToPrimitive,
+    {
+        NormalFit::new(&sorted_values(self.items())?)
+    }
+
+    /// Fit an [exponential distribution](<https://en.wikipedia.org/wiki/Exponential_distribution>)
+    /// to the items, such as the times between events, by maximum likelihood,
+    /// so its rate is one over the mean of the items. The result also has the
+    /// exact confidence interval for the rate at the `confidence` level (such as
+    /// `0.95`), and the log-likelihood of the items. `NaN`s are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// // minutes between buses
+    /// let gaps = vec![12.0, 3.5, 8.0, 15.5, 6.0, 9.0, 2.0, 14.0];
+    /// let fit = gaps.fit_exponential(0.95).unwrap();
+    /// assert_eq!(fit.distribution.mean(), 8.75);
+    /// let (lower, upper) = fit.rate_interval;
+    /// assert!(lower < 1.0 / 8.75 && 1.0 / 8.75 < upper);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if there are no items that aren't `NaN`, if they are all 0
+    /// (`StatsError::ZeroVariance`), if an item is negative
+    /// (`StatsError::OutOfDomain`) or infinite (`StatsError::InfinityEncountered`),
+    /// if the sum of the items or the rate overflows (`StatsError::Overflow`),
+    /// or if an item can't be converted to [`f64`]
+    ///
+    /// # Panics
+    /// Panics if `confidence` is not strictly between 0 and 1
+    fn fit_exponential(&self, confidence: f64) -> Result<ExponentialFit>
+    where
+        Self::Item: ToPrimitive,
+    {
+        ExponentialFit::new(self.items().map(item_to_f64), confidence)
+    }
+
+    /// Fit a [Poisson distribution](<https://en.wikipedia.org/wiki/Poisson_distribution>)
+    /// to the items, which are counts of events in intervals of the same length,
+    /// by maximum likelihood, so its rate is the mean of the counts. The result
+    /// also has the exact confidence interval for the rate at the `confidence`
+    /// level (such as `0.95`), and the log-likelihood of the counts.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// // goals in each match
+    /// let goals = vec![2, 3, 1, 4, 2, 0, 3, 2, 1, 2];
+    /// let fit = goals.fit_poisson(0.95).unwrap();
+    /// assert_eq!(fit.distribution.rate(), 2.0);
+    /// let (lower, upper) = fit.rate_interval;
+    /// assert!((lower - 1.222).abs() < 1e-3);
+    /// assert!((upper - 3.089).abs() < 1e-3);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the collection is empty, or if an item can't be
+    /// converted to a count because it is negative or too large
+    ///
+    /// # Panics
+    /// Panics if `confidence` is not strictly between 0 and 1
+    fn fit_poisson(&self, confidence: f64) -> Result<PoissonFit>
+    where
+        Self::Item: ToPrimitive,
+    {
+        let counts = self.items().map(|k| {
+            k.to_usize().ok_or(StatsError::CouldNotConvert {
+                from: DataType::Item,
+                to: DataType::Usize,
+            })
+        });
+        PoissonFit::new(counts, confidence)
+    }
+
+    /// Returns the largest item of each consecutive block of `block_size`
+    /// items, such as the highest river level of each year of daily readings,
+    /// for [`Stats::fit_gumbel`] and other statistics of extreme values. An
+    /// incomplete block at the end is left out, so that every maximum is of
+    /// the same number of items. `NaN`s are ignored, and a block of only
+    /// `NaN`s has a maximum of `NaN`.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let loads = vec![3, 7, 2, 5, 9, 1, 4, 8];
+    /// assert_eq!(loads.block_maxima(3), Ok(vec![7.0, 9.0]));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if an item can't be converted to [`f64`]
+    ///
+    /// # Panics
+    /// Panics if `block_size` is 0
+    #[cfg(feature = "alloc")]
+    fn block_maxima(&self, block_size: usize) -> Result<Vec<f64>>
+    where
+        Self::Item: ToPrimitive,
+    {
+        assert!(block_size > 0, "block size must be greater than 0");
+        let mut maxima = Vec::new();
+        let (mut max, mut count) = (f64::NAN, 0);
+        for x in self.items() {
+            max = max.max(item_to_f64(x)?);
+            count += 1;
+            if count == block_size {
+                maxima.push(max);
+                (max, count) = (f64::NAN, 0);
+            }
+        }
+        Ok(maxima)
+    }
+
+    /// Fit a [Gumbel distribution](<https://en.wikipedia.org/wiki/Gumbel_distribution>)
+    /// to the items, which are maxima such as from [`Stats::block_maxima`], by
+    /// maximum likelihood. Its [`return_level`](crate::distributions::Gumbel::return_level)
+    /// answers questions like how high a 1-in-100-year flood is, which the
+    /// largest item can't when there are fewer than 100 years of them.
+    /// The result also has the log-likelihood of the items. `NaN`s are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// // the highest river level in each of 12 years, in metres
+    /// let maxima = vec![3.1, 2.7, 3.9, 3.3, 2.9, 4.4, 3.0, 3.6, 2.8, 3.4, 5.1, 3.2];
+    /// let fit = maxima.fit_gumbel().unwrap();
+    /// let level = fit.distribution.return_level(100.0);
+    /// // higher than any level seen in the 12 years
+    /// assert!(level > 5.1);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if there are no items that aren't `NaN`, if they are all the
+    /// same (`StatsError::ZeroVariance`), if an item is infinite
+    /// (`StatsError::InfinityEncountered`), if the items are so spread out that
+    /// the parameters overflow (`StatsError::Overflow`), or if an item can't be
+    /// converted to [`f64`]
+    #[cfg(feature = "alloc")]
+    fn fit_gumbel(&self) -> Result<GumbelFit>
+    where
+        Self::Item: ToPrimitive,
+    {
+        GumbelFit::new(&non_nan_values(self.items())?)
+    }
+
+    /// Count and summarize the items above `threshold`, for the
+    /// [peaks-over-threshold](<https://en.wikipedia.org/wiki/Peaks_over_threshold>)
+    /// approach to extreme values, such as how often and by how much a
+    /// power load goes over a limit. See [`Stats::fit_generalized_pareto`]
+    /// to fit a distribution to the excesses. `NaN`s are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let loads = vec![62, 75, 58, 91, 66, 84, 70, 99, 61, 73];
+    /// let exceedances = loads.exceedances(80.0).unwrap();
+    /// assert_eq!(exceedances.exceedance_count, 3);
+    /// assert_eq!(exceedances.exceedance_rate, 0.3);
+    /// assert_eq!(exceedances.mean_excess, 11.333333333333334);
+    /// assert_eq!(exceedances.max_excess, 19.0);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if there are no items that aren't `NaN`,
+    /// or if an item can't be converted to [`f64`]
+    fn exceedances(&self, threshold: f64) -> Result<Exceedances>
+    where
+        Self::Item: ToPrimitive,
+    {
+        Exceedances::new(self.items().map(item_to_f64), threshold)
+    }
+
+    /// Fit a [generalized Pareto distribution](<https://en.wikipedia.org/wiki/Generalized_Pareto_distribution>)
+    /// to how far the items above `threshold` exceed it, by the
+    /// probability-weighted moments of Hosking and Wallis. Above a high
+    /// enough threshold the excesses of most distributions follow it, so its
+    /// [`return_level`](crate::distributions::GeneralizedParetoFit::return_level)
+    /// estimates values rarer than any of the items, using every item above
+    /// the threshold rather than only the [`Stats::block_maxima`]. `NaN`s are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// // daily rainfall in millimetres
+    /// let rainfall = vec![
+    ///     0.0, 2.5, 14.0, 0.5, 31.0, 6.0, 0.0, 22.5, 3.0, 48.0, 1.0, 0.0, 17.5, 13.0, 26.0,
+    ///     0.0, 4.5, 65.0, 12.0, 0.0, 19.0, 10.5, 0.0, 38.5, 2.0, 11.0, 0.0, 28.0, 5.5, 15.5,
+    /// ];
+    /// let fit = rainfall.fit_generalized_pareto(10.0).unwrap();
+    /// assert_eq!(fit.exceedance_rate, 0.5);
+    /// // the daily rainfall exceeded once every 1000 days
+    /// let level = fit.return_level(1000.0);
+    /// assert!(level > 65.0);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if fewer than 2 items are above the threshold, if
+    /// they are all the same (`StatsError::ZeroVariance`), if one is infinite
+    /// (`StatsError::InfinityEncountered`), if they are so large that the
+    /// parameters overflow (`StatsError::Overflow`), or if an item can't be
+    /// converted to [`f64`]
+    #[cfg(feature = "alloc")]
+    fn fit_generalized_pareto(&self, threshold: f64) -> Result<GeneralizedParetoFit>
+    where
+        Self::Item: ToPrimitive,
+    {
+        let mut count = 0;
+        let mut excesses = Vec::new();
+        for x in self.items() {
+            let x = item_to_f64(x)?;
+            if x.is_nan() {
+                continue;
+            }
+            count += 1;
+            if x > threshold {
+                excesses.push(x - threshold);
+            }
+        }
+        excesses.sort_by(partial_cmp_nan_last);
+        GeneralizedParetoFit::new(&excesses, threshold, count)
+    }
+
+    /// Returns the points of a normal [Q–Q plot](<https://en.wikipedia.org/wiki/Q%E2%80%93Q_plot>)
+    /// of the items, as `(theoretical, sample)` pairs of the quantiles of the
+    /// standard normal distribution and the sorted items. If the items are
+    /// normally distributed the points lie close to a straight line, whose
+    /// intercept is the mean and whose slope is the standard deviation.
+    /// See [`Stats::qq_points_with`] for other distributions. `NaN`s are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let points = vec![5.1, 4.2, 6.3].qq_points().unwrap();
+    /// assert_eq!(points.len(), 3);
+    /// assert_eq!(points[1], (0.0, 5.1));
+    /// assert!((points[0].0 + 0.8694).abs() < 1e-4);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if there are no items that aren't `NaN`,
+    /// or if an item can't be converted to [`f64`]
+    #[cfg(feature = "alloc")]
+    fn qq_points(&self) -> Result<Vec<(f64, f64)>>
+    where
+        Self::Item: ToPrimitive,
+    {
+        self.qq_points_with(normal_quantile)
+    }
+
+    /// Returns the points of a [Q–Q plot](<https://en.wikipedia.org/wiki/Q%E2%80%93Q_plot>)
+    /// of the items against the distribution with the quantile function
+    /// `quantile`, as `(theoretical, sample)` pairs. The sorted items are paired
+    /// with `quantile` of the plotting positions `(i - a) / (n + 1 - 2a)`, where
+    /// `a` is 3/8 for up to 10 items and 1/2 otherwise, as in R's `qqnorm`.
+    /// `NaN`s are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// // against the exponential distribution with rate 1
+    /// let points = vec![0.3, 2.2, 0.9, 0.1].qq_points_with(|p| -(1.0 - p).ln()).unwrap();
+    /// assert_eq!(points[0].1, 0.1);
+    /// assert!((points[0].0 - 0.1591).abs() < 1e-4);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if there are no items that aren't `NaN`,
+    /// or if an item can't be converted to [`f64`]
+    #[cfg(feature = "alloc")]
+    fn qq_points_with<F>(&self, mut quantile: F) -> Result<Vec<(f64, f64)>>
+    where
+        Self::Item: ToPrimitive,
+        F: FnMut(f64) -> f64,
+    {
+        let sorted = sorted_values(self.items())?;
+        let n = sorted.len() as f64;
+        let a = if sorted.len() <= 10 { 0.375 } else { 0.5 };
+        Ok(sorted
+            .into_iter()
+            .enumerate()
+            .map(|(i, x)| (quantile((i as f64 + 1.0 - a) / (n + 1.0 - 2.0 * a)), x))
+            .collect())
+    }
+
+    /// Test whether the items come from the distribution with the cumulative
+    /// distribution function `cdf`, using the one-sample
+    /// [Kolmogorov–Smirnov test](<https://en.wikipedia.org/wiki/Kolmogorov%E2%80%93Smirnov_test>).
+    /// The statistic is the largest distance between `cdf` and the empirical
+    /// distribution function of the items, and the p-value is from its asymptotic
+    /// distribution. `NaN`s are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let samples = vec![0.12, 0.95, 0.47, 0.33, 0.71, 0.58, 0.04, 0.86, 0.29, 0.64];
+    /// // are these uniformly distributed between 0 and 1?
+    /// let test = samples.ks_test(|x| x.clamp(0.0, 1.0)).unwrap();
+    /// assert!(test.statistic < 0.2);
+    /// assert!(test.p_value > 0.5);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if there are no items that aren't `NaN`,
+    /// or if an item can't be converted to [`f64`]
+    #[cfg(feature = "alloc")]
+    fn ks_test<F>(&self, cdf: F) -> Result<TestResult>
+    where
+        Self::Item: ToPrimitive,
+        F: FnMut(f64) -> f64,
+    {
+        Ok(TestResult::kolmogorov_smirnov(
+            &sorted_values(self.items())?,
+            cdf,
+        ))
+    }
+
+    /// Test whether the items and the items of `other` come from the same
+    /// distribution, using the two-sample
+    /// [Kolmogorov–Smirnov test](<https://en.wikipedia.org/wiki/Kolmogorov%E2%80%93Smirnov_test#Two-sample_Kolmogorov%E2%80%93Smirnov_test>).
+    /// The statistic is the largest distance between the empirical distribution
+    /// functions of the two collections, so it notices any kind of difference
+    /// between them, not only a shift in the mean. The p-value is from its
+    /// asymptotic distribution. `NaN`s are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let yesterday = vec![12, 15, 11, 14, 13, 12, 16, 14, 13, 15];
+    /// let today = vec![18, 21, 17, 20, 19, 22, 18, 20, 21, 19];
+    /// let test = yesterday.ks_test_two_sample(&today).unwrap();
+    /// assert_eq!(test.statistic, 1.0);
+    /// assert!(test.p_value < 0.001);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if either collection has no items that aren't
+    /// `NaN`, or if an item can't be converted to [`f64`]
+    #[cfg(feature = "alloc")]
+    fn ks_test_two_sample<O>(&self, other: &O) -> Result<TestResult>
+    where
+        Self::Item: ToPrimitive,
+        O: Stats + ?Sized,
+        O::Item: ToPrimitive,
+    {
+        Ok(TestResult::kolmogorov_smirnov_two_sample(
+            &sorted_values(self.items())?,
+            &sorted_values(other.items())?,
+        ))
+    }
+
+    /// Estimate the bias and standard error of `statistic` of the items with the
+    /// leave-one-out [jackknife](<https://en.wikipedia.org/wiki/Jackknife_resampling>),
+    /// calculating it again with each item left out in turn. `statistic` is given
+    /// the items converted to [`f64`], so any statistic of the crate can be used.
+    /// This needs no randomness, but it only works well for smooth statistics
+    /// such as the mean or variance, and not for the median or other quantiles.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Stats;
+    /// let vec = vec![2, 4, 4, 5, 7, 8];
+    /// let jackknife = vec.jackknife(|sample| sample.variance()).unwrap();
+    /// assert_eq!(jackknife.estimate, 4.0);
+    /// // removing the bias of the population variance gives the sample variance
+    /// assert!((jackknife.bias_corrected() - 4.8).abs() < 1e-12);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if there are fewer than two items, if an item can't be
+    /// converted to [`f64`], or if `statistic` returns an error for the items
+    /// or the items with one of them left out
+    #[cfg(feature = "alloc")]
+    fn jackknife<F>(&self, statistic: F) -> Result<Jackknife>
+    where
+        Self::Item: ToPrimitive,
+        F: FnMut(&[f64]) -> Result<f64>,
+    {
+        Jackknife::new(&values_of(self.items())?, statistic)
+    }
+
+    /// Estimate how much `statistic` of the items would vary between samples with
+    /// the [bootstrap](<https://en.wikipedia.org/wiki/Bootstrapping_(statistics)>),
+    /// calculating it on `resamples` resamples drawn with replacement from the
+    /// items, using `rng` as the source of randomness. `statistic` is given the
+    /// items of each resample converted to [`f64`], so any statistic of the crate
+    /// can be used, and the result has its standard error and confidence intervals.
+    /// A few thousand resamples are usually enough for confidence intervals.
+    ///
+    /// # Examples
+    /// ```
+    /// use rand::rngs::SmallRng;
+    /// use rand::SeedableRng;
+    /// use stats_traits::Stats;
+    /// let vec = vec![2.1, 3.4, 1.9, 5.6, 2.8, 3.3, 4.1, 2.5, 3.9, 3.0];
+    /// let mut rng = SmallRng::seed_from_u64(1);
+    /// let bootstrap = vec
+    ///     .bootstrap(|sample| sample.std_dev(), 2000, &mut rng)
+    ///     .unwrap();
+    /// let (lower, upper) = bootstrap.bca_interval(0.95);
+    /// assert!(lower < bootstrap.estimate() && bootstrap.estimate() < upper);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if there are fewer than two items, if an item can't be
+    /// converted to [`f64`], or if `statistic` returns an error for the items,
+    /// a resample, or the items with one of them left out (which is needed for
+    /// [`Bootstrap::bca_interval`](crate::Bootstrap::bca_interval))
+    ///
+    /// # Panics
+    /// Panics if `resamples` is 0
+    #[cfg(feature = "rand")]
+    fn bootstrap<F, R>(&self, statistic: F, resamples: usize, rng: &mut R) -> Result<Bootstrap>
+    where
+        Self::Item: ToPrimitive,
+        F: FnMut(&[f64]) -> Result<f64>,
+        R: Rng + ?Sized,
+    {
+        Bootstrap::new(&values_of(self.items())?, statistic, resamples, rng)
+    }
+
+    /// Draw a simple random sample of `k` items without replacement, using
+    /// `rng` as the source of randomness, so every set of `k` items is
+    /// equally likely. The sample keeps the order of the items, and is
+    /// every item if there are no more than `k`. It is a collection itself,
+    /// so its statistics estimate those of the items in a fraction of the
+    /// time. See [`Reservoir`](crate::Reservoir) to sample a stream instead.
+    ///
+    /// # Examples
+    /// ```
+    /// use rand::rngs::SmallRng;
+    /// use rand::SeedableRng;
+    /// use stats_traits::Stats;
+    /// let vec: Vec<f64> = (0..100_000).map(f64::from).collect();
+    /// let mut rng = SmallRng::seed_from_u64(1);
+    /// let sample = vec.sample(1000, &mut rng);
+    /// assert_eq!(sample.len(), 1000);
+    /// assert!((sample.mean().unwrap() - 50_000.0).abs() < 5000.0);
+    /// ```
+    #[cfg(feature = "rand")]
+    fn sample<R>(&self, k: usize, rng: &mut R) -> Vec<Self::Item>
+    where
+        R: Rng + ?Sized,
+    {
+        sampling::simple_random(self.items(), self.count(), k, rng)
+    }
+
+    /// Draw a systematic sample of `k` items, which are evenly spaced through
+    /// the collection from a random start in the first of `k` intervals,
+    /// using `rng` as the source of randomness. This spreads the sample over
+    /// the whole collection, such as every part of a day of readings, but
+    /// a pattern that repeats at the interval biases it. The sample keeps
+    /// the order of the items, and is every item if there are no more than `k`.
+    ///
+    /// # Examples
+    /// ```
+    /// use rand::rngs::SmallRng;
+    /// use rand::SeedableRng;
+    /// use stats_traits::Stats;
+    /// let vec: Vec<u32> = (0..100).collect();
+    /// let mut rng = SmallRng::seed_from_u64(1);
+    /// let sample = vec.systematic_sample(4, &mut rng);
+    /// // one item from each 25
+    /// assert_eq!(sample.len(), 4);
+    /// assert!(sample.windows(2).all(|pair| pair[1] - pair[0] == 25));
+    /// ```
+    #[cfg(feature = "rand")]
+    fn systematic_sample<R>(&self, k: usize, rng: &mut R) -> Vec<Self::Item>
+    where
+        R: Rng + ?Sized,
+    {
+        sampling::systematic(self.items(), self.count(), k, rng)
+    }
+
+    /// Draw a stratified sample, grouping the items into strata by `key` and
+    /// drawing a simple random sample of `fraction` of the items of each
+    /// stratum, using `rng` as the source of randomness. Each stratum keeps
+    /// its share of the sample, so a stratum of rare items isn't missed by
+    /// chance. Returns the strata in the order of their keys, each with its
+    /// sample, which is rounded to the nearest item but is at least one.
+    ///
+    /// # Examples
+    /// ```
+    /// use rand::rngs::SmallRng;
+    /// use rand::SeedableRng;
+    /// use stats_traits::Stats;
+    /// // response times in milliseconds, of which a few time out
+    /// let vec: Vec<u32> = (0..1000)
+    ///     .map(|i| if i % 100 == 0 { 5000 } else { 100 + i % 7 })
+    ///     .collect();
+    /// let mut rng = SmallRng::seed_from_u64(1);
+    /// let strata = vec.stratified_sample(0.1, |&x| x >= 5000, &mut rng);
+    /// assert_eq!(strata.len(), 2);
+    /// assert_eq!(strata[0].0, false);
+    /// assert_eq!(strata[0].1.len(), 99);
+    /// // one of the 10 timeouts, rather than none or several by chance
+    /// assert_eq!(strata[1], (true, vec![5000]));
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `fraction` is not between 0 and 1
+    #[cfg(feature = "rand")]
+    fn stratified_sample<K, F, R>(
+        &self,
+        fraction: f64,
+        key: F,
+        rng: &mut R,
+    ) -> Vec<(K, Vec<Self::Item>)>
+    where
+        K: Ord,
+        F: FnMut(&Self::Item) -> K,
+        R: Rng + ?Sized,
+    {
+        assert!(
+            (0.0..=1.0).contains(&fraction),
+            "fraction must be between 0 and 1"
+        );
+        sampling::stratified(self.items(), fraction, key, rng)
+    }
+}
+
+/// Blanket implementation for all types whose references implement [`IntoIterator`]
+/// over references to the items. This allows us to use the methods on any type
+/// that implements those traits. For example, we can use the methods on `Vec`,
+/// arrays, `VecDeque` and `[i32]` (and so on `&[i32]`).
+impl<T, I> Stats for T
+where
+    T: ?Sized,
+    for<'a> &'a T: IntoIterator<Item = &'a I>,
+    I: NumExt,
+{
+    type Item = I;
+
+    fn items<'a>(&'a self) -> impl Iterator<Item = I> + 'a
+    where
+        I: 'a,
+    {
+        self.into_iter().cloned()
+    }
+}
+
+/// Returns the sum of the items and their number converted to the type of
+/// the items, going through them once. Errors under the same conditions as
+/// [`Stats::non_zero_count_into_item`].
+fn sum_and_count<I>(items: I) -> Result<(I::Item, I::Item)>
+where
+    I: IntoIterator,
+    I::Item: NumExt,
+{
+    let (sum, count) = sum_and_len(items)?;
+    let count = I::Item::from_usize(count).ok_or(StatsError::CouldNotConvert {
+        from: DataType::Usize,
+        to: DataType::Item,
+    })?;
+    Ok((sum, count))
+}
+
+/// Returns the sum of the items and their number, going through them once.
+/// Returns `StatsError::EmptyCollection` if there are no items.
+fn sum_and_len<I>(items: I) -> Result<(I::Item, usize)>
+where
+    I: IntoIterator,
+    I::Item: NumExt,
+{
+    let (sum, len) = items
+        .into_iter()
+        .fold((I::Item::zero(), 0), |(sum, len), x| (sum + x, len + 1));
+    if len == 0 {
+        return Err(StatsError::EmptyCollection);
+    }
+    Ok((sum, len))
+}
+
+/// Divides `sum` by `count`, rounding the quotient down so the remainder is
+/// between 0 and `count`
+fn floor_div_rem<T: PrimInt>(sum: T, count: T) -> (T, T) {
+    let (quotient, remainder) = (sum / count, sum % count);
+    if remainder < T::zero() {
+        (quotient - T::one(), remainder + count)
+    } else {
+        (quotient, remainder)
+    }
+}
+
+/// Returns the items paired with their weights from `weights`, in the same
+/// order, leaving out the items with a weight of 0
+///
+/// # Panics
+/// Panics when it is iterated over if there aren't the same number of
+/// weights as items, or if a weight is negative or `NaN`
+fn with_weights<I, W>(items: I, weights: W) -> impl Iterator<Item = (I::Item, f64)>
+where
+    I: IntoIterator,
+    W: IntoIterator<Item = f64>,
+{
+    let mut items = items.into_iter();
+    let mut weights = weights.into_iter();
+    core::iter::from_fn(move || {
+        let Some(x) = items.next() else {
+            assert!(
+                weights.next().is_none(),
+                "there must be the same number of weights as items"
+            );
+            return None;
+        };
+        let w = weights
+            .next()
+            .expect("there must be the same number of weights as items");
+        assert!(w >= 0.0, "weights must not be negative or NaN");
+        Some((x, w))
+    })
+    .filter(|&(_, w)| w > 0.0)
+}
+
+/// Returns the sum of the squared differences between the items and `mean`
+fn sum_of_squares<I>(items: I, mean: &I::Item) -> I::Item
+where
+    I: IntoIterator,
+    I::Item: NumExt,
+{
+    items
+        .into_iter()
+        .map(|x| {
+            let diff = x - mean.clone();
+            diff.clone() * diff
+        })
+        .sum()
+}
+
+/// Returns `StatsError::NaNEncountered` if an item of the collection is `NaN`,
+/// with the name of the `statistic` and the index of the first `NaN`
+fn reject_nan<S>(items: &S, statistic: &'static str) -> core::result::Result<(), ErrorContext>
+where
+    S: Stats + ?Sized,
+{
+    match items.items().position(|x| is_nan(&x)) {
+        Some(index) => Err(ErrorContext::new(statistic, StatsError::NaNEncountered).at(index)),
+        None => Ok(()),
+    }
+}
+
+/// Returns the number of items, their sum and the sum of their squares as
+/// [`i128`]s, for exact arithmetic. Errors if there are no items, if an item
+/// doesn't fit in an [`i64`], or if the sum of the squares overflows.
+#[cfg(feature = "num-rational")]
+fn exact_sums<S>(items: &S) -> Result<(i128, i128, i128)>
+where
+    S: Stats + ?Sized,
+    S::Item: PrimInt,
+{
+    let (mut count, mut sum, mut squares) = (0i128, 0i128, 0i128);
+    for x in items.items() {
+        let x = i128::from(x.to_i64().ok_or(StatsError::CouldNotConvert {
+            from: DataType::Item,
+            to: DataType::I64,
+        })?);
+        count += 1;
+        // the sum can't overflow before the squares do
+        sum += x;
+        squares = squares.checked_add(x * x).ok_or(StatsError::Overflow)?;
+    }
+    if count == 0 {
+        return Err(StatsError::EmptyCollection);
+    }
+    Ok((count, sum, squares))
+}
+
+/// Converts a fraction in lowest terms to one of [`i64`]s, or
+/// errors with `StatsError::Overflow` if it doesn't fit
+#[cfg(feature = "num-rational")]
+fn narrow_ratio(ratio: Ratio<i128>) -> Result<Ratio<i64>> {
+    let (numer, denom) = ratio.into_raw();
+    match (i64::try_from(numer), i64::try_from(denom)) {
+        (Ok(numer), Ok(denom)) => Ok(Ratio::new_raw(numer, denom)),
+        _ => Err(StatsError::Overflow),
+    }
+}
+
+/// Returns the square root of `x`, calculated as an [`f64`]
+fn sqrt_of<T>(x: T) -> Result<T>
+where
+    T: NumExt + ToPrimitive,
+{
+    T::from_f64(match x.to_f64() {
+        Some(x) => x.sqrt(),
+        None => {
+            return Err(StatsError::CouldNotConvert {
+                from: DataType::Item,
+                to: DataType::F64,
+            })
+        }
+    })
+    .ok_or(StatsError::CouldNotConvert {
+        from: DataType::F64,
+        to: DataType::Item,
+    })
+}
+
+/// Returns an [`OnlineStats`] with every item of the collection pushed to it
+fn online_stats_of<I>(items: I) -> Result<OnlineStats>
+where
+    I: IntoIterator,
+    I::Item: ToPrimitive,
+{
+    let mut stats = OnlineStats::new();
+    for x in items {
+        stats.push(item_to_f64(x)?);
+    }
+    Ok(stats)
+}
+
+/// Returns the items converted to [`f64`], in order
+#[cfg(feature = "alloc")]
+fn values_of<I>(items: I) -> Result<Vec<f64>>
+where
+    I: IntoIterator,
+    I::Item: ToPrimitive,
+{
+    items.into_iter().map(item_to_f64).collect()
+}
+
+/// Returns the median and the median absolute deviation of the non-empty `sorted`
+#[cfg(feature = "alloc")]
+fn median_and_mad(mut sorted: Vec<f64>) -> (f64, f64) {
+    let median = quantile_of_sorted(&sorted, 0.5);
+    for x in &mut sorted {
+        *x = (*x - median).abs();
+    }
+    (median, median_in_place(&mut sorted))
+}
+
+/// Returns the indices of the items for which `is_outlier` returns `true`
+#[cfg(feature = "alloc")]
+fn outlier_indices<I, F>(items: I, mut is_outlier: F) -> Result<Vec<usize>>
+where
+    I: IntoIterator,
+    I::Item: ToPrimitive,
+    F: FnMut(f64) -> bool,
+{
+    let mut indices = Vec::new();
+    for (i, x) in items.into_iter().enumerate() {
+        if is_outlier(item_to_f64(x)?) {
+            indices.push(i);
+        }
+    }
+    Ok(indices)
+}
+
+/// Returns how many of `pair(sorted[i], sorted[j])` are at most `t`, over the
+/// pairs with `j >= i + offset`, where `pair` increases with its second argument
+#[cfg(feature = "alloc")]
+fn pair_values_at_most<F>(sorted: &[f64], offset: usize, pair: &F, t: f64) -> usize
+where
+    F: Fn(f64, f64) -> f64,
+{
+    sorted
+        .iter()
+        .enumerate()
+        .map(|(i, &x)| {
+            sorted[Ord::min(i + offset, sorted.len())..].partition_point(|&y| pair(x, y) <= t)
+        })
+        .sum()
+}
+
+/// Returns the `k`th smallest (counting from 1) of `pair(sorted[i], sorted[j])`
+/// over the pairs with `j >= i + offset`, where `pair` increases with its second
+/// argument. The range of values containing it is halved until only a few
+/// values fall in it, and only those are calculated and sorted, so the values
+/// of all the pairs are never calculated.
+#[cfg(feature = "alloc")]
+fn kth_pair_value<F>(sorted: &[f64], offset: usize, k: usize, pair: F) -> f64
+where
+    F: Fn(f64, f64) -> f64,
+{
+    let n = sorted.len();
+    let rows = || (0..n.saturating_sub(offset)).map(|i| (sorted[i], sorted[i + offset]));
+    // invariant: fewer than k values are at most `low`, and at least k are at most `high`
+    let mut low = rows()
+        .map(|(x, y)| pair(x, y))
+        .fold(f64::INFINITY, f64::min);
+    let mut high = rows()
+        .map(|(x, _)| pair(x, sorted[n - 1]))
+        .fold(f64::NEG_INFINITY, f64::max);
+    let mut below = pair_values_at_most(sorted, offset, &pair, low);
+    if below >= k {
+        return low;
+    }
+    let mut candidates = pair_values_at_most(sorted, offset, &pair, high) - below;
+    while candidates > n {
+        let middle = low + (high - low) / 2.0;
+        if middle <= low || middle >= high {
+            // no value between them, so every candidate is `high`
+            return high;
+        }
+        let at_most = pair_values_at_most(sorted, offset, &pair, middle);
+        if at_most >= k {
+            high = middle;
+            candidates = at_most - below;
+        } else {
+            low = middle;
+            candidates -= at_most - below;
+            below = at_most;
+        }
+    }
+    let mut values = Vec::with_capacity(candidates);
+    for (i, &x) in sorted.iter().enumerate() {
+        let row = &sorted[Ord::min(i + offset, n)..];
+        let first = row.partition_point(|&y| pair(x, y) <= low);
+        for &y in &row[first..] {
+            let value = pair(x, y);
+            if value > high {
+                break;
+            }
+            values.push(value);
+        }
+    }
+    values.sort_by(partial_cmp_nan_last);
+    values[k - below - 1]
+}
+
+/// Returns the `k`th smallest (counting from 1) of the two sorted sequences
+/// of lengths `a_len` and `b_len` whose items are returned by `a` and `b`
+#[cfg(feature = "alloc")]
+fn kth_of_two_sorted<A, B>(a: A, a_len: usize, b: B, b_len: usize, k: usize) -> f64
+where
+    A: Fn(usize) -> f64,
+    B: Fn(usize) -> f64,
+{
+    // find how many of the k smallest come from `a`
+    let (mut low, mut high) = (k.saturating_sub(b_len), Ord::min(k, a_len));
+    while low < high {
+        let from_a = low + (high - low) / 2;
+        if a(from_a) < b(k - from_a - 1) {
+            low = from_a + 1;
+        } else {
+            high = from_a;
+        }
+    }
+    let from_a = low;
+    match (from_a, k - from_a) {
+        (0, from_b) => b(from_b - 1),
+        (from_a, 0) => a(from_a - 1),
+        (from_a, from_b) => a(from_a - 1).max(b(from_b - 1)),
+    }
+}
+
+/// Returns the mean of the cosines and the mean of the sines of the items,
+/// which are the coordinates of the mean resultant vector of the angles
+fn mean_resultant<I>(items: I) -> Result<(f64, f64)>
+where
+    I: IntoIterator,
+    I::Item: ToPrimitive,
+{
+    let (mut cos, mut sin, mut count) = (0.0, 0.0, 0usize);
+    for x in items {
+        let (s, c) = item_to_f64(x)?.sin_cos();
+        cos += c;
+        sin += s;
+        count += 1;
+    }
+    if count == 0 {
+        return Err(StatsError::EmptyCollection);
+    }
+    Ok((cos / count as f64, sin / count as f64))
+}
+
+/// Returns the items that aren't `NaN` converted to [`f64`] and sorted
+#[cfg(feature = "alloc")]
+fn sorted_values<I>(items: I) -> Result<Vec<f64>>
+where
+    I: IntoIterator,
+    I::Item: ToPrimitive,
+{
+    let mut sorted = non_nan_values(items)?;
+    sorted.sort_by(partial_cmp_nan_last);
+    Ok(sorted)
+}
+
+/// Returns the items that aren't `NaN` converted to [`f64`], in order,
+/// checking that there is at least one
+#[cfg(feature = "alloc")]
+fn non_nan_values<I>(items: I) -> Result<Vec<f64>>
+where
+    I: IntoIterator,
+    I::Item: ToPrimitive,
+{
+    let mut values = Vec::new();
+    for x in items {
+        let x = item_to_f64(x)?;
+        if !x.is_nan() {
+            values.push(x);
+        }
+    }
+    if values.is_empty() {
+        return Err(StatsError::EmptyCollection);
+    }
+    Ok(values)
+}
+
+/// Returns the sum of the products of the differences from the mean of each
+/// item and the item `lag` places after it, and the number of items
+fn lagged_co_moment<S>(series: &S, lag: usize) -> Result<(f64, usize)>
+where
+    S: Stats + ?Sized,
+    S::Item: ToPrimitive,
+{
+    let count = series.non_zero_count()?;
+    if count <= lag {
+        return Err(StatsError::InsufficientData {
+            required: lag + 1,
+            actual: count,
+        });
+    }
+    let mut sum = 0.0;
+    for x in series.items() {
+        sum += item_to_f64(x)?;
+    }
+    let mean = sum / count as f64;
+
+    let mut sum_products = 0.0;
+    let lagged = series.items().skip(lag);
+    for (x, y) in series.items().zip(lagged) {
+        sum_products += (item_to_f64(x)? - mean) * (item_to_f64(y)? - mean);
+    }
+    Ok((sum_products, count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    extern crate std;
+    use std::vec;
+    use std::vec::Vec;
+
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_borrowed_collections() {
+        let slice: &[i32] = &[1, 2, 3];
+        assert_eq!(slice.sum(), 6);
+        assert_eq!([1.0, 2.0, 3.0].mean(), Ok(2.0));
+        let deque = std::collections::VecDeque::from([4, 5, 6]);
+        assert_eq!(deque.mean(), Ok(5));
+        assert_eq!(slice.items().count(), 3);
+
+        // the items don't have to be 'static
+        fn total<I: NumExt>(items: &[I]) -> I {
+            items.sum()
+        }
+        assert_eq!(total(slice), 6);
+    }
+
+    #[test]
+    fn test_fixed_capacity_collections() {
+        let mut heapless_vec = heapless::Vec::<i32, 8>::new();
+        heapless_vec.extend([4, 1, 3]);
+        assert_eq!(heapless_vec.sum(), 8);
+        assert_eq!(heapless_vec.variance(), Ok(2));
+        let array_vec = arrayvec::ArrayVec::<f64, 8>::from_iter([4.0, 1.0, 3.0]);
+        assert_relative_eq!(array_vec.mean().unwrap(), 8.0 / 3.0);
+        assert_eq!(Stats::max(&array_vec), Ok(4.0));
+        // a ring buffer of the last 3 values
+        let mut window = heapless::Deque::<f64, 3>::new();
+        for x in [9.0, 2.0, 4.0, 6.0] {
+            if window.is_full() {
+                window.pop_front();
+            }
+            window.push_back(x).unwrap();
+        }
+        assert_eq!(window.mean(), Ok(4.0));
+        assert_eq!(crate::Sorted::new(heapless_vec).median(), Ok(3.0));
+        // the streaming types can be kept in statics without an allocator
+        static STREAMING: (OnlineStats, crate::P2Quantile) =
+            (OnlineStats::new(), crate::P2Quantile::new(0.5));
+        assert_eq!(STREAMING.0.count(), 0);
+        assert_eq!(STREAMING.1.p(), 0.5);
+    }
+
+    #[test]
+    fn test_sum_vec() {
+        let v = vec![1, 2, 3];
+        assert_eq!(v.sum(), 6);
+
+        let v = vec![1.0, 2.0, 3.0];
+        assert_relative_eq!(v.sum(), 6.0);
+    }
+
+    #[test]
+    fn test_pairwise_sum_vec() {
+        assert_eq!(vec![1, 2, 3].pairwise_sum(), Ok(6.0));
+        assert_eq!(Vec::<f64>::new().pairwise_sum(), Ok(0.0));
+    }
+
+    #[test]
+    fn test_strategies_vec() {
+        use crate::summation::{Kahan, Naive};
+        let vec = vec![2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+        assert_eq!(vec.mean_with::<Naive>(), Ok(5.0));
+        assert_eq!(vec.variance_with::<Kahan>(), Ok(4.0));
+        assert_eq!(
+            Vec::<f64>::new().mean_with::<Kahan>(),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+
+    #[test]
+    fn test_checked_vec() {
+        assert_eq!(vec![u32::MAX, 1].checked_sum(), Err(StatsError::Overflow));
+        assert_eq!(vec![u32::MAX, 0].checked_mean(), Ok(u32::MAX / 2));
+        // the differences from the mean are negative for some items
+        assert_eq!(
+            vec![2u8, 4, 6].checked_variance(),
+            Err(StatsError::Overflow)
+        );
+        assert_eq!(vec![2i8, 4, 6].checked_variance(), Ok(2));
+        assert_eq!(
+            Vec::<i8>::new().checked_mean(),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+
+    #[test]
+    fn test_wrapping_saturating_vec() {
+        let vec: Vec<u16> = vec![u16::MAX, 2, 3];
+        assert_eq!(vec.wrapping_sum(), 4);
+        assert_eq!(vec.wrapping_mean(), Ok(1));
+        assert_eq!(vec.saturating_sum(), u16::MAX);
+        assert_eq!(vec.saturating_mean(), Ok(21845));
+        let vec: Vec<i32> = vec![i32::MIN, -1, i32::MAX];
+        assert_eq!(vec.wrapping_sum(), -2);
+        assert_eq!(vec.saturating_sum(), -1);
+        let empty: Vec<u8> = vec![];
+        assert_eq!(empty.wrapping_sum(), 0);
+        assert_eq!(empty.saturating_mean(), Err(StatsError::EmptyCollection));
+    }
+
+    #[test]
+    fn test_wide_vec() {
+        let vec = vec![i16::MAX, i16::MAX, i16::MIN + 1];
+        assert_eq!(vec.wide_sum(), i16::MAX as i64);
+        assert_eq!(vec.wide_mean(), Ok(i16::MAX / 3));
+        assert_eq!(vec![1.5f32, 2.5].wide_mean(), Ok(2.0));
+        assert_eq!(
+            Vec::<u8>::new().wide_mean(),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+
+    #[test]
+    fn test_mean_rounded_vec() {
+        let cases = [
+            // items, floor, ceil, toward zero, half even
+            (vec![1, 2], [1, 2, 1, 2]),
+            (vec![2, 3], [2, 3, 2, 2]),
+            (vec![-1, -2], [-2, -1, -1, -2]),
+            (vec![-2, -3], [-3, -2, -2, -2]),
+            (vec![-1, -1, 0], [-1, 0, 0, -1]),
+            (vec![4, 4], [4, 4, 4, 4]),
+        ];
+        let modes = [
+            Rounding::Floor,
+            Rounding::Ceil,
+            Rounding::TowardZero,
+            Rounding::HalfEven,
+        ];
+        for (items, expected) in cases {
+            for (mode, expected) in modes.into_iter().zip(expected) {
+                assert_eq!(items.mean_rounded(mode), Ok(expected), "{items:?} {mode:?}");
+            }
+            assert_eq!(items.mean_rounded(Rounding::TowardZero), items.mean());
+        }
+        let counter = PassCounter {
+            items: vec![1, 2, 4, 6],
+            passes: Default::default(),
+        };
+        assert_eq!(counter.mean_rounded(Rounding::Ceil), Ok(4));
+        assert_eq!(counter.passes.get(), 1);
+    }
+
+    #[cfg(feature = "num-rational")]
+    #[test]
+    fn test_exact_vec() {
+        let vec = vec![3u64, 1, 4, 1, 5, 9, 2, 6];
+        assert_eq!(vec.mean_exact(), Ok(Ratio::new(31, 8)));
+        assert_eq!(vec.variance_exact(), Ok(Ratio::new(423, 64)));
+        assert_relative_eq!(
+            423.0 / 64.0,
+            vec.variance_with::<crate::summation::Naive>().unwrap()
+        );
+        assert_eq!(
+            vec![i64::MAX, i64::MAX].mean_exact(),
+            Ok(Ratio::from(i64::MAX))
+        );
+        assert_eq!(
+            vec![i64::MAX, i64::MAX - 1].mean_exact(),
+            Err(StatsError::Overflow)
+        );
+        assert_eq!(
+            vec![u64::MAX].mean_exact(),
+            Err(StatsError::CouldNotConvert {
+                from: DataType::Item,
+                to: DataType::I64
+            })
+        );
+        assert_eq!(
+            Vec::<i8>::new().variance_exact(),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+
+    #[test]
+    fn test_variance_ddof_vec() {
+        let vec = vec![1, 2, 3, 4, 5];
+        assert_eq!(vec.variance_ddof(0), vec.variance());
+        assert_eq!(vec.variance_ddof(1), Ok(2));
+        assert_eq!(vec.variance_ddof(2), Ok(3));
+        assert_eq!(vec.std_dev_ddof(3), Ok(2));
+        assert_eq!(
+            vec.variance_ddof(5),
+            Err(StatsError::InsufficientData {
+                required: 6,
+                actual: 5
+            })
+        );
+        assert_eq!(
+            Vec::<f64>::new().variance_ddof(0),
+            Err(StatsError::EmptyCollection)
+        );
+        assert_eq!(vec![7.0].variance_ddof(0), Ok(0.0));
+        let counter = PassCounter {
+            items: vec,
+            passes: Default::default(),
+        };
+        assert_eq!(counter.variance_ddof(1), Ok(2));
+        assert_eq!(counter.passes.get(), 2);
+    }
+
+    #[test]
+    fn test_nan_vec() {
+        let vec = vec![f64::NAN, 4.0, 1.0, f64::NAN, 1.0];
+        assert_eq!(vec.nan_sum(), 6.0);
+        assert_eq!(vec.nan_mean(), Ok(2.0));
+        assert_eq!(vec.nan_variance(), Ok(2.0));
+        assert_eq!(vec.nan_min(), Ok(1.0));
+        assert_eq!(vec.nan_max(), Ok(4.0));
+        assert_eq!(vec.strict_sum(), Err(StatsError::NaNEncountered));
+        assert_eq!(vec.strict_mean(), Err(StatsError::NaNEncountered));
+        assert_eq!(vec.strict_variance(), Err(StatsError::NaNEncountered));
+        assert_eq!(vec.strict_min(), Err(StatsError::NaNEncountered));
+        assert_eq!(vec.strict_max(), Err(StatsError::NaNEncountered));
+        let only_nan = vec![f64::NAN];
+        assert_eq!(only_nan.nan_sum(), 0.0);
+        assert_eq!(only_nan.nan_mean(), Err(StatsError::EmptyCollection));
+        assert_eq!(only_nan.nan_max(), Err(StatsError::EmptyCollection));
+        // integers are never NaN
+        let ints = vec![3, 1, 2];
+        assert_eq!(ints.nan_mean(), ints.strict_mean());
+        assert_eq!(ints.strict_min(), Ok(1));
+    }
+
+    #[test]
+    fn test_error_context_vec() {
+        let vec = vec![1.0, f64::NAN, 2.0, f64::NAN];
+        let context = vec.strict_variance_with_context().unwrap_err();
+        assert_eq!(context.statistic, "variance");
+        assert_eq!(context.index, Some(1));
+        assert_eq!(context.error, StatsError::NaNEncountered);
+        assert_eq!(vec.strict_variance(), Err(StatsError::NaNEncountered));
+        assert_eq!(vec.strict_sum_with_context().unwrap_err().index, Some(1));
+        let fine = vec![1.0, 2.0];
+        assert_eq!(fine.strict_mean_with_context(), Ok(1.5));
+        assert_eq!(fine.strict_sum_with_context(), Ok(3.0));
+        let empty: Vec<f64> = vec![];
+        let context = empty.strict_variance_with_context().unwrap_err();
+        assert_eq!(context.index, None);
+        assert_eq!(context.error, StatsError::EmptyCollection);
+    }
+
+    #[test]
+    fn test_conditional_vec() {
+        let vec = vec![3, -1, 4, -1, 5, -9];
+        assert_eq!(vec.count_if(|&x| x < 0), 3);
+        assert_eq!(vec.sum_if(|&x| x < 0), -11);
+        assert_eq!(vec.mean_if(|&x| x > 0), Ok(4));
+        assert_eq!(vec.variance_if(|&x| x == -1), Ok(0));
+        assert_eq!(vec.mean_if(|&x| x > 5), Err(StatsError::EmptyCollection));
+        let floats = vec![1.0, f64::NAN, 3.0];
+        assert_eq!(floats.mean_if(|x| !x.is_nan()), floats.nan_mean());
+    }
+
+    #[test]
+    fn test_validity_report_vec() {
+        let report = vec![-3i8, 0, 7, -1].validity_report();
+        assert_eq!(report.count, 4);
+        assert_eq!(report.negative, 2);
+        assert_eq!(report.zero, 1);
+        assert!(report.is_finite());
+        let floats = vec![f32::NEG_INFINITY, f32::NAN, 2.5];
+        assert_eq!(floats.validity_report().finite(), 1);
+    }
+
+    #[test]
+    fn test_total_min_max_vec() {
+        let vec = vec![1.0, f64::NAN, -f64::NAN, f64::INFINITY];
+        assert_eq!(Stats::min(&vec), Ok(1.0));
+        assert_eq!(Stats::max(&vec), Ok(f64::INFINITY));
+        assert!(vec.total_min().unwrap().is_sign_negative());
+        assert!(vec.total_min().unwrap().is_nan());
+        assert!(vec.total_max().unwrap().is_nan());
+        assert_eq!(vec![3usize, 1, 2].total_max(), Ok(3));
+        assert_eq!(Stats::min(&vec![3usize, 1, 2]), Ok(1));
+        assert_eq!(
+            Vec::<f32>::new().total_min(),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_order_statistics_vec() {
+        let vec = vec![4.0, f64::NAN, 1.0, 3.0, 2.0];
+        assert_eq!(vec.median(), Ok(2.5));
+        assert_eq!(vec.quantile(1.0), Ok(4.0));
+        assert_eq!(vec.quantile(1.0 / 3.0), Ok(2.0));
+        assert_eq!(vec.ranks(), Ok(vec![4.0, 5.0, 1.0, 3.0, 2.0]));
+        assert_eq!(vec.quantiles(&[1.0, 0.5, 0.0]), Ok(vec![4.0, 2.5, 1.0]));
+        assert_eq!(
+            Vec::<f64>::new().quantiles(&[0.5]),
+            Err(StatsError::EmptyCollection)
+        );
+        assert_eq!(Vec::<f64>::new().median(), Err(StatsError::EmptyCollection));
+        assert_eq!(vec![2u8, 7, 7, 2, 9].mode(), Ok(2));
+        assert_eq!(Vec::<u8>::new().mode(), Err(StatsError::EmptyCollection));
+        assert_eq!(vec![2u8, 7, 7].tally().frequency(7), 2);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_order_statistics_infinite_vec() {
+        let vec = vec![1.0, 2.0, f64::INFINITY];
+        assert_eq!(vec.median(), Ok(2.0));
+        assert_eq!(vec.quantile(1.0), Ok(f64::INFINITY));
+        assert_eq!(vec.quantiles(&[0.5, 0.0]), Ok(vec![2.0, 1.0]));
+        assert_eq!(vec![1.0, f64::INFINITY].quantile(0.0), Ok(1.0));
+        assert_eq!(vec![1.0, f64::INFINITY].median(), Ok(f64::INFINITY));
+        assert_eq!(vec![f64::NEG_INFINITY, 1.0].median(), Ok(f64::NEG_INFINITY));
+    }
+
+    #[test]
+    fn test_weighted_means_vec() {
+        let vec = vec![1, 4, 16];
+        assert_relative_eq!(vec.weighted_geometric_mean([1.0; 3]).unwrap(), 4.0);
+        assert_relative_eq!(vec.weighted_geometric_mean([1.0, 0.0, 1.0]).unwrap(), 4.0);
+        assert_relative_eq!(
+            vec.weighted_harmonic_mean([1.0, 2.0, 1.0]).unwrap(),
+            4.0 / (1.0 + 0.5 + 1.0 / 16.0)
+        );
+        assert_eq!(vec![0.0, 5.0].weighted_geometric_mean([1.0, 1.0]), Ok(0.0));
+        assert_eq!(vec![0.0, 5.0].weighted_harmonic_mean([1.0, 1.0]), Ok(0.0));
+        assert_eq!(
+            vec.weighted_harmonic_mean([0.0; 3]),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+
+    #[test]
+    fn test_log_summary_vec() {
+        let vec = vec![1.0, 10.0, 100.0];
+        assert_relative_eq!(vec.log_summary().unwrap().geometric_mean, 10.0);
+        assert_eq!(vec![1.0, 0.0].log_summary(), Err(StatsError::OutOfDomain));
+        assert_eq!(
+            vec![2, -3].geometric_std_dev(),
+            Err(StatsError::OutOfDomain)
+        );
+        assert_eq!(
+            Vec::<f64>::new().geometric_std_dev(),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+
+    #[test]
+    fn test_weighted_means_negative() {
+        let vec = vec![2.0, -1.0];
+        assert_eq!(
+            vec.weighted_geometric_mean([1.0, 1.0]),
+            Err(StatsError::OutOfDomain)
+        );
+        assert_eq!(
+            vec.weighted_harmonic_mean([1.0, 1.0]),
+            Err(StatsError::OutOfDomain)
+        );
+        // items with a weight of 0 are left out, even if they are negative
+        assert_eq!(vec.weighted_harmonic_mean([1.0, 0.0]), Ok(2.0));
+    }
+
+    #[test]
+    fn test_survey_estimate_vec() {
+        let vec = vec![2.0, 4.0, 9.0];
+        // the same weights as repeating items, apart from the standard errors
+        let estimate = vec.survey_estimate([2.0, 1.0, 1.0]).unwrap();
+        assert_relative_eq!(estimate.mean, [2.0, 2.0, 4.0, 9.0].mean().unwrap());
+        assert_eq!(estimate.count, 3);
+        assert_eq!(estimate.weight_sum, 4.0);
+        assert_relative_eq!(estimate.effective_count, 16.0 / 6.0);
+        assert_relative_eq!(estimate.design_effect, 1.125);
+        // scaling the weights only changes the total
+        let scaled = vec.survey_estimate([200.0, 100.0, 100.0]).unwrap();
+        assert_relative_eq!(scaled.mean_std_err, estimate.mean_std_err);
+        assert_relative_eq!(scaled.total, estimate.total * 100.0);
+        assert_eq!(
+            vec.survey_estimate([1.0, 0.0, 0.0]),
+            Err(StatsError::InsufficientData {
+                required: 2,
+                actual: 1
+            })
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_bincount_vec() {
+        let statuses: Vec<u16> = vec![200, 404, 200, 500, 200];
+        let counts = statuses.bincount(599).unwrap();
+        assert_eq!(counts.len(), 501);
+        assert_eq!(counts[200], 3);
+        assert_eq!(counts[404], 1);
+        assert_eq!(counts.iter().sum::<usize>(), 5);
+        assert_eq!(
+            statuses.bincount(499),
+            Err(StatsError::InvalidParameter { name: "limit" })
+        );
+        assert_eq!(Vec::<u32>::new().bincount(10), Ok(vec![]));
+        assert_eq!(
+            vec![u128::MAX].bincount(usize::MAX).is_err(),
+            usize::BITS < 128
+        );
+        assert_eq!(
+            vec![usize::MAX].bincount(usize::MAX),
+            Err(StatsError::Overflow)
+        );
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_weighted_mode_vec() {
+        let vec = vec![2.5, 1.0, 2.5, 4.0];
+        let weights = [0.5, 2.0, 0.5, 0.0];
+        assert_eq!(vec.weighted_mode(weights, TieBreak::Smallest), Ok(1.0));
+        assert_eq!(vec.weighted_mode(weights, TieBreak::Largest), Ok(1.0));
+        let weights = [1.0, 2.0, 1.0, 2.0];
+        assert_eq!(vec.weighted_mode(weights, TieBreak::Smallest), Ok(1.0));
+        assert_eq!(vec.weighted_mode(weights, TieBreak::Largest), Ok(4.0));
+        assert_eq!(
+            vec.weighted_mode([0.0; 4], TieBreak::Smallest),
+            Err(StatsError::EmptyCollection)
+        );
+    }
 
-    extern crate std;
-    use std::prelude::rust_2021::*;
-    use std::vec;
+    #[cfg(feature = "alloc")]
+    #[test]
+    #[should_panic(expected = "there must be the same number of weights as items")]
+    fn test_weighted_mode_too_many_weights() {
+        let _ = vec![1, 2].weighted_mode([1.0; 3], TieBreak::Smallest);
+    }
 
-    use approx::assert_relative_eq;
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_seven_number_summary_vec() {
+        let vec: Vec<f64> = (0..=80).map(f64::from).rev().collect();
+        let summary = vec.seven_number_summary_at(0.125).unwrap();
+        assert_eq!(
+            summary.to_array(),
+            [0.0, 10.0, 20.0, 40.0, 60.0, 70.0, 80.0]
+        );
+        assert_eq!(summary.quartile_skewness(), 0.0);
+        assert_eq!(
+            vec![f64::NAN].seven_number_summary(),
+            Err(StatsError::EmptyCollection)
+        );
+    }
 
+    #[cfg(feature = "alloc")]
     #[test]
-    fn test_sum_vec() {
-        let v = vec![1, 2, 3];
-        assert_eq!(v.sum(), 6);
+    #[should_panic]
+    fn test_quantile_out_of_range() {
+        let _ = vec![1.0].quantile(-0.1);
+    }
 
-        let v = vec![1.0, 2.0, 3.0];
-        assert_relative_eq!(v.sum(), 6.0);
+    #[test]
+    fn test_big_integers_vec() {
+        use num_bigint::BigInt;
+        // the sum of these doesn't fit in an i128
+        let ids: Vec<BigInt> = [u128::MAX, u128::MAX - 4, 2]
+            .into_iter()
+            .map(BigInt::from)
+            .collect();
+        let sum = BigInt::from(u128::MAX) * 2u8 - 2u8;
+        let mean = sum.clone() / 3u8;
+        assert_eq!(ids.sum(), sum);
+        assert_eq!(ids.mean(), Ok(mean.clone()));
+        assert_eq!(Stats::max(&ids), Ok(BigInt::from(u128::MAX)));
+        assert_eq!(Stats::min(&ids), Ok(BigInt::from(2)));
+        let squares: BigInt = ids.iter().map(|x| (x - &mean) * (x - &mean)).sum();
+        assert_eq!(ids.variance(), Ok(squares / 3u8));
+        assert_eq!(ids.count_of(BigInt::from(2)), 1);
+    }
+
+    #[test]
+    fn test_root_std_dev_vec() {
+        let vec: Vec<i64> = vec![1, 2, 3, 4, 5, 6, 7];
+        assert_eq!(vec.variance(), Ok(4));
+        assert_eq!(vec.root_std_dev(), Ok(2));
+        let floats = vec![2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+        assert_eq!(floats.root_std_dev(), floats.float_std_dev());
+        assert_eq!(
+            Vec::<u8>::new().root_std_dev(),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+
+    #[test]
+    fn test_float_std_dev_vec() {
+        let vec: Vec<f32> = vec![0.1, 0.2, 0.4, 0.8];
+        assert_eq!(vec.float_std_dev(), Ok(vec.variance().unwrap().sqrt()));
+        assert_relative_eq!(
+            vec.float_std_dev().unwrap(),
+            vec.std_dev().unwrap(),
+            max_relative = 1e-6
+        );
+        assert_eq!(
+            Vec::<f64>::new().float_std_dev(),
+            Err(StatsError::EmptyCollection)
+        );
     }
 
     #[test]
@@ -220,6 +4848,40 @@ mod tests {
         assert_eq!(v.count(), 3);
     }
 
+    /// A collection of a given number of ones, which are never stored
+    struct Ones(usize);
+
+    impl<'a> IntoIterator for &'a Ones {
+        type Item = &'a f64;
+        type IntoIter = core::iter::RepeatN<&'a f64>;
+
+        fn into_iter(self) -> Self::IntoIter {
+            core::iter::repeat_n(&1.0, self.0)
+        }
+    }
+
+    #[test]
+    fn test_count_exact_size() {
+        // far too many to go through, so this only passes if the length is used
+        assert_eq!(Ones(1 << 60).count(), 1 << 60);
+        assert_eq!(Ones(1 << 60).non_zero_count(), Ok(1 << 60));
+        assert_eq!(Ones(0).non_zero_count(), Err(StatsError::EmptyCollection));
+        // a filtered iterator doesn't know its length, so it is counted
+        let odd: Vec<i32> = (0..7).filter(|x| x % 2 == 1).collect();
+        assert_eq!(odd.count(), 3);
+    }
+
+    #[test]
+    fn test_count_of_vec() {
+        let v = vec![1, 2, 2, 3];
+        assert_eq!(v.count_of(2), 2);
+        assert_eq!(v.count_of(5), 0);
+
+        let v = vec![1.0, f64::NAN, f64::NAN];
+        assert_eq!(v.count_of(f64::NAN), 2);
+        assert_eq!(v.count_of(1.0), 1);
+    }
+
     #[test]
     fn test_mean_vec() {
         let v = vec![1, 2, 3];
@@ -238,6 +4900,34 @@ mod tests {
         assert_relative_eq!(v.variance().unwrap(), 2.0 / 3.0);
     }
 
+    /// A collection that counts how many times it has been iterated over
+    struct PassCounter<T> {
+        items: Vec<T>,
+        passes: core::cell::Cell<usize>,
+    }
+
+    impl<'a, T> IntoIterator for &'a PassCounter<T> {
+        type Item = &'a T;
+        type IntoIter = core::slice::Iter<'a, T>;
+
+        fn into_iter(self) -> Self::IntoIter {
+            self.passes.set(self.passes.get() + 1);
+            self.items.iter()
+        }
+    }
+
+    #[test]
+    fn test_variance_passes() {
+        let counter = PassCounter {
+            items: vec![1.0, 2.0, 3.0, 6.0],
+            passes: Default::default(),
+        };
+        assert_eq!(counter.mean(), Ok(3.0));
+        assert_eq!(counter.passes.get(), 1);
+        assert_eq!(counter.std_dev(), Ok(3.5_f64.sqrt()));
+        assert_eq!(counter.passes.get(), 3);
+    }
+
     #[test]
     fn test_std_dev_vec() {
         let v = vec![1.0, 2.0, 3.0];
@@ -255,7 +4945,7 @@ mod tests {
     #[test]
     fn test_non_zero_count_as_item_fail() {
         assert_eq!(
-            Vec::<i8>::from_iter(std::iter::repeat(1).take(128)).non_zero_count_into_item(),
+            Vec::<i8>::from_iter(std::iter::repeat_n(1, 128)).non_zero_count_into_item(),
             Err(StatsError::CouldNotConvert {
                 from: DataType::Usize,
                 to: DataType::Item
@@ -277,7 +4967,7 @@ mod tests {
         assert_eq!(<Vec<i32> as Stats>::min(&v), Ok(1));
 
         let v = vec![1.0, 2.0, 3.0];
-        assert_eq!(v.min(), Ok(1.0));
+        assert_eq!(Stats::min(&v), Ok(1.0));
     }
 
     #[test]
@@ -286,7 +4976,183 @@ mod tests {
         assert_eq!(<Vec<i32> as Stats>::max(&v), Ok(3));
 
         let v = vec![1.0, 2.0, 3.0];
-        assert_eq!(v.max(), Ok(3.0));
+        assert_eq!(Stats::max(&v), Ok(3.0));
+    }
+
+    #[test]
+    fn test_run_stats_vec() {
+        let runs = vec![1.0, 2.0, 3.0, 4.0].run_stats().unwrap();
+        assert_eq!(runs.threshold(), 2.5);
+        assert_eq!(runs.runs(), 2);
+        assert_eq!(
+            Vec::<i32>::new().run_stats_about(0.0),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+
+    #[test]
+    fn test_hhi_vec() {
+        assert_eq!(vec![0.5, 0.5].hhi(), Ok(0.5));
+        assert_eq!(vec![3, 1].hhi(), Ok(0.625));
+        assert_eq!(Vec::<i32>::new().hhi(), Err(StatsError::EmptyCollection));
+        assert_eq!(vec![0, 0].hhi(), Err(StatsError::OutOfDomain));
+        assert_eq!(vec![3e200, 1e200].hhi(), Ok(0.625));
+    }
+
+    #[test]
+    fn test_circular_vec() {
+        let angles = vec![0.1, 0.3, 6.2, 0.5, 5.9];
+        assert_relative_eq!(
+            angles.circular_mean().unwrap(),
+            0.08769914252713158,
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(
+            angles.circular_variance().unwrap(),
+            0.04594471588986171,
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(
+            angles.circular_std_dev().unwrap(),
+            0.30670395958282587,
+            epsilon = 1e-12
+        );
+        // cancellation in the logarithm leaves an error near the square root of the epsilon
+        assert!(vec![2.0, 2.0, 2.0].circular_std_dev().unwrap() < 1e-7);
+        assert_eq!(
+            Vec::<f64>::new().circular_mean(),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+
+    #[test]
+    fn test_hhi_invalid_items() {
+        assert_eq!(vec![2, -1].hhi(), Err(StatsError::OutOfDomain));
+        assert_eq!(vec![2.0, f64::NAN].hhi(), Err(StatsError::NaNEncountered));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_qcut_vec() {
+        let data: Vec<f64> = vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, f64::NAN];
+        let (labels, edges) = data.qcut(4).unwrap();
+        assert_eq!(edges, vec![0.0, 1.75, 3.5, 5.25, 7.0]);
+        assert_eq!(
+            labels,
+            vec![
+                Some(0),
+                Some(0),
+                Some(1),
+                Some(1),
+                Some(2),
+                Some(2),
+                Some(3),
+                Some(3),
+                None
+            ]
+        );
+        assert_eq!(Vec::<f64>::new().qcut(2), Err(StatsError::EmptyCollection));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_cut_vec() {
+        let data = vec![0, 10, 5, 3, 7, 20];
+        let (labels, counts) = data.cut(2).unwrap();
+        assert_eq!(
+            labels,
+            vec![Some(0), Some(1), Some(0), Some(0), Some(0), Some(1)]
+        );
+        assert_eq!(counts.as_slice(), &[(4, 0), (2, 1)]);
+
+        let (labels, _) = vec![2.0, 2.0].cut(3).unwrap();
+        assert_eq!(labels, vec![Some(1), Some(1)]);
+        let (labels, _) = vec![1e20, 1e20].cut(3).unwrap();
+        assert_eq!(labels, vec![Some(1), Some(1)]);
+        let (labels, _) = vec![f64::MAX].cut(2).unwrap();
+        assert_eq!(labels, vec![Some(1)]);
+
+        assert_eq!(
+            vec![1.0, f64::INFINITY].cut(3),
+            Err(StatsError::InfinityEncountered)
+        );
+        assert_eq!(
+            vec![1.0, 1.0 + f64::EPSILON].cut(10),
+            Err(StatsError::InvalidParameter { name: "n" })
+        );
+        assert_eq!(vec![-f64::MAX, f64::MAX].cut(2), Err(StatsError::Overflow));
+
+        let (labels, counts) = data.cut_edges(&[0.0, 5.0, 10.0]).unwrap();
+        assert_eq!(
+            labels,
+            vec![Some(0), Some(1), Some(1), Some(0), Some(1), None]
+        );
+        assert_eq!(counts.frequency(1), 3);
+        assert_eq!(counts.len(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    #[should_panic]
+    fn test_cut_edges_decreasing() {
+        let _ = vec![1.0].cut_edges(&[1.0, 0.0]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_grouped_stats_vec() {
+        let data = vec![1.0, 12.0, 3.0, 15.0, 2.0, 11.0];
+        let groups = data.grouped_stats(|x| x >= 10.0).unwrap();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[&false].count, 3);
+        assert_relative_eq!(groups[&false].mean, 2.0);
+        assert_relative_eq!(groups[&true].variance, 26.0 / 9.0);
+        assert_eq!(groups[&true].min, 11.0);
+        assert!(Vec::<f64>::new().grouped_stats(|_| 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_autocorrelation_vec() {
+        // a slowly rising series is strongly correlated with itself one step later
+        let data = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+        assert_relative_eq!(data.autocorrelation(1).unwrap(), 0.7);
+        assert_relative_eq!(data.autocorrelation(3).unwrap(), 0.1484848484848485);
+        assert_eq!(
+            data.autocorrelation(10),
+            Err(StatsError::InsufficientData {
+                required: 11,
+                actual: 10
+            })
+        );
+        assert_eq!(
+            vec![2.0, 2.0].autocorrelation(1),
+            Err(StatsError::ZeroVariance)
+        );
+    }
+
+    #[test]
+    fn test_autocovariance_vec() {
+        let data = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+        assert_relative_eq!(data.autocovariance(0).unwrap(), 8.25);
+        assert_relative_eq!(data.autocovariance(2).unwrap(), 3.4);
+        assert_relative_eq!(data.unbiased_autocovariance(2).unwrap(), 4.25);
+        assert_eq!(
+            vec![1.0].unbiased_autocovariance(1),
+            Err(StatsError::InsufficientData {
+                required: 2,
+                actual: 1
+            })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_acf_vec() {
+        let data = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+        let acf = data.acf(3).unwrap();
+        assert_eq!(acf.len(), 4);
+        assert_relative_eq!(acf[0], 1.0);
+        assert_relative_eq!(acf[2], 0.4121212121212121);
     }
 
     #[test]
@@ -297,4 +5163,517 @@ mod tests {
         let v = vec![1.0, 2.0, 3.0];
         assert_eq!(v.range(), Ok(2.0));
     }
+
+    #[test]
+    fn test_t_test_two_sample_vec() {
+        let a = vec![1, 2, 3, 4];
+        let b = vec![3, 4, 5, 6];
+        let welch = a.t_test_two_sample(&b).unwrap();
+        let pooled = a.pooled_t_test_two_sample(&b).unwrap();
+        // with equal sizes and variances the two tests agree
+        assert_relative_eq!(welch.statistic, -2.1908902300206643, epsilon = 1e-12);
+        assert_relative_eq!(welch.statistic, pooled.statistic, epsilon = 1e-12);
+        assert_relative_eq!(welch.degrees_of_freedom, 6.0, epsilon = 1e-12);
+        assert_eq!(
+            a.t_test_two_sample(&vec![1]),
+            Err(StatsError::InsufficientData {
+                required: 2,
+                actual: 1
+            })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ks_test_vec() {
+        let vec = vec![0.9, 0.1, f64::NAN, 0.5, 0.35, 0.52, 0.3];
+        let test = vec.ks_test(|x| x).unwrap();
+        assert_relative_eq!(test.statistic, 0.31333333333333335, epsilon = 1e-15);
+        assert_eq!(
+            vec![f64::NAN].ks_test_two_sample(&vec),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+
+    #[test]
+    fn test_sign_test_vec() {
+        let vec = vec![5, 7, 3, 9, 6, 8, 5, 10];
+        let test = vec.sign_test(5).unwrap();
+        assert_eq!(test.statistic, 5.0);
+        // 5 above and 1 below, out of 6 once the items equal to 5 are dropped
+        assert_relative_eq!(test.p_value, 14.0 / 64.0, epsilon = 1e-14);
+        assert_eq!(vec![5, 5].sign_test(5), Err(StatsError::ZeroVariance));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_runs_test_vec() {
+        let vec = vec![
+            3, 1, 2, 5, 4, 6, 8, 7, 9, 10, 12, 11, 14, 13, 15, 17, 16, 18, 20, 19,
+        ];
+        let test = vec.runs_test().unwrap();
+        assert_eq!(test.statistic, 2.0);
+        assert_relative_eq!(test.p_value, 3.5462304914688456e-05, epsilon = 1e-15);
+        assert_eq!(vec![1, 1, 1].runs_test(), Err(StatsError::ZeroVariance));
+        assert_eq!(
+            Vec::<f64>::new().runs_test(),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+
+    #[test]
+    fn test_ljung_box_vec() {
+        let vec = vec![0.5, 1.2, 0.8, 1.9, 1.4, 2.2, 1.7, 2.6, 2.0, 2.9, 2.3, 3.1];
+        let test = vec.ljung_box(3).unwrap();
+        assert_relative_eq!(test.statistic, 7.971274760669424, epsilon = 1e-12);
+        assert_relative_eq!(test.p_value, 0.046609115195973064, epsilon = 1e-12);
+        assert_eq!(
+            vec![1.0, 2.0].ljung_box(2),
+            Err(StatsError::InsufficientData {
+                required: 3,
+                actual: 2
+            })
+        );
+        assert_eq!(vec![1.0, 1.0].ljung_box(1), Err(StatsError::ZeroVariance));
+    }
+
+    #[test]
+    fn test_mean_confidence_interval_vec() {
+        let vec = vec![4.2, 5.1, 3.9, 4.8, 5.5, 4.4, 4.9, 5.0, 4.6, 4.1, 5.3];
+        let (lower, upper) = vec.mean_confidence_interval(0.95).unwrap();
+        assert_relative_eq!(lower, 4.363315311922814, epsilon = 1e-12);
+        assert_relative_eq!(upper, 5.054866506259004, epsilon = 1e-12);
+        assert_eq!(
+            vec![1].mean_confidence_interval(0.95),
+            Err(StatsError::InsufficientData {
+                required: 2,
+                actual: 1
+            })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "rand")]
+    fn test_bootstrap_vec() {
+        use rand::rngs::SmallRng;
+        use rand::SeedableRng;
+
+        let vec = vec![3, 1, 4, 1, 5, 9, 2, 6];
+        let mut rng = SmallRng::seed_from_u64(3);
+        let bootstrap = vec
+            .bootstrap(
+                |sample| Ok(sample.iter().copied().fold(f64::MIN, f64::max)),
+                500,
+                &mut rng,
+            )
+            .unwrap();
+        assert_eq!(bootstrap.estimate(), 9.0);
+        // the maximum of a resample is never more than the maximum of the items
+        assert!(bootstrap.bias() < 0.0);
+        assert_eq!(bootstrap.distribution()[499], 9.0);
+        assert_eq!(
+            vec.bootstrap(|_| Err(StatsError::ZeroVariance), 10, &mut rng),
+            Err(StatsError::ZeroVariance)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_jackknife_vec() {
+        let vec = vec![1, 2, 3, 4, 10];
+        let jackknife = vec.jackknife(|sample| sample.mean()).unwrap();
+        assert_eq!(jackknife.estimate, 4.0);
+        assert_eq!(jackknife.leave_one_out, vec![4.75, 4.5, 4.25, 4.0, 2.5]);
+        assert_relative_eq!(jackknife.bias, 0.0, epsilon = 1e-12);
+        assert_eq!(
+            Vec::<f64>::new().jackknife(|sample| sample.mean()),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+
+    #[test]
+    fn test_cohens_d_vec() {
+        let a = vec![1, 2, 3, 4, 5];
+        let b = vec![3, 4, 5, 6, 7];
+        // the pooled standard deviation is sqrt(2.5)
+        assert_relative_eq!(
+            a.cohens_d(&b).unwrap(),
+            -2.0 / 2.5_f64.sqrt(),
+            epsilon = 1e-12
+        );
+        assert!(a.hedges_g(&b).unwrap() > a.cohens_d(&b).unwrap());
+        assert_eq!(
+            a.cohens_d(&vec![1]),
+            Err(StatsError::InsufficientData {
+                required: 2,
+                actual: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_grubbs_test_vec() {
+        let vec = vec![12, 14, 13, 2, 15, 13, 14, 12];
+        let test = vec.grubbs_test(Tail::Lower, 0.05).unwrap();
+        assert_eq!((test.index, test.value), (3, 2.0));
+        assert!(test.is_outlier);
+        assert_eq!(vec.grubbs_test(Tail::Both, 0.05).unwrap().index, 3);
+        assert!(!vec.grubbs_test(Tail::Upper, 0.05).unwrap().is_outlier);
+        assert_eq!(
+            vec![1, 2].grubbs_test(Tail::Both, 0.05),
+            Err(StatsError::InsufficientData {
+                required: 3,
+                actual: 2
+            })
+        );
+        assert_eq!(
+            vec![1, 1, 1].grubbs_test(Tail::Both, 0.05),
+            Err(StatsError::ZeroVariance)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_outliers_vec() {
+        let vec = vec![4, 5, 5, 6, 5, 4, 6, 5, 40, 5, -20, 5];
+        assert_eq!(vec.outliers_zscore(2.5).unwrap(), vec![8]);
+        assert_eq!(vec.outliers_zscore(2.0).unwrap(), vec![8, 10]);
+        // the median is 5 and the MAD is 0.5
+        assert_eq!(vec.outliers_modified_zscore(3.5).unwrap(), vec![8, 10]);
+        assert_eq!(
+            vec.outliers_modified_zscore(1.3).unwrap(),
+            vec![0, 3, 5, 6, 8, 10]
+        );
+        assert_eq!(
+            vec![1.0, 2.0, f64::NAN, 3.0, 100.0].outliers_modified_zscore(3.5),
+            Ok(vec![4])
+        );
+        assert_eq!(
+            vec![3, 3, 3, 1, 9].outliers_modified_zscore(3.5),
+            Err(StatsError::ZeroVariance)
+        );
+        assert_eq!(
+            vec![3, 3].outliers_zscore(3.0),
+            Err(StatsError::ZeroVariance)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_hodges_lehmann_vec() {
+        // compare with the median of every average, with plenty of ties
+        let mut state = 12345_u64;
+        for n in [1, 2, 3, 10, 57, 200] {
+            let vec: Vec<f64> = (0..n)
+                .map(|_| {
+                    state = state
+                        .wrapping_mul(6364136223846793005)
+                        .wrapping_add(1442695040888963407);
+                    ((state >> 33) % 50) as f64 / 4.0
+                })
+                .collect();
+            let mut averages = Vec::new();
+            for i in 0..n {
+                for j in i..n {
+                    averages.push((vec[i] + vec[j]) / 2.0);
+                }
+            }
+            averages.sort_by(partial_cmp_nan_last);
+            assert_eq!(vec.hodges_lehmann(), Ok(quantile_of_sorted(&averages, 0.5)));
+        }
+        assert_eq!(vec![2.5, f64::NAN].hodges_lehmann(), Ok(2.5));
+        assert_eq!(
+            Vec::<f64>::new().hodges_lehmann(),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_biweight_vec() {
+        let vec = vec![4.1, 3.9, 4.0, 4.2, 9.0, 3.8, 4.1, 9.5];
+        assert_relative_eq!(
+            vec.biweight_location().unwrap(),
+            4.025988929391116,
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(
+            vec.biweight_midvariance().unwrap(),
+            0.03402979009164377,
+            epsilon = 1e-12
+        );
+        let vec = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 100];
+        assert_relative_eq!(
+            vec.biweight_location().unwrap(),
+            5.0596483196556115,
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(
+            vec.biweight_midvariance().unwrap(),
+            8.205569114796472,
+            epsilon = 1e-12
+        );
+        // more than half the items are the same, so the MAD is 0
+        let vec = vec![2, 2, 2, 3, 50];
+        assert_eq!(vec.biweight_location(), Ok(2.0));
+        assert_eq!(vec.biweight_midvariance(), Ok(0.0));
+        assert_eq!(
+            Vec::<f64>::new().biweight_location(),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_qn_sn_vec() {
+        let vec = vec![4.1, 3.9, 4.0, 4.2, 9.0, 3.8, 4.1, 9.5];
+        assert_relative_eq!(vec.qn().unwrap(), 0.2972902200000003, epsilon = 1e-12);
+        assert_relative_eq!(vec.sn().unwrap(), 0.23971260000000022, epsilon = 1e-12);
+        let vec = vec![
+            12.4, 11.9, 12.1, 12.6, 12.0, 11.7, 12.3, 12.2, 30.0, 12.1, 12.5,
+        ];
+        assert_relative_eq!(vec.qn().unwrap(), 0.39420806451612767, epsilon = 1e-12);
+        assert_relative_eq!(vec.sn().unwrap(), 0.38966138613861484, epsilon = 1e-12);
+        assert_eq!(
+            vec![1.0, f64::NAN].qn(),
+            Err(StatsError::InsufficientData {
+                required: 2,
+                actual: 1
+            })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_qn_sn_large_vec() {
+        // compare with the definitions, calculating every distance
+        let mut state = 99_u64;
+        let vec: Vec<f64> = (0..301)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                ((state >> 33) % 1000) as f64 / 10.0
+            })
+            .collect();
+        let n = vec.len();
+        let mut sorted = vec.clone();
+        sorted.sort_by(partial_cmp_nan_last);
+        let mut distances = Vec::new();
+        for i in 0..n {
+            for j in i + 1..n {
+                distances.push(sorted[j] - sorted[i]);
+            }
+        }
+        distances.sort_by(partial_cmp_nan_last);
+        let h = n / 2 + 1;
+        let factor = n as f64 / (n as f64 + 1.4);
+        assert_eq!(
+            vec.qn(),
+            Ok(2.2219 * factor * distances[h * (h - 1) / 2 - 1])
+        );
+
+        let mut medians: Vec<f64> = vec
+            .iter()
+            .map(|x| {
+                let mut distances: Vec<f64> = vec.iter().map(|y| (x - y).abs()).collect();
+                distances.sort_by(partial_cmp_nan_last);
+                distances[n / 2]
+            })
+            .collect();
+        medians.sort_by(partial_cmp_nan_last);
+        let factor = n as f64 / (n as f64 - 0.9);
+        assert_eq!(vec.sn(), Ok(1.1926 * factor * medians[n.div_ceil(2) - 1]));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_mad_vec() {
+        let vec = vec![2.0, 6.0, f64::NAN, 1.0, 9.0, 4.0, 5.0];
+        // the median is 4.5 and the distances from it are 0.5, 0.5, 1.5, 2.5, 3.5 and 4.5
+        assert_eq!(vec.mad(), Ok(2.0));
+        assert_eq!(vec.mad_scaled(), Ok(2.9652));
+        assert_eq!(vec.mad_scaled_by(0.5), Ok(1.0));
+        assert_eq!(vec![f64::NAN].mad(), Err(StatsError::EmptyCollection));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_robust_scaled_vec() {
+        let vec = vec![2.0, 6.0, f64::NAN, 1.0, 9.0, 4.0, 5.0];
+        // the quartiles are 2.5 and 5.75
+        let scaled = vec.robust_scaled().unwrap();
+        assert_eq!(scaled[1], 1.5 / 3.25);
+        assert_eq!(scaled[3], -3.5 / 3.25);
+        assert!(scaled[2].is_nan());
+        let scaled = vec.robust_scaled_with(RobustScale::Mad).unwrap();
+        assert_eq!(scaled[4], 4.5 / 2.9652);
+        assert!(scaled[2].is_nan());
+        assert_eq!(
+            vec![1, 1, 1, 1, 5].robust_scaled(),
+            Err(StatsError::ZeroVariance)
+        );
+        assert_eq!(
+            vec![1, 2, 2, 2, 5].robust_scaled_with(RobustScale::Mad),
+            Err(StatsError::ZeroVariance)
+        );
+        assert_eq!(
+            vec![f64::NAN].robust_scaled(),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_box_cox_vec() {
+        let vec = vec![2.0, f64::NAN, 8.0, 4.0];
+        let logs = vec.box_cox(0.0).unwrap();
+        assert_eq!(logs[0], 2.0f64.ln());
+        assert!(logs[1].is_nan());
+        let lambda = vec.box_cox_optimal().unwrap();
+        let back = vec
+            .box_cox(lambda)
+            .unwrap()
+            .inverse_box_cox(lambda)
+            .unwrap();
+        assert_relative_eq!(back[2], 8.0, epsilon = 1e-9);
+        assert!(back[1].is_nan());
+        assert_eq!(
+            vec![f64::NAN, 3.0].box_cox_optimal(),
+            Err(StatsError::InsufficientData {
+                required: 2,
+                actual: 1
+            })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_box_cox_not_positive() {
+        assert_eq!(vec![1.0, 0.0].box_cox(1.0), Err(StatsError::OutOfDomain));
+        assert_eq!(vec![1, -2, 3].box_cox(0.0), Err(StatsError::OutOfDomain));
+        assert_eq!(
+            vec![1.0, 2.0, 0.0].box_cox_optimal(),
+            Err(StatsError::OutOfDomain)
+        );
+        assert_eq!(
+            vec![f64::NAN, -1.0, 2.0].box_cox_optimal(),
+            Err(StatsError::OutOfDomain)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_half_sample_mode_vec() {
+        let vec = vec![0.5, 1.2, 1.9, 2.0, 2.05, 2.1, 2.4, 3.1, 4.8, 7.5, 7.6];
+        assert_relative_eq!(vec.half_sample_mode().unwrap(), 2.025, epsilon = 1e-12);
+        assert_eq!(vec![3].half_sample_mode(), Ok(3.0));
+        assert_eq!(vec![3, 4].half_sample_mode(), Ok(3.5));
+        assert_eq!(vec![1, 2, 3].half_sample_mode(), Ok(2.0));
+        assert_eq!(vec![1, 2, 5].half_sample_mode(), Ok(1.5));
+        assert_eq!(
+            vec![f64::NAN].half_sample_mode(),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "rand")]
+    fn test_dip_test_vec() {
+        use rand::rngs::SmallRng;
+        use rand::SeedableRng;
+
+        let mut rng = SmallRng::seed_from_u64(5);
+        let unimodal = vec![1, 3, 4, 5, 5, 6, 6, 6, 7, 7, 8, 9, 11];
+        let test = unimodal.dip_test(500, &mut rng).unwrap();
+        assert_eq!(Ok(test.statistic), unimodal.dip());
+        assert!(test.p_value > 0.5);
+        let bimodal = vec![1, 2, 2, 3, 3, 3, 4, 12, 13, 13, 14, 14, 14, 15];
+        assert!(bimodal.dip_test(500, &mut rng).unwrap().p_value < 0.05);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_qq_points_vec() {
+        let vec: Vec<u32> = (1..=12).rev().collect();
+        let points = vec.qq_points_with(|p| p).unwrap();
+        // with more than 10 items the plotting positions are (i - 0.5) / n
+        assert_eq!(points[0], (0.5 / 12.0, 1.0));
+        assert_eq!(points[11], (11.5 / 12.0, 12.0));
+        let points = vec![2.0, f64::NAN, 1.0].qq_points().unwrap();
+        assert_eq!(points.len(), 2);
+        assert_relative_eq!(points[0].0, -points[1].0, epsilon = 1e-15);
+        // the quantile of (1 - 3/8) / (2 + 1/4)
+        assert_relative_eq!(points[0].0, -0.5894557978497785, epsilon = 1e-9);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_fit_normal_vec() {
+        let fit = vec![1, 2, 3, 4, 5].fit_normal().unwrap();
+        assert_eq!(fit.distribution.mean(), 3.0);
+        assert_eq!(fit.distribution.std_dev(), 2.0_f64.sqrt());
+        assert_eq!(vec![7, 7].fit_normal(), Err(StatsError::ZeroVariance));
+        assert_eq!(
+            vec![0.0, 1.0, f64::INFINITY].fit_normal(),
+            Err(StatsError::InfinityEncountered)
+        );
+        assert_eq!(
+            vec![f64::NEG_INFINITY, 1.0].fit_normal(),
+            Err(StatsError::InfinityEncountered)
+        );
+        assert_eq!(vec![-1e300, 1e300].fit_normal(), Err(StatsError::Overflow));
+        assert_eq!(
+            Vec::<f64>::new().fit_normal(),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_fit_generalized_pareto_vec() {
+        let fit = vec![0.0, 20.0, f64::NAN, 30.0, 45.0, 70.0]
+            .fit_generalized_pareto(10.0)
+            .unwrap();
+        assert_eq!(fit.exceedance_rate, 0.8);
+        assert_eq!(
+            vec![0.0, 20.0, 30.0, 40.0, f64::INFINITY].fit_generalized_pareto(10.0),
+            Err(StatsError::InfinityEncountered)
+        );
+    }
+
+    #[test]
+    fn test_fit_exponential_and_poisson() {
+        let fit = [1.0, 3.0].fit_exponential(0.9).unwrap();
+        assert_eq!(fit.distribution.rate(), 0.5);
+        assert_eq!(
+            Vec::<f64>::new().fit_exponential(0.9),
+            Err(StatsError::EmptyCollection)
+        );
+        assert_eq!(
+            [2.0, -1.0].fit_exponential(0.9),
+            Err(StatsError::OutOfDomain)
+        );
+        assert_eq!(
+            [1.0, f64::INFINITY].fit_exponential(0.9),
+            Err(StatsError::InfinityEncountered)
+        );
+        assert_eq!(
+            [f64::MAX, f64::MAX].fit_exponential(0.9),
+            Err(StatsError::Overflow)
+        );
+        assert_eq!(
+            [f64::MIN_POSITIVE / 4.0].fit_exponential(0.9),
+            Err(StatsError::Overflow)
+        );
+        let fit = [1, 2, 6].fit_poisson(0.9).unwrap();
+        assert_eq!(fit.distribution.rate(), 3.0);
+        assert_eq!(
+            [1, -2].fit_poisson(0.9),
+            Err(StatsError::CouldNotConvert {
+                from: DataType::Item,
+                to: DataType::Usize
+            })
+        );
+    }
 }
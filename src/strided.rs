@@ -0,0 +1,88 @@
+use crate::Stats;
+
+/// A collection seen as every `step`th item, starting from the one at
+/// `offset`, created by [`Stats::strided`]. It implements [`Stats`], so
+/// the statistics of one channel of interleaved data, such as the left
+/// channel of stereo audio or the red values of RGB pixels, can be
+/// calculated without copying the channel out first.
+#[derive(Debug, Clone, Copy)]
+pub struct Strided<'a, T: ?Sized> {
+    inner: &'a T,
+    offset: usize,
+    step: usize,
+}
+
+impl<'a, T: ?Sized> Strided<'a, T> {
+    /// Create a view of every `step`th item of `inner`, from the one at `offset`
+    ///
+    /// # Panics
+    /// Panics if `step` is 0
+    pub(crate) fn new(inner: &'a T, offset: usize, step: usize) -> Self {
+        assert!(step > 0, "step must be greater than 0");
+        Self {
+            inner,
+            offset,
+            step,
+        }
+    }
+
+    /// Returns the collection with all of the items
+    pub fn get(&self) -> &'a T {
+        self.inner
+    }
+
+    /// Returns the index of the first item in the view
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the distance between the items in the view
+    pub fn step(&self) -> usize {
+        self.step
+    }
+}
+
+impl<T> Stats for Strided<'_, T>
+where
+    T: Stats + ?Sized,
+{
+    type Item = T::Item;
+
+    fn items<'a>(&'a self) -> impl Iterator<Item = T::Item> + 'a
+    where
+        Self::Item: 'a,
+    {
+        self.inner.items().skip(self.offset).step_by(self.step)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Stats;
+    use crate::StatsError;
+
+    #[test]
+    fn test_strided() {
+        // red, green and blue values of four pixels
+        let pixels = [255, 0, 10, 205, 4, 20, 235, 8, 30, 225, 12, 40];
+        let green = pixels.strided(1, 3);
+        assert_eq!(green.count(), 4);
+        assert_eq!(green.mean(), Ok(6));
+        assert_eq!(Stats::max(&pixels.strided(0, 3)), Ok(255));
+        assert_eq!(pixels.strided(2, 3).sum(), 100);
+        assert_eq!(green.get(), &pixels);
+        assert_eq!((green.offset(), green.step()), (1, 3));
+        // views can be strided again, taking every other green value
+        assert_eq!(green.strided(0, 2).sum(), 8);
+        assert_eq!(
+            pixels.strided(12, 3).mean(),
+            Err(StatsError::EmptyCollection)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "step must be greater than 0")]
+    fn test_zero_step() {
+        let _ = [1.0].strided(0, 0);
+    }
+}
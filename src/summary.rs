@@ -0,0 +1,149 @@
+use crate::helpers::Float;
+use crate::OnlineStats;
+
+/// A finalized set of statistics describing a non-empty collection,
+/// such as the result of [`OnlineStats::summary`](crate::OnlineStats::summary).
+///
+/// Summaries of separate parts of a data set can be combined with
+/// [`Summary::merge`] to get the summary of the whole data set.
+///
+/// # Examples
+/// ```
+/// use stats_traits::OnlineStats;
+/// let mut a = OnlineStats::from_iter([1.0, 2.0]).summary().unwrap();
+/// let b = OnlineStats::from_iter([3.0, 4.0, 5.0]).summary().unwrap();
+/// a.merge(&b);
+/// assert_eq!(a.count, 5);
+/// assert_eq!(a.mean, 3.0);
+/// assert_eq!(a.variance, 2.0);
+/// assert_eq!(a.max, 5.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct Summary {
+    /// Number of values in the collection
+    pub count: usize,
+    /// Mean of the collection
+    pub mean: f64,
+    /// Population variance of the collection
+    pub variance: f64,
+    /// Smallest value in the collection
+    pub min: f64,
+    /// Largest value in the collection
+    pub max: f64,
+    /// Third central moment of the collection, the mean of the cubed
+    /// differences from the mean, for [`Summary::skewness`]
+    pub third_moment: f64,
+    /// Fourth central moment of the collection, the mean of the differences
+    /// from the mean to the fourth power, for [`Summary::kurtosis`]
+    pub fourth_moment: f64,
+}
+
+impl Summary {
+    /// Create a summary of a collection of `count` values from its mean,
+    /// population variance, smallest and largest value. Its higher moments
+    /// are unknown, so [`Summary::skewness`] and [`Summary::kurtosis`] are
+    /// `NaN` until they are set with [`Summary::with_moments`].
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Summary;
+    /// let mut a = Summary::new(2, 1.5, 0.25, 1.0, 2.0);
+    /// a.merge(&Summary::new(2, 3.5, 0.25, 3.0, 4.0));
+    /// assert_eq!(a.mean, 2.5);
+    /// assert_eq!(a.variance, 1.25);
+    /// assert!(a.skewness().is_nan());
+    /// ```
+    pub fn new(count: usize, mean: f64, variance: f64, min: f64, max: f64) -> Self {
+        Self {
+            count,
+            mean,
+            variance,
+            min,
+            max,
+            third_moment: f64::NAN,
+            fourth_moment: f64::NAN,
+        }
+    }
+
+    /// Set the third and fourth central moments of the collection,
+    /// returning the summary so that calls can be chained
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::Summary;
+    /// let summary = Summary::new(3, 0.0, 4.0, -2.0, 2.0).with_moments(0.0, 24.0);
+    /// assert_eq!(summary.skewness(), 0.0);
+    /// assert_eq!(summary.kurtosis(), -1.5);
+    /// ```
+    #[must_use]
+    pub fn with_moments(mut self, third_moment: f64, fourth_moment: f64) -> Self {
+        self.third_moment = third_moment;
+        self.fourth_moment = fourth_moment;
+        self
+    }
+
+    /// Returns the standard deviation, which is the square root of the variance
+    pub fn std_dev(&self) -> f64 {
+        Float::sqrt(self.variance)
+    }
+
+    /// Returns the range (the smallest subtracted from the largest)
+    pub fn range(&self) -> f64 {
+        self.max - self.min
+    }
+
+    /// Returns the (population) skewness, like [`OnlineStats::skewness`].
+    /// It is `NaN` if every value is the same.
+    pub fn skewness(&self) -> f64 {
+        self.third_moment / Float::powf(self.variance, 1.5)
+    }
+
+    /// Returns the (population) excess kurtosis, like [`OnlineStats::kurtosis`].
+    /// It is `NaN` if every value is the same.
+    pub fn kurtosis(&self) -> f64 {
+        self.fourth_moment / (self.variance * self.variance) - 3.0
+    }
+
+    /// Combine `other` into `self`, so that `self` describes both collections.
+    /// See [`OnlineStats::merge`](crate::OnlineStats::merge).
+    pub fn merge(&mut self, other: &Self) {
+        let mut stats = OnlineStats::from(*self);
+        stats.merge(&OnlineStats::from(*other));
+        // there is no summary if both collections are empty
+        if let Ok(summary) = stats.summary() {
+            *self = summary;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_merge() {
+        // the values 1, 2 and 3, 4
+        let mut a = Summary::new(2, 1.5, 0.25, 1.0, 2.0).with_moments(0.0, 0.0625);
+        let b = Summary::new(2, 3.5, 0.25, 3.0, 4.0).with_moments(0.0, 0.0625);
+        a.merge(&b);
+        assert_eq!(a.count, 4);
+        assert_relative_eq!(a.mean, 2.5);
+        assert_relative_eq!(a.variance, 1.25);
+        assert_eq!(a.range(), 3.0);
+        assert_relative_eq!(a.skewness(), 0.0);
+        assert_relative_eq!(a.kurtosis(), -1.36, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_std_dev() {
+        let summary = Summary::new(3, 0.0, 4.0, -2.0, 2.0).with_moments(0.0, 24.0);
+        assert_eq!(summary.std_dev(), 2.0);
+        assert_eq!(summary.skewness(), 0.0);
+        assert_eq!(summary.kurtosis(), -1.5);
+    }
+}
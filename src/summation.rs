@@ -0,0 +1,246 @@
+//! Strategies for adding up floating point numbers, which trade speed for
+//! accuracy, for use with [`Stats::sum_with`](crate::Stats::sum_with) and
+//! the other methods that take a [`SumStrategy`].
+//!
+//! # Examples
+//! ```
+//! use stats_traits::summation::{Kahan, Naive, Pairwise};
+//! use stats_traits::Stats;
+//! let values = vec![1e16, 1.0, -1e16, 1.0];
+//! assert_eq!(values.sum_with::<Naive>(), Ok(1.0));
+//! assert_eq!(values.sum_with::<Pairwise>(), Ok(1.0));
+//! assert_eq!(values.sum_with::<Kahan>(), Ok(2.0));
+//! ```
+
+/// A way of adding up [`f64`]s one at a time, which is
+/// also the sum in progress. Start with [`Default::default`].
+pub trait SumStrategy: Default {
+    /// Add `x` to the sum
+    fn add(&mut self, x: f64);
+
+    /// Returns the sum of the values added so far
+    fn sum(&self) -> f64;
+}
+
+/// Adds the values one after another, which is the fastest way, but the
+/// rounding error can grow in proportion to the number of values. This is
+/// how [`Stats::sum`](crate::Stats::sum) adds up floats.
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Naive {
+    sum: f64,
+}
+
+impl SumStrategy for Naive {
+    fn add(&mut self, x: f64) {
+        self.sum += x;
+    }
+
+    fn sum(&self) -> f64 {
+        self.sum
+    }
+}
+
+/// Adds the values with [Kahan summation](<https://en.wikipedia.org/wiki/Kahan_summation_algorithm>),
+/// keeping track of the rounding error of each addition and adding it back at
+/// the end, so the error doesn't grow with the number of values. It uses
+/// Neumaier's improvement, which also works when a value is larger than the
+/// sum so far. It is the most accurate and the slowest, taking about four
+/// times as many operations as [`Naive`].
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Kahan {
+    sum: f64,
+    /// The total rounding error of the additions so far
+    compensation: f64,
+}
+
+impl SumStrategy for Kahan {
+    fn add(&mut self, x: f64) {
+        let sum = self.sum + x;
+        self.compensation += if self.sum.abs() >= x.abs() {
+            (self.sum - sum) + x
+        } else {
+            (x - sum) + self.sum
+        };
+        self.sum = sum;
+    }
+
+    fn sum(&self) -> f64 {
+        self.sum + self.compensation
+    }
+}
+
+/// The number of values added one after another before the sums are
+/// added in pairs, which keeps most of the speed of the naive sum
+const BLOCK: usize = 32;
+
+/// Adds the values with [pairwise summation](<https://en.wikipedia.org/wiki/Pairwise_summation>),
+/// where the error grows with the logarithm of the number of values rather than
+/// in proportion to it, as it does for [`Naive`]. It is nearly as fast.
+///
+/// The values are added in blocks, and the sums of the blocks are combined
+/// like the digits of a binary counter, so equal numbers of values are always
+/// added together. This works on values as they arrive, and only keeps one
+/// partial sum for each power of two.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pairwise {
+    /// The sum of `2^level` blocks, for each level with a set bit in `blocks`
+    #[cfg_attr(feature = "serde", serde(with = "partials"))]
+    partials: [f64; usize::BITS as usize],
+    /// The number of complete blocks
+    blocks: usize,
+    /// The sum of the values of the current block
+    block: f64,
+    /// The number of values in the current block
+    in_block: usize,
+}
+
+/// Serializes the partial sums of a [`Pairwise`] as a sequence, because
+/// serde only implements its traits for arrays of up to 32 items
+#[cfg(feature = "serde")]
+mod partials {
+    use core::fmt;
+
+    use serde::de::{Error, SeqAccess, Visitor};
+    use serde::{Deserializer, Serializer};
+
+    const LEVELS: usize = usize::BITS as usize;
+
+    pub fn serialize<S: Serializer>(
+        partials: &[f64; LEVELS],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(partials)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<[f64; LEVELS], D::Error> {
+        struct PartialsVisitor;
+
+        impl<'de> Visitor<'de> for PartialsVisitor {
+            type Value = [f64; LEVELS];
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "a sequence of {LEVELS} floats")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut partials = [0.0; LEVELS];
+                for (i, partial) in partials.iter_mut().enumerate() {
+                    *partial = seq
+                        .next_element()?
+                        .ok_or_else(|| A::Error::invalid_length(i, &self))?;
+                }
+                if seq.next_element::<f64>()?.is_some() {
+                    return Err(A::Error::invalid_length(LEVELS + 1, &self));
+                }
+                Ok(partials)
+            }
+        }
+
+        deserializer.deserialize_seq(PartialsVisitor)
+    }
+}
+
+impl Default for Pairwise {
+    fn default() -> Self {
+        Self {
+            partials: [0.0; usize::BITS as usize],
+            blocks: 0,
+            block: 0.0,
+            in_block: 0,
+        }
+    }
+}
+
+impl SumStrategy for Pairwise {
+    fn add(&mut self, x: f64) {
+        self.block += x;
+        self.in_block += 1;
+        if self.in_block < BLOCK {
+            return;
+        }
+        // carry the block up through the levels that are already full
+        let mut sum = self.block;
+        let mut level = 0;
+        while self.blocks >> level & 1 == 1 {
+            sum += self.partials[level];
+            level += 1;
+        }
+        self.partials[level] = sum;
+        self.blocks += 1;
+        self.block = 0.0;
+        self.in_block = 0;
+    }
+
+    fn sum(&self) -> f64 {
+        // the smaller partial sums first
+        (0..usize::BITS as usize)
+            .filter(|level| self.blocks >> level & 1 == 1)
+            .fold(self.block, |sum, level| sum + self.partials[level])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kahan_large_values() {
+        let mut kahan = Kahan::default();
+        for x in [1.0, 1e100, 1.0, -1e100] {
+            kahan.add(x);
+        }
+        assert_eq!(kahan.sum(), 2.0);
+    }
+
+    #[test]
+    fn test_exact_for_integers() {
+        for n in [0, 1, 31, 32, 33, 64, 100, 1000] {
+            let mut sum = Pairwise::default();
+            for i in 1..=n {
+                sum.add(i as f64);
+            }
+            assert_eq!(sum.sum(), (n * (n + 1) / 2) as f64);
+        }
+    }
+
+    #[test]
+    fn test_more_accurate_than_naive() {
+        let mut pairwise = Pairwise::default();
+        let mut naive = Naive::default();
+        let mut kahan = Kahan::default();
+        for _ in 0..1_000_000 {
+            pairwise.add(0.1);
+            naive.add(0.1);
+            kahan.add(0.1);
+        }
+        let pairwise_error = (pairwise.sum() - 100_000.0).abs();
+        let naive_error = (naive.sum() - 100_000.0).abs();
+        assert!(pairwise_error < 1e-8);
+        assert!(naive_error > 1000.0 * pairwise_error);
+        // the exact sum of a million of the float nearest to 0.1, rounded
+        assert_eq!(kahan.sum(), 100_000.0);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_pairwise() {
+        let mut sum = Pairwise::default();
+        for i in 0..100 {
+            sum.add(f64::from(i));
+        }
+        let json = serde_json::to_string(&sum).unwrap();
+        let loaded: Pairwise = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.sum(), sum.sum());
+        assert_eq!(loaded.partials, sum.partials);
+        let short = json.replacen(",0.0]", "]", 1);
+        assert!(serde_json::from_str::<Pairwise>(&short).is_err());
+    }
+}
@@ -0,0 +1,83 @@
+use crate::helpers::Float;
+
+/// Estimates of the mean and total of a population from a sample with
+/// sampling weights, such as a poll weighted to match the population,
+/// as calculated by [`Stats::survey_estimate`](crate::Stats::survey_estimate).
+///
+/// Unequal weights make the estimates less precise than a simple random
+/// sample of the same size would be. Kish's design effect measures by how
+/// much, and the standard errors are those of a simple random sample of
+/// the effective size.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SurveyEstimate {
+    /// Number of items with a weight greater than 0
+    pub count: usize,
+    /// Sum of the weights, which estimates the size of the population
+    pub weight_sum: f64,
+    /// Kish's effective sample size, the square of the sum of the weights
+    /// divided by the sum of their squares, which is the size of a simple
+    /// random sample that would be as precise
+    pub effective_count: f64,
+    /// Kish's design effect of the weights, `count` divided by
+    /// `effective_count`, which is 1 when every weight is the same
+    pub design_effect: f64,
+    /// Weighted mean of the items, which estimates the mean of the population
+    pub mean: f64,
+    /// Standard error of [`SurveyEstimate::mean`], adjusted for the design effect
+    pub mean_std_err: f64,
+    /// Weighted sum of the items, which estimates the total of the population
+    pub total: f64,
+    /// Standard error of [`SurveyEstimate::total`], treating the size of the
+    /// population as known
+    pub total_std_err: f64,
+}
+
+impl SurveyEstimate {
+    /// Calculate the estimates from the number of items with a weight greater
+    /// than 0, the sum of the weights and of their squares, the weighted
+    /// mean and the weighted sum of the squared differences from it
+    pub(crate) fn new(
+        count: usize,
+        weight_sum: f64,
+        weight_square_sum: f64,
+        mean: f64,
+        m2: f64,
+    ) -> Self {
+        let n = count as f64;
+        let effective_count = weight_sum * weight_sum / weight_square_sum;
+        // the weighted variance, corrected for the bias of a sample
+        let variance = m2 / weight_sum * n / (n - 1.0);
+        let mean_std_err = Float::sqrt(variance / effective_count);
+        Self {
+            count,
+            weight_sum,
+            effective_count,
+            design_effect: n / effective_count,
+            mean,
+            mean_std_err,
+            total: weight_sum * mean,
+            total_std_err: weight_sum * mean_std_err,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_equal_weights() {
+        // the items 1, 2, 3 and 4, each with a weight of 10
+        let estimate = SurveyEstimate::new(4, 40.0, 400.0, 2.5, 50.0);
+        assert_eq!(estimate.effective_count, 4.0);
+        assert_eq!(estimate.design_effect, 1.0);
+        // the sample variance is 5 / 3
+        assert_relative_eq!(estimate.mean_std_err, (5.0f64 / 12.0).sqrt());
+        assert_eq!(estimate.total, 100.0);
+        assert_relative_eq!(estimate.total_std_err, 40.0 * (5.0f64 / 12.0).sqrt());
+    }
+}
@@ -0,0 +1,404 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use core::slice;
+
+use num_traits::ToPrimitive;
+
+use crate::helpers::partial_cmp_nan_last;
+use crate::render;
+
+/// An owned collection of values and how often each one occurs.
+///
+/// The entries are kept sorted by value and every value appears at most
+/// once, so frequencies for the same value are always merged together.
+/// `&FrequencyTable` iterates over references to the `(usize, T)` tuples, which
+/// means all the methods of [`FrequencyStats`](crate::FrequencyStats) can be used on it.
+///
+/// # Examples
+/// ```
+/// use stats_traits::{FrequencyStats, FrequencyTable};
+/// let table: FrequencyTable<i32> = vec![3, 1, 2, 3, 3].into_iter().collect();
+/// assert_eq!(table.frequency(3), 3);
+/// assert_eq!(table.as_slice(), &[(1, 1), (1, 2), (3, 3)]);
+/// assert_eq!(table.mode(), Ok(3));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        try_from = "FrequencyTableRepr<T>",
+        bound(deserialize = "T: serde::Deserialize<'de> + PartialOrd")
+    )
+)]
+pub struct FrequencyTable<T> {
+    entries: Vec<(usize, T)>,
+}
+
+impl<T> FrequencyTable<T> {
+    /// Create an empty table
+    pub const fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Returns the number of distinct values in the table
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if there are no values in the table
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the entries of the table as `(frequency, value)` tuples,
+    /// sorted by value
+    pub fn as_slice(&self) -> &[(usize, T)] {
+        &self.entries
+    }
+
+    /// Returns an iterator over the `(frequency, value)` tuples of the table,
+    /// sorted by value
+    pub fn iter(&self) -> slice::Iter<'_, (usize, T)> {
+        self.entries.iter()
+    }
+}
+
+impl<T> FrequencyTable<T>
+where
+    T: PartialOrd + Copy,
+{
+    /// Create a table from `(frequency, value)` tuples, merging the
+    /// frequencies of values that appear more than once and dropping
+    /// values with a frequency of zero.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::FrequencyTable;
+    /// let table = FrequencyTable::from_frequencies(vec![(1, 2), (2, 1), (3, 2), (0, 5)]);
+    /// assert_eq!(table.as_slice(), &[(2, 1), (4, 2)]);
+    /// ```
+    pub fn from_frequencies<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (usize, T)>,
+    {
+        let mut table = Self::new();
+        for (freq, val) in iter {
+            table.insert_many(val, freq);
+        }
+        table
+    }
+
+    /// Find the index of `value` in the entries, or the index
+    /// it would be inserted at if it isn't present
+    fn search(&self, value: &T) -> core::result::Result<usize, usize> {
+        self.entries
+            .binary_search_by(|(_, val)| partial_cmp_nan_last(val, value))
+    }
+
+    /// Record one occurrence of `value`
+    pub fn insert(&mut self, value: T) {
+        self.insert_many(value, 1);
+    }
+
+    /// Record `freq` occurrences of `value`
+    pub fn insert_many(&mut self, value: T, freq: usize) {
+        if freq == 0 {
+            return;
+        }
+        match self.search(&value) {
+            Ok(index) => self.entries[index].0 += freq,
+            Err(index) => self.entries.insert(index, (freq, value)),
+        }
+    }
+
+    /// Record one occurrence of `value`, returning the table so that
+    /// calls can be chained
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::FrequencyTable;
+    /// let table = FrequencyTable::new().with(2).with(1).with(2);
+    /// assert_eq!(table.as_slice(), &[(1, 1), (2, 2)]);
+    /// ```
+    #[must_use]
+    pub fn with(mut self, value: T) -> Self {
+        self.insert(value);
+        self
+    }
+
+    /// Record `freq` occurrences of `value`, returning the table so that
+    /// calls can be chained
+    #[must_use]
+    pub fn with_many(mut self, value: T, freq: usize) -> Self {
+        self.insert_many(value, freq);
+        self
+    }
+
+    /// Remove one occurrence of `value`, returning `true` if
+    /// it was present in the table
+    pub fn remove(&mut self, value: T) -> bool {
+        self.remove_many(value, 1) == 1
+    }
+
+    /// Remove up to `freq` occurrences of `value`, returning
+    /// how many were actually removed. Values whose frequency
+    /// drops to zero are removed from the table.
+    pub fn remove_many(&mut self, value: T, freq: usize) -> usize {
+        match self.search(&value) {
+            Ok(index) => {
+                let entry = &mut self.entries[index].0;
+                let removed = freq.min(*entry);
+                *entry -= removed;
+                if *entry == 0 {
+                    self.entries.remove(index);
+                }
+                removed
+            }
+            Err(_) => 0,
+        }
+    }
+
+    /// Returns how many times `value` has been recorded. Values that aren't
+    /// equal to themselves, such as `NaN`, are recorded as the same value,
+    /// like in [`FrequencyStats::count_of`](crate::FrequencyStats::count_of).
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::FrequencyTable;
+    /// let table: FrequencyTable<f64> = vec![f64::NAN, 1.0, f64::NAN].into_iter().collect();
+    /// assert_eq!(table.frequency(f64::NAN), 2);
+    /// ```
+    pub fn frequency(&self, value: T) -> usize {
+        self.search(&value)
+            .map(|index| self.entries[index].0)
+            .unwrap_or(0)
+    }
+
+    /// Returns an iterator over `(relative frequency, value)` tuples,
+    /// where the relative frequency is the proportion of all recorded
+    /// occurrences that are equal to the value. The relative
+    /// frequencies add up to 1.
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::FrequencyTable;
+    /// let table: FrequencyTable<i32> = vec![1, 1, 1, 2].into_iter().collect();
+    /// let normalized: Vec<(f64, i32)> = table.normalized().collect();
+    /// assert_eq!(normalized, vec![(0.75, 1), (0.25, 2)]);
+    /// ```
+    pub fn normalized(&self) -> impl Iterator<Item = (f64, T)> + '_ {
+        let total = self
+            .entries
+            .iter()
+            .map(|(freq, _)| freq)
+            .sum::<usize>()
+            .to_f64()
+            .unwrap_or(f64::NAN);
+        self.entries
+            .iter()
+            .map(move |&(freq, val)| (freq.to_f64().unwrap_or(f64::NAN) / total, val))
+    }
+}
+
+impl<T> FrequencyTable<T>
+where
+    T: fmt::Display,
+{
+    /// Render the frequencies as a sparkline, with a block for each value
+    /// as high as its frequency relative to the largest one, in the order
+    /// of the values. See [`Histogram::sparkline`](crate::Histogram::sparkline).
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::FrequencyTable;
+    /// let rolls: FrequencyTable<u8> = vec![1, 2, 2, 3, 3, 3, 3].into_iter().collect();
+    /// assert_eq!(rolls.sparkline(), "▂▄█");
+    /// ```
+    pub fn sparkline(&self) -> String {
+        render::sparkline(self.entries.iter().map(|&(freq, _)| freq))
+    }
+
+    /// Render the table as a horizontal bar chart, with a line for each
+    /// value showing it, a bar of `bar` (such as `'█'`, or `'#'` for plain
+    /// ASCII) up to `width` characters long for the largest frequency, and
+    /// its frequency
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::FrequencyTable;
+    /// let statuses: FrequencyTable<u16> = vec![200, 200, 200, 404, 500].into_iter().collect();
+    /// let expected = "\
+    /// 200 │██████ 3
+    /// 404 │██ 1
+    /// 500 │██ 1
+    /// ";
+    /// assert_eq!(statuses.bar_chart(6, '█'), expected);
+    /// ```
+    pub fn bar_chart(&self, width: usize, bar: char) -> String {
+        let rows = self
+            .entries
+            .iter()
+            .map(|(freq, value)| (format!("{value}"), *freq));
+        render::bar_chart(rows, width, bar)
+    }
+}
+
+impl<T> Default for FrequencyTable<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> FromIterator<T> for FrequencyTable<T>
+where
+    T: PartialOrd + Copy,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut table = Self::new();
+        table.extend(iter);
+        table
+    }
+}
+
+impl<T> Extend<T> for FrequencyTable<T>
+where
+    T: PartialOrd + Copy,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for val in iter {
+            self.insert(val);
+        }
+    }
+}
+
+impl<T> IntoIterator for FrequencyTable<T> {
+    type Item = (usize, T);
+    type IntoIter = alloc::vec::IntoIter<(usize, T)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a FrequencyTable<T> {
+    type Item = &'a (usize, T);
+    type IntoIter = slice::Iter<'a, (usize, T)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.iter()
+    }
+}
+
+impl<T> PartialOrd for FrequencyTable<T>
+where
+    T: PartialOrd,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.entries.partial_cmp(&other.entries)
+    }
+}
+
+/// The entries of a [`FrequencyTable`] as they are deserialized,
+/// before they are checked to be sorted and distinct
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct FrequencyTableRepr<T> {
+    entries: Vec<(usize, T)>,
+}
+
+#[cfg(feature = "serde")]
+impl<T> TryFrom<FrequencyTableRepr<T>> for FrequencyTable<T>
+where
+    T: PartialOrd,
+{
+    type Error = crate::CheckpointError;
+
+    fn try_from(repr: FrequencyTableRepr<T>) -> core::result::Result<Self, Self::Error> {
+        let entries = repr.entries;
+        // the binary search in the table depends on the values being sorted
+        // and distinct, and values with a frequency of zero are removed
+        let sorted = entries
+            .windows(2)
+            .all(|pair| partial_cmp_nan_last(&pair[0].1, &pair[1].1) == Ordering::Less);
+        if !sorted || entries.iter().any(|&(freq, _)| freq == 0) {
+            return Err(crate::CheckpointError::Invalid);
+        }
+        Ok(Self { entries })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FrequencyStats;
+
+    extern crate std;
+    use std::vec;
+
+    #[test]
+    fn test_from_iter_sorted_and_deduplicated() {
+        let table: FrequencyTable<i32> = vec![3, 1, 2, 3, 1, 3].into_iter().collect();
+        assert_eq!(table.as_slice(), &[(2, 1), (1, 2), (3, 3)]);
+        assert_eq!(table.len(), 3);
+    }
+
+    #[test]
+    fn test_from_frequencies_merges() {
+        let table = FrequencyTable::from_frequencies(vec![(1, 2.0), (2, 1.0), (3, 2.0)]);
+        assert_eq!(table.as_slice(), &[(2, 1.0), (4, 2.0)]);
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut table: FrequencyTable<i32> = vec![1, 1, 2].into_iter().collect();
+        assert!(table.remove(1));
+        assert_eq!(table.frequency(1), 1);
+        assert!(table.remove(2));
+        assert!(!table.remove(2));
+        assert_eq!(table.as_slice(), &[(1, 1)]);
+        assert_eq!(table.remove_many(1, 5), 1);
+        assert!(table.is_empty());
+    }
+
+    #[test]
+    fn test_nan_is_last() {
+        let table: FrequencyTable<f64> = vec![f64::NAN, 1.0, f64::NAN].into_iter().collect();
+        assert_eq!(table.len(), 2);
+        assert_eq!(table.as_slice()[0], (1, 1.0));
+        assert_eq!(table.as_slice()[1].0, 2);
+        assert_eq!(table.frequency(f64::NAN), 2);
+        assert_eq!(table.frequency(f64::NAN), table.count_of(f64::NAN));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let table: FrequencyTable<f64> = vec![2.0, 3.0, 1.0, 2.0].into_iter().collect();
+        let json = serde_json::to_string(&table).unwrap();
+        let loaded: FrequencyTable<f64> = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, table);
+        assert_eq!(loaded.frequency(2.0), 2);
+        for tampered in [
+            r#"{"entries":[[1,2.0],[1,1.0]]}"#,
+            r#"{"entries":[[1,1.0],[1,1.0]]}"#,
+            r#"{"entries":[[0,1.0]]}"#,
+        ] {
+            assert!(serde_json::from_str::<FrequencyTable<f64>>(tampered).is_err());
+        }
+    }
+
+    #[test]
+    fn test_frequency_stats() {
+        let table = FrequencyTable::new().with(1).with(2).with_many(3, 3);
+        assert_eq!(table.count(), 5);
+        assert_eq!(table.sum(), Ok(12));
+        assert_eq!(table.mode(), Ok(3));
+        assert_eq!(table.count_of(3), 3);
+    }
+}
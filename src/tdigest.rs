@@ -0,0 +1,590 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::f64::consts::PI;
+
+use crate::checkpoint;
+use crate::checkpoint::Reader;
+use crate::checkpoint::Writer;
+use crate::helpers::partial_cmp_nan_last;
+use crate::helpers::Float;
+use crate::CheckpointError;
+use crate::Result;
+use crate::StatsError;
+
+/// A cluster of values in a [`TDigest`], summarized by their mean and how many there are
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Centroid {
+    mean: f64,
+    weight: f64,
+}
+
+/// A sketch for estimating quantiles of a stream of values in bounded memory,
+/// using the merging [t-digest](<https://arxiv.org/abs/1902.04023>) of Dunning and Ertl.
+///
+/// Values are grouped into clusters (centroids), with small clusters near the
+/// ends of the distribution and larger ones in the middle, so extreme quantiles
+/// such as the 99th percentile stay accurate. The number of clusters is bounded
+/// by the compression parameter, not by the number of values pushed.
+///
+/// # Examples
+/// ```
+/// use stats_traits::TDigest;
+/// let mut digest = TDigest::new(100.0);
+/// for x in 0..=1000 {
+///     digest.push(x as f64);
+/// }
+/// let p99 = digest.quantile(0.99).unwrap();
+/// assert!((p99 - 990.0).abs() < 2.0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TDigest {
+    compression: f64,
+    centroids: Vec<Centroid>,
+    buffer: Vec<f64>,
+    count: f64,
+    min: f64,
+    max: f64,
+    /// Whether the next compression should merge from the largest centroid down
+    reverse: bool,
+}
+
+impl TDigest {
+    /// Create an empty digest. Larger values of `compression` use more memory
+    /// and give more accurate quantiles; 100 is a common choice.
+    ///
+    /// # Panics
+    /// Panics if `compression` is not greater than 0
+    pub fn new(compression: f64) -> Self {
+        assert!(compression > 0.0, "compression must be greater than 0");
+        Self {
+            compression,
+            centroids: Vec::new(),
+            buffer: Vec::new(),
+            count: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            reverse: false,
+        }
+    }
+
+    /// Returns the compression parameter of the digest
+    pub fn compression(&self) -> f64 {
+        self.compression
+    }
+
+    /// Returns the number of values pushed to the digest, other than `NaN`s
+    pub fn count(&self) -> usize {
+        self.count as usize
+    }
+
+    /// Returns the smallest value pushed to the digest
+    ///
+    /// # Errors
+    /// Returns an error if no values have been pushed
+    pub fn min(&self) -> Result<f64> {
+        self.non_empty()?;
+        Ok(self.min)
+    }
+
+    /// Returns the largest value pushed to the digest
+    ///
+    /// # Errors
+    /// Returns an error if no values have been pushed
+    pub fn max(&self) -> Result<f64> {
+        self.non_empty()?;
+        Ok(self.max)
+    }
+
+    /// Returns `Err(StatsError::EmptyCollection)` if no values have been pushed
+    fn non_empty(&self) -> Result<()> {
+        if self.count == 0.0 {
+            Err(StatsError::EmptyCollection)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Add a value to the digest, unless it is `NaN`
+    pub fn push(&mut self, x: f64) {
+        if x.is_nan() {
+            return;
+        }
+        self.buffer.push(x);
+        self.count += 1.0;
+        self.min = self.min.min(x);
+        self.max = self.max.max(x);
+        if self.buffer.len() as f64 >= 5.0 * self.compression {
+            self.flush();
+        }
+    }
+
+    /// Combine the values seen by `other` into `self`, as if every value
+    /// pushed to `other` had been pushed to `self` as well
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::TDigest;
+    /// let mut a = TDigest::new(100.0);
+    /// let mut b = TDigest::new(100.0);
+    /// for x in 0..500 {
+    ///     a.push(x as f64);
+    ///     b.push((x + 500) as f64);
+    /// }
+    /// a.merge(&b);
+    /// assert_eq!(a.count(), 1000);
+    /// assert!((a.quantile(0.5).unwrap() - 500.0).abs() < 2.0);
+    /// ```
+    pub fn merge(&mut self, other: &Self) {
+        let mut centroids = core::mem::take(&mut self.centroids);
+        centroids.extend_from_slice(&other.centroids);
+        centroids.extend(
+            other
+                .buffer
+                .iter()
+                .map(|&mean| Centroid { mean, weight: 1.0 }),
+        );
+        centroids.extend(
+            self.buffer
+                .drain(..)
+                .map(|mean| Centroid { mean, weight: 1.0 }),
+        );
+        self.count += other.count;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.centroids = self.compress(centroids, self.reverse);
+        self.reverse = !self.reverse;
+    }
+
+    /// Merge the buffered values into the centroids
+    fn flush(&mut self) {
+        if self.buffer.is_empty() {
+            return;
+        }
+        self.centroids = self.merged_centroids();
+        self.buffer.clear();
+        self.reverse = !self.reverse;
+    }
+
+    /// Returns the centroids with the buffered values merged in
+    fn merged_centroids(&self) -> Vec<Centroid> {
+        let mut centroids = self.centroids.clone();
+        centroids.extend(
+            self.buffer
+                .iter()
+                .map(|&mean| Centroid { mean, weight: 1.0 }),
+        );
+        self.compress(centroids, self.reverse)
+    }
+
+    /// The scale function, which maps a quantile to a "k-size" so that
+    /// centroids near the tails are kept small
+    fn scale(&self, q: f64) -> f64 {
+        self.compression / (2.0 * PI) * Float::asin(2.0 * q - 1.0)
+    }
+
+    /// The inverse of [`TDigest::scale`]
+    fn inverse_scale(&self, k: f64) -> f64 {
+        let angle = k * 2.0 * PI / self.compression;
+        if angle >= PI / 2.0 {
+            1.0
+        } else {
+            (Float::sin(angle) + 1.0) / 2.0
+        }
+    }
+
+    /// Sort the centroids and merge neighbours together as long as
+    /// each merged centroid spans less than one unit of k-size.
+    ///
+    /// Merging always starting from the smallest centroid biases the means
+    /// towards the larger values, so successive compressions alternate the
+    /// direction by passing `reverse`.
+    fn compress(&self, mut centroids: Vec<Centroid>, reverse: bool) -> Vec<Centroid> {
+        centroids.sort_by(|a, b| partial_cmp_nan_last(&a.mean, &b.mean));
+        if reverse {
+            centroids.reverse();
+        }
+        let total: f64 = centroids.iter().map(|c| c.weight).sum();
+        let mut merged: Vec<Centroid> = Vec::with_capacity(centroids.len());
+        let mut centroids = centroids.into_iter();
+        let Some(mut current) = centroids.next() else {
+            return merged;
+        };
+
+        let mut weight_so_far = 0.0;
+        let mut q_limit = self.inverse_scale(self.scale(0.0) + 1.0);
+        for next in centroids {
+            let q = (weight_so_far + current.weight + next.weight) / total;
+            if q <= q_limit {
+                let weight = current.weight + next.weight;
+                current.mean += (next.mean - current.mean) * next.weight / weight;
+                current.weight = weight;
+            } else {
+                weight_so_far += current.weight;
+                merged.push(current);
+                q_limit = self.inverse_scale(self.scale(weight_so_far / total) + 1.0);
+                current = next;
+            }
+        }
+        merged.push(current);
+        if reverse {
+            merged.reverse();
+        }
+        merged
+    }
+
+    /// Estimate the `p`-quantile of the values pushed so far, where `p` is
+    /// between 0 and 1 (so `0.5` is the median and `0.99` the 99th percentile).
+    ///
+    /// # Errors
+    /// Returns an error if no values have been pushed
+    ///
+    /// # Panics
+    /// Panics if `p` is not between 0 and 1
+    pub fn quantile(&self, p: f64) -> Result<f64> {
+        assert!((0.0..=1.0).contains(&p), "p must be between 0 and 1");
+        self.non_empty()?;
+        if self.buffer.is_empty() {
+            Ok(self.quantile_of(&self.centroids, p))
+        } else {
+            Ok(self.quantile_of(&self.merged_centroids(), p))
+        }
+    }
+
+    /// Estimate the quantile of the values pushed so far for every `p` in
+    /// `ps`, in the same order, like [`TDigest::quantile`] but merging the
+    /// values that are still buffered only once
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::TDigest;
+    /// let mut digest = TDigest::default();
+    /// digest.extend((0..=100).map(f64::from));
+    /// assert_eq!(digest.quantiles(&[0.0, 1.0]), Ok(vec![0.0, 100.0]));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if no values have been pushed
+    ///
+    /// # Panics
+    /// Panics if any `p` is not between 0 and 1
+    pub fn quantiles(&self, ps: &[f64]) -> Result<Vec<f64>> {
+        assert!(
+            ps.iter().all(|p| (0.0..=1.0).contains(p)),
+            "p must be between 0 and 1"
+        );
+        self.non_empty()?;
+        let merged;
+        let centroids = if self.buffer.is_empty() {
+            &self.centroids
+        } else {
+            merged = self.merged_centroids();
+            &merged
+        };
+        Ok(ps.iter().map(|&p| self.quantile_of(centroids, p)).collect())
+    }
+
+    /// Estimate the `p`-quantile from all of the `centroids`, of which there is at least one
+    fn quantile_of(&self, centroids: &[Centroid], p: f64) -> f64 {
+        // a single centroid is both the first and the last, so the ends
+        // are still interpolated towards the smallest and largest values
+        let target = p * self.count;
+        let first = centroids[0];
+        if target < first.weight / 2.0 {
+            // between the smallest value and the centre of the first centroid
+            return self.min + (first.mean - self.min) * target / (first.weight / 2.0);
+        }
+
+        let mut weight_so_far = 0.0;
+        for pair in centroids.windows(2) {
+            let (left, right) = (pair[0], pair[1]);
+            let left_centre = weight_so_far + left.weight / 2.0;
+            let right_centre = weight_so_far + left.weight + right.weight / 2.0;
+            if target <= right_centre {
+                let fraction = (target - left_centre) / (right_centre - left_centre);
+                return left.mean + (right.mean - left.mean) * fraction;
+            }
+            weight_so_far += left.weight;
+        }
+
+        // between the centre of the last centroid and the largest value
+        let last = centroids[centroids.len() - 1];
+        let last_centre = self.count - last.weight / 2.0;
+        let fraction = (target - last_centre) / (last.weight / 2.0);
+        last.mean + (self.max - last.mean) * fraction.min(1.0)
+    }
+
+    /// Save the state of the digest as a compact checkpoint, which
+    /// [`TDigest::from_bytes`] restores, so a long-running aggregation
+    /// can survive a restart or be sent to another machine
+    ///
+    /// # Examples
+    /// ```
+    /// use stats_traits::TDigest;
+    /// let mut digest = TDigest::default();
+    /// digest.extend((0..1000).map(f64::from));
+    /// let restored = TDigest::from_bytes(&digest.to_bytes()).unwrap();
+    /// assert_eq!(restored.quantile(0.9), digest.quantile(0.9));
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let len = 51 + 16 * self.centroids.len() + 8 * self.buffer.len();
+        let mut bytes = vec![0; len];
+        let mut writer = Writer::new(&mut bytes, checkpoint::TDIGEST);
+        writer.put_f64(self.compression);
+        writer.put_f64(self.count);
+        writer.put_f64(self.min);
+        writer.put_f64(self.max);
+        writer.put_u8(u8::from(self.reverse));
+        writer.put_usize(self.centroids.len());
+        for centroid in &self.centroids {
+            writer.put_f64(centroid.mean);
+            writer.put_f64(centroid.weight);
+        }
+        writer.put_usize(self.buffer.len());
+        for &x in &self.buffer {
+            writer.put_f64(x);
+        }
+        bytes
+    }
+
+    /// Restore a digest from a checkpoint written by [`TDigest::to_bytes`]
+    ///
+    /// # Errors
+    /// Returns an error if `bytes` isn't a checkpoint of a `TDigest`
+    /// in a version of the format this version of the crate can read, or if
+    /// the digest in it is inconsistent, such as a count that isn't the
+    /// number of values in its centroids and buffer
+    pub fn from_bytes(bytes: &[u8]) -> core::result::Result<Self, CheckpointError> {
+        let mut reader = Reader::new(bytes, checkpoint::TDIGEST)?;
+        let compression = reader.get_f64()?;
+        if compression.is_nan() || compression <= 0.0 {
+            return Err(CheckpointError::Invalid);
+        }
+        let (count, min, max) = (reader.get_f64()?, reader.get_f64()?, reader.get_f64()?);
+        let reverse = match reader.get_u8()? {
+            0 => false,
+            1 => true,
+            _ => return Err(CheckpointError::Invalid),
+        };
+        // the lengths aren't trusted to allocate up front
+        let mut centroids = Vec::new();
+        let mut total = 0.0;
+        for _ in 0..reader.get_usize()? {
+            let centroid = Centroid {
+                mean: reader.get_f64()?,
+                weight: reader.get_f64()?,
+            };
+            if centroid.mean.is_nan() || !(centroid.weight.is_finite() && centroid.weight > 0.0) {
+                return Err(CheckpointError::Invalid);
+            }
+            total += centroid.weight;
+            centroids.push(centroid);
+        }
+        let mut buffer = Vec::new();
+        for _ in 0..reader.get_usize()? {
+            let x = reader.get_f64()?;
+            if x.is_nan() {
+                return Err(CheckpointError::Invalid);
+            }
+            buffer.push(x);
+        }
+        reader.finish()?;
+        // the weights count values, so they add up exactly
+        if count != total + buffer.len() as f64
+            || (count > 0.0 && (min > max || min.is_nan() || max.is_nan()))
+        {
+            return Err(CheckpointError::Invalid);
+        }
+        Ok(Self {
+            compression,
+            centroids,
+            buffer,
+            count,
+            min,
+            max,
+            reverse,
+        })
+    }
+}
+
+impl Default for TDigest {
+    /// Create an empty digest with a compression of 100
+    fn default() -> Self {
+        Self::new(100.0)
+    }
+}
+
+impl Extend<f64> for TDigest {
+    fn extend<I: IntoIterator<Item = f64>>(&mut self, iter: I) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_checkpoint() {
+        let mut digest = TDigest::new(20.0);
+        digest.extend((0..777).map(|x| f64::from(x * x % 101)));
+        // some of the values are still buffered
+        assert!(!digest.buffer.is_empty());
+        let checkpoint = digest.to_bytes();
+        assert_eq!(TDigest::from_bytes(&checkpoint), Ok(digest.clone()));
+        let mut corrupt = checkpoint.clone();
+        corrupt[2..10].copy_from_slice(&(-1.0f64).to_le_bytes());
+        assert_eq!(TDigest::from_bytes(&corrupt), Err(CheckpointError::Invalid));
+        assert_eq!(
+            TDigest::from_bytes(&checkpoint[..checkpoint.len() - 1]),
+            Err(CheckpointError::Truncated)
+        );
+    }
+
+    #[test]
+    fn test_checkpoint_inconsistent() {
+        let mut digest = TDigest::new(20.0);
+        digest.extend((0..200).map(f64::from));
+        digest.flush();
+        digest.push(1.0);
+        let checkpoint = digest.to_bytes();
+        let corrupt = |offset: usize, value: f64| {
+            let mut corrupt = checkpoint.clone();
+            corrupt[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+            TDigest::from_bytes(&corrupt)
+        };
+        // the count, min and max follow the compression
+        assert_eq!(corrupt(10, 5.0), Err(CheckpointError::Invalid));
+        assert_eq!(corrupt(18, 500.0), Err(CheckpointError::Invalid));
+        // the first centroid's mean and weight follow the number of centroids
+        assert_eq!(corrupt(43, f64::NAN), Err(CheckpointError::Invalid));
+        for weight in [0.0, -1.0, f64::INFINITY, f64::NAN] {
+            assert_eq!(corrupt(51, weight), Err(CheckpointError::Invalid));
+        }
+        // the buffered value is at the end
+        assert_eq!(
+            corrupt(checkpoint.len() - 8, f64::NAN),
+            Err(CheckpointError::Invalid)
+        );
+
+        // a count with nothing to back it up
+        let mut empty = TDigest::default().to_bytes();
+        empty[10..18].copy_from_slice(&5.0f64.to_le_bytes());
+        assert_eq!(TDigest::from_bytes(&empty), Err(CheckpointError::Invalid));
+    }
+
+    #[test]
+    fn test_empty() {
+        let digest = TDigest::default();
+        assert_eq!(digest.quantile(0.5), Err(StatsError::EmptyCollection));
+        assert_eq!(digest.min(), Err(StatsError::EmptyCollection));
+    }
+
+    #[test]
+    fn test_single_value() {
+        let mut digest = TDigest::default();
+        digest.push(3.0);
+        assert_eq!(digest.quantile(0.0), Ok(3.0));
+        assert_eq!(digest.quantile(1.0), Ok(3.0));
+    }
+
+    #[test]
+    fn test_single_centroid() {
+        // so little compression that every value is merged into one centroid
+        let mut digest = TDigest::new(0.1);
+        digest.extend([1.0, 2.0, 3.0, 4.0, 5.0]);
+        digest.flush();
+        assert_eq!(digest.centroids.len(), 1);
+        assert_eq!(digest.quantile(0.0), Ok(1.0));
+        assert_eq!(digest.quantile(0.5), Ok(3.0));
+        assert_eq!(digest.quantile(1.0), Ok(5.0));
+    }
+
+    #[test]
+    fn test_nan_skipped() {
+        let mut digest = TDigest::default();
+        digest.extend([1.0, f64::NAN, 2.0, 3.0, f64::NAN, f64::NAN, 4.0]);
+        assert_eq!(digest.count(), 4);
+        assert_eq!(digest.quantile(0.5), Ok(2.5));
+        assert_eq!(digest.quantile(1.0), Ok(4.0));
+        let mut only_nan = TDigest::default();
+        only_nan.push(f64::NAN);
+        assert_eq!(only_nan.quantile(0.5), Err(StatsError::EmptyCollection));
+    }
+
+    #[test]
+    fn test_small_exact() {
+        let mut digest = TDigest::default();
+        digest.extend([1.0, 2.0, 3.0, 4.0, 5.0]);
+        assert_eq!(digest.quantile(0.0), Ok(1.0));
+        assert_eq!(digest.quantile(0.5), Ok(3.0));
+        assert_eq!(digest.quantile(1.0), Ok(5.0));
+    }
+
+    #[test]
+    fn test_uniform_accuracy() {
+        let mut digest = TDigest::new(100.0);
+        // a scrambled order of 0..100_000
+        for i in 0..100_000u64 {
+            digest.push(((i * 7919) % 100_000) as f64);
+        }
+        assert!(digest.centroids.len() <= 100);
+        for p in [0.001, 0.01, 0.1, 0.5, 0.9, 0.99, 0.999] {
+            let estimate = digest.quantile(p).unwrap();
+            // the error in the quantile (rather than the value) is what the digest bounds
+            assert!((estimate / 100_000.0 - p).abs() < 1e-3);
+        }
+        assert_relative_eq!(digest.quantile(1.0).unwrap(), 99_999.0);
+        let ps = [0.5, 0.01, 0.99];
+        let quantiles = ps.map(|p| digest.quantile(p).unwrap());
+        assert_eq!(digest.quantiles(&ps).unwrap(), quantiles);
+    }
+
+    #[test]
+    fn test_merge_matches_single_digest() {
+        let mut a = TDigest::new(50.0);
+        let mut b = TDigest::new(50.0);
+        for i in 0..10_000 {
+            if i % 2 == 0 {
+                a.push(i as f64);
+            } else {
+                b.push(i as f64);
+            }
+        }
+        a.merge(&b);
+        assert_eq!(a.count(), 10_000);
+        assert_eq!(a.min(), Ok(0.0));
+        assert_eq!(a.max(), Ok(9_999.0));
+        assert!((a.quantile(0.5).unwrap() - 5_000.0).abs() < 50.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_quantile() {
+        let mut digest = TDigest::default();
+        digest.push(1.0);
+        let _ = digest.quantile(1.5);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_resume() {
+        let mut digest = TDigest::new(50.0);
+        for i in 0..1000 {
+            digest.push(f64::from(i));
+        }
+        let json = serde_json::to_string(&digest).unwrap();
+        let mut loaded: TDigest = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, digest);
+        for i in 1000..2000 {
+            digest.push(f64::from(i));
+            loaded.push(f64::from(i));
+        }
+        assert_eq!(loaded.quantile(0.9), digest.quantile(0.9));
+    }
+}
@@ -0,0 +1,131 @@
+/// Counts of the values that often mean that data is broken or needs
+/// cleaning, such as `NaN`s and infinities, as a cheap check before
+/// trusting the statistics of it. It is returned by
+/// [`Stats::validity_report`](crate::Stats::validity_report), or values
+/// can be pushed into it one at a time like the other streaming types.
+///
+/// Every value is counted in exactly one of `nan`, `positive_infinity`,
+/// `negative_infinity`, `zero`, `negative` and [`ValidityReport::positive`].
+///
+/// # Examples
+/// ```
+/// use stats_traits::ValidityReport;
+/// let mut report = ValidityReport::new();
+/// report.extend([1.5, f64::NAN, 0.0, -2.0, f64::INFINITY, -0.0]);
+/// assert_eq!(report.count, 6);
+/// assert_eq!(report.nan, 1);
+/// assert_eq!(report.zero, 2);
+/// assert_eq!(report.finite(), 4);
+/// assert_eq!(report.positive(), 1);
+/// assert!(!report.is_finite());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ValidityReport {
+    /// Number of values
+    pub count: usize,
+    /// Number of `NaN`s
+    pub nan: usize,
+    /// Number of positive infinities
+    pub positive_infinity: usize,
+    /// Number of negative infinities
+    pub negative_infinity: usize,
+    /// Number of zeros, including negative zeros
+    pub zero: usize,
+    /// Number of finite values that are less than zero
+    pub negative: usize,
+}
+
+impl ValidityReport {
+    /// Create a report of no values
+    pub const fn new() -> Self {
+        Self {
+            count: 0,
+            nan: 0,
+            positive_infinity: 0,
+            negative_infinity: 0,
+            zero: 0,
+            negative: 0,
+        }
+    }
+
+    /// Count a value
+    pub fn push(&mut self, x: f64) {
+        self.count += 1;
+        if x.is_nan() {
+            self.nan += 1;
+        } else if x == f64::INFINITY {
+            self.positive_infinity += 1;
+        } else if x == f64::NEG_INFINITY {
+            self.negative_infinity += 1;
+        } else if x == 0.0 {
+            self.zero += 1;
+        } else if x < 0.0 {
+            self.negative += 1;
+        }
+    }
+
+    /// Returns the number of values that are neither `NaN` nor infinite
+    pub fn finite(&self) -> usize {
+        self.count - self.nan - self.positive_infinity - self.negative_infinity
+    }
+
+    /// Returns the number of finite values that are greater than zero
+    pub fn positive(&self) -> usize {
+        self.finite() - self.zero - self.negative
+    }
+
+    /// Returns `true` if every value is finite, so there are no `NaN`s or
+    /// infinities, which is also the case if there are no values
+    pub fn is_finite(&self) -> bool {
+        self.finite() == self.count
+    }
+
+    /// Combine the report of other values with this one, as if they had
+    /// been pushed into it
+    pub fn merge(&mut self, other: &Self) {
+        self.count += other.count;
+        self.nan += other.nan;
+        self.positive_infinity += other.positive_infinity;
+        self.negative_infinity += other.negative_infinity;
+        self.zero += other.zero;
+        self.negative += other.negative;
+    }
+}
+
+impl Extend<f64> for ValidityReport {
+    fn extend<I: IntoIterator<Item = f64>>(&mut self, iter: I) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+impl FromIterator<f64> for ValidityReport {
+    fn from_iter<I: IntoIterator<Item = f64>>(iter: I) -> Self {
+        let mut report = Self::new();
+        report.extend(iter);
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_categories() {
+        let values = [3.0, -1.0, f64::NEG_INFINITY, f64::NAN, 0.0, 7.0, -0.0];
+        let mut report = ValidityReport::from_iter(values[..4].iter().copied());
+        report.merge(&ValidityReport::from_iter(values[4..].iter().copied()));
+        assert_eq!(report, ValidityReport::from_iter(values));
+        assert_eq!(report.count, 7);
+        assert_eq!(report.negative_infinity, 1);
+        assert_eq!(report.positive_infinity, 0);
+        assert_eq!(report.negative, 1);
+        assert_eq!(report.zero, 2);
+        assert_eq!(report.positive(), 2);
+        assert!(ValidityReport::new().is_finite());
+    }
+}
@@ -0,0 +1,104 @@
+use crate::helpers::NumExt;
+
+/// Types whose sums can be accumulated in a wider type, so that adding up
+/// many small items doesn't overflow when their mean fits in the type. See
+/// [`Stats::wide_sum`](crate::Stats::wide_sum) and
+/// [`Stats::wide_mean`](crate::Stats::wide_mean).
+///
+/// Integers of up to 32 bits are widened to 64 bits, 64-bit integers to 128
+/// bits, and [`f32`] to [`f64`]. The widest types are their own `WideSum`.
+pub trait Widen: Sized {
+    /// The type that sums of this type are accumulated in
+    type WideSum: NumExt;
+
+    /// Convert to the wider type, which can't fail
+    fn widen(self) -> Self::WideSum;
+
+    /// Convert back from the wider type, returning `None` if `wide` doesn't fit
+    fn narrow(wide: Self::WideSum) -> Option<Self>;
+}
+
+macro_rules! impl_widen_int {
+    ($($t:ty => $wide:ty),*) => {
+        $(
+            impl Widen for $t {
+                type WideSum = $wide;
+
+                fn widen(self) -> $wide {
+                    self.into()
+                }
+
+                fn narrow(wide: $wide) -> Option<Self> {
+                    wide.try_into().ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_widen_int!(
+    u8 => u64, u16 => u64, u32 => u64, u64 => u128, u128 => u128,
+    i8 => i64, i16 => i64, i32 => i64, i64 => i128, i128 => i128
+);
+
+impl Widen for usize {
+    type WideSum = u128;
+
+    fn widen(self) -> u128 {
+        self as u128
+    }
+
+    fn narrow(wide: u128) -> Option<Self> {
+        wide.try_into().ok()
+    }
+}
+
+impl Widen for isize {
+    type WideSum = i128;
+
+    fn widen(self) -> i128 {
+        self as i128
+    }
+
+    fn narrow(wide: i128) -> Option<Self> {
+        wide.try_into().ok()
+    }
+}
+
+impl Widen for f32 {
+    type WideSum = f64;
+
+    fn widen(self) -> f64 {
+        self.into()
+    }
+
+    fn narrow(wide: f64) -> Option<Self> {
+        Some(wide as f32)
+    }
+}
+
+impl Widen for f64 {
+    type WideSum = f64;
+
+    fn widen(self) -> f64 {
+        self
+    }
+
+    fn narrow(wide: f64) -> Option<Self> {
+        Some(wide)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        assert_eq!(u8::narrow(200u8.widen()), Some(200));
+        assert_eq!(u8::narrow(256), None);
+        assert_eq!(i16::narrow(-40_000), None);
+        assert_eq!(usize::narrow(usize::MAX.widen()), Some(usize::MAX));
+        assert_eq!(f32::narrow(0.5f32.widen()), Some(0.5));
+    }
+}
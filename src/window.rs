@@ -0,0 +1,712 @@
+#[cfg(feature = "alloc")]
+use alloc::collections::VecDeque;
+
+use num_traits::ToPrimitive;
+
+use crate::helpers::Float;
+use crate::helpers::MinMax;
+use crate::helpers::NanLast;
+use crate::helpers::NumExt;
+use crate::Result;
+use crate::StatsError;
+
+/// A double-ended queue that a sliding window is kept in, so the same
+/// code can keep it in a [`VecDeque`] or in a fixed-capacity [`Ring`]
+pub(crate) trait Queue<E> {
+    fn len(&self) -> usize;
+    fn push_back(&mut self, x: E);
+    fn pop_front(&mut self) -> Option<E>;
+    fn pop_back(&mut self) -> Option<E>;
+    fn front(&self) -> Option<&E>;
+    fn back(&self) -> Option<&E>;
+    fn clear(&mut self);
+    fn iter<'a>(&'a self) -> impl Iterator<Item = &'a E>
+    where
+        E: 'a;
+}
+
+#[cfg(feature = "alloc")]
+impl<E> Queue<E> for VecDeque<E> {
+    fn len(&self) -> usize {
+        VecDeque::len(self)
+    }
+
+    fn push_back(&mut self, x: E) {
+        VecDeque::push_back(self, x);
+    }
+
+    fn pop_front(&mut self) -> Option<E> {
+        VecDeque::pop_front(self)
+    }
+
+    fn pop_back(&mut self) -> Option<E> {
+        VecDeque::pop_back(self)
+    }
+
+    fn front(&self) -> Option<&E> {
+        VecDeque::front(self)
+    }
+
+    fn back(&self) -> Option<&E> {
+        VecDeque::back(self)
+    }
+
+    fn clear(&mut self) {
+        VecDeque::clear(self);
+    }
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = &'a E>
+    where
+        E: 'a,
+    {
+        VecDeque::iter(self)
+    }
+}
+
+/// A ring buffer of at most `N` values, stored inline
+#[derive(Debug, Clone)]
+pub(crate) struct Ring<E, const N: usize> {
+    items: [Option<E>; N],
+    /// Index of the front of the queue in `items`
+    head: usize,
+    len: usize,
+}
+
+impl<E: Copy, const N: usize> Ring<E, N> {
+    pub(crate) const fn new() -> Self {
+        Self {
+            items: [None; N],
+            head: 0,
+            len: 0,
+        }
+    }
+}
+
+impl<E: Copy, const N: usize> Queue<E> for Ring<E, N> {
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    /// # Panics
+    /// Panics if the ring buffer is full
+    fn push_back(&mut self, x: E) {
+        assert!(self.len < N, "ring buffer is full");
+        self.items[(self.head + self.len) % N] = Some(x);
+        self.len += 1;
+    }
+
+    fn pop_front(&mut self) -> Option<E> {
+        if self.len == 0 {
+            return None;
+        }
+        let x = self.items[self.head].take();
+        self.head = (self.head + 1) % N;
+        self.len -= 1;
+        x
+    }
+
+    fn pop_back(&mut self) -> Option<E> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        self.items[(self.head + self.len) % N].take()
+    }
+
+    fn front(&self) -> Option<&E> {
+        self.iter().next()
+    }
+
+    fn back(&self) -> Option<&E> {
+        self.iter().last()
+    }
+
+    fn clear(&mut self) {
+        self.items = [None; N];
+        self.head = 0;
+        self.len = 0;
+    }
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = &'a E>
+    where
+        E: 'a,
+    {
+        (0..self.len).filter_map(move |i| self.items[(self.head + i) % N].as_ref())
+    }
+}
+
+/// The values of a sliding window and their running statistics, shared by
+/// [`RollingStats`](crate::RollingStats) and [`FixedRollingStats`], which
+/// only differ in the queues they keep the window in.
+#[derive(Debug, Clone)]
+pub(crate) struct Window<T, V, Q> {
+    pub(crate) values: V,
+    pub(crate) capacity: usize,
+    pub(crate) sum: T,
+    pub(crate) mean: f64,
+    pub(crate) m2: f64,
+    /// Total number of values ever pushed, used to number them
+    pushed: usize,
+    /// Increasing `(number, value)` pairs, the front being the minimum
+    min_queue: Q,
+    /// Decreasing `(number, value)` pairs, the front being the maximum
+    max_queue: Q,
+}
+
+impl<T, V, Q> Window<T, V, Q>
+where
+    T: NumExt + Copy + ToPrimitive + MinMax,
+    V: Queue<T>,
+    Q: Queue<(usize, T)>,
+{
+    /// Create an empty window of `capacity` values, kept in the empty queues
+    pub(crate) fn new(capacity: usize, values: V, min_queue: Q, max_queue: Q) -> Self {
+        Self {
+            values,
+            capacity,
+            sum: T::zero(),
+            mean: 0.0,
+            m2: 0.0,
+            pushed: 0,
+            min_queue,
+            max_queue,
+        }
+    }
+
+    /// Add a value to the window. If the window was already full, the oldest
+    /// value is removed from it and returned.
+    pub(crate) fn push(&mut self, x: T) -> Option<T> {
+        let evicted = if self.values.len() == self.capacity {
+            let old = self.values.pop_front();
+            if let Some(old) = old {
+                self.remove(old);
+            }
+            old
+        } else {
+            None
+        };
+
+        self.values.push_back(x);
+        self.push_extremes(x);
+        self.sum = self.sum + x;
+        let x = x.to_f64().unwrap_or(f64::NAN);
+        let delta = x - self.mean;
+        self.mean += delta / self.values.len() as f64;
+        self.m2 += delta * (x - self.mean);
+
+        evicted
+    }
+
+    /// Add `x` to the back of the monotonic queues, first removing every
+    /// value that can no longer be the minimum or maximum of the window
+    fn push_extremes(&mut self, x: T) {
+        let number = self.pushed;
+        self.pushed += 1;
+
+        while let Some(&(_, back)) = self.min_queue.back() {
+            if T::min(back, x) == x {
+                self.min_queue.pop_back();
+            } else {
+                break;
+            }
+        }
+        self.min_queue.push_back((number, x));
+
+        while let Some(&(_, back)) = self.max_queue.back() {
+            if T::max(back, x) == x {
+                self.max_queue.pop_back();
+            } else {
+                break;
+            }
+        }
+        self.max_queue.push_back((number, x));
+    }
+
+    /// Update the running totals after `old` has left the window
+    fn remove(&mut self, old: T) {
+        // the value that left is numbered one before the oldest one still in the window
+        let number = self.pushed - self.values.len() - 1;
+        if self.min_queue.front().is_some_and(|&(n, _)| n == number) {
+            self.min_queue.pop_front();
+        }
+        if self.max_queue.front().is_some_and(|&(n, _)| n == number) {
+            self.max_queue.pop_front();
+        }
+
+        self.sum = self.sum - old;
+        let count = self.values.len();
+        if count == 0 {
+            self.mean = 0.0;
+            self.m2 = 0.0;
+            return;
+        }
+        let old = old.to_f64().unwrap_or(f64::NAN);
+        if !old.is_finite() {
+            // a NaN or infinity can't be taken back out of the running
+            // totals, so they are recalculated from the values still left
+            self.recalculate();
+            return;
+        }
+        let delta = old - self.mean;
+        self.mean -= delta / count as f64;
+        self.m2 -= delta * (old - self.mean);
+    }
+
+    /// Recalculate the sum, mean and variance from the values in the window
+    fn recalculate(&mut self) {
+        self.sum = self.values.iter().copied().sum();
+        self.mean = 0.0;
+        self.m2 = 0.0;
+        for (i, &x) in self.values.iter().enumerate() {
+            let x = x.to_f64().unwrap_or(f64::NAN);
+            let delta = x - self.mean;
+            self.mean += delta / (i + 1) as f64;
+            self.m2 += delta * (x - self.mean);
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.values.clear();
+        self.min_queue.clear();
+        self.max_queue.clear();
+        self.sum = T::zero();
+        self.mean = 0.0;
+        self.m2 = 0.0;
+    }
+
+    pub(crate) fn count(&self) -> usize {
+        self.values.len()
+    }
+
+    pub(crate) fn is_full(&self) -> bool {
+        self.values.len() == self.capacity
+    }
+
+    /// Returns `Err(StatsError::EmptyCollection)` if the window is empty
+    fn non_empty(&self) -> Result<()> {
+        if self.values.len() == 0 {
+            Err(StatsError::EmptyCollection)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub(crate) fn mean(&self) -> Result<f64> {
+        self.non_empty()?;
+        Ok(self.mean)
+    }
+
+    pub(crate) fn variance(&self) -> Result<f64> {
+        self.non_empty()?;
+        let variance = self.m2 / self.values.len() as f64;
+        // rounding when values leave the window can make this very slightly
+        // negative, which is clamped without hiding a NaN
+        Ok(if variance < 0.0 { 0.0 } else { variance })
+    }
+
+    pub(crate) fn std_dev(&self) -> Result<f64> {
+        Ok(Float::sqrt(self.variance()?))
+    }
+
+    pub(crate) fn min(&self) -> Result<T> {
+        self.min_queue
+            .front()
+            .map(|&(_, x)| x)
+            .ok_or(StatsError::EmptyCollection)
+    }
+
+    pub(crate) fn max(&self) -> Result<T> {
+        self.max_queue
+            .front()
+            .map(|&(_, x)| x)
+            .ok_or(StatsError::EmptyCollection)
+    }
+}
+
+/// Keeps statistics over the last `N` values of a stream like
+/// [`RollingStats`](crate::RollingStats), but in a buffer of `N` values
+/// stored inline rather than on the heap, so it can be used without an
+/// allocator, such as in a `static` or on the stack of an embedded device.
+///
+/// # Examples
+/// ```
+/// use stats_traits::FixedRollingStats;
+/// let mut rolling = FixedRollingStats::<i32, 3>::new();
+/// for x in [1, 2, 3, 4] {
+///     rolling.push(x);
+/// }
+/// // only 2, 3 and 4 are still in the window
+/// assert_eq!(rolling.count(), 3);
+/// assert_eq!(rolling.sum(), 9);
+/// assert_eq!(rolling.mean(), Ok(3.0));
+/// assert_eq!(rolling.min(), Ok(2));
+/// ```
+#[derive(Debug, Clone)]
+pub struct FixedRollingStats<T, const N: usize> {
+    window: Window<T, Ring<T, N>, Ring<(usize, T), N>>,
+}
+
+impl<T, const N: usize> FixedRollingStats<T, N>
+where
+    T: NumExt + Copy + ToPrimitive + MinMax,
+{
+    /// Create an empty window that holds at most `N` values
+    ///
+    /// # Panics
+    /// Panics if `N` is 0
+    pub fn new() -> Self {
+        assert!(N > 0, "window size must be greater than 0");
+        Self {
+            window: Window::new(N, Ring::new(), Ring::new(), Ring::new()),
+        }
+    }
+
+    /// Add a value to the window. If the window was already full, the oldest
+    /// value is removed from it and returned.
+    pub fn push(&mut self, x: T) -> Option<T> {
+        self.window.push(x)
+    }
+
+    /// Remove every value from the window
+    pub fn clear(&mut self) {
+        self.window.clear();
+    }
+
+    /// Returns the number of values currently in the window
+    pub fn count(&self) -> usize {
+        self.window.count()
+    }
+
+    /// Returns the maximum number of values the window can hold, which is `N`
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns `true` if the window holds as many values as it can
+    pub fn is_full(&self) -> bool {
+        self.window.is_full()
+    }
+
+    /// Returns an iterator over the values in the window, from oldest to newest
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.window.values.iter()
+    }
+
+    /// Returns the sum of the values in the window
+    pub fn sum(&self) -> T {
+        self.window.sum
+    }
+
+    /// Returns the mean of the values in the window
+    ///
+    /// # Errors
+    /// Returns an error if the window is empty
+    pub fn mean(&self) -> Result<f64> {
+        self.window.mean()
+    }
+
+    /// Returns the (population) variance of the values in the window,
+    /// which is `NaN` while a `NaN` or infinity is in the window
+    ///
+    /// # Errors
+    /// Returns an error if the window is empty
+    pub fn variance(&self) -> Result<f64> {
+        self.window.variance()
+    }
+
+    /// Returns the standard deviation of the values in the window
+    ///
+    /// # Errors
+    /// Returns an error if the window is empty
+    pub fn std_dev(&self) -> Result<f64> {
+        self.window.std_dev()
+    }
+
+    /// Returns the smallest value in the window
+    ///
+    /// # Errors
+    /// Returns an error if the window is empty
+    pub fn min(&self) -> Result<T> {
+        self.window.min()
+    }
+
+    /// Returns the largest value in the window
+    ///
+    /// # Errors
+    /// Returns an error if the window is empty
+    pub fn max(&self) -> Result<T> {
+        self.window.max()
+    }
+
+    /// Returns the range of the window (the smallest subtracted from the largest)
+    ///
+    /// # Errors
+    /// Returns an error if the window is empty
+    pub fn range(&self) -> Result<T> {
+        Ok(self.max()? - self.min()?)
+    }
+}
+
+impl<T, const N: usize> Default for FixedRollingStats<T, N>
+where
+    T: NumExt + Copy + ToPrimitive + MinMax,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Keeps track of the median of the last `N` values of a stream like
+/// [`MovingMedian`](crate::MovingMedian), but in buffers of `N` values
+/// stored inline rather than on the heap, so it can be used without an
+/// allocator.
+///
+/// The values in the window are also kept in sorted order, so pushing a
+/// value is O(N) rather than O(log N), which is fast for the small windows
+/// that fit on the stack, and the median is found in O(1).
+///
+/// # Examples
+/// ```
+/// use stats_traits::FixedMovingMedian;
+/// let mut median = FixedMovingMedian::<i32, 3>::new();
+/// for x in [5, 1, 100, 3] {
+///     median.push(x);
+/// }
+/// // the window is now [1, 100, 3]
+/// assert_eq!(median.median(), Ok(3));
+/// ```
+#[derive(Debug, Clone)]
+pub struct FixedMovingMedian<T, const N: usize> {
+    window: Ring<T, N>,
+    /// The values in the window in ascending order, with `NaN` last
+    sorted: [Option<T>; N],
+}
+
+impl<T, const N: usize> FixedMovingMedian<T, N>
+where
+    T: NumExt + Copy + PartialOrd,
+{
+    /// Create an empty window that holds at most `N` values
+    ///
+    /// # Panics
+    /// Panics if `N` is 0
+    pub fn new() -> Self {
+        assert!(N > 0, "window size must be greater than 0");
+        Self {
+            window: Ring::new(),
+            sorted: [None; N],
+        }
+    }
+
+    /// Returns the sorted values in the window
+    fn sorted(&self) -> impl DoubleEndedIterator<Item = T> + '_ {
+        self.sorted[..self.window.len()].iter().flatten().copied()
+    }
+
+    /// Add a value to the window. If the window was already full, the oldest
+    /// value is removed from it and returned.
+    pub fn push(&mut self, x: T) -> Option<T> {
+        let evicted = if self.window.len() == N {
+            self.evict()
+        } else {
+            None
+        };
+
+        let len = self.window.len();
+        let index = self
+            .sorted()
+            .take_while(|&y| NanLast(y) <= NanLast(x))
+            .count();
+        self.sorted.copy_within(index..len, index + 1);
+        self.sorted[index] = Some(x);
+        self.window.push_back(x);
+
+        evicted
+    }
+
+    /// Remove the oldest value from the window and return it
+    pub fn evict(&mut self) -> Option<T> {
+        let x = *self.window.front()?;
+        let len = self.window.len();
+        let index = self.sorted().position(|y| NanLast(y) == NanLast(x));
+        if let Some(index) = index {
+            self.sorted.copy_within(index + 1..len, index);
+            self.sorted[len - 1] = None;
+        }
+        self.window.pop_front()
+    }
+
+    /// Remove every value from the window
+    pub fn clear(&mut self) {
+        self.window.clear();
+        self.sorted = [None; N];
+    }
+
+    /// Returns the number of values currently in the window
+    pub fn count(&self) -> usize {
+        self.window.len()
+    }
+
+    /// Returns the maximum number of values the window can hold, which is `N`
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns the median of the values in the window, in the same way as
+    /// [`MovingMedian::median`](crate::MovingMedian::median)
+    ///
+    /// # Errors
+    /// Returns an error if the window is empty
+    pub fn median(&self) -> Result<T> {
+        let len = self.window.len();
+        let mut middle = self.sorted().skip(len.saturating_sub(1) / 2);
+        let low = middle.next().ok_or(StatsError::EmptyCollection)?;
+        match middle.next() {
+            Some(high) if len % 2 == 0 => Ok(midpoint(low, high)),
+            _ => Ok(low),
+        }
+    }
+}
+
+impl<T, const N: usize> Default for FixedMovingMedian<T, N>
+where
+    T: NumExt + Copy + PartialOrd,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the mean of `low` and `high`, which are in ascending order,
+/// without overflowing on the way. For integers it is rounded down.
+pub(crate) fn midpoint<T>(low: T, high: T) -> T
+where
+    T: NumExt + Copy + PartialOrd,
+{
+    let two = T::one() + T::one();
+    let zero = T::zero();
+    if low == high {
+        // the difference of two equal infinities would be NaN
+        low
+    } else if (low < zero) == (high < zero) {
+        // the difference of values with the same sign can't overflow
+        low + (high - low) / two
+    } else {
+        // and neither can the sum of values with different signs, but
+        // dividing it rounds towards 0 rather than down
+        let sum = low + high;
+        let half = sum / two;
+        if half + half != sum && sum < zero {
+            half - T::one()
+        } else {
+            half
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Stats;
+
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_ring() {
+        let mut ring = Ring::<i32, 3>::new();
+        for x in [1, 2, 3] {
+            ring.push_back(x);
+        }
+        assert_eq!(ring.pop_front(), Some(1));
+        ring.push_back(4);
+        assert!(ring.iter().copied().eq([2, 3, 4]));
+        assert_eq!(ring.pop_back(), Some(4));
+        assert_eq!((ring.front(), ring.back()), (Some(&2), Some(&3)));
+        ring.clear();
+        assert_eq!(ring.pop_front(), None);
+    }
+
+    #[test]
+    fn test_fixed_rolling_matches_recalculation() {
+        let data = [4.0, 7.0, 13.0, 16.0, 1.0, -3.0, 8.5, 2.25, 9.0, 11.0];
+        let mut rolling = FixedRollingStats::<f64, 4>::new();
+        for (i, &x) in data.iter().enumerate() {
+            rolling.push(x);
+            let window = &data[i.saturating_sub(3)..=i];
+            // the window can be copied into a fixed-capacity collection
+            let copied: heapless::Vec<f64, 4> = rolling.iter().copied().collect();
+            assert_eq!(copied, window);
+            assert_relative_eq!(rolling.mean().unwrap(), copied.mean().unwrap());
+            assert_relative_eq!(
+                rolling.variance().unwrap(),
+                copied.variance().unwrap(),
+                epsilon = 1e-9
+            );
+            assert_eq!(rolling.min(), Stats::min(&copied));
+            assert_eq!(rolling.max(), Stats::max(&copied));
+        }
+        rolling.clear();
+        assert_eq!(rolling.mean(), Err(StatsError::EmptyCollection));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_fixed_rolling_matches_heap() {
+        let data: arrayvec::ArrayVec<i64, 18> =
+            [3, 1, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5, 9, 9, 1, 0, 0, 7].into();
+        let mut fixed = FixedRollingStats::<i64, 5>::default();
+        let mut heap = crate::RollingStats::new(5);
+        for &x in &data {
+            assert_eq!(fixed.push(x), heap.push(x));
+            assert!(fixed.iter().eq(heap.iter()));
+            assert_eq!(fixed.sum(), heap.sum());
+            assert_eq!(fixed.std_dev(), heap.std_dev());
+            assert_eq!(fixed.range(), heap.range());
+        }
+        assert!(fixed.is_full());
+        assert_eq!(fixed.capacity(), 5);
+    }
+
+    #[test]
+    fn test_fixed_moving_median_matches_sorting() {
+        let data = [
+            3.0, 1.0, 4.0, 1.0, 5.0, 9.0, 2.0, 6.0, 5.0, 3.0, 5.0, 8.0, 9.0, 7.0, 9.0, 3.0, 2.0,
+        ];
+        let mut median = FixedMovingMedian::<f64, 4>::new();
+        for (i, &x) in data.iter().enumerate() {
+            median.push(x);
+            let window = &data[(i + 1).saturating_sub(4)..=i];
+            let mut sorted: heapless::Vec<f64, 4> = window.iter().copied().collect();
+            sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            assert_eq!(median.median(), crate::Sorted::new(sorted).median());
+        }
+    }
+
+    #[test]
+    fn test_fixed_moving_median_evict() {
+        let mut median = FixedMovingMedian::<i64, 3>::new();
+        assert_eq!(median.push(i64::MIN), None);
+        assert_eq!(median.push(i64::MAX), None);
+        assert_eq!(median.median(), Ok(-1));
+        assert_eq!(median.push(0), None);
+        assert_eq!(median.push(7), Some(i64::MIN));
+        assert_eq!(median.evict(), Some(i64::MAX));
+        assert_eq!(median.count(), 2);
+        assert_eq!(median.median(), Ok(3));
+        median.clear();
+        assert_eq!(median.median(), Err(StatsError::EmptyCollection));
+        let mut nan = FixedMovingMedian::<f64, 3>::new();
+        for x in [f64::NAN, 1.0, 2.0] {
+            nan.push(x);
+        }
+        assert_eq!(nan.median(), Ok(2.0));
+        nan.push(0.0);
+        assert_eq!(nan.median(), Ok(1.0));
+    }
+
+    #[test]
+    #[should_panic(expected = "window size must be greater than 0")]
+    fn test_zero_window() {
+        FixedRollingStats::<f64, 0>::new();
+    }
+}
@@ -0,0 +1,92 @@
+use alloc::vec::Vec;
+
+use crate::helpers::partial_cmp_nan_last;
+use crate::Result;
+use crate::StatsError;
+
+/// Clip the `values` in place to the bounds of
+/// [winsorizing](<https://en.wikipedia.org/wiki/Winsorizing>) them, so the
+/// `fraction` of them that are smallest are raised to the smallest value
+/// that is kept, and likewise the largest are lowered, as
+/// [`Stats::winsorize`](crate::Stats::winsorize) does for a copy of a
+/// collection. `NaN`s are left as they are, and aren't counted.
+///
+/// # Examples
+/// ```
+/// use stats_traits::{winsorize_in_place, Stats};
+/// let mut readings = [9.0, 10.0, 12.0, 11.0, 250.0, 10.5, -40.0, 11.5, 9.5, 10.0];
+/// winsorize_in_place(&mut readings, 0.1).unwrap();
+/// assert_eq!(readings, [9.0, 10.0, 12.0, 11.0, 12.0, 10.5, 9.0, 11.5, 9.5, 10.0]);
+/// assert_eq!(readings.mean(), Ok(10.45));
+/// ```
+///
+/// # Errors
+/// Returns an error if there are no values that aren't `NaN`
+///
+/// # Panics
+/// Panics if `fraction` is not at least 0 and less than 0.5
+pub fn winsorize_in_place(values: &mut [f64], fraction: f64) -> Result<()> {
+    let (lower, upper) = bounds(values.iter().copied(), fraction)?;
+    for x in values {
+        *x = x.clamp(lower, upper);
+    }
+    Ok(())
+}
+
+/// Returns the smallest and largest of `values` that are kept when
+/// winsorizing them, which are the `k`th smallest and `k`th largest,
+/// where `k` is `fraction` of the number of values that aren't `NaN`,
+/// rounded down
+pub(crate) fn bounds<I>(values: I, fraction: f64) -> Result<(f64, f64)>
+where
+    I: IntoIterator<Item = f64>,
+{
+    assert!(
+        (0.0..0.5).contains(&fraction),
+        "fraction must be at least 0 and less than 0.5"
+    );
+    let mut values: Vec<f64> = values.into_iter().filter(|x| !x.is_nan()).collect();
+    if values.is_empty() {
+        return Err(StatsError::EmptyCollection);
+    }
+    let k = (fraction * values.len() as f64) as usize;
+    let upper_rank = values.len() - 1 - k;
+    let (below, &mut upper, _) = values.select_nth_unstable_by(upper_rank, partial_cmp_nan_last);
+    let lower = if k == upper_rank {
+        upper
+    } else {
+        *below.select_nth_unstable_by(k, partial_cmp_nan_last).1
+    };
+    Ok((lower, upper))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounds() {
+        let values = [5.0, 1.0, 4.0, f64::NAN, 2.0, 3.0];
+        assert_eq!(bounds(values, 0.0), Ok((1.0, 5.0)));
+        assert_eq!(bounds(values, 0.2), Ok((2.0, 4.0)));
+        // 0.39 of 5 values rounds down to 1
+        assert_eq!(bounds(values, 0.39), Ok((2.0, 4.0)));
+        assert_eq!(bounds(values, 0.4), Ok((3.0, 3.0)));
+        assert_eq!(bounds([7.0], 0.3), Ok((7.0, 7.0)));
+        assert_eq!(bounds([f64::NAN], 0.1), Err(StatsError::EmptyCollection));
+    }
+
+    #[test]
+    fn test_in_place_keeps_nan() {
+        let mut values = [f64::NAN, 1.0, 2.0, 3.0, 4.0];
+        winsorize_in_place(&mut values, 0.25).unwrap();
+        assert!(values[0].is_nan());
+        assert_eq!(values[1..], [2.0, 2.0, 3.0, 3.0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "fraction must be at least 0 and less than 0.5")]
+    fn test_half() {
+        let _ = winsorize_in_place(&mut [1.0, 2.0], 0.5);
+    }
+}